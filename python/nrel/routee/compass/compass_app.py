@@ -5,7 +5,18 @@ import os
 from tempfile import TemporaryDirectory
 
 from pathlib import Path
-from typing import Any, List, Optional, Union, Callable, TYPE_CHECKING, cast
+from typing import (
+    Any,
+    Dict,
+    Iterator,
+    List,
+    Optional,
+    Tuple,
+    Union,
+    Callable,
+    TYPE_CHECKING,
+    cast,
+)
 from nrel.routee.compass.routee_compass_py import (
     CompassAppWrapper,
 )
@@ -316,6 +327,243 @@ class CompassApp:
             return results[0]
         return results
 
+    def run_keyed(
+        self,
+        queries: List[CompassQuery],
+        config: Optional[Config] = None,
+    ) -> Dict[str, Union[Result, Results]]:
+        """
+        Run a list of queries against the CompassApp, returning the results
+        keyed by the `query_id` of each query rather than in a list, so that
+        results can be joined back to their queries by id. Every query must
+        have a `query_id`. String ids are used as keys as is, and other ids
+        as their JSON text.
+
+        Results that share an id, such as those of a query expanded by the
+        grid search plugin, are stored as a list under that id. A batch with
+        duplicate ids fails when the `duplicate_query_id_policy` is "error".
+
+        Args:
+            queries: A list of queries to run, each with a `query_id`
+            config: optional configuration
+
+        Returns:
+            results: The result of each query, keyed by query id
+
+        Example:
+            >>> from nrel.routee.compass import CompassApp
+            >>> app = CompassApp.from_config_file("config.toml")
+            >>> results = app.run_keyed([{"query_id": "trip-1", **query}])
+            >>> results["trip-1"]["route"]
+
+        """
+        queries_str = list(map(json.dumps, queries))
+        config_str = json.dumps(config) if config is not None else None
+        results_json: str = self._app._run_queries_keyed(queries_str, config_str)
+        return json.loads(results_json)
+
+    def run_cost_only(
+        self,
+        queries: List[CompassQuery],
+        state_features: Optional[List[str]] = None,
+    ) -> List[Union[float, Result]]:
+        """
+        Run a list of queries in the leanest mode, for cost-only workloads
+        such as large origin-destination studies. Each result is only the cost
+        of the route, the value minimized by the search. The request is not
+        echoed and no output plugins are run. Input plugins are still applied.
+
+        Args:
+            queries: A list of queries to run
+            state_features: optional state features, such as "distance" and
+                "time", to report along with each cost
+
+        Returns:
+            results: The cost of each query, in query order. When state
+                features are listed, each result is an object with the `cost`
+                and the value of each feature. A failed query gives its error.
+
+        Example:
+            >>> from nrel.routee.compass import CompassApp
+            >>> app = CompassApp.from_config_file("config.toml")
+            >>> costs = app.run_cost_only(queries)
+            >>> costs = app.run_cost_only(queries, ["distance", "time"])
+            >>> costs[0]["time"]
+
+        """
+        queries_str = list(map(json.dumps, queries))
+        features = state_features if state_features is not None else []
+        results_json: str = self._app._run_queries_cost_only(queries_str, features)
+        return json.loads(results_json)
+
+    def run_iter(
+        self, queries: List[CompassQuery], chunk_size: int = 1000
+    ) -> Iterator[Result]:
+        """
+        Run a list of queries against the CompassApp, yielding each result in
+        the order of the queries. Queries run in parallel one chunk at a time,
+        and the next chunk only runs once the results of the previous chunk
+        have been consumed, so memory use is bounded by the chunk size.
+
+        Unlike `run`, results are not written to the configured response output
+        policy.
+
+        Args:
+            queries: A list of queries to run
+            chunk_size: the number of queries to run at a time
+
+        Returns:
+            results: An iterator over the result of each query
+
+        Example:
+            >>> from nrel.routee.compass import CompassApp
+            >>> app = CompassApp.from_config_file("config.toml")
+            >>> for result in app.run_iter(queries, chunk_size=100):
+            ...     process(result)
+
+        """
+        if chunk_size < 1:
+            raise ValueError(f"chunk_size must be positive, found {chunk_size}")
+        for start in range(0, len(queries), chunk_size):
+            chunk = queries[start : start + chunk_size]
+            queries_str = list(map(json.dumps, chunk))
+            results_json: List[str] = self._app._run_queries_in_order(queries_str)
+            for result_json in results_json:
+                yield json.loads(result_json)
+
+    def run_with_edge_usage(
+        self,
+        queries: List[CompassQuery],
+        config: Optional[Config] = None,
+    ) -> Tuple[Results, Dict[int, int]]:
+        """
+        Run a list of queries against the CompassApp, also counting how many
+        routes traverse each edge across the batch, for example to find the
+        key corridors of a set of trips.
+
+        Args:
+            queries: A list of queries to run
+            config: optional configuration
+
+        Returns:
+            results: A list of results
+            edge_usage: the number of routes traversing each edge id, for each
+                edge used by at least one route
+
+        Example:
+            >>> import pandas as pd
+            >>> from nrel.routee.compass import CompassApp
+            >>> app = CompassApp.from_config_file("config.toml")
+            >>> results, edge_usage = app.run_with_edge_usage(queries)
+            >>> df = pd.DataFrame(edge_usage.items(), columns=["edge_id", "count"])
+            >>> df.to_parquet("edge_usage.parquet")
+
+        """
+        queries_str = list(map(json.dumps, queries))
+        config_str = json.dumps(config) if config is not None else None
+
+        results_json, edge_usage = self._app._run_queries_with_edge_usage(
+            queries_str, config_str
+        )
+
+        results: Results = list(map(json.loads, results_json))
+        return results, dict(edge_usage)
+
+    def edge_importance(
+        self,
+        samples: int,
+        seed: Optional[int] = None,
+        config: Optional[Config] = None,
+    ) -> Dict[int, float]:
+        """
+        Approximate the betweenness of each edge by routing between a random
+        sample of origin and destination vertices, in parallel, and counting how
+        often each edge lies on a route, for example to prioritize corridors.
+
+        Args:
+            samples: the number of origin-destination pairs to route between
+            seed: optional random seed, so that the sample can be repeated
+            config: optional configuration
+
+        Returns:
+            importance: the share of the sampled routes that traverse each edge
+                id, from 0 to 1, for each edge used by at least one route
+
+        Example:
+            >>> import pandas as pd
+            >>> from nrel.routee.compass import CompassApp
+            >>> app = CompassApp.from_config_file("config.toml")
+            >>> importance = app.edge_importance(10000, seed=0)
+            >>> df = pd.DataFrame(importance.items(), columns=["edge_id", "importance"])
+            >>> df.to_parquet("edge_importance.parquet")
+
+        """
+        if samples < 1:
+            raise ValueError(f"samples must be positive, found {samples}")
+        config_str = json.dumps(config) if config is not None else None
+        importance = self._app._run_edge_importance(samples, seed, config_str)
+        return dict(importance)
+
+    def eta_distribution(self, query: CompassQuery, samples: int) -> Dict[str, Any]:
+        """
+        Run a query once for each of a number of samples of its edge travel
+        times, drawn from the mean and variance of each edge, to find the
+        distribution of its trip time. Requires a speed table traversal model
+        with a `time_variance_input_file`.
+
+        Args:
+            query: the query to sample. its `seed`, if set, is the seed of the
+                first sample, and each following sample increments it
+            samples: the number of samples to run
+
+        Returns:
+            distribution: the mean, standard deviation, range, percentiles and
+                the trip time of each sample that found a route
+
+        Example:
+            >>> from nrel.routee.compass import CompassApp
+            >>> app = CompassApp.from_config_file("config.toml")
+            >>> eta = app.eta_distribution(query, samples=1000)
+            >>> eta["mean"], eta["percentiles"]
+
+        """
+        if samples < 1:
+            raise ValueError(f"samples must be positive, found {samples}")
+        result_json = self._app._run_eta_distribution(json.dumps(query), samples)
+        return json.loads(result_json)
+
+    def cost_matrix(
+        self,
+        origins: List[int],
+        destinations: List[int],
+        query: Optional[CompassQuery] = None,
+    ) -> List[List[Optional[float]]]:
+        """
+        Compute the network cost from each origin vertex to each destination
+        vertex, such as a travel time matrix for dispatching. Runs one search
+        per origin, in parallel, that stops once every destination is reached.
+        Routes are not built, and input and output plugins are not applied.
+
+        Args:
+            origins: the vertex ids to search from
+            destinations: the vertex ids to search to
+            query: optional query parameters used to build the models, such as
+                model overrides
+
+        Returns:
+            matrix: for each origin, the cost to each destination in the order
+                given, or None where the destination is not reachable
+
+        Example:
+            >>> from nrel.routee.compass import CompassApp
+            >>> app = CompassApp.from_config_file("config.toml")
+            >>> matrix = app.cost_matrix([0, 15, 742], [3, 99])
+            >>> matrix[0][1]
+
+        """
+        query_str = json.dumps(query) if query is not None else None
+        return self._app._run_matrix(origins, destinations, query_str)
+
     def graph_edge_origin(self, edge_id: int) -> int:
         """
         get the origin vertex id for some edge
@@ -355,6 +603,23 @@ class CompassApp:
         """
         return cast(float, self._app.graph_edge_distance(edge_id, distance_unit))
 
+    def graph_edge_distances(
+        self, edge_ids: List[int], distance_unit: Optional[str] = None
+    ) -> List[float]:
+        """
+        get the distances of many edges in one call, which avoids the overhead
+        of calling graph_edge_distance once per edge
+
+        Args:
+            edge_ids: the ids of the edges
+            distance_unit: distance unit, by default meters
+
+        Returns:
+            dists: the distance covered by traversing each edge, in the order given
+        """
+        distances = self._app.graph_edge_distances(edge_ids, distance_unit)
+        return cast(List[float], distances)
+
     def graph_get_out_edge_ids(self, vertex_id: int) -> List[int]:
         """
         get the list of edge ids that depart from some vertex
@@ -378,3 +643,79 @@ class CompassApp:
             edges: the edge ids of edges arriving at this vertex
         """
         return cast(List[int], self._app.graph_get_in_edge_ids(vertex_id))
+
+    def graph_get_out_edge_ids_batch(self, vertex_ids: List[int]) -> List[List[int]]:
+        """
+        get the lists of edge ids that depart from many vertices in one call
+
+        Args:
+            vertex_ids: the ids of the vertices
+
+        Returns:
+            edges: the edge ids of edges departing from each vertex, in the order given
+        """
+        return cast(List[List[int]], self._app.graph_get_out_edge_ids_batch(vertex_ids))
+
+    def graph_get_in_edge_ids_batch(self, vertex_ids: List[int]) -> List[List[int]]:
+        """
+        get the lists of edge ids that arrive at many vertices in one call
+
+        Args:
+            vertex_ids: the ids of the vertices
+
+        Returns:
+            edges: the edge ids of edges arriving at each vertex, in the order given
+        """
+        return cast(List[List[int]], self._app.graph_get_in_edge_ids_batch(vertex_ids))
+
+    def graph_vertex_sequence(self, edge_ids: List[int]) -> List[int]:
+        """
+        get the ordered list of vertex ids visited by a route
+
+        Args:
+            edge_ids: the ids of the edges of the route, in order of traversal
+
+        Returns:
+            vertices: the vertex ids visited by the route, from the origin of
+                the first edge to the destination of the last edge
+        """
+        return cast(List[int], self._app.graph_vertex_sequence(edge_ids))
+
+    def graph_vertex_coordinate(self, vertex_id: int) -> Tuple[float, float]:
+        """
+        get the coordinate of some vertex
+
+        Args:
+            vertex_id: the id of the vertex
+
+        Returns:
+            coordinate: the (x, y) coordinate of the vertex, its longitude and latitude
+        """
+        return cast(Tuple[float, float], self._app.graph_vertex_coordinate(vertex_id))
+
+    def graph_vertex_coordinates(
+        self, vertex_ids: List[int]
+    ) -> List[Tuple[float, float]]:
+        """
+        get the coordinates of many vertices in one call, such as the vertices
+        of a route from graph_vertex_sequence
+
+        Args:
+            vertex_ids: the ids of the vertices
+
+        Returns:
+            coordinates: the (x, y) coordinate of each vertex, in the order given
+        """
+        coordinates = self._app.graph_vertex_coordinates(vertex_ids)
+        return cast(List[Tuple[float, float]], coordinates)
+
+    def fingerprint(self) -> Dict[str, str]:
+        """
+        get the fingerprints of the loaded graph and configuration. these are
+        also included in each response, so a stored result can be tied to the
+        inputs that produced it.
+
+        Returns:
+            fingerprints: the "graph" and "config" fingerprints as hex strings
+        """
+        return cast(Dict[str, str], self._app.fingerprint())
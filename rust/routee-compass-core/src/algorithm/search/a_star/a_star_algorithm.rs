@@ -1,3 +1,4 @@
+use super::search_frontier::SearchFrontier;
 use crate::algorithm::search::Direction;
 use crate::algorithm::search::EdgeTraversal;
 use crate::algorithm::search::SearchError;
@@ -6,13 +7,9 @@ use crate::algorithm::search::SearchResult;
 use crate::algorithm::search::SearchTreeBranch;
 use crate::model::network::edge_id::EdgeId;
 use crate::model::network::vertex_id::VertexId;
-use crate::model::unit::AsF64;
 use crate::model::unit::Cost;
-use crate::model::unit::ReverseCost;
-use crate::util::priority_queue::InternalPriorityQueue;
 
-use std::collections::HashMap;
-use std::time::Instant;
+use std::collections::{HashMap, HashSet};
 
 /// run an A* Search over the given directed graph model. traverses links
 /// from the source, via the provided direction, to the target. uses the
@@ -26,156 +23,127 @@ pub fn run_vertex_oriented(
     weight_factor: Option<Cost>,
     si: &SearchInstance,
 ) -> Result<SearchResult, SearchError> {
-    if target.map_or(false, |t| t == source) {
-        return Ok(SearchResult::default());
-    }
-
-    // context for the search (graph, search functions, frontier priority queue)
-    let mut costs: InternalPriorityQueue<VertexId, ReverseCost> = InternalPriorityQueue::default();
-    let mut traversal_costs: HashMap<VertexId, Cost> = HashMap::new();
-    let mut solution: HashMap<VertexId, SearchTreeBranch> = HashMap::new();
-
-    // setup initial search state
-    traversal_costs.insert(source, Cost::ZERO);
-    let initial_state = si.state_model.initial_state()?;
-    let origin_cost = match target {
-        None => Cost::ZERO,
-        Some(target) => {
-            let cost_est = si.estimate_traversal_cost(source, target, &initial_state)?;
-            Cost::new(cost_est.as_f64() * weight_factor.unwrap_or(Cost::ONE).as_f64())
-        }
-    };
-    costs.push(source, origin_cost.into());
-
-    let start_time = Instant::now();
-    let mut iterations = 0;
-
-    loop {
-        si.termination_model
-            .test(&start_time, solution.len(), iterations)?;
-
-        let current_vertex_id = match advance_search(&mut costs, source, target)? {
-            None => break,
-            Some(id) => id,
-        };
-
-        let last_edge_id = get_last_traversed_edge_id(&current_vertex_id, &source, &solution)?;
-        // let last_edge = match last_edge_id {
-        //     Some(id) => Some(si.directed_graph.get_edge(&id)?),
-        //     None => None,
-        // };
-
-        // grab the current state from the solution
-        let current_state = if current_vertex_id == source {
-            initial_state.clone()
-        } else {
-            solution
-                .get(&current_vertex_id)
-                .ok_or_else(|| {
-                    SearchError::InternalError(format!(
-                        "expected vertex id {} missing from solution",
-                        current_vertex_id
-                    ))
-                })?
-                .edge_traversal
-                .result_state
-                .clone()
-        };
-
-        // visit all neighbors of this source vertex
-        let incident_edge_iterator = direction.get_incident_edges(&current_vertex_id, si);
-        for edge_id in incident_edge_iterator {
-            let e = si.graph.get_edge(edge_id)?;
+    run_search(source, target, direction, weight_factor, None, si)
+}
 
-            let terminal_vertex_id = direction.terminal_vertex_id(e);
-            let key_vertex_id = direction.tree_key_vertex_id(e);
+/// counts the vertices reachable from the source, including the source itself,
+/// with a total cost within the budget. runs a one-to-many Dijkstra search that
+/// stops expanding at the budget and returns only the size of the settled set, so
+/// that it can be run from every vertex of a graph (for example, as a proxy for
+/// network centrality) without building or returning the search trees.
+pub fn run_reachable_count(
+    source: VertexId,
+    budget: Cost,
+    direction: &Direction,
+    si: &SearchInstance,
+) -> Result<usize, SearchError> {
+    let result = run_search(source, None, direction, Some(Cost::ZERO), Some(budget), si)?;
+    Ok(result.tree.len() + 1)
+}
 
-            let valid_frontier = si.frontier_model.valid_frontier(
-                e,
-                &current_state,
-                &solution,
-                direction,
-                &si.state_model,
-            )?;
-            if !valid_frontier {
-                continue;
-            }
-            let et =
-                direction.perform_edge_traversal(*edge_id, last_edge_id, &current_state, si)?;
-            let current_gscore = traversal_costs
-                .get(&terminal_vertex_id)
-                .unwrap_or(&Cost::INFINITY)
-                .to_owned();
-            let tentative_gscore = current_gscore + et.total_cost();
-            let existing_gscore = traversal_costs
-                .get(&key_vertex_id)
-                .unwrap_or(&Cost::INFINITY)
-                .to_owned();
-            if tentative_gscore < existing_gscore {
-                traversal_costs.insert(key_vertex_id, tentative_gscore);
-
-                // update solution
-                let traversal = SearchTreeBranch {
-                    terminal_vertex: terminal_vertex_id,
-                    edge_traversal: et,
-                };
-                solution.insert(key_vertex_id, traversal);
-
-                let dst_h_cost = match target {
-                    None => Cost::ZERO,
-                    Some(target_v) => {
-                        let cost_est =
-                            si.estimate_traversal_cost(key_vertex_id, target_v, &current_state)?;
-                        Cost::new(cost_est.as_f64() * weight_factor.unwrap_or(Cost::ONE).as_f64())
-                    }
-                };
-                let f_score_value = tentative_gscore + dst_h_cost;
-                costs.push_increase(key_vertex_id, f_score_value.into());
+/// finds the `k` targets nearest to the source by network cost, in order of
+/// increasing cost. runs a one-to-many Dijkstra search that stops as soon as `k`
+/// targets have been settled, so nearby targets are found without searching the
+/// whole graph. if fewer than `k` targets are reachable, all reachable targets are
+/// returned. the source is included if it is itself a target.
+pub fn run_nearest_targets(
+    source: VertexId,
+    targets: &HashSet<VertexId>,
+    k: usize,
+    direction: &Direction,
+    si: &SearchInstance,
+) -> Result<Vec<(VertexId, Cost)>, SearchError> {
+    let mut nearest = Vec::with_capacity(k.min(targets.len()));
+    if k == 0 {
+        return Ok(nearest);
+    }
+    let frontier = SearchFrontier::new(source, None, direction, Some(Cost::ZERO), si)?;
+    for settled in frontier {
+        let (vertex_id, cost) = settled?;
+        if targets.contains(&vertex_id) {
+            nearest.push((vertex_id, cost));
+            if nearest.len() == k {
+                break;
             }
         }
-        iterations += 1;
     }
-    log::debug!(
-        "search iterations: {}, size of search tree: {}",
-        iterations,
-        solution.len()
-    );
-
-    #[cfg(debug_assertions)]
-    {
-        use std::io::Write;
-        use std::path::PathBuf;
-
-        log::debug!("Building flamegraph for search memory usage..");
-        let mut flamegraph = allocative::FlameGraphBuilder::default();
-        flamegraph.visit_root(&costs);
-        flamegraph.visit_root(&traversal_costs);
-        flamegraph.visit_root(&solution);
-        let output = flamegraph.finish_and_write_flame_graph();
-
-        let search_name = match target {
-            None => format!("{}_to_all", source),
-            Some(tid) => format!("{}_to_{}", source, tid),
-        };
+    Ok(nearest)
+}
 
-        let outdir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .join("..")
-            .join("target")
-            .join("flamegraph");
+/// finds the target that is cheapest to reach from the source, such as the entry
+/// point of a destination region. runs a one-to-many Dijkstra search that stops as
+/// soon as any target is settled. the search tree is returned so that the route to
+/// the target can be reconstructed, and the target is None if none are reachable.
+pub fn run_first_target(
+    source: VertexId,
+    targets: &HashSet<VertexId>,
+    direction: &Direction,
+    si: &SearchInstance,
+) -> Result<(Option<(VertexId, Cost)>, SearchResult), SearchError> {
+    let mut first = None;
+    let mut frontier = SearchFrontier::new(source, None, direction, Some(Cost::ZERO), si)?;
+    if !targets.is_empty() {
+        for settled in frontier.by_ref() {
+            let (vertex_id, cost) = settled?;
+            if targets.contains(&vertex_id) {
+                first = Some((vertex_id, cost));
+                break;
+            }
+        }
+    }
+    Ok((first, frontier.into_search_result()))
+}
 
-        if !outdir.exists() {
-            std::fs::create_dir(&outdir).unwrap();
+/// ranks the targets by network cost from the source, in order of increasing cost.
+/// runs a one-to-many Dijkstra search that stops as soon as every target has been
+/// settled. targets that are not reachable are left out of the ranking. the search
+/// tree is returned so that the route to any ranked target can be reconstructed.
+pub fn run_ranked_targets(
+    source: VertexId,
+    targets: &HashSet<VertexId>,
+    direction: &Direction,
+    si: &SearchInstance,
+) -> Result<(Vec<(VertexId, Cost)>, SearchResult), SearchError> {
+    let mut ranked = Vec::with_capacity(targets.len());
+    let mut frontier = SearchFrontier::new(source, None, direction, Some(Cost::ZERO), si)?;
+    if !targets.is_empty() {
+        for settled in frontier.by_ref() {
+            let (vertex_id, cost) = settled?;
+            if targets.contains(&vertex_id) {
+                ranked.push((vertex_id, cost));
+                if ranked.len() == targets.len() {
+                    break;
+                }
+            }
         }
+    }
+    Ok((ranked, frontier.into_search_result()))
+}
 
-        let mut flamegraph_file = std::fs::File::create(
-            outdir.join(format!("search_memory_flamegraph_{}.out", search_name)),
-        )
-        .unwrap();
-        flamegraph_file.write_all(output.as_bytes()).unwrap();
+/// the A* search shared by the one-to-one, one-to-many and reachable count searches.
+/// when a budget is provided, vertices with a cost greater than the budget are not
+/// added to the search. runs a [`SearchFrontier`] to completion.
+fn run_search(
+    source: VertexId,
+    target: Option<VertexId>,
+    direction: &Direction,
+    weight_factor: Option<Cost>,
+    budget: Option<Cost>,
+    si: &SearchInstance,
+) -> Result<SearchResult, SearchError> {
+    if target.map_or(false, |t| t == source) {
+        return Ok(SearchResult::default());
     }
 
-    let result = SearchResult::new(solution, iterations);
-    Ok(result)
+    let frontier = SearchFrontier::new(source, target, direction, weight_factor, si)?;
+    let mut frontier = match budget {
+        Some(budget) => frontier.with_budget(budget),
+        None => frontier,
+    };
+    for settled in frontier.by_ref() {
+        settled?;
+    }
+    Ok(frontier.into_search_result())
 }
 
 /// convenience method when origin and destination are specified using
@@ -210,6 +178,7 @@ pub fn run_edge_oriented(
             let SearchResult {
                 mut tree,
                 iterations,
+                restricted,
             } = run_vertex_oriented(e1_dst, None, direction, weight_factor, si)?;
             if !tree.contains_key(&e1_dst) {
                 tree.extend([(e1_dst, src_branch)]);
@@ -217,6 +186,7 @@ pub fn run_edge_oriented(
             let updated = SearchResult {
                 tree,
                 iterations: iterations + 1,
+                restricted,
             };
             Ok(updated)
         }
@@ -245,16 +215,14 @@ pub fn run_edge_oriented(
                     edge_traversal: src_et,
                 };
                 let tree = HashMap::from([(e2_dst, src_traversal), (e1_dst, dst_traversal)]);
-                let result = SearchResult {
-                    tree,
-                    iterations: 1,
-                };
+                let result = SearchResult::new(tree, 1);
                 return Ok(result);
             } else {
                 // run a search and append source/target edges to result
                 let SearchResult {
                     mut tree,
                     iterations,
+                    restricted,
                 } = run_vertex_oriented(e1_dst, Some(e2_src), direction, weight_factor, si)?;
 
                 if tree.is_empty() {
@@ -295,6 +263,7 @@ pub fn run_edge_oriented(
                 let result = SearchResult {
                     tree,
                     iterations: iterations + 2,
+                    restricted,
                 };
                 Ok(result)
             }
@@ -302,98 +271,26 @@ pub fn run_edge_oriented(
     }
 }
 
-/// grab the current vertex id, but handle some other termination conditions
-/// based on the state of the priority queue and optional search destination
-/// - we reach the destination                                       (Ok)
-/// - if the set is ever empty and there's no destination            (Ok)
-/// - if the set is ever empty and there's a destination             (Err)
-///
-/// # Arguments
-/// * `cost`   - queue of priority-ranked vertices for exploration
-/// * `source` - search source vertex
-/// * `target` - optional search destination
-///
-/// # Results
-/// The next vertex to search. None if the queue has been exhausted in a search with no
-/// destination, or we have reached our destination.
-/// An error if no path exists for a search that includes a destination.
-fn advance_search(
-    cost: &mut InternalPriorityQueue<VertexId, ReverseCost>,
-    source: VertexId,
-    target: Option<VertexId>,
-) -> Result<Option<VertexId>, SearchError> {
-    match (cost.pop(), target) {
-        (None, Some(target_vertex_id)) => Err(SearchError::NoPathExistsBetweenVertices(
-            source,
-            target_vertex_id,
-        )),
-        (None, None) => Ok(None),
-        (Some((current_v, _)), Some(target_v)) if current_v == target_v => Ok(None),
-        (Some((current_vertex_id, _)), _) => Ok(Some(current_vertex_id)),
-    }
-}
-
-/// Find the last-traversed edge before reaching this vertex id.
-/// The logic is the same for forward and reverse searches but finds
-/// a different result because the trees are different.
-/// Forward case: find `prev` from v2 in `(v1)-[prev]->(v2)-[next]->(v3)`
-/// Reverse case: find `next` from v2 in `(v1)-[prev]->(v2)-[next]->(v3)`
-///
-/// # Arguments
-/// * `this_vertex_id`  - current vertex, v2 in diagram
-/// * `first_vertex_id` - source of this search, the origin vertex in a forward
-///                       search or the destination vertex in a reverse search
-/// * `tree`            - current search solution tree
-///
-/// # Returns
-///
-/// The EdgeId for the edge that was traversed to reach this vertex, or None
-/// if no edges have yet been traversed.
-fn get_last_traversed_edge_id(
-    this_vertex_id: &VertexId,
-    first_vertex_id: &VertexId,
-    tree: &HashMap<VertexId, SearchTreeBranch>,
-) -> Result<Option<EdgeId>, SearchError> {
-    if this_vertex_id == first_vertex_id {
-        Ok(None)
-    } else {
-        let edge_id = tree
-            .get(this_vertex_id)
-            .ok_or_else(|| {
-                SearchError::InternalError(format!(
-                    "expected vertex id {} missing from solution",
-                    this_vertex_id
-                ))
-            })?
-            .edge_traversal
-            .edge_id;
-        Ok(Some(edge_id))
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::algorithm::search::backtrack::vertex_oriented_route;
+    use crate::algorithm::search::util::test_search_instance::{self, build_graph};
+    use crate::algorithm::search::util::turn_count;
     use crate::algorithm::search::MinSearchTree;
-    use crate::model::access::default::NoAccessModel;
-    use crate::model::cost::CostAggregation;
-    use crate::model::cost::CostModel;
-    use crate::model::cost::VehicleCostRate;
+    use crate::algorithm::search::RerouteHysteresis;
+    use crate::model::access::{AccessModel, AccessModelError};
     use crate::model::frontier::default::no_restriction::NoRestriction;
-
-    use crate::model::map::MapModel;
-    use crate::model::map::MapModelConfig;
+    use crate::model::frontier::{FrontierModel, FrontierModelError};
     use crate::model::network::edge_id::EdgeId;
     use crate::model::network::graph::Graph;
     use crate::model::network::Edge;
     use crate::model::network::Vertex;
     use crate::model::state::StateFeature;
     use crate::model::state::StateModel;
+    use crate::model::state::StateVariable;
     use crate::model::termination::TerminationModel;
-    use crate::model::traversal::default::DistanceTraversalModel;
-    use crate::model::unit::{Distance, DistanceUnit};
-    use crate::util::compact_ordered_hash_map::CompactOrderedHashMap;
+    use crate::model::unit::{AsF64, Distance, DistanceUnit};
     use rayon::prelude::*;
     use std::sync::Arc;
 
@@ -403,6 +300,7 @@ mod tests {
             Vertex::new(1, 0.0, 0.0),
             Vertex::new(2, 0.0, 0.0),
             Vertex::new(3, 0.0, 0.0),
+            Vertex::new(4, 0.0, 0.0),
         ];
 
         let edges = vec![
@@ -416,19 +314,47 @@ mod tests {
             Edge::new(7, 0, 3, 2.0),
         ];
 
-        let mut adj = vec![CompactOrderedHashMap::empty(); vertices.len()];
-        let mut rev = vec![CompactOrderedHashMap::empty(); vertices.len()];
+        build_graph(vertices, edges)
+    }
 
-        for edge in &edges {
-            adj[edge.src_vertex_id.0].insert(edge.edge_id, edge.dst_vertex_id);
-            rev[edge.dst_vertex_id.0].insert(edge.edge_id, edge.src_vertex_id);
+    /// builds a search instance over the mock graph using a distance cost function
+    fn build_mock_search_instance(
+        frontier_model: Arc<dyn FrontierModel>,
+        termination_model: TerminationModel,
+    ) -> SearchInstance {
+        build_search_instance(build_mock_graph(), frontier_model, termination_model)
+    }
+
+    fn build_search_instance(
+        graph: Graph,
+        frontier_model: Arc<dyn FrontierModel>,
+        termination_model: TerminationModel,
+    ) -> SearchInstance {
+        SearchInstance {
+            frontier_model,
+            termination_model: Arc::new(termination_model),
+            ..test_search_instance::build_search_instance(graph)
+        }
+    }
+
+    /// frontier model that removes a fixed set of edges from the search
+    struct BlockedEdges(Vec<EdgeId>);
+
+    impl FrontierModel for BlockedEdges {
+        fn valid_frontier(
+            &self,
+            edge: &Edge,
+            _previous_edge_id: Option<EdgeId>,
+            _state: &[StateVariable],
+            _tree: &HashMap<VertexId, SearchTreeBranch>,
+            _direction: &Direction,
+            _state_model: &StateModel,
+        ) -> Result<bool, FrontierModelError> {
+            self.valid_edge(edge)
         }
 
-        Graph {
-            adj: adj.into_boxed_slice(),
-            rev: rev.into_boxed_slice(),
-            edges: edges.into_boxed_slice(),
-            vertices: vertices.into_boxed_slice(),
+        fn valid_edge(&self, edge: &Edge) -> Result<bool, FrontierModelError> {
+            Ok(!self.0.contains(&edge.edge_id))
         }
     }
 
@@ -479,44 +405,10 @@ mod tests {
             (VertexId(2), VertexId(3), vec![EdgeId(4)]), // 2 -[4]-> 3
         ];
 
-        let graph = Arc::new(build_mock_graph());
-        let map_model = Arc::new(MapModel::new(graph.clone(), MapModelConfig::default()).unwrap());
-
-        // setup the graph, traversal model, and a* heuristic to be shared across the queries in parallel
-        // these live in the "driver" process and are passed as read-only memory to each executor process
-        let state_model = Arc::new(
-            StateModel::empty()
-                .extend(vec![(
-                    String::from("distance"),
-                    StateFeature::Distance {
-                        distance_unit: DistanceUnit::Kilometers,
-                        initial: Distance::new(0.0),
-                    },
-                )])
-                .unwrap(),
+        let si = build_mock_search_instance(
+            Arc::new(NoRestriction {}),
+            TerminationModel::IterationsLimit { limit: 20 },
         );
-        let cost_model = CostModel::new(
-            // vec![(String::from("distance"), 0usize)],
-            Arc::new(HashMap::from([(String::from("distance"), 1.0)])),
-            Arc::new(HashMap::from([(
-                String::from("distance"),
-                VehicleCostRate::Raw,
-            )])),
-            Arc::new(HashMap::new()),
-            CostAggregation::Sum,
-            state_model.clone(),
-        )
-        .unwrap();
-        let si = SearchInstance {
-            graph,
-            map_model,
-            state_model: state_model.clone(),
-            traversal_model: Arc::new(DistanceTraversalModel::new(DistanceUnit::Meters)),
-            access_model: Arc::new(NoAccessModel {}),
-            cost_model: Arc::new(cost_model),
-            frontier_model: Arc::new(NoRestriction {}),
-            termination_model: Arc::new(TerminationModel::IterationsLimit { limit: 20 }),
-        };
 
         // execute the route search
         let result: Vec<Result<MinSearchTree, SearchError>> = queries
@@ -540,4 +432,583 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_landmarks_bound_costs_and_preserve_routes() {
+        use crate::algorithm::search::a_star::{
+            LandmarkSelection, Landmarks, LandmarksConfig, SearchFrontier,
+        };
+        use itertools::Itertools;
+
+        let si = build_mock_search_instance(
+            Arc::new(NoRestriction {}),
+            TerminationModel::IterationsLimit { limit: 20 },
+        );
+        // the cost of the final vertex settled by a search to the target
+        fn search_cost(
+            src: VertexId,
+            dst: VertexId,
+            direction: &Direction,
+            si: &SearchInstance,
+        ) -> Option<Cost> {
+            SearchFrontier::new(src, Some(dst), direction, None, si)
+                .unwrap()
+                .last()
+                .and_then(|settled| settled.ok())
+                .filter(|(v, _)| *v == dst)
+                .map(|(_, cost)| cost)
+        }
+        let connected = [VertexId(0), VertexId(1), VertexId(2), VertexId(3)];
+
+        for selection in [LandmarkSelection::Random, LandmarkSelection::Farthest] {
+            let config = LandmarksConfig {
+                count: 2,
+                selection,
+                seed: Some(0),
+            };
+            let landmarks =
+                Arc::new(Landmarks::build(&config, &si, serde_json::json!({})).unwrap());
+            assert_eq!(landmarks.vertex_ids().len(), 2);
+            let alt_si = SearchInstance {
+                landmarks: Some(landmarks.clone()),
+                ..build_mock_search_instance(
+                    Arc::new(NoRestriction {}),
+                    TerminationModel::IterationsLimit { limit: 20 },
+                )
+            };
+
+            let pairs = connected
+                .iter()
+                .cartesian_product(connected.iter())
+                .filter(|(src, dst)| src != dst);
+            for (src, dst) in pairs {
+                let expected = search_cost(*src, *dst, &Direction::Forward, &si).unwrap();
+                let bound = landmarks.lower_bound(*src, *dst);
+                assert!(
+                    bound <= expected,
+                    "bound {} exceeds cost {} from {} to {}",
+                    bound,
+                    expected,
+                    src,
+                    dst
+                );
+                let forward = search_cost(*src, *dst, &Direction::Forward, &alt_si);
+                let reverse = search_cost(*dst, *src, &Direction::Reverse, &alt_si);
+                assert_eq!(forward, Some(expected));
+                assert_eq!(reverse, Some(expected));
+            }
+
+            // the tighter heuristic does not change the routes
+            let route = run_vertex_oriented(
+                VertexId(0),
+                Some(VertexId(1)),
+                &Direction::Forward,
+                None,
+                &alt_si,
+            )
+            .and_then(|r| vertex_oriented_route(VertexId(0), VertexId(1), &r.tree))
+            .unwrap();
+            let route_edges: Vec<EdgeId> = route.iter().map(|r| r.edge_id).collect();
+            assert_eq!(route_edges, vec![EdgeId(7), EdgeId(5), EdgeId(3)]);
+        }
+    }
+
+    #[test]
+    fn test_no_route_causes() {
+        use crate::algorithm::search::NoRouteCause;
+        use std::time::Duration;
+
+        // vertex 4 has no incident edges
+        let si = build_mock_search_instance(
+            Arc::new(NoRestriction {}),
+            TerminationModel::IterationsLimit { limit: 20 },
+        );
+        let disconnected = run_vertex_oriented(
+            VertexId(0),
+            Some(VertexId(4)),
+            &Direction::Forward,
+            None,
+            &si,
+        );
+        assert_eq!(
+            disconnected.err().and_then(|e| e.no_route_cause()),
+            Some(NoRouteCause::Disconnected)
+        );
+
+        // both edges leaving vertex 0 are blocked
+        let si = build_mock_search_instance(
+            Arc::new(BlockedEdges(vec![EdgeId(0), EdgeId(7)])),
+            TerminationModel::IterationsLimit { limit: 20 },
+        );
+        let restricted = run_vertex_oriented(
+            VertexId(0),
+            Some(VertexId(2)),
+            &Direction::Forward,
+            None,
+            &si,
+        );
+        assert_eq!(
+            restricted.err().and_then(|e| e.no_route_cause()),
+            Some(NoRouteCause::Restricted)
+        );
+
+        // the cause is recorded during the search rather than by searching the
+        // graph again, so any blocked edge marks a failed search as restricted,
+        // even to a vertex that is also unconnected
+        let si = build_mock_search_instance(
+            Arc::new(BlockedEdges(vec![EdgeId(0)])),
+            TerminationModel::IterationsLimit { limit: 20 },
+        );
+        let restricted = run_vertex_oriented(
+            VertexId(0),
+            Some(VertexId(4)),
+            &Direction::Forward,
+            None,
+            &si,
+        );
+        assert_eq!(
+            restricted.err().and_then(|e| e.no_route_cause()),
+            Some(NoRouteCause::Restricted)
+        );
+
+        // every edge costs more than the budget
+        let si = build_mock_search_instance(
+            Arc::new(NoRestriction {}),
+            TerminationModel::IterationsLimit { limit: 20 },
+        );
+        let frontier = SearchFrontier::new(
+            VertexId(0),
+            Some(VertexId(1)),
+            &Direction::Forward,
+            None,
+            &si,
+        )
+        .unwrap()
+        .with_budget(Cost::new(1.0));
+        let budget = frontier.collect::<Result<Vec<_>, _>>();
+        assert_eq!(
+            budget.err().and_then(|e| e.no_route_cause()),
+            Some(NoRouteCause::BudgetExceeded)
+        );
+
+        // the search requires more than one iteration
+        let si = build_mock_search_instance(
+            Arc::new(NoRestriction {}),
+            TerminationModel::IterationsLimit { limit: 1 },
+        );
+        let terminated = run_vertex_oriented(
+            VertexId(0),
+            Some(VertexId(1)),
+            &Direction::Forward,
+            None,
+            &si,
+        );
+        assert_eq!(
+            terminated.err().and_then(|e| e.no_route_cause()),
+            Some(NoRouteCause::TerminationLimit)
+        );
+
+        // a zero runtime limit is exceeded on the first iteration
+        let si = build_mock_search_instance(
+            Arc::new(NoRestriction {}),
+            TerminationModel::QueryRuntimeLimit {
+                limit: Duration::ZERO,
+                frequency: 1,
+            },
+        );
+        let timeout = run_vertex_oriented(
+            VertexId(0),
+            Some(VertexId(1)),
+            &Direction::Forward,
+            None,
+            &si,
+        );
+        assert_eq!(
+            timeout.err().and_then(|e| e.no_route_cause()),
+            Some(NoRouteCause::Timeout)
+        );
+    }
+
+    #[test]
+    fn test_wrong_way_penalty() {
+        // (0) -[0]-> (1) 10 units distance
+        // (1) -[1]-> (2) 10 units distance
+        // (2) -[2]-> (0) 1 unit distance, one-way, a worthwhile wrong-way shortcut
+        // (1) -[3]-> (3) 1 unit distance
+        // (3) -[4]-> (0) 5 units distance, one-way, not worth traversing the wrong way
+        let vertices: Vec<Vertex> = (0..4).map(|i| Vertex::new(i, 0.0, 0.0)).collect();
+        let edges = vec![
+            Edge::new(0, 0, 1, 10.0),
+            Edge::new(1, 1, 2, 10.0),
+            Edge::new(2, 2, 0, 1.0),
+            Edge::new(3, 1, 3, 1.0),
+            Edge::new(4, 3, 0, 5.0),
+        ];
+        let run = |penalty: Option<f64>, destination: usize| -> Vec<EdgeId> {
+            let mut si = build_search_instance(
+                build_graph(vertices.clone(), edges.clone()),
+                Arc::new(NoRestriction {}),
+                TerminationModel::IterationsLimit { limit: 20 },
+            );
+            si.wrong_way_penalty = penalty.map(Cost::new);
+            let dst = VertexId(destination);
+            let result =
+                run_vertex_oriented(VertexId(0), Some(dst), &Direction::Forward, None, &si)
+                    .unwrap();
+            vertex_oriented_route(VertexId(0), dst, &result.tree)
+                .unwrap()
+                .iter()
+                .map(|et| et.edge_id)
+                .collect()
+        };
+
+        // without a penalty, one-way edges are only traversed in their direction
+        assert_eq!(run(None, 2), vec![EdgeId(0), EdgeId(1)]);
+        assert_eq!(run(None, 3), vec![EdgeId(0), EdgeId(3)]);
+
+        // with a high penalty, only the wrong-way shortcut is used (10 < 20, but 50 > 11)
+        assert_eq!(run(Some(10.0), 2), vec![EdgeId(2)]);
+        assert_eq!(run(Some(10.0), 3), vec![EdgeId(0), EdgeId(3)]);
+
+        // an infinite penalty reproduces normal routing
+        assert_eq!(run(Some(f64::INFINITY), 2), vec![EdgeId(0), EdgeId(1)]);
+        assert_eq!(run(Some(f64::INFINITY), 3), vec![EdgeId(0), EdgeId(3)]);
+    }
+
+    /// access model that adds a fixed turn cost, in meters of distance, to every
+    /// movement. fails on movements where the edges do not meet.
+    struct TurnCost(f64);
+
+    impl AccessModel for TurnCost {
+        fn state_features(&self) -> Vec<(String, StateFeature)> {
+            vec![]
+        }
+
+        fn access_edge(
+            &self,
+            traversal: (&Vertex, &Edge, &Vertex, &Edge, &Vertex),
+            state: &mut Vec<StateVariable>,
+            state_model: &StateModel,
+        ) -> Result<(), AccessModelError> {
+            let (_, prev, _, next, _) = traversal;
+            if prev.dst_vertex_id != next.src_vertex_id {
+                return Err(AccessModelError::RuntimeError {
+                    name: String::from("turn cost"),
+                    error: format!("edges {} and {} do not meet", prev.edge_id, next.edge_id),
+                });
+            }
+            state_model.add_distance(
+                state,
+                &String::from("distance"),
+                &Distance::new(self.0),
+                &DistanceUnit::Meters,
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_wrong_way_penalty_access_costs() {
+        // (0) -[0]-> (1) 10 units distance
+        // (2) -[1]-> (1) 1 unit distance, one-way, traversed the wrong way
+        // (2) -[2]-> (3) 1 unit distance
+        // (3) -[3]-> (4) 1 unit distance
+        let edges = vec![
+            Edge::new(0, 0, 1, 10.0),
+            Edge::new(1, 2, 1, 1.0),
+            Edge::new(2, 2, 3, 1.0),
+            Edge::new(3, 3, 4, 1.0),
+        ];
+        let mut si = build_search_instance(
+            build_graph(test_search_instance::vertices_at_origin(5), edges),
+            Arc::new(NoRestriction {}),
+            TerminationModel::IterationsLimit { limit: 20 },
+        );
+        si.access_model = Arc::new(TurnCost(5.0));
+        si.wrong_way_penalty = Some(Cost::new(2.0));
+        let dst = VertexId(4);
+        let result =
+            run_vertex_oriented(VertexId(0), Some(dst), &Direction::Forward, None, &si).unwrap();
+        let route = vertex_oriented_route(VertexId(0), dst, &result.tree).unwrap();
+        let edge_ids: Vec<EdgeId> = route.iter().map(|et| et.edge_id).collect();
+        assert_eq!(edge_ids, vec![EdgeId(0), EdgeId(1), EdgeId(2), EdgeId(3)]);
+
+        // turns onto and off of the wrong-way edge are not costed, only the
+        // final turn along the direction of both edges
+        let access_costs: Vec<f64> = route.iter().map(|et| et.access_cost.as_f64()).collect();
+        assert_eq!(access_costs, vec![0.0, 0.0, 0.0, 5.0]);
+        assert_eq!(route[1].traversal_cost, Cost::new(2.0));
+    }
+
+    #[test]
+    fn test_reroute_hysteresis() {
+        // (0) -[0]-> (2) 10 units distance, direct
+        // (0) -[1]-> (1) 5 units distance
+        // (1) -[2]-> (2) 4.9 units distance, then perturbed to 5.1 and to 8.0
+        let vertices: Vec<Vertex> = (0..3).map(|i| Vertex::new(i, 0.0, 0.0)).collect();
+        let run = |last_edge_distance: f64, hysteresis: Option<RerouteHysteresis>| {
+            let edges = vec![
+                Edge::new(0, 0, 2, 10.0),
+                Edge::new(1, 0, 1, 5.0),
+                Edge::new(2, 1, 2, last_edge_distance),
+            ];
+            let mut si = build_search_instance(
+                build_graph(vertices.clone(), edges),
+                Arc::new(NoRestriction {}),
+                TerminationModel::IterationsLimit { limit: 20 },
+            );
+            si.reroute_hysteresis = hysteresis;
+            let dst = VertexId(2);
+            let result =
+                run_vertex_oriented(VertexId(0), Some(dst), &Direction::Forward, None, &si)
+                    .unwrap();
+            let route = vertex_oriented_route(VertexId(0), dst, &result.tree).unwrap();
+            let edge_ids = route.iter().map(|et| et.edge_id).collect::<Vec<_>>();
+            let route_cost = route.iter().map(|et| et.total_cost().as_f64()).sum::<f64>();
+            (edge_ids, route_cost)
+        };
+        let previous_route = vec![EdgeId(1), EdgeId(2)];
+        assert_eq!(run(4.9, None).0, previous_route);
+        let hysteresis = || Some(RerouteHysteresis::new(previous_route.clone(), 0.05).unwrap());
+
+        // a tiny perturbation flips the route unless the previous route is preferred
+        assert_eq!(run(5.1, None).0, vec![EdgeId(0)]);
+        assert_eq!(run(5.1, hysteresis()).0, previous_route);
+
+        // a meaningful change still flips the route
+        assert_eq!(run(8.0, hysteresis()).0, vec![EdgeId(0)]);
+
+        // the preference only orders the search, the route reports its full cost
+        assert_eq!(run(4.9, hysteresis()), run(4.9, None));
+        assert_eq!(run(5.1, hysteresis()).1, 10.1);
+    }
+
+    #[test]
+    fn test_reachable_count_matches_tree() {
+        let si = build_mock_search_instance(
+            Arc::new(NoRestriction {}),
+            TerminationModel::IterationsLimit { limit: 20 },
+        );
+        let source = VertexId(0);
+        let full =
+            run_vertex_oriented(source, None, &Direction::Forward, Some(Cost::ZERO), &si).unwrap();
+        // cost to reach each vertex in the full one-to-many tree
+        let reached_costs: Vec<f64> = full
+            .tree
+            .keys()
+            .map(|v| {
+                vertex_oriented_route(source, *v, &full.tree)
+                    .unwrap()
+                    .iter()
+                    .map(|et| et.total_cost().as_f64())
+                    .sum()
+            })
+            .collect();
+        assert_eq!(
+            reached_costs.len(),
+            3,
+            "all other vertices of the box are reachable"
+        );
+
+        let mut budgets = reached_costs.clone();
+        budgets.extend([0.0, f64::MAX]);
+        for budget in budgets {
+            let expected = 1 + reached_costs.iter().filter(|c| **c <= budget).count();
+            let count =
+                run_reachable_count(source, Cost::new(budget), &Direction::Forward, &si).unwrap();
+            assert_eq!(count, expected, "reachable count with budget {}", budget);
+        }
+    }
+
+    #[test]
+    fn test_frontier_reproduces_tree() {
+        let si = build_mock_search_instance(
+            Arc::new(NoRestriction {}),
+            TerminationModel::IterationsLimit { limit: 20 },
+        );
+        let source = VertexId(0);
+        let full =
+            run_vertex_oriented(source, None, &Direction::Forward, Some(Cost::ZERO), &si).unwrap();
+
+        let mut frontier =
+            SearchFrontier::new(source, None, &Direction::Forward, Some(Cost::ZERO), &si).unwrap();
+        let settled: Vec<(VertexId, Cost)> = frontier.by_ref().map(|r| r.unwrap()).collect();
+        assert_eq!(settled.first(), Some(&(source, Cost::ZERO)));
+        assert_eq!(settled.len(), full.tree.len() + 1);
+        for pair in settled.windows(2) {
+            assert!(pair[0].1 <= pair[1].1, "vertices settled in cost order");
+        }
+        for (vertex_id, cost) in settled.iter().skip(1) {
+            let expected: f64 = vertex_oriented_route(source, *vertex_id, &full.tree)
+                .unwrap()
+                .iter()
+                .map(|et| et.total_cost().as_f64())
+                .sum();
+            assert_eq!(cost.as_f64(), expected, "cost to vertex {}", vertex_id);
+        }
+
+        let result = frontier.into_search_result();
+        assert_eq!(result.iterations, full.iterations);
+        let mut keys: Vec<&VertexId> = result.tree.keys().collect();
+        let mut expected_keys: Vec<&VertexId> = full.tree.keys().collect();
+        keys.sort();
+        expected_keys.sort();
+        assert_eq!(keys, expected_keys);
+    }
+
+    #[test]
+    fn test_nearest_targets() {
+        // a full search from vertex 0 needs 4 iterations, which exceeds this limit
+        let si = build_mock_search_instance(
+            Arc::new(NoRestriction {}),
+            TerminationModel::IterationsLimit { limit: 3 },
+        );
+        let full = run_vertex_oriented(VertexId(0), None, &Direction::Forward, None, &si);
+        assert!(full.is_err());
+
+        let targets = HashSet::from([VertexId(1), VertexId(2), VertexId(3), VertexId(4)]);
+        let nearest =
+            run_nearest_targets(VertexId(0), &targets, 2, &Direction::Forward, &si).unwrap();
+        let ids: Vec<VertexId> = nearest.iter().map(|(v, _)| *v).collect();
+        assert_eq!(ids, vec![VertexId(3), VertexId(2)]);
+        assert!(
+            nearest[0].1 < nearest[1].1,
+            "nearest targets ordered by cost"
+        );
+    }
+
+    #[test]
+    fn test_first_target() {
+        let si = build_mock_search_instance(
+            Arc::new(NoRestriction {}),
+            TerminationModel::IterationsLimit { limit: 20 },
+        );
+        let targets = HashSet::from([VertexId(1), VertexId(2), VertexId(3)]);
+        let (first, result) =
+            run_first_target(VertexId(0), &targets, &Direction::Forward, &si).unwrap();
+        let (vertex_id, cost) = first.unwrap();
+        assert_eq!(
+            vertex_id,
+            VertexId(3),
+            "the cheapest target is reached first"
+        );
+        let route = vertex_oriented_route(VertexId(0), vertex_id, &result.tree).unwrap();
+        let route_cost: f64 = route.iter().map(|et| et.total_cost().as_f64()).sum();
+        assert_eq!(cost.as_f64(), route_cost);
+
+        // vertex 4 is not connected to the rest of the graph
+        let unreachable = HashSet::from([VertexId(4)]);
+        let (first, _) =
+            run_first_target(VertexId(0), &unreachable, &Direction::Forward, &si).unwrap();
+        assert!(first.is_none());
+    }
+
+    #[test]
+    fn test_nearest_targets_fewer_than_k() {
+        let si = build_mock_search_instance(
+            Arc::new(NoRestriction {}),
+            TerminationModel::IterationsLimit { limit: 20 },
+        );
+        // vertex 4 is not connected to the rest of the graph
+        let targets = HashSet::from([VertexId(0), VertexId(1), VertexId(4)]);
+        let nearest =
+            run_nearest_targets(VertexId(0), &targets, 3, &Direction::Forward, &si).unwrap();
+        let ids: Vec<VertexId> = nearest.iter().map(|(v, _)| *v).collect();
+        assert_eq!(ids, vec![VertexId(0), VertexId(1)]);
+        assert_eq!(nearest[0].1, Cost::ZERO);
+    }
+
+    #[test]
+    fn test_ranked_targets() {
+        // a full search from vertex 0 exceeds this limit, so the search must stop
+        // once both targets are settled
+        let si = build_mock_search_instance(
+            Arc::new(NoRestriction {}),
+            TerminationModel::IterationsLimit { limit: 3 },
+        );
+        let targets = HashSet::from([VertexId(2), VertexId(3)]);
+        let (ranked, result) =
+            run_ranked_targets(VertexId(0), &targets, &Direction::Forward, &si).unwrap();
+        let ids: Vec<VertexId> = ranked.iter().map(|(v, _)| *v).collect();
+        assert_eq!(ids, vec![VertexId(3), VertexId(2)]);
+        for (vertex_id, _) in ranked.iter() {
+            let route = vertex_oriented_route(VertexId(0), *vertex_id, &result.tree).unwrap();
+            assert!(!route.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_frontier_early_stop() {
+        let si = build_mock_search_instance(
+            Arc::new(NoRestriction {}),
+            TerminationModel::IterationsLimit { limit: 20 },
+        );
+        let mut frontier =
+            SearchFrontier::new(VertexId(0), None, &Direction::Forward, None, &si).unwrap();
+        let first_two: Vec<(VertexId, Cost)> =
+            frontier.by_ref().take(2).map(|r| r.unwrap()).collect();
+        assert_eq!(first_two.len(), 2);
+        assert_eq!(frontier.iterations(), 2);
+        assert!(frontier.tree().contains_key(&first_two[1].0));
+    }
+
+    #[test]
+    fn test_prefer_fewer_turns() {
+        // 3x3 grid world with unit-length edges in both directions between
+        // neighboring vertices. all monotone paths from the bottom-left corner
+        // (0) to the top-right corner (8) have equal distance, but only the two
+        // L-shaped paths have a single turn.
+        // (6) - (7) - (8)
+        //  |     |     |
+        // (3) - (4) - (5)
+        //  |     |     |
+        // (0) - (1) - (2)
+        let vertices: Vec<Vertex> = (0..9)
+            .map(|i| {
+                Vertex::new(
+                    i,
+                    -105.0 + 0.001 * (i % 3) as f32,
+                    40.0 + 0.001 * (i / 3) as f32,
+                )
+            })
+            .collect();
+        let mut edges = vec![];
+        for i in 0..9 {
+            let neighbors = [(i % 3 < 2, i + 1), (i / 3 < 2, i + 3)];
+            for (exists, j) in neighbors {
+                if exists {
+                    edges.push(Edge::new(edges.len(), i, j, 1.0));
+                    edges.push(Edge::new(edges.len(), j, i, 1.0));
+                }
+            }
+        }
+        let run = |prefer_fewer_turns: bool| -> (usize, usize) {
+            let mut si = build_search_instance(
+                build_graph(vertices.clone(), edges.clone()),
+                Arc::new(NoRestriction {}),
+                TerminationModel::IterationsLimit { limit: 20 },
+            );
+            si.prefer_fewer_turns = prefer_fewer_turns;
+            let (src, dst) = (VertexId(0), VertexId(8));
+            let result =
+                run_vertex_oriented(src, Some(dst), &Direction::Forward, Some(Cost::ZERO), &si)
+                    .unwrap();
+            let route = vertex_oriented_route(src, dst, &result.tree).unwrap();
+            let turns = route
+                .windows(2)
+                .filter(|w| turn_count::is_turn(&w[0].edge_id, &w[1].edge_id, &si.graph).unwrap())
+                .count();
+            (route.len(), turns)
+        };
+
+        let (default_len, default_turns) = run(false);
+        let (preferred_len, preferred_turns) = run(true);
+        assert_eq!(default_len, 4);
+        assert_eq!(preferred_len, 4, "tie-break should not change route cost");
+        assert_eq!(
+            default_turns, 3,
+            "without the preference, the first path found zig-zags"
+        );
+        assert_eq!(preferred_turns, 1);
+    }
 }
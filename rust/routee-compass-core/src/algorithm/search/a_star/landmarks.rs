@@ -0,0 +1,215 @@
+use super::SearchFrontier;
+use crate::algorithm::search::{Direction, SearchError, SearchInstance};
+use crate::model::network::vertex_id::VertexId;
+use crate::model::unit::{AsF64, Cost};
+use rand::rngs::StdRng;
+use rand::seq::IteratorRandom;
+use rand::SeedableRng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// how the landmark vertices are chosen
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LandmarkSelection {
+    /// vertices chosen uniformly at random
+    Random,
+    /// starting from a random vertex, each landmark is the vertex with the
+    /// greatest cost from the landmarks chosen so far. landmarks on the
+    /// periphery of the network give tighter bounds than random ones.
+    #[default]
+    Farthest,
+}
+
+/// configures the landmarks used by the ALT (A*, landmarks, triangle
+/// inequality) heuristic.
+///
+/// ```toml
+/// [landmarks]
+/// count = 16
+/// selection = "farthest"
+/// seed = 42
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct LandmarksConfig {
+    /// number of landmarks. each landmark stores two costs per vertex.
+    pub count: usize,
+    #[serde(default)]
+    pub selection: LandmarkSelection,
+    /// seed for the random choices made during selection
+    pub seed: Option<u64>,
+}
+
+/// the costs from and to a set of landmark vertices, used to bound the cost
+/// between any pair of vertices with the triangle inequality. for a landmark L,
+/// the cost from v to t is at least `d(L,t) - d(L,v)` and `d(v,L) - d(t,L)`.
+///
+/// the tables are computed once with the models of a single search instance.
+/// the bound is only admissible for searches whose edge costs are no lower
+/// than the costs used to build the tables, so the tables carry a fingerprint
+/// of the cost configuration they were built with. a search instance should
+/// only be given the tables when its own configuration has the same fingerprint.
+pub struct Landmarks {
+    vertex_ids: Vec<VertexId>,
+    /// describes the cost configuration of the search instance the tables were built with
+    fingerprint: serde_json::Value,
+    /// cost from each landmark to every vertex, indexed by vertex id
+    from_landmark: Vec<Vec<Cost>>,
+    /// cost from every vertex to each landmark, indexed by vertex id
+    to_landmark: Vec<Vec<Cost>>,
+}
+
+impl Landmarks {
+    /// selects the landmarks and computes their cost tables with one search
+    /// in each direction from each landmark. the fingerprint describes the
+    /// cost configuration of the search instance.
+    pub fn build(
+        config: &LandmarksConfig,
+        si: &SearchInstance,
+        fingerprint: serde_json::Value,
+    ) -> Result<Landmarks, SearchError> {
+        let n_vertices = si.graph.n_vertices();
+        if config.count == 0 || config.count > n_vertices {
+            return Err(SearchError::BuildError(format!(
+                "landmark count must be between 1 and the number of vertices ({}), found {}",
+                n_vertices, config.count
+            )));
+        }
+        let mut rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        let (vertex_ids, from_landmark) = match config.selection {
+            LandmarkSelection::Random => {
+                let vertex_ids = si
+                    .graph
+                    .vertex_ids()
+                    .choose_multiple(&mut rng, config.count);
+                let from_landmark = vertex_ids
+                    .par_iter()
+                    .map(|v| costs_from(*v, &Direction::Forward, si))
+                    .collect::<Result<Vec<_>, _>>()?;
+                (vertex_ids, from_landmark)
+            }
+            LandmarkSelection::Farthest => select_farthest(config.count, &mut rng, si)?,
+        };
+        let to_landmark = vertex_ids
+            .par_iter()
+            .map(|v| costs_from(*v, &Direction::Reverse, si))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Landmarks {
+            vertex_ids,
+            fingerprint,
+            from_landmark,
+            to_landmark,
+        })
+    }
+
+    /// the landmark vertices
+    pub fn vertex_ids(&self) -> &[VertexId] {
+        &self.vertex_ids
+    }
+
+    /// the fingerprint of the cost configuration the tables were built with
+    pub fn fingerprint(&self) -> &serde_json::Value {
+        &self.fingerprint
+    }
+
+    /// a lower bound on the cost from src to dst, zero when no landmark
+    /// reaches both vertices.
+    pub fn lower_bound(&self, src: VertexId, dst: VertexId) -> Cost {
+        let bounds_from = self
+            .from_landmark
+            .iter()
+            .filter_map(|costs| difference(costs, dst, src));
+        let bounds_to = self
+            .to_landmark
+            .iter()
+            .filter_map(|costs| difference(costs, src, dst));
+        let bound = bounds_from
+            .chain(bounds_to)
+            .fold(0.0_f64, |acc, bound| acc.max(bound));
+        Cost::new(bound)
+    }
+}
+
+/// the difference `costs[a] - costs[b]`, if both costs are finite
+fn difference(costs: &[Cost], a: VertexId, b: VertexId) -> Option<f64> {
+    let a = costs.get(a.0)?.as_f64();
+    let b = costs.get(b.0)?.as_f64();
+    if a.is_finite() && b.is_finite() {
+        Some(a - b)
+    } else {
+        None
+    }
+}
+
+/// the cost between the source and every vertex, infinite for unreachable
+/// vertices. a reverse search gives the costs from every vertex to the source.
+fn costs_from(
+    source: VertexId,
+    direction: &Direction,
+    si: &SearchInstance,
+) -> Result<Vec<Cost>, SearchError> {
+    let mut costs = vec![Cost::INFINITY; si.graph.n_vertices()];
+    for settled in SearchFrontier::new(source, None, direction, None, si)? {
+        let (vertex_id, cost) = settled?;
+        if let Some(c) = costs.get_mut(vertex_id.0) {
+            *c = cost;
+        }
+    }
+    Ok(costs)
+}
+
+/// greedily selects each landmark as the reachable vertex with the greatest
+/// cost from its nearest landmark, beginning with the vertex farthest from
+/// the start vertex. when no unselected vertex is reachable from the landmarks,
+/// the next landmark is chosen at random. returns the landmarks along with
+/// their forward cost tables.
+fn select_farthest(
+    count: usize,
+    rng: &mut StdRng,
+    si: &SearchInstance,
+) -> Result<(Vec<VertexId>, Vec<Vec<Cost>>), SearchError> {
+    let start = random_vertex(&[], rng, si)?;
+    let mut nearest = costs_from(start, &Direction::Forward, si)?;
+    let mut vertex_ids = Vec::with_capacity(count);
+    let mut from_landmark = Vec::with_capacity(count);
+    while vertex_ids.len() < count {
+        let farthest = nearest
+            .iter()
+            .enumerate()
+            .filter(|(idx, cost)| {
+                cost.as_f64().is_finite() && !vertex_ids.contains(&VertexId(*idx))
+            })
+            .max_by_key(|(_, cost)| **cost)
+            .map(|(idx, _)| VertexId(idx));
+        let next = match farthest {
+            Some(v) => v,
+            None => random_vertex(&vertex_ids, rng, si)?,
+        };
+        let costs = costs_from(next, &Direction::Forward, si)?;
+        for (n, c) in nearest.iter_mut().zip(&costs) {
+            *n = (*n).min(*c);
+        }
+        vertex_ids.push(next);
+        from_landmark.push(costs);
+    }
+    Ok((vertex_ids, from_landmark))
+}
+
+/// a random vertex that is not one of the excluded vertices
+fn random_vertex(
+    exclude: &[VertexId],
+    rng: &mut StdRng,
+    si: &SearchInstance,
+) -> Result<VertexId, SearchError> {
+    si.graph
+        .vertex_ids()
+        .filter(|v| !exclude.contains(v))
+        .choose(rng)
+        .ok_or_else(|| SearchError::BuildError(String::from("no vertices left to select")))
+}
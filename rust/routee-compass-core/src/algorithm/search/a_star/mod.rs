@@ -1,4 +1,11 @@
 mod a_star_algorithm;
 pub mod bidirectional_ops;
+mod landmarks;
+mod search_frontier;
 
-pub use a_star_algorithm::{run_edge_oriented, run_vertex_oriented};
+pub use a_star_algorithm::{
+    run_edge_oriented, run_first_target, run_nearest_targets, run_ranked_targets,
+    run_reachable_count, run_vertex_oriented,
+};
+pub use landmarks::{LandmarkSelection, Landmarks, LandmarksConfig};
+pub use search_frontier::SearchFrontier;
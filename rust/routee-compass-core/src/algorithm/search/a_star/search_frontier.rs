@@ -0,0 +1,472 @@
+use crate::algorithm::search::util::turn_count;
+use crate::algorithm::search::Direction;
+use crate::algorithm::search::SearchError;
+use crate::algorithm::search::SearchInstance;
+use crate::algorithm::search::SearchResult;
+use crate::algorithm::search::SearchTreeBranch;
+use crate::model::network::edge_id::EdgeId;
+use crate::model::network::vertex_id::VertexId;
+use crate::model::state::StateVariable;
+use crate::model::unit::AsF64;
+use crate::model::unit::Cost;
+use crate::model::unit::ReverseCost;
+use crate::util::priority_queue::InternalPriorityQueue;
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// an A* search that is driven by the caller. each call to `next` settles
+/// one vertex, expands its neighbors, and yields the settled `(VertexId, Cost)`,
+/// where the cost is the cost of the best path found from the source. vertices
+/// are yielded in order of increasing cost when no target is set (Dijkstra).
+///
+/// the iterator ends when the frontier is exhausted or after the target is
+/// settled. the caller may stop at any point, inspect the partial search tree
+/// with [`SearchFrontier::tree`], or keep the search result with
+/// [`SearchFrontier::into_search_result`]. errors, including termination model
+/// failures, are yielded once and end the iteration.
+pub struct SearchFrontier<'a> {
+    source: VertexId,
+    target: Option<VertexId>,
+    direction: Direction,
+    weight_factor: Option<Cost>,
+    budget: Option<Cost>,
+    si: &'a SearchInstance,
+    costs: InternalPriorityQueue<VertexId, ReverseCost>,
+    traversal_costs: HashMap<VertexId, Cost>,
+    solution: HashMap<VertexId, SearchTreeBranch>,
+    // number of turns on the best path to each vertex, used to break cost ties
+    turn_counts: HashMap<VertexId, usize>,
+    initial_state: Vec<StateVariable>,
+    // an infinite penalty is equivalent to forbidding wrong-way traversals
+    wrong_way_penalty: Option<Cost>,
+    start_time: Instant,
+    iterations: u64,
+    // tracks if the budget removed any paths, to explain a failed search
+    over_budget: bool,
+    // tracks if the frontier model rejected any edge, to explain a failed search
+    restricted: bool,
+    finished: bool,
+}
+
+impl<'a> SearchFrontier<'a> {
+    /// sets up a search from the source. when a target is provided, the
+    /// frontier is ordered by the A* heuristic, scaled by the weight factor.
+    pub fn new(
+        source: VertexId,
+        target: Option<VertexId>,
+        direction: &Direction,
+        weight_factor: Option<Cost>,
+        si: &'a SearchInstance,
+    ) -> Result<SearchFrontier<'a>, SearchError> {
+        let mut costs: InternalPriorityQueue<VertexId, ReverseCost> =
+            InternalPriorityQueue::default();
+        let mut traversal_costs: HashMap<VertexId, Cost> = HashMap::new();
+
+        // setup initial search state
+        traversal_costs.insert(source, Cost::ZERO);
+        let initial_state = si.state_model.initial_state()?;
+        let origin_cost = match target {
+            None => Cost::ZERO,
+            Some(target) => {
+                heuristic(source, target, direction, weight_factor, &initial_state, si)?
+            }
+        };
+        costs.push(source, origin_cost.into());
+
+        Ok(SearchFrontier {
+            source,
+            target,
+            direction: *direction,
+            weight_factor,
+            budget: None,
+            si,
+            costs,
+            traversal_costs,
+            solution: HashMap::new(),
+            turn_counts: HashMap::new(),
+            initial_state,
+            wrong_way_penalty: si.wrong_way_penalty.filter(|p| p.as_f64().is_finite()),
+            start_time: Instant::now(),
+            iterations: 0,
+            over_budget: false,
+            restricted: false,
+            finished: target == Some(source),
+        })
+    }
+
+    /// limits the search to vertices with a cost within the budget. vertices
+    /// beyond the budget are not added to the frontier, and a search that is
+    /// exhausted without reaching its target after leaving out any vertex fails
+    /// with [`SearchError::CostBudgetExceeded`].
+    pub fn with_budget(self, budget: Cost) -> SearchFrontier<'a> {
+        SearchFrontier {
+            budget: Some(budget),
+            ..self
+        }
+    }
+
+    /// the search tree built so far
+    pub fn tree(&self) -> &HashMap<VertexId, SearchTreeBranch> {
+        &self.solution
+    }
+
+    /// the cost of the best path found so far from the source to this vertex,
+    /// which is final once the vertex has been settled
+    pub fn cost(&self, vertex_id: &VertexId) -> Option<Cost> {
+        self.traversal_costs.get(vertex_id).cloned()
+    }
+
+    /// the number of vertices expanded so far
+    pub fn iterations(&self) -> u64 {
+        self.iterations
+    }
+
+    /// true once the frontier model has rejected an edge, in which case a target
+    /// the search does not reach may be cut off by restrictions
+    pub fn restricted(&self) -> bool {
+        self.restricted
+    }
+
+    /// completes this search, returning the search tree built so far
+    pub fn into_search_result(self) -> SearchResult {
+        log::debug!(
+            "search iterations: {}, size of search tree: {}",
+            self.iterations,
+            self.solution.len()
+        );
+
+        #[cfg(debug_assertions)]
+        {
+            use std::io::Write;
+            use std::path::PathBuf;
+
+            log::debug!("Building flamegraph for search memory usage..");
+            let mut flamegraph = allocative::FlameGraphBuilder::default();
+            flamegraph.visit_root(&self.costs);
+            flamegraph.visit_root(&self.traversal_costs);
+            flamegraph.visit_root(&self.solution);
+            let output = flamegraph.finish_and_write_flame_graph();
+
+            let search_name = match self.target {
+                None => format!("{}_to_all", self.source),
+                Some(tid) => format!("{}_to_{}", self.source, tid),
+            };
+
+            let outdir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join("target")
+                .join("flamegraph");
+
+            if !outdir.exists() {
+                std::fs::create_dir(&outdir).unwrap();
+            }
+
+            let mut flamegraph_file = std::fs::File::create(
+                outdir.join(format!("search_memory_flamegraph_{}.out", search_name)),
+            )
+            .unwrap();
+            flamegraph_file.write_all(output.as_bytes()).unwrap();
+        }
+
+        SearchResult {
+            restricted: self.restricted,
+            ..SearchResult::new(self.solution, self.iterations)
+        }
+    }
+
+    /// settles the next vertex. returns None when the frontier is exhausted in a
+    /// search without a target, and an error when it is exhausted before reaching
+    /// the target.
+    fn settle_next(&mut self) -> Result<Option<(VertexId, Cost)>, SearchError> {
+        self.si
+            .termination_model
+            .test(&self.start_time, self.solution.len(), self.iterations)?;
+
+        let current_vertex_id = match (self.costs.pop(), self.target) {
+            (None, Some(target_vertex_id)) if self.over_budget => {
+                return Err(SearchError::CostBudgetExceeded(
+                    self.source,
+                    target_vertex_id,
+                ))
+            }
+            (None, Some(target_vertex_id)) => {
+                return Err(self
+                    .si
+                    .no_path_error(self.source, target_vertex_id, self.restricted))
+            }
+            (None, None) => return Ok(None),
+            (Some((current_vertex_id, _)), _) => current_vertex_id,
+        };
+        let current_cost = self
+            .traversal_costs
+            .get(&current_vertex_id)
+            .cloned()
+            .unwrap_or(Cost::INFINITY);
+
+        // the target is settled but not expanded
+        if Some(current_vertex_id) == self.target {
+            self.finished = true;
+        } else {
+            self.expand(current_vertex_id)?;
+            self.iterations += 1;
+        }
+        Ok(Some((current_vertex_id, current_cost)))
+    }
+
+    /// visits all neighbors of this vertex, adding improved paths to the frontier
+    fn expand(&mut self, current_vertex_id: VertexId) -> Result<(), SearchError> {
+        let si = self.si;
+        let direction = self.direction;
+        let wrong_way_direction = direction.reverse();
+        let last_edge_id =
+            get_last_traversed_edge_id(&current_vertex_id, &self.source, &self.solution)?;
+
+        // the last edge was traversed the wrong way if the tree does not reach
+        // this vertex along its direction
+        let last_edge_wrong_way = match last_edge_id {
+            Some(edge_id) => {
+                direction.tree_key_vertex_id(si.graph.get_edge(&edge_id)?) != current_vertex_id
+            }
+            None => false,
+        };
+
+        // grab the current state from the solution
+        let current_state = if current_vertex_id == self.source {
+            self.initial_state.clone()
+        } else {
+            self.solution
+                .get(&current_vertex_id)
+                .ok_or_else(|| {
+                    SearchError::InternalError(format!(
+                        "expected vertex id {} missing from solution",
+                        current_vertex_id
+                    ))
+                })?
+                .edge_traversal
+                .result_state
+                .clone()
+        };
+
+        // visit all neighbors of this source vertex. if wrong-way traversals are
+        // permitted, one-way edges incident in the opposite direction are also
+        // visited, traversed against their direction at a penalized cost.
+        let incident_edge_iterator = direction
+            .get_incident_edges(&current_vertex_id, si)
+            .map(|edge_id| (edge_id, None));
+        let wrong_way_edge_iterator = match self.wrong_way_penalty {
+            None => {
+                Box::new(std::iter::empty()) as Box<dyn Iterator<Item = (&EdgeId, Option<Cost>)>>
+            }
+            Some(penalty) => Box::new(
+                wrong_way_direction
+                    .get_incident_edges(&current_vertex_id, si)
+                    .map(move |edge_id| (edge_id, Some(penalty))),
+            ),
+        };
+        for (edge_id, penalty) in incident_edge_iterator.chain(wrong_way_edge_iterator) {
+            let e = si.graph.get_edge(edge_id)?;
+            if penalty.is_some() && !si.graph.is_one_way(edge_id)? {
+                continue;
+            }
+
+            // a wrong-way traversal reaches the opposite end of the edge
+            let (terminal_vertex_id, key_vertex_id) = match penalty {
+                None => (
+                    direction.terminal_vertex_id(e),
+                    direction.tree_key_vertex_id(e),
+                ),
+                Some(_) => (
+                    wrong_way_direction.terminal_vertex_id(e),
+                    wrong_way_direction.tree_key_vertex_id(e),
+                ),
+            };
+
+            let valid_frontier = si.frontier_model.valid_frontier(
+                e,
+                last_edge_id,
+                &current_state,
+                &self.solution,
+                &direction,
+                &si.state_model,
+            )?;
+            if !valid_frontier {
+                self.restricted = true;
+                continue;
+            }
+            // access models expect a movement (v1)-[prev]->(v2)-[next]->(v3) along
+            // both edges, so moving onto or off of a wrong-way edge has no access cost
+            let access_edge_id = match (penalty, last_edge_wrong_way) {
+                (None, false) => last_edge_id,
+                _ => None,
+            };
+            let mut et =
+                direction.perform_edge_traversal(*edge_id, access_edge_id, &current_state, si)?;
+            if let Some(penalty) = penalty {
+                et.traversal_cost = Cost::new(et.traversal_cost.as_f64() * penalty.as_f64());
+            }
+            // a reroute preference discounts the cost used to order the search,
+            // while the tree keeps the undiscounted cost of the traversal
+            let search_cost = match &si.reroute_hysteresis {
+                Some(hysteresis) => et.access_cost + hysteresis.apply(edge_id, et.traversal_cost),
+                None => et.total_cost(),
+            };
+            let current_gscore = self
+                .traversal_costs
+                .get(&terminal_vertex_id)
+                .unwrap_or(&Cost::INFINITY)
+                .to_owned();
+            let tentative_gscore = current_gscore + search_cost;
+            let existing_gscore = self
+                .traversal_costs
+                .get(&key_vertex_id)
+                .unwrap_or(&Cost::INFINITY)
+                .to_owned();
+            let within_budget = self.budget.is_none_or(|b| tentative_gscore <= b);
+
+            // among equal-cost paths, optionally prefer the one with fewer turns
+            let tentative_turns = match (si.prefer_fewer_turns, last_edge_id) {
+                (true, Some(last_edge_id)) => {
+                    let turn = turn_count::is_turn(&last_edge_id, edge_id, &si.graph)?;
+                    self.turn_counts.get(&terminal_vertex_id).unwrap_or(&0) + usize::from(turn)
+                }
+                _ => 0,
+            };
+            let fewer_turns = si.prefer_fewer_turns
+                && tentative_gscore == existing_gscore
+                && self
+                    .turn_counts
+                    .get(&key_vertex_id)
+                    .is_some_and(|existing_turns| tentative_turns < *existing_turns);
+
+            let improves = tentative_gscore < existing_gscore || fewer_turns;
+            if improves && !within_budget {
+                self.over_budget = true;
+            }
+            if improves && within_budget {
+                self.traversal_costs.insert(key_vertex_id, tentative_gscore);
+                if si.prefer_fewer_turns {
+                    self.turn_counts.insert(key_vertex_id, tentative_turns);
+                }
+
+                // update solution
+                let traversal = SearchTreeBranch {
+                    terminal_vertex: terminal_vertex_id,
+                    edge_traversal: et,
+                };
+                self.solution.insert(key_vertex_id, traversal);
+
+                let dst_h_cost = match self.target {
+                    None => Cost::ZERO,
+                    Some(target_v) => heuristic(
+                        key_vertex_id,
+                        target_v,
+                        &direction,
+                        self.weight_factor,
+                        &current_state,
+                        si,
+                    )?,
+                };
+                let f_score_value = tentative_gscore + dst_h_cost;
+                self.costs
+                    .push_increase(key_vertex_id, f_score_value.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for SearchFrontier<'_> {
+    type Item = Result<(VertexId, Cost), SearchError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.settle_next() {
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Ok(Some(settled)) => Some(Ok(settled)),
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// the A* heuristic for a vertex: the traversal model estimate of the cost to
+/// the target, raised to the landmark lower bound when landmarks are loaded.
+/// in a reverse search, the target is the origin of the route, so the bound is
+/// on the cost from the target to the vertex. landmarks are ignored unless
+/// [`SearchInstance::admissible_landmarks`] accepts them.
+fn heuristic(
+    vertex_id: VertexId,
+    target: VertexId,
+    direction: &Direction,
+    weight_factor: Option<Cost>,
+    state: &[StateVariable],
+    si: &SearchInstance,
+) -> Result<Cost, SearchError> {
+    let cost_est = si.estimate_traversal_cost(vertex_id, target, state)?;
+    let landmark_bound = match si.admissible_landmarks() {
+        Some(landmarks) => match direction {
+            Direction::Forward => landmarks.lower_bound(vertex_id, target),
+            Direction::Reverse => landmarks.lower_bound(target, vertex_id),
+        },
+        None => Cost::ZERO,
+    };
+    let h = cost_est.max(landmark_bound);
+    Ok(Cost::new(h.as_f64() * heuristic_factor(weight_factor, si)))
+}
+
+/// the factor applied to A* heuristic estimates: the weight factor, reduced
+/// when a reroute preference discounts edges below their estimated cost
+fn heuristic_factor(weight_factor: Option<Cost>, si: &SearchInstance) -> f64 {
+    let hysteresis_factor = si
+        .reroute_hysteresis
+        .as_ref()
+        .map(|h| h.heuristic_factor())
+        .unwrap_or(1.0);
+    weight_factor.unwrap_or(Cost::ONE).as_f64() * hysteresis_factor
+}
+
+/// Find the last-traversed edge before reaching this vertex id.
+/// The logic is the same for forward and reverse searches but finds
+/// a different result because the trees are different.
+/// Forward case: find `prev` from v2 in `(v1)-[prev]->(v2)-[next]->(v3)`
+/// Reverse case: find `next` from v2 in `(v1)-[prev]->(v2)-[next]->(v3)`
+///
+/// # Arguments
+/// * `this_vertex_id`  - current vertex, v2 in diagram
+/// * `first_vertex_id` - source of this search, the origin vertex in a forward
+///   search or the destination vertex in a reverse search
+/// * `tree`            - current search solution tree
+///
+/// # Returns
+///
+/// The EdgeId for the edge that was traversed to reach this vertex, or None
+/// if no edges have yet been traversed.
+fn get_last_traversed_edge_id(
+    this_vertex_id: &VertexId,
+    first_vertex_id: &VertexId,
+    tree: &HashMap<VertexId, SearchTreeBranch>,
+) -> Result<Option<EdgeId>, SearchError> {
+    if this_vertex_id == first_vertex_id {
+        Ok(None)
+    } else {
+        let edge_id = tree
+            .get(this_vertex_id)
+            .ok_or_else(|| {
+                SearchError::InternalError(format!(
+                    "expected vertex id {} missing from solution",
+                    this_vertex_id
+                ))
+            })?
+            .edge_traversal
+            .edge_id;
+        Ok(Some(edge_id))
+    }
+}
@@ -0,0 +1,218 @@
+use super::{
+    a_star::{bidirectional_ops, SearchFrontier},
+    backtrack,
+    direction::Direction,
+    search_algorithm::SearchAlgorithm,
+    search_algorithm_result::SearchAlgorithmResult,
+    search_error::SearchError,
+    search_instance::SearchInstance,
+};
+use crate::model::{network::vertex_id::VertexId, unit::Cost};
+
+/// finds the shortest route from source to target by growing a Dijkstra search
+/// forward from the source and another backward from the target over the reversed
+/// adjacency, stopping once no route through an unsettled vertex can be cheaper
+/// than the best route found where the two searches meet. each step advances the
+/// search with the lower settled cost, so both cover about half the cost of the
+/// route, which settles far fewer vertices than a one-way search on large graphs.
+///
+/// the two halves of the route are read from the forward and reverse search trees
+/// and joined at the meeting vertex. the reverse half is then traversed again
+/// forward from the final state of the forward half, so the state and costs of the
+/// returned route are those of a forward search.
+///
+/// the route is the shortest when the cost of an edge does not depend on the state
+/// of the search at that edge, as with distance or free-flow time. the reverse
+/// search starts from the initial state at the target, so when costs depend on the
+/// state (for example, on battery state of charge or time of day), the searches
+/// meet at a vertex that is only best by the reverse estimates of those costs, and
+/// the turn at the meeting vertex is not part of that choice. the route is valid
+/// and its costs exact, but it may not be the shortest. cost models that do not sum
+/// their feature costs (see [`crate::model::cost::CostModel::is_additive`]) run a
+/// forward Dijkstra search instead.
+pub fn run(
+    source: VertexId,
+    target: Option<VertexId>,
+    query: &serde_json::Value,
+    si: &SearchInstance,
+) -> Result<SearchAlgorithmResult, SearchError> {
+    let target = match target {
+        Some(target) if target != source && si.cost_model.is_additive() => target,
+        _ => {
+            return SearchAlgorithm::Dijkstra.run_vertex_oriented(
+                source,
+                target,
+                query,
+                &Direction::Forward,
+                si,
+            )
+        }
+    };
+
+    let mut fwd = SearchFrontier::new(source, None, &Direction::Forward, Some(Cost::ZERO), si)?;
+    let mut rev = SearchFrontier::new(target, None, &Direction::Reverse, Some(Cost::ZERO), si)?;
+
+    // the cheapest route found through a vertex reached by both searches
+    let mut best: Option<(VertexId, Cost)> = None;
+    let (mut fwd_cost, mut rev_cost) = (Cost::ZERO, Cost::ZERO);
+    loop {
+        if let Some((_, best_cost)) = best {
+            if fwd_cost + rev_cost >= best_cost {
+                break;
+            }
+        }
+        // advance the search with the lower settled cost. once either search is
+        // exhausted, every route from the source to the target has been compared.
+        let forward = fwd_cost <= rev_cost;
+        let (frontier, other) = if forward {
+            (&mut fwd, &rev)
+        } else {
+            (&mut rev, &fwd)
+        };
+        let (vertex_id, cost) = match frontier.next() {
+            None => break,
+            Some(settled) => settled?,
+        };
+        if forward {
+            fwd_cost = cost;
+        } else {
+            rev_cost = cost;
+        }
+        if let Some(other_cost) = other.cost(&vertex_id) {
+            let route_cost = cost + other_cost;
+            if best.is_none_or(|(_, best_cost)| route_cost < best_cost) {
+                best = Some((vertex_id, route_cost));
+            }
+        }
+    }
+
+    let (meeting_vertex, _) = match best {
+        Some(best) => best,
+        None => {
+            let restricted = fwd.restricted() || rev.restricted();
+            return Err(si.no_path_error(source, target, restricted));
+        }
+    };
+    let iterations = fwd.iterations() + rev.iterations();
+    let fwd_tree = fwd.into_search_result().tree;
+    let rev_tree = rev.into_search_result().tree;
+    let fwd_route = backtrack::vertex_oriented_route(source, meeting_vertex, &fwd_tree)?;
+    let rev_route_backward = backtrack::vertex_oriented_route(target, meeting_vertex, &rev_tree)?;
+    let rev_route = bidirectional_ops::reorient_reverse_route(&fwd_route, &rev_route_backward, si)?;
+    let route = fwd_route.into_iter().chain(rev_route).collect();
+
+    Ok(SearchAlgorithmResult {
+        trees: vec![fwd_tree, rev_tree],
+        routes: vec![route],
+        iterations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::util::test_search_instance::{
+        build_graph, vertices_at_origin, TestSearchInstance,
+    };
+    use crate::model::cost::CostAggregation;
+    use crate::model::network::Edge;
+    use crate::model::unit::AsF64;
+
+    /// a ladder of two rows of vertices with one-way rungs and rails of varied
+    /// lengths, so that shortest routes change rows and pass through several
+    /// candidate meeting vertices. vertex 8 has no edges. all vertices are
+    /// placed at (0,0).
+    /// (0) - (1) - (2) - (3)
+    ///  |     |     |     |
+    /// (4) - (5) - (6) - (7)
+    fn build_search_instance(cost_aggregation: CostAggregation) -> SearchInstance {
+        let links = [
+            (0, 1, 5.0),
+            (1, 2, 1.0),
+            (2, 3, 9.0),
+            (4, 5, 2.0),
+            (5, 6, 8.0),
+            (6, 7, 1.0),
+        ];
+        let rungs = [(0, 4, 1.0), (5, 1, 1.0), (2, 6, 2.0), (7, 3, 1.0)];
+        let mut edges = vec![];
+        for (src, dst, distance) in links {
+            edges.push(Edge::new(edges.len(), src, dst, distance));
+            edges.push(Edge::new(edges.len(), dst, src, distance));
+        }
+        for (src, dst, distance) in rungs {
+            edges.push(Edge::new(edges.len(), src, dst, distance));
+        }
+        TestSearchInstance {
+            cost_aggregation,
+            ..Default::default()
+        }
+        .build(build_graph(vertices_at_origin(9), edges))
+    }
+
+    fn route_cost(result: &SearchAlgorithmResult) -> f64 {
+        result.routes[0]
+            .iter()
+            .map(|e| e.total_cost().as_f64())
+            .sum()
+    }
+
+    #[test]
+    fn test_matches_dijkstra() {
+        let si = build_search_instance(CostAggregation::Sum);
+        let query = serde_json::json!({});
+        for source in 0..8 {
+            for target in 0..8 {
+                let (source, target) = (VertexId(source), VertexId(target));
+                if source == target {
+                    continue;
+                }
+                let expected = SearchAlgorithm::Dijkstra
+                    .run_vertex_oriented(source, Some(target), &query, &Direction::Forward, &si)
+                    .unwrap();
+                let result = run(source, Some(target), &query, &si).unwrap();
+                assert_eq!(result.trees.len(), 2);
+                assert_eq!(
+                    route_cost(&result),
+                    route_cost(&expected),
+                    "route from {} to {}",
+                    source,
+                    target
+                );
+
+                // the route is continuous, with distance accumulated along it
+                let route = &result.routes[0];
+                assert_eq!(si.graph.src_vertex_id(&route[0].edge_id).unwrap(), source);
+                let last = route.last().unwrap();
+                assert_eq!(si.graph.dst_vertex_id(&last.edge_id).unwrap(), target);
+                for (prev, next) in route.iter().zip(route.iter().skip(1)) {
+                    assert_eq!(
+                        si.graph.dst_vertex_id(&prev.edge_id).unwrap(),
+                        si.graph.src_vertex_id(&next.edge_id).unwrap()
+                    );
+                }
+                assert_eq!(last.result_state[0].0, route_cost(&expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_path() {
+        let si = build_search_instance(CostAggregation::Sum);
+        let query = serde_json::json!({});
+        let result = run(VertexId(0), Some(VertexId(8)), &query, &si);
+        assert!(matches!(
+            result,
+            Err(SearchError::NoPathExistsBetweenVertices(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_fallback_when_not_additive() {
+        let si = build_search_instance(CostAggregation::Mul);
+        let query = serde_json::json!({});
+        let result = run(VertexId(0), Some(VertexId(3)), &query, &si).unwrap();
+        assert_eq!(result.trees.len(), 1);
+        assert_eq!(route_cost(&result), 9.0);
+    }
+}
@@ -14,6 +14,14 @@ pub enum Direction {
 }
 
 impl Direction {
+    /// the opposite search direction
+    pub fn reverse(&self) -> Direction {
+        match self {
+            Direction::Forward => Direction::Reverse,
+            Direction::Reverse => Direction::Forward,
+        }
+    }
+
     pub fn get_incident_edges<'a>(
         &'a self,
         vertex_id: &VertexId,
@@ -0,0 +1,113 @@
+use super::{
+    direction::Direction, edge_traversal::EdgeTraversal, search_algorithm::SearchAlgorithm,
+    search_algorithm_result::SearchAlgorithmResult, search_error::SearchError,
+    search_instance::SearchInstance, util::RouteSimilarityFunction,
+};
+use crate::model::network::vertex_id::VertexId;
+use itertools::Itertools;
+
+/// query key for the maximum number of alternative routes to return
+pub const MAX_ALTERNATIVES: &str = "max_alternatives";
+
+/// reads the maximum number of alternative routes from the query, falling back to
+/// the configured value.
+///
+/// # Returns
+///
+/// the maximum number of routes, or an error if it is not a positive integer
+pub fn get_max_alternatives(
+    query: &serde_json::Value,
+    default: usize,
+) -> Result<usize, SearchError> {
+    match query.get(MAX_ALTERNATIVES) {
+        None => Ok(default),
+        Some(value) => match value.as_u64() {
+            Some(n) if n >= 1 => Ok(n as usize),
+            _ => Err(SearchError::BuildError(format!(
+                "{} must be a positive integer, found {}",
+                MAX_ALTERNATIVES, value
+            ))),
+        },
+    }
+}
+
+/// finds up to `max_alternatives` routes from source to target that are each
+/// meaningfully distinct from the others. the candidates are the routes of the
+/// underlying search, typically a k-shortest paths algorithm, in the order it
+/// returns them. each candidate is kept if its distinctness from every route kept
+/// before it is at least `min_distinctness`, so near-duplicates of a better route
+/// are dropped rather than padding the result.
+///
+/// distinctness is one minus the distance-weighted cosine similarity of two routes,
+/// which is 0 for routes over the same edges and 1 for routes sharing no edges.
+/// the result has fewer than `max_alternatives` routes when the network offers
+/// fewer distinct options, and at least one route when any route is found.
+pub fn run(
+    source: VertexId,
+    target: VertexId,
+    query: &serde_json::Value,
+    max_alternatives: usize,
+    min_distinctness: f64,
+    underlying: &SearchAlgorithm,
+    si: &SearchInstance,
+) -> Result<SearchAlgorithmResult, SearchError> {
+    if !(0.0..=1.0).contains(&min_distinctness) {
+        return Err(SearchError::BuildError(format!(
+            "min_distinctness must be between 0 and 1, found {}",
+            min_distinctness
+        )));
+    }
+    let SearchAlgorithmResult {
+        trees,
+        routes,
+        iterations,
+    } = underlying.run_vertex_oriented(source, Some(target), query, &Direction::Forward, si)?;
+    let routes = select_distinct(routes, max_alternatives, min_distinctness, si)?;
+    Ok(SearchAlgorithmResult {
+        trees,
+        routes,
+        iterations,
+    })
+}
+
+/// keeps each route in order whose distinctness from every route already kept is
+/// at least `min_distinctness`, until `max_alternatives` routes are kept.
+fn select_distinct(
+    candidates: Vec<Vec<EdgeTraversal>>,
+    max_alternatives: usize,
+    min_distinctness: f64,
+    si: &SearchInstance,
+) -> Result<Vec<Vec<EdgeTraversal>>, SearchError> {
+    let similarity = RouteSimilarityFunction::DistanceWeightedCosineSimilarity {
+        threshold: 1.0 - min_distinctness,
+    };
+    let mut selected: Vec<Vec<EdgeTraversal>> = vec![];
+    for candidate in candidates.into_iter() {
+        if selected.len() >= max_alternatives {
+            break;
+        }
+        if candidate.is_empty() {
+            continue;
+        }
+        let candidate_edges = candidate.iter().collect_vec();
+        let mut distinct = true;
+        for route in selected.iter() {
+            // exact duplicates are dropped even when no distinctness is required
+            let rank =
+                similarity.rank_similarity(&candidate_edges, &route.iter().collect_vec(), si)?;
+            if 1.0 - rank < min_distinctness || same_edges(&candidate, route) {
+                distinct = false;
+                break;
+            }
+        }
+        if distinct {
+            selected.push(candidate);
+        }
+    }
+    Ok(selected)
+}
+
+/// compares two routes by their sequence of edge ids
+fn same_edges(a: &[EdgeTraversal], b: &[EdgeTraversal]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(e1, e2)| e1.edge_id == e2.edge_id)
+}
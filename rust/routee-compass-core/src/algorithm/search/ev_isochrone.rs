@@ -0,0 +1,263 @@
+use super::{
+    direction::Direction, edge_traversal::EdgeTraversal, search_error::SearchError,
+    search_instance::SearchInstance, MinSearchTree,
+};
+use crate::{
+    model::{
+        network::{edge_id::EdgeId, vertex_id::VertexId},
+        state::StateVariable,
+    },
+    util::priority_queue::InternalPriorityQueue,
+};
+use ordered_float::OrderedFloat;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
+
+/// state of charge after charging, in percent
+pub const FULL_SOC_PERCENT: f64 = 100.0;
+
+/// the partial route with the most charge remaining at a vertex
+struct Label {
+    soc: f64,
+    last_edge_id: Option<EdgeId>,
+    state: Vec<StateVariable>,
+}
+
+/// finds the vertices an electric vehicle can reach from the source while keeping
+/// at least `reserve_soc` percent state of charge, charging to full at any charger
+/// it passes. the state of charge is read from the `soc_feature` of the state,
+/// which should be set by the traversal model, and starts at its initial value.
+///
+/// vertices are expanded in order of the most charge remaining, keeping one label
+/// per vertex, so each vertex is reached with the most charge possible among routes
+/// that stay above the reserve at every vertex. this ignores cost: a vertex is in
+/// the set if any feasible route reaches it, not only the cheapest.
+///
+/// # Returns
+///
+/// each reachable vertex, including the source, with the most charge remaining
+/// there in percent, ordered by vertex id. at a charger this is after charging.
+pub fn run(
+    source: VertexId,
+    chargers: &HashSet<VertexId>,
+    soc_feature: &str,
+    reserve_soc: f64,
+    si: &SearchInstance,
+) -> Result<Vec<(VertexId, f64)>, SearchError> {
+    if !(0.0..=FULL_SOC_PERCENT).contains(&reserve_soc) {
+        return Err(SearchError::BuildError(format!(
+            "reserve state of charge must be between 0 and {}, found {}",
+            FULL_SOC_PERCENT, reserve_soc
+        )));
+    }
+    let soc_index = si
+        .state_model
+        .indexed_iter()
+        .find(|(_, (n, _))| *n == soc_feature)
+        .map(|(index, _)| index)
+        .ok_or_else(|| {
+            SearchError::BuildError(format!(
+                "state of charge feature '{}' is not in the state model, found {}",
+                soc_feature,
+                si.state_model.get_names()
+            ))
+        })?;
+    let soc_feature_name = String::from(soc_feature);
+    let charge = |vertex_id: &VertexId, state: &mut Vec<StateVariable>| {
+        if chargers.contains(vertex_id) {
+            si.state_model
+                .set_custom_f64(state, &soc_feature_name, &FULL_SOC_PERCENT)?;
+        }
+        Ok::<f64, SearchError>(state.get(soc_index).map(|s| s.0).unwrap_or_default())
+    };
+
+    let mut initial_state = si.state_model.initial_state()?;
+    let initial_soc = charge(&source, &mut initial_state)?;
+    if initial_soc < reserve_soc {
+        return Ok(vec![]);
+    }
+    let mut labels: HashMap<VertexId, Label> = HashMap::from([(
+        source,
+        Label {
+            soc: initial_soc,
+            last_edge_id: None,
+            state: initial_state,
+        },
+    )]);
+    let mut queue: InternalPriorityQueue<VertexId, OrderedFloat<f64>> =
+        InternalPriorityQueue::default();
+    queue.push(source, OrderedFloat(initial_soc));
+    let mut iterations: u64 = 0;
+    let start_time = Instant::now();
+    let empty_tree = MinSearchTree::new();
+
+    while let Some((vertex_id, _)) = queue.pop() {
+        si.termination_model
+            .test(&start_time, labels.len(), iterations)?;
+        iterations += 1;
+        let (last_edge_id, state) = match labels.get(&vertex_id) {
+            Some(label) => (label.last_edge_id, label.state.clone()),
+            None => {
+                return Err(SearchError::InternalError(format!(
+                    "expanded vertex {} has no label",
+                    vertex_id
+                )))
+            }
+        };
+
+        for edge_id in Direction::Forward.get_incident_edges(&vertex_id, si) {
+            let edge = si.graph.get_edge(edge_id)?;
+            let next_vertex_id = edge.dst_vertex_id;
+            if !si.frontier_model.valid_frontier(
+                edge,
+                last_edge_id,
+                &state,
+                &empty_tree,
+                &Direction::Forward,
+                &si.state_model,
+            )? {
+                continue;
+            }
+            let et = EdgeTraversal::forward_traversal(*edge_id, last_edge_id, &state, si)?;
+            let mut next_state = et.result_state;
+            let arrival_soc = next_state.get(soc_index).map(|s| s.0).unwrap_or_default();
+            if arrival_soc < reserve_soc {
+                continue;
+            }
+            let next_soc = charge(&next_vertex_id, &mut next_state)?;
+            let improves = labels
+                .get(&next_vertex_id)
+                .is_none_or(|label| next_soc > label.soc);
+            if improves {
+                labels.insert(
+                    next_vertex_id,
+                    Label {
+                        soc: next_soc,
+                        last_edge_id: Some(*edge_id),
+                        state: next_state,
+                    },
+                );
+                queue.push_increase(next_vertex_id, OrderedFloat(next_soc));
+            }
+        }
+    }
+
+    let mut reachable = labels
+        .into_iter()
+        .map(|(vertex_id, label)| (vertex_id, label.soc))
+        .collect::<Vec<_>>();
+    reachable.sort_by_key(|(vertex_id, _)| *vertex_id);
+    Ok(reachable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::util::test_search_instance::{
+        build_graph, custom_feature, vertices_at_origin, TestSearchInstance,
+    };
+    use crate::model::network::{Edge, Vertex};
+    use crate::model::state::{StateFeature, StateModel};
+    use crate::model::traversal::default::DistanceTraversalModel;
+    use crate::model::traversal::{TraversalModel, TraversalModelError};
+    use crate::model::unit::{AsF64, DistanceUnit};
+    use std::sync::Arc;
+
+    /// uses a fixed percent of charge per unit distance
+    struct SocTraversalModel {
+        distance: DistanceTraversalModel,
+        soc_per_unit_distance: f64,
+    }
+
+    impl TraversalModel for SocTraversalModel {
+        fn state_features(&self) -> Vec<(String, StateFeature)> {
+            vec![]
+        }
+
+        fn traverse_edge(
+            &self,
+            trajectory: (&Vertex, &Edge, &Vertex),
+            state: &mut Vec<StateVariable>,
+            state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            self.distance
+                .traverse_edge(trajectory, state, state_model)?;
+            let (_, edge, _) = trajectory;
+            let name = String::from("soc");
+            let soc = state_model.get_custom_f64(state, &name)?;
+            let used = edge.distance.as_f64() * self.soc_per_unit_distance;
+            state_model.set_custom_f64(state, &name, &(soc - used))?;
+            Ok(())
+        }
+
+        fn estimate_traversal(
+            &self,
+            od: (&Vertex, &Vertex),
+            state: &mut Vec<StateVariable>,
+            state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            self.distance.estimate_traversal(od, state, state_model)
+        }
+    }
+
+    /// a road of five vertices, (0) <-> (1) <-> (2) <-> (3) <-> (4), where each
+    /// edge is 10 units long and uses 30% of the charge.
+    fn build_search_instance() -> SearchInstance {
+        let edges = (0..4)
+            .flat_map(|i| {
+                [
+                    Edge::new(2 * i, i, i + 1, 10.0),
+                    Edge::new(2 * i + 1, i + 1, i, 10.0),
+                ]
+            })
+            .collect::<Vec<_>>();
+        TestSearchInstance {
+            traversal_model: Arc::new(SocTraversalModel {
+                distance: DistanceTraversalModel::new(DistanceUnit::Meters),
+                soc_per_unit_distance: 3.0,
+            }),
+            custom_features: vec![custom_feature("soc", "percent", 100.0)],
+            ..Default::default()
+        }
+        .build(build_graph(vertices_at_origin(5), edges))
+    }
+
+    #[test]
+    fn test_charger_extends_reachable_set() {
+        let si = build_search_instance();
+        let reach = |chargers: &[usize], reserve_soc: f64| -> Vec<(usize, f64)> {
+            let chargers = chargers.iter().map(|v| VertexId(*v)).collect();
+            run(VertexId(0), &chargers, "soc", reserve_soc, &si)
+                .unwrap()
+                .into_iter()
+                .map(|(v, soc)| (v.0, soc.round()))
+                .collect()
+        };
+
+        // three edges use 90% of the charge, so a fourth is out of reach
+        let no_chargers = reach(&[], 0.0);
+        assert_eq!(
+            no_chargers,
+            vec![(0, 100.0), (1, 70.0), (2, 40.0), (3, 10.0)]
+        );
+        // charging at vertex 2 leaves enough to reach the end of the road
+        let with_charger = reach(&[2], 0.0);
+        assert_eq!(
+            with_charger,
+            vec![(0, 100.0), (1, 70.0), (2, 100.0), (3, 70.0), (4, 40.0)]
+        );
+        // a reserve for the return trip shrinks the reachable set
+        let with_reserve = reach(&[], 35.0);
+        assert_eq!(with_reserve, vec![(0, 100.0), (1, 70.0), (2, 40.0)]);
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let si = build_search_instance();
+        let chargers = HashSet::new();
+        assert!(run(VertexId(0), &chargers, "battery", 0.0, &si).is_err());
+        assert!(run(VertexId(0), &chargers, "soc", 120.0, &si).is_err());
+    }
+}
@@ -0,0 +1,262 @@
+use super::{
+    direction::Direction, edge_traversal::EdgeTraversal, search_error::SearchError,
+    search_instance::SearchInstance, MinSearchTree, SearchTreeBranch,
+};
+use crate::{
+    model::{
+        network::{edge_id::EdgeId, vertex_id::VertexId},
+        state::StateVariable,
+        unit::{AsF64, Cost, ReverseCost},
+    },
+    util::priority_queue::InternalPriorityQueue,
+};
+use std::{collections::HashMap, time::Instant};
+
+/// the limit of an isochrone search, either on the total cost of the cost model
+/// or on the amount accumulated in a state feature, such as time, distance or energy.
+#[derive(Clone, Debug)]
+pub enum IsochroneBudget {
+    Cost(Cost),
+    Feature { name: String, limit: f64 },
+}
+
+/// an edge leaving the isochrone, where the budget runs out partway along it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundaryEdge {
+    pub edge_id: EdgeId,
+    /// the fraction of the edge, from its source, that is within the budget
+    pub fraction: f64,
+}
+
+/// the area reachable from an origin within a budget
+#[derive(Debug)]
+pub struct Isochrone {
+    /// the cheapest route to each vertex within the budget
+    pub tree: MinSearchTree,
+    /// the edges from a vertex within the budget whose traversal crosses the budget
+    pub boundary_edges: Vec<BoundaryEdge>,
+}
+
+/// finds every vertex reachable from the source within the budget by running a
+/// one-to-all Dijkstra search over the cost model, which stops expanding a route
+/// once it exceeds the budget. each edge leaving a vertex within the budget whose
+/// traversal exceeds the budget is reported as a boundary edge, even when its end
+/// is reached within the budget by another route, along with the fraction of the
+/// edge within the budget, found by interpolating the budget measure linearly
+/// along the edge, so that the isochrone geometry can be cut partway along it.
+///
+/// a budget on a state feature measures the amount the feature has grown since
+/// the initial state, so it applies to features that accumulate along a route.
+/// routes are still chosen by cost, so the budget is checked along the cheapest
+/// route to each vertex, which for a time budget is the fastest route only when
+/// the cost model weights time alone.
+///
+/// # Arguments
+///
+/// * `source` - the vertex to search from
+/// * `budget` - the limit on the cost, or on a state feature, of each route
+/// * `si` - the search instance with the models of this search
+///
+/// # Returns
+///
+/// the search tree of vertices within the budget and the boundary edges, ordered
+/// by edge id, or an error if the budget names a feature that is not in the
+/// state model or the search fails
+pub fn run(
+    source: VertexId,
+    budget: &IsochroneBudget,
+    si: &SearchInstance,
+) -> Result<Isochrone, SearchError> {
+    let initial_state = si.state_model.initial_state()?;
+    let feature_index = match budget {
+        IsochroneBudget::Cost(_) => None,
+        IsochroneBudget::Feature { name, .. } => Some(
+            si.state_model
+                .indexed_iter()
+                .find(|(_, (n, _))| *n == name)
+                .map(|(index, _)| index)
+                .ok_or_else(|| {
+                    SearchError::BuildError(format!(
+                        "isochrone budget feature '{}' is not in the state model, found {}",
+                        name,
+                        si.state_model.get_names()
+                    ))
+                })?,
+        ),
+    };
+    let limit = match budget {
+        IsochroneBudget::Cost(cost) => cost.as_f64(),
+        IsochroneBudget::Feature { limit, .. } => *limit,
+    };
+    // the amount of the budget used by a route with this cost and state
+    let measure = |cost: Cost, state: &[StateVariable]| match feature_index {
+        None => cost.as_f64(),
+        Some(index) => {
+            let value = state.get(index).map(|s| s.0).unwrap_or_default();
+            let initial = initial_state.get(index).map(|s| s.0).unwrap_or_default();
+            value - initial
+        }
+    };
+
+    let mut costs: HashMap<VertexId, Cost> = HashMap::from([(source, Cost::ZERO)]);
+    let mut tree = MinSearchTree::new();
+    let mut boundary_edges = vec![];
+    let mut queue: InternalPriorityQueue<VertexId, ReverseCost> = InternalPriorityQueue::default();
+    queue.push(source, Cost::ZERO.into());
+    let mut iterations: u64 = 0;
+    let start_time = Instant::now();
+
+    while let Some((vertex_id, _)) = queue.pop() {
+        si.termination_model
+            .test(&start_time, tree.len(), iterations)?;
+        iterations += 1;
+        let cost = costs.get(&vertex_id).cloned().unwrap_or(Cost::INFINITY);
+        let (last_edge_id, state) = match tree.get(&vertex_id) {
+            Some(branch) => (
+                Some(branch.edge_traversal.edge_id),
+                branch.edge_traversal.result_state.clone(),
+            ),
+            None => (None, initial_state.clone()),
+        };
+        let used = measure(cost, &state);
+
+        for edge_id in Direction::Forward.get_incident_edges(&vertex_id, si) {
+            let edge = si.graph.get_edge(edge_id)?;
+            let next_vertex_id = edge.dst_vertex_id;
+            if !si.frontier_model.valid_frontier(
+                edge,
+                last_edge_id,
+                &state,
+                &tree,
+                &Direction::Forward,
+                &si.state_model,
+            )? {
+                continue;
+            }
+            let et = EdgeTraversal::forward_traversal(*edge_id, last_edge_id, &state, si)?;
+            let next_cost = cost + et.total_cost();
+            let next_used = measure(next_cost, &et.result_state);
+            if next_used > limit {
+                let fraction = if next_used > used {
+                    ((limit - used) / (next_used - used)).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                boundary_edges.push(BoundaryEdge {
+                    edge_id: *edge_id,
+                    fraction,
+                });
+                continue;
+            }
+            let improves = costs
+                .get(&next_vertex_id)
+                .is_none_or(|existing| next_cost < *existing);
+            if improves && next_vertex_id != source {
+                costs.insert(next_vertex_id, next_cost);
+                tree.insert(
+                    next_vertex_id,
+                    SearchTreeBranch {
+                        terminal_vertex: vertex_id,
+                        edge_traversal: et,
+                    },
+                );
+                queue.push_increase(next_vertex_id, next_cost.into());
+            }
+        }
+    }
+
+    boundary_edges.sort_by_key(|b| b.edge_id);
+    Ok(Isochrone {
+        tree,
+        boundary_edges,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::util::test_search_instance::{
+        build_graph, vertices_at_origin, TestSearchInstance,
+    };
+    use crate::model::network::Edge;
+
+    /// a road of five vertices, (0) <-> (1) <-> (2) <-> (3) <-> (4), where each
+    /// edge is 10 units long. the cost of an edge is twice its distance.
+    fn build_search_instance() -> SearchInstance {
+        let edges = (0..4)
+            .flat_map(|i| {
+                [
+                    Edge::new(2 * i, i, i + 1, 10.0),
+                    Edge::new(2 * i + 1, i + 1, i, 10.0),
+                ]
+            })
+            .collect::<Vec<_>>();
+        TestSearchInstance {
+            distance_weight: 2.0,
+            ..Default::default()
+        }
+        .build(build_graph(vertices_at_origin(5), edges))
+    }
+
+    fn reached(isochrone: &Isochrone) -> Vec<usize> {
+        let mut vertices = isochrone.tree.keys().map(|v| v.0).collect::<Vec<_>>();
+        vertices.sort();
+        vertices
+    }
+
+    #[test]
+    fn test_feature_and_cost_budgets() {
+        let si = build_search_instance();
+        let by_distance = IsochroneBudget::Feature {
+            name: String::from("distance"),
+            limit: 25.0,
+        };
+        let by_cost = IsochroneBudget::Cost(Cost::new(50.0));
+        for budget in [by_distance, by_cost] {
+            let isochrone = run(VertexId(1), &budget, &si).unwrap();
+            // 25 units of distance, or 50 of cost, reaches one vertex back toward
+            // the start of the road and two ahead, and half of the edges leaving
+            // the farthest vertex
+            assert_eq!(reached(&isochrone), vec![0, 2, 3], "{:?}", budget);
+            assert_eq!(
+                isochrone.boundary_edges,
+                vec![
+                    BoundaryEdge {
+                        edge_id: EdgeId(5),
+                        fraction: 0.5
+                    },
+                    BoundaryEdge {
+                        edge_id: EdgeId(6),
+                        fraction: 0.5
+                    }
+                ],
+                "{:?}",
+                budget
+            );
+            let branch = isochrone.tree.get(&VertexId(3)).unwrap();
+            assert_eq!(branch.terminal_vertex, VertexId(2));
+            assert_eq!(branch.edge_traversal.edge_id, EdgeId(4));
+        }
+    }
+
+    #[test]
+    fn test_budget_within_first_edge() {
+        let si = build_search_instance();
+        let budget = IsochroneBudget::Cost(Cost::new(5.0));
+        let isochrone = run(VertexId(0), &budget, &si).unwrap();
+        assert!(isochrone.tree.is_empty());
+        assert_eq!(
+            isochrone.boundary_edges,
+            vec![BoundaryEdge {
+                edge_id: EdgeId(0),
+                fraction: 0.25
+            }]
+        );
+
+        let unknown = IsochroneBudget::Feature {
+            name: String::from("time"),
+            limit: 5.0,
+        };
+        assert!(run(VertexId(0), &unknown, &si).is_err());
+    }
+}
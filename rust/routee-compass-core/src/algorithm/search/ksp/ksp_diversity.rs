@@ -0,0 +1,75 @@
+use crate::{algorithm::search::EdgeTraversal, model::network::edge_id::EdgeId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// requires each route of a k-shortest paths search to differ from the routes
+/// already accepted. a candidate route is rejected if the Jaccard overlap of its
+/// edge set with any accepted route exceeds `max_overlap`. candidates are
+/// generated until k diverse routes are found or `max_candidates` have been
+/// tested, after which the search fails if fewer than k routes were found.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KspDiversity {
+    /// the largest edge-set Jaccard overlap allowed between two routes, from 0 to 1
+    pub max_overlap: f64,
+    /// the number of candidate routes to test before giving up
+    pub max_candidates: usize,
+}
+
+impl KspDiversity {
+    /// true if the candidate overlaps each of the accepted routes by no more
+    /// than the max overlap
+    pub fn is_diverse(&self, candidate: &[EdgeTraversal], accepted: &[Vec<EdgeTraversal>]) -> bool {
+        accepted
+            .iter()
+            .all(|route| jaccard_overlap(candidate, route) <= self.max_overlap)
+    }
+}
+
+/// the number of edges shared by two routes divided by the number of distinct
+/// edges in either, from 0 for routes with no edge in common to 1 for routes
+/// with the same edges
+pub fn jaccard_overlap(a: &[EdgeTraversal], b: &[EdgeTraversal]) -> f64 {
+    let a_edges = a.iter().map(|e| e.edge_id).collect::<HashSet<EdgeId>>();
+    let b_edges = b.iter().map(|e| e.edge_id).collect::<HashSet<EdgeId>>();
+    let union = a_edges.union(&b_edges).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a_edges.intersection(&b_edges).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::unit::Cost;
+
+    fn route(edge_ids: &[usize]) -> Vec<EdgeTraversal> {
+        edge_ids
+            .iter()
+            .map(|id| EdgeTraversal {
+                edge_id: EdgeId(*id),
+                access_cost: Cost::ZERO,
+                traversal_cost: Cost::ZERO,
+                result_state: vec![],
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_jaccard_overlap() {
+        assert_eq!(
+            jaccard_overlap(&route(&[0, 1, 4]), &route(&[0, 2, 3, 4])),
+            0.4
+        );
+        assert_eq!(jaccard_overlap(&route(&[0, 1]), &route(&[1, 0])), 1.0);
+        assert_eq!(jaccard_overlap(&route(&[0]), &route(&[1])), 0.0);
+
+        let diversity = KspDiversity {
+            max_overlap: 0.5,
+            max_candidates: 10,
+        };
+        let accepted = vec![route(&[0, 1, 4]), route(&[5, 6])];
+        assert!(diversity.is_diverse(&route(&[0, 2, 3, 4]), &accepted));
+        assert!(!diversity.is_diverse(&route(&[0, 1, 3, 4]), &accepted));
+    }
+}
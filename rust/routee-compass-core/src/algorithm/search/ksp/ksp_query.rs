@@ -1,3 +1,4 @@
+use super::KspDiversity;
 use crate::{algorithm::search::SearchError, model::network::vertex_id::VertexId};
 
 #[derive(Debug, Clone)]
@@ -6,6 +7,8 @@ pub struct KspQuery<'a> {
     pub target: VertexId,
     pub user_query: &'a serde_json::Value,
     pub k: usize,
+    /// if set, routes must differ from each other by this diversity constraint
+    pub diversity: Option<KspDiversity>,
 }
 
 impl<'a> KspQuery<'a> {
@@ -14,6 +17,7 @@ impl<'a> KspQuery<'a> {
         target: VertexId,
         query: &'a serde_json::Value,
         k_default: usize,
+        diversity: Option<&KspDiversity>,
     ) -> Result<KspQuery<'a>, SearchError> {
         let k = match query.get("k") {
             Some(k_json) => k_json
@@ -27,11 +31,25 @@ impl<'a> KspQuery<'a> {
                 .map(|k_u64| k_u64 as usize),
             None => Ok(k_default),
         }?;
+        if let Some(diversity) = diversity {
+            if !(0.0..=1.0).contains(&diversity.max_overlap) {
+                return Err(SearchError::BuildError(format!(
+                    "ksp diversity max_overlap must be between 0 and 1, found {}",
+                    diversity.max_overlap
+                )));
+            }
+            if diversity.max_candidates == 0 {
+                return Err(SearchError::BuildError(String::from(
+                    "ksp diversity max_candidates must be at least 1",
+                )));
+            }
+        }
         let ksp_query = KspQuery {
             source,
             target,
             user_query: query,
             k,
+            diversity: diversity.cloned(),
         };
         Ok(ksp_query)
     }
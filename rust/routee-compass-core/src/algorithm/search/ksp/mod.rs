@@ -1,7 +1,9 @@
+mod ksp_diversity;
 mod ksp_query;
 mod ksp_termination_criteria;
 pub mod svp;
 pub mod yens;
 
+pub use ksp_diversity::{jaccard_overlap, KspDiversity};
 pub use ksp_query::KspQuery;
 pub use ksp_termination_criteria::KspTerminationCriteria;
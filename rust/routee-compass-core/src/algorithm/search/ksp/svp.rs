@@ -12,6 +12,10 @@ use itertools::Itertools;
 use std::collections::HashMap;
 
 /// generates a set of k-shortest paths using the single-via path algorithm.
+///
+/// when the query has a diversity constraint, each candidate route popped from
+/// the intersection queue counts toward its candidate budget, and the search
+/// fails if fewer than k diverse routes are found.
 pub fn run(
     query: &KspQuery,
     termination: &KspTerminationCriteria,
@@ -94,6 +98,12 @@ pub fn run(
             );
             break;
         }
+        if let Some(diversity) = &query.diversity {
+            if ksp_it as usize >= diversity.max_candidates {
+                log::debug!("ksp:{} candidate budget exhausted, quitting", ksp_it);
+                break;
+            }
+        }
         match intersection_queue.pop() {
             None => {
                 log::debug!("ksp:{} queue is empty, quitting", ksp_it);
@@ -137,6 +147,14 @@ pub fn run(
                     }
                 }
 
+                // test the user-provided diversity constraint
+                if let Some(diversity) = &query.diversity {
+                    if accept_route && !diversity.is_diverse(&this_route, &solution) {
+                        log::debug!("ksp:{} overlaps an accepted route", ksp_it);
+                        accept_route = false;
+                    }
+                }
+
                 if accept_route {
                     log::debug!("ksp:{} alternative accepted", ksp_it);
                     solution.push(this_route);
@@ -147,6 +165,12 @@ pub fn run(
     }
 
     log::debug!("ksp ran in {} iterations", ksp_it);
+    if query.diversity.is_some() && solution.len() < query.k {
+        return Err(SearchError::InsufficientDiverseRoutes(
+            solution.len(),
+            query.k,
+        ));
+    }
 
     let routes = solution.into_iter().take(query.k).collect_vec();
 
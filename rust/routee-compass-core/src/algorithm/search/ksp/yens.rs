@@ -15,6 +15,9 @@ use std::{collections::HashSet, sync::Arc};
 /// Yen, Jin Y. "Finding the k shortest loopless paths in a network."
 /// management Science 17.11 (1971): 712-716.
 ///
+/// when the query has a diversity constraint, each spur path counts toward its
+/// candidate budget, and the search fails if fewer than k diverse paths are found.
+///
 /// # Returns
 ///
 /// The search tree of the true shortest path, along with all paths found
@@ -39,9 +42,16 @@ pub fn run(
     let shortest_path = get_first_route(&shortest)?;
     let mut accepted: Vec<Vec<EdgeTraversal>> = vec![shortest_path.to_owned()];
     let mut iterations: u64 = 1; // number of times we call underlying search
+    let mut candidates: usize = 0; // number of candidates tested for diversity
+    let budget_exhausted = |candidates: usize| {
+        query
+            .diversity
+            .as_ref()
+            .is_some_and(|d| candidates >= d.max_candidates)
+    };
 
     while accepted.len() < query.k {
-        if termination.terminate_search(query.k, accepted.len()) {
+        if termination.terminate_search(query.k, accepted.len()) || budget_exhausted(candidates) {
             break;
         }
 
@@ -58,6 +68,9 @@ pub fn run(
 
         // step through each index along the most recently-accepted path
         for spur_idx in 0..prev_accepted_path.len() - 2 {
+            if budget_exhausted(candidates) {
+                break;
+            }
             let spur_len: usize = spur_idx + 1;
             let mut cut_edges: HashSet<EdgeId> = HashSet::new();
             let root_path = prev_accepted_path.iter().take(spur_len).collect_vec();
@@ -93,6 +106,10 @@ pub fn run(
                 cost_model: si.cost_model.clone(),
                 frontier_model: Arc::new(yens_frontier),
                 termination_model: si.termination_model.clone(),
+                wrong_way_penalty: si.wrong_way_penalty,
+                prefer_fewer_turns: si.prefer_fewer_turns,
+                reroute_hysteresis: si.reroute_hysteresis.clone(),
+                landmarks: si.landmarks.clone(),
             };
             let spur_result = underlying.run_vertex_oriented(
                 spur_vertex_id,
@@ -109,6 +126,13 @@ pub fn run(
                 .chain(spur_path)
                 .cloned()
                 .collect_vec();
+            // reject candidates that overlap an accepted path beyond the diversity constraint
+            if let Some(diversity) = &query.diversity {
+                candidates += 1;
+                if !diversity.is_diverse(&candidate_path, &accepted) {
+                    continue;
+                }
+            }
             let candidate_test_path: &Vec<&EdgeTraversal> = &candidate_path.iter().collect_vec();
             // replace best candidate if current candidate is sufficiently dissimilar and improves on cost
             for test_path in accepted.iter() {
@@ -132,11 +156,24 @@ pub fn run(
                 }
             }
             if let Some((ref best_path, _)) = best_candidate {
-                accepted.push(best_path.clone());
+                let diverse = query
+                    .diversity
+                    .as_ref()
+                    .is_none_or(|d| d.is_diverse(best_path, &accepted));
+                if diverse {
+                    accepted.push(best_path.clone());
+                }
             }
         }
     }
 
+    if query.diversity.is_some() && accepted.len() < query.k {
+        return Err(SearchError::InsufficientDiverseRoutes(
+            accepted.len(),
+            query.k,
+        ));
+    }
+
     let result = SearchAlgorithmResult {
         trees: shortest.trees,
         routes: accepted,
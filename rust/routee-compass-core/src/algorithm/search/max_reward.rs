@@ -0,0 +1,330 @@
+use super::{
+    direction::Direction,
+    edge_traversal::EdgeTraversal,
+    search_algorithm::SearchAlgorithm,
+    search_algorithm_result::SearchAlgorithmResult,
+    search_error::SearchError,
+    search_instance::SearchInstance,
+    util::label::{backtrack_labels, visits, Label},
+    MinSearchTree,
+};
+use crate::{
+    model::{
+        network::vertex_id::VertexId,
+        state::StateVariable,
+        unit::{AsF64, Cost, ReverseCost},
+    },
+    util::priority_queue::InternalPriorityQueue,
+};
+use std::{collections::HashMap, time::Instant};
+
+/// the number of labels kept at each vertex when not configured
+pub const DEFAULT_MAX_LABELS: usize = 16;
+
+/// finds the route from source to target with the greatest reward, such as a scenic
+/// score, among routes whose cost is no more than `budget_ratio` times the cost of the
+/// shortest route. the reward is the value of a state feature at the end of the route,
+/// which should be accumulated by the traversal model and never decrease along a route.
+///
+/// maximizing reward under a budget is NP-hard (an orienteering problem), so this runs
+/// a bounded multi-label search. partial routes are expanded in order of cost, pruned
+/// when their cost plus the a* cost estimate to the target exceeds the budget, and kept
+/// at each vertex only while no other partial route there has both a lower cost and a
+/// greater reward. routes never revisit a vertex. at most `max_labels` partial routes
+/// are kept per vertex.
+///
+/// the returned route never exceeds the budget and has at least the reward of the
+/// shortest route, which is returned when no better route is found. when no vertex
+/// reaches the `max_labels` limit, no route that is not dominated at any vertex is
+/// discarded, so the best such route is found.
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    source: VertexId,
+    target: VertexId,
+    query: &serde_json::Value,
+    reward_feature: &str,
+    budget_ratio: f64,
+    max_labels: usize,
+    underlying: &SearchAlgorithm,
+    si: &SearchInstance,
+) -> Result<SearchAlgorithmResult, SearchError> {
+    if !(budget_ratio >= 1.0 && budget_ratio.is_finite()) {
+        return Err(SearchError::BuildError(format!(
+            "budget_ratio must be a finite number of at least 1.0, found {}",
+            budget_ratio
+        )));
+    }
+    let reward_index = si
+        .state_model
+        .indexed_iter()
+        .find(|(_, (n, _))| *n == reward_feature)
+        .map(|(index, _)| index)
+        .ok_or_else(|| {
+            SearchError::BuildError(format!(
+                "reward feature '{}' is not in the state model, found {}",
+                reward_feature,
+                si.state_model.get_names()
+            ))
+        })?;
+    let reward_of = |state: &[StateVariable]| -> f64 {
+        state.get(reward_index).map(|s| s.0).unwrap_or_default()
+    };
+
+    // the shortest route sets the budget and is the fallback solution
+    let shortest =
+        underlying.run_vertex_oriented(source, Some(target), query, &Direction::Forward, si)?;
+    let shortest_route = shortest.routes.first().cloned().unwrap_or_default();
+    let shortest_cost: Cost = shortest_route
+        .iter()
+        .fold(Cost::ZERO, |acc, et| acc + et.total_cost());
+    let budget = Cost::new(shortest_cost.as_f64() * budget_ratio);
+    let initial_state = si.state_model.initial_state()?;
+    let shortest_reward = shortest_route
+        .last()
+        .map(|et| reward_of(&et.result_state))
+        .unwrap_or_else(|| reward_of(&initial_state));
+
+    let mut labels: Vec<Label> = vec![Label::source(source, reward_of(&initial_state))];
+    let mut vertex_labels: HashMap<VertexId, Vec<usize>> = HashMap::from([(source, vec![0])]);
+    let mut queue: InternalPriorityQueue<usize, ReverseCost> = InternalPriorityQueue::default();
+    queue.push(0, Cost::ZERO.into());
+    let mut best: Option<usize> = None;
+    let mut iterations: u64 = 0;
+    let start_time = Instant::now();
+    let empty_tree = MinSearchTree::new();
+
+    while let Some((label_idx, _)) = queue.pop() {
+        si.termination_model
+            .test(&start_time, labels.len(), iterations)?;
+        let label = &labels[label_idx];
+        if !label.active {
+            continue;
+        }
+        if label.vertex_id == target {
+            let improves = match best {
+                None => true,
+                Some(b) => {
+                    label.value > labels[b].value
+                        || (label.value == labels[b].value && label.cost < labels[b].cost)
+                }
+            };
+            if improves {
+                best = Some(label_idx);
+            }
+            continue;
+        }
+        iterations += 1;
+
+        let current_vertex_id = label.vertex_id;
+        let current_cost = label.cost;
+        let last_edge_id = label.edge_traversal.as_ref().map(|et| et.edge_id);
+        let current_state = label
+            .edge_traversal
+            .as_ref()
+            .map(|et| et.result_state.clone())
+            .unwrap_or_else(|| initial_state.clone());
+
+        for edge_id in Direction::Forward.get_incident_edges(&current_vertex_id, si) {
+            let edge = si.graph.get_edge(edge_id)?;
+            let next_vertex_id = edge.dst_vertex_id;
+            if visits(&labels, label_idx, next_vertex_id) {
+                continue;
+            }
+            if !si.frontier_model.valid_frontier(
+                edge,
+                last_edge_id,
+                &current_state,
+                &empty_tree,
+                &Direction::Forward,
+                &si.state_model,
+            )? {
+                continue;
+            }
+            let et = EdgeTraversal::forward_traversal(*edge_id, last_edge_id, &current_state, si)?;
+            let next_cost = current_cost + et.total_cost();
+            let estimate = if next_vertex_id == target {
+                Cost::ZERO
+            } else {
+                si.estimate_traversal_cost(next_vertex_id, target, &et.result_state)?
+            };
+            if next_cost + estimate > budget {
+                continue;
+            }
+            let next_reward = reward_of(&et.result_state);
+
+            // discard the new label if dominated, and deactivate labels it dominates
+            let existing = vertex_labels.entry(next_vertex_id).or_default();
+            let dominated = existing.iter().any(|idx| {
+                let other = &labels[*idx];
+                other.cost <= next_cost && other.value >= next_reward
+            });
+            if dominated {
+                continue;
+            }
+            existing.retain(|idx| {
+                let other = &mut labels[*idx];
+                let keep = !(next_cost <= other.cost && next_reward >= other.value);
+                other.active = keep;
+                keep
+            });
+            if existing.len() >= max_labels {
+                continue;
+            }
+            let next_idx = labels.len();
+            existing.push(next_idx);
+            labels.push(Label {
+                vertex_id: next_vertex_id,
+                parent: Some(label_idx),
+                edge_traversal: Some(et),
+                cost: next_cost,
+                value: next_reward,
+                active: true,
+            });
+            queue.push(next_idx, next_cost.into());
+        }
+    }
+
+    let route = match best {
+        Some(best_idx) if labels[best_idx].value > shortest_reward => {
+            backtrack_labels(&labels, best_idx)
+        }
+        _ => shortest_route,
+    };
+    Ok(SearchAlgorithmResult {
+        trees: shortest.trees,
+        routes: vec![route],
+        iterations: shortest.iterations + iterations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::util::test_search_instance::{
+        build_graph, custom_feature, vertices_at_origin, TestSearchInstance,
+    };
+    use crate::model::network::{Edge, EdgeId, Vertex};
+    use crate::model::state::{StateFeature, StateModel};
+    use crate::model::traversal::default::DistanceTraversalModel;
+    use crate::model::traversal::{TraversalModel, TraversalModelError};
+    use crate::model::unit::DistanceUnit;
+    use std::sync::Arc;
+
+    /// accumulates a fixed scenic score per edge in addition to distance
+    struct ScenicTraversalModel {
+        distance: DistanceTraversalModel,
+        scenic_scores: Vec<f64>,
+    }
+
+    impl TraversalModel for ScenicTraversalModel {
+        fn state_features(&self) -> Vec<(String, StateFeature)> {
+            vec![]
+        }
+
+        fn traverse_edge(
+            &self,
+            trajectory: (&Vertex, &Edge, &Vertex),
+            state: &mut Vec<StateVariable>,
+            state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            self.distance
+                .traverse_edge(trajectory, state, state_model)?;
+            let (_, edge, _) = trajectory;
+            let name = String::from("scenic");
+            let scenic = state_model.get_custom_f64(state, &name)?;
+            let score = self.scenic_scores[edge.edge_id.0];
+            state_model.set_custom_f64(state, &name, &(scenic + score))?;
+            Ok(())
+        }
+
+        fn estimate_traversal(
+            &self,
+            od: (&Vertex, &Vertex),
+            state: &mut Vec<StateVariable>,
+            state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            self.distance.estimate_traversal(od, state, state_model)
+        }
+    }
+
+    /// three routes from vertex 0 to vertex 3. all vertices are placed at (0,0)
+    /// so that the cost estimate is zero.
+    /// (0) -[0]-> (1) -[1]-> (3): 20 units distance, scenic score 0
+    /// (0) -[2]-> (2) -[3]-> (3): 22 units distance, scenic score 10
+    /// (0) -[4]-> (4) -[5]-> (3): 40 units distance, scenic score 100
+    fn build_search_instance() -> SearchInstance {
+        let edges = vec![
+            Edge::new(0, 0, 1, 10.0),
+            Edge::new(1, 1, 3, 10.0),
+            Edge::new(2, 0, 2, 11.0),
+            Edge::new(3, 2, 3, 11.0),
+            Edge::new(4, 0, 4, 20.0),
+            Edge::new(5, 4, 3, 20.0),
+        ];
+        TestSearchInstance {
+            traversal_model: Arc::new(ScenicTraversalModel {
+                distance: DistanceTraversalModel::new(DistanceUnit::Meters),
+                scenic_scores: vec![0.0, 0.0, 5.0, 5.0, 50.0, 50.0],
+            }),
+            custom_features: vec![custom_feature("scenic", "score", 0.0)],
+            ..Default::default()
+        }
+        .build(build_graph(vertices_at_origin(5), edges))
+    }
+
+    #[test]
+    fn test_max_reward_within_budget() {
+        let si = build_search_instance();
+        let query = serde_json::json!({});
+        let run_with_budget = |budget_ratio: f64| -> Vec<EdgeId> {
+            let result = run(
+                VertexId(0),
+                VertexId(3),
+                &query,
+                "scenic",
+                budget_ratio,
+                DEFAULT_MAX_LABELS,
+                &SearchAlgorithm::Dijkstra,
+                &si,
+            )
+            .unwrap();
+            result.routes[0].iter().map(|et| et.edge_id).collect()
+        };
+
+        // the slightly longer route is much more scenic and within 120% of the shortest
+        assert_eq!(run_with_budget(1.2), vec![EdgeId(2), EdgeId(3)]);
+        // the most scenic route is twice as long as the shortest
+        assert_eq!(run_with_budget(2.0), vec![EdgeId(4), EdgeId(5)]);
+        // without slack, only the shortest route is within budget
+        assert_eq!(run_with_budget(1.0), vec![EdgeId(0), EdgeId(1)]);
+    }
+
+    #[test]
+    fn test_max_reward_invalid_arguments() {
+        let si = build_search_instance();
+        let query = serde_json::json!({});
+        let alg = SearchAlgorithm::Dijkstra;
+        let missing_feature = run(
+            VertexId(0),
+            VertexId(3),
+            &query,
+            "beauty",
+            1.2,
+            4,
+            &alg,
+            &si,
+        );
+        assert!(missing_feature.is_err());
+        let bad_ratio = run(
+            VertexId(0),
+            VertexId(3),
+            &query,
+            "scenic",
+            0.5,
+            4,
+            &alg,
+            &si,
+        );
+        assert!(bad_ratio.is_err());
+    }
+}
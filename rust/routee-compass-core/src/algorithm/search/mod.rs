@@ -3,9 +3,17 @@ use std::collections::HashMap;
 
 pub mod a_star;
 pub mod backtrack;
+pub mod bidirectional_dijkstra;
 mod direction;
+pub mod distinct_alternatives;
 mod edge_traversal;
+pub mod ev_isochrone;
+pub mod isochrone;
 pub mod ksp;
+pub mod max_reward;
+mod no_route_cause;
+mod reroute_hysteresis;
+pub mod resource_budget;
 mod search_algorithm;
 mod search_algorithm_result;
 mod search_error;
@@ -16,6 +24,8 @@ pub mod util;
 
 pub use direction::Direction;
 pub use edge_traversal::EdgeTraversal;
+pub use no_route_cause::NoRouteCause;
+pub use reroute_hysteresis::{RerouteHysteresis, MAX_REROUTE_PREFERENCE};
 pub use search_algorithm::SearchAlgorithm;
 pub use search_algorithm_result::SearchAlgorithmResult;
 pub use search_error::SearchError;
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// explains why a search ended without finding a route. this is reported
+/// to the user in a `no_route` response so that clients can handle each
+/// condition deterministically.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NoRouteCause {
+    /// the search frontier was exhausted without reaching the destination,
+    /// with no edge rejected by the frontier model, so the destination cannot
+    /// be reached from the origin over the edges of the graph
+    Disconnected,
+    /// the search frontier was exhausted without reaching the destination
+    /// after the frontier model rejected some edge, so its restrictions may
+    /// have removed every route to it
+    Restricted,
+    /// every route to the destination costs more than the budget of the search
+    BudgetExceeded,
+    /// the termination model ended the search due to an iteration or
+    /// solution size limit
+    TerminationLimit,
+    /// the termination model ended the search due to a runtime limit
+    Timeout,
+    /// a route was found but its distance exceeded the query's maximum
+    /// ratio to the straight-line distance between its endpoints
+    DetourExceeded,
+    /// every route to the destination accumulates more of a constrained state
+    /// feature, such as CO2, than the budget of the query allows
+    ResourceBudgetExceeded,
+    /// an origin or destination coordinate is beyond the map matching
+    /// tolerance of every road, so no search was run
+    OffNetwork,
+}
+
+impl std::fmt::Display for NoRouteCause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = serde_json::to_string(self)
+            .map_err(|_| std::fmt::Error)?
+            .replace('\"', "");
+        write!(f, "{}", s)
+    }
+}
@@ -0,0 +1,88 @@
+use super::SearchError;
+use crate::model::{
+    network::edge_id::EdgeId,
+    unit::{AsF64, Cost},
+};
+use std::collections::HashSet;
+
+/// the largest discount that may be applied to edges of a previous route
+pub const MAX_REROUTE_PREFERENCE: f64 = 0.5;
+
+/// a preference for the edges of a previously recommended route, used when
+/// rerouting so that small changes in cost do not flip the recommendation
+/// between near-equal routes. the traversal cost of each edge on the previous
+/// route is reduced by the `discount` fraction when ordering the search, while
+/// the route reports the undiscounted costs.
+///
+/// the discount is bounded below one so that every traversal cost stays
+/// non-negative, which keeps label-setting searches correct. the A* heuristic
+/// is scaled by the same factor so that it never overestimates a discounted path.
+#[derive(Clone, Debug)]
+pub struct RerouteHysteresis {
+    edges: HashSet<EdgeId>,
+    discount: f64,
+}
+
+impl RerouteHysteresis {
+    /// creates a preference for the edges of a previous route.
+    ///
+    /// # Arguments
+    ///
+    /// * `edges` - the edges of the previously recommended route
+    /// * `discount` - fraction of the traversal cost of those edges to remove,
+    ///   between 0 and [`MAX_REROUTE_PREFERENCE`]
+    ///
+    /// # Returns
+    ///
+    /// the preference, or an error if the discount is out of bounds
+    pub fn new(
+        edges: impl IntoIterator<Item = EdgeId>,
+        discount: f64,
+    ) -> Result<RerouteHysteresis, SearchError> {
+        if !(0.0..=MAX_REROUTE_PREFERENCE).contains(&discount) {
+            return Err(SearchError::BuildError(format!(
+                "reroute preference must be between 0 and {}, found {}",
+                MAX_REROUTE_PREFERENCE, discount
+            )));
+        }
+        Ok(RerouteHysteresis {
+            edges: edges.into_iter().collect(),
+            discount,
+        })
+    }
+
+    /// applies the preference to the traversal cost of an edge
+    pub fn apply(&self, edge_id: &EdgeId, traversal_cost: Cost) -> Cost {
+        if self.edges.contains(edge_id) {
+            Cost::new(traversal_cost.as_f64() * (1.0 - self.discount))
+        } else {
+            traversal_cost
+        }
+    }
+
+    /// factor applied to heuristic cost estimates, which keeps them from
+    /// overestimating the cost of a path along the previous route
+    pub fn heuristic_factor(&self) -> f64 {
+        1.0 - self.discount
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discount_bounds() {
+        let hysteresis = RerouteHysteresis::new(vec![EdgeId(1)], 0.1).unwrap();
+        assert_eq!(
+            hysteresis.apply(&EdgeId(1), Cost::new(10.0)),
+            Cost::new(9.0)
+        );
+        assert_eq!(
+            hysteresis.apply(&EdgeId(2), Cost::new(10.0)),
+            Cost::new(10.0)
+        );
+        assert!(RerouteHysteresis::new(vec![EdgeId(1)], 0.6).is_err());
+        assert!(RerouteHysteresis::new(vec![EdgeId(1)], -0.1).is_err());
+    }
+}
@@ -0,0 +1,429 @@
+use super::{
+    backtrack,
+    direction::Direction,
+    edge_traversal::EdgeTraversal,
+    search_algorithm_result::SearchAlgorithmResult,
+    search_error::SearchError,
+    search_instance::SearchInstance,
+    search_tree_branch::SearchTreeBranch,
+    util::label::{backtrack_labels, Label},
+    MinSearchTree,
+};
+use crate::{
+    model::{
+        network::vertex_id::VertexId,
+        state::StateVariable,
+        unit::{Cost, ReverseCost},
+    },
+    util::priority_queue::InternalPriorityQueue,
+};
+use std::{collections::HashMap, time::Instant};
+
+/// the number of resource buckets the budget is divided into when not configured
+pub const DEFAULT_BUCKETS: usize = 100;
+
+/// finds the least-cost route from source to target among routes that accumulate
+/// no more than `budget` of a resource state feature, such as the fastest route
+/// whose CO2 emissions stay under a carbon budget. the resource should be
+/// accumulated by the traversal model and never decrease along a route.
+///
+/// this runs a labeled search over (vertex, resource bucket) pairs, where the budget
+/// is divided into `buckets` equal buckets. partial routes are expanded in order of
+/// cost and dropped once they exceed the budget. a partial route is kept at a vertex
+/// only if it reaches a lower bucket than every cheaper partial route there, so at
+/// most `buckets + 1` partial routes are kept per vertex. more buckets find routes
+/// closer to the budget at the expense of a larger search.
+///
+/// the route with the least resource is found first. if it exceeds the budget, the
+/// search fails with the minimum achievable resource. it is also returned when the
+/// bucketed search does not find a route within the budget.
+///
+/// the labeled search does not traverse edges against their direction, break
+/// ties by turn count or prefer a previous route, so searches with a wrong way
+/// penalty, fewer turns preference or reroute hysteresis are rejected.
+pub fn run(
+    source: VertexId,
+    target: VertexId,
+    resource_feature: &str,
+    budget: f64,
+    buckets: usize,
+    si: &SearchInstance,
+) -> Result<SearchAlgorithmResult, SearchError> {
+    if !(budget >= 0.0 && budget.is_finite()) {
+        return Err(SearchError::BuildError(format!(
+            "budget must be a finite, non-negative number, found {}",
+            budget
+        )));
+    }
+    if buckets == 0 {
+        return Err(SearchError::BuildError(String::from(
+            "buckets must be a positive integer",
+        )));
+    }
+    let unsupported = [
+        ("wrong_way_penalty", si.wrong_way_penalty.is_some()),
+        ("prefer_fewer_turns", si.prefer_fewer_turns),
+        ("reroute_hysteresis", si.reroute_hysteresis.is_some()),
+    ];
+    if let Some((name, _)) = unsupported.iter().find(|(_, is_set)| *is_set) {
+        return Err(SearchError::BuildError(format!(
+            "{} is not supported when routing under a resource budget",
+            name
+        )));
+    }
+    let resource_index = si
+        .state_model
+        .indexed_iter()
+        .find(|(_, (n, _))| *n == resource_feature)
+        .map(|(index, _)| index)
+        .ok_or_else(|| {
+            SearchError::BuildError(format!(
+                "resource feature '{}' is not in the state model, found {}",
+                resource_feature,
+                si.state_model.get_names()
+            ))
+        })?;
+    let resource_of = |state: &[StateVariable]| -> f64 {
+        state.get(resource_index).map(|s| s.0).unwrap_or_default()
+    };
+    let bucket_size = budget / buckets as f64;
+    let bucket_of = |resource: f64| -> i64 {
+        if bucket_size > 0.0 {
+            (resource / bucket_size).floor() as i64
+        } else {
+            0
+        }
+    };
+
+    // the least-resource route decides if the budget can be met and is the fallback solution
+    let (min_route, min_iterations) = min_resource_route(source, target, &resource_of, si)?;
+    let initial_state = si.state_model.initial_state()?;
+    let minimum = min_route
+        .last()
+        .map(|et| resource_of(&et.result_state))
+        .unwrap_or_else(|| resource_of(&initial_state));
+    if minimum > budget {
+        return Err(SearchError::ResourceBudgetExceeded {
+            feature: resource_feature.to_string(),
+            budget,
+            minimum,
+        });
+    }
+
+    let mut labels: Vec<Label> = vec![Label::source(source, resource_of(&initial_state))];
+    // the lowest bucket settled at each vertex. labels are settled in order of
+    // cost, so a label is dominated by any label settled before it in the same
+    // or a lower bucket.
+    let mut settled_buckets: HashMap<VertexId, i64> = HashMap::new();
+    let mut queue: InternalPriorityQueue<usize, ReverseCost> = InternalPriorityQueue::default();
+    queue.push(0, Cost::ZERO.into());
+    let mut best: Option<usize> = None;
+    let mut iterations: u64 = 0;
+    let start_time = Instant::now();
+    let empty_tree = MinSearchTree::new();
+
+    while let Some((label_idx, _)) = queue.pop() {
+        si.termination_model
+            .test(&start_time, labels.len(), iterations)?;
+        let label = &labels[label_idx];
+        let bucket = bucket_of(label.value);
+        if settled_buckets
+            .get(&label.vertex_id)
+            .is_some_and(|settled| *settled <= bucket)
+        {
+            continue;
+        }
+        settled_buckets.insert(label.vertex_id, bucket);
+        if label.vertex_id == target {
+            best = Some(label_idx);
+            break;
+        }
+        iterations += 1;
+
+        let current_vertex_id = label.vertex_id;
+        let current_cost = label.cost;
+        let last_edge_id = label.edge_traversal.as_ref().map(|et| et.edge_id);
+        let current_state = label
+            .edge_traversal
+            .as_ref()
+            .map(|et| et.result_state.clone())
+            .unwrap_or_else(|| initial_state.clone());
+
+        for edge_id in Direction::Forward.get_incident_edges(&current_vertex_id, si) {
+            let edge = si.graph.get_edge(edge_id)?;
+            let next_vertex_id = edge.dst_vertex_id;
+            if !si.frontier_model.valid_frontier(
+                edge,
+                last_edge_id,
+                &current_state,
+                &empty_tree,
+                &Direction::Forward,
+                &si.state_model,
+            )? {
+                continue;
+            }
+            let et = EdgeTraversal::forward_traversal(*edge_id, last_edge_id, &current_state, si)?;
+            let next_resource = resource_of(&et.result_state);
+            let dominated = settled_buckets
+                .get(&next_vertex_id)
+                .is_some_and(|settled| *settled <= bucket_of(next_resource));
+            if next_resource > budget || dominated {
+                continue;
+            }
+            let next_cost = current_cost + et.total_cost();
+            let estimate = if next_vertex_id == target {
+                Cost::ZERO
+            } else {
+                si.estimate_traversal_cost(next_vertex_id, target, &et.result_state)?
+            };
+            let next_idx = labels.len();
+            labels.push(Label {
+                vertex_id: next_vertex_id,
+                parent: Some(label_idx),
+                edge_traversal: Some(et),
+                cost: next_cost,
+                value: next_resource,
+                active: true,
+            });
+            queue.push(next_idx, (next_cost + estimate).into());
+        }
+    }
+
+    let route = match best {
+        Some(best_idx) => backtrack_labels(&labels, best_idx),
+        None => min_route,
+    };
+    let tree: MinSearchTree = route
+        .iter()
+        .map(|et| {
+            let edge = si.graph.get_edge(&et.edge_id)?;
+            let branch = SearchTreeBranch {
+                terminal_vertex: edge.src_vertex_id,
+                edge_traversal: et.clone(),
+            };
+            Ok((edge.dst_vertex_id, branch))
+        })
+        .collect::<Result<_, SearchError>>()?;
+    Ok(SearchAlgorithmResult {
+        trees: vec![tree],
+        routes: vec![route],
+        iterations: min_iterations + iterations,
+    })
+}
+
+/// finds the route from source to target that accumulates the least resource,
+/// settling vertices in order of their resource value.
+fn min_resource_route(
+    source: VertexId,
+    target: VertexId,
+    resource_of: &dyn Fn(&[StateVariable]) -> f64,
+    si: &SearchInstance,
+) -> Result<(Vec<EdgeTraversal>, u64), SearchError> {
+    let initial_state = si.state_model.initial_state()?;
+    let mut tree = MinSearchTree::new();
+    let mut resources: HashMap<VertexId, f64> =
+        HashMap::from([(source, resource_of(&initial_state))]);
+    let mut queue: InternalPriorityQueue<VertexId, ReverseCost> = InternalPriorityQueue::default();
+    queue.push(source, Cost::new(resource_of(&initial_state)).into());
+    let mut iterations: u64 = 0;
+    // tracks if the frontier model rejected any edge, to explain a failed search
+    let mut restricted = false;
+    let start_time = Instant::now();
+
+    while let Some((current_vertex_id, _)) = queue.pop() {
+        si.termination_model
+            .test(&start_time, tree.len(), iterations)?;
+        if current_vertex_id == target {
+            let route = backtrack::vertex_oriented_route(source, target, &tree)?;
+            return Ok((route, iterations));
+        }
+        iterations += 1;
+
+        let current_branch = tree.get(&current_vertex_id);
+        let last_edge_id = current_branch.map(|b| b.edge_traversal.edge_id);
+        let current_state = current_branch
+            .map(|b| b.edge_traversal.result_state.clone())
+            .unwrap_or_else(|| initial_state.clone());
+        let current_resource = resources
+            .get(&current_vertex_id)
+            .cloned()
+            .unwrap_or(f64::INFINITY);
+
+        for edge_id in Direction::Forward.get_incident_edges(&current_vertex_id, si) {
+            let edge = si.graph.get_edge(edge_id)?;
+            let next_vertex_id = edge.dst_vertex_id;
+            if !si.frontier_model.valid_frontier(
+                edge,
+                last_edge_id,
+                &current_state,
+                &tree,
+                &Direction::Forward,
+                &si.state_model,
+            )? {
+                restricted = true;
+                continue;
+            }
+            let et = EdgeTraversal::forward_traversal(*edge_id, last_edge_id, &current_state, si)?;
+            let next_resource = resource_of(&et.result_state).max(current_resource);
+            let existing = resources
+                .get(&next_vertex_id)
+                .cloned()
+                .unwrap_or(f64::INFINITY);
+            if next_resource < existing {
+                resources.insert(next_vertex_id, next_resource);
+                tree.insert(
+                    next_vertex_id,
+                    SearchTreeBranch {
+                        terminal_vertex: current_vertex_id,
+                        edge_traversal: et,
+                    },
+                );
+                queue.push_increase(next_vertex_id, Cost::new(next_resource).into());
+            }
+        }
+    }
+
+    Err(si.no_path_error(source, target, restricted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::util::test_search_instance::{
+        build_graph, custom_feature, vertices_at_origin, TestSearchInstance,
+    };
+    use crate::model::network::{Edge, EdgeId, Vertex};
+    use crate::model::state::{StateFeature, StateModel};
+    use crate::model::traversal::default::DistanceTraversalModel;
+    use crate::model::traversal::{TraversalModel, TraversalModelError};
+    use crate::model::unit::DistanceUnit;
+    use std::sync::Arc;
+
+    /// accumulates fixed CO2 emissions per edge in addition to distance
+    struct EmissionsTraversalModel {
+        distance: DistanceTraversalModel,
+        emissions: Vec<f64>,
+    }
+
+    impl TraversalModel for EmissionsTraversalModel {
+        fn state_features(&self) -> Vec<(String, StateFeature)> {
+            vec![]
+        }
+
+        fn traverse_edge(
+            &self,
+            trajectory: (&Vertex, &Edge, &Vertex),
+            state: &mut Vec<StateVariable>,
+            state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            self.distance
+                .traverse_edge(trajectory, state, state_model)?;
+            let (_, edge, _) = trajectory;
+            let name = String::from("co2");
+            let co2 = state_model.get_custom_f64(state, &name)?;
+            let emitted = self.emissions[edge.edge_id.0];
+            state_model.set_custom_f64(state, &name, &(co2 + emitted))?;
+            Ok(())
+        }
+
+        fn estimate_traversal(
+            &self,
+            od: (&Vertex, &Vertex),
+            state: &mut Vec<StateVariable>,
+            state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            self.distance.estimate_traversal(od, state, state_model)
+        }
+    }
+
+    /// three routes from vertex 0 to vertex 3, where the shorter routes emit more
+    /// CO2. all vertices are placed at (0,0) so that the cost estimate is zero.
+    /// (0) -[0]-> (1) -[1]-> (3): 20 units distance, 10 units CO2
+    /// (0) -[2]-> (2) -[3]-> (3): 30 units distance, 4 units CO2
+    /// (0) -[4]-> (4) -[5]-> (3): 50 units distance, 1 unit CO2
+    fn build_search_instance() -> SearchInstance {
+        let edges = vec![
+            Edge::new(0, 0, 1, 10.0),
+            Edge::new(1, 1, 3, 10.0),
+            Edge::new(2, 0, 2, 15.0),
+            Edge::new(3, 2, 3, 15.0),
+            Edge::new(4, 0, 4, 25.0),
+            Edge::new(5, 4, 3, 25.0),
+        ];
+        TestSearchInstance {
+            traversal_model: Arc::new(EmissionsTraversalModel {
+                distance: DistanceTraversalModel::new(DistanceUnit::Meters),
+                emissions: vec![5.0, 5.0, 2.0, 2.0, 0.5, 0.5],
+            }),
+            custom_features: vec![custom_feature("co2", "kilograms", 0.0)],
+            ..Default::default()
+        }
+        .build(build_graph(vertices_at_origin(5), edges))
+    }
+
+    #[test]
+    fn test_resource_budget_forces_lower_emission_routes() {
+        let si = build_search_instance();
+        let run_with_budget = |budget: f64| -> Vec<EdgeId> {
+            let result = run(
+                VertexId(0),
+                VertexId(3),
+                "co2",
+                budget,
+                DEFAULT_BUCKETS,
+                &si,
+            )
+            .unwrap();
+            result.routes[0].iter().map(|et| et.edge_id).collect()
+        };
+
+        // a loose budget allows the shortest route
+        assert_eq!(run_with_budget(100.0), vec![EdgeId(0), EdgeId(1)]);
+        // tightening the budget forces longer routes with lower emissions
+        assert_eq!(run_with_budget(5.0), vec![EdgeId(2), EdgeId(3)]);
+        assert_eq!(run_with_budget(2.0), vec![EdgeId(4), EdgeId(5)]);
+        // the budget is inclusive
+        assert_eq!(run_with_budget(1.0), vec![EdgeId(4), EdgeId(5)]);
+    }
+
+    #[test]
+    fn test_resource_budget_infeasible() {
+        let si = build_search_instance();
+        let result = run(VertexId(0), VertexId(3), "co2", 0.5, DEFAULT_BUCKETS, &si);
+        match result {
+            Err(SearchError::ResourceBudgetExceeded {
+                feature,
+                budget,
+                minimum,
+            }) => {
+                assert_eq!(feature, "co2");
+                assert_eq!(budget, 0.5);
+                assert_eq!(minimum, 1.0);
+            }
+            other => panic!("expected a resource budget error, found {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_resource_budget_invalid_arguments() {
+        let si = build_search_instance();
+        let missing_feature = run(VertexId(0), VertexId(3), "nox", 5.0, 10, &si);
+        assert!(missing_feature.is_err());
+        let negative_budget = run(VertexId(0), VertexId(3), "co2", -1.0, 10, &si);
+        assert!(negative_budget.is_err());
+        let no_buckets = run(VertexId(0), VertexId(3), "co2", 5.0, 0, &si);
+        assert!(no_buckets.is_err());
+    }
+
+    #[test]
+    fn test_resource_budget_rejects_unsupported_options() {
+        let run_with =
+            |si: &SearchInstance| run(VertexId(0), VertexId(3), "co2", 5.0, DEFAULT_BUCKETS, si);
+        let mut si = build_search_instance();
+        si.wrong_way_penalty = Some(Cost::new(2.0));
+        assert!(matches!(run_with(&si), Err(SearchError::BuildError(_))));
+        let mut si = build_search_instance();
+        si.prefer_fewer_turns = true;
+        assert!(matches!(run_with(&si), Err(SearchError::BuildError(_))));
+    }
+}
@@ -1,8 +1,13 @@
 use super::backtrack;
+use super::bidirectional_dijkstra;
+use super::distinct_alternatives;
 use super::edge_traversal::EdgeTraversal;
+use super::ksp::KspDiversity;
 use super::ksp::KspQuery;
 use super::ksp::KspTerminationCriteria;
 use super::ksp::{svp, yens};
+use super::max_reward;
+use super::resource_budget;
 use super::search_algorithm_result::SearchAlgorithmResult;
 use super::search_error::SearchError;
 use super::search_instance::SearchInstance;
@@ -18,6 +23,10 @@ use std::collections::HashMap;
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum SearchAlgorithm {
     Dijkstra,
+    /// a Dijkstra search from both the source and the target that meets in the
+    /// middle. falls back to [`SearchAlgorithm::Dijkstra`] without a target or
+    /// when the cost model is not additive.
+    BidirectionalDijkstra,
     #[serde(rename = "a*")]
     AStarAlgorithm {
         weight_factor: Option<Cost>,
@@ -27,12 +36,37 @@ pub enum SearchAlgorithm {
         underlying: Box<SearchAlgorithm>,
         similarity: Option<RouteSimilarityFunction>,
         termination: Option<KspTerminationCriteria>,
+        diversity: Option<KspDiversity>,
     },
     Yens {
         k: usize,
         underlying: Box<SearchAlgorithm>,
         similarity: Option<RouteSimilarityFunction>,
         termination: Option<KspTerminationCriteria>,
+        diversity: Option<KspDiversity>,
+    },
+    /// finds the route that maximizes a reward state feature, such as a scenic
+    /// score, within a budget of `budget_ratio` times the shortest route cost.
+    MaxReward {
+        reward: String,
+        budget_ratio: f64,
+        max_labels: Option<usize>,
+        underlying: Box<SearchAlgorithm>,
+    },
+    /// finds the least-cost route that accumulates no more than `budget` of a
+    /// resource state feature, such as the fastest route under a CO2 budget.
+    /// a query may override the budget with a `resource_budget` value.
+    ResourceBudget {
+        resource: String,
+        budget: f64,
+        buckets: Option<usize>,
+    },
+    /// returns up to `max_alternatives` routes, each with at least `min_distinctness`
+    /// from the others, chosen from the routes of an underlying k-shortest paths search.
+    DistinctAlternatives {
+        max_alternatives: usize,
+        min_distinctness: f64,
+        underlying: Box<SearchAlgorithm>,
     },
 }
 
@@ -50,6 +84,11 @@ impl SearchAlgorithm {
                 weight_factor: Some(Cost::ZERO),
             }
             .run_vertex_oriented(src_id, dst_id_opt, query, direction, si),
+            SearchAlgorithm::BidirectionalDijkstra => match direction {
+                Direction::Forward => bidirectional_dijkstra::run(src_id, dst_id_opt, query, si),
+                Direction::Reverse => SearchAlgorithm::Dijkstra
+                    .run_vertex_oriented(src_id, dst_id_opt, query, direction, si),
+            },
             SearchAlgorithm::AStarAlgorithm { weight_factor } => {
                 let w_val = match query.get("weight_factor") {
                     Some(w_json) => w_json
@@ -82,6 +121,7 @@ impl SearchAlgorithm {
                 underlying,
                 similarity,
                 termination,
+                diversity,
             } => {
                 let dst_id = dst_id_opt.ok_or_else(|| {
                     SearchError::BuildError(String::from(
@@ -90,7 +130,7 @@ impl SearchAlgorithm {
                 })?;
                 let sim_fn = similarity.as_ref().cloned().unwrap_or_default();
                 let term_fn = termination.as_ref().cloned().unwrap_or_default();
-                let ksp_query = KspQuery::new(src_id, dst_id, query, *k)?;
+                let ksp_query = KspQuery::new(src_id, dst_id, query, *k, diversity.as_ref())?;
                 yens::run(&ksp_query, &term_fn, &sim_fn, si, underlying)
             }
             SearchAlgorithm::KspSingleVia {
@@ -98,6 +138,7 @@ impl SearchAlgorithm {
                 underlying,
                 similarity,
                 termination,
+                diversity,
             } => {
                 let dst_id = dst_id_opt.ok_or_else(|| {
                     SearchError::BuildError(String::from(
@@ -106,11 +147,107 @@ impl SearchAlgorithm {
                 })?;
                 let sim_fn = similarity.as_ref().cloned().unwrap_or_default();
                 let term_fn = termination.as_ref().cloned().unwrap_or_default();
-                let ksp_query = KspQuery::new(src_id, dst_id, query, *k)?;
+                let ksp_query = KspQuery::new(src_id, dst_id, query, *k, diversity.as_ref())?;
                 svp::run(&ksp_query, &term_fn, &sim_fn, si, underlying)
             }
+            SearchAlgorithm::MaxReward {
+                reward,
+                budget_ratio,
+                max_labels,
+                underlying,
+            } => {
+                let dst_id = dst_id_opt.ok_or_else(|| {
+                    SearchError::BuildError(String::from(
+                        "attempting to run max reward algorithm without destination",
+                    ))
+                })?;
+                let budget_ratio = match query.get("budget_ratio") {
+                    Some(b_json) => b_json.as_f64().ok_or(SearchError::BuildError(format!(
+                        "budget_ratio must be a float, found {}",
+                        b_json
+                    )))?,
+                    None => *budget_ratio,
+                };
+                max_reward::run(
+                    src_id,
+                    dst_id,
+                    query,
+                    reward,
+                    budget_ratio,
+                    max_labels.unwrap_or(max_reward::DEFAULT_MAX_LABELS),
+                    underlying,
+                    si,
+                )
+            }
+            SearchAlgorithm::ResourceBudget {
+                resource,
+                budget,
+                buckets,
+            } => {
+                let dst_id = dst_id_opt.ok_or_else(|| {
+                    SearchError::BuildError(String::from(
+                        "attempting to run resource budget algorithm without destination",
+                    ))
+                })?;
+                let budget = match query.get("resource_budget") {
+                    Some(b_json) => b_json.as_f64().ok_or(SearchError::BuildError(format!(
+                        "resource_budget must be a float, found {}",
+                        b_json
+                    )))?,
+                    None => *budget,
+                };
+                resource_budget::run(
+                    src_id,
+                    dst_id,
+                    resource,
+                    budget,
+                    buckets.unwrap_or(resource_budget::DEFAULT_BUCKETS),
+                    si,
+                )
+            }
+            SearchAlgorithm::DistinctAlternatives {
+                max_alternatives,
+                min_distinctness,
+                underlying,
+            } => {
+                let dst_id = dst_id_opt.ok_or_else(|| {
+                    SearchError::BuildError(String::from(
+                        "attempting to run distinct alternatives algorithm without destination",
+                    ))
+                })?;
+                let max_alternatives =
+                    distinct_alternatives::get_max_alternatives(query, *max_alternatives)?;
+                distinct_alternatives::run(
+                    src_id,
+                    dst_id,
+                    query,
+                    max_alternatives,
+                    *min_distinctness,
+                    underlying,
+                    si,
+                )
+            }
         }
     }
+
+    /// the number of routes a query asks of this algorithm, for algorithms that
+    /// return up to a requested number of alternative routes.
+    ///
+    /// # Returns
+    ///
+    /// the requested number of routes, or None if this algorithm does not take one
+    pub fn alternatives_requested(
+        &self,
+        query: &serde_json::Value,
+    ) -> Result<Option<usize>, SearchError> {
+        match self {
+            SearchAlgorithm::DistinctAlternatives {
+                max_alternatives, ..
+            } => distinct_alternatives::get_max_alternatives(query, *max_alternatives).map(Some),
+            _ => Ok(None),
+        }
+    }
+
     pub fn run_edge_oriented(
         &self,
         src_id: EdgeId,
@@ -124,6 +261,9 @@ impl SearchAlgorithm {
                 weight_factor: Some(Cost::ZERO),
             }
             .run_edge_oriented(src_id, dst_id_opt, query, direction, search_instance),
+            SearchAlgorithm::BidirectionalDijkstra => {
+                run_edge_oriented(src_id, dst_id_opt, query, direction, self, search_instance)
+            }
             SearchAlgorithm::AStarAlgorithm { weight_factor } => {
                 let search_result = a_star::run_edge_oriented(
                     src_id,
@@ -155,12 +295,30 @@ impl SearchAlgorithm {
                 underlying: _,
                 similarity: _,
                 termination: _,
+                diversity: _,
             } => run_edge_oriented(src_id, dst_id_opt, query, direction, self, search_instance),
             SearchAlgorithm::Yens {
                 k: _,
                 underlying: _,
                 similarity: _,
                 termination: _,
+                diversity: _,
+            } => run_edge_oriented(src_id, dst_id_opt, query, direction, self, search_instance),
+            SearchAlgorithm::MaxReward {
+                reward: _,
+                budget_ratio: _,
+                max_labels: _,
+                underlying: _,
+            } => run_edge_oriented(src_id, dst_id_opt, query, direction, self, search_instance),
+            SearchAlgorithm::ResourceBudget {
+                resource: _,
+                budget: _,
+                buckets: _,
+            } => run_edge_oriented(src_id, dst_id_opt, query, direction, self, search_instance),
+            SearchAlgorithm::DistinctAlternatives {
+                max_alternatives: _,
+                min_distinctness: _,
+                underlying: _,
             } => run_edge_oriented(src_id, dst_id_opt, query, direction, self, search_instance),
         }
     }
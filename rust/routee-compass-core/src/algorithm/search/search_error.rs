@@ -1,3 +1,4 @@
+use super::NoRouteCause;
 use crate::model::{
     access::AccessModelError,
     cost::CostModelError,
@@ -53,8 +54,108 @@ pub enum SearchError {
     NoPathExistsBetweenVertices(VertexId, VertexId),
     #[error("no path exists between edges {0} and {1}")]
     NoPathExistsBetweenEdges(EdgeId, EdgeId),
+    #[error("no path exists between vertices {0} and {1} due to frontier model restrictions")]
+    NoPathExistsDueToRestrictions(VertexId, VertexId),
+    #[error("no path exists between vertices {0} and {1} within the cost budget")]
+    CostBudgetExceeded(VertexId, VertexId),
+    #[error("required edge {0} cannot be reached from vertex {1}")]
+    RequiredEdgeUnreachable(EdgeId, VertexId),
+    #[error("destination vertex {1} cannot be reached after required edge {0}")]
+    DestinationUnreachableAfterRequiredEdge(EdgeId, VertexId),
+    #[error("route distance is {0:.2} times the straight-line distance, exceeding the max_detour_ratio of {1}")]
+    DetourRatioExceeded(f64, f64),
+    #[error("no route keeps {feature} within the budget of {budget}, the minimum achievable {feature} is {minimum}")]
+    ResourceBudgetExceeded {
+        feature: String,
+        budget: f64,
+        minimum: f64,
+    },
+    #[error("found {0} of the {1} requested routes that are diverse enough before exhausting the candidate budget")]
+    InsufficientDiverseRoutes(usize, usize),
+    #[error("route is not connected: edge {0} does not end where edge {1} begins")]
+    RouteNotConnected(EdgeId, EdgeId),
     #[error("error accessing shared read-only dataset: {0}")]
     ReadOnlyPoisonError(String),
     #[error("internal error due to search logic: {0}")]
     InternalError(String),
 }
+
+impl SearchError {
+    /// if this error describes a search that ended without finding a route,
+    /// classifies the cause. for all other errors, returns None.
+    pub fn no_route_cause(&self) -> Option<NoRouteCause> {
+        match self {
+            SearchError::NoPathExistsBetweenVertices(_, _) => Some(NoRouteCause::Disconnected),
+            SearchError::NoPathExistsBetweenEdges(_, _) => Some(NoRouteCause::Disconnected),
+            SearchError::NoPathExistsDueToRestrictions(_, _) => Some(NoRouteCause::Restricted),
+            SearchError::RequiredEdgeUnreachable(_, _) => Some(NoRouteCause::Disconnected),
+            SearchError::DestinationUnreachableAfterRequiredEdge(_, _) => {
+                Some(NoRouteCause::Disconnected)
+            }
+            SearchError::CostBudgetExceeded(_, _) => Some(NoRouteCause::BudgetExceeded),
+            SearchError::QueryTerminated(_) => Some(NoRouteCause::TerminationLimit),
+            SearchError::DetourRatioExceeded(_, _) => Some(NoRouteCause::DetourExceeded),
+            SearchError::ResourceBudgetExceeded { .. } => {
+                Some(NoRouteCause::ResourceBudgetExceeded)
+            }
+            SearchError::TerminationModelFailure { source } => match source {
+                TerminationModelError::QueryTerminated(_) => Some(NoRouteCause::TerminationLimit),
+                TerminationModelError::QueryTimedOut(_) => Some(NoRouteCause::Timeout),
+                TerminationModelError::BuildError(_) => None,
+                TerminationModelError::RuntimeError(_) => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_route_cause() {
+        let cases = vec![
+            (
+                SearchError::NoPathExistsBetweenVertices(VertexId(0), VertexId(1)),
+                Some(NoRouteCause::Disconnected),
+            ),
+            (
+                SearchError::NoPathExistsBetweenEdges(EdgeId(0), EdgeId(1)),
+                Some(NoRouteCause::Disconnected),
+            ),
+            (
+                SearchError::NoPathExistsDueToRestrictions(VertexId(0), VertexId(1)),
+                Some(NoRouteCause::Restricted),
+            ),
+            (
+                SearchError::CostBudgetExceeded(VertexId(0), VertexId(1)),
+                Some(NoRouteCause::BudgetExceeded),
+            ),
+            (
+                SearchError::from(TerminationModelError::QueryTerminated(String::from("i"))),
+                Some(NoRouteCause::TerminationLimit),
+            ),
+            (
+                SearchError::from(TerminationModelError::QueryTimedOut(String::from("t"))),
+                Some(NoRouteCause::Timeout),
+            ),
+            (
+                SearchError::DetourRatioExceeded(3.2, 2.0),
+                Some(NoRouteCause::DetourExceeded),
+            ),
+            (
+                SearchError::ResourceBudgetExceeded {
+                    feature: String::from("co2"),
+                    budget: 1.0,
+                    minimum: 2.0,
+                },
+                Some(NoRouteCause::ResourceBudgetExceeded),
+            ),
+            (SearchError::InternalError(String::from("oops")), None),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(error.no_route_cause(), expected, "{}", error);
+        }
+    }
+}
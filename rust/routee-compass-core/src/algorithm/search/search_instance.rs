@@ -1,4 +1,4 @@
-use super::search_error::SearchError;
+use super::{a_star::Landmarks, reroute_hysteresis::RerouteHysteresis, search_error::SearchError};
 use crate::model::{
     access::AccessModel,
     cost::CostModel,
@@ -23,6 +23,17 @@ pub struct SearchInstance {
     pub cost_model: Arc<CostModel>,
     pub frontier_model: Arc<dyn FrontierModel>,
     pub termination_model: Arc<TerminationModel>,
+    /// when set, one-way edges may also be traversed against their direction,
+    /// with the traversal cost multiplied by this penalty factor.
+    pub wrong_way_penalty: Option<Cost>,
+    /// when true, ties between equal-cost paths are broken in favor of the
+    /// path with fewer turns.
+    pub prefer_fewer_turns: bool,
+    /// when set, the edges of a previously recommended route are preferred
+    /// when rerouting.
+    pub reroute_hysteresis: Option<RerouteHysteresis>,
+    /// when set, landmark cost tables tighten the A* heuristic
+    pub landmarks: Option<Arc<Landmarks>>,
 }
 
 impl SearchInstance {
@@ -43,4 +54,32 @@ impl SearchInstance {
         let cost_estimate = self.cost_model.cost_estimate(state, &dst_state)?;
         Ok(cost_estimate)
     }
+
+    /// the landmark tables, when their lower bound is admissible for this
+    /// instance. as in the bidirectional search, feature costs must add up edge
+    /// by edge, and no access model may add costs between edges.
+    /// wrong-way traversals may also undercut the tabled costs.
+    pub fn admissible_landmarks(&self) -> Option<&Landmarks> {
+        let admissible = self.cost_model.is_additive()
+            && !self.access_model.adds_costs()
+            && self.wrong_way_penalty.is_none();
+        self.landmarks.as_deref().filter(|_| admissible)
+    }
+
+    /// the error for a search from the source that ended without reaching the
+    /// target. if the frontier model rejected any edge during the search, the
+    /// failure is reported as a restriction. otherwise, the target is not
+    /// connected to the source.
+    pub fn no_path_error(
+        &self,
+        source: VertexId,
+        target: VertexId,
+        restricted: bool,
+    ) -> SearchError {
+        if restricted {
+            SearchError::NoPathExistsDueToRestrictions(source, target)
+        } else {
+            SearchError::NoPathExistsBetweenVertices(source, target)
+        }
+    }
 }
@@ -6,10 +6,16 @@ use std::collections::HashMap;
 pub struct SearchResult {
     pub tree: HashMap<VertexId, SearchTreeBranch>,
     pub iterations: u64,
+    /// true if the frontier model rejected any edge during the search
+    pub restricted: bool,
 }
 
 impl SearchResult {
     pub fn new(tree: HashMap<VertexId, SearchTreeBranch>, iterations: u64) -> SearchResult {
-        SearchResult { tree, iterations }
+        SearchResult {
+            tree,
+            iterations,
+            restricted: false,
+        }
     }
 }
@@ -28,6 +28,7 @@ impl FrontierModel for EdgeCutFrontierModel {
     fn valid_frontier(
         &self,
         edge: &Edge,
+        previous_edge_id: Option<EdgeId>,
         state: &[crate::model::state::StateVariable],
         tree: &std::collections::HashMap<
             crate::model::network::VertexId,
@@ -39,8 +40,14 @@ impl FrontierModel for EdgeCutFrontierModel {
         if self.cut_edges.contains(&edge.edge_id) {
             Ok(false)
         } else {
-            self.underlying
-                .valid_frontier(edge, state, tree, direction, state_model)
+            self.underlying.valid_frontier(
+                edge,
+                previous_edge_id,
+                state,
+                tree,
+                direction,
+                state_model,
+            )
         }
     }
 
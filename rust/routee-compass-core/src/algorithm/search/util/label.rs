@@ -0,0 +1,57 @@
+use crate::algorithm::search::EdgeTraversal;
+use crate::model::{network::vertex_id::VertexId, unit::Cost};
+
+/// a partial route from the source in a labeled search, stored as a link to its
+/// parent label. labeled searches keep more than one partial route per vertex,
+/// each tracking the value of a state feature, such as a reward or a resource.
+pub struct Label {
+    pub vertex_id: VertexId,
+    pub parent: Option<usize>,
+    pub edge_traversal: Option<EdgeTraversal>,
+    pub cost: Cost,
+    pub value: f64,
+    /// false once the label is dominated by another label at the same vertex
+    pub active: bool,
+}
+
+impl Label {
+    /// the label at the source of the search, with the value of the initial state
+    pub fn source(vertex_id: VertexId, value: f64) -> Label {
+        Label {
+            vertex_id,
+            parent: None,
+            edge_traversal: None,
+            cost: Cost::ZERO,
+            value,
+            active: true,
+        }
+    }
+}
+
+/// tests whether the partial route ending at a label already visits a vertex
+pub fn visits(labels: &[Label], label_idx: usize, vertex_id: VertexId) -> bool {
+    let mut current = Some(label_idx);
+    while let Some(idx) = current {
+        let label = &labels[idx];
+        if label.vertex_id == vertex_id {
+            return true;
+        }
+        current = label.parent;
+    }
+    false
+}
+
+/// collects the edge traversals of the route ending at a label, from the source
+pub fn backtrack_labels(labels: &[Label], label_idx: usize) -> Vec<EdgeTraversal> {
+    let mut route = vec![];
+    let mut current = Some(label_idx);
+    while let Some(idx) = current {
+        let label = &labels[idx];
+        if let Some(et) = &label.edge_traversal {
+            route.push(et.clone());
+        }
+        current = label.parent;
+    }
+    route.reverse();
+    route
+}
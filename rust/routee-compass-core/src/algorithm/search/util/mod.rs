@@ -1,5 +1,10 @@
 mod edge_cut_frontier_model;
+pub mod label;
 mod route_similarity_function;
+#[cfg(test)]
+pub mod test_search_instance;
+pub mod turn_count;
+pub mod vertex_sequence;
 
 pub use edge_cut_frontier_model::EdgeCutFrontierModel;
 pub use route_similarity_function::RouteSimilarityFunction;
@@ -0,0 +1,125 @@
+//! graphs and search instances shared by the unit tests of the search algorithms.
+
+use crate::algorithm::search::SearchInstance;
+use crate::model::access::default::NoAccessModel;
+use crate::model::cost::{CostAggregation, CostModel, VehicleCostRate};
+use crate::model::frontier::default::no_restriction::NoRestriction;
+use crate::model::map::{MapModel, MapModelConfig};
+use crate::model::network::{graph::Graph, Edge, Vertex};
+use crate::model::state::{CustomFeatureFormat, StateFeature, StateModel};
+use crate::model::termination::TerminationModel;
+use crate::model::traversal::default::DistanceTraversalModel;
+use crate::model::traversal::TraversalModel;
+use crate::model::unit::{Distance, DistanceUnit};
+use crate::util::compact_ordered_hash_map::CompactOrderedHashMap;
+use ordered_float::OrderedFloat;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// builds a graph from its vertices and edges
+pub fn build_graph(vertices: Vec<Vertex>, edges: Vec<Edge>) -> Graph {
+    let mut adj = vec![CompactOrderedHashMap::empty(); vertices.len()];
+    let mut rev = vec![CompactOrderedHashMap::empty(); vertices.len()];
+    for edge in &edges {
+        adj[edge.src_vertex_id.0].insert(edge.edge_id, edge.dst_vertex_id);
+        rev[edge.dst_vertex_id.0].insert(edge.edge_id, edge.src_vertex_id);
+    }
+    Graph {
+        adj: adj.into_boxed_slice(),
+        rev: rev.into_boxed_slice(),
+        edges: edges.into_boxed_slice(),
+        vertices: vertices.into_boxed_slice(),
+    }
+}
+
+/// `n` vertices placed at (0,0), so that cost estimates are zero
+pub fn vertices_at_origin(n: usize) -> Vec<Vertex> {
+    (0..n).map(|i| Vertex::new(i, 0.0, 0.0)).collect()
+}
+
+/// a floating point custom state feature
+pub fn custom_feature(name: &str, unit: &str, initial: f64) -> (String, StateFeature) {
+    (
+        String::from(name),
+        StateFeature::Custom {
+            r#type: String::from(name),
+            unit: String::from(unit),
+            format: CustomFeatureFormat::FloatingPoint {
+                initial: OrderedFloat(initial),
+            },
+        },
+    )
+}
+
+/// the models of a test search instance. the state always has a distance feature
+/// in meters, which is the only feature with a cost. any custom features should
+/// be updated by the traversal model.
+pub struct TestSearchInstance {
+    pub traversal_model: Arc<dyn TraversalModel>,
+    pub custom_features: Vec<(String, StateFeature)>,
+    pub distance_weight: f64,
+    pub cost_aggregation: CostAggregation,
+}
+
+impl Default for TestSearchInstance {
+    fn default() -> Self {
+        Self {
+            traversal_model: Arc::new(DistanceTraversalModel::new(DistanceUnit::Meters)),
+            custom_features: vec![],
+            distance_weight: 1.0,
+            cost_aggregation: CostAggregation::Sum,
+        }
+    }
+}
+
+impl TestSearchInstance {
+    /// builds a search instance over the graph without restrictions, limited to 1000 iterations
+    pub fn build(self, graph: Graph) -> SearchInstance {
+        let graph = Arc::new(graph);
+        let map_model = Arc::new(MapModel::new(graph.clone(), MapModelConfig::default()).unwrap());
+        let distance = (
+            String::from("distance"),
+            StateFeature::Distance {
+                distance_unit: DistanceUnit::Meters,
+                initial: Distance::new(0.0),
+            },
+        );
+        let features = std::iter::once(distance)
+            .chain(self.custom_features)
+            .collect();
+        let state_model = Arc::new(StateModel::empty().extend(features).unwrap());
+        let cost_model = CostModel::new(
+            Arc::new(HashMap::from([(
+                String::from("distance"),
+                self.distance_weight,
+            )])),
+            Arc::new(HashMap::from([(
+                String::from("distance"),
+                VehicleCostRate::Raw,
+            )])),
+            Arc::new(HashMap::new()),
+            self.cost_aggregation,
+            state_model.clone(),
+        )
+        .unwrap();
+        SearchInstance {
+            graph,
+            map_model,
+            state_model,
+            traversal_model: self.traversal_model,
+            access_model: Arc::new(NoAccessModel {}),
+            cost_model: Arc::new(cost_model),
+            frontier_model: Arc::new(NoRestriction {}),
+            termination_model: Arc::new(TerminationModel::IterationsLimit { limit: 1000 }),
+            wrong_way_penalty: None,
+            prefer_fewer_turns: false,
+            reroute_hysteresis: None,
+            landmarks: None,
+        }
+    }
+}
+
+/// builds a search instance over the graph where the cost is the distance
+pub fn build_search_instance(graph: Graph) -> SearchInstance {
+    TestSearchInstance::default().build(graph)
+}
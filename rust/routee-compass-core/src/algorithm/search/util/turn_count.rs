@@ -0,0 +1,43 @@
+use crate::algorithm::search::SearchError;
+use crate::model::network::{EdgeId, Graph};
+use crate::util::geo::haversine;
+
+/// minimum change in heading, in degrees, between two consecutive edges
+/// for the transition to count as a turn
+pub const TURN_ANGLE_THRESHOLD_DEGREES: f32 = 30.0;
+
+/// tests whether moving between two consecutive edges is a turn. edge
+/// headings are taken as the bearing from the edge's source vertex to its
+/// destination vertex, so the result does not depend on the order of the
+/// two edges.
+///
+/// # Arguments
+///
+/// * `prev_edge_id` - the first edge
+/// * `next_edge_id` - the edge following the first edge
+/// * `graph` - the graph containing both edges
+///
+/// # Returns
+///
+/// true if the heading changes by more than [`TURN_ANGLE_THRESHOLD_DEGREES`]
+pub fn is_turn(
+    prev_edge_id: &EdgeId,
+    next_edge_id: &EdgeId,
+    graph: &Graph,
+) -> Result<bool, SearchError> {
+    let prev_bearing = edge_bearing(prev_edge_id, graph)?;
+    let next_bearing = edge_bearing(next_edge_id, graph)?;
+    let angle = (next_bearing - prev_bearing).rem_euclid(360.0);
+    let turn_angle = angle.min(360.0 - angle);
+    Ok(turn_angle > TURN_ANGLE_THRESHOLD_DEGREES)
+}
+
+fn edge_bearing(edge_id: &EdgeId, graph: &Graph) -> Result<f32, SearchError> {
+    let edge = graph.get_edge(edge_id)?;
+    let src = graph.get_vertex(&edge.src_vertex_id)?;
+    let dst = graph.get_vertex(&edge.dst_vertex_id)?;
+    Ok(haversine::coord_bearing_degrees(
+        &src.coordinate,
+        &dst.coordinate,
+    ))
+}
@@ -0,0 +1,34 @@
+use crate::algorithm::search::SearchError;
+use crate::model::network::{EdgeId, Graph, VertexId};
+
+/// derives the ordered sequence of vertices visited by a route from its
+/// edge sequence. the sequence begins at the source vertex of the first
+/// edge, followed by the destination vertex of each edge, so a route of
+/// n edges visits n + 1 vertices.
+///
+/// # Arguments
+///
+/// * `edge_ids` - the edges of the route, in order of traversal
+/// * `graph` - the graph containing the route
+///
+/// # Returns
+///
+/// the vertex sequence, empty for an empty route, or an error if an edge
+/// is missing or does not begin where the previous edge ends
+pub fn vertex_sequence(edge_ids: &[EdgeId], graph: &Graph) -> Result<Vec<VertexId>, SearchError> {
+    let mut result: Vec<VertexId> = Vec::with_capacity(edge_ids.len() + 1);
+    let mut prev_edge_id: Option<EdgeId> = None;
+    for edge_id in edge_ids.iter() {
+        let edge = graph.get_edge(edge_id)?;
+        match (prev_edge_id, result.last()) {
+            (Some(prev), Some(prev_dst)) if *prev_dst != edge.src_vertex_id => {
+                return Err(SearchError::RouteNotConnected(prev, *edge_id));
+            }
+            (None, _) => result.push(edge.src_vertex_id),
+            _ => {}
+        }
+        result.push(edge.dst_vertex_id);
+        prev_edge_id = Some(*edge_id);
+    }
+    Ok(result)
+}
@@ -11,6 +11,22 @@ pub trait AccessModel: Send + Sync {
     /// into the state model by listing them here.
     fn state_features(&self) -> Vec<(String, StateFeature)>;
 
+    /// lists the names of state features this access model reads or updates
+    /// without declaring them in [`AccessModel::state_features`]. each must be
+    /// provided by the state configuration or the traversal model, such as the
+    /// time feature that turn delays are added to.
+    fn input_features(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// true when accessing an edge may add to the cost of a route, such as a
+    /// turn delay. the cost of a turn depends on the previous edge, so searches
+    /// that tabulate costs by vertex, such as landmark lower bounds, only hold
+    /// when no access model adds costs.
+    fn adds_costs(&self) -> bool {
+        true
+    }
+
     /// Updates the traversal state by accessing some destination edge
     /// when coming from some previous edge.
     ///
@@ -4,24 +4,89 @@ use crate::model::{
     state::{StateFeature, StateModel, StateVariable},
 };
 use itertools::Itertools;
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 pub struct CombinedAccessModelService {
-    pub services: Vec<Arc<dyn AccessModelService>>,
+    /// each access model service along with the name used to refer to it
+    pub services: Vec<(String, Arc<dyn AccessModelService>)>,
+    /// for each access model name, the names of other access models it depends on
+    pub dependencies: HashMap<String, Vec<String>>,
 }
 
 pub struct CombinedAccessModel {
     pub models: Vec<Arc<dyn AccessModel>>,
+    /// models disabled for this query. their state features are retained so that
+    /// they remain in the state model, but they make no contribution to the state.
+    pub disabled_models: Vec<Arc<dyn AccessModel>>,
+}
+
+impl CombinedAccessModelService {
+    /// query key listing the names of access models to skip for a search
+    pub const DISABLE_MODELS: &'static str = "disable_models";
+
+    /// reads the set of disabled model names from the query, validating that
+    /// each name is known and that no enabled model depends on it.
+    fn disabled_models(
+        &self,
+        query: &serde_json::Value,
+    ) -> Result<HashSet<String>, AccessModelError> {
+        let disabled: HashSet<String> = match query.get(Self::DISABLE_MODELS) {
+            None => HashSet::new(),
+            Some(value) => serde_json::from_value(value.clone()).map_err(|e| {
+                AccessModelError::BuildError(format!(
+                    "expected '{}' to be a list of model names: {}",
+                    Self::DISABLE_MODELS,
+                    e
+                ))
+            })?,
+        };
+        for name in disabled.iter().sorted() {
+            if !self.services.iter().any(|(n, _)| n == name) {
+                return Err(AccessModelError::BuildError(format!(
+                    "cannot disable unknown access model '{}', should be one of: {{{}}}",
+                    name,
+                    self.services.iter().map(|(n, _)| n).join(",")
+                )));
+            }
+            let dependent = self.services.iter().find(|(n, _)| {
+                !disabled.contains(n)
+                    && self
+                        .dependencies
+                        .get(n)
+                        .map(|deps| deps.contains(name))
+                        .unwrap_or_default()
+            });
+            if let Some((dependent_name, _)) = dependent {
+                return Err(AccessModelError::BuildError(format!(
+                    "cannot disable access model '{}' because access model '{}' depends on it",
+                    name, dependent_name
+                )));
+            }
+        }
+        Ok(disabled)
+    }
 }
 
 impl AccessModelService for CombinedAccessModelService {
     fn build(&self, query: &serde_json::Value) -> Result<Arc<dyn AccessModel>, AccessModelError> {
-        let models = self
-            .services
-            .iter()
-            .map(|m| m.build(query))
-            .collect::<Result<_, _>>()?;
-        Ok(Arc::new(CombinedAccessModel { models }))
+        let disabled = self.disabled_models(query)?;
+        let mut models = vec![];
+        let mut disabled_models = vec![];
+        for (name, service) in self.services.iter() {
+            let model = service.build(query)?;
+            if disabled.contains(name) {
+                disabled_models.push(model);
+            } else {
+                models.push(model);
+            }
+        }
+        Ok(Arc::new(CombinedAccessModel {
+            models,
+            disabled_models,
+        }))
     }
 }
 
@@ -29,10 +94,23 @@ impl AccessModel for CombinedAccessModel {
     fn state_features(&self) -> Vec<(String, StateFeature)> {
         self.models
             .iter()
+            .chain(self.disabled_models.iter())
             .flat_map(|m| m.state_features())
             .collect_vec()
     }
 
+    fn input_features(&self) -> Vec<String> {
+        self.models
+            .iter()
+            .flat_map(|m| m.input_features())
+            .unique()
+            .collect_vec()
+    }
+
+    fn adds_costs(&self) -> bool {
+        self.models.iter().any(|m| m.adds_costs())
+    }
+
     fn access_edge(
         &self,
         traversal: (&Vertex, &Edge, &Vertex, &Edge, &Vertex),
@@ -45,3 +123,43 @@ impl AccessModel for CombinedAccessModel {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::access::default::NoAccessModel;
+    use serde_json::json;
+
+    fn mock_service() -> CombinedAccessModelService {
+        let no_access: Arc<dyn AccessModelService> = Arc::new(NoAccessModel {});
+        CombinedAccessModelService {
+            services: vec![
+                (String::from("turn_delay"), no_access.clone()),
+                (String::from("signal_delay"), no_access),
+            ],
+            dependencies: HashMap::from([(
+                String::from("signal_delay"),
+                vec![String::from("turn_delay")],
+            )]),
+        }
+    }
+
+    #[test]
+    fn test_disable_unknown_model() {
+        let service = mock_service();
+        let result = service.build(&json!({"disable_models": ["toll"]}));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_disable_model_with_dependent() {
+        let service = mock_service();
+        let result = service.build(&json!({"disable_models": ["turn_delay"]}));
+        let msg = result.err().map(|e| e.to_string()).unwrap_or_default();
+        assert!(msg.contains("'signal_delay' depends on it"), "{}", msg);
+
+        // disabling both the model and its dependent is allowed
+        let both = service.build(&json!({"disable_models": ["turn_delay", "signal_delay"]}));
+        assert!(both.is_ok());
+    }
+}
@@ -0,0 +1,44 @@
+use super::{
+    comfort_access_model_engine::ComfortAccessModelEngine, comfort_weights::ComfortWeights,
+};
+use crate::model::{
+    access::{AccessModel, AccessModelError},
+    network::{Edge, Vertex},
+    state::{CustomFeatureFormat, StateFeature, StateModel, StateVariable},
+};
+use std::sync::Arc;
+
+/// accumulates a comfort cost for each move between two edges, blending
+/// turn sharpness, stops and changes in speed. lower is smoother.
+pub struct ComfortAccessModel {
+    pub engine: Arc<ComfortAccessModelEngine>,
+    pub weights: ComfortWeights,
+}
+
+impl AccessModel for ComfortAccessModel {
+    fn access_edge(
+        &self,
+        traversal: (&Vertex, &Edge, &Vertex, &Edge, &Vertex),
+        state: &mut Vec<StateVariable>,
+        state_model: &StateModel,
+    ) -> Result<(), AccessModelError> {
+        let cost = self.engine.get_comfort_cost(traversal, &self.weights)?;
+        let name = &self.engine.comfort_feature_name;
+        let prev = state_model.get_custom_f64(state, name)?;
+        state_model.set_custom_f64(state, name, &(prev + cost))?;
+        Ok(())
+    }
+
+    fn state_features(&self) -> Vec<(String, StateFeature)> {
+        vec![(
+            self.engine.comfort_feature_name.clone(),
+            StateFeature::Custom {
+                r#type: String::from("comfort_cost"),
+                unit: String::from("comfort"),
+                format: CustomFeatureFormat::FloatingPoint {
+                    initial: 0.0.into(),
+                },
+            },
+        )]
+    }
+}
@@ -0,0 +1,119 @@
+use super::comfort_weights::ComfortWeights;
+use crate::model::access::AccessModelError;
+use crate::model::network::{Edge, EdgeId, Vertex};
+use crate::model::unit::{AsF64, Speed};
+use crate::util::geo::haversine;
+
+pub struct ComfortAccessModelEngine {
+    /// speed of each edge, used to find the magnitude of speed changes
+    pub speed_table: Option<Box<[Speed]>>,
+    /// for each vertex, whether traffic stops there, such as at a stop sign or signal
+    pub stop_table: Option<Box<[bool]>>,
+    /// default weights of the comfort cost components, which queries may override
+    pub weights: ComfortWeights,
+    pub comfort_feature_name: String,
+}
+
+impl ComfortAccessModelEngine {
+    /// the comfort cost of moving from the previous edge to the next edge. turn
+    /// sharpness is taken from the bearings between the three vertices, and the
+    /// stop and speed change components are zero when their tables are missing.
+    pub fn get_comfort_cost(
+        &self,
+        traversal: (&Vertex, &Edge, &Vertex, &Edge, &Vertex),
+        weights: &ComfortWeights,
+    ) -> Result<f64, AccessModelError> {
+        let (v1, src, v2, dst, v3) = traversal;
+        let arrival = haversine::coord_bearing_degrees(&v1.coordinate, &v2.coordinate);
+        let departure = haversine::coord_bearing_degrees(&v2.coordinate, &v3.coordinate);
+        let angle = (departure - arrival).rem_euclid(360.0);
+        let sharpness = angle.min(360.0 - angle) as f64 / 180.0;
+
+        let stop = match &self.stop_table {
+            None => false,
+            Some(table) => *get_row(table, v2.vertex_id.0, "stop", &v2.vertex_id)?,
+        };
+        let stop = if stop { 1.0 } else { 0.0 };
+
+        let speed_change = match &self.speed_table {
+            None => 0.0,
+            Some(table) => {
+                let src_speed = get_speed(table, src.edge_id)?;
+                let dst_speed = get_speed(table, dst.edge_id)?;
+                (dst_speed.as_f64() - src_speed.as_f64()).abs()
+            }
+        };
+
+        Ok(weights.turn * sharpness + weights.stop * stop + weights.speed_change * speed_change)
+    }
+}
+
+fn get_speed(table: &[Speed], edge_id: EdgeId) -> Result<Speed, AccessModelError> {
+    get_row(table, edge_id.as_usize(), "speed", &edge_id).cloned()
+}
+
+fn get_row<'a, T, I: std::fmt::Display>(
+    table: &'a [T],
+    index: usize,
+    table_name: &str,
+    id: &I,
+) -> Result<&'a T, AccessModelError> {
+    table
+        .get(index)
+        .ok_or_else(|| AccessModelError::RuntimeError {
+            name: String::from("comfort access model"),
+            error: format!("{} table missing entry for id {}", table_name, id),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comfort_cost() {
+        // a right turn at vertex 1 onto a slower edge, where traffic stops
+        let vertices = [
+            Vertex::new(0, 0.0, 0.0),
+            Vertex::new(1, 0.0, 0.01),
+            Vertex::new(2, 0.01, 0.01),
+        ];
+        let edges = [Edge::new(0, 0, 1, 1000.0), Edge::new(1, 1, 2, 1000.0)];
+        let engine = ComfortAccessModelEngine {
+            speed_table: Some(Box::new([Speed::new(50.0), Speed::new(30.0)])),
+            stop_table: Some(Box::new([false, true, false])),
+            weights: ComfortWeights::default(),
+            comfort_feature_name: String::from("comfort_cost"),
+        };
+        let traversal = (
+            &vertices[0],
+            &edges[0],
+            &vertices[1],
+            &edges[1],
+            &vertices[2],
+        );
+        let weights = ComfortWeights {
+            turn: 1.0,
+            stop: 2.0,
+            speed_change: 0.1,
+        };
+        let cost = engine.get_comfort_cost(traversal, &weights).unwrap();
+        // 0.5 for a 90 degree turn, 2.0 for the stop and 2.0 for the 20 kph change
+        assert!((cost - 4.5).abs() < 0.01, "{}", cost);
+
+        let straight = (
+            &vertices[0],
+            &edges[0],
+            &vertices[1],
+            &edges[0],
+            &Vertex::new(3, 0.0, 0.02),
+        );
+        let no_stops = ComfortAccessModelEngine {
+            speed_table: None,
+            stop_table: None,
+            ..engine
+        };
+        let cost = no_stops.get_comfort_cost(straight, &weights).unwrap();
+        assert!(cost.abs() < 1e-6, "{}", cost);
+    }
+}
@@ -0,0 +1,47 @@
+use super::{
+    comfort_access_model::ComfortAccessModel,
+    comfort_access_model_engine::ComfortAccessModelEngine, comfort_weights::ComfortWeights,
+};
+use crate::model::access::{AccessModel, AccessModelError, AccessModelService};
+use std::sync::Arc;
+
+/// query key for the weights of the comfort cost components, such as
+/// `{"turn": 2.0, "stop": 0.5}`. weights not listed keep their configured values.
+pub const COMFORT_WEIGHTS: &str = "comfort_weights";
+
+pub struct ComfortAccessModelService {
+    pub engine: Arc<ComfortAccessModelEngine>,
+}
+
+impl AccessModelService for ComfortAccessModelService {
+    fn build(&self, query: &serde_json::Value) -> Result<Arc<dyn AccessModel>, AccessModelError> {
+        let weights = match query.get(COMFORT_WEIGHTS) {
+            None => self.engine.weights.clone(),
+            Some(value) => {
+                let mut merged = serde_json::to_value(&self.engine.weights).map_err(|e| {
+                    AccessModelError::BuildError(format!("failed to serialize weights: {}", e))
+                })?;
+                let overrides = value.as_object().ok_or_else(|| {
+                    AccessModelError::BuildError(format!(
+                        "query field '{}' must be an object, found {}",
+                        COMFORT_WEIGHTS, value
+                    ))
+                })?;
+                for (k, v) in overrides.iter() {
+                    merged[k] = v.clone();
+                }
+                serde_json::from_value::<ComfortWeights>(merged).map_err(|e| {
+                    AccessModelError::BuildError(format!(
+                        "query field '{}' is invalid: {}",
+                        COMFORT_WEIGHTS, e
+                    ))
+                })?
+            }
+        };
+        let model = ComfortAccessModel {
+            engine: self.engine.clone(),
+            weights,
+        };
+        Ok(Arc::new(model))
+    }
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// weights of each component of the comfort cost of moving between two edges.
+/// the comfort cost is the weighted sum of the turn sharpness, from 0 for
+/// continuing straight to 1 for a u-turn, a stop at the shared vertex, and the
+/// magnitude of the change in speed, in the units of the speed table.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ComfortWeights {
+    #[serde(default = "default_turn")]
+    pub turn: f64,
+    #[serde(default = "default_stop")]
+    pub stop: f64,
+    #[serde(default = "default_speed_change")]
+    pub speed_change: f64,
+}
+
+fn default_turn() -> f64 {
+    1.0
+}
+
+fn default_stop() -> f64 {
+    1.0
+}
+
+/// a change of 20 speed units, such as 20 kph, weighs the same as one stop
+fn default_speed_change() -> f64 {
+    0.05
+}
+
+impl Default for ComfortWeights {
+    fn default() -> Self {
+        ComfortWeights {
+            turn: default_turn(),
+            stop: default_stop(),
+            speed_change: default_speed_change(),
+        }
+    }
+}
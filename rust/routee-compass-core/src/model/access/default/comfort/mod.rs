@@ -0,0 +1,9 @@
+mod comfort_access_model;
+mod comfort_access_model_engine;
+mod comfort_access_model_service;
+mod comfort_weights;
+
+pub use comfort_access_model::ComfortAccessModel;
+pub use comfort_access_model_engine::ComfortAccessModelEngine;
+pub use comfort_access_model_service::ComfortAccessModelService;
+pub use comfort_weights::ComfortWeights;
@@ -1,4 +1,5 @@
 mod combined_model;
+pub mod comfort;
 mod no_access_model;
 pub mod turn_delays;
 
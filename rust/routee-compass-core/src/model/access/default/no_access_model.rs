@@ -12,6 +12,10 @@ impl AccessModel for NoAccessModel {
         vec![]
     }
 
+    fn adds_costs(&self) -> bool {
+        false
+    }
+
     fn access_edge(
         &self,
         _: (&Vertex, &Edge, &Vertex, &Edge, &Vertex),
@@ -1,3 +1,5 @@
+use crate::util::geo::haversine;
+use geo::LineString;
 use serde::Deserialize;
 
 /// simplifies the representation of directionality for a linestring
@@ -18,6 +20,19 @@ impl EdgeHeading {
         }
     }
 
+    /// creates an EdgeHeading from the bearings of the first and last segments
+    /// of an edge geometry. returns None if the geometry has fewer than two points.
+    pub fn from_linestring(linestring: &LineString<f32>) -> Option<Self> {
+        let first = linestring.lines().next()?;
+        let last = linestring.lines().last()?;
+        let start = haversine::coord_bearing_degrees(&first.start, &first.end);
+        let end = haversine::coord_bearing_degrees(&last.start, &last.end);
+        Some(Self::new(
+            start.round() as i16 % 360,
+            end.round() as i16 % 360,
+        ))
+    }
+
     /// retrieve the start
     pub fn start_heading(&self) -> i16 {
         self.arrival_heading
@@ -43,6 +58,16 @@ impl EdgeHeading {
     }
 }
 
+/// the absolute difference between two headings, in degrees in the range [0, 180]
+pub fn heading_difference(a: f64, b: f64) -> f64 {
+    let diff = (a - b).rem_euclid(360.0);
+    if diff > 180.0 {
+        360.0 - diff
+    } else {
+        diff
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -64,4 +89,22 @@ mod test {
         let next_edge_heading = EdgeHeading::new(10, 10);
         assert_eq!(edge_heading.bearing_to_destination(&next_edge_heading), 20);
     }
+
+    #[test]
+    fn test_from_linestring() {
+        // east, then north
+        let linestring = LineString::from(vec![(0.0, 0.0), (0.001, 0.0), (0.001, 0.001)]);
+        let edge_heading = EdgeHeading::from_linestring(&linestring).unwrap();
+        assert_eq!(edge_heading.start_heading(), 90);
+        assert_eq!(edge_heading.end_heading(), 0);
+        assert!(EdgeHeading::from_linestring(&LineString::new(vec![])).is_none());
+    }
+
+    #[test]
+    fn test_heading_difference() {
+        assert_eq!(heading_difference(10.0, 350.0), 20.0);
+        assert_eq!(heading_difference(350.0, 10.0), 20.0);
+        assert_eq!(heading_difference(90.0, 270.0), 180.0);
+        assert_eq!(heading_difference(45.0, 45.0), 0.0);
+    }
 }
@@ -1,13 +1,17 @@
 mod edge_heading;
+mod movement_delay;
 mod turn;
 mod turn_delay_access_model;
 mod turn_delay_access_model_engine;
 mod turn_delay_access_model_service;
 mod turn_delay_model;
+mod turn_penalties;
 
-pub use edge_heading::EdgeHeading;
+pub use edge_heading::{heading_difference, EdgeHeading};
+pub use movement_delay::MovementDelay;
 pub use turn::Turn;
 pub use turn_delay_access_model::TurnDelayAccessModel;
 pub use turn_delay_access_model_engine::TurnDelayAccessModelEngine;
 pub use turn_delay_access_model_service::TurnDelayAccessModelService;
 pub use turn_delay_model::TurnDelayModel;
+pub use turn_penalties::{TurnPenalties, LEFT_TURNS_FEATURE, TURN_PENALTY_FEATURE};
@@ -0,0 +1,14 @@
+use crate::model::network::EdgeId;
+use crate::model::unit::Time;
+use serde::Deserialize;
+
+/// a measured delay for a single intersection movement, from one edge onto the
+/// next. a row of a movement delay file, which is a CSV with header
+/// `from_edge_id,to_edge_id,delay`, with delays in the time unit of the turn
+/// delay model.
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct MovementDelay {
+    pub from_edge_id: EdgeId,
+    pub to_edge_id: EdgeId,
+    pub delay: Time,
+}
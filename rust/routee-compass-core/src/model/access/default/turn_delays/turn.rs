@@ -23,6 +23,17 @@ impl Display for Turn {
 }
 
 impl Turn {
+    /// true for turns to the left that are sharper than a slight left. in right-hand
+    /// traffic these turns cross oncoming traffic.
+    pub fn is_left(&self) -> bool {
+        matches!(self, Turn::Left | Turn::SharpLeft)
+    }
+
+    /// true for turns to the right that are sharper than a slight right
+    pub fn is_right(&self) -> bool {
+        matches!(self, Turn::Right | Turn::SharpRight)
+    }
+
     pub fn from_angle(angle: i16) -> Result<Self, AccessModelError> {
         match angle {
             -180..=-160 => Ok(Turn::UTurn),
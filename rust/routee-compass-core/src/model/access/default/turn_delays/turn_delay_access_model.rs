@@ -1,8 +1,9 @@
 use super::turn_delay_access_model_engine::TurnDelayAccessModelEngine;
+use super::turn_penalties::{LEFT_TURNS_FEATURE, TURN_PENALTY_FEATURE};
 use crate::model::{
     access::{AccessModel, AccessModelError},
     network::{Edge, Vertex},
-    state::{StateFeature, StateModel, StateVariable},
+    state::{CustomFeatureFormat, StateFeature, StateModel, StateVariable},
 };
 use std::sync::Arc;
 
@@ -19,10 +20,49 @@ impl AccessModel for TurnDelayAccessModel {
     ) -> Result<(), AccessModelError> {
         let (delay, delay_unit) = self.engine.get_delay(traversal)?;
         state_model.add_time(state, &self.engine.time_feature_name, &delay, delay_unit)?;
+
+        if let Some(penalties) = &self.engine.turn_penalties {
+            let turn = self.engine.get_turn(traversal)?;
+            let penalty_name = String::from(TURN_PENALTY_FEATURE);
+            let penalty = state_model.get_custom_f64(state, &penalty_name)?;
+            let next_penalty = penalty + penalties.get_penalty(&turn);
+            state_model.set_custom_f64(state, &penalty_name, &next_penalty)?;
+            if turn.is_left() {
+                let left_turns_name = String::from(LEFT_TURNS_FEATURE);
+                let left_turns = state_model.get_custom_u64(state, &left_turns_name)?;
+                state_model.set_custom_u64(state, &left_turns_name, &(left_turns + 1))?;
+            }
+        }
         Ok(())
     }
 
     fn state_features(&self) -> Vec<(String, StateFeature)> {
-        vec![]
+        if self.engine.turn_penalties.is_none() {
+            return vec![];
+        }
+        vec![
+            (
+                String::from(TURN_PENALTY_FEATURE),
+                StateFeature::Custom {
+                    r#type: String::from("turn_penalty"),
+                    unit: String::from("penalty"),
+                    format: CustomFeatureFormat::FloatingPoint {
+                        initial: 0.0.into(),
+                    },
+                },
+            ),
+            (
+                String::from(LEFT_TURNS_FEATURE),
+                StateFeature::Custom {
+                    r#type: String::from("turn_count"),
+                    unit: String::from("turns"),
+                    format: CustomFeatureFormat::UnsignedInteger { initial: 0 },
+                },
+            ),
+        ]
+    }
+
+    fn input_features(&self) -> Vec<String> {
+        vec![self.engine.time_feature_name.clone()]
     }
 }
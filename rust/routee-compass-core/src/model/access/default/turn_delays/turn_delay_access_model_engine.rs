@@ -1,28 +1,40 @@
 use super::edge_heading::EdgeHeading;
 use super::turn::Turn;
 use super::turn_delay_model::TurnDelayModel;
+use super::turn_penalties::TurnPenalties;
 use crate::model::access::AccessModelError;
 use crate::model::network::{Edge, EdgeId, Vertex};
 use crate::model::unit::{Time, TimeUnit};
+use std::collections::HashMap;
 
 pub struct TurnDelayAccessModelEngine {
     pub edge_headings: Box<[EdgeHeading]>,
     pub turn_delay_model: TurnDelayModel,
     pub time_feature_name: String,
+    /// if set, penalizes left and right turns and counts left turns
+    pub turn_penalties: Option<TurnPenalties>,
+    /// if set, measured delays of specific movements from one edge onto the
+    /// next, in the time unit of the turn delay model, which replace the
+    /// delay of the turn classified by angle
+    pub movement_delays: Option<HashMap<(EdgeId, EdgeId), Time>>,
 }
 
 impl TurnDelayAccessModelEngine {
+    /// finds the delay of moving from the previous edge onto the next edge. a
+    /// movement listed in the movement delays takes its measured delay, and
+    /// any other movement takes the delay of its turn.
     pub fn get_delay<'a>(
         &'a self,
         traversal: (&Vertex, &Edge, &Vertex, &Edge, &Vertex),
     ) -> Result<(Time, &'a TimeUnit), AccessModelError> {
         let (_v1, src, _v2, dst, _v3) = traversal;
-        let src_heading = get_headings(&self.edge_headings, src.edge_id)?;
-        let dst_heading = get_headings(&self.edge_headings, dst.edge_id)?;
-        let angle = src_heading.bearing_to_destination(&dst_heading);
+        let movement = (src.edge_id, dst.edge_id);
         match &self.turn_delay_model {
             TurnDelayModel::TabularDiscrete { table, time_unit } => {
-                let turn = Turn::from_angle(angle)?;
+                if let Some(delay) = self.movement_delays.as_ref().and_then(|d| d.get(&movement)) {
+                    return Ok((*delay, time_unit));
+                }
+                let turn = self.get_turn(traversal)?;
                 let delay = table.get(&turn).ok_or_else(|| {
                     let name = String::from("tabular discrete turn delay model");
                     let error = format!("table missing entry for turn {}", turn);
@@ -32,6 +44,18 @@ impl TurnDelayAccessModelEngine {
             } // TurnDelayModel::TabularDiscreteWithRoadClasses { table, time_unit } => {}
         }
     }
+
+    /// classifies the turn from the previous edge to the next edge by their headings
+    pub fn get_turn(
+        &self,
+        traversal: (&Vertex, &Edge, &Vertex, &Edge, &Vertex),
+    ) -> Result<Turn, AccessModelError> {
+        let (_v1, src, _v2, dst, _v3) = traversal;
+        let src_heading = get_headings(&self.edge_headings, src.edge_id)?;
+        let dst_heading = get_headings(&self.edge_headings, dst.edge_id)?;
+        let angle = src_heading.bearing_to_destination(&dst_heading);
+        Turn::from_angle(angle)
+    }
 }
 
 /// lookup up the edge heading from the headings table
@@ -48,3 +72,55 @@ pub fn get_headings(
             })?;
     Ok(*heading)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_movement_delay_overrides_turn_delay() {
+        // edge 0 heads north into vertex 1, where edge 1 turns left and edge 2 turns right
+        let vertices = [
+            Vertex::new(0, 0.0, 0.0),
+            Vertex::new(1, 0.0, 0.01),
+            Vertex::new(2, -0.01, 0.01),
+            Vertex::new(3, 0.01, 0.01),
+        ];
+        let edges = [
+            Edge::new(0, 0, 1, 1000.0),
+            Edge::new(1, 1, 2, 1000.0),
+            Edge::new(2, 1, 3, 1000.0),
+        ];
+        let engine = TurnDelayAccessModelEngine {
+            edge_headings: Box::new([
+                EdgeHeading::new(0, 0),
+                EdgeHeading::new(270, 270),
+                EdgeHeading::new(90, 90),
+            ]),
+            turn_delay_model: TurnDelayModel::TabularDiscrete {
+                table: HashMap::from([
+                    (Turn::Left, Time::new(30.0)),
+                    (Turn::Right, Time::new(5.0)),
+                ]),
+                time_unit: TimeUnit::Seconds,
+            },
+            time_feature_name: String::from("time"),
+            turn_penalties: None,
+            movement_delays: Some(HashMap::from([((EdgeId(0), EdgeId(1)), Time::new(75.0))])),
+        };
+        let delay = |dst: usize| {
+            let traversal = (
+                &vertices[0],
+                &edges[0],
+                &vertices[1],
+                &edges[dst],
+                &vertices[dst + 1],
+            );
+            engine.get_delay(traversal).unwrap().0
+        };
+        // the left turn onto edge 1 is a listed movement with a measured delay
+        assert_eq!(delay(1), Time::new(75.0));
+        // the unlisted right turn onto edge 2 takes the delay of a right turn
+        assert_eq!(delay(2), Time::new(5.0));
+    }
+}
@@ -0,0 +1,57 @@
+use super::turn::Turn;
+use serde::{Deserialize, Serialize};
+
+/// name of the state feature accumulating turn penalties
+pub const TURN_PENALTY_FEATURE: &str = "turn_penalty";
+
+/// name of the state feature counting left turns
+pub const LEFT_TURNS_FEATURE: &str = "left_turns";
+
+/// cost penalties for left and right turns, kept apart from turn delays so that
+/// routes can avoid turns across traffic without changing the modeled trip time.
+/// the penalties accumulate in the `turn_penalty` state feature, which the cost
+/// model weighs like any other feature.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TurnPenalties {
+    #[serde(default)]
+    pub left: f64,
+    #[serde(default)]
+    pub right: f64,
+}
+
+impl TurnPenalties {
+    /// the penalty for a turn, which is zero for slight turns, u-turns and no turn
+    pub fn get_penalty(&self, turn: &Turn) -> f64 {
+        if turn.is_left() {
+            self.left
+        } else if turn.is_right() {
+            self.right
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_penalties_are_asymmetric() {
+        let penalties = TurnPenalties {
+            left: 30.0,
+            right: 5.0,
+        };
+        let left = Turn::from_angle(-90).unwrap();
+        let right = Turn::from_angle(90).unwrap();
+        assert_eq!(penalties.get_penalty(&left), 30.0);
+        assert_eq!(penalties.get_penalty(&right), 5.0);
+        assert_eq!(
+            penalties.get_penalty(&Turn::from_angle(-150).unwrap()),
+            30.0
+        );
+        assert_eq!(penalties.get_penalty(&Turn::from_angle(-30).unwrap()), 0.0);
+        assert_eq!(penalties.get_penalty(&Turn::from_angle(180).unwrap()), 0.0);
+    }
+}
@@ -0,0 +1,361 @@
+use super::CostModelError;
+use crate::model::state::{StateModel, StateVariable};
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// an arithmetic expression over state feature names which computes the cost
+/// of a state transition, such as `0.5 * time + 2 * energy`. each feature name
+/// evaluates to the change in that feature across the transition, the same
+/// value that is mapped by a [`super::VehicleCostRate`]. supports numbers,
+/// feature names, `+`, `-`, `*`, `/`, unary minus and parentheses.
+///
+/// an expression is a generalization of the linear cost model, where the
+/// weighted sum `w1 * r1(x1) + w2 * r2(x2)` is replaced by any expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CostExpression {
+    Constant(f64),
+    /// a state feature, by name, as parsed
+    Feature(String),
+    /// a state feature, by name and resolved index in the state vector
+    StateIndex(String, usize),
+    Add(Box<CostExpression>, Box<CostExpression>),
+    Sub(Box<CostExpression>, Box<CostExpression>),
+    Mul(Box<CostExpression>, Box<CostExpression>),
+    Div(Box<CostExpression>, Box<CostExpression>),
+    Neg(Box<CostExpression>),
+}
+
+impl CostExpression {
+    /// the names of the state features referenced by this expression
+    pub fn feature_names(&self) -> BTreeSet<&str> {
+        use CostExpression as E;
+        match self {
+            E::Constant(_) => BTreeSet::new(),
+            E::Feature(name) | E::StateIndex(name, _) => BTreeSet::from([name.as_str()]),
+            E::Add(a, b) | E::Sub(a, b) | E::Mul(a, b) | E::Div(a, b) => {
+                let mut names = a.feature_names();
+                names.extend(b.feature_names());
+                names
+            }
+            E::Neg(a) => a.feature_names(),
+        }
+    }
+
+    /// tests whether this expression can be shown to be non-negative for any
+    /// non-negative feature values. search algorithms such as a-star require
+    /// that costs never decrease, so expressions containing a subtraction,
+    /// negation or negative constant are rejected, as is division by anything
+    /// but a positive constant.
+    pub fn is_non_negative(&self) -> bool {
+        use CostExpression as E;
+        match self {
+            E::Constant(c) => *c >= 0.0,
+            E::Feature(_) | E::StateIndex(_, _) => true,
+            E::Add(a, b) | E::Mul(a, b) => a.is_non_negative() && b.is_non_negative(),
+            E::Div(a, b) => a.is_non_negative() && matches!(**b, E::Constant(c) if c > 0.0),
+            E::Sub(_, _) | E::Neg(_) => false,
+        }
+    }
+
+    /// resolves each feature name to its index in the state vector, and checks
+    /// that the expression is non-negative.
+    ///
+    /// # Arguments
+    ///
+    /// * `state_model` - the state model of the search
+    ///
+    /// # Returns
+    ///
+    /// a copy of this expression that can be evaluated, or an error if a feature
+    /// is unknown to the state model or the expression may be negative
+    pub fn resolve(&self, state_model: &StateModel) -> Result<CostExpression, CostModelError> {
+        if !self.is_non_negative() {
+            return Err(CostModelError::InvalidConfiguration(format!(
+                "cost expression '{}' may be negative, which is not supported by the search algorithms. use only non-negative constants, '+', '*' and division by positive constants",
+                self
+            )));
+        }
+        self.resolve_indices(state_model)
+    }
+
+    fn resolve_indices(&self, state_model: &StateModel) -> Result<CostExpression, CostModelError> {
+        use CostExpression as E;
+        let resolve = |e: &CostExpression| e.resolve_indices(state_model).map(Box::new);
+        match self {
+            E::Constant(c) => Ok(E::Constant(*c)),
+            E::Feature(name) | E::StateIndex(name, _) => {
+                let index = state_model
+                    .indexed_iter()
+                    .find(|(_, (n, _))| *n == name)
+                    .map(|(index, _)| index)
+                    .ok_or_else(|| {
+                        CostModelError::StateVariableNotFound(
+                            name.clone(),
+                            String::from("cost expression"),
+                            state_model.get_names(),
+                        )
+                    })?;
+                Ok(E::StateIndex(name.clone(), index))
+            }
+            E::Add(a, b) => Ok(E::Add(resolve(a)?, resolve(b)?)),
+            E::Sub(a, b) => Ok(E::Sub(resolve(a)?, resolve(b)?)),
+            E::Mul(a, b) => Ok(E::Mul(resolve(a)?, resolve(b)?)),
+            E::Div(a, b) => Ok(E::Div(resolve(a)?, resolve(b)?)),
+            E::Neg(a) => Ok(E::Neg(resolve(a)?)),
+        }
+    }
+
+    /// evaluates the expression for a state transition. the expression must
+    /// first be resolved against the state model, see [`CostExpression::resolve`].
+    ///
+    /// # Arguments
+    ///
+    /// * `prev_state` - state before the transition
+    /// * `next_state` - state after the transition
+    ///
+    /// # Returns
+    ///
+    /// the value of the expression, or an error if a feature is unresolved
+    pub fn evaluate(
+        &self,
+        prev_state: &[StateVariable],
+        next_state: &[StateVariable],
+    ) -> Result<f64, CostModelError> {
+        use CostExpression as E;
+        let eval = |e: &CostExpression| e.evaluate(prev_state, next_state);
+        match self {
+            E::Constant(c) => Ok(*c),
+            E::Feature(name) => Err(CostModelError::BuildError(format!(
+                "cost expression feature {} was not resolved against the state model",
+                name
+            ))),
+            E::StateIndex(name, index) => {
+                let prev = prev_state
+                    .get(*index)
+                    .ok_or_else(|| CostModelError::StateIndexOutOfBounds(*index, name.clone()))?;
+                let next = next_state
+                    .get(*index)
+                    .ok_or_else(|| CostModelError::StateIndexOutOfBounds(*index, name.clone()))?;
+                Ok(next.0 - prev.0)
+            }
+            E::Add(a, b) => Ok(eval(a)? + eval(b)?),
+            E::Sub(a, b) => Ok(eval(a)? - eval(b)?),
+            E::Mul(a, b) => Ok(eval(a)? * eval(b)?),
+            E::Div(a, b) => Ok(eval(a)? / eval(b)?),
+            E::Neg(a) => Ok(-eval(a)?),
+        }
+    }
+}
+
+impl Display for CostExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use CostExpression as E;
+        match self {
+            E::Constant(c) => write!(f, "{}", c),
+            E::Feature(name) | E::StateIndex(name, _) => write!(f, "{}", name),
+            E::Add(a, b) => write!(f, "({} + {})", a, b),
+            E::Sub(a, b) => write!(f, "({} - {})", a, b),
+            E::Mul(a, b) => write!(f, "({} * {})", a, b),
+            E::Div(a, b) => write!(f, "({} / {})", a, b),
+            E::Neg(a) => write!(f, "-{}", a),
+        }
+    }
+}
+
+impl FromStr for CostExpression {
+    type Err = CostModelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expression = parser.expression()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(expression),
+            Some(token) => Err(CostModelError::InvalidConfiguration(format!(
+                "unexpected '{}' in cost expression '{}'",
+                token, s
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Op(char),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Name(name) => write!(f, "{}", name),
+            Token::Op(op) => write!(f, "{}", op),
+        }
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, CostModelError> {
+    let mut tokens = vec![];
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.peek().cloned() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+            while let Some(d) = chars.peek().filter(|d| d.is_ascii_digit() || **d == '.') {
+                number.push(*d);
+                chars.next();
+            }
+            let value = number.parse::<f64>().map_err(|e| {
+                CostModelError::InvalidConfiguration(format!(
+                    "invalid number '{}' in cost expression '{}': {}",
+                    number, s, e
+                ))
+            })?;
+            tokens.push(Token::Number(value));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut name = String::new();
+            while let Some(d) = chars.peek().filter(|d| d.is_alphanumeric() || **d == '_') {
+                name.push(*d);
+                chars.next();
+            }
+            tokens.push(Token::Name(name));
+        } else if "+-*/()".contains(c) {
+            tokens.push(Token::Op(c));
+            chars.next();
+        } else {
+            return Err(CostModelError::InvalidConfiguration(format!(
+                "unexpected character '{}' in cost expression '{}'",
+                c, s
+            )));
+        }
+    }
+    Ok(tokens)
+}
+
+/// recursive descent parser with the usual precedence, where `*` and `/`
+/// bind tighter than `+` and `-`, and operators are left-associative.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next_op(&mut self, ops: &str) -> Option<char> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) if ops.contains(*op) => {
+                self.pos += 1;
+                Some(*op)
+            }
+            _ => None,
+        }
+    }
+
+    fn expression(&mut self) -> Result<CostExpression, CostModelError> {
+        let mut lhs = self.term()?;
+        while let Some(op) = self.next_op("+-") {
+            let rhs = self.term()?;
+            lhs = match op {
+                '+' => CostExpression::Add(Box::new(lhs), Box::new(rhs)),
+                _ => CostExpression::Sub(Box::new(lhs), Box::new(rhs)),
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<CostExpression, CostModelError> {
+        let mut lhs = self.factor()?;
+        while let Some(op) = self.next_op("*/") {
+            let rhs = self.factor()?;
+            lhs = match op {
+                '*' => CostExpression::Mul(Box::new(lhs), Box::new(rhs)),
+                _ => CostExpression::Div(Box::new(lhs), Box::new(rhs)),
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn factor(&mut self) -> Result<CostExpression, CostModelError> {
+        if self.next_op("-").is_some() {
+            let inner = self.factor()?;
+            return Ok(CostExpression::Neg(Box::new(inner)));
+        }
+        if self.next_op("(").is_some() {
+            let inner = self.expression()?;
+            return match self.next_op(")") {
+                Some(_) => Ok(inner),
+                None => Err(CostModelError::InvalidConfiguration(String::from(
+                    "cost expression is missing a closing parenthesis",
+                ))),
+            };
+        }
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        match token {
+            Some(Token::Number(n)) => Ok(CostExpression::Constant(n)),
+            Some(Token::Name(name)) => Ok(CostExpression::Feature(name)),
+            Some(other) => Err(CostModelError::InvalidConfiguration(format!(
+                "unexpected '{}' in cost expression",
+                other
+            ))),
+            None => Err(CostModelError::InvalidConfiguration(String::from(
+                "cost expression ended unexpectedly",
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_precedence() {
+        let expression = CostExpression::from_str("0.5*time + 2 * (energy + 1) / 4").unwrap();
+        assert_eq!(
+            expression.to_string(),
+            "((0.5 * time) + ((2 * (energy + 1)) / 4))"
+        );
+        assert_eq!(
+            expression.feature_names(),
+            BTreeSet::from(["energy", "time"])
+        );
+        assert!(expression.is_non_negative());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(CostExpression::from_str("time +").is_err());
+        assert!(CostExpression::from_str("(time + 1").is_err());
+        assert!(CostExpression::from_str("time $ 2").is_err());
+        assert!(CostExpression::from_str("time distance").is_err());
+    }
+
+    #[test]
+    fn test_non_negative() {
+        let negative = ["time - distance", "-time", "-1 * time", "time / distance"];
+        for s in negative {
+            let expression = CostExpression::from_str(s).unwrap();
+            assert!(!expression.is_non_negative(), "{} may be negative", s);
+        }
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let expression = CostExpression::Add(
+            Box::new(CostExpression::Mul(
+                Box::new(CostExpression::Constant(0.5)),
+                Box::new(CostExpression::StateIndex(String::from("time"), 0)),
+            )),
+            Box::new(CostExpression::StateIndex(String::from("energy"), 1)),
+        );
+        let prev = [StateVariable(10.0), StateVariable(1.0)];
+        let next = [StateVariable(14.0), StateVariable(4.0)];
+        assert_eq!(expression.evaluate(&prev, &next).unwrap(), 5.0);
+        let unresolved = CostExpression::from_str("time").unwrap();
+        assert!(unresolved.evaluate(&prev, &next).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use super::{cost_ops, network::NetworkCostRate, CostAggregation, VehicleCostRate};
+use super::{cost_ops, network::NetworkCostRate, CostAggregation, CostExpression, VehicleCostRate};
 use crate::model::cost::CostModelError;
 use crate::model::network::Edge;
 use crate::model::state::StateModel;
@@ -17,6 +17,7 @@ pub struct CostModel {
     vehicle_rates: Vec<VehicleCostRate>,
     network_rates: Vec<NetworkCostRate>,
     cost_aggregation: CostAggregation,
+    expression: Option<CostExpression>,
 }
 
 impl CostModel {
@@ -29,6 +30,7 @@ impl CostModel {
     const FEATURE: &'static str = "feature";
     const WEIGHT: &'static str = "weight";
     const COST_AGGREGATION: &'static str = "cost_aggregation";
+    const EXPRESSION: &'static str = "expression";
 
     /// builds a cost model for a specific query.
     ///
@@ -50,6 +52,55 @@ impl CostModel {
         cost_aggregation: CostAggregation,
         state_model: Arc<StateModel>,
     ) -> Result<CostModel, CostModelError> {
+        let model = CostModel::vectorize(
+            weights_mapping,
+            vehicle_rate_mapping,
+            network_rate_mapping,
+            cost_aggregation,
+            state_model,
+        );
+        if model.weights.iter().sum::<f64>() == 0.0 {
+            return Err(CostModelError::InvalidCostVariables);
+        }
+        Ok(model)
+    }
+
+    /// builds a cost model where the vehicle cost of each state transition is
+    /// computed by an expression over the state features, in place of the
+    /// weighted vehicle rates. network rates are still applied using the weights.
+    ///
+    /// # Arguments
+    /// * `expression`           - cost expression, validated against the state model
+    /// * `weights`              - weighting factors for the network rates of each feature
+    /// * `network_rate_mapping` - for each feature name, a network cost rate for that feature
+    /// * `cost_aggregation`     - function for aggregating each network feature cost
+    /// * `state_model`          - state model instance for this search
+    pub fn from_expression(
+        expression: &CostExpression,
+        weights_mapping: Arc<HashMap<String, f64>>,
+        network_rate_mapping: Arc<HashMap<String, NetworkCostRate>>,
+        cost_aggregation: CostAggregation,
+        state_model: Arc<StateModel>,
+    ) -> Result<CostModel, CostModelError> {
+        let resolved = expression.resolve(&state_model)?;
+        let mut model = CostModel::vectorize(
+            weights_mapping,
+            Arc::new(HashMap::new()),
+            network_rate_mapping,
+            cost_aggregation,
+            state_model,
+        );
+        model.expression = Some(resolved);
+        Ok(model)
+    }
+
+    fn vectorize(
+        weights_mapping: Arc<HashMap<String, f64>>,
+        vehicle_rate_mapping: Arc<HashMap<String, VehicleCostRate>>,
+        network_rate_mapping: Arc<HashMap<String, NetworkCostRate>>,
+        cost_aggregation: CostAggregation,
+        state_model: Arc<StateModel>,
+    ) -> CostModel {
         let mut indices = vec![];
         let mut weights = vec![];
         let mut vehicle_rates = vec![];
@@ -71,16 +122,33 @@ impl CostModel {
             network_rates.push(n_rate.clone());
         }
 
-        if weights.iter().sum::<f64>() == 0.0 {
-            return Err(CostModelError::InvalidCostVariables);
-        }
-        Ok(CostModel {
+        CostModel {
             feature_indices: indices,
             weights,
             vehicle_rates,
             network_rates,
             cost_aggregation,
-        })
+            expression: None,
+        }
+    }
+
+    /// the vehicle cost of a state transition, from the cost expression if
+    /// one is configured, otherwise from the weighted vehicle rates
+    fn vehicle_cost(
+        &self,
+        prev_state: &[StateVariable],
+        next_state: &[StateVariable],
+    ) -> Result<Cost, CostModelError> {
+        match &self.expression {
+            Some(expression) => expression.evaluate(prev_state, next_state).map(Cost::new),
+            None => cost_ops::calculate_vehicle_costs(
+                (prev_state, next_state),
+                &self.feature_indices,
+                &self.weights,
+                &self.vehicle_rates,
+                &self.cost_aggregation,
+            ),
+        }
     }
 
     /// Calculates the cost of traversing an edge due to some state transition.
@@ -100,13 +168,7 @@ impl CostModel {
         prev_state: &[StateVariable],
         next_state: &[StateVariable],
     ) -> Result<Cost, CostModelError> {
-        let vehicle_cost = cost_ops::calculate_vehicle_costs(
-            (prev_state, next_state),
-            &self.feature_indices,
-            &self.weights,
-            &self.vehicle_rates,
-            &self.cost_aggregation,
-        )?;
+        let vehicle_cost = self.vehicle_cost(prev_state, next_state)?;
         let network_cost = cost_ops::calculate_network_traversal_costs(
             (prev_state, next_state),
             edge,
@@ -144,13 +206,7 @@ impl CostModel {
         prev_state: &[StateVariable],
         next_state: &[StateVariable],
     ) -> Result<Cost, CostModelError> {
-        let vehicle_cost = cost_ops::calculate_vehicle_costs(
-            (prev_state, next_state),
-            &self.feature_indices,
-            &self.weights,
-            &self.vehicle_rates,
-            &self.cost_aggregation,
-        )?;
+        let vehicle_cost = self.vehicle_cost(prev_state, next_state)?;
         let network_cost = cost_ops::calculate_network_access_costs(
             (prev_state, next_state),
             (prev_edge, next_edge),
@@ -182,22 +238,24 @@ impl CostModel {
         src_state: &[StateVariable],
         dst_state: &[StateVariable],
     ) -> Result<Cost, CostModelError> {
-        let vehicle_cost = cost_ops::calculate_vehicle_costs(
-            (src_state, dst_state),
-            &self.feature_indices,
-            &self.weights,
-            &self.vehicle_rates,
-            &self.cost_aggregation,
-        )?;
+        let vehicle_cost = self.vehicle_cost(src_state, dst_state)?;
         let pos_cost = Cost::enforce_non_negative(vehicle_cost);
         Ok(pos_cost)
     }
 
+    /// true when this model sums the cost of each feature, without a cost
+    /// expression. searches that combine partial route costs, such as a
+    /// bidirectional search, rely on feature costs adding up.
+    pub fn is_additive(&self) -> bool {
+        self.expression.is_none() && matches!(self.cost_aggregation, CostAggregation::Sum)
+    }
+
     /// Serializes the cost of a traversal state into a JSON value.
     ///
     /// # Arguments
     ///
     /// * `state` - the state to serialize
+    /// * `route_cost` - the summed cost of the edges of the route ending in this state
     ///
     /// # Returns
     ///
@@ -207,7 +265,14 @@ impl CostModel {
     pub fn serialize_cost(
         &self,
         state: &[StateVariable],
+        route_cost: Cost,
     ) -> Result<serde_json::Value, CostModelError> {
+        if self.expression.is_some() {
+            // an expression is evaluated per edge and may not be linear, so the
+            // cost of the route is the sum of its edge costs, not the expression
+            // evaluated over the whole route
+            return Ok(json!({ "total_cost": route_cost }));
+        }
         let mut state_variable_costs = self
             .feature_indices
             .iter()
@@ -294,6 +359,9 @@ impl CostModel {
             Self::COST_AGGREGATION.to_string(),
             json![self.cost_aggregation],
         );
+        if let Some(expression) = &self.expression {
+            result.insert(Self::EXPRESSION.to_string(), json![expression.to_string()]);
+        }
 
         Ok(json![result])
     }
@@ -1,5 +1,42 @@
 use super::{network::NetworkCostRate, CostAggregation, CostModelError, VehicleCostRate};
 use crate::model::{network::Edge, state::StateVariable, unit::Cost};
+use std::collections::HashMap;
+
+/// divides the weight of each feature by a reference value for that feature, so
+/// that weights express relative importance on a common scale. a feature with a
+/// reference of 100 and a weight of 1 contributes the same cost as a raw weight
+/// of 0.01. features without a reference keep their weight.
+///
+/// # Arguments
+/// * `weights`       - weighting factors for each feature
+/// * `normalization` - reference value for each normalized feature
+///
+/// # Returns
+///
+/// the normalized weights, or an error if a reference is not a positive number,
+/// which would reorder or negate costs and break the search
+pub fn normalize_weights(
+    weights: &HashMap<String, f64>,
+    normalization: &HashMap<String, f64>,
+) -> Result<HashMap<String, f64>, CostModelError> {
+    if let Some((name, reference)) = normalization
+        .iter()
+        .find(|(_, r)| !(r.is_finite() && **r > 0.0))
+    {
+        return Err(CostModelError::InvalidConfiguration(format!(
+            "normalization reference for '{}' must be a positive number, found {}",
+            name, reference
+        )));
+    }
+    let normalized = weights
+        .iter()
+        .map(|(name, weight)| match normalization.get(name) {
+            Some(reference) => (name.clone(), weight / reference),
+            None => (name.clone(), *weight),
+        })
+        .collect();
+    Ok(normalized)
+}
 
 /// steps through each state variable and assigns vehicle costs related to that variable
 /// due to an edge access + traversal event.
@@ -1,4 +1,5 @@
 mod cost_aggregation;
+mod cost_expression;
 mod cost_model;
 mod cost_model_error;
 pub mod cost_ops;
@@ -6,6 +7,7 @@ pub mod network;
 mod vehicle;
 
 pub use cost_aggregation::CostAggregation;
+pub use cost_expression::CostExpression;
 pub use cost_model::CostModel;
 pub use cost_model_error::CostModelError;
 pub use vehicle::vehicle_cost_rate::VehicleCostRate;
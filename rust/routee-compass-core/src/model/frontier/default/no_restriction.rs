@@ -11,6 +11,7 @@ impl FrontierModel for NoRestriction {
     fn valid_frontier(
         &self,
         _edge: &crate::model::network::Edge,
+        _previous_edge_id: Option<crate::model::network::EdgeId>,
         _state: &[crate::model::state::StateVariable],
         _tree: &std::collections::HashMap<
             crate::model::network::VertexId,
@@ -4,7 +4,7 @@ use super::frontier_model_error::FrontierModelError;
 use crate::{
     algorithm::search::{Direction, SearchTreeBranch},
     model::{
-        network::{Edge, VertexId},
+        network::{Edge, EdgeId, VertexId},
         state::{StateModel, StateVariable},
     },
 };
@@ -21,6 +21,9 @@ pub trait FrontierModel: Send + Sync {
     /// # Arguments
     ///
     /// * `edge` - the edge to traverse
+    /// * `previous_edge_id` - the edge traversed just before this one in the search,
+    ///   or None at the start of the search. in a reverse search, this is the edge
+    ///   that follows this one along the trip
     /// * `state` - the state of the traversal at the beginning of this edge
     /// * `tree` - the search tree for this search
     /// * `direction` - search direction
@@ -32,6 +35,7 @@ pub trait FrontierModel: Send + Sync {
     fn valid_frontier(
         &self,
         edge: &Edge,
+        previous_edge_id: Option<EdgeId>,
         state: &[StateVariable],
         tree: &HashMap<VertexId, SearchTreeBranch>,
         direction: &Direction,
@@ -5,20 +5,21 @@ use crate::{
     model::network::{EdgeId, Graph},
     util::{fs::read_utils, geo::geo_io_utils},
 };
-use geo::LineString;
+use geo::{Coord, LineString};
 use kdam::{Bar, BarExt};
 
 /// model for link geometries by edge id. can be constructed either
 /// from edge geometry dataset ([`GeometryModel::new_from_edges`]) or
 /// from the vertices ([`GeometryModel::new_from_vertices`]) by simply
-/// drawing lines between coordinates.
-pub struct GeometryModel(Vec<LineString<f32>>);
+/// drawing lines between coordinates. when no geometry output is needed,
+/// [`GeometryModel::new_without_geometries`] skips loading geometries to save memory.
+pub struct GeometryModel(Option<Vec<LineString<f32>>>);
 
 impl GeometryModel {
     /// with no provided geometries, create minimal LineStrings from pairs of vertex Points
     pub fn new_from_vertices(graph: Arc<Graph>) -> Result<GeometryModel, MapError> {
         let edges = create_linestrings_from_vertices(graph)?;
-        Ok(GeometryModel(edges))
+        Ok(GeometryModel(Some(edges)))
     }
 
     /// use a user-provided enumerated textfile input to load LineString geometries
@@ -27,19 +28,50 @@ impl GeometryModel {
         graph: Arc<Graph>,
     ) -> Result<GeometryModel, MapError> {
         let edges = read_linestrings(geometry_input_file, graph.edges.len())?;
-        Ok(GeometryModel(edges))
+        Ok(GeometryModel(Some(edges)))
+    }
+
+    /// create a model that holds no geometries, for applications that only
+    /// compute costs. any geometry lookup fails.
+    pub fn new_without_geometries() -> GeometryModel {
+        GeometryModel(None)
+    }
+
+    /// true if this model holds edge geometries
+    pub fn is_loaded(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// approximate number of bytes allocated to hold the geometries of this model
+    pub fn allocated_bytes(&self) -> usize {
+        match &self.0 {
+            None => 0,
+            Some(geoms) => {
+                let coords = geoms
+                    .iter()
+                    .map(|g| g.0.capacity() * std::mem::size_of::<Coord<f32>>())
+                    .sum::<usize>();
+                coords + geoms.capacity() * std::mem::size_of::<LineString<f32>>()
+            }
+        }
     }
 
     /// iterate through the geometries of this model
     pub fn geometries<'a>(&'a self) -> Box<dyn Iterator<Item = &'a LineString<f32>> + 'a> {
-        Box::new(self.0.iter())
+        match &self.0 {
+            None => Box::new(std::iter::empty()),
+            Some(geoms) => Box::new(geoms.iter()),
+        }
     }
 
     /// get a single geometry by it's EdgeId
     pub fn get<'a>(&'a self, edge_id: &EdgeId) -> Result<&'a LineString<f32>, MapError> {
-        self.0
-            .get(edge_id.0)
-            .ok_or(MapError::MissingEdgeId(*edge_id))
+        match &self.0 {
+            None => Err(MapError::GeometryNotLoaded),
+            Some(geoms) => geoms
+                .get(edge_id.0)
+                .ok_or(MapError::MissingEdgeId(*edge_id)),
+        }
     }
 }
 
@@ -11,6 +11,10 @@ pub enum MapError {
     BuildError(String),
     #[error("map geometries missing EdgeId {0}")]
     MissingEdgeId(EdgeId),
+    #[error(
+        "edge geometries are not loaded, as the map model is configured with load_geometry = false"
+    )]
+    GeometryNotLoaded,
     #[error("failure matching query to map: {0}")]
     MapMatchError(String),
     #[error("this Compass instance is configured to require destinations on inputs, but the appropriate 'destination_*' fields were not found on query (looked for: {0})")]
@@ -27,6 +31,8 @@ pub enum MapError {
     ProjectionError { geometry: String, error: String },
     #[error("result not found within distance threshold of {1}/{2}: {0}")]
     DistanceThresholdError(String, Distance, DistanceUnit),
+    #[error("coordinate ({0}, {1}) is off-network, with no road within the tolerance of {2} {3}")]
+    OffNetwork(f32, f32, Distance, DistanceUnit),
     #[error("{0}")]
     InternalError(String),
 }
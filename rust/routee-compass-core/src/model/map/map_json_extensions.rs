@@ -1,6 +1,10 @@
-use super::{map_error::MapError, map_json_key::MapJsonKey};
+use super::{map_error::MapError, map_json_key::MapJsonKey, snap_endpoint::SnapEndpoint};
 use crate::model::network::{EdgeId, VertexId};
 use geo;
+use itertools::Itertools;
+
+/// tolerance, in degrees, used for an arrival bearing when a query does not provide one
+pub const DEFAULT_ARRIVAL_BEARING_TOLERANCE: f64 = 45.0;
 
 pub trait MapJsonExtensions {
     fn get_origin_coordinate(&self) -> Result<geo::Coord<f32>, MapError>;
@@ -13,6 +17,10 @@ pub trait MapJsonExtensions {
     fn get_destination_vertex(&self) -> Result<Option<VertexId>, MapError>;
     fn get_origin_edge(&self) -> Result<EdgeId, MapError>;
     fn get_destination_edge(&self) -> Result<Option<EdgeId>, MapError>;
+    /// the optional arrival bearing and bearing tolerance of a query, in degrees
+    fn get_arrival_bearing(&self) -> Result<Option<(f64, f64)>, MapError>;
+    /// the optional endpoint selection for snapped origin and destination edges
+    fn get_snap_endpoint(&self) -> Result<Option<SnapEndpoint>, MapError>;
 }
 
 impl MapJsonExtensions for serde_json::Value {
@@ -162,4 +170,36 @@ impl MapJsonExtensions for serde_json::Value {
             )),
         }
     }
+
+    fn get_arrival_bearing(&self) -> Result<Option<(f64, f64)>, MapError> {
+        let bearing_key = MapJsonKey::ArrivalBearing.to_string();
+        let tolerance_key = MapJsonKey::ArrivalBearingTolerance.to_string();
+        let bearing = match self.get(&bearing_key) {
+            None => return Ok(None),
+            Some(v) => v.as_f64().ok_or_else(|| {
+                MapError::InputDeserializingError(bearing_key.clone(), String::from("f64"))
+            })?,
+        };
+        let tolerance = match self.get(&tolerance_key) {
+            None => DEFAULT_ARRIVAL_BEARING_TOLERANCE,
+            Some(v) => v.as_f64().filter(|t| *t >= 0.0).ok_or_else(|| {
+                MapError::InputDeserializingError(
+                    tolerance_key.clone(),
+                    String::from("non-negative f64"),
+                )
+            })?,
+        };
+        Ok(Some((bearing, tolerance)))
+    }
+
+    fn get_snap_endpoint(&self) -> Result<Option<SnapEndpoint>, MapError> {
+        let key = MapJsonKey::SnapEndpoint.to_string();
+        match self.get(&key) {
+            None => Ok(None),
+            Some(v) => serde_json::from_value(v.clone()).map(Some).map_err(|_| {
+                let expected = SnapEndpoint::ALL.iter().map(|e| e.to_string()).join(", ");
+                MapError::InputDeserializingError(key.clone(), format!("one of [{}]", expected))
+            }),
+        }
+    }
 }
@@ -12,6 +12,9 @@ pub enum MapJsonKey {
     DestinationVertex,
     OriginEdge,
     DestinationEdge,
+    ArrivalBearing,
+    ArrivalBearingTolerance,
+    SnapEndpoint,
 }
 
 impl Display for MapJsonKey {
@@ -26,6 +29,9 @@ impl Display for MapJsonKey {
             I::DestinationVertex => "destination_vertex",
             I::OriginEdge => "origin_edge",
             I::DestinationEdge => "destination_edge",
+            I::ArrivalBearing => "arrival_bearing",
+            I::ArrivalBearingTolerance => "arrival_bearing_tolerance",
+            I::SnapEndpoint => "snap_endpoint",
         };
         write!(f, "{}", s)
     }
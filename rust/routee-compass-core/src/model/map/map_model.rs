@@ -1,10 +1,13 @@
 use super::map_error::MapError;
+use super::map_json_extensions::MapJsonExtensions;
 use super::map_model_config::MapModelConfig;
 use super::matching_type::MatchingType;
+use super::snap_endpoint::SnapEndpoint;
 use super::spatial_index::SpatialIndex;
 use super::{geometry_model::GeometryModel, matching_type::MapInputResult};
 use crate::algorithm::search::SearchInstance;
 use crate::model::network::{EdgeId, Graph};
+use crate::util::geo::coordinate_orientation::CoordinateOrientation;
 use geo::LineString;
 use std::sync::Arc;
 
@@ -13,24 +16,43 @@ pub struct MapModel {
     pub spatial_index: SpatialIndex,
     pub geometry_model: GeometryModel,
     pub queries_without_destinations: bool,
+    /// order of the values in query coordinates
+    pub coordinate_orientation: CoordinateOrientation,
+    /// where searches begin and end on snapped edges, unless set by the query
+    pub snap_endpoint: SnapEndpoint,
 }
 
 impl MapModel {
     pub fn new(graph: Arc<Graph>, config: MapModelConfig) -> Result<MapModel, MapError> {
         let matching_type = config.get_matching_type()?;
+        let coordinate_orientation = config.get_coordinate_orientation();
         match config {
             MapModelConfig::VertexMapModelConfig {
                 tolerance,
                 geometry_input_file,
                 queries_without_destinations,
                 matching_type: _,
+                load_geometry,
+                coordinate_orientation: _,
             } => {
                 let tol_unpacked = tolerance.map(|t| t.unpack());
+                // vertices without edges, such as those pruned by a graph filter, cannot be routed
+                let connected_vertices = graph
+                    .vertices
+                    .iter()
+                    .filter(|v| !graph.is_isolated(&v.vertex_id))
+                    .cloned()
+                    .collect::<Vec<_>>();
                 let spatial_index =
-                    SpatialIndex::new_vertex_oriented(&graph.clone().vertices, tol_unpacked);
-                let geometry_model = match geometry_input_file {
-                    None => GeometryModel::new_from_vertices(graph),
-                    Some(file) => GeometryModel::new_from_edges(&file, graph.clone()),
+                    SpatialIndex::new_vertex_oriented(&connected_vertices, tol_unpacked);
+                let geometry_model = match (geometry_input_file, load_geometry.unwrap_or(true)) {
+                    (None, true) => GeometryModel::new_from_vertices(graph),
+                    (Some(file), true) => GeometryModel::new_from_edges(&file, graph.clone()),
+                    (None, false) => Ok(GeometryModel::new_without_geometries()),
+                    (Some(file), false) => Err(MapError::BuildError(format!(
+                        "geometry_input_file '{}' cannot be used when load_geometry is false",
+                        file
+                    ))),
                 }?;
 
                 let map_model = MapModel {
@@ -38,6 +60,8 @@ impl MapModel {
                     spatial_index,
                     geometry_model,
                     queries_without_destinations,
+                    coordinate_orientation,
+                    snap_endpoint: SnapEndpoint::default(),
                 };
                 Ok(map_model)
             }
@@ -46,6 +70,8 @@ impl MapModel {
                 geometry_input_file,
                 queries_without_destinations,
                 matching_type: _,
+                coordinate_orientation: _,
+                snap_endpoint,
             } => {
                 let tol_unpacked = tolerance.map(|t| t.unpack());
                 let geometry_model =
@@ -57,6 +83,8 @@ impl MapModel {
                     spatial_index,
                     geometry_model,
                     queries_without_destinations,
+                    coordinate_orientation,
+                    snap_endpoint: snap_endpoint.unwrap_or_default(),
                 };
                 Ok(map_model)
             }
@@ -73,11 +101,12 @@ impl MapModel {
         si: &SearchInstance,
     ) -> Result<(), MapError> {
         self.matching_type.process_origin(query, si)?;
-        match self.matching_type.process_destination(query, si)? {
-            MapInputResult::NotFound if !self.queries_without_destinations => {
-                Err(MapError::DestinationsRequired(self.matching_type.clone()))
+        if let MapInputResult::NotFound = self.matching_type.process_destination(query, si)? {
+            if !self.queries_without_destinations {
+                return Err(MapError::DestinationsRequired(self.matching_type.clone()));
             }
-            _ => Ok(()),
         }
+        let snap_endpoint = query.get_snap_endpoint()?.unwrap_or(self.snap_endpoint);
+        snap_endpoint.apply(query, si)
     }
 }
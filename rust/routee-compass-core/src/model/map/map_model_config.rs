@@ -1,5 +1,6 @@
-use super::{map_error::MapError, matching_type::MatchingType};
+use super::{map_error::MapError, matching_type::MatchingType, snap_endpoint::SnapEndpoint};
 use crate::model::unit::{Distance, DistanceUnit};
+use crate::util::geo::coordinate_orientation::CoordinateOrientation;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::str::FromStr;
@@ -14,6 +15,12 @@ pub enum MapModelConfig {
         geometry_input_file: Option<String>,
         queries_without_destinations: bool,
         matching_type: Option<Vec<String>>,
+        /// if false, no edge geometries are loaded, which saves memory when
+        /// only costs are computed. true by default.
+        load_geometry: Option<bool>,
+        /// order of the values in the origin and destination coordinates of
+        /// queries. lon_lat by default.
+        coordinate_orientation: Option<CoordinateOrientation>,
     },
     #[serde(rename = "edge")]
     EdgeMapModelConfig {
@@ -22,10 +29,29 @@ pub enum MapModelConfig {
         geometry_input_file: String,
         queries_without_destinations: bool,
         matching_type: Option<Vec<String>>,
+        /// order of the values in the origin and destination coordinates of
+        /// queries. lon_lat by default.
+        coordinate_orientation: Option<CoordinateOrientation>,
+        /// where searches begin and end on snapped edges. mid_edge by default.
+        snap_endpoint: Option<SnapEndpoint>,
     },
 }
 
 impl MapModelConfig {
+    pub fn get_coordinate_orientation(&self) -> CoordinateOrientation {
+        let orientation = match self {
+            MapModelConfig::VertexMapModelConfig {
+                coordinate_orientation,
+                ..
+            } => coordinate_orientation,
+            MapModelConfig::EdgeMapModelConfig {
+                coordinate_orientation,
+                ..
+            } => coordinate_orientation,
+        };
+        orientation.unwrap_or_default()
+    }
+
     pub fn get_matching_type(&self) -> Result<MatchingType, MapError> {
         let matching_type = match self {
             MapModelConfig::VertexMapModelConfig {
@@ -33,12 +59,16 @@ impl MapModelConfig {
                 geometry_input_file: _,
                 queries_without_destinations: _,
                 matching_type,
+                load_geometry: _,
+                coordinate_orientation: _,
             } => matching_type,
             MapModelConfig::EdgeMapModelConfig {
                 tolerance: _,
                 geometry_input_file: _,
                 queries_without_destinations: _,
                 matching_type,
+                coordinate_orientation: _,
+                snap_endpoint: _,
             } => matching_type,
         };
         match matching_type {
@@ -66,6 +96,8 @@ impl Default for MapModelConfig {
             geometry_input_file: None,
             queries_without_destinations: true,
             matching_type: Some(MatchingType::names()),
+            load_geometry: None,
+            coordinate_orientation: None,
         }
     }
 }
@@ -4,7 +4,11 @@ use super::{
 };
 use crate::{
     algorithm::search::SearchInstance,
-    model::{frontier::FrontierModel, network::Edge},
+    model::{
+        access::default::turn_delays::{heading_difference, EdgeHeading},
+        frontier::FrontierModel,
+        network::{Edge, EdgeId},
+    },
 };
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
@@ -98,6 +102,7 @@ impl MatchingType {
                 for matching_type in vec.iter() {
                     match matching_type.process_origin(query, si) {
                         Ok(_) => return Ok(()),
+                        Err(e @ MapError::OffNetwork(..)) => return Err(e),
                         Err(e) => {
                             let mit = serde_json::to_string(matching_type).unwrap_or_default();
                             let msg = format!("no origin {} on input query: {}", mit, e);
@@ -135,7 +140,14 @@ impl MatchingType {
             MT::Point => {
                 // iterate through nearest values in the spatial index to this point that
                 // are within our matching tolerance and validate them with the frontier model
-                let src_point = geo::Point(query.get_origin_coordinate()?);
+                let src_coord = si
+                    .map_model
+                    .coordinate_orientation
+                    .orient_checked(query.get_origin_coordinate()?)
+                    .map_err(MapError::MapMatchError)?;
+                let src_point = geo::Point(src_coord);
+                // a point beyond the tolerance of every map element is off-network
+                si.map_model.spatial_index.nearest_graph_id(&src_point)?;
                 for nearest in si.map_model.spatial_index.nearest_graph_id_iter(&src_point) {
                     match nearest {
                         NearestSearchResult::NearestVertex(vertex_id) => {
@@ -185,6 +197,8 @@ impl MatchingType {
                 for matching_type in vec.iter() {
                     match matching_type.process_destination(query, si) {
                         Ok(_) => return Ok(MapInputResult::Found),
+                        // an off-network point cannot be matched by any other type
+                        Err(e @ MapError::OffNetwork(..)) => return Err(e),
                         Err(e) => {
                             let mit = serde_json::to_string(matching_type).unwrap_or_default();
                             let msg = format!("no destination {} on input query: {}", mit, e);
@@ -237,9 +251,18 @@ impl MatchingType {
                 // iterate through nearest values in the spatial index to this point that
                 // are within our matching tolerance and validate them with the frontier model
                 let dst_point = match query.get_destination_coordinate()? {
-                    Some(coord) => geo::Point(coord),
+                    Some(coord) => si
+                        .map_model
+                        .coordinate_orientation
+                        .orient_checked(coord)
+                        .map(geo::Point)
+                        .map_err(MapError::MapMatchError)?,
                     None => return Ok(MapInputResult::NotFound),
                 };
+                // when an arrival bearing is given, only destination edges traveling in that
+                // direction are matched, such as to arrive on one side of a divided road
+                let arrival_bearing = query.get_arrival_bearing()?;
+                si.map_model.spatial_index.nearest_graph_id(&dst_point)?;
 
                 for nearest in si.map_model.spatial_index.nearest_graph_id_iter(&dst_point) {
                     match nearest {
@@ -258,7 +281,11 @@ impl MatchingType {
                         NearestSearchResult::NearestEdge(edge_id) => {
                             let edge = si.graph.get_edge(&edge_id).map_err(|e| MapError::MapMatchError(format!("while attempting to validate edge id {} from nearest neighbor search for map matching, the underlying Graph model caused an error: {}", edge_id, e)))?;
                             let is_valid = test_edge(edge, si.frontier_model.clone())?;
-                            if is_valid {
+                            let approaches = match arrival_bearing {
+                                None => true,
+                                Some(bearing) => test_arrival_bearing(&edge_id, bearing, si)?,
+                            };
+                            if is_valid && approaches {
                                 query.add_destination_edge(edge_id)?;
                                 return Ok(MapInputResult::Found);
                             }
@@ -275,6 +302,21 @@ impl MatchingType {
     }
 }
 
+/// tests if traveling along an edge arrives at its end with a heading within
+/// the tolerance of a bearing. edges without a heading never match.
+fn test_arrival_bearing(
+    edge_id: &EdgeId,
+    arrival_bearing: (f64, f64),
+    si: &SearchInstance,
+) -> Result<bool, MapError> {
+    let (bearing, tolerance) = arrival_bearing;
+    let geometry = si.map_model.geometry_model.get(edge_id)?;
+    match EdgeHeading::from_linestring(geometry) {
+        None => Ok(false),
+        Some(heading) => Ok(heading_difference(heading.end_heading() as f64, bearing) <= tolerance),
+    }
+}
+
 fn test_edge(edge: &Edge, fm: Arc<dyn FrontierModel>) -> Result<bool, MapError> {
     let is_valid = fm.valid_edge(edge).map_err(|e| MapError::MapMatchError(format!("while attempting to validate edge id {} for map matching, the underlying FrontierModel caused an error: {}", edge.edge_id, e)))?;
     Ok(is_valid)
@@ -8,6 +8,7 @@ mod map_model_config;
 mod map_vertex_rtree_object;
 mod matching_type;
 mod nearest_search_result;
+mod snap_endpoint;
 mod spatial_index;
 pub mod spatial_index_ops;
 
@@ -21,4 +22,5 @@ pub use map_model_config::MapModelConfig;
 pub use map_vertex_rtree_object::MapVertexRTreeObject;
 pub use matching_type::{MapInputResult, MatchingType};
 pub use nearest_search_result::NearestSearchResult;
+pub use snap_endpoint::SnapEndpoint;
 pub use spatial_index::SpatialIndex;
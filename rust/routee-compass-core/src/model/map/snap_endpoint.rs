@@ -0,0 +1,162 @@
+use super::{
+    map_error::MapError, map_json_extensions::MapJsonExtensions, map_json_key::MapJsonKey,
+};
+use crate::{
+    algorithm::search::SearchInstance,
+    model::network::{EdgeId, VertexId},
+    util::geo::haversine,
+};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// a [`SnapEndpoint`] selects where a search begins or ends when a query
+/// coordinate is snapped to an edge by edge-oriented map matching.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapEndpoint {
+    /// the route begins and ends on the snapped edges, at the query coordinates,
+    /// with the traveled part of each edge set by `include_partial_edges`.
+    #[default]
+    MidEdge,
+    /// the search begins at the vertex reached by traveling along the origin edge
+    /// and ends at the vertex the destination edge is entered from, leaving the
+    /// snapped edges out of the route.
+    Reachable,
+    /// the search begins and ends at the endpoint of each snapped edge nearest to
+    /// the query coordinate. an endpoint that the search cannot leave (origin) or
+    /// reach (destination) by a valid edge is skipped for the other endpoint.
+    Nearest,
+}
+
+impl SnapEndpoint {
+    pub const ALL: [SnapEndpoint; 3] = [Self::MidEdge, Self::Reachable, Self::Nearest];
+
+    /// replaces the origin and destination edges of a query with vertices, as
+    /// selected by this [`SnapEndpoint`]. queries without an origin edge, and all
+    /// queries under [`SnapEndpoint::MidEdge`], are left as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - a map matched user query
+    /// * `si` - the search assets for this query
+    pub fn apply(
+        &self,
+        query: &mut serde_json::Value,
+        si: &SearchInstance,
+    ) -> Result<(), MapError> {
+        if *self == SnapEndpoint::MidEdge {
+            return Ok(());
+        }
+        let Ok(origin_edge) = query.get_origin_edge() else {
+            return Ok(());
+        };
+        let destination_edge = query.get_destination_edge()?;
+        let origin = self.select_vertex(origin_edge, true, query, si)?;
+        let destination = destination_edge
+            .map(|edge_id| self.select_vertex(edge_id, false, query, si))
+            .transpose()?;
+
+        if let Some(obj) = query.as_object_mut() {
+            obj.remove(&MapJsonKey::OriginEdge.to_string());
+            obj.remove(&MapJsonKey::DestinationEdge.to_string());
+        }
+        query.add_origin_vertex(origin)?;
+        if let Some(destination) = destination {
+            query.add_destination_vertex(destination)?;
+        }
+        Ok(())
+    }
+
+    /// selects the vertex of a snapped origin or destination edge to search from or to
+    fn select_vertex(
+        &self,
+        edge_id: EdgeId,
+        origin: bool,
+        query: &serde_json::Value,
+        si: &SearchInstance,
+    ) -> Result<VertexId, MapError> {
+        let graph_error = |e| {
+            MapError::MapMatchError(format!(
+                "while selecting an endpoint of edge {}, the underlying Graph model caused an error: {}",
+                edge_id, e
+            ))
+        };
+        let src = si.graph.src_vertex_id(&edge_id).map_err(graph_error)?;
+        let dst = si.graph.dst_vertex_id(&edge_id).map_err(graph_error)?;
+        match self {
+            SnapEndpoint::MidEdge => Err(MapError::InternalError(String::from(
+                "mid_edge endpoints are not replaced with vertices",
+            ))),
+            SnapEndpoint::Reachable if origin => Ok(dst),
+            SnapEndpoint::Reachable => Ok(src),
+            SnapEndpoint::Nearest => {
+                let coordinate = if origin {
+                    query.get_origin_coordinate()?
+                } else {
+                    query
+                        .get_destination_coordinate()?
+                        .ok_or(MapError::InputMissingField(MapJsonKey::DestinationX))?
+                };
+                let coordinate = si
+                    .map_model
+                    .coordinate_orientation
+                    .orient_checked(coordinate)
+                    .map_err(MapError::MapMatchError)?;
+                let candidates = [src, dst]
+                    .into_iter()
+                    .map(|vertex_id| {
+                        let vertex = si.graph.get_vertex(&vertex_id).map_err(graph_error)?;
+                        let distance =
+                            haversine::coord_distance_meters(&vertex.coordinate, &coordinate)
+                                .map_err(MapError::MapMatchError)?;
+                        Ok((vertex_id, distance))
+                    })
+                    .collect::<Result<Vec<_>, MapError>>()?;
+                for (vertex_id, _) in candidates.into_iter().sorted_by(|(_, a), (_, b)| a.cmp(b)) {
+                    if continues_search(&vertex_id, origin, si)? {
+                        return Ok(vertex_id);
+                    }
+                }
+                Err(MapError::MapMatchError(format!(
+                    "neither endpoint of edge {} has a valid {} edge to continue the search",
+                    edge_id,
+                    if origin { "outgoing" } else { "incoming" }
+                )))
+            }
+        }
+    }
+}
+
+/// tests if a search can leave an origin vertex, or reach a destination vertex,
+/// by an edge that is valid according to the frontier model.
+fn continues_search(
+    vertex_id: &VertexId,
+    origin: bool,
+    si: &SearchInstance,
+) -> Result<bool, MapError> {
+    let edge_ids = if origin {
+        si.graph.out_edges(vertex_id)
+    } else {
+        si.graph.in_edges(vertex_id)
+    };
+    for edge_id in edge_ids.iter() {
+        let edge = si.graph.get_edge(edge_id).map_err(|e| MapError::MapMatchError(format!("while attempting to validate edge id {} for map matching, the underlying Graph model caused an error: {}", edge_id, e)))?;
+        let is_valid = si.frontier_model.valid_edge(edge).map_err(|e| MapError::MapMatchError(format!("while attempting to validate edge id {} for map matching, the underlying FrontierModel caused an error: {}", edge_id, e)))?;
+        if is_valid {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+impl Display for SnapEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            SnapEndpoint::MidEdge => "mid_edge",
+            SnapEndpoint::Reachable => "reachable",
+            SnapEndpoint::Nearest => "nearest",
+        };
+        write!(f, "{}", s)
+    }
+}
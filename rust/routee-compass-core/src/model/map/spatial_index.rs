@@ -5,11 +5,12 @@ use super::{
     map_vertex_rtree_object::MapVertexRTreeObject, nearest_search_result::NearestSearchResult,
 };
 use crate::model::{
-    network::{Graph, Vertex},
-    unit::{Distance, DistanceUnit},
+    network::{Graph, Vertex, VertexId},
+    unit::{AsF64, Distance, DistanceUnit},
 };
-use geo::Point;
-use rstar::RTree;
+use geo::{BoundingRect, Intersects, Point, Polygon};
+use rstar::{RTree, AABB};
+use std::collections::HashSet;
 
 pub enum SpatialIndex {
     VertexOrientedIndex {
@@ -38,7 +39,7 @@ impl SpatialIndex {
 
     /// creates a new instance of the rtree model that is edge-oriented; that is, the
     /// rtree is built over the edges in the graph, and nearest neighbor searches return
-    /// the edge's destination vertex.
+    /// the edge's destination vertex. edges removed by a graph filter are not indexed.
     /// - future work: make SearchOrientation set which incident vertex is returned.
     pub fn new_edge_oriented(
         graph: Arc<Graph>,
@@ -49,6 +50,7 @@ impl SpatialIndex {
             .edges
             .iter()
             .zip(geometry_model.geometries())
+            .filter(|(e, _)| graph.contains_edge(&e.edge_id))
             .map(|(e, g)| MapEdgeRTreeObject::new(e, g))
             .collect();
         let rtree = RTree::bulk_load(entries.to_vec());
@@ -77,16 +79,60 @@ impl SpatialIndex {
         }
     }
 
+    /// the distance within which map elements are matched, if limited
+    pub fn tolerance(&self) -> Option<(Distance, DistanceUnit)> {
+        match self {
+            SpatialIndex::VertexOrientedIndex { tolerance, .. } => *tolerance,
+            SpatialIndex::EdgeOrientedIndex { tolerance, .. } => *tolerance,
+        }
+    }
+
     /// builds an iterator over map edges ordered by nearness to the given point.
-    /// applies the (map-matching) distance tolerance filter.
+    /// applies the (map-matching) distance tolerance filter. when a tolerance is
+    /// set, the iterator ends once elements are beyond it.
     pub fn nearest_graph_id_iter<'a>(
         &'a self,
         point: &'a Point<f32>,
     ) -> Box<dyn Iterator<Item = NearestSearchResult> + 'a> {
+        if let Some((distance, unit)) = self.tolerance() {
+            let radius_meters = unit.convert(&distance, &DistanceUnit::Meters);
+            return self.nearest_graph_id_iter_within(point, radius_meters);
+        }
+        match self {
+            SpatialIndex::VertexOrientedIndex { rtree, .. } => {
+                let iter = rtree
+                    .nearest_neighbor_iter(point)
+                    .map(|next| NearestSearchResult::NearestVertex(next.vertex_id));
+                Box::new(iter)
+            }
+            SpatialIndex::EdgeOrientedIndex { rtree, .. } => {
+                let iter = rtree
+                    .nearest_neighbor_iter(point)
+                    .map(|next| NearestSearchResult::NearestEdge(next.edge_id));
+                Box::new(iter)
+            }
+        }
+    }
+
+    /// builds an iterator over map elements ordered by nearness to the given
+    /// point, ending once elements are further than a radius in meters. the
+    /// radius is compared in degrees, widened so that no element within the
+    /// radius is missed, so callers needing an exact radius should check the
+    /// distance to each element. applies the (map-matching) distance tolerance filter.
+    pub fn nearest_graph_id_iter_within<'a>(
+        &'a self,
+        point: &'a Point<f32>,
+        radius_meters: Distance,
+    ) -> Box<dyn Iterator<Item = NearestSearchResult> + 'a> {
+        let lat_degrees = radius_meters.as_f64() / METERS_PER_DEGREE;
+        let max_lat = (point.y().abs() as f64 + lat_degrees).min(89.9);
+        let lon_degrees = radius_meters.as_f64() / (METERS_PER_DEGREE * max_lat.to_radians().cos());
+        let max_distance_2 = lat_degrees.max(lon_degrees).powi(2) as f32;
         match self {
             SpatialIndex::VertexOrientedIndex { rtree, tolerance } => {
                 let iter = rtree
                     .nearest_neighbor_iter_with_distance_2(point)
+                    .take_while(move |(_, d2)| *d2 <= max_distance_2)
                     .filter(|(obj, _)| obj.test_threshold(point, tolerance).unwrap_or(false))
                     .map(|(next, _)| NearestSearchResult::NearestVertex(next.vertex_id));
                 Box::new(iter)
@@ -94,14 +140,61 @@ impl SpatialIndex {
             SpatialIndex::EdgeOrientedIndex { rtree, tolerance } => {
                 let iter = rtree
                     .nearest_neighbor_iter_with_distance_2(point)
+                    .take_while(move |(_, d2)| *d2 <= max_distance_2)
                     .filter(|(obj, _)| obj.test_threshold(point, tolerance).unwrap_or(false))
                     .map(|(next, _)| NearestSearchResult::NearestEdge(next.edge_id));
                 Box::new(iter)
             }
         }
     }
+
+    /// finds the graph vertices that lie inside or on the boundary of a polygon.
+    /// the index narrows the search to the polygon's bounding box. for an
+    /// edge-oriented index, the endpoints of the edges in the bounding box are
+    /// tested, so vertices without any indexed edge are not found.
+    pub fn vertices_within(
+        &self,
+        polygon: &Polygon<f32>,
+        graph: &Graph,
+    ) -> Result<HashSet<VertexId>, MapError> {
+        let bbox = match polygon.bounding_rect() {
+            Some(rect) => AABB::from_corners(Point(rect.min()), Point(rect.max())),
+            None => return Ok(HashSet::new()),
+        };
+        let candidates: Vec<VertexId> = match self {
+            SpatialIndex::VertexOrientedIndex { rtree, .. } => rtree
+                .locate_in_envelope(&bbox)
+                .map(|obj| obj.vertex_id)
+                .collect(),
+            SpatialIndex::EdgeOrientedIndex { rtree, .. } => rtree
+                .locate_in_envelope_intersecting(&bbox)
+                .map(|obj| {
+                    let edge = graph
+                        .get_edge(&obj.edge_id)
+                        .map_err(|e| MapError::InternalError(e.to_string()))?;
+                    Ok([edge.src_vertex_id, edge.dst_vertex_id])
+                })
+                .collect::<Result<Vec<_>, MapError>>()?
+                .into_iter()
+                .flatten()
+                .collect(),
+        };
+        let mut within = HashSet::new();
+        for vertex_id in candidates {
+            let vertex = graph
+                .get_vertex(&vertex_id)
+                .map_err(|e| MapError::InternalError(e.to_string()))?;
+            if polygon.intersects(&Point::new(vertex.x(), vertex.y())) {
+                within.insert(vertex_id);
+            }
+        }
+        Ok(within)
+    }
 }
 
+/// length of one degree of latitude, in meters
+const METERS_PER_DEGREE: f64 = 111_320.0;
+
 #[cfg(test)]
 mod test {
     use std::path::PathBuf;
@@ -6,42 +6,47 @@ use crate::{
 use geo::Point;
 use rstar::AABB;
 
+/// tests if the nearest point of an envelope is within the tolerance distance
+/// of a point.
 pub fn test_threshold(
     envelope: &AABB<Point<f32>>,
     other: &Point<f32>,
     tolerance_distance: Distance,
     tolerance_distance_unit: DistanceUnit,
 ) -> Result<bool, MapError> {
-    let this_coord = envelope.lower().0;
-    let distance_meters =
-        haversine::coord_distance_meters(&this_coord, &other.0).map_err(MapError::MapMatchError)?;
-    let distance = DistanceUnit::Meters.convert(&distance_meters, &tolerance_distance_unit);
-    Ok(distance >= tolerance_distance)
+    let distance = envelope_distance(envelope, other, &tolerance_distance_unit)?;
+    Ok(distance <= tolerance_distance)
 }
 
+/// confirms that the nearest point of an envelope is within the tolerance
+/// distance of a point, which otherwise is off-network.
 pub fn within_threshold(
     envelope: &AABB<Point<f32>>,
     other: &Point<f32>,
     tolerance_distance: Distance,
     tolerance_distance_unit: DistanceUnit,
 ) -> Result<(), MapError> {
-    let this_coord = envelope.lower().0;
-    let distance_meters =
-        haversine::coord_distance_meters(&this_coord, &other.0).map_err(MapError::MapMatchError)?;
-    let distance = DistanceUnit::Meters.convert(&distance_meters, &tolerance_distance_unit);
-    if distance >= tolerance_distance {
-        Err(MapError::MapMatchError(
-            format!(
-                "coord {:?} nearest vertex coord is {:?} which is {} {} away, exceeding the distance tolerance of {} {}", 
-                this_coord,
-                other,
-                distance,
-                tolerance_distance_unit,
-                tolerance_distance,
-                tolerance_distance_unit,
-            )
-        ))
-    } else {
+    if test_threshold(envelope, other, tolerance_distance, tolerance_distance_unit)? {
         Ok(())
+    } else {
+        Err(MapError::OffNetwork(
+            other.x(),
+            other.y(),
+            tolerance_distance,
+            tolerance_distance_unit,
+        ))
     }
 }
+
+/// the great-circle distance from a point to the nearest point of an envelope.
+/// for an edge, this is a lower bound on the distance to its geometry.
+fn envelope_distance(
+    envelope: &AABB<Point<f32>>,
+    other: &Point<f32>,
+    unit: &DistanceUnit,
+) -> Result<Distance, MapError> {
+    let nearest_coord = envelope.min_point(other).0;
+    let distance_meters = haversine::coord_distance_meters(&nearest_coord, &other.0)
+        .map_err(MapError::MapMatchError)?;
+    Ok(DistanceUnit::Meters.convert(&distance_meters, unit))
+}
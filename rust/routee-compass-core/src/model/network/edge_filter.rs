@@ -0,0 +1,185 @@
+use super::{EdgeId, NetworkError};
+use crate::util::fs::read_utils;
+use kdam::Bar;
+use std::collections::HashSet;
+
+/// a predicate over edge attributes used to load a subset of the road network,
+/// such as excluding service roads, without preprocessing the graph files.
+/// an edge is kept only if it satisfies every configured condition.
+///
+/// configured in the optional `filter` section of the graph configuration:
+///
+/// ```toml
+/// [graph.filter]
+/// road_class_input_file = "edges-road-class-enumerated.txt.gz"
+/// exclude_road_classes = ["service"]
+/// speed_input_file = "edges-posted-speed-enumerated.txt.gz"
+/// min_speed = 30.0
+/// ```
+pub struct EdgeFilter {
+    road_classes: Option<Box<[String]>>,
+    include_road_classes: Option<HashSet<String>>,
+    exclude_road_classes: HashSet<String>,
+    speeds: Option<Box<[f64]>>,
+    min_speed: Option<f64>,
+}
+
+/// the number of graph entities removed by an [`EdgeFilter`]
+#[derive(Debug, Default, PartialEq)]
+pub struct EdgeFilterSummary {
+    pub dropped_edges: usize,
+    pub dropped_vertices: usize,
+}
+
+impl TryFrom<&serde_json::Value> for EdgeFilter {
+    type Error = NetworkError;
+
+    fn try_from(value: &serde_json::Value) -> Result<Self, Self::Error> {
+        let get_path = |key: &str| -> Result<Option<String>, NetworkError> {
+            match value.get(key) {
+                None => Ok(None),
+                Some(v) => v.as_str().map(|s| Some(s.to_string())).ok_or_else(|| {
+                    NetworkError::DatasetError(format!(
+                        "graph filter value at key {} is not a string",
+                        key
+                    ))
+                }),
+            }
+        };
+        let get_classes = |key: &str| -> Result<Option<HashSet<String>>, NetworkError> {
+            match value.get(key) {
+                None => Ok(None),
+                Some(serde_json::Value::Array(values)) => {
+                    let classes = values.iter().map(road_class_string).collect();
+                    Ok(Some(classes))
+                }
+                Some(other) => Err(NetworkError::DatasetError(format!(
+                    "graph filter value at key {} should be a list of road classes, found {}",
+                    key, other
+                ))),
+            }
+        };
+
+        let include_road_classes = get_classes("road_classes")?;
+        let exclude_road_classes = get_classes("exclude_road_classes")?.unwrap_or_default();
+        let road_classes = match get_path("road_class_input_file")? {
+            Some(file) => {
+                let classes = read_utils::read_raw_file(
+                    &file,
+                    |_idx, row| Ok(row.trim().to_string()),
+                    Some(Bar::builder().desc("graph filter road classes")),
+                    None,
+                )?;
+                Some(classes)
+            }
+            None if include_road_classes.is_some() || !exclude_road_classes.is_empty() => {
+                return Err(NetworkError::DatasetError(String::from(
+                    "graph filter by road class requires a road_class_input_file",
+                )))
+            }
+            None => None,
+        };
+
+        let min_speed = match value.get("min_speed") {
+            None => None,
+            Some(v) => Some(v.as_f64().ok_or_else(|| {
+                NetworkError::DatasetError(format!(
+                    "graph filter value at key min_speed is not a number, found {}",
+                    v
+                ))
+            })?),
+        };
+        let speeds = match (get_path("speed_input_file")?, min_speed) {
+            (Some(file), _) => {
+                let speeds = read_utils::read_raw_file(
+                    &file,
+                    |idx, row| {
+                        row.trim().parse::<f64>().map_err(|e| {
+                            std::io::Error::new(
+                                std::io::ErrorKind::InvalidData,
+                                format!("invalid speed on row {}: {}", idx, e),
+                            )
+                        })
+                    },
+                    Some(Bar::builder().desc("graph filter speeds")),
+                    None,
+                )?;
+                Some(speeds)
+            }
+            (None, Some(_)) => {
+                return Err(NetworkError::DatasetError(String::from(
+                    "graph filter by min_speed requires a speed_input_file",
+                )))
+            }
+            (None, None) => None,
+        };
+
+        Ok(EdgeFilter {
+            road_classes,
+            include_road_classes,
+            exclude_road_classes,
+            speeds,
+            min_speed,
+        })
+    }
+}
+
+impl EdgeFilter {
+    /// confirms that each attribute table has a row for every edge of the graph
+    pub fn validate(&self, n_edges: usize) -> Result<(), NetworkError> {
+        let tables = [
+            (
+                "road_class_input_file",
+                self.road_classes.as_ref().map(|t| t.len()),
+            ),
+            ("speed_input_file", self.speeds.as_ref().map(|t| t.len())),
+        ];
+        for (key, len) in tables {
+            match len {
+                Some(len) if len != n_edges => {
+                    return Err(NetworkError::DatasetError(format!(
+                        "graph filter {} has {} rows but the graph has {} edges",
+                        key, len, n_edges
+                    )))
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// tests whether an edge satisfies the filter and should be kept
+    pub fn keep(&self, edge_id: &EdgeId) -> bool {
+        let keep_class = match &self.road_classes {
+            None => true,
+            Some(classes) => match classes.get(edge_id.0) {
+                None => false,
+                Some(class) => {
+                    let included = self
+                        .include_road_classes
+                        .as_ref()
+                        .map(|include| include.contains(class))
+                        .unwrap_or(true);
+                    included && !self.exclude_road_classes.contains(class)
+                }
+            },
+        };
+        let keep_speed = match (&self.speeds, self.min_speed) {
+            (Some(speeds), Some(min_speed)) => speeds
+                .get(edge_id.0)
+                .map(|speed| *speed >= min_speed)
+                .unwrap_or(false),
+            _ => true,
+        };
+        keep_class && keep_speed
+    }
+}
+
+/// road classes may be written as numbers or strings in the configuration, and
+/// are compared to the rows of the road class file as text.
+fn road_class_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
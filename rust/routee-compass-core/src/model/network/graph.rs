@@ -1,11 +1,19 @@
+use super::edge_filter::{EdgeFilter, EdgeFilterSummary};
+use super::graph_diameter::GraphDiameter;
+use super::graph_statistics::{EdgeLengthBin, GraphStatistics};
 use super::{Edge, EdgeId, NetworkError, Vertex, VertexId};
 use crate::algorithm::search::Direction;
+use crate::model::unit::{AsF64, Distance};
 use crate::util::compact_ordered_hash_map::CompactOrderedHashMap;
+use crate::util::fingerprint::Fingerprint;
 use crate::util::fs::read_utils;
+use crate::util::geo::coordinate_orientation::{check_coordinate, CoordinateOrientation};
 use allocative::Allocative;
 use itertools::Itertools;
 use kdam::Bar;
-use std::collections::HashSet;
+use ordered_float::OrderedFloat;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashSet};
 use std::path::Path;
 
 /// Road network topology represented as an adjacency list.
@@ -39,7 +47,9 @@ impl TryFrom<&serde_json::Value> for Graph {
     type Error = NetworkError;
 
     /// create a graph from a JSON argument. it should be an object that contains
-    /// two keys, one for each file path.
+    /// two keys, one for each file path, and optionally a `filter` section used
+    /// to load a subset of the edges, see [`EdgeFilter`], and the
+    /// `coordinate_orientation` of the x and y columns of the vertex file.
     fn try_from(value: &serde_json::Value) -> Result<Self, Self::Error> {
         let edge_list_value = value.get("edge_list_input_file").ok_or_else(|| {
             NetworkError::DatasetError(String::from(
@@ -67,7 +77,29 @@ impl TryFrom<&serde_json::Value> for Graph {
                 ))
             })?
             .to_string();
-        Self::from_files(&edge_list_str, &vertex_list_str)
+        let coordinate_orientation: CoordinateOrientation =
+            match value.get("coordinate_orientation") {
+                None => CoordinateOrientation::default(),
+                Some(v) => serde_json::from_value(v.clone()).map_err(|e| {
+                    NetworkError::DatasetError(format!(
+                        "configuration value at key coordinate_orientation is invalid: {}",
+                        e
+                    ))
+                })?,
+            };
+        let mut graph = Self::from_files(&edge_list_str, &vertex_list_str)?;
+        graph.orient_vertices(&coordinate_orientation)?;
+        if let Some(filter_value) = value.get("filter") {
+            let filter = EdgeFilter::try_from(filter_value)?;
+            filter.validate(graph.n_edges())?;
+            let summary = graph.filter_edges(|edge_id| filter.keep(edge_id));
+            log::info!(
+                "graph filter dropped {} edges and {} vertices",
+                summary.dropped_edges,
+                summary.dropped_vertices
+            );
+        }
+        Ok(graph)
     }
 }
 
@@ -137,6 +169,94 @@ impl Graph {
 
         Ok(graph)
     }
+    /// reads the vertex coordinates in the given orientation and checks that they
+    /// are valid longitude, latitude pairs, so that a vertex file written in the
+    /// other coordinate order is caught at startup instead of matching queries
+    /// to the wrong vertices.
+    pub fn orient_vertices(
+        &mut self,
+        orientation: &CoordinateOrientation,
+    ) -> Result<(), NetworkError> {
+        for vertex in self.vertices.iter_mut() {
+            *vertex.coordinate = orientation.orient(*vertex.coordinate);
+        }
+        let invalid = self
+            .vertices
+            .iter()
+            .filter_map(|v| check_coordinate(&v.coordinate).err())
+            .collect_vec();
+        match invalid.first() {
+            None => Ok(()),
+            Some(example) => Err(NetworkError::DatasetError(format!(
+                "{} vertices have invalid coordinates with coordinate_orientation {}, such as: {}",
+                invalid.len(),
+                orientation,
+                example
+            ))),
+        }
+    }
+
+    /// removes the edges that do not satisfy a predicate from the adjacency
+    /// lists so they cannot be traversed. edge and vertex ids are unchanged so
+    /// that per-edge attribute tables stay aligned with the graph. vertices left
+    /// without any incident edges are pruned, see [`Graph::is_isolated`].
+    ///
+    /// # Arguments
+    ///
+    /// * `keep` - returns true for edges that should remain in the graph
+    ///
+    /// # Returns
+    ///
+    /// the number of edges and vertices dropped from the graph
+    pub fn filter_edges(&mut self, keep: impl Fn(&EdgeId) -> bool) -> EdgeFilterSummary {
+        let connected_before = self.vertex_ids().filter(|v| !self.is_isolated(v)).count();
+        let edges_before: usize = self.adj.iter().map(|a| a.len()).sum();
+        let retain = |links: &CompactOrderedHashMap<EdgeId, VertexId>| {
+            let kept = links
+                .iter()
+                .filter(|(edge_id, _)| keep(edge_id))
+                .map(|(edge_id, vertex_id)| (*edge_id, *vertex_id))
+                .collect_vec();
+            CompactOrderedHashMap::new(kept)
+        };
+        self.adj = self.adj.iter().map(retain).collect();
+        self.rev = self.rev.iter().map(retain).collect();
+        let connected_after = self.vertex_ids().filter(|v| !self.is_isolated(v)).count();
+        let edges_after: usize = self.adj.iter().map(|a| a.len()).sum();
+        EdgeFilterSummary {
+            dropped_edges: edges_before - edges_after,
+            dropped_vertices: connected_before - connected_after,
+        }
+    }
+
+    /// tests whether an edge can be traversed, that is, it has not been
+    /// removed by an edge filter
+    pub fn contains_edge(&self, edge_id: &EdgeId) -> bool {
+        match self.edges.get(edge_id.0) {
+            None => false,
+            Some(edge) => self
+                .adj
+                .get(edge.src_vertex_id.0)
+                .map(|out_links| out_links.contains_key(edge_id))
+                .unwrap_or(false),
+        }
+    }
+
+    /// tests whether a vertex has no incident edges
+    pub fn is_isolated(&self, vertex_id: &VertexId) -> bool {
+        let no_out = self
+            .adj
+            .get(vertex_id.0)
+            .map(|a| a.is_empty())
+            .unwrap_or(true);
+        let no_in = self
+            .rev
+            .get(vertex_id.0)
+            .map(|r| r.is_empty())
+            .unwrap_or(true);
+        no_out && no_in
+    }
+
     /// number of edges in the Graph
     pub fn n_edges(&self) -> usize {
         self.edges.len()
@@ -243,6 +363,26 @@ impl Graph {
         }
     }
 
+    /// tests whether an edge is one-way, that is, the graph has no edge
+    /// connecting its destination vertex back to its source vertex.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge_id` - the edge to test
+    ///
+    /// # Returns
+    ///
+    /// true if the edge has no reverse counterpart, or an error if the id is missing
+    pub fn is_one_way(&self, edge_id: &EdgeId) -> Result<bool, NetworkError> {
+        let edge = self.get_edge(edge_id)?;
+        let has_reverse = self
+            .adj
+            .get(edge.dst_vertex_id.0)
+            .map(|out_links| out_links.iter().any(|(_, v)| *v == edge.src_vertex_id))
+            .unwrap_or_default();
+        Ok(!has_reverse)
+    }
+
     /// retrieve the source vertex id of an edge
     ///
     /// # Arguments
@@ -410,4 +550,320 @@ impl Graph {
             })
             .collect()
     }
+
+    /// computes summary statistics for this graph: vertex and edge counts,
+    /// in- and out-degree distributions, a histogram of edge lengths, and
+    /// the number of one-way and two-way edges. an edge is two-way if the
+    /// graph also contains an edge from its destination to its source.
+    ///
+    /// # Returns
+    ///
+    /// the statistics for this graph
+    pub fn statistics(&self) -> GraphStatistics {
+        let mut in_degree_distribution: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut out_degree_distribution: BTreeMap<usize, usize> = BTreeMap::new();
+        for in_links in self.rev.iter() {
+            *in_degree_distribution.entry(in_links.len()).or_default() += 1;
+        }
+        for out_links in self.adj.iter() {
+            *out_degree_distribution.entry(out_links.len()).or_default() += 1;
+        }
+
+        let mut edge_length_histogram = EdgeLengthBin::empty_histogram();
+        let mut n_two_way_edges = 0;
+        for edge in self.edges.iter() {
+            let distance = edge.distance.as_f64();
+            if let Some(bin) = edge_length_histogram
+                .iter_mut()
+                .find(|b| b.contains(distance))
+            {
+                bin.count += 1;
+            }
+            if !self.is_one_way(&edge.edge_id).unwrap_or(true) {
+                n_two_way_edges += 1;
+            }
+        }
+
+        GraphStatistics {
+            n_vertices: self.n_vertices(),
+            n_edges: self.n_edges(),
+            in_degree_distribution,
+            out_degree_distribution,
+            edge_length_histogram,
+            n_one_way_edges: self.n_edges() - n_two_way_edges,
+            n_two_way_edges,
+        }
+    }
+
+    /// computes a fingerprint of this graph from the ids, coordinates and
+    /// distances of its vertices and edges. the fingerprint is the same for
+    /// identical graph data and changes when any vertex or edge changes, so it
+    /// can tie a result to the graph that produced it.
+    ///
+    /// # Returns
+    ///
+    /// the fingerprint as 16 hexadecimal characters
+    pub fn fingerprint(&self) -> String {
+        let mut fingerprint = Fingerprint::default();
+        fingerprint.write_u64(self.n_vertices() as u64);
+        for vertex in self.vertices.iter() {
+            fingerprint.write_u64(vertex.vertex_id.0 as u64);
+            fingerprint.write(&vertex.x().to_bits().to_le_bytes());
+            fingerprint.write(&vertex.y().to_bits().to_le_bytes());
+        }
+        fingerprint.write_u64(self.n_edges() as u64);
+        for edge in self.edges.iter() {
+            fingerprint.write_u64(edge.edge_id.0 as u64);
+            fingerprint.write_u64(edge.src_vertex_id.0 as u64);
+            fingerprint.write_u64(edge.dst_vertex_id.0 as u64);
+            fingerprint.write_u64(edge.distance.as_f64().to_bits());
+        }
+        fingerprint.to_hex()
+    }
+
+    /// computes the exact diameter of this graph, the longest shortest path by
+    /// distance between any two vertices where one can reach the other. runs a
+    /// shortest path search from every vertex, so it is only practical for small
+    /// graphs; see [`Graph::approx_diameter`] for large graphs.
+    ///
+    /// # Returns
+    ///
+    /// the diameter, or None if no vertex can reach another
+    pub fn diameter(&self) -> Option<GraphDiameter> {
+        self.vertex_ids()
+            .filter_map(|src| {
+                self.farthest_vertex(&src, &Direction::Forward)
+                    .map(|(dst, distance)| (src, dst, distance))
+            })
+            .max_by_key(|(_, _, distance)| OrderedFloat(*distance))
+            .map(|(src, dst, distance)| GraphDiameter {
+                distance: Distance::new(distance),
+                src_vertex_id: src,
+                dst_vertex_id: dst,
+                exact: true,
+            })
+    }
+
+    /// approximates the diameter of this graph with the double sweep heuristic.
+    /// from each of `samples` start vertices, spread evenly over the vertex ids,
+    /// a forward search finds the farthest reachable vertex, and a reverse search
+    /// from that vertex finds the farthest vertex that can reach it. every sweep
+    /// measures a shortest path, so the result is a lower bound on the exact
+    /// diameter, and is often equal to it on road networks.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - the number of start vertices, each costing two searches
+    ///
+    /// # Returns
+    ///
+    /// the longest shortest path found, or None if no vertex can reach another
+    pub fn approx_diameter(&self, samples: usize) -> Option<GraphDiameter> {
+        let connected = self
+            .vertex_ids()
+            .filter(|v| !self.is_isolated(v))
+            .collect_vec();
+        if connected.is_empty() || samples == 0 {
+            return None;
+        }
+        let step = (connected.len() / samples).max(1);
+        let mut best: Option<(VertexId, VertexId, f64)> = None;
+        let mut update = |src: VertexId, dst: VertexId, distance: f64| match best {
+            Some((_, _, d)) if d >= distance => {}
+            _ => best = Some((src, dst, distance)),
+        };
+        for start in connected.iter().step_by(step).take(samples) {
+            if let Some((far, d1)) = self.farthest_vertex(start, &Direction::Forward) {
+                update(*start, far, d1);
+                if let Some((back, d2)) = self.farthest_vertex(&far, &Direction::Reverse) {
+                    update(back, far, d2);
+                }
+            }
+        }
+        best.map(|(src, dst, distance)| GraphDiameter {
+            distance: Distance::new(distance),
+            src_vertex_id: src,
+            dst_vertex_id: dst,
+            exact: false,
+        })
+    }
+
+    /// finds the vertex farthest by shortest path distance from a source vertex,
+    /// searching along out edges (Forward) or in edges (Reverse).
+    fn farthest_vertex(&self, source: &VertexId, direction: &Direction) -> Option<(VertexId, f64)> {
+        let mut distances: Vec<Option<f64>> = vec![None; self.n_vertices()];
+        let mut heap = BinaryHeap::from([Reverse((OrderedFloat(0.0), source.0))]);
+        distances[source.0] = Some(0.0);
+        let mut farthest: Option<(VertexId, f64)> = None;
+        while let Some(Reverse((OrderedFloat(distance), vertex))) = heap.pop() {
+            if distances[vertex].map(|d| d < distance).unwrap_or_default() {
+                continue;
+            }
+            if vertex != source.0 && farthest.map(|(_, d)| distance > d).unwrap_or(true) {
+                farthest = Some((VertexId(vertex), distance));
+            }
+            for edge_id in self.incident_edges_iter(&VertexId(vertex), direction) {
+                let (Ok(edge), Ok(next)) = (
+                    self.get_edge(edge_id),
+                    self.incident_vertex(edge_id, direction),
+                ) else {
+                    continue;
+                };
+                let tentative = distance + edge.distance.as_f64();
+                if distances[next.0].map(|d| tentative < d).unwrap_or(true) {
+                    distances[next.0] = Some(tentative);
+                    heap.push(Reverse((OrderedFloat(tentative), next.0)));
+                }
+            }
+        }
+        farthest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a small graph with a two-way pair between 0 and 1, and a one-way
+    /// loop 1 -> 2 -> 3 -> 1. vertex 4 is isolated.
+    fn build_fixture_graph() -> Graph {
+        let vertices = (0..5).map(|i| Vertex::new(i, 0.0, 0.0)).collect_vec();
+        let edges = vec![
+            Edge::new(0, 0, 1, 5.0),
+            Edge::new(1, 1, 0, 5.0),
+            Edge::new(2, 1, 2, 75.0),
+            Edge::new(3, 2, 3, 750.0),
+            Edge::new(4, 3, 1, 12000.0),
+        ];
+        let mut adj = vec![CompactOrderedHashMap::empty(); vertices.len()];
+        let mut rev = vec![CompactOrderedHashMap::empty(); vertices.len()];
+        for edge in &edges {
+            adj[edge.src_vertex_id.0].insert(edge.edge_id, edge.dst_vertex_id);
+            rev[edge.dst_vertex_id.0].insert(edge.edge_id, edge.src_vertex_id);
+        }
+        Graph {
+            adj: adj.into_boxed_slice(),
+            rev: rev.into_boxed_slice(),
+            edges: edges.into_boxed_slice(),
+            vertices: vertices.into_boxed_slice(),
+        }
+    }
+
+    #[test]
+    fn test_statistics() {
+        let stats = build_fixture_graph().statistics();
+        assert_eq!(stats.n_vertices, 5);
+        assert_eq!(stats.n_edges, 5);
+        assert_eq!(stats.n_two_way_edges, 2);
+        assert_eq!(stats.n_one_way_edges, 3);
+        // vertex 1 has in-degree 2 (from 0 and 3), vertex 4 has none
+        assert_eq!(
+            stats.in_degree_distribution,
+            BTreeMap::from([(0, 1), (1, 3), (2, 1)])
+        );
+        // vertex 1 has out-degree 2 (to 0 and 2), vertex 4 has none
+        assert_eq!(
+            stats.out_degree_distribution,
+            BTreeMap::from([(0, 1), (1, 3), (2, 1)])
+        );
+        let counts = stats
+            .edge_length_histogram
+            .iter()
+            .map(|b| b.count)
+            .collect_vec();
+        assert_eq!(counts, vec![2, 0, 1, 0, 1, 0, 1]);
+        assert_eq!(stats.edge_length_histogram.last().unwrap().upper, None);
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let graph = build_fixture_graph();
+        assert_eq!(graph.fingerprint(), build_fixture_graph().fingerprint());
+        assert_eq!(graph.fingerprint().len(), 16);
+
+        let mut longer = build_fixture_graph();
+        longer.edges[2] = Edge::new(2, 1, 2, 76.0);
+        assert_ne!(graph.fingerprint(), longer.fingerprint());
+
+        let mut moved = build_fixture_graph();
+        moved.vertices[4] = Vertex::new(4, 0.0, 0.001);
+        assert_ne!(graph.fingerprint(), moved.fingerprint());
+    }
+
+    #[test]
+    fn test_filter_edges() {
+        let mut graph = build_fixture_graph();
+        // dropping the two-way pair leaves vertex 0 without any edges
+        let summary = graph.filter_edges(|edge_id| edge_id.0 >= 2);
+        assert_eq!(summary.dropped_edges, 2);
+        assert_eq!(summary.dropped_vertices, 1);
+        assert!(!graph.contains_edge(&EdgeId(0)));
+        assert!(!graph.contains_edge(&EdgeId(1)));
+        assert!(graph.contains_edge(&EdgeId(2)));
+        assert!(graph.is_isolated(&VertexId(0)));
+        assert!(!graph.is_isolated(&VertexId(1)));
+        assert_eq!(graph.out_edges(&VertexId(1)), vec![EdgeId(2)]);
+        assert_eq!(graph.in_edges(&VertexId(1)), vec![EdgeId(4)]);
+        // every remaining adjacency entry refers to a kept edge
+        for vertex_id in graph.vertex_ids() {
+            for edge_id in graph.out_edges(&vertex_id) {
+                assert!(graph.contains_edge(&edge_id));
+                assert_eq!(graph.get_edge(&edge_id).unwrap().src_vertex_id, vertex_id);
+            }
+            for edge_id in graph.in_edges(&vertex_id) {
+                assert!(graph.contains_edge(&edge_id));
+                assert_eq!(graph.get_edge(&edge_id).unwrap().dst_vertex_id, vertex_id);
+            }
+        }
+        // ids are unchanged so attribute tables stay aligned
+        assert_eq!(graph.n_edges(), 5);
+        assert_eq!(graph.n_vertices(), 5);
+    }
+
+    #[test]
+    fn test_orient_vertices() {
+        // vertices written in latitude, longitude order
+        let mut graph = build_fixture_graph();
+        for vertex in graph.vertices.iter_mut() {
+            *vertex = Vertex::new(vertex.vertex_id.0, 39.738, -105.168);
+        }
+        let error = graph
+            .orient_vertices(&CoordinateOrientation::LonLat)
+            .unwrap_err();
+        assert!(error.to_string().contains("5 vertices"));
+        assert!(error.to_string().contains("look swapped"));
+
+        graph
+            .orient_vertices(&CoordinateOrientation::LatLon)
+            .unwrap();
+        assert_eq!(graph.vertices[0].x(), -105.168);
+        assert_eq!(graph.vertices[0].y(), 39.738);
+    }
+
+    #[test]
+    fn test_diameter() {
+        let graph = build_fixture_graph();
+        // the longest shortest path is 2 -> 3 -> 1 -> 0, as leaving vertex 2 in any
+        // direction requires the long edge back from 3 to 1
+        let exact = graph.diameter().unwrap();
+        assert_eq!(exact.distance, Distance::new(12755.0));
+        assert_eq!(exact.src_vertex_id, VertexId(2));
+        assert_eq!(exact.dst_vertex_id, VertexId(0));
+        assert!(exact.exact);
+
+        for samples in 1..5 {
+            let approx = graph.approx_diameter(samples).unwrap();
+            assert!(!approx.exact);
+            assert!(approx.distance <= exact.distance);
+            assert!(approx.distance > Distance::ZERO);
+        }
+        // sweeping from vertex 0 only finds 0 -> 1 -> 2 -> 3, while sweeping from
+        // every connected vertex includes vertex 2 and finds the exact diameter
+        assert_eq!(
+            graph.approx_diameter(1).unwrap().distance,
+            Distance::new(830.0)
+        );
+        assert_eq!(graph.approx_diameter(4).unwrap().distance, exact.distance);
+        assert!(graph.approx_diameter(0).is_none());
+    }
 }
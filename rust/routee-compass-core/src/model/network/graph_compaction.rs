@@ -0,0 +1,475 @@
+use super::{Edge, EdgeId, Graph, NetworkError, Vertex, VertexId};
+use crate::model::unit::{AsF64, Distance};
+use crate::util::compact_ordered_hash_map::CompactOrderedHashMap;
+use geo::LineString;
+use itertools::Itertools;
+
+/// a road network where chains of degree-2 vertices, such as the shape points of
+/// a curving road, are contracted into single edges. searches on the compacted
+/// graph settle far fewer vertices, and each compacted edge keeps the original
+/// edges it replaces so that routes can be reported on the original network.
+///
+/// a vertex is contracted when it only passes traffic through, that is, it has a
+/// single edge in and a single edge out to two other vertices (one-way), or a
+/// pair of edges in each direction to two other vertices (two-way). all other
+/// vertices are kept and renumbered in their original order. edges removed by a
+/// graph filter are not part of the compacted graph. a cycle of contracted
+/// vertices keeps the vertex at the start of its lowest edge id.
+///
+/// per-edge attributes are not part of the graph and must be merged onto the
+/// compacted edges along with it, see [`GraphCompaction::merge_by_distance`],
+/// [`GraphCompaction::merge_speeds`] and [`GraphCompaction::merge_geometries`].
+pub struct GraphCompaction {
+    pub graph: Graph,
+    /// for each compacted edge, the original edges it replaces in travel order
+    pub original_edges: Box<[Box<[EdgeId]>]>,
+    /// for each compacted vertex, the id of the original vertex
+    pub original_vertices: Box<[VertexId]>,
+}
+
+impl GraphCompaction {
+    /// contracts every degree-2 vertex of the graph
+    pub fn new(graph: &Graph) -> Result<GraphCompaction, NetworkError> {
+        GraphCompaction::new_with_splits(graph, |_, _| true)
+    }
+
+    /// contracts the degree-2 vertices of the graph where each pair of edges
+    /// passing through the vertex may be merged.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - the graph to compact
+    /// * `can_merge` - true if an edge entering a vertex may be merged with the
+    ///   edge leaving it, for example when both have the same road class. a
+    ///   vertex is kept wherever this is false.
+    pub fn new_with_splits(
+        graph: &Graph,
+        can_merge: impl Fn(&EdgeId, &EdgeId) -> bool,
+    ) -> Result<GraphCompaction, NetworkError> {
+        let mut junction = graph
+            .vertex_ids()
+            .map(|v| !is_pass_through(&v, graph, &can_merge))
+            .collect_vec();
+        let mut covered = vec![false; graph.n_edges()];
+        let mut chains: Vec<Vec<EdgeId>> = vec![];
+
+        // chains start at junction vertices. edges left over afterward belong to
+        // cycles without a junction, which are broken at the source of their
+        // lowest edge id.
+        for promote in [false, true] {
+            for edge in graph.edges.iter() {
+                if covered[edge.edge_id.0] || !graph.contains_edge(&edge.edge_id) {
+                    continue;
+                }
+                if promote {
+                    junction[edge.src_vertex_id.0] = true;
+                } else if !junction[edge.src_vertex_id.0] {
+                    continue;
+                }
+                let chain = trace_chain(edge, graph, &junction)?;
+                for edge_id in chain.iter() {
+                    covered[edge_id.0] = true;
+                }
+                chains.push(chain);
+            }
+        }
+
+        let mut new_vertex_ids: Vec<Option<VertexId>> = vec![None; graph.n_vertices()];
+        let mut vertices = vec![];
+        let mut original_vertices = vec![];
+        for vertex in graph.vertices.iter() {
+            if junction[vertex.vertex_id.0] {
+                let vertex_id = VertexId(vertices.len());
+                new_vertex_ids[vertex.vertex_id.0] = Some(vertex_id);
+                vertices.push(Vertex {
+                    vertex_id,
+                    coordinate: vertex.coordinate,
+                });
+                original_vertices.push(vertex.vertex_id);
+            }
+        }
+        let new_vertex_id = |v: VertexId| {
+            new_vertex_ids[v.0].ok_or_else(|| {
+                NetworkError::InternalError(format!("chain ends at contracted vertex {}", v))
+            })
+        };
+
+        let mut edges = Vec::with_capacity(chains.len());
+        let mut adj = vec![CompactOrderedHashMap::empty(); vertices.len()];
+        let mut rev = vec![CompactOrderedHashMap::empty(); vertices.len()];
+        for chain in chains.iter() {
+            let (first, last) = match (chain.first(), chain.last()) {
+                (Some(first), Some(last)) => (first, last),
+                _ => return Err(NetworkError::InternalError(String::from("empty chain"))),
+            };
+            let distance = chain
+                .iter()
+                .map(|e| graph.get_edge(e).map(|e| e.distance.as_f64()))
+                .sum::<Result<f64, _>>()?;
+            let edge = Edge {
+                edge_id: EdgeId(edges.len()),
+                src_vertex_id: new_vertex_id(graph.src_vertex_id(first)?)?,
+                dst_vertex_id: new_vertex_id(graph.dst_vertex_id(last)?)?,
+                distance: Distance::new(distance),
+            };
+            adj[edge.src_vertex_id.0].insert(edge.edge_id, edge.dst_vertex_id);
+            rev[edge.dst_vertex_id.0].insert(edge.edge_id, edge.src_vertex_id);
+            edges.push(edge);
+        }
+
+        let compacted = Graph {
+            adj: adj.into_boxed_slice(),
+            rev: rev.into_boxed_slice(),
+            edges: edges.into_boxed_slice(),
+            vertices: vertices.into_boxed_slice(),
+        };
+        Ok(GraphCompaction {
+            graph: compacted,
+            original_edges: chains.into_iter().map(|c| c.into_boxed_slice()).collect(),
+            original_vertices: original_vertices.into_boxed_slice(),
+        })
+    }
+
+    /// the original edges of a route on the compacted graph, in travel order
+    pub fn original_route(&self, route: &[EdgeId]) -> Result<Vec<EdgeId>, NetworkError> {
+        let mut original = vec![];
+        for edge_id in route.iter() {
+            let edges = self
+                .original_edges
+                .get(edge_id.0)
+                .ok_or(NetworkError::EdgeNotFound(*edge_id))?;
+            original.extend(edges.iter());
+        }
+        Ok(original)
+    }
+
+    /// merges a numeric attribute of the original edges, such as grade, into
+    /// the distance-weighted mean over each compacted edge. compacted edges
+    /// with no length take the plain mean.
+    ///
+    /// # Arguments
+    ///
+    /// * `original` - the graph that was compacted
+    /// * `values` - the attribute of each original edge, in edge id order
+    pub fn merge_by_distance(
+        &self,
+        original: &Graph,
+        values: &[f64],
+    ) -> Result<Vec<f64>, NetworkError> {
+        self.merge_with(original, values, |parts| {
+            let total = parts.iter().map(|(d, _)| d).sum::<f64>();
+            if total > 0.0 {
+                parts.iter().map(|(d, v)| d * v).sum::<f64>() / total
+            } else {
+                parts.iter().map(|(_, v)| v).sum::<f64>() / parts.len() as f64
+            }
+        })
+    }
+
+    /// merges the speeds of the original edges so that traversing a compacted
+    /// edge takes as long as traversing the edges it replaces, which is the
+    /// distance-weighted harmonic mean of the speeds. edges without a positive
+    /// speed cannot be traversed, so a compacted edge that includes one has
+    /// a speed of zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `original` - the graph that was compacted
+    /// * `speeds` - the speed of each original edge, in edge id order
+    pub fn merge_speeds(&self, original: &Graph, speeds: &[f64]) -> Result<Vec<f64>, NetworkError> {
+        self.merge_with(original, speeds, |parts| {
+            if parts.iter().any(|(_, s)| *s <= 0.0) {
+                return 0.0;
+            }
+            let total = parts.iter().map(|(d, _)| d).sum::<f64>();
+            if total > 0.0 {
+                total / parts.iter().map(|(d, s)| d / s).sum::<f64>()
+            } else {
+                parts.iter().map(|(_, s)| s).sum::<f64>() / parts.len() as f64
+            }
+        })
+    }
+
+    /// joins the geometries of the original edges into a geometry for each
+    /// compacted edge, dropping the repeated point where consecutive edges meet
+    pub fn merge_geometries(
+        &self,
+        geometries: &[LineString<f32>],
+    ) -> Result<Vec<LineString<f32>>, NetworkError> {
+        self.original_edges
+            .iter()
+            .map(|chain| {
+                let mut coords = vec![];
+                for edge_id in chain.iter() {
+                    let geometry = geometries
+                        .get(edge_id.0)
+                        .ok_or(NetworkError::EdgeNotFound(*edge_id))?;
+                    let skip = usize::from(coords.last() == geometry.0.first());
+                    coords.extend(geometry.0.iter().skip(skip));
+                }
+                Ok(LineString::new(coords))
+            })
+            .collect()
+    }
+
+    /// combines the (distance, value) pairs of the original edges of each
+    /// compacted edge into a single value
+    fn merge_with(
+        &self,
+        original: &Graph,
+        values: &[f64],
+        merge: impl Fn(&[(f64, f64)]) -> f64,
+    ) -> Result<Vec<f64>, NetworkError> {
+        if values.len() != original.n_edges() {
+            return Err(NetworkError::DatasetError(format!(
+                "attribute has {} values but the graph has {} edges",
+                values.len(),
+                original.n_edges()
+            )));
+        }
+        self.original_edges
+            .iter()
+            .map(|chain| {
+                let parts = chain
+                    .iter()
+                    .map(|e| {
+                        let distance = original.get_edge(e)?.distance.as_f64();
+                        Ok((distance, values[e.0]))
+                    })
+                    .collect::<Result<Vec<_>, NetworkError>>()?;
+                Ok(merge(&parts))
+            })
+            .collect()
+    }
+}
+
+/// true if a vertex only passes traffic between two other vertices, with one
+/// edge in and one out (one-way) or an edge in each direction to each of them
+/// (two-way), and each pair of edges passing through it may be merged
+fn is_pass_through(
+    vertex_id: &VertexId,
+    graph: &Graph,
+    can_merge: &impl Fn(&EdgeId, &EdgeId) -> bool,
+) -> bool {
+    let out_edges = graph.out_edges_iter(vertex_id).cloned().collect_vec();
+    let in_edges = graph.in_edges_iter(vertex_id).cloned().collect_vec();
+    let neighbor = |edge_id: &EdgeId, src: bool| {
+        let edge = graph.get_edge(edge_id).ok()?;
+        let v = if src {
+            edge.src_vertex_id
+        } else {
+            edge.dst_vertex_id
+        };
+        Some(v).filter(|v| v != vertex_id)
+    };
+    let out_neighbors = out_edges.iter().map(|e| neighbor(e, false)).collect_vec();
+    let in_neighbors = in_edges.iter().map(|e| neighbor(e, true)).collect_vec();
+    match (
+        in_edges.as_slice(),
+        out_edges.as_slice(),
+        in_neighbors.as_slice(),
+        out_neighbors.as_slice(),
+    ) {
+        ([e_in], [e_out], [Some(u)], [Some(w)]) => u != w && can_merge(e_in, e_out),
+        ([in_a, in_b], [out_a, out_b], [Some(u_a), Some(u_b)], [Some(w_a), Some(w_b)]) => {
+            if u_a == u_b || w_a == w_b {
+                return false;
+            }
+            // pair each edge in with the edge out to the other neighbor
+            let (out_from_a, out_from_b) = if w_a == u_b && w_b == u_a {
+                (out_a, out_b)
+            } else if w_a == u_a && w_b == u_b {
+                (out_b, out_a)
+            } else {
+                return false;
+            };
+            can_merge(in_a, out_from_a) && can_merge(in_b, out_from_b)
+        }
+        _ => false,
+    }
+}
+
+/// follows edges from the start edge through pass-through vertices until a
+/// junction is reached, returning the edges traversed
+fn trace_chain(
+    start: &Edge,
+    graph: &Graph,
+    junction: &[bool],
+) -> Result<Vec<EdgeId>, NetworkError> {
+    let mut chain = vec![start.edge_id];
+    let mut prev = start.src_vertex_id;
+    let mut current = start.dst_vertex_id;
+    while !junction[current.0] {
+        // a pass-through vertex has exactly one edge out that does not turn back
+        let next = graph
+            .out_edges_iter(&current)
+            .map(|e| graph.get_edge(e))
+            .filter_ok(|e| e.dst_vertex_id != prev)
+            .next()
+            .ok_or_else(|| {
+                NetworkError::InternalError(format!("no edge continues through vertex {}", current))
+            })??;
+        chain.push(next.edge_id);
+        prev = current;
+        current = next.dst_vertex_id;
+    }
+    Ok(chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::search::util::test_search_instance::{self, build_search_instance};
+    use crate::algorithm::search::{a_star, backtrack, Direction, SearchInstance};
+    use crate::model::unit::Cost;
+
+    /// a network of junctions 0, 1 and 2 joined by shape points. vertices 3 and 4
+    /// are shape points of a two-way road from 0 to 1, vertex 5 is a shape point
+    /// of a one-way road from 1 to 2, and vertex 6 is a one-way shortcut from 0
+    /// to 2 that is a junction because it also has a dead end spur to vertex 7.
+    /// vertices 8, 9 and 10 form a one-way cycle of shape points.
+    fn build_graph() -> Graph {
+        let vertices = (0..11)
+            .map(|i| Vertex::new(i, i as f32 * 0.01, 0.0))
+            .collect_vec();
+        let links = [
+            (0, 3, 1.0, true),
+            (3, 4, 2.0, true),
+            (4, 1, 3.0, true),
+            (1, 5, 4.0, false),
+            (5, 2, 5.0, false),
+            (0, 6, 4.0, false),
+            (6, 2, 4.0, false),
+            (6, 7, 1.0, true),
+            (2, 0, 20.0, false),
+            (8, 9, 1.0, false),
+            (9, 10, 1.0, false),
+            (10, 8, 1.0, false),
+        ];
+        let mut edges = vec![];
+        for (src, dst, distance, two_way) in links {
+            edges.push(Edge::new(edges.len(), src, dst, distance));
+            if two_way {
+                edges.push(Edge::new(edges.len(), dst, src, distance));
+            }
+        }
+        test_search_instance::build_graph(vertices, edges)
+    }
+
+    /// the route between two vertices and its cost, if one exists
+    fn shortest_route(
+        source: VertexId,
+        target: VertexId,
+        si: &SearchInstance,
+    ) -> Option<(Vec<EdgeId>, f64)> {
+        let result = a_star::run_vertex_oriented(
+            source,
+            Some(target),
+            &Direction::Forward,
+            Some(Cost::ZERO),
+            si,
+        )
+        .ok()?;
+        let route = backtrack::vertex_oriented_route(source, target, &result.tree).ok()?;
+        let cost = route.iter().map(|e| e.total_cost().as_f64()).sum();
+        Some((route.iter().map(|e| e.edge_id).collect(), cost))
+    }
+
+    #[test]
+    fn test_compaction() {
+        let graph = build_graph();
+        let compaction = GraphCompaction::new(&graph).unwrap();
+        let original_vertices = compaction.original_vertices.to_vec();
+        assert_eq!(
+            original_vertices,
+            vec![
+                VertexId(0),
+                VertexId(1),
+                VertexId(2),
+                VertexId(6),
+                VertexId(7),
+                VertexId(8)
+            ]
+        );
+        // each two-way road becomes a pair of edges, and every original edge is
+        // part of exactly one compacted edge
+        assert_eq!(compaction.graph.n_edges(), 9);
+        let mut covered = compaction
+            .original_edges
+            .iter()
+            .flat_map(|c| c.iter().map(|e| e.0))
+            .collect_vec();
+        covered.sort();
+        assert_eq!(covered, (0..graph.n_edges()).collect_vec());
+
+        // routes between the kept vertices have the same distance, and expand to
+        // the route found on the original graph
+        let original_si = build_search_instance(build_graph());
+        let compacted_si = build_search_instance(GraphCompaction::new(&graph).unwrap().graph);
+        for (src, dst) in (0..6).cartesian_product(0..6).filter(|(s, d)| s != d) {
+            let original =
+                shortest_route(original_vertices[src], original_vertices[dst], &original_si);
+            let compacted = shortest_route(VertexId(src), VertexId(dst), &compacted_si);
+            match (original, compacted) {
+                (None, None) => {}
+                (Some((original_route, original_cost)), Some((route, cost))) => {
+                    assert_eq!(cost, original_cost);
+                    assert_eq!(compaction.original_route(&route).unwrap(), original_route);
+                }
+                (original, compacted) => {
+                    panic!("{} to {}: {:?} vs {:?}", src, dst, original, compacted)
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_compaction_splits() {
+        let graph = build_graph();
+        // keep vertex 4 by refusing to merge through it
+        let split = [EdgeId(2), EdgeId(3), EdgeId(4), EdgeId(5)];
+        let compaction = GraphCompaction::new_with_splits(&graph, |a, b| {
+            !(split.contains(a) && split.contains(b))
+        })
+        .unwrap();
+        assert!(compaction.original_vertices.contains(&VertexId(4)));
+        assert!(!compaction.original_vertices.contains(&VertexId(3)));
+        assert_eq!(compaction.graph.n_edges(), 11);
+    }
+
+    #[test]
+    fn test_merge_attributes() {
+        let graph = build_graph();
+        let compaction = GraphCompaction::new(&graph).unwrap();
+        let one_way = compaction
+            .original_edges
+            .iter()
+            .position(|c| c.as_ref() == [EdgeId(6), EdgeId(7)])
+            .unwrap();
+
+        // edges 6 and 7 have lengths 4 and 5
+        let mut values = vec![0.0; graph.n_edges()];
+        values[6] = 10.0;
+        values[7] = 1.0;
+        let merged = compaction.merge_by_distance(&graph, &values).unwrap();
+        assert_eq!(merged[one_way], (4.0 * 10.0 + 5.0 * 1.0) / 9.0);
+        let speeds = compaction.merge_speeds(&graph, &values).unwrap();
+        assert_eq!(speeds[one_way], 9.0 / (4.0 / 10.0 + 5.0 / 1.0));
+        assert_eq!(speeds[0], 0.0);
+        assert!(compaction.merge_speeds(&graph, &values[1..]).is_err());
+
+        let geometries = graph
+            .edges
+            .iter()
+            .map(|e| {
+                let src = graph.vertices[e.src_vertex_id.0].coordinate.0;
+                let dst = graph.vertices[e.dst_vertex_id.0].coordinate.0;
+                LineString::new(vec![src, dst])
+            })
+            .collect_vec();
+        let merged = compaction.merge_geometries(&geometries).unwrap();
+        let xs = merged[one_way].0.iter().map(|c| c.x).collect_vec();
+        let expected = [1, 5, 2].map(|v| graph.vertices[v].x());
+        assert_eq!(xs, expected.to_vec());
+    }
+}
@@ -0,0 +1,34 @@
+use super::VertexId;
+use crate::model::unit::{AsF64, Distance};
+use serde::Serialize;
+use std::fmt::Display;
+
+/// the longest shortest path found in a `Graph`, by distance. paths only
+/// count between vertices where one can reach the other.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct GraphDiameter {
+    pub distance: Distance,
+    pub src_vertex_id: VertexId,
+    pub dst_vertex_id: VertexId,
+    /// true if every pair of vertices was considered, false if the diameter
+    /// is an approximation, which is a lower bound on the exact diameter
+    pub exact: bool,
+}
+
+impl Display for GraphDiameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = if self.exact {
+            "diameter"
+        } else {
+            "approximate diameter"
+        };
+        writeln!(
+            f,
+            "{}: {} from vertex {} to vertex {}",
+            kind,
+            self.distance.as_f64(),
+            self.src_vertex_id,
+            self.dst_vertex_id
+        )
+    }
+}
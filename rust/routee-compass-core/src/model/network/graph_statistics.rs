@@ -0,0 +1,84 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+/// upper bounds of the edge length histogram bins, in the units of the
+/// graph's edge distances (typically meters). a final, unbounded bin
+/// collects all edges at least as long as the last bound.
+pub const EDGE_LENGTH_BIN_BOUNDS: [f64; 6] = [10.0, 50.0, 100.0, 500.0, 1000.0, 5000.0];
+
+/// summary statistics describing the topology of a `Graph`, used to
+/// sanity-check a road network dataset.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct GraphStatistics {
+    pub n_vertices: usize,
+    pub n_edges: usize,
+    /// for each in-degree, the number of vertices with that in-degree
+    pub in_degree_distribution: BTreeMap<usize, usize>,
+    /// for each out-degree, the number of vertices with that out-degree
+    pub out_degree_distribution: BTreeMap<usize, usize>,
+    pub edge_length_histogram: Vec<EdgeLengthBin>,
+    /// edges with no edge in the opposite direction between the same vertices
+    pub n_one_way_edges: usize,
+    /// edges with at least one edge in the opposite direction between the same vertices
+    pub n_two_way_edges: usize,
+}
+
+/// count of edges with distance in the range `[lower, upper)`. the last bin
+/// of a histogram has no upper bound.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct EdgeLengthBin {
+    pub lower: f64,
+    pub upper: Option<f64>,
+    pub count: usize,
+}
+
+impl EdgeLengthBin {
+    /// creates an empty histogram using [`EDGE_LENGTH_BIN_BOUNDS`]
+    pub fn empty_histogram() -> Vec<EdgeLengthBin> {
+        let lowers = std::iter::once(0.0).chain(EDGE_LENGTH_BIN_BOUNDS.iter().cloned());
+        let uppers = EDGE_LENGTH_BIN_BOUNDS
+            .iter()
+            .cloned()
+            .map(Some)
+            .chain(std::iter::once(None));
+        lowers
+            .zip(uppers)
+            .map(|(lower, upper)| EdgeLengthBin {
+                lower,
+                upper,
+                count: 0,
+            })
+            .collect()
+    }
+
+    /// true if the distance falls within the range of this bin
+    pub fn contains(&self, distance: f64) -> bool {
+        distance >= self.lower && self.upper.map(|u| distance < u).unwrap_or(true)
+    }
+}
+
+impl Display for GraphStatistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "vertices: {}", self.n_vertices)?;
+        writeln!(f, "edges: {}", self.n_edges)?;
+        writeln!(f, "one-way edges: {}", self.n_one_way_edges)?;
+        writeln!(f, "two-way edges: {}", self.n_two_way_edges)?;
+        writeln!(f, "in-degree distribution (degree: vertex count):")?;
+        for (degree, count) in self.in_degree_distribution.iter() {
+            writeln!(f, "  {}: {}", degree, count)?;
+        }
+        writeln!(f, "out-degree distribution (degree: vertex count):")?;
+        for (degree, count) in self.out_degree_distribution.iter() {
+            writeln!(f, "  {}: {}", degree, count)?;
+        }
+        writeln!(f, "edge length histogram (range: edge count):")?;
+        for bin in self.edge_length_histogram.iter() {
+            match bin.upper {
+                Some(upper) => writeln!(f, "  [{}, {}): {}", bin.lower, upper, bin.count)?,
+                None => writeln!(f, "  [{}, inf): {}", bin.lower, bin.count)?,
+            }
+        }
+        Ok(())
+    }
+}
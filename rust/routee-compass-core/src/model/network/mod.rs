@@ -1,13 +1,20 @@
 pub mod edge;
+pub mod edge_filter;
 pub mod edge_id;
 pub mod graph;
+pub mod graph_compaction;
+pub mod graph_diameter;
+pub mod graph_statistics;
 pub mod network_error;
 pub mod vertex;
 pub mod vertex_id;
 
 pub use edge::Edge;
+pub use edge_filter::{EdgeFilter, EdgeFilterSummary};
 pub use edge_id::EdgeId;
 pub use graph::Graph;
+pub use graph_compaction::GraphCompaction;
+pub use graph_statistics::GraphStatistics;
 pub use network_error::NetworkError;
 pub use vertex::Vertex;
 pub use vertex_id::VertexId;
@@ -16,6 +16,8 @@ pub enum StateModelError {
     UnexpectedFeatureType(String, String),
     #[error("expected feature unit to be {0} but found {1}")]
     UnexpectedFeatureUnit(String, String),
+    #[error("{0} requires state feature '{1}' which is not provided upstream, found [{2}]")]
+    MissingInputFeature(String, String, String),
     #[error("{0}")]
     BuildError(String),
     #[error("{0}")]
@@ -1,5 +1,7 @@
+mod termination_limits;
 mod termination_model;
 mod termination_model_error;
 
+pub use termination_limits::TerminationLimits;
 pub use termination_model::TerminationModel;
 pub use termination_model_error::TerminationModelError;
@@ -0,0 +1,15 @@
+use std::time::Duration;
+
+/// termination limits set by a query in place of the configured ones. limits
+/// left as None keep their configured values.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TerminationLimits {
+    /// runtime limit for the search
+    pub runtime: Option<Duration>,
+    /// how many iterations to run between runtime limit checks
+    pub frequency: Option<u64>,
+    /// limit on the number of search iterations
+    pub iterations: Option<u64>,
+    /// limit on the size of the search tree
+    pub solution_size: Option<usize>,
+}
@@ -1,3 +1,4 @@
+use super::termination_limits::TerminationLimits;
 use super::termination_model_error::TerminationModelError;
 use crate::util::duration_extension::DurationExtension;
 use serde::Deserialize;
@@ -6,7 +7,7 @@ use std::time::{Duration, Instant};
 /// the termination model for the application should be evaluated at the top of each iteration
 /// of a search. if it returns true, an error response should be created for the user using the
 /// explain method.
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub enum TerminationModel {
     /// terminates a query if the runtime exceeds some limit.
     /// only checks at some provided iteration frequency, since the computation is expensive.
@@ -23,7 +24,83 @@ pub enum TerminationModel {
     Combined { models: Vec<TerminationModel> },
 }
 
+/// runtime check frequency used when a query adds a runtime limit that is not configured
+pub const DEFAULT_RUNTIME_FREQUENCY: u64 = 1_000;
+
 impl TerminationModel {
+    /// builds a copy of this model with the limits set by a query. limits that
+    /// match a configured model replace its limit, wherever it is nested, and
+    /// limits with no configured counterpart are combined with this model.
+    pub fn with_limits(
+        &self,
+        limits: &TerminationLimits,
+    ) -> Result<TerminationModel, TerminationModelError> {
+        let mut model = self.clone();
+        let mut unmatched = limits.clone();
+        model.apply_limits(limits, &mut unmatched);
+
+        let mut models = vec![];
+        if let Some(limit) = unmatched.runtime {
+            models.push(TerminationModel::QueryRuntimeLimit {
+                limit,
+                frequency: limits.frequency.unwrap_or(DEFAULT_RUNTIME_FREQUENCY),
+            });
+        } else if let Some(frequency) = unmatched.frequency {
+            return Err(TerminationModelError::BuildError(format!(
+                "runtime check frequency {} set without a runtime limit",
+                frequency
+            )));
+        }
+        if let Some(limit) = unmatched.iterations {
+            models.push(TerminationModel::IterationsLimit { limit });
+        }
+        if let Some(limit) = unmatched.solution_size {
+            models.push(TerminationModel::SolutionSizeLimit { limit });
+        }
+
+        if models.is_empty() {
+            Ok(model)
+        } else {
+            models.insert(0, model);
+            Ok(TerminationModel::Combined { models })
+        }
+    }
+
+    /// replaces the limits of this model and any nested models, clearing each
+    /// limit in `unmatched` once it has been applied.
+    fn apply_limits(&mut self, limits: &TerminationLimits, unmatched: &mut TerminationLimits) {
+        use TerminationModel as T;
+        match self {
+            T::QueryRuntimeLimit { limit, frequency } => {
+                if let Some(l) = limits.runtime {
+                    *limit = l;
+                    unmatched.runtime = None;
+                }
+                if let Some(f) = limits.frequency {
+                    *frequency = f;
+                    unmatched.frequency = None;
+                }
+            }
+            T::SolutionSizeLimit { limit } => {
+                if let Some(l) = limits.solution_size {
+                    *limit = l;
+                    unmatched.solution_size = None;
+                }
+            }
+            T::IterationsLimit { limit } => {
+                if let Some(l) = limits.iterations {
+                    *limit = l;
+                    unmatched.iterations = None;
+                }
+            }
+            T::Combined { models } => {
+                for m in models.iter_mut() {
+                    m.apply_limits(limits, unmatched);
+                }
+            }
+        }
+    }
+
     /// Tests if the search should terminate.
     pub fn test(
         &self,
@@ -43,6 +120,9 @@ impl TerminationModel {
                         iterations
                     )))
                 }
+                Some(msg) if self.exceeds_runtime_limit(start_time, iterations) => {
+                    return Err(TerminationModelError::QueryTimedOut(msg))
+                }
                 Some(msg) => return Err(TerminationModelError::QueryTerminated(msg)),
             }
         }
@@ -76,6 +156,21 @@ impl TerminationModel {
         }
     }
 
+    /// tests whether any runtime limit in this model has been exceeded, in order
+    /// to distinguish a timeout from other search budgets.
+    pub fn exceeds_runtime_limit(&self, start_time: &Instant, iteration: u64) -> bool {
+        use TerminationModel as T;
+        match self {
+            T::QueryRuntimeLimit { .. } => self
+                .terminate_search(start_time, 0, iteration)
+                .unwrap_or(false),
+            T::Combined { models } => models
+                .iter()
+                .any(|m| m.exceeds_runtime_limit(start_time, iteration)),
+            _ => false,
+        }
+    }
+
     /// this method will a string explaining why a model terminated. if the
     /// conditions do not merit termination, then the result will be None.
     pub fn explain_termination(
@@ -131,6 +226,7 @@ mod tests {
     use std::time::{Duration, Instant};
 
     use super::TerminationModel as T;
+    use crate::model::termination::{TerminationLimits, TerminationModelError};
 
     #[test]
     fn test_within_runtime_limit() {
@@ -172,6 +268,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_error_distinguishes_timeout() {
+        let start_time = Instant::now() - Duration::from_secs(3);
+        let runtime = T::QueryRuntimeLimit {
+            limit: Duration::from_secs(2),
+            frequency: 1,
+        };
+        let iterations = T::IterationsLimit { limit: 5 };
+        assert!(matches!(
+            runtime.test(&start_time, 0, 1),
+            Err(TerminationModelError::QueryTimedOut(_))
+        ));
+        assert!(matches!(
+            iterations.test(&start_time, 0, 5),
+            Err(TerminationModelError::QueryTerminated(_))
+        ));
+        let combined = T::Combined {
+            models: vec![iterations, runtime],
+        };
+        assert!(matches!(
+            combined.test(&start_time, 0, 5),
+            Err(TerminationModelError::QueryTimedOut(_))
+        ));
+    }
+
     #[test]
     fn test_iterations_limit() {
         let m = T::IterationsLimit { limit: 5 };
@@ -270,4 +391,47 @@ mod tests {
         );
         assert_eq!(msg, expected);
     }
+
+    #[test]
+    fn test_with_limits() {
+        let start_time = Instant::now() - Duration::from_secs(3);
+        let configured = T::QueryRuntimeLimit {
+            limit: Duration::from_secs(600),
+            frequency: 100,
+        };
+        assert!(configured.test(&start_time, 0, 0).is_ok());
+
+        // a tighter runtime limit replaces the configured one and keeps its frequency
+        let limits = TerminationLimits {
+            runtime: Some(Duration::from_secs(2)),
+            ..Default::default()
+        };
+        let tight = configured.with_limits(&limits).unwrap();
+        assert!(matches!(tight, T::QueryRuntimeLimit { frequency: 100, .. }));
+        assert!(matches!(
+            tight.test(&start_time, 0, 0),
+            Err(TerminationModelError::QueryTimedOut(_))
+        ));
+        assert!(configured.test(&start_time, 0, 0).is_ok());
+
+        // an iteration limit with no configured counterpart is combined with the model
+        let limits = TerminationLimits {
+            iterations: Some(5),
+            ..Default::default()
+        };
+        let combined = configured.with_limits(&limits).unwrap();
+        assert!(combined.test(&start_time, 0, 3).is_ok());
+        assert!(matches!(
+            combined.test(&start_time, 0, 5),
+            Err(TerminationModelError::QueryTerminated(_))
+        ));
+
+        // a check frequency only applies to a runtime limit
+        let no_runtime = T::IterationsLimit { limit: 5 };
+        let limits = TerminationLimits {
+            frequency: Some(10),
+            ..Default::default()
+        };
+        assert!(no_runtime.with_limits(&limits).is_err());
+    }
 }
@@ -2,6 +2,10 @@
 pub enum TerminationModelError {
     #[error("query terminated due to {0}")]
     QueryTerminated(String),
+    #[error("query timed out due to {0}")]
+    QueryTimedOut(String),
+    #[error("failed to build termination model: {0}")]
+    BuildError(String),
     #[error("termination model runtime error {0}")]
     RuntimeError(String),
 }
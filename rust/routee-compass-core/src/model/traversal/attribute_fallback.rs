@@ -0,0 +1,192 @@
+use super::TraversalModelError;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// a level of a fallback chain supplying per-edge attribute values, such as
+/// speeds or grades, for edges that have no value in the primary table.
+#[derive(Clone, Debug)]
+pub enum AttributeFallback<T> {
+    /// a default value for each road class, with the road class of each edge
+    /// indexed by edge id
+    RoadClass {
+        road_classes: Box<[u8]>,
+        defaults: HashMap<u8, T>,
+    },
+    /// a single default value for every edge
+    Global(T),
+}
+
+/// the level of a fallback chain that supplied an edge's attribute value
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeSource {
+    Table,
+    RoadClass,
+    Global,
+}
+
+impl Display for AttributeSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            AttributeSource::Table => "table",
+            AttributeSource::RoadClass => "road_class",
+            AttributeSource::Global => "global",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl<T: Copy> AttributeFallback<T> {
+    /// the value this level supplies for an edge, if any
+    pub fn get(&self, edge_index: usize) -> Option<T> {
+        match self {
+            AttributeFallback::RoadClass {
+                road_classes,
+                defaults,
+            } => road_classes
+                .get(edge_index)
+                .and_then(|road_class| defaults.get(road_class))
+                .cloned(),
+            AttributeFallback::Global(value) => Some(*value),
+        }
+    }
+
+    pub fn source(&self) -> AttributeSource {
+        match self {
+            AttributeFallback::RoadClass { .. } => AttributeSource::RoadClass,
+            AttributeFallback::Global(_) => AttributeSource::Global,
+        }
+    }
+
+    /// number of edges this level has information about, if it is limited
+    fn n_edges(&self) -> Option<usize> {
+        match self {
+            AttributeFallback::RoadClass { road_classes, .. } => Some(road_classes.len()),
+            AttributeFallback::Global(_) => None,
+        }
+    }
+}
+
+/// an ordered chain of fallbacks consulted, after the primary table, for
+/// edges missing an attribute value. the first level that supplies a value
+/// is used.
+#[derive(Clone, Debug, Default)]
+pub struct AttributeFallbackChain<T> {
+    pub fallbacks: Vec<AttributeFallback<T>>,
+    /// if true, the source of each edge's value is kept for diagnostics
+    pub record_source: bool,
+}
+
+/// per-edge attribute values after applying a fallback chain
+pub struct ResolvedAttribute<T> {
+    pub values: Box<[T]>,
+    /// the source of each value, if recorded
+    pub sources: Option<Box<[AttributeSource]>>,
+}
+
+impl<T: Copy> AttributeFallbackChain<T> {
+    /// fills the missing values of a table by consulting the fallbacks in order.
+    /// the result covers every edge known to the table or to a road class
+    /// fallback. the number of values taken from each level is logged.
+    ///
+    /// # Arguments
+    ///
+    /// * `attribute` - name of the attribute, for logging and errors
+    /// * `table` - the primary per-edge table, with None for missing values
+    ///
+    /// # Returns
+    ///
+    /// the resolved values, or an error if an edge has no value at any level
+    pub fn resolve(
+        &self,
+        attribute: &str,
+        table: &[Option<T>],
+    ) -> Result<ResolvedAttribute<T>, TraversalModelError> {
+        let n_edges = self
+            .fallbacks
+            .iter()
+            .filter_map(|f| f.n_edges())
+            .fold(table.len(), usize::max);
+        let mut values = Vec::with_capacity(n_edges);
+        let mut sources = Vec::with_capacity(n_edges);
+        for edge_index in 0..n_edges {
+            let from_table = table
+                .get(edge_index)
+                .cloned()
+                .flatten()
+                .map(|v| (v, AttributeSource::Table));
+            let (value, source) = from_table
+                .or_else(|| {
+                    self.fallbacks
+                        .iter()
+                        .find_map(|f| f.get(edge_index).map(|v| (v, f.source())))
+                })
+                .ok_or_else(|| {
+                    TraversalModelError::BuildError(format!(
+                        "no {} found for edge {} in the table or any fallback",
+                        attribute, edge_index
+                    ))
+                })?;
+            values.push(value);
+            sources.push(source);
+        }
+
+        let mut counts: HashMap<AttributeSource, usize> = HashMap::new();
+        for source in sources.iter() {
+            *counts.entry(*source).or_default() += 1;
+        }
+        for (source, count) in counts.iter() {
+            log::info!("{} for {} edges taken from {}", attribute, count, source);
+        }
+
+        Ok(ResolvedAttribute {
+            values: values.into_boxed_slice(),
+            sources: if self.record_source {
+                Some(sources.into_boxed_slice())
+            } else {
+                None
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_precedence() {
+        // edge 0 is in the table, edge 1 is blank in the table, edge 2 is beyond
+        // the table, and edge 3 has a road class without a default
+        let table = [Some(50.0), None];
+        let chain = AttributeFallbackChain {
+            fallbacks: vec![
+                AttributeFallback::RoadClass {
+                    road_classes: Box::new([1, 1, 2, 3]),
+                    defaults: HashMap::from([(1, 100.0), (2, 80.0)]),
+                },
+                AttributeFallback::Global(40.0),
+            ],
+            record_source: true,
+        };
+        let resolved = chain.resolve("speed", &table).unwrap();
+        assert_eq!(resolved.values.as_ref(), &[50.0, 100.0, 80.0, 40.0]);
+        assert_eq!(
+            resolved.sources.unwrap().as_ref(),
+            &[
+                AttributeSource::Table,
+                AttributeSource::RoadClass,
+                AttributeSource::RoadClass,
+                AttributeSource::Global,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_missing() {
+        let chain: AttributeFallbackChain<f64> = AttributeFallbackChain::default();
+        let result = chain.resolve("speed", &[Some(50.0), None]);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,248 @@
+use crate::model::{
+    network::EdgeId,
+    state::{CustomFeatureFormat, StateFeature},
+    traversal::TraversalModelError,
+};
+use crate::util::fs::read_utils;
+use std::path::Path;
+
+/// per-edge numeric attributes discovered from the columns of an edge
+/// attributes CSV file. each numeric column becomes a custom state feature
+/// named after its column header, which the speed traversal model accumulates
+/// over each traversed edge, so that adding a column to the file surfaces a
+/// new feature without any configuration changes. rows are indexed by the
+/// `edge_id` column if present, otherwise by row order.
+pub struct CustomEdgeFeatures {
+    pub columns: Vec<(String, Box<[f64]>)>,
+}
+
+impl CustomEdgeFeatures {
+    /// column excluded from feature discovery and used to index rows, if present
+    pub const EDGE_ID_COLUMN: &'static str = "edge_id";
+    /// value used in the `unit` field of discovered custom features
+    pub const FEATURE_UNIT: &'static str = "edge_attribute";
+
+    /// reads an edge attributes CSV file, retaining each numeric column.
+    /// a column is numeric if every row value parses as a floating point
+    /// number. non-numeric columns are ignored. fails if the edge ids of
+    /// the `edge_id` column are not complete and unique.
+    pub fn new<P: AsRef<Path>>(
+        edge_attributes_path: &P,
+    ) -> Result<CustomEdgeFeatures, TraversalModelError> {
+        let path_str = edge_attributes_path.as_ref().to_str().unwrap_or_default();
+        let mut rows =
+            read_utils::iterator_from_csv::<_, Vec<String>>(edge_attributes_path, false, None)
+                .map_err(|e| {
+                    TraversalModelError::BuildError(format!(
+                        "cannot read {} due to {}",
+                        path_str, e
+                    ))
+                })?;
+        let header = match rows.next() {
+            Some(Ok(h)) => h,
+            Some(Err(e)) => {
+                return Err(TraversalModelError::BuildError(format!(
+                    "failure reading header of {} due to {}",
+                    path_str, e
+                )))
+            }
+            None => {
+                return Err(TraversalModelError::BuildError(format!(
+                    "edge attributes file {} is empty",
+                    path_str
+                )))
+            }
+        };
+
+        let edge_id_col = header
+            .iter()
+            .position(|name| name == CustomEdgeFeatures::EDGE_ID_COLUMN);
+
+        let mut edge_ids: Vec<usize> = vec![];
+
+        // parse every column, dropping a column at the first value that is not numeric
+        let mut parsed: Vec<Option<Vec<f64>>> = header
+            .iter()
+            .map(|name| {
+                if name == CustomEdgeFeatures::EDGE_ID_COLUMN {
+                    None
+                } else {
+                    Some(vec![])
+                }
+            })
+            .collect();
+        for (row_idx, row_result) in rows.enumerate() {
+            let row = row_result.map_err(|e| {
+                TraversalModelError::BuildError(format!(
+                    "failure reading row {} of {} due to {}",
+                    row_idx, path_str, e
+                ))
+            })?;
+            if let Some(col_idx) = edge_id_col {
+                let edge_id = row
+                    .get(col_idx)
+                    .and_then(|v| v.parse::<usize>().ok())
+                    .ok_or_else(|| {
+                        TraversalModelError::BuildError(format!(
+                            "invalid edge_id in row {} of {}",
+                            row_idx, path_str
+                        ))
+                    })?;
+                edge_ids.push(edge_id);
+            }
+            for (col_idx, column) in parsed.iter_mut().enumerate() {
+                if let Some(values) = column {
+                    match row.get(col_idx).and_then(|v| v.parse::<f64>().ok()) {
+                        Some(value) => values.push(value),
+                        None => {
+                            log::debug!(
+                                "edge attributes column '{}' has non-numeric value at row {}, ignoring column",
+                                header[col_idx],
+                                row_idx
+                            );
+                            *column = None;
+                        }
+                    }
+                }
+            }
+        }
+
+        // the row of each edge id. every slot is filled when the edge ids are
+        // unique and each is less than the number of rows.
+        let mut rows_by_edge_id: Vec<Option<usize>> = vec![None; edge_ids.len()];
+        for (row_idx, edge_id) in edge_ids.iter().enumerate() {
+            let slot = rows_by_edge_id.get_mut(*edge_id).ok_or_else(|| {
+                TraversalModelError::BuildError(format!(
+                    "edge_id {} out of range for {} with {} rows",
+                    edge_id,
+                    path_str,
+                    edge_ids.len()
+                ))
+            })?;
+            if slot.is_some() {
+                return Err(TraversalModelError::BuildError(format!(
+                    "duplicate edge_id {} in {}",
+                    edge_id, path_str
+                )));
+            }
+            *slot = Some(row_idx);
+        }
+        let index_by_edge_id = |values: Vec<f64>| -> Box<[f64]> {
+            if edge_id_col.is_none() {
+                return values.into_boxed_slice();
+            }
+            rows_by_edge_id
+                .iter()
+                .flatten()
+                .map(|row_idx| values[*row_idx])
+                .collect()
+        };
+
+        let columns = header
+            .into_iter()
+            .zip(parsed)
+            .filter_map(|(name, values)| values.map(|v| (name, index_by_edge_id(v))))
+            .collect::<Vec<_>>();
+        log::info!(
+            "discovered {} numeric edge attributes in {}: [{}]",
+            columns.len(),
+            path_str,
+            columns
+                .iter()
+                .map(|(n, _)| n.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        Ok(CustomEdgeFeatures { columns })
+    }
+
+    /// the names of each discovered feature
+    pub fn feature_names(&self) -> Vec<String> {
+        self.columns.iter().map(|(n, _)| n.clone()).collect()
+    }
+
+    /// a custom floating point state feature for each discovered column
+    pub fn state_features(&self) -> Vec<(String, StateFeature)> {
+        self.columns
+            .iter()
+            .map(|(name, _)| {
+                let feature = StateFeature::Custom {
+                    r#type: name.clone(),
+                    unit: String::from(CustomEdgeFeatures::FEATURE_UNIT),
+                    format: CustomFeatureFormat::FloatingPoint {
+                        initial: 0.0.into(),
+                    },
+                };
+                (name.clone(), feature)
+            })
+            .collect()
+    }
+
+    /// looks up the value of a column for some edge
+    pub fn get_value(
+        column: &(String, Box<[f64]>),
+        edge_id: EdgeId,
+    ) -> Result<f64, TraversalModelError> {
+        let (name, values) = column;
+        values.get(edge_id.as_usize()).cloned().ok_or_else(|| {
+            TraversalModelError::TraversalModelFailure(format!(
+                "missing index {} from edge attribute '{}'",
+                edge_id, name
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn test_file(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("model")
+            .join("traversal")
+            .join("default")
+            .join("test")
+            .join(name)
+    }
+
+    #[test]
+    fn test_discovers_numeric_columns() {
+        let engine = CustomEdgeFeatures::new(&test_file("edge_attributes.csv")).unwrap();
+        assert_eq!(
+            engine.feature_names(),
+            vec![String::from("toll"), String::from("noise")]
+        );
+        let toll = CustomEdgeFeatures::get_value(&engine.columns[0], EdgeId(2)).unwrap();
+        assert_eq!(toll, 2.25);
+    }
+
+    #[test]
+    fn test_indexes_rows_by_edge_id() {
+        let engine = CustomEdgeFeatures::new(&test_file("edge_attributes_unordered.csv")).unwrap();
+        let toll = |edge_id: usize| {
+            CustomEdgeFeatures::get_value(&engine.columns[0], EdgeId(edge_id)).unwrap()
+        };
+        assert_eq!(toll(0), 1.5);
+        assert_eq!(toll(1), 0.0);
+        assert_eq!(toll(2), 2.25);
+    }
+
+    #[test]
+    fn test_rejects_missing_edge_ids() {
+        let result = CustomEdgeFeatures::new(&test_file("edge_attributes_missing.csv"));
+        match result {
+            Err(TraversalModelError::BuildError(msg)) => {
+                assert!(
+                    msg.contains("edge_id 3 out of range"),
+                    "unexpected error: {}",
+                    msg
+                )
+            }
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("expected missing edge ids to fail"),
+        }
+    }
+}
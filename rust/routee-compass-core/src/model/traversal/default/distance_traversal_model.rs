@@ -67,4 +67,9 @@ impl TraversalModel for DistanceTraversalModel {
     fn state_features(&self) -> Vec<(String, StateFeature)> {
         vec![]
     }
+
+    /// distance is declared by the state configuration
+    fn input_features(&self) -> Vec<String> {
+        vec![String::from(Self::DISTANCE)]
+    }
 }
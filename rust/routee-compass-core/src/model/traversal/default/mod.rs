@@ -1,11 +1,23 @@
+mod custom_edge_features;
 mod distance_traversal_model;
 mod distance_traversal_service;
+mod speed_profile;
+mod speed_schedule;
 mod speed_traversal_engine;
 mod speed_traversal_model;
 mod speed_traversal_service;
+mod surface_type;
+mod time_sampler;
+mod toll_schedule;
 
+pub use custom_edge_features::CustomEdgeFeatures;
 pub use distance_traversal_model::DistanceTraversalModel;
 pub use distance_traversal_service::DistanceTraversalService;
+pub use speed_profile::{seconds_of_week, SpeedProfile, SPEED_BIN_SECONDS};
+pub use speed_schedule::{parse_time_of_day, SpeedOverride, SpeedSchedule};
 pub use speed_traversal_engine::SpeedTraversalEngine;
 pub use speed_traversal_model::SpeedTraversalModel;
-pub use speed_traversal_service::SpeedLookupService;
+pub use speed_traversal_service::{SpeedLookupService, SEED, START_TIME, START_WEEKDAY};
+pub use surface_type::{SurfacePenalty, SurfaceType};
+pub use time_sampler::TimeSampler;
+pub use toll_schedule::{TollSchedule, TollWindow};
@@ -0,0 +1,179 @@
+use super::speed_schedule::{parse_time_of_day, SECONDS_PER_DAY};
+use crate::model::network::EdgeId;
+use crate::model::traversal::TraversalModelError;
+use crate::model::unit::Speed;
+use crate::util::fs::read_utils;
+use chrono::Weekday;
+use kdam::Bar;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// width of each speed bin, in seconds
+pub const SPEED_BIN_SECONDS: f64 = 900.0;
+const SECONDS_PER_WEEK: f64 = 7.0 * SECONDS_PER_DAY;
+const BINS_PER_WEEK: usize = (SECONDS_PER_WEEK / SPEED_BIN_SECONDS) as usize;
+
+/// a row of a speed profile file, which is a CSV with header
+/// `edge_id,weekday,time,speed`. the weekday is written as `monday`, `mon`,
+/// etc, the time as `HH:MM` or `HH:MM:SS`, and the speed is in the speed
+/// unit of the speed table.
+#[derive(Deserialize, Debug)]
+struct SpeedProfileRow {
+    edge_id: EdgeId,
+    weekday: String,
+    time: String,
+    speed: Speed,
+}
+
+/// typical speeds of edges across a week in 15-minute bins, such as hourly
+/// speeds from probe data. each row of the file sets the speed of an edge from
+/// its bin up to the bin of the next row of that edge, wrapping around the end
+/// of the week, so an hourly profile needs one row per hour.
+#[derive(Clone, Debug, Default)]
+pub struct SpeedProfile {
+    /// for each edge, the bins where its speed changes, sorted by bin
+    profiles: HashMap<EdgeId, Vec<(usize, Speed)>>,
+    /// fastest speed over all bins of all edges
+    max_speed: Speed,
+}
+
+impl SpeedProfile {
+    /// reads a speed profile CSV file. see [`SpeedProfileRow`] for the format.
+    pub fn from_csv<P: AsRef<Path>>(path: &P) -> Result<SpeedProfile, TraversalModelError> {
+        let rows: Box<[SpeedProfileRow]> =
+            read_utils::from_csv(path, true, Some(Bar::builder().desc("speed profile")), None)
+                .map_err(|e| {
+                    TraversalModelError::BuildError(format!(
+                        "cannot read {} due to {}",
+                        path.as_ref().to_str().unwrap_or_default(),
+                        e,
+                    ))
+                })?;
+        let mut entries = vec![];
+        for row in rows.iter() {
+            let weekday = row.weekday.parse::<Weekday>().map_err(|_| {
+                TraversalModelError::BuildError(format!(
+                    "speed profile weekday must be a weekday such as 'monday', found '{}'",
+                    row.weekday
+                ))
+            })?;
+            let time_of_week = seconds_of_week(weekday, parse_time_of_day(&row.time)?);
+            entries.push((row.edge_id, speed_bin(time_of_week), row.speed));
+        }
+        SpeedProfile::new(entries)
+    }
+
+    /// builds a speed profile from the edge, bin and speed where each edge's speed changes.
+    pub fn new(
+        entries: impl IntoIterator<Item = (EdgeId, usize, Speed)>,
+    ) -> Result<SpeedProfile, TraversalModelError> {
+        let mut profiles: HashMap<EdgeId, Vec<(usize, Speed)>> = HashMap::new();
+        let mut max_speed = Speed::ZERO;
+        for (edge_id, bin, speed) in entries {
+            if speed <= Speed::ZERO {
+                return Err(TraversalModelError::BuildError(format!(
+                    "speed profile of edge {} has non-positive speed {}",
+                    edge_id, speed
+                )));
+            }
+            if bin >= BINS_PER_WEEK {
+                return Err(TraversalModelError::BuildError(format!(
+                    "speed profile of edge {} has bin {} beyond the {} bins of a week",
+                    edge_id, bin, BINS_PER_WEEK
+                )));
+            }
+            max_speed = if speed > max_speed { speed } else { max_speed };
+            profiles.entry(edge_id).or_default().push((bin, speed));
+        }
+        for (edge_id, profile) in profiles.iter_mut() {
+            profile.sort_by_key(|(bin, _)| *bin);
+            if profile.windows(2).any(|w| w[0].0 == w[1].0) {
+                return Err(TraversalModelError::BuildError(format!(
+                    "speed profile of edge {} has more than one speed for the same 15-minute bin",
+                    edge_id
+                )));
+            }
+        }
+        Ok(SpeedProfile {
+            profiles,
+            max_speed,
+        })
+    }
+
+    /// the fastest speed over all bins of all edges
+    pub fn max_speed(&self) -> Speed {
+        self.max_speed
+    }
+
+    /// finds the speed of an edge at some time of the week, in seconds since
+    /// midnight of monday. times beyond one week wrap around.
+    ///
+    /// # Returns
+    ///
+    /// the speed of the bin containing the time, or None if the edge has no profile
+    pub fn get_speed(&self, edge_id: &EdgeId, time_of_week: f64) -> Option<Speed> {
+        let profile = self.profiles.get(edge_id)?;
+        let bin = speed_bin(time_of_week);
+        match profile.partition_point(|(b, _)| *b <= bin) {
+            0 => profile.last().map(|(_, speed)| *speed),
+            n => profile.get(n - 1).map(|(_, speed)| *speed),
+        }
+    }
+}
+
+/// the time of the week, in seconds since midnight of monday, of a time of day
+/// on a weekday. times of day beyond one day roll over to the following days.
+pub fn seconds_of_week(weekday: Weekday, time_of_day: f64) -> f64 {
+    let days = weekday.num_days_from_monday() as f64;
+    (days * SECONDS_PER_DAY + time_of_day).rem_euclid(SECONDS_PER_WEEK)
+}
+
+/// the start of the 15-minute bin following a time, in the same seconds as the time.
+/// bins start on the quarter hour of every day, so this holds for times of day and
+/// times of the week alike.
+pub fn next_bin_start(time: f64) -> f64 {
+    ((time / SPEED_BIN_SECONDS).floor() + 1.0) * SPEED_BIN_SECONDS
+}
+
+/// the 15-minute bin of a time of the week, in seconds since midnight of monday
+fn speed_bin(time_of_week: f64) -> usize {
+    let bin = (time_of_week.rem_euclid(SECONDS_PER_WEEK) / SPEED_BIN_SECONDS) as usize;
+    bin.min(BINS_PER_WEEK - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speed_bins_wrap_around_the_week() {
+        let at = |weekday: Weekday, time: &str| {
+            speed_bin(seconds_of_week(weekday, parse_time_of_day(time).unwrap()))
+        };
+        let profile = SpeedProfile::new([
+            (EdgeId(0), at(Weekday::Mon, "07:00"), Speed::new(20.0)),
+            (EdgeId(0), at(Weekday::Mon, "09:00"), Speed::new(50.0)),
+            (EdgeId(0), at(Weekday::Sat, "00:00"), Speed::new(60.0)),
+        ])
+        .unwrap();
+        let speed = |weekday: Weekday, time: &str| {
+            let time_of_week = seconds_of_week(weekday, parse_time_of_day(time).unwrap());
+            profile.get_speed(&EdgeId(0), time_of_week)
+        };
+        assert_eq!(speed(Weekday::Mon, "07:00"), Some(Speed::new(20.0)));
+        assert_eq!(speed(Weekday::Mon, "08:59:59"), Some(Speed::new(20.0)));
+        assert_eq!(speed(Weekday::Wed, "08:00"), Some(Speed::new(50.0)));
+        assert_eq!(speed(Weekday::Sun, "23:00"), Some(Speed::new(60.0)));
+        // before the first bin of the week, the last speed of the week holds
+        assert_eq!(speed(Weekday::Mon, "06:45"), Some(Speed::new(60.0)));
+        assert_eq!(profile.get_speed(&EdgeId(1), 0.0), None);
+        assert_eq!(profile.max_speed(), Speed::new(60.0));
+        // a time of day past midnight rolls over to the next day
+        assert_eq!(
+            seconds_of_week(Weekday::Sun, SECONDS_PER_DAY + 3600.0),
+            3600.0
+        );
+        assert!(SpeedProfile::new([(EdgeId(0), 0, Speed::ZERO)]).is_err());
+    }
+}
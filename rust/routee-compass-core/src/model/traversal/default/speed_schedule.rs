@@ -0,0 +1,144 @@
+use crate::model::network::EdgeId;
+use crate::model::traversal::TraversalModelError;
+use crate::model::unit::Speed;
+use crate::util::fs::read_utils;
+use chrono::{NaiveTime, Timelike};
+use kdam::Bar;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+pub(crate) const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// a row of a speed schedule file, which is a CSV with header
+/// `edge_id,start_time,end_time,speed`. times are times of day written
+/// as `HH:MM` or `HH:MM:SS`, and speeds are in the speed unit of the
+/// speed table.
+#[derive(Deserialize, Debug)]
+struct SpeedScheduleRow {
+    edge_id: EdgeId,
+    start_time: String,
+    end_time: String,
+    speed: Speed,
+}
+
+/// a speed limit that is in effect on an edge during a daily time window,
+/// such as a school zone or work zone. times are in seconds since midnight.
+/// a window with an end before its start wraps around midnight.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpeedOverride {
+    pub start: f64,
+    pub end: f64,
+    pub speed: Speed,
+}
+
+impl SpeedOverride {
+    /// true if the time of day, in seconds since midnight, falls within `[start, end)`
+    pub fn is_active(&self, time_of_day: f64) -> bool {
+        is_within_window(self.start, self.end, time_of_day)
+    }
+}
+
+/// true if the time of day falls within the daily window `[start, end)`, all in
+/// seconds since midnight. a window with an end before its start wraps around midnight.
+pub(crate) fn is_within_window(start: f64, end: f64, time_of_day: f64) -> bool {
+    if start <= end {
+        start <= time_of_day && time_of_day < end
+    } else {
+        start <= time_of_day || time_of_day < end
+    }
+}
+
+/// time-windowed speed overrides, grouped by edge.
+#[derive(Clone, Debug, Default)]
+pub struct SpeedSchedule(HashMap<EdgeId, Vec<SpeedOverride>>);
+
+impl SpeedSchedule {
+    /// reads a speed schedule CSV file. see [`SpeedScheduleRow`] for the format.
+    pub fn from_csv<P: AsRef<Path>>(path: &P) -> Result<SpeedSchedule, TraversalModelError> {
+        let rows: Box<[SpeedScheduleRow]> = read_utils::from_csv(
+            path,
+            true,
+            Some(Bar::builder().desc("speed schedule")),
+            None,
+        )
+        .map_err(|e| {
+            TraversalModelError::BuildError(format!(
+                "cannot read {} due to {}",
+                path.as_ref().to_str().unwrap_or_default(),
+                e,
+            ))
+        })?;
+        let mut schedule: HashMap<EdgeId, Vec<SpeedOverride>> = HashMap::new();
+        for row in rows.iter() {
+            let speed_override = SpeedOverride {
+                start: parse_time_of_day(&row.start_time)?,
+                end: parse_time_of_day(&row.end_time)?,
+                speed: row.speed,
+            };
+            schedule
+                .entry(row.edge_id)
+                .or_default()
+                .push(speed_override);
+        }
+        Ok(SpeedSchedule(schedule))
+    }
+
+    /// finds the speed limit in effect on an edge at some time of day, in
+    /// seconds since midnight. times beyond one day wrap around. if more than
+    /// one window is active, the lowest speed is returned.
+    pub fn get_speed(&self, edge_id: &EdgeId, time_of_day: f64) -> Option<Speed> {
+        let time_of_day = time_of_day.rem_euclid(SECONDS_PER_DAY);
+        self.0
+            .get(edge_id)?
+            .iter()
+            .filter(|o| o.is_active(time_of_day))
+            .map(|o| o.speed)
+            .min()
+    }
+}
+
+/// parses a time of day written as `HH:MM` or `HH:MM:SS` into seconds since midnight
+pub fn parse_time_of_day(value: &str) -> Result<f64, TraversalModelError> {
+    let time = NaiveTime::parse_from_str(value, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M"))
+        .map_err(|e| {
+            TraversalModelError::BuildError(format!(
+                "unable to parse '{}' as a time of day (HH:MM or HH:MM:SS): {}",
+                value, e
+            ))
+        })?;
+    Ok(time.num_seconds_from_midnight() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_active_window() {
+        let morning = SpeedOverride {
+            start: parse_time_of_day("07:00").unwrap(),
+            end: parse_time_of_day("09:00").unwrap(),
+            speed: Speed::new(30.0),
+        };
+        let overnight = SpeedOverride {
+            start: parse_time_of_day("22:00").unwrap(),
+            end: parse_time_of_day("05:30:00").unwrap(),
+            speed: Speed::new(40.0),
+        };
+        let schedule = SpeedSchedule(HashMap::from([(EdgeId(0), vec![morning, overnight])]));
+        let at = |t: &str| schedule.get_speed(&EdgeId(0), parse_time_of_day(t).unwrap());
+        assert_eq!(at("07:30"), Some(Speed::new(30.0)));
+        assert_eq!(at("12:00"), None);
+        assert_eq!(at("09:00"), None);
+        assert_eq!(at("23:00"), Some(Speed::new(40.0)));
+        assert_eq!(at("01:00"), Some(Speed::new(40.0)));
+        assert_eq!(schedule.get_speed(&EdgeId(1), 27000.0), None);
+        // next day wraps around
+        assert_eq!(
+            schedule.get_speed(&EdgeId(0), SECONDS_PER_DAY + 27000.0),
+            Some(Speed::new(30.0))
+        );
+    }
+}
@@ -1,5 +1,13 @@
 use kdam::Bar;
 
+use super::custom_edge_features::CustomEdgeFeatures;
+use super::speed_profile::SpeedProfile;
+use super::speed_schedule::SpeedSchedule;
+use super::surface_type::{validate_surface_penalty, SurfacePenalty, SurfaceType};
+use super::toll_schedule::TollSchedule;
+use crate::model::network::{Edge, EdgeId};
+use crate::model::traversal::attribute_fallback::{AttributeFallbackChain, AttributeSource};
+use crate::model::unit::AsF64;
 use crate::model::unit::DistanceUnit;
 use crate::model::unit::{SpeedUnit, TimeUnit, BASE_DISTANCE_UNIT, BASE_TIME_UNIT};
 use crate::util::fs::read_decoders;
@@ -7,6 +15,7 @@ use crate::{
     model::{traversal::TraversalModelError, unit::Speed},
     util::fs::read_utils,
 };
+use std::collections::HashSet;
 use std::path::Path;
 
 pub struct SpeedTraversalEngine {
@@ -15,6 +24,23 @@ pub struct SpeedTraversalEngine {
     pub time_unit: TimeUnit,
     pub distance_unit: DistanceUnit,
     pub max_speed: Speed,
+    /// optional time-windowed speed limits, such as school zones
+    pub speed_schedule: Option<SpeedSchedule>,
+    /// optional typical speeds of edges across a week, such as from probe data
+    pub speed_profile: Option<SpeedProfile>,
+    /// for each edge, the fallback level that supplied its speed, if recorded
+    pub speed_source: Option<Box<[AttributeSource]>>,
+    /// optional variance of each edge's travel time, in the squared time unit
+    /// of this engine, used to penalize unreliable edges
+    pub time_variance_table: Option<Box<[f64]>>,
+    /// optional road surface of each edge, used to penalize unpaved edges
+    pub surface_table: Option<Box<[SurfaceType]>>,
+    /// default penalty factor of each surface type, which queries may override
+    pub surface_penalty: SurfacePenalty,
+    /// optional time-windowed tolls, such as congestion pricing
+    pub toll_schedule: Option<TollSchedule>,
+    /// optional numeric edge attributes, each accumulated to a custom state feature
+    pub custom_features: Option<CustomEdgeFeatures>,
 }
 
 impl SpeedTraversalEngine {
@@ -37,6 +63,94 @@ impl SpeedTraversalEngine {
                 e,
             ))
         })?;
+        SpeedTraversalEngine::from_speed_table(
+            speed_table,
+            speed_unit,
+            distance_unit_opt,
+            time_unit_opt,
+        )
+    }
+
+    /// creates an engine reading speeds from a speed table that may have missing
+    /// (blank) rows, filling missing speeds from a chain of fallbacks such as
+    /// road class or global defaults. see [`AttributeFallbackChain::resolve`].
+    pub fn new_with_fallback<P: AsRef<Path>>(
+        speed_table_path: &P,
+        fallback: &AttributeFallbackChain<Speed>,
+        speed_unit: SpeedUnit,
+        distance_unit_opt: Option<DistanceUnit>,
+        time_unit_opt: Option<TimeUnit>,
+    ) -> Result<SpeedTraversalEngine, TraversalModelError> {
+        let table: Box<[Option<Speed>]> = read_utils::read_raw_file(
+            speed_table_path,
+            read_decoders::optional,
+            Some(Bar::builder().desc("link speeds")),
+            None,
+        )
+        .map_err(|e| {
+            TraversalModelError::BuildError(format!(
+                "cannot read {} due to {}",
+                speed_table_path.as_ref().to_str().unwrap_or_default(),
+                e,
+            ))
+        })?;
+        let resolved = fallback.resolve("speed", &table)?;
+        let engine = SpeedTraversalEngine::from_speed_table(
+            resolved.values,
+            speed_unit,
+            distance_unit_opt,
+            time_unit_opt,
+        )?;
+        Ok(SpeedTraversalEngine {
+            speed_source: resolved.sources,
+            ..engine
+        })
+    }
+
+    /// the fallback level that supplied the speed of an edge, if sources were recorded
+    pub fn get_speed_source(&self, edge_id: EdgeId) -> Option<AttributeSource> {
+        self.speed_source
+            .as_ref()
+            .and_then(|sources| sources.get(edge_id.as_usize()).cloned())
+    }
+
+    /// creates an engine reading speeds from a named column of a shared edge
+    /// attributes CSV file keyed by `edge_id`. see
+    /// [`read_utils::read_edge_attribute_column`] for the file requirements.
+    pub fn from_edge_attributes<P: AsRef<Path>>(
+        edge_attributes_path: &P,
+        speed_column: &str,
+        speed_unit: SpeedUnit,
+        distance_unit_opt: Option<DistanceUnit>,
+        time_unit_opt: Option<TimeUnit>,
+    ) -> Result<SpeedTraversalEngine, TraversalModelError> {
+        let speed_table: Box<[Speed]> = read_utils::read_edge_attribute_column(
+            edge_attributes_path,
+            speed_column,
+            Some(Bar::builder().desc("link speeds")),
+        )
+        .map_err(|e| {
+            TraversalModelError::BuildError(format!(
+                "cannot read column {} of {} due to {}",
+                speed_column,
+                edge_attributes_path.as_ref().to_str().unwrap_or_default(),
+                e,
+            ))
+        })?;
+        SpeedTraversalEngine::from_speed_table(
+            speed_table,
+            speed_unit,
+            distance_unit_opt,
+            time_unit_opt,
+        )
+    }
+
+    fn from_speed_table(
+        speed_table: Box<[Speed]>,
+        speed_unit: SpeedUnit,
+        distance_unit_opt: Option<DistanceUnit>,
+        time_unit_opt: Option<TimeUnit>,
+    ) -> Result<SpeedTraversalEngine, TraversalModelError> {
         let max_speed = get_max_speed(&speed_table)?;
         let time_unit = time_unit_opt.unwrap_or(BASE_TIME_UNIT);
         let distance_unit = distance_unit_opt.unwrap_or(BASE_DISTANCE_UNIT);
@@ -46,9 +160,277 @@ impl SpeedTraversalEngine {
             time_unit,
             speed_unit,
             max_speed,
+            speed_schedule: None,
+            speed_profile: None,
+            speed_source: None,
+            time_variance_table: None,
+            surface_table: None,
+            surface_penalty: SurfacePenalty::new(),
+            toll_schedule: None,
+            custom_features: None,
         };
         Ok(model)
     }
+
+    /// applies a smoothing pass over the speed table, replacing each edge's speed
+    /// with the average speed over the edges topologically within `window` hops
+    /// upstream or downstream of it. see [`smooth_speed_table`].
+    ///
+    /// # Arguments
+    ///
+    /// * `edge_list_path` - the graph edge list CSV, used to find neighboring edges
+    /// * `window` - the number of hops upstream and downstream to include
+    ///
+    /// # Returns
+    ///
+    /// the engine with a smoothed speed table, or an error if the edges cannot be read
+    pub fn with_smoothing<P: AsRef<Path>>(
+        self,
+        edge_list_path: &P,
+        window: usize,
+    ) -> Result<SpeedTraversalEngine, TraversalModelError> {
+        let edges: Box<[Edge]> = read_utils::from_csv(
+            edge_list_path,
+            true,
+            Some(Bar::builder().desc("speed smoothing edges")),
+            None,
+        )
+        .map_err(|e| {
+            TraversalModelError::BuildError(format!(
+                "cannot read {} due to {}",
+                edge_list_path.as_ref().to_str().unwrap_or_default(),
+                e,
+            ))
+        })?;
+        let speed_table = smooth_speed_table(&self.speed_table, &edges, window)?;
+        // profiled speeds still bound the A* estimate after smoothing
+        let table_max_speed = get_max_speed(&speed_table)?;
+        let max_speed = match &self.speed_profile {
+            Some(profile) if profile.max_speed() > table_max_speed => profile.max_speed(),
+            _ => table_max_speed,
+        };
+        Ok(SpeedTraversalEngine {
+            speed_table,
+            max_speed,
+            ..self
+        })
+    }
+
+    /// adds a schedule of time-windowed speed limits to this engine. while a
+    /// window is active on an edge, the edge speed is capped at the scheduled
+    /// speed. see [`SpeedSchedule::from_csv`] for the file format.
+    pub fn with_speed_schedule<P: AsRef<Path>>(
+        self,
+        speed_schedule_path: &P,
+    ) -> Result<SpeedTraversalEngine, TraversalModelError> {
+        let speed_schedule = SpeedSchedule::from_csv(speed_schedule_path)?;
+        Ok(SpeedTraversalEngine {
+            speed_schedule: Some(speed_schedule),
+            ..self
+        })
+    }
+
+    /// adds typical speeds of edges across a week in 15-minute bins, which replace
+    /// the speed table on profiled edges. see [`SpeedProfile::from_csv`] for the
+    /// file format.
+    ///
+    /// an edge is traversed at the speed of the bin in which it is entered, but a
+    /// trip never arrives later than one entering the edge at the start of a following
+    /// bin, which keeps each edge first-in-first-out. the engine max speed is raised to
+    /// the fastest speed of any bin, so that the A* estimate stays a lower bound on
+    /// trip time.
+    pub fn with_speed_profile<P: AsRef<Path>>(
+        self,
+        speed_profile_path: &P,
+    ) -> Result<SpeedTraversalEngine, TraversalModelError> {
+        let speed_profile = SpeedProfile::from_csv(speed_profile_path)?;
+        let max_speed = if speed_profile.max_speed() > self.max_speed {
+            speed_profile.max_speed()
+        } else {
+            self.max_speed
+        };
+        Ok(SpeedTraversalEngine {
+            speed_profile: Some(speed_profile),
+            max_speed,
+            ..self
+        })
+    }
+
+    /// adds a schedule of time-windowed tolls to this engine. the toll of an edge
+    /// is charged by the pricing window active when the edge is entered. see
+    /// [`TollSchedule::from_csv`] for the file format.
+    pub fn with_toll_schedule<P: AsRef<Path>>(
+        self,
+        toll_schedule_path: &P,
+    ) -> Result<SpeedTraversalEngine, TraversalModelError> {
+        let toll_schedule = TollSchedule::from_csv(toll_schedule_path)?;
+        Ok(SpeedTraversalEngine {
+            toll_schedule: Some(toll_schedule),
+            ..self
+        })
+    }
+
+    /// adds the numeric columns of an edge attributes CSV file, each tracked as
+    /// a custom state feature. see [`CustomEdgeFeatures`] for the file requirements.
+    pub fn with_custom_features<P: AsRef<Path>>(
+        self,
+        edge_attributes_path: &P,
+    ) -> Result<SpeedTraversalEngine, TraversalModelError> {
+        let custom_features = CustomEdgeFeatures::new(edge_attributes_path)?;
+        Ok(SpeedTraversalEngine {
+            custom_features: Some(custom_features),
+            ..self
+        })
+    }
+
+    /// adds a table with the variance of each edge's travel time, in the squared
+    /// time unit of this engine. the variance of a path is the sum of the variances
+    /// of its edges, which assumes edge travel times are independent.
+    pub fn with_time_variance<P: AsRef<Path>>(
+        self,
+        time_variance_path: &P,
+    ) -> Result<SpeedTraversalEngine, TraversalModelError> {
+        let table: Box<[f64]> = read_utils::read_raw_file(
+            time_variance_path,
+            read_decoders::f64,
+            Some(Bar::builder().desc("link travel time variance")),
+            None,
+        )
+        .map_err(|e| {
+            TraversalModelError::BuildError(format!(
+                "cannot read {} due to {}",
+                time_variance_path.as_ref().to_str().unwrap_or_default(),
+                e,
+            ))
+        })?;
+        if let Some((idx, v)) = table.iter().enumerate().find(|(_, v)| **v < 0.0) {
+            return Err(TraversalModelError::BuildError(format!(
+                "travel time variance must be non-negative, found {} for edge {}",
+                v, idx
+            )));
+        }
+        Ok(SpeedTraversalEngine {
+            time_variance_table: Some(table),
+            ..self
+        })
+    }
+
+    /// adds a table with the road surface of each edge, one of `paved`, `gravel`
+    /// or `dirt` per row, along with the default penalty factor of each surface.
+    pub fn with_surface<P: AsRef<Path>>(
+        self,
+        surface_path: &P,
+        surface_penalty: SurfacePenalty,
+    ) -> Result<SpeedTraversalEngine, TraversalModelError> {
+        let table: Box<[SurfaceType]> = read_utils::read_raw_file(
+            surface_path,
+            read_decoders::default,
+            Some(Bar::builder().desc("link surface type")),
+            None,
+        )
+        .map_err(|e| {
+            TraversalModelError::BuildError(format!(
+                "cannot read {} due to {}",
+                surface_path.as_ref().to_str().unwrap_or_default(),
+                e,
+            ))
+        })?;
+        validate_surface_penalty(&surface_penalty)?;
+        Ok(SpeedTraversalEngine {
+            surface_table: Some(table),
+            surface_penalty,
+            ..self
+        })
+    }
+}
+
+/// smooths noisy per-edge speeds by averaging each edge's speed with its
+/// topological neighbors: the edges reachable within `window` hops downstream
+/// from its destination vertex or upstream from its source vertex. the average
+/// is the distance-weighted harmonic mean, so that the travel time across a
+/// neighborhood is preserved. edges with zero speed are left unchanged and are
+/// not used as neighbors.
+///
+/// # Arguments
+///
+/// * `speed_table` - the speed of each edge, indexed by edge id
+/// * `edges` - the edges of the graph, indexed by edge id
+/// * `window` - the number of hops upstream and downstream to include
+///
+/// # Returns
+///
+/// the smoothed speed table, or an error if the tables do not match in size
+pub fn smooth_speed_table(
+    speed_table: &[Speed],
+    edges: &[Edge],
+    window: usize,
+) -> Result<Box<[Speed]>, TraversalModelError> {
+    if speed_table.len() != edges.len() {
+        return Err(TraversalModelError::BuildError(format!(
+            "speed table has {} entries but edge list has {} edges",
+            speed_table.len(),
+            edges.len()
+        )));
+    }
+    let n_vertices = edges
+        .iter()
+        .map(|e| e.src_vertex_id.0.max(e.dst_vertex_id.0) + 1)
+        .max()
+        .unwrap_or_default();
+    let mut out_edges: Vec<Vec<usize>> = vec![vec![]; n_vertices];
+    let mut in_edges: Vec<Vec<usize>> = vec![vec![]; n_vertices];
+    for (idx, edge) in edges.iter().enumerate() {
+        out_edges[edge.src_vertex_id.0].push(idx);
+        in_edges[edge.dst_vertex_id.0].push(idx);
+    }
+
+    let smoothed = edges
+        .iter()
+        .enumerate()
+        .map(|(idx, edge)| {
+            if speed_table[idx] == Speed::ZERO {
+                return speed_table[idx];
+            }
+            let mut neighborhood: HashSet<usize> = HashSet::from([idx]);
+            let mut downstream = vec![edge.dst_vertex_id.0];
+            let mut upstream = vec![edge.src_vertex_id.0];
+            for _ in 0..window {
+                let downstream_edges = downstream
+                    .iter()
+                    .flat_map(|v| out_edges[*v].iter())
+                    .filter(|e| neighborhood.insert(**e))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let upstream_edges = upstream
+                    .iter()
+                    .flat_map(|v| in_edges[*v].iter())
+                    .filter(|e| neighborhood.insert(**e))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                downstream = downstream_edges
+                    .iter()
+                    .map(|e| edges[*e].dst_vertex_id.0)
+                    .collect();
+                upstream = upstream_edges
+                    .iter()
+                    .map(|e| edges[*e].src_vertex_id.0)
+                    .collect();
+            }
+            let (distance, time) = neighborhood
+                .iter()
+                .filter(|e| speed_table[**e] > Speed::ZERO)
+                .fold((0.0, 0.0), |(d, t), e| {
+                    let e_distance = edges[*e].distance.as_f64();
+                    (d + e_distance, t + e_distance / speed_table[*e].as_f64())
+                });
+            if time > 0.0 {
+                Speed::new(distance / time)
+            } else {
+                speed_table[idx]
+            }
+        })
+        .collect::<Vec<_>>();
+    Ok(smoothed.into_boxed_slice())
 }
 
 pub fn get_max_speed(speed_table: &[Speed]) -> Result<Speed, TraversalModelError> {
@@ -70,3 +452,107 @@ pub fn get_max_speed(speed_table: &[Speed]) -> Result<Speed, TraversalModelError
         Ok(max_speed)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// variance of the differences in speed between consecutive edges
+    fn consecutive_variance(speeds: &[Speed]) -> f64 {
+        let diffs = speeds
+            .windows(2)
+            .map(|w| w[1].as_f64() - w[0].as_f64())
+            .collect::<Vec<_>>();
+        let mean = diffs.iter().sum::<f64>() / diffs.len() as f64;
+        diffs.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / diffs.len() as f64
+    }
+
+    fn travel_time(speeds: &[Speed], edges: &[Edge]) -> f64 {
+        speeds
+            .iter()
+            .zip(edges)
+            .map(|(s, e)| e.distance.as_f64() / s.as_f64())
+            .sum()
+    }
+
+    #[test]
+    fn test_smoothing_reduces_noise() {
+        // a chain of 12 edges with speeds alternating between 30 and 55
+        let edges = (0..12)
+            .map(|i| Edge::new(i, i, i + 1, 100.0))
+            .collect::<Vec<_>>();
+        let speeds = (0..12)
+            .map(|i| Speed::new(if i % 2 == 0 { 30.0 } else { 55.0 }))
+            .collect::<Vec<_>>();
+
+        let unchanged = smooth_speed_table(&speeds, &edges, 0).unwrap();
+        assert_eq!(unchanged.to_vec(), speeds);
+
+        let smoothed = smooth_speed_table(&speeds, &edges, 2).unwrap();
+        let noisy_variance = consecutive_variance(&speeds);
+        let smoothed_variance = consecutive_variance(&smoothed);
+        assert!(
+            smoothed_variance < noisy_variance / 4.0,
+            "expected smoothing to reduce variance {} but found {}",
+            noisy_variance,
+            smoothed_variance
+        );
+
+        let noisy_time = travel_time(&speeds, &edges);
+        let smoothed_time = travel_time(&smoothed, &edges);
+        assert!(
+            ((smoothed_time - noisy_time) / noisy_time).abs() < 0.02,
+            "expected travel time {} to be preserved but found {}",
+            noisy_time,
+            smoothed_time
+        );
+    }
+
+    #[test]
+    fn test_speed_fallback_chain() {
+        use crate::model::traversal::attribute_fallback::AttributeFallback;
+        use std::collections::HashMap;
+        use std::path::PathBuf;
+
+        // edge 0 has a speed, edge 1 is blank in the table and edge 2 is beyond it
+        let file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("model")
+            .join("traversal")
+            .join("default")
+            .join("test")
+            .join("velocities_missing.txt");
+        let fallback = AttributeFallbackChain {
+            fallbacks: vec![
+                AttributeFallback::RoadClass {
+                    road_classes: Box::new([1, 2, 3]),
+                    defaults: HashMap::from([(1, Speed::new(90.0)), (2, Speed::new(25.0))]),
+                },
+                AttributeFallback::Global(Speed::new(15.0)),
+            ],
+            record_source: true,
+        };
+        let engine = SpeedTraversalEngine::new_with_fallback(
+            &file,
+            &fallback,
+            SpeedUnit::KilometersPerHour,
+            None,
+            None,
+        )
+        .unwrap();
+        let expected = [10.0, 25.0, 15.0].map(Speed::new);
+        assert_eq!(engine.speed_table.as_ref(), &expected);
+        assert_eq!(
+            engine.get_speed_source(EdgeId(0)),
+            Some(AttributeSource::Table)
+        );
+        assert_eq!(
+            engine.get_speed_source(EdgeId(1)),
+            Some(AttributeSource::RoadClass)
+        );
+        assert_eq!(
+            engine.get_speed_source(EdgeId(2)),
+            Some(AttributeSource::Global)
+        );
+    }
+}
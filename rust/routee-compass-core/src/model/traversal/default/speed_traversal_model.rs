@@ -1,25 +1,92 @@
+use super::custom_edge_features::CustomEdgeFeatures;
+use super::speed_profile::{next_bin_start, seconds_of_week, SPEED_BIN_SECONDS};
 use super::speed_traversal_engine::SpeedTraversalEngine;
+use super::surface_type::SurfacePenalty;
+use super::time_sampler::TimeSampler;
 use crate::model::network::edge_id::EdgeId;
 use crate::model::network::{Edge, Vertex};
-use crate::model::state::StateFeature;
 use crate::model::state::StateModel;
 use crate::model::state::StateVariable;
+use crate::model::state::{CustomFeatureFormat, StateFeature};
+use crate::model::traversal::attribute_fallback::AttributeSource;
 use crate::model::traversal::traversal_model::TraversalModel;
-use crate::model::unit::{Distance, Time, BASE_DISTANCE_UNIT};
+use crate::model::unit::{AsF64, Distance, Time, TimeUnit, BASE_DISTANCE_UNIT};
 use crate::model::{traversal::traversal_model_error::TraversalModelError, unit::Speed};
 use crate::util::geo::haversine;
+use chrono::Weekday;
 use std::sync::Arc;
 
 pub struct SpeedTraversalModel {
     engine: Arc<SpeedTraversalEngine>,
+    /// trip start time in seconds since midnight, used to look up
+    /// time-windowed speed limits, weekly speed profiles and tolls
+    start_time: Option<f64>,
+    /// weekday of the trip start, used with the start time to look up weekly
+    /// speed profiles
+    start_weekday: Option<Weekday>,
+    /// penalty factors of each surface type, replacing the engine defaults
+    surface_penalty: Option<SurfacePenalty>,
+    /// draws edge travel times from their mean and variance, rather than using
+    /// the mean travel time
+    time_sampler: Option<TimeSampler>,
 }
 
 impl SpeedTraversalModel {
     pub fn new(engine: Arc<SpeedTraversalEngine>) -> SpeedTraversalModel {
-        SpeedTraversalModel { engine }
+        SpeedTraversalModel {
+            engine,
+            start_time: None,
+            start_weekday: None,
+            surface_penalty: None,
+            time_sampler: None,
+        }
+    }
+
+    /// sets the trip start time, in seconds since midnight
+    pub fn with_start_time(self, start_time: f64) -> SpeedTraversalModel {
+        SpeedTraversalModel {
+            start_time: Some(start_time),
+            ..self
+        }
+    }
+
+    /// sets the weekday of the trip start
+    pub fn with_start_weekday(self, start_weekday: Weekday) -> SpeedTraversalModel {
+        SpeedTraversalModel {
+            start_weekday: Some(start_weekday),
+            ..self
+        }
+    }
+
+    /// replaces the engine's default surface penalty factors for this model
+    pub fn with_surface_penalty(self, surface_penalty: SurfacePenalty) -> SpeedTraversalModel {
+        SpeedTraversalModel {
+            surface_penalty: Some(surface_penalty),
+            ..self
+        }
+    }
+
+    /// samples the travel time of each edge from its mean and variance, which
+    /// requires a travel time variance table
+    pub fn with_time_sampler(self, time_sampler: TimeSampler) -> SpeedTraversalModel {
+        SpeedTraversalModel {
+            time_sampler: Some(time_sampler),
+            ..self
+        }
     }
     const DISTANCE: &'static str = "distance";
     const TIME: &'static str = "time";
+    pub const TIME_VARIANCE: &'static str = "time_variance";
+    pub const UNPAVED_DISTANCE: &'static str = "unpaved_distance";
+    pub const SURFACE_PENALTY: &'static str = "surface_penalty";
+    pub const MODELED_DISTANCE: &'static str = "modeled_distance";
+    pub const MEASURED_SPEED_FRACTION: &'static str = "measured_speed_fraction";
+    pub const TOLL: &'static str = "toll";
+
+    /// name of the state feature accumulating the tolls of a pricing period
+    pub fn toll_period_feature(period: &str) -> String {
+        format!("{}_{}", Self::TOLL, period)
+    }
 }
 
 impl TraversalModel for SpeedTraversalModel {
@@ -31,14 +98,18 @@ impl TraversalModel for SpeedTraversalModel {
     ) -> Result<(), TraversalModelError> {
         let (_, edge, _) = trajectory;
         let distance = BASE_DISTANCE_UNIT.convert(&edge.distance, &self.engine.distance_unit);
-        let speed = get_speed(&self.engine.speed_table, edge.edge_id)?;
-        let edge_time = Time::create(
-            &speed,
-            &self.engine.speed_unit,
-            &distance,
-            &self.engine.distance_unit,
-            &self.engine.time_unit,
-        )?;
+        self.add_scheduled_toll(edge, state, state_model)?;
+        let edge_time = self.get_edge_time(edge, &distance, state, state_model)?;
+        let edge_time = match (&self.time_sampler, &self.engine.time_variance_table) {
+            (Some(sampler), Some(variance_table)) => {
+                let variance = variance_table
+                    .get(edge.edge_id.as_usize())
+                    .cloned()
+                    .unwrap_or_default();
+                Time::new(sampler.sample(&edge.edge_id, edge_time.as_f64(), variance))
+            }
+            _ => edge_time,
+        };
 
         state_model.add_time(
             state,
@@ -52,6 +123,57 @@ impl TraversalModel for SpeedTraversalModel {
             &distance,
             &self.engine.distance_unit,
         )?;
+        if self.engine.speed_source.is_some() {
+            self.add_speed_source(edge, &distance, state, state_model)?;
+        }
+        if let Some(variance_table) = &self.engine.time_variance_table {
+            let variance = variance_table.get(edge.edge_id.as_usize()).ok_or_else(|| {
+                TraversalModelError::TraversalModelFailure(format!(
+                    "could not find expected index {} in travel time variance table",
+                    edge.edge_id
+                ))
+            })?;
+            let name = String::from(Self::TIME_VARIANCE);
+            let prev = state_model.get_custom_f64(state, &name)?;
+            state_model.set_custom_f64(state, &name, &(prev + variance))?;
+        }
+        if let Some(surface_table) = &self.engine.surface_table {
+            let surface = surface_table.get(edge.edge_id.as_usize()).ok_or_else(|| {
+                TraversalModelError::TraversalModelFailure(format!(
+                    "could not find expected index {} in surface table",
+                    edge.edge_id
+                ))
+            })?;
+            if !surface.is_paved() {
+                state_model.add_distance(
+                    state,
+                    &Self::UNPAVED_DISTANCE.into(),
+                    &distance,
+                    &self.engine.distance_unit,
+                )?;
+            }
+            let factor = self
+                .surface_penalty
+                .as_ref()
+                .unwrap_or(&self.engine.surface_penalty)
+                .get(surface)
+                .cloned()
+                .unwrap_or_default();
+            if factor > 0.0 {
+                let name = String::from(Self::SURFACE_PENALTY);
+                let prev = state_model.get_custom_f64(state, &name)?;
+                let penalty = distance.as_f64() * factor;
+                state_model.set_custom_f64(state, &name, &(prev + penalty))?;
+            }
+        }
+        if let Some(custom_features) = &self.engine.custom_features {
+            for column in custom_features.columns.iter() {
+                let name = &column.0;
+                let value = CustomEdgeFeatures::get_value(column, edge.edge_id)?;
+                let prev = state_model.get_custom_f64(state, name)?;
+                state_model.set_custom_f64(state, name, &(prev + value))?;
+            }
+        }
         Ok(())
     }
 
@@ -97,9 +219,15 @@ impl TraversalModel for SpeedTraversalModel {
 
         Ok(())
     }
-    /// track the time state feature
+    /// track the time and distance state features, the travel time variance
+    /// when a variance table is present, the unpaved distance and surface
+    /// penalty when a surface table is present, the modeled distance and
+    /// measured speed fraction when speed sources are recorded, and the total
+    /// toll along with the toll of each pricing period when a toll schedule is
+    /// present, and each custom edge feature. these are not estimated, which
+    /// keeps the a* heuristic admissible.
     fn state_features(&self) -> Vec<(String, StateFeature)> {
-        vec![
+        let mut features = vec![
             (
                 String::from(Self::TIME),
                 StateFeature::Time {
@@ -114,7 +242,220 @@ impl TraversalModel for SpeedTraversalModel {
                     initial: Distance::ZERO,
                 },
             ),
-        ]
+        ];
+        if self.engine.time_variance_table.is_some() {
+            features.push((
+                String::from(Self::TIME_VARIANCE),
+                StateFeature::Custom {
+                    r#type: String::from(Self::TIME_VARIANCE),
+                    unit: format!("{}_squared", self.engine.time_unit),
+                    format: CustomFeatureFormat::FloatingPoint {
+                        initial: 0.0.into(),
+                    },
+                },
+            ));
+        }
+        if self.engine.speed_source.is_some() {
+            features.push((
+                String::from(Self::MODELED_DISTANCE),
+                StateFeature::Distance {
+                    distance_unit: self.engine.distance_unit,
+                    initial: Distance::ZERO,
+                },
+            ));
+            features.push((
+                String::from(Self::MEASURED_SPEED_FRACTION),
+                StateFeature::Custom {
+                    r#type: String::from(Self::MEASURED_SPEED_FRACTION),
+                    unit: String::from("fraction"),
+                    format: CustomFeatureFormat::FloatingPoint {
+                        initial: 0.0.into(),
+                    },
+                },
+            ));
+        }
+        if self.engine.surface_table.is_some() {
+            features.push((
+                String::from(Self::UNPAVED_DISTANCE),
+                StateFeature::Distance {
+                    distance_unit: self.engine.distance_unit,
+                    initial: Distance::ZERO,
+                },
+            ));
+            features.push((
+                String::from(Self::SURFACE_PENALTY),
+                StateFeature::Custom {
+                    r#type: String::from(Self::SURFACE_PENALTY),
+                    unit: self.engine.distance_unit.to_string(),
+                    format: CustomFeatureFormat::FloatingPoint {
+                        initial: 0.0.into(),
+                    },
+                },
+            ));
+        }
+        if let Some(schedule) = &self.engine.toll_schedule {
+            let names = std::iter::once(String::from(Self::TOLL)).chain(
+                schedule
+                    .periods()
+                    .iter()
+                    .map(|p| Self::toll_period_feature(p)),
+            );
+            for name in names {
+                features.push((
+                    name,
+                    StateFeature::Custom {
+                        r#type: String::from(Self::TOLL),
+                        unit: String::from("currency"),
+                        format: CustomFeatureFormat::FloatingPoint {
+                            initial: 0.0.into(),
+                        },
+                    },
+                ));
+            }
+        }
+        if let Some(custom_features) = &self.engine.custom_features {
+            features.extend(custom_features.state_features());
+        }
+        features
+    }
+}
+
+impl SpeedTraversalModel {
+    /// the travel time of an edge entered once the time so far on the trip has elapsed.
+    ///
+    /// without a trip start time, the edge speed comes from the speed table. with a
+    /// start time, it is the speed at the time of entry, see [`Self::get_speed_at`].
+    /// with a weekly speed profile, a trip entering an edge at the slow speed of one
+    /// bin could otherwise arrive after a trip entering it later, at the start of a
+    /// faster bin. the arrival is therefore capped by the arrival of a trip entering
+    /// at the start of each following bin, as in the time-dependent travel speeds of
+    /// Ichoua, Gendreau and Potvin (2003). a later entry then never arrives earlier,
+    /// so each edge is first-in-first-out.
+    fn get_edge_time(
+        &self,
+        edge: &Edge,
+        distance: &Distance,
+        state: &[StateVariable],
+        state_model: &StateModel,
+    ) -> Result<Time, TraversalModelError> {
+        let table_speed = get_speed(&self.engine.speed_table, edge.edge_id)?;
+        let time_at_speed = |speed: Speed| {
+            Time::create(
+                &speed,
+                &self.engine.speed_unit,
+                distance,
+                &self.engine.distance_unit,
+                &self.engine.time_unit,
+            )
+        };
+        let start_time = match self.start_time {
+            Some(start_time)
+                if self.engine.speed_schedule.is_some() || self.engine.speed_profile.is_some() =>
+            {
+                start_time
+            }
+            _ => return Ok(time_at_speed(table_speed)?),
+        };
+        let elapsed = state_model.get_time(state, &Self::TIME.into(), &TimeUnit::Seconds)?;
+        let entry = start_time + elapsed.as_f64();
+        let edge_time = time_at_speed(self.get_speed_at(edge, table_speed, entry))?;
+        if self.engine.speed_profile.is_none() || self.start_weekday.is_none() {
+            return Ok(edge_time);
+        }
+
+        let to_seconds = |time: Time| {
+            self.engine
+                .time_unit
+                .convert(&time, &TimeUnit::Seconds)
+                .as_f64()
+        };
+        let arrival = entry + to_seconds(edge_time);
+        let mut capped_arrival = arrival;
+        let mut bin_start = next_bin_start(entry);
+        while bin_start < capped_arrival {
+            let bin_time = time_at_speed(self.get_speed_at(edge, table_speed, bin_start))?;
+            capped_arrival = capped_arrival.min(bin_start + to_seconds(bin_time));
+            bin_start += SPEED_BIN_SECONDS;
+        }
+        if capped_arrival < arrival {
+            let capped = Time::new(capped_arrival - entry);
+            Ok(TimeUnit::Seconds.convert(&capped, &self.engine.time_unit))
+        } else {
+            Ok(edge_time)
+        }
+    }
+
+    /// looks up the edge speed at some time of day, from the weekly speed profile
+    /// when the trip has a weekday, or else the speed from the speed table. the
+    /// speed is then capped by any scheduled speed limit active on the edge.
+    fn get_speed_at(&self, edge: &Edge, table_speed: Speed, time_of_day: f64) -> Speed {
+        let speed = match (&self.engine.speed_profile, self.start_weekday) {
+            (Some(profile), Some(weekday)) => profile
+                .get_speed(&edge.edge_id, seconds_of_week(weekday, time_of_day))
+                .unwrap_or(table_speed),
+            _ => table_speed,
+        };
+        let limit = self
+            .engine
+            .speed_schedule
+            .as_ref()
+            .and_then(|schedule| schedule.get_speed(&edge.edge_id, time_of_day));
+        match limit {
+            Some(limit) if limit < speed => limit,
+            _ => speed,
+        }
+    }
+
+    /// charges the toll of the pricing window active on the edge at the time it is
+    /// entered, adding it to the total toll and to the toll of its pricing period.
+    /// like the speed schedule, tolls are only charged when the trip has a start time.
+    fn add_scheduled_toll(
+        &self,
+        edge: &Edge,
+        state: &mut [StateVariable],
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        let (schedule, start_time) = match (&self.engine.toll_schedule, self.start_time) {
+            (Some(schedule), Some(start_time)) => (schedule, start_time),
+            _ => return Ok(()),
+        };
+        let elapsed = state_model.get_time(state, &Self::TIME.into(), &TimeUnit::Seconds)?;
+        if let Some((toll, period)) =
+            schedule.get_toll(&edge.edge_id, start_time + elapsed.as_f64())
+        {
+            for name in [String::from(Self::TOLL), Self::toll_period_feature(period)] {
+                let prev = state_model.get_custom_f64(state, &name)?;
+                state_model.set_custom_f64(state, &name, &(prev + toll))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// adds the distance of an edge whose speed came from a fallback, rather than
+    /// measured in the speed table, to the modeled distance, and updates the
+    /// fraction of the distance so far traveled at measured speeds.
+    fn add_speed_source(
+        &self,
+        edge: &Edge,
+        distance: &Distance,
+        state: &mut [StateVariable],
+        state_model: &StateModel,
+    ) -> Result<(), TraversalModelError> {
+        let unit = &self.engine.distance_unit;
+        let modeled_name = String::from(Self::MODELED_DISTANCE);
+        match self.engine.get_speed_source(edge.edge_id) {
+            Some(AttributeSource::Table) => {}
+            _ => state_model.add_distance(state, &modeled_name, distance, unit)?,
+        }
+        let total = state_model.get_distance(state, &Self::DISTANCE.into(), unit)?;
+        let modeled = state_model.get_distance(state, &modeled_name, unit)?;
+        let fraction = if total > Distance::ZERO {
+            1.0 - modeled.as_f64() / total.as_f64()
+        } else {
+            0.0
+        };
+        state_model.set_custom_f64(state, &Self::MEASURED_SPEED_FRACTION.into(), &fraction)?;
+        Ok(())
     }
 }
 
@@ -263,4 +604,188 @@ mod tests {
         // approx_eq(result.updated_state[1].into(), expected, 0.001);
         approx_eq(state[1].into(), expected, 0.001);
     }
+
+    #[test]
+    fn test_speed_schedule_applies_during_window() {
+        let schedule_file = filepath().with_file_name("speed_schedule.csv");
+        let engine = SpeedTraversalEngine::new(
+            &filepath(),
+            SpeedUnit::KilometersPerHour,
+            None,
+            Some(TimeUnit::Seconds),
+        )
+        .unwrap()
+        .with_speed_schedule(&schedule_file)
+        .unwrap();
+        let engine = Arc::new(engine);
+        let state_model = Arc::new(
+            StateModel::empty()
+                .extend(SpeedTraversalModel::new(engine.clone()).state_features())
+                .unwrap(),
+        );
+        let traverse = |model: SpeedTraversalModel| -> f64 {
+            let mut state = state_model.initial_state().unwrap();
+            let v = mock_vertex();
+            model
+                .traverse_edge((&v, &mock_edge(0), &v), &mut state, &state_model)
+                .unwrap();
+            state_model
+                .get_time(&state, &"time".into(), &TimeUnit::Seconds)
+                .unwrap()
+                .as_f64()
+        };
+        // 100 meters @ 10kph takes 36 seconds, @ 5kph during the school zone window 72 seconds
+        let morning = SpeedTraversalModel::new(engine.clone()).with_start_time(7.5 * 3600.0);
+        let midday = SpeedTraversalModel::new(engine.clone()).with_start_time(12.0 * 3600.0);
+        let no_start_time = SpeedTraversalModel::new(engine.clone());
+        approx_eq(traverse(morning), 72.0, 0.001);
+        approx_eq(traverse(midday), 36.0, 0.001);
+        approx_eq(traverse(no_start_time), 36.0, 0.001);
+    }
+
+    #[test]
+    fn test_speed_profile_is_first_in_first_out() {
+        let profile_file = filepath().with_file_name("speed_profile.csv");
+        let engine = SpeedTraversalEngine::new(
+            &filepath(),
+            SpeedUnit::KilometersPerHour,
+            None,
+            Some(TimeUnit::Seconds),
+        )
+        .unwrap()
+        .with_speed_profile(&profile_file)
+        .unwrap();
+        assert_eq!(engine.max_speed, Speed::new(50.0));
+        let engine = Arc::new(engine);
+        let state_model = Arc::new(
+            StateModel::empty()
+                .extend(SpeedTraversalModel::new(engine.clone()).state_features())
+                .unwrap(),
+        );
+        let traverse = |model: SpeedTraversalModel| -> f64 {
+            let mut state = state_model.initial_state().unwrap();
+            let v = mock_vertex();
+            model
+                .traverse_edge((&v, &mock_edge(0), &v), &mut state, &state_model)
+                .unwrap();
+            state_model
+                .get_time(&state, &"time".into(), &TimeUnit::Seconds)
+                .unwrap()
+                .as_f64()
+        };
+        let at = |weekday: Weekday, time: f64| {
+            SpeedTraversalModel::new(engine.clone())
+                .with_start_weekday(weekday)
+                .with_start_time(time * 3600.0)
+        };
+        // 100 meters @ 5kph takes 72 seconds, @ 50kph 7.2 seconds, @ 10kph 36 seconds
+        approx_eq(traverse(at(Weekday::Mon, 8.5)), 72.0, 0.001);
+        approx_eq(traverse(at(Weekday::Mon, 10.0)), 7.2, 0.001);
+        approx_eq(traverse(at(Weekday::Tue, 8.5)), 36.0, 0.001);
+        // entered 3.6 seconds before the 09:00 bin, the trip arrives with one that
+        // enters at 09:00, rather than after it
+        approx_eq(traverse(at(Weekday::Mon, 8.999)), 10.8, 0.001);
+        // a later entry never arrives earlier
+        let arrivals = (0..20)
+            .map(|i| {
+                let time = 8.98 + 0.001 * i as f64;
+                time * 3600.0 + traverse(at(Weekday::Mon, time))
+            })
+            .collect::<Vec<_>>();
+        assert!(arrivals.windows(2).all(|w| w[0] <= w[1]), "{:?}", arrivals);
+        // without a weekday the speed table is used
+        let no_weekday = SpeedTraversalModel::new(engine.clone()).with_start_time(8.5 * 3600.0);
+        approx_eq(traverse(no_weekday), 36.0, 0.001);
+    }
+
+    #[test]
+    fn test_measured_speed_fraction() {
+        use crate::model::traversal::attribute_fallback::{
+            AttributeFallback, AttributeFallbackChain,
+        };
+        // edge 0 has a measured speed of 10kph, edge 1 falls back to a modeled 15kph
+        let fallback = AttributeFallbackChain {
+            fallbacks: vec![AttributeFallback::Global(Speed::new(15.0))],
+            record_source: true,
+        };
+        let engine = SpeedTraversalEngine::new_with_fallback(
+            &filepath().with_file_name("velocities_missing.txt"),
+            &fallback,
+            SpeedUnit::KilometersPerHour,
+            None,
+            Some(TimeUnit::Seconds),
+        )
+        .unwrap();
+        let model = SpeedTraversalModel::new(Arc::new(engine));
+        let state_model = StateModel::empty().extend(model.state_features()).unwrap();
+        let mut state = state_model.initial_state().unwrap();
+        let v = mock_vertex();
+        for edge_id in [0, 1, 1] {
+            model
+                .traverse_edge((&v, &mock_edge(edge_id), &v), &mut state, &state_model)
+                .unwrap();
+        }
+        // 100 meters @ 10kph takes 36 seconds, and 200 meters @ 15kph takes 48 seconds
+        let time = state_model
+            .get_time(&state, &"time".into(), &TimeUnit::Seconds)
+            .unwrap();
+        approx_eq(time.as_f64(), 84.0, 0.001);
+        let modeled = state_model
+            .get_distance(&state, &"modeled_distance".into(), &DistanceUnit::Meters)
+            .unwrap();
+        approx_eq(modeled.as_f64(), 200.0, 0.001);
+        let fraction = state_model
+            .get_custom_f64(&state, &"measured_speed_fraction".into())
+            .unwrap();
+        approx_eq(fraction, 1.0 / 3.0, 0.001);
+    }
+
+    #[test]
+    fn test_new_custom_column_is_weightable_without_config() {
+        use crate::model::cost::{CostAggregation, CostModel, VehicleCostRate};
+        use std::collections::HashMap;
+
+        let engine = SpeedTraversalEngine::new(
+            &filepath(),
+            SpeedUnit::KilometersPerHour,
+            None,
+            Some(TimeUnit::Seconds),
+        )
+        .unwrap()
+        .with_custom_features(&filepath().with_file_name("edge_attributes_extended.csv"))
+        .unwrap();
+        let model = SpeedTraversalModel::new(Arc::new(engine));
+        let state_model = Arc::new(StateModel::empty().extend(model.state_features()).unwrap());
+
+        // only the cost model refers to the new "pothole_count" column
+        let cost_model = CostModel::new(
+            Arc::new(HashMap::from([(String::from("pothole_count"), 1.0)])),
+            Arc::new(HashMap::from([(
+                String::from("pothole_count"),
+                VehicleCostRate::Raw,
+            )])),
+            Arc::new(HashMap::new()),
+            CostAggregation::Sum,
+            state_model.clone(),
+        )
+        .unwrap();
+
+        let v = mock_vertex();
+        let mut prev = state_model.initial_state().unwrap();
+        model
+            .traverse_edge((&v, &mock_edge(0), &v), &mut prev, &state_model)
+            .unwrap();
+        let mut next = prev.clone();
+        model
+            .traverse_edge((&v, &mock_edge(2), &v), &mut next, &state_model)
+            .unwrap();
+        let potholes = state_model
+            .get_custom_f64(&next, &String::from("pothole_count"))
+            .unwrap();
+        assert_eq!(potholes, 10.0);
+        let cost = cost_model
+            .traversal_cost(&mock_edge(2), &prev, &next)
+            .unwrap();
+        assert_eq!(cost.as_f64(), 7.0);
+    }
 }
@@ -1,12 +1,35 @@
 use super::{
-    speed_traversal_engine::SpeedTraversalEngine, speed_traversal_model::SpeedTraversalModel,
+    speed_schedule::parse_time_of_day,
+    speed_traversal_engine::SpeedTraversalEngine,
+    speed_traversal_model::SpeedTraversalModel,
+    surface_type::{validate_surface_penalty, SurfacePenalty},
+    time_sampler::TimeSampler,
 };
 use crate::model::traversal::{
     traversal_model::TraversalModel, traversal_model_error::TraversalModelError,
     traversal_model_service::TraversalModelService,
 };
+use chrono::Weekday;
 use std::sync::Arc;
 
+/// query key for the trip start time, written as a time of day (`HH:MM` or
+/// `HH:MM:SS`). used to apply time-windowed speed limits.
+pub const START_TIME: &str = "start_time";
+
+/// query key for the weekday of the trip start, such as `"monday"`. used with
+/// the start time to look up weekly speed profiles.
+pub const START_WEEKDAY: &str = "start_weekday";
+
+/// query key for the penalty factor of each surface type, such as
+/// `{"gravel": 0.5, "dirt": 2.0}`. replaces the configured factors, so that
+/// each vehicle may set its own tolerance for unpaved roads.
+pub const SURFACE_PENALTY: &str = "surface_penalty";
+
+/// query key for a random seed. when set, the travel time of each edge is drawn
+/// from its mean and variance, so that a query run with many seeds gives a
+/// distribution of trip times. requires a travel time variance table.
+pub const SEED: &str = "seed";
+
 pub struct SpeedLookupService {
     pub e: Arc<SpeedTraversalEngine>,
 }
@@ -14,8 +37,76 @@ pub struct SpeedLookupService {
 impl TraversalModelService for SpeedLookupService {
     fn build(
         &self,
-        _parameters: &serde_json::Value,
+        parameters: &serde_json::Value,
     ) -> Result<Arc<dyn TraversalModel>, TraversalModelError> {
-        Ok(Arc::new(SpeedTraversalModel::new(self.e.clone())))
+        let model = SpeedTraversalModel::new(self.e.clone());
+        let model = match parameters.get(SURFACE_PENALTY) {
+            None => model,
+            Some(value) => {
+                let surface_penalty: SurfacePenalty = serde_json::from_value(value.clone())
+                    .map_err(|e| {
+                        TraversalModelError::BuildError(format!(
+                            "query field '{}' must map surface types to penalty factors: {}",
+                            SURFACE_PENALTY, e
+                        ))
+                    })?;
+                validate_surface_penalty(&surface_penalty)?;
+                model.with_surface_penalty(surface_penalty)
+            }
+        };
+        let model = match parameters.get(SEED) {
+            None => model,
+            Some(value) => {
+                let seed = value.as_u64().ok_or_else(|| {
+                    TraversalModelError::BuildError(format!(
+                        "query field '{}' must be a non-negative integer, found {}",
+                        SEED, value
+                    ))
+                })?;
+                if self.e.time_variance_table.is_none() {
+                    return Err(TraversalModelError::BuildError(format!(
+                        "query field '{}' samples edge travel times, which requires a time_variance_input_file",
+                        SEED
+                    )));
+                }
+                model.with_time_sampler(TimeSampler::new(seed))
+            }
+        };
+        let model = match parameters.get(START_WEEKDAY) {
+            None => model,
+            Some(value) => {
+                let weekday = value
+                    .as_str()
+                    .and_then(|s| s.parse::<Weekday>().ok())
+                    .ok_or_else(|| {
+                        TraversalModelError::BuildError(format!(
+                            "query field '{}' must be a weekday such as 'monday', found {}",
+                            START_WEEKDAY, value
+                        ))
+                    })?;
+                model.with_start_weekday(weekday)
+            }
+        };
+        match parameters.get(START_TIME) {
+            None => Ok(Arc::new(model)),
+            Some(value) => {
+                let start_time = value
+                    .as_str()
+                    .ok_or_else(|| {
+                        TraversalModelError::BuildError(format!(
+                            "query field '{}' must be a string, found {}",
+                            START_TIME, value
+                        ))
+                    })
+                    .and_then(parse_time_of_day)?;
+                if self.e.speed_profile.is_some() && parameters.get(START_WEEKDAY).is_none() {
+                    return Err(TraversalModelError::BuildError(format!(
+                        "query field '{}' is required with '{}' to look up the weekly speed profile",
+                        START_WEEKDAY, START_TIME
+                    )));
+                }
+                Ok(Arc::new(model.with_start_time(start_time)))
+            }
+        }
     }
 }
@@ -0,0 +1,54 @@
+use crate::model::traversal::TraversalModelError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// the road surface of an edge
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SurfaceType {
+    Paved,
+    Gravel,
+    Dirt,
+}
+
+/// penalty factor for each surface type. traversing an edge accrues a penalty
+/// of the edge distance times the factor of its surface. surfaces without a
+/// factor have no penalty.
+pub type SurfacePenalty = HashMap<SurfaceType, f64>;
+
+impl SurfaceType {
+    pub fn is_paved(&self) -> bool {
+        matches!(self, SurfaceType::Paved)
+    }
+}
+
+impl FromStr for SurfaceType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "paved" => Ok(SurfaceType::Paved),
+            "gravel" => Ok(SurfaceType::Gravel),
+            "dirt" => Ok(SurfaceType::Dirt),
+            other => Err(format!(
+                "unknown surface type '{}', expected one of 'paved', 'gravel', 'dirt'",
+                other
+            )),
+        }
+    }
+}
+
+/// confirms all penalty factors are non-negative, which keeps the a* heuristic
+/// admissible when the penalty is used as a cost.
+pub fn validate_surface_penalty(
+    surface_penalty: &SurfacePenalty,
+) -> Result<(), TraversalModelError> {
+    match surface_penalty.iter().find(|(_, factor)| **factor < 0.0) {
+        Some((surface, factor)) => Err(TraversalModelError::BuildError(format!(
+            "surface penalty must be non-negative, found {} for surface {:?}",
+            factor, surface
+        ))),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,84 @@
+use crate::model::network::EdgeId;
+
+/// draws random edge travel times from a log-normal distribution with the mean
+/// and variance of each edge, for Monte Carlo estimates of trip times.
+///
+/// each draw is a hash of the seed and the edge id, rather than the next value of
+/// a random number generator, so that an edge has the same travel time however
+/// many times a search traverses it, and the same seed always repeats a sample.
+#[derive(Clone, Copy, Debug)]
+pub struct TimeSampler {
+    seed: u64,
+}
+
+impl TimeSampler {
+    pub fn new(seed: u64) -> TimeSampler {
+        TimeSampler { seed }
+    }
+
+    /// draws the travel time of an edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge_id` - the edge traversed
+    /// * `mean` - the mean travel time of the edge
+    /// * `variance` - the variance of the travel time, in the square of the unit of `mean`
+    ///
+    /// # Returns
+    ///
+    /// a travel time, which is the mean when the mean or variance is not positive
+    pub fn sample(&self, edge_id: &EdgeId, mean: f64, variance: f64) -> f64 {
+        if mean <= 0.0 || variance <= 0.0 {
+            return mean;
+        }
+        let sigma_squared = (1.0 + variance / (mean * mean)).ln();
+        let mu = mean.ln() - sigma_squared / 2.0;
+        let z = self.standard_normal(edge_id);
+        (mu + sigma_squared.sqrt() * z).exp()
+    }
+
+    /// a standard normal value for an edge, by the Box-Muller transform of two
+    /// uniform values hashed from the seed and the edge id
+    fn standard_normal(&self, edge_id: &EdgeId) -> f64 {
+        let h1 = splitmix64(self.seed ^ splitmix64(edge_id.0 as u64));
+        let h2 = splitmix64(h1);
+        // uniform values in (0, 1], from the top 53 bits of each hash
+        let u1 = ((h1 >> 11) + 1) as f64 / (1u64 << 53) as f64;
+        let u2 = (h2 >> 11) as f64 / (1u64 << 53) as f64;
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+/// the SplitMix64 mixing function, which scatters consecutive inputs across the
+/// full range of outputs
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_mean_and_variance() {
+        let sampler = TimeSampler::new(7);
+        let n = 100_000;
+        let samples = (0..n)
+            .map(|i| sampler.sample(&EdgeId(i), 2.0, 0.5))
+            .collect::<Vec<_>>();
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n as f64;
+        assert!((mean - 2.0).abs() < 0.01, "mean {}", mean);
+        assert!((variance - 0.5).abs() < 0.02, "variance {}", variance);
+        assert!(samples.iter().all(|s| *s > 0.0));
+
+        // draws repeat for the same seed and edge, and differ across seeds
+        let edge_id = EdgeId(3);
+        assert_eq!(sampler.sample(&edge_id, 2.0, 0.5), samples[3]);
+        assert_ne!(TimeSampler::new(8).sample(&edge_id, 2.0, 0.5), samples[3]);
+        assert_eq!(sampler.sample(&edge_id, 2.0, 0.0), 2.0);
+    }
+}
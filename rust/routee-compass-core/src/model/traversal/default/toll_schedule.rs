@@ -0,0 +1,139 @@
+use super::speed_schedule::{is_within_window, parse_time_of_day, SECONDS_PER_DAY};
+use crate::model::network::EdgeId;
+use crate::model::traversal::TraversalModelError;
+use crate::util::fs::read_utils;
+use kdam::Bar;
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+/// a row of a toll schedule file, which is a CSV with header
+/// `edge_id,start_time,end_time,toll,period`. times are times of day written
+/// as `HH:MM` or `HH:MM:SS`, and the period names the pricing period of the
+/// window, such as `peak` or `off_peak`.
+#[derive(Deserialize, Debug)]
+struct TollScheduleRow {
+    edge_id: EdgeId,
+    start_time: String,
+    end_time: String,
+    toll: f64,
+    period: String,
+}
+
+/// a toll charged for entering an edge during a daily time window. times are
+/// in seconds since midnight, and a window with an end before its start wraps
+/// around midnight.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TollWindow {
+    pub start: f64,
+    pub end: f64,
+    pub toll: f64,
+    /// index of the pricing period in [`TollSchedule::periods`]
+    pub period: usize,
+}
+
+/// time-windowed toll rates, grouped by edge, such as for congestion pricing.
+#[derive(Clone, Debug, Default)]
+pub struct TollSchedule {
+    windows: HashMap<EdgeId, Vec<TollWindow>>,
+    /// the names of the pricing periods, sorted
+    periods: Vec<String>,
+}
+
+impl TollSchedule {
+    /// reads a toll schedule CSV file. see [`TollScheduleRow`] for the format.
+    pub fn from_csv<P: AsRef<Path>>(path: &P) -> Result<TollSchedule, TraversalModelError> {
+        let rows: Box<[TollScheduleRow]> =
+            read_utils::from_csv(path, true, Some(Bar::builder().desc("toll schedule")), None)
+                .map_err(|e| {
+                    TraversalModelError::BuildError(format!(
+                        "cannot read {} due to {}",
+                        path.as_ref().to_str().unwrap_or_default(),
+                        e,
+                    ))
+                })?;
+        let periods: Vec<String> = rows
+            .iter()
+            .map(|r| r.period.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let mut windows: HashMap<EdgeId, Vec<TollWindow>> = HashMap::new();
+        for row in rows.iter() {
+            if !(row.toll >= 0.0 && row.toll.is_finite()) {
+                return Err(TraversalModelError::BuildError(format!(
+                    "toll must be a non-negative number, found {} for edge {}",
+                    row.toll, row.edge_id
+                )));
+            }
+            let window = TollWindow {
+                start: parse_time_of_day(&row.start_time)?,
+                end: parse_time_of_day(&row.end_time)?,
+                toll: row.toll,
+                period: periods
+                    .iter()
+                    .position(|p| *p == row.period)
+                    .unwrap_or_default(),
+            };
+            windows.entry(row.edge_id).or_default().push(window);
+        }
+        Ok(TollSchedule { windows, periods })
+    }
+
+    /// the names of the pricing periods of this schedule, sorted
+    pub fn periods(&self) -> &[String] {
+        &self.periods
+    }
+
+    /// finds the toll charged for entering an edge at some time of day, in
+    /// seconds since midnight. times beyond one day wrap around. if more than
+    /// one window is active, the highest toll is charged.
+    ///
+    /// # Returns
+    ///
+    /// the toll and the name of its pricing period, or None if no toll is charged
+    pub fn get_toll(&self, edge_id: &EdgeId, time_of_day: f64) -> Option<(f64, &str)> {
+        let time_of_day = time_of_day.rem_euclid(SECONDS_PER_DAY);
+        self.windows
+            .get(edge_id)?
+            .iter()
+            .filter(|w| is_within_window(w.start, w.end, time_of_day))
+            .max_by(|a, b| a.toll.total_cmp(&b.toll))
+            .map(|w| (w.toll, self.periods[w.period].as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toll_by_period() {
+        let peak = TollWindow {
+            start: parse_time_of_day("07:00").unwrap(),
+            end: parse_time_of_day("09:00").unwrap(),
+            toll: 5.0,
+            period: 1,
+        };
+        let off_peak = TollWindow {
+            start: parse_time_of_day("09:00").unwrap(),
+            end: parse_time_of_day("07:00").unwrap(),
+            toll: 1.0,
+            period: 0,
+        };
+        let schedule = TollSchedule {
+            windows: HashMap::from([(EdgeId(0), vec![peak, off_peak])]),
+            periods: vec![String::from("off_peak"), String::from("peak")],
+        };
+        let at = |t: &str| schedule.get_toll(&EdgeId(0), parse_time_of_day(t).unwrap());
+        assert_eq!(at("08:00"), Some((5.0, "peak")));
+        assert_eq!(at("09:00"), Some((1.0, "off_peak")));
+        assert_eq!(at("03:00"), Some((1.0, "off_peak")));
+        assert_eq!(schedule.get_toll(&EdgeId(1), 28800.0), None);
+        // next day wraps around
+        assert_eq!(
+            schedule.get_toll(&EdgeId(0), SECONDS_PER_DAY + 28800.0),
+            Some((5.0, "peak"))
+        );
+    }
+}
@@ -1,3 +1,4 @@
+pub mod attribute_fallback;
 pub mod default;
 mod traversal_model;
 mod traversal_model_builder;
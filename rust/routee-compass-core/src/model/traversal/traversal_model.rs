@@ -19,6 +19,13 @@ pub trait TraversalModel: Send + Sync {
     /// into the state model by listing them here.
     fn state_features(&self) -> Vec<(String, StateFeature)>;
 
+    /// lists the names of state features this traversal model reads or updates
+    /// without declaring them in [`TraversalModel::state_features`]. each must be
+    /// provided by the state configuration.
+    fn input_features(&self) -> Vec<String> {
+        vec![]
+    }
+
     /// Updates the traversal state by traversing an edge.
     ///
     /// # Arguments
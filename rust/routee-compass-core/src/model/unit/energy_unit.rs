@@ -9,8 +9,11 @@ pub enum EnergyUnit {
     GallonsGasoline,
     GallonsDiesel,
     KilowattHours,
+    Megajoules,
 }
 
+const MEGAJOULES_PER_KILOWATT_HOUR: f64 = 3.6;
+
 impl EnergyUnit {
     // see https://epact.energy.gov/fuel-conversion-factors
     pub fn convert(&self, value: &Energy, target: &EnergyUnit) -> Energy {
@@ -25,6 +28,15 @@ impl EnergyUnit {
             (S::KilowattHours, S::GallonsDiesel) => *value * 0.02457,
             (S::GallonsDiesel, S::GallonsGasoline) => *value * 1.155,
             (S::GallonsGasoline, S::GallonsDiesel) => *value * 0.866,
+            (S::Megajoules, S::Megajoules) => *value,
+            (S::KilowattHours, S::Megajoules) => *value * MEGAJOULES_PER_KILOWATT_HOUR,
+            (S::Megajoules, S::KilowattHours) => *value * (1.0 / MEGAJOULES_PER_KILOWATT_HOUR),
+            (S::Megajoules, other) => {
+                S::KilowattHours.convert(&S::Megajoules.convert(value, &S::KilowattHours), other)
+            }
+            (other, S::Megajoules) => {
+                S::KilowattHours.convert(&other.convert(value, &S::KilowattHours), &S::Megajoules)
+            }
         }
     }
 }
@@ -45,3 +57,19 @@ impl FromStr for EnergyUnit {
         string_deserialize(s)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::EnergyUnit as E;
+    use crate::model::unit::{AsF64, Energy};
+
+    #[test]
+    fn test_megajoule_conversions() {
+        let kwh = E::Megajoules.convert(&Energy::new(36.0), &E::KilowattHours);
+        assert!((kwh.as_f64() - 10.0).abs() < 1e-9);
+        let mj = E::KilowattHours.convert(&Energy::new(10.0), &E::Megajoules);
+        assert!((mj.as_f64() - 36.0).abs() < 1e-9);
+        let gallons = E::Megajoules.convert(&Energy::new(3.6), &E::GallonsGasoline);
+        assert!((gallons.as_f64() - 0.031).abs() < 1e-9);
+    }
+}
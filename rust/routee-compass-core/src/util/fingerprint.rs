@@ -0,0 +1,72 @@
+use serde_json::Value;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// a 64-bit FNV-1a hash. unlike the hashers of the standard library, the result
+/// is stable across runs, platforms and releases, so it can identify inputs in
+/// stored results.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fingerprint(u64);
+
+impl Default for Fingerprint {
+    fn default() -> Self {
+        Fingerprint(FNV_OFFSET_BASIS)
+    }
+}
+
+impl Fingerprint {
+    pub fn write(&mut self, bytes: &[u8]) {
+        self.0 = bytes.iter().fold(self.0, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+        });
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    /// writes a JSON value with the keys of every object in sorted order, so that
+    /// values that differ only in key order have the same fingerprint
+    pub fn write_json(&mut self, value: &Value) {
+        let mut canonical = String::new();
+        write_canonical(value, &mut canonical);
+        self.write(canonical.as_bytes());
+    }
+
+    /// the hash as 16 hexadecimal characters
+    pub fn to_hex(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
+
+/// writes a JSON value with the keys of every object in sorted order
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (idx, key) in keys.into_iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        Value::Array(values) => {
+            out.push('[');
+            for (idx, v) in values.iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                write_canonical(v, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
@@ -10,6 +10,21 @@ where
     })
 }
 
+/// read decoder for a type T that allows missing values. blank rows
+/// decode to None, and other rows are decoded via the FromStr trait.
+pub fn optional<T>(_idx: usize, row: String) -> Result<Option<T>, std::io::Error>
+where
+    T: std::str::FromStr<Err = String>,
+{
+    if row.trim().is_empty() {
+        return Ok(None);
+    }
+    row.trim().parse::<T>().map(Some).map_err(|e| {
+        let msg = format!("failure decoding row {} due to: {:}", row, e);
+        std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+    })
+}
+
 pub fn string(_idx: usize, row: String) -> Result<String, std::io::Error> {
     Ok(row)
 }
@@ -20,3 +35,10 @@ pub fn u8(_idx: usize, row: String) -> Result<u8, std::io::Error> {
         std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
     })
 }
+
+pub fn f64(_idx: usize, row: String) -> Result<f64, std::io::Error> {
+    row.parse::<f64>().map_err(|e| {
+        let msg = format!("failure decoding row {} due to: {:}", row, e);
+        std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+    })
+}
@@ -126,6 +126,93 @@ where
     Ok(Box::new(reader))
 }
 
+/// reads a single named column from a CSV file of edge attributes into a
+/// table indexed by edge id. the file must have a header row with an
+/// `edge_id` column, and must contain exactly one row for each edge id
+/// from zero to the number of rows, in any order. this allows several
+/// models to share one attributes file, keeping their values aligned.
+///
+/// # Arguments
+/// * `filepath` - path to the CSV file, which may be gzipped
+/// * `column` - name of the column to read
+/// * `bar_builder` - optional progress bar
+///
+/// # Returns
+///
+/// the column values indexed by edge id, or an error if a column is missing,
+/// a value fails to decode, or the edge ids are not complete and unique.
+pub fn read_edge_attribute_column<F, T>(
+    filepath: F,
+    column: &str,
+    bar_builder: Option<BarBuilder>,
+) -> Result<Box<[T]>, io::Error>
+where
+    F: AsRef<Path>,
+    T: std::str::FromStr<Err = String>,
+{
+    let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+    let f = File::open(filepath.as_ref())?;
+    let r: Box<dyn io::Read> = if fs_utils::is_gzip(filepath.as_ref()) {
+        Box::new(BufReader::new(GzDecoder::new(f)))
+    } else {
+        Box::new(f)
+    };
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .trim(csv::Trim::Fields)
+        .from_reader(r);
+    let headers = reader.headers().map_err(io::Error::from)?.clone();
+    let find_column = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h == name)
+            .ok_or_else(|| invalid(format!("edge attributes file has no column '{}'", name)))
+    };
+    let edge_id_idx = find_column("edge_id")?;
+    let value_idx = find_column(column)?;
+
+    let mut bar_opt = bar_builder.and_then(progress::build_progress_bar);
+    let mut rows: Vec<(usize, T)> = vec![];
+    for record in reader.records() {
+        let record = record.map_err(io::Error::from)?;
+        let edge_id = record
+            .get(edge_id_idx)
+            .and_then(|e| e.parse::<usize>().ok())
+            .ok_or_else(|| invalid(format!("invalid edge_id in row {:?}", record)))?;
+        let raw = record.get(value_idx).unwrap_or_default();
+        let value = raw.parse::<T>().map_err(|e| {
+            invalid(format!(
+                "failure decoding column '{}' value '{}' for edge {} due to: {}",
+                column, raw, edge_id, e
+            ))
+        })?;
+        rows.push((edge_id, value));
+        if let Some(bar) = &mut bar_opt {
+            let _ = bar.update(1);
+        }
+    }
+    if bar_opt.is_some() {
+        eprintln!();
+    }
+
+    let n_rows = rows.len();
+    let mut table: Vec<Option<T>> = (0..n_rows).map(|_| None).collect();
+    for (edge_id, value) in rows.into_iter() {
+        let slot = table.get_mut(edge_id).ok_or_else(|| {
+            invalid(format!(
+                "edge_id {} out of range for edge attributes file with {} rows",
+                edge_id, n_rows
+            ))
+        })?;
+        if slot.is_some() {
+            return Err(invalid(format!("duplicate edge_id {}", edge_id)));
+        }
+        *slot = Some(value);
+    }
+    // every slot is filled: there are n_rows unique ids, each less than n_rows
+    Ok(table.into_iter().flatten().collect())
+}
+
 /// reads a regular file using a simple deserialization operation.
 ///
 /// # Arguments
@@ -199,7 +286,8 @@ where
 mod tests {
     use std::path::PathBuf;
 
-    use super::read_raw_file;
+    use super::{read_edge_attribute_column, read_raw_file};
+    use crate::model::unit::Speed;
 
     #[test]
     fn test_read_raw_file() {
@@ -250,4 +338,19 @@ mod tests {
             "result should include each row from the source file"
         );
     }
+
+    #[test]
+    fn test_read_edge_attribute_column() {
+        let filepath = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("util")
+            .join("fs")
+            .join("test")
+            .join("edge_attributes.csv");
+        let speeds: Box<[Speed]> = read_edge_attribute_column(&filepath, "speed", None).unwrap();
+        let expected = [50.0, 30.0, 80.0].map(Speed::new);
+        assert_eq!(speeds.as_ref(), &expected);
+        let missing = read_edge_attribute_column::<_, Speed>(&filepath, "lanes", None);
+        assert!(missing.is_err());
+    }
 }
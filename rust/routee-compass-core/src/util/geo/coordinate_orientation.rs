@@ -0,0 +1,91 @@
+use geo::{Coord, CoordNum};
+use serde::{Deserialize, Serialize};
+
+/// order of the two values of a coordinate in an input source. compass works
+/// in longitude, latitude (x, y) order, which is the default for every input.
+/// sources written in latitude, longitude order are read by setting
+/// `coordinate_orientation = "lat_lon"` on the component that reads them.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CoordinateOrientation {
+    #[default]
+    LonLat,
+    LatLon,
+}
+
+impl CoordinateOrientation {
+    /// reads a coordinate whose x and y fields hold values in this orientation
+    /// as a longitude, latitude coordinate.
+    pub fn orient<T: CoordNum>(&self, coord: Coord<T>) -> Coord<T> {
+        match self {
+            CoordinateOrientation::LonLat => coord,
+            CoordinateOrientation::LatLon => Coord {
+                x: coord.y,
+                y: coord.x,
+            },
+        }
+    }
+
+    /// reads a coordinate in this orientation and flags it if it is out of
+    /// range, see [`check_coordinate`].
+    pub fn orient_checked(&self, coord: Coord<f32>) -> Result<Coord<f32>, String> {
+        let oriented = self.orient(coord);
+        check_coordinate(&oriented)
+            .map_err(|e| format!("{}, coordinate_orientation is {}", e, self))?;
+        Ok(oriented)
+    }
+}
+
+impl std::fmt::Display for CoordinateOrientation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CoordinateOrientation::LonLat => write!(f, "lon_lat"),
+            CoordinateOrientation::LatLon => write!(f, "lat_lon"),
+        }
+    }
+}
+
+/// sanity check that a longitude, latitude coordinate is in range. a latitude
+/// outside of [-90, 90] whose longitude would be a valid latitude usually means
+/// the source was written in the other coordinate order.
+pub fn check_coordinate(coord: &Coord<f32>) -> Result<(), String> {
+    let lat_valid = (-90.0..=90.0).contains(&coord.y);
+    let lon_valid = (-180.0..=180.0).contains(&coord.x);
+    match (lon_valid, lat_valid) {
+        (true, true) => Ok(()),
+        (_, false) if (-90.0..=90.0).contains(&coord.x) => Err(format!(
+            "coordinate ({}, {}) has latitude outside of [-90, 90], its values look swapped",
+            coord.x, coord.y
+        )),
+        _ => Err(format!(
+            "coordinate ({}, {}) is not a valid longitude, latitude pair",
+            coord.x, coord.y
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::coord;
+
+    #[test]
+    fn test_swapped_coordinate_is_corrected_or_flagged() {
+        // Denver, written in latitude, longitude order
+        let swapped = coord! {x: 39.738_f32, y: -105.168_f32};
+        let flagged = CoordinateOrientation::LonLat.orient_checked(swapped);
+        assert!(flagged.unwrap_err().contains("look swapped"));
+
+        let corrected = CoordinateOrientation::LatLon
+            .orient_checked(swapped)
+            .unwrap();
+        assert_eq!(corrected, coord! {x: -105.168_f32, y: 39.738_f32});
+
+        // both orders are plausible for a point near the origin, so nothing is flagged
+        let ambiguous = coord! {x: 10.0_f32, y: 20.0_f32};
+        assert!(CoordinateOrientation::LonLat
+            .orient_checked(ambiguous)
+            .is_ok());
+        assert!(check_coordinate(&coord! {x: 500.0_f32, y: 500.0_f32}).is_err());
+    }
+}
@@ -56,3 +56,14 @@ pub fn haversine_distance_meters(
     let distance_meters = APPROX_EARTH_RADIUS_M * c;
     Ok(Distance::new(distance_meters.into()))
 }
+
+/// initial great circle bearing from the source to the destination
+/// coordinate, in degrees clockwise from north in the range [0, 360).
+/// coordinates are assumed to be in the WGS84 Coordinate System.
+pub fn coord_bearing_degrees(src: &Coord<f32>, dst: &Coord<f32>) -> f32 {
+    let (src_lat, dst_lat) = (src.y.to_radians(), dst.y.to_radians());
+    let delta_lon = (dst.x - src.x).to_radians();
+    let y = delta_lon.sin() * dst_lat.cos();
+    let x = src_lat.cos() * dst_lat.sin() - src_lat.sin() * dst_lat.cos() * delta_lon.cos();
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
@@ -1,3 +1,4 @@
 pub mod coord;
+pub mod coordinate_orientation;
 pub mod geo_io_utils;
 pub mod haversine;
@@ -2,6 +2,7 @@ pub mod cache_policy;
 pub mod compact_ordered_hash_map;
 pub mod conversion;
 pub mod duration_extension;
+pub mod fingerprint;
 pub mod fs;
 pub mod geo;
 pub mod io_utils;
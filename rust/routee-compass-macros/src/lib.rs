@@ -56,6 +56,48 @@ pub fn pybindings(_attr: TokenStream, item: TokenStream) -> TokenStream {
             fn graph_get_in_edge_ids(&self, vertex_id: usize) -> Vec<usize> {
                 CompassAppBindings::graph_get_in_edge_ids(self, vertex_id)
             }
+            #[pyo3(signature = (edge_ids, distance_unit=None))]
+            fn graph_edge_distances(&self, edge_ids: Vec<usize>, distance_unit: Option<String>) -> PyResult<Vec<f64>> {
+                CompassAppBindings::graph_edge_distances(self, edge_ids, distance_unit).map_err(|e| {
+                    PyException::new_err(format!(
+                        "error retrieving edge distances: {}",
+                        e
+                    ))
+                })
+            }
+            fn graph_get_out_edge_ids_batch(&self, vertex_ids: Vec<usize>) -> Vec<Vec<usize>> {
+                CompassAppBindings::graph_get_out_edge_ids_batch(self, vertex_ids)
+            }
+            fn graph_get_in_edge_ids_batch(&self, vertex_ids: Vec<usize>) -> Vec<Vec<usize>> {
+                CompassAppBindings::graph_get_in_edge_ids_batch(self, vertex_ids)
+            }
+            fn graph_vertex_sequence(&self, edge_ids: Vec<usize>) -> PyResult<Vec<usize>> {
+                CompassAppBindings::graph_vertex_sequence(self, edge_ids).map_err(|e| {
+                    PyException::new_err(format!(
+                        "error retrieving vertex sequence for route: {}",
+                        e
+                    ))
+                })
+            }
+            fn graph_vertex_coordinate(&self, vertex_id: usize) -> PyResult<(f64, f64)> {
+                CompassAppBindings::graph_vertex_coordinate(self, vertex_id).map_err(|e| {
+                    PyException::new_err(format!(
+                        "error retrieving vertex coordinate for vertex_id {}: {}",
+                        vertex_id, e
+                    ))
+                })
+            }
+            fn graph_vertex_coordinates(&self, vertex_ids: Vec<usize>) -> PyResult<Vec<(f64, f64)>> {
+                CompassAppBindings::graph_vertex_coordinates(self, vertex_ids).map_err(|e| {
+                    PyException::new_err(format!(
+                        "error retrieving vertex coordinates: {}",
+                        e
+                    ))
+                })
+            }
+            fn fingerprint(&self) -> std::collections::HashMap<String, String> {
+                CompassAppBindings::fingerprint(self)
+            }
             #[staticmethod]
             pub fn _from_config_toml_string(
                 config_string: String,
@@ -80,6 +122,68 @@ pub fn pybindings(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 CompassAppBindings::run_queries(self, queries, config)
                     .map_err(|e| PyException::new_err(format!("Error while running queries: {}", e)))
             }
+
+            #[pyo3(signature = (queries, config=None))]
+            pub fn _run_queries_keyed(
+                &self,
+                queries: Vec<String>,
+                config: Option<String>,
+            ) -> PyResult<String> {
+                CompassAppBindings::run_queries_keyed(self, queries, config)
+                    .map_err(|e| PyException::new_err(format!("Error while running queries: {}", e)))
+            }
+
+            #[pyo3(signature = (queries, config=None))]
+            pub fn _run_queries_with_edge_usage(
+                &self,
+                queries: Vec<String>,
+                config: Option<String>,
+            ) -> PyResult<(Vec<String>, std::collections::HashMap<usize, u64>)> {
+                CompassAppBindings::run_queries_with_edge_usage(self, queries, config)
+                    .map_err(|e| PyException::new_err(format!("Error while running queries: {}", e)))
+            }
+
+            #[pyo3(signature = (samples, seed=None, config=None))]
+            pub fn _run_edge_importance(
+                &self,
+                samples: usize,
+                seed: Option<u64>,
+                config: Option<String>,
+            ) -> PyResult<std::collections::HashMap<usize, f64>> {
+                CompassAppBindings::run_edge_importance(self, samples, seed, config)
+                    .map_err(|e| PyException::new_err(format!("Error while computing edge importance: {}", e)))
+            }
+
+            pub fn _run_eta_distribution(&self, query: String, samples: usize) -> PyResult<String> {
+                CompassAppBindings::run_eta_distribution(self, query, samples)
+                    .map_err(|e| PyException::new_err(format!("Error while sampling trip times: {}", e)))
+            }
+
+            #[pyo3(signature = (origins, destinations, query=None))]
+            pub fn _run_matrix(
+                &self,
+                origins: Vec<usize>,
+                destinations: Vec<usize>,
+                query: Option<String>,
+            ) -> PyResult<Vec<Vec<Option<f64>>>> {
+                CompassAppBindings::run_matrix(self, origins, destinations, query)
+                    .map_err(|e| PyException::new_err(format!("Error while computing cost matrix: {}", e)))
+            }
+
+            #[pyo3(signature = (queries, state_features=vec![]))]
+            pub fn _run_queries_cost_only(
+                &self,
+                queries: Vec<String>,
+                state_features: Vec<String>,
+            ) -> PyResult<String> {
+                CompassAppBindings::run_queries_cost_only(self, queries, state_features)
+                    .map_err(|e| PyException::new_err(format!("Error while running queries: {}", e)))
+            }
+
+            pub fn _run_queries_in_order(&self, queries: Vec<String>) -> PyResult<Vec<String>> {
+                CompassAppBindings::run_queries_in_order(self, queries)
+                    .map_err(|e| PyException::new_err(format!("Error while running queries: {}", e)))
+            }
         }
     };
 
@@ -0,0 +1,158 @@
+use routee_compass_core::model::{
+    network::{Edge, EdgeId},
+    traversal::TraversalModelError,
+    unit::{AsF64, Distance, DistanceUnit, Grade, GradeUnit, BASE_DISTANCE_UNIT},
+};
+use serde::{Deserialize, Serialize};
+
+/// largest difference between a table grade and an elevation-derived grade that
+/// is not flagged, in percent, when none is configured
+pub const DEFAULT_TOLERANCE_PERCENT: f64 = 1.0;
+
+/// the grade used for traversal when an energy model has both a grade table and
+/// vertex elevations.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GradeSource {
+    /// the grade table is authoritative, and elevations are only used to check it
+    #[default]
+    Table,
+    /// the grade derived from the elevations of each edge's endpoints is used
+    Elevation,
+}
+
+/// an edge whose table grade differs from the grade derived from the
+/// elevations of its endpoints by more than the tolerance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GradeDisagreement {
+    pub edge_id: EdgeId,
+    pub table_grade: Grade,
+    pub elevation_grade: Grade,
+}
+
+/// computes the grade of each edge from the elevations of its endpoints, as the
+/// rise from the source to the destination vertex over the edge distance. grades
+/// are uphill positive, and edges with zero distance have zero grade.
+///
+/// # Arguments
+///
+/// * `edges` - the edges of the graph, in edge id order
+/// * `elevations` - the elevation of each vertex, in vertex id order
+/// * `elevation_unit` - unit of the elevations
+/// * `grade_unit` - unit of the resulting grades
+///
+/// # Returns
+///
+/// the grade of each edge, or an error if an edge references a vertex without an elevation
+pub fn grades_from_elevation(
+    edges: &[Edge],
+    elevations: &[f64],
+    elevation_unit: DistanceUnit,
+    grade_unit: GradeUnit,
+) -> Result<Box<[Grade]>, TraversalModelError> {
+    let elevation = |edge: &Edge, vertex_id: usize| {
+        let value = elevations.get(vertex_id).ok_or_else(|| {
+            TraversalModelError::BuildError(format!(
+                "edge {} references vertex {} which has no elevation, found {} elevations",
+                edge.edge_id,
+                vertex_id,
+                elevations.len()
+            ))
+        })?;
+        let meters = elevation_unit.convert(&Distance::new(*value), &BASE_DISTANCE_UNIT);
+        Ok::<f64, TraversalModelError>(meters.as_f64())
+    };
+    edges
+        .iter()
+        .map(|edge| {
+            let rise =
+                elevation(edge, edge.dst_vertex_id.0)? - elevation(edge, edge.src_vertex_id.0)?;
+            let run = edge.distance.as_f64();
+            let decimal = if run > 0.0 { rise / run } else { 0.0 };
+            Ok(GradeUnit::Decimal.convert(&Grade::new(decimal), &grade_unit))
+        })
+        .collect()
+}
+
+/// compares a grade table with the grades derived from vertex elevations.
+///
+/// # Arguments
+///
+/// * `table` - grade of each edge from the grade table
+/// * `elevation` - grade of each edge derived from vertex elevations
+/// * `tolerance` - largest difference that is not flagged, in the unit of both grades
+///
+/// # Returns
+///
+/// the edges whose grades differ by more than the tolerance, in edge id order, or an
+/// error if the two tables have a different number of edges
+pub fn find_disagreements(
+    table: &[Grade],
+    elevation: &[Grade],
+    tolerance: Grade,
+) -> Result<Vec<GradeDisagreement>, TraversalModelError> {
+    if table.len() != elevation.len() {
+        return Err(TraversalModelError::BuildError(format!(
+            "grade table has {} rows but {} edges have grades derived from elevation",
+            table.len(),
+            elevation.len()
+        )));
+    }
+    let disagreements = table
+        .iter()
+        .zip(elevation.iter())
+        .enumerate()
+        .filter(|(_, (t, e))| (t.as_f64() - e.as_f64()).abs() > tolerance.as_f64())
+        .map(|(edge_id, (t, e))| GradeDisagreement {
+            edge_id: EdgeId(edge_id),
+            table_grade: *t,
+            elevation_grade: *e,
+        })
+        .collect();
+    Ok(disagreements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inconsistent_edge_is_flagged() {
+        // vertex 1 is 10 meters above vertices 0 and 2
+        let elevations = [100.0, 110.0, 100.0];
+        let edges = [
+            Edge::new(0, 0, 1, 200.0),
+            Edge::new(1, 1, 2, 200.0),
+            Edge::new(2, 2, 0, 0.0),
+        ];
+        let derived = grades_from_elevation(
+            &edges,
+            &elevations,
+            DistanceUnit::Meters,
+            GradeUnit::Percent,
+        )
+        .unwrap();
+        let derived_f64: Vec<f64> = derived.iter().map(|g| g.as_f64()).collect();
+        assert_eq!(derived_f64, vec![5.0, -5.0, 0.0]);
+
+        // edge 1 is recorded as uphill in the table
+        let table = [Grade::new(4.5), Grade::new(5.0), Grade::new(0.0)];
+        let flagged = find_disagreements(&table, &derived, Grade::new(1.0)).unwrap();
+        assert_eq!(
+            flagged,
+            vec![GradeDisagreement {
+                edge_id: EdgeId(1),
+                table_grade: Grade::new(5.0),
+                elevation_grade: Grade::new(-5.0),
+            }]
+        );
+        assert!(find_disagreements(&table[..2], &derived, Grade::new(1.0)).is_err());
+        assert!(grades_from_elevation(
+            &edges,
+            &elevations[..2],
+            DistanceUnit::Meters,
+            GradeUnit::Percent
+        )
+        .is_err());
+    }
+}
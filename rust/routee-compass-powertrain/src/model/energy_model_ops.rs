@@ -1,6 +1,10 @@
+use super::vehicle::VehicleType;
 use routee_compass_core::model::{
-    access::default::turn_delays::EdgeHeading, network::edge_id::EdgeId,
-    traversal::TraversalModelError, unit::Grade,
+    access::default::turn_delays::EdgeHeading,
+    network::edge_id::EdgeId,
+    state::StateModel,
+    traversal::TraversalModelError,
+    unit::{AsF64, Distance, DistanceUnit, Grade, GradeUnit, Speed, SpeedUnit},
 };
 
 pub const ZERO_ENERGY: f64 = 1e-9;
@@ -37,3 +41,72 @@ pub fn get_headings(
     })?;
     Ok(*heading)
 }
+
+/// maximum number of downhill grade table entries sampled by [`downhill_energy_increase_ratio`]
+const GRADE_SIGN_CHECK_SAMPLES: usize = 1000;
+
+/// sanity check for grade sign convention mismatches between a grade table and a
+/// vehicle model. for a sample of the downhill (negative) grades in the table, compares
+/// the energy the vehicle consumes on a segment at that grade against the same segment
+/// on flat ground. a model and table that agree on the sign convention should consume
+/// less energy going downhill, so a high ratio suggests the convention is inverted.
+///
+/// # Arguments
+///
+/// * `vehicle`     - the vehicle model to test
+/// * `grade_table` - grade values, in the internal (uphill positive) convention
+/// * `grade_unit`  - unit of the grade table values
+///
+/// # Returns
+///
+/// the fraction of sampled downhill segments that consume more energy than flat ground,
+/// or None if the table has no downhill segments
+pub fn downhill_energy_increase_ratio(
+    vehicle: &dyn VehicleType,
+    grade_table: &[Grade],
+    grade_unit: GradeUnit,
+) -> Result<Option<f64>, TraversalModelError> {
+    let downhill = grade_table
+        .iter()
+        .filter(|g| g.as_f64() < 0.0)
+        .collect::<Vec<_>>();
+    if downhill.is_empty() {
+        return Ok(None);
+    }
+    let step = (downhill.len() / GRADE_SIGN_CHECK_SAMPLES).max(1);
+    let samples = downhill.into_iter().step_by(step).collect::<Vec<_>>();
+
+    let features = vehicle.state_features();
+    let (energy_feature, _) = features.first().cloned().ok_or_else(|| {
+        TraversalModelError::BuildError(format!("vehicle {} has no state features", vehicle.name()))
+    })?;
+    let state_model = StateModel::empty()
+        .extend(features)
+        .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+    let segment_energy = |grade: Grade| -> Result<f64, TraversalModelError> {
+        let initial = state_model
+            .initial_state()
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let mut state = initial.clone();
+        vehicle.consume_energy(
+            (Speed::new(40.0), SpeedUnit::KilometersPerHour),
+            (grade, grade_unit),
+            (Distance::new(1.0), DistanceUnit::Kilometers),
+            &mut state,
+            &state_model,
+        )?;
+        let delta = state_model
+            .get_delta(&initial, &state, &energy_feature)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        Ok(delta.0)
+    };
+
+    let flat_energy = segment_energy(Grade::ZERO)?;
+    let mut n_increase = 0;
+    for grade in samples.iter() {
+        if segment_energy(**grade)? > flat_energy {
+            n_increase += 1;
+        }
+    }
+    Ok(Some(n_increase as f64 / samples.len() as f64))
+}
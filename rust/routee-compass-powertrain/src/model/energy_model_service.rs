@@ -1,5 +1,11 @@
+use super::elevation_grade::{self, GradeSource};
+use super::energy_model_ops;
 use super::energy_traversal_model::EnergyTraversalModel;
+use super::grade_sign::GradeSign;
+use super::temperature::TemperatureModel;
 use super::vehicle::VehicleType;
+use super::wind::WindModel;
+use itertools::Itertools;
 use kdam::Bar;
 use routee_compass_core::model::traversal::{
     TraversalModel, TraversalModelError, TraversalModelService,
@@ -20,6 +26,8 @@ pub struct EnergyModelService {
     pub time_unit: TimeUnit,
     pub distance_unit: DistanceUnit,
     pub vehicle_library: HashMap<String, Arc<dyn VehicleType>>,
+    pub wind_model: Option<Arc<WindModel>>,
+    pub temperature_model: Option<Arc<TemperatureModel>>,
 }
 
 impl EnergyModelService {
@@ -29,16 +37,14 @@ impl EnergyModelService {
         time_model_speed_unit: SpeedUnit,
         grade_table_path_option: &Option<P>,
         grade_table_grade_unit: GradeUnit,
+        grade_sign: GradeSign,
         output_time_unit_option: Option<TimeUnit>,
         output_distance_unit_option: Option<DistanceUnit>,
         vehicle_library: HashMap<String, Arc<dyn VehicleType>>,
     ) -> Result<Self, TraversalModelError> {
-        let output_time_unit = output_time_unit_option.unwrap_or(BASE_TIME_UNIT);
-        let output_distance_unit = output_distance_unit_option.unwrap_or(BASE_DISTANCE_UNIT);
-
-        let grade_table: Arc<Option<Box<[Grade]>>> = match grade_table_path_option {
-            Some(gtp) => Arc::new(Some(
-                read_utils::read_raw_file(
+        let grade_table = match grade_table_path_option {
+            Some(gtp) => {
+                let grades: Box<[Grade]> = read_utils::read_raw_file(
                     gtp,
                     read_decoders::default,
                     Some(Bar::builder().desc("link grades")),
@@ -50,11 +56,70 @@ impl EnergyModelService {
                         gtp.as_ref().to_str().unwrap_or_default(),
                         e
                     ))
-                })?,
-            )),
+                })?;
+                Some(grades)
+            }
+            None => None,
+        };
+        EnergyModelService::new_with_grade_table(
+            time_model_service,
+            time_model_speed_unit,
+            grade_table,
+            grade_table_grade_unit,
+            grade_sign,
+            output_time_unit_option,
+            output_distance_unit_option,
+            vehicle_library,
+        )
+    }
+
+    /// builds the service with a grade table that has already been loaded,
+    /// for example from a column of a shared edge attributes file.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_with_grade_table(
+        time_model_service: Arc<dyn TraversalModelService>,
+        time_model_speed_unit: SpeedUnit,
+        grade_table_option: Option<Box<[Grade]>>,
+        grade_table_grade_unit: GradeUnit,
+        grade_sign: GradeSign,
+        output_time_unit_option: Option<TimeUnit>,
+        output_distance_unit_option: Option<DistanceUnit>,
+        vehicle_library: HashMap<String, Arc<dyn VehicleType>>,
+    ) -> Result<Self, TraversalModelError> {
+        let output_time_unit = output_time_unit_option.unwrap_or(BASE_TIME_UNIT);
+        let output_distance_unit = output_distance_unit_option.unwrap_or(BASE_DISTANCE_UNIT);
+
+        // grade values are normalized to the internal (uphill positive) convention at load
+        let grade_table: Arc<Option<Box<[Grade]>>> = match grade_table_option {
+            Some(mut grades) => {
+                if grade_sign != GradeSign::UphillPositive {
+                    grades
+                        .iter_mut()
+                        .for_each(|grade| *grade = grade_sign.normalize(*grade));
+                }
+                Arc::new(Some(grades))
+            }
             None => Arc::new(None),
         };
 
+        // warn when a vehicle consumes more energy going downhill than on flat ground
+        if let Some(grades) = grade_table.as_ref() {
+            for (name, vehicle) in vehicle_library.iter() {
+                let ratio = energy_model_ops::downhill_energy_increase_ratio(
+                    vehicle.as_ref(),
+                    grades,
+                    grade_table_grade_unit,
+                )?;
+                if let Some(r) = ratio.filter(|r| *r > 0.5) {
+                    log::warn!(
+                        "vehicle {} consumes more energy than on flat ground on {:.0}% of downhill segments, the grade_sign of the grade table may be inverted",
+                        name,
+                        r * 100.0
+                    );
+                }
+            }
+        }
+
         Ok(EnergyModelService {
             time_model_service,
             time_model_speed_unit,
@@ -63,8 +128,79 @@ impl EnergyModelService {
             time_unit: output_time_unit,
             distance_unit: output_distance_unit,
             vehicle_library,
+            wind_model: None,
+            temperature_model: None,
+        })
+    }
+
+    /// adjusts the energy of each edge traversal for the wind along the edge
+    pub fn with_wind_model(
+        self,
+        wind_model: WindModel,
+    ) -> Result<EnergyModelService, TraversalModelError> {
+        wind_model.validate()?;
+        Ok(EnergyModelService {
+            wind_model: Some(Arc::new(wind_model)),
+            ..self
         })
     }
+
+    /// records the temperature along each traversed edge
+    pub fn with_temperature_model(self, temperature_model: TemperatureModel) -> EnergyModelService {
+        EnergyModelService {
+            temperature_model: Some(Arc::new(temperature_model)),
+            ..self
+        }
+    }
+
+    /// checks the grade table against grades derived from vertex elevations, logging
+    /// the edges where they differ by more than the tolerance, and uses the grades
+    /// from the chosen source for traversal. without a grade table, the derived
+    /// grades are used. the derived grades and tolerance are in the grade table unit.
+    pub fn with_elevation_grades(
+        self,
+        elevation_grades: Box<[Grade]>,
+        source: GradeSource,
+        tolerance: Grade,
+    ) -> Result<EnergyModelService, TraversalModelError> {
+        let table = match self.grade_table.as_ref() {
+            None => {
+                return Ok(EnergyModelService {
+                    grade_table: Arc::new(Some(elevation_grades)),
+                    ..self
+                })
+            }
+            Some(table) => table,
+        };
+        let disagreements =
+            elevation_grade::find_disagreements(table, &elevation_grades, tolerance)?;
+        if !disagreements.is_empty() {
+            let examples = disagreements
+                .iter()
+                .take(10)
+                .map(|d| {
+                    format!(
+                        "edge {} (table {}, elevation {})",
+                        d.edge_id, d.table_grade, d.elevation_grade
+                    )
+                })
+                .join(", ");
+            log::warn!(
+                "{} edges have a table grade that differs from the grade derived from vertex elevations by more than {} {}, such as {}",
+                disagreements.len(),
+                tolerance,
+                self.grade_table_grade_unit,
+                examples
+            );
+        }
+        match source {
+            GradeSource::Table => Ok(self),
+            GradeSource::Elevation => Ok(EnergyModelService {
+                grade_table: Arc::new(Some(elevation_grades)),
+                ..self
+            }),
+        }
+    }
 }
 
 impl TraversalModelService for EnergyModelService {
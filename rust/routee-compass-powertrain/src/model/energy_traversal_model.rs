@@ -2,9 +2,9 @@ use super::energy_model_ops::get_grade;
 use super::energy_model_service::EnergyModelService;
 use super::vehicle::VehicleType;
 use routee_compass_core::model::network::{Edge, Vertex};
-use routee_compass_core::model::state::StateFeature;
 use routee_compass_core::model::state::StateModel;
 use routee_compass_core::model::state::StateVariable;
+use routee_compass_core::model::state::{CustomFeatureFormat, StateFeature};
 use routee_compass_core::model::traversal::TraversalModel;
 use routee_compass_core::model::traversal::TraversalModelError;
 use routee_compass_core::model::unit::*;
@@ -18,10 +18,25 @@ pub struct EnergyTraversalModel {
 }
 
 impl TraversalModel for EnergyTraversalModel {
-    /// inject the state features required by the VehicleType
+    /// inject the state features required by the VehicleType, along with
+    /// the grade and, with a temperature model, the temperature of the most
+    /// recently traversed edge
     fn state_features(&self) -> Vec<(String, StateFeature)> {
         let mut features = self.vehicle.state_features();
         features.extend(self.time_model.state_features());
+        features.push((
+            String::from(Self::LEG_GRADE),
+            StateFeature::Custom {
+                r#type: String::from("grade"),
+                unit: self.energy_model_service.grade_table_grade_unit.to_string(),
+                format: CustomFeatureFormat::FloatingPoint {
+                    initial: 0.0.into(),
+                },
+            },
+        ));
+        if let Some(temperature_model) = &self.energy_model_service.temperature_model {
+            features.push(temperature_model.state_feature());
+        }
         features
     }
 
@@ -59,6 +74,15 @@ impl TraversalModel for EnergyTraversalModel {
 
         // perform vehicle energy traversal
         let grade = get_grade(&self.energy_model_service.grade_table, edge.edge_id)?;
+        state_model.set_custom_f64(state, &Self::LEG_GRADE.into(), &grade.as_f64())?;
+        if let Some(temperature_model) = &self.energy_model_service.temperature_model {
+            let temperature = temperature_model.get_temperature(&edge.edge_id);
+            state_model.set_custom_f64(
+                state,
+                &temperature_model.temperature_feature_name,
+                &temperature,
+            )?;
+        }
 
         let distance_in_time_model_unit = BASE_DISTANCE_UNIT.convert(
             &edge.distance,
@@ -68,10 +92,18 @@ impl TraversalModel for EnergyTraversalModel {
                 .associated_distance_unit(),
         );
         let speed = Speed::from((distance_in_time_model_unit, time_delta));
+        let speed = (speed, self.energy_model_service.time_model_speed_unit);
+
+        // the energy of a traversal is proportional to its distance, so the wind
+        // adjustment is applied by scaling the distance seen by the vehicle
+        let energy_distance = match &self.energy_model_service.wind_model {
+            Some(wind_model) => distance * wind_model.energy_factor(&edge.edge_id, speed)?,
+            None => distance,
+        };
         self.vehicle.consume_energy(
-            (speed, self.energy_model_service.time_model_speed_unit),
+            speed,
             (grade, self.energy_model_service.grade_table_grade_unit),
-            (distance, self.energy_model_service.distance_unit),
+            (energy_distance, self.energy_model_service.distance_unit),
             state,
             state_model,
         )?;
@@ -115,6 +147,7 @@ impl TraversalModel for EnergyTraversalModel {
 
 impl EnergyTraversalModel {
     const TIME: &'static str = "time";
+    pub const LEG_GRADE: &'static str = "leg_grade";
 
     pub fn new(
         energy_model_service: Arc<EnergyModelService>,
@@ -159,16 +192,24 @@ impl EnergyTraversalModel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::energy_model_ops::downhill_energy_increase_ratio;
     use crate::model::{
-        prediction::load_prediction_model, prediction::ModelType, vehicle::default::ICE,
+        elevation_grade::{grades_from_elevation, GradeSource},
+        grade_sign::GradeSign,
+        prediction::load_prediction_model,
+        prediction::ModelType,
+        temperature::TemperatureModel,
+        vehicle::default::ICE,
+        wind::{Wind, WindField, WindModel},
     };
     use geo::coord;
     use routee_compass_core::{
         model::{
+            access::default::turn_delays::EdgeHeading,
             network::{Edge, EdgeId, Vertex, VertexId},
             traversal::default::{SpeedLookupService, SpeedTraversalEngine},
         },
-        util::geo::coord::InternalCoord,
+        util::{fs::read_utils, geo::coord::InternalCoord},
     };
     use std::{collections::HashMap, path::PathBuf};
 
@@ -211,6 +252,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         )
         .unwrap();
 
@@ -233,6 +275,7 @@ mod tests {
             &Some(grade_file_path),
             // SpeedUnit::KilometersPerHour,
             GradeUnit::Millis,
+            GradeSign::UphillPositive,
             None,
             None,
             model_library,
@@ -253,4 +296,443 @@ mod tests {
             .unwrap();
         println!("{:?}", state);
     }
+
+    /// vehicle consuming one kilowatt hour per kilometer on flat ground, changing
+    /// by `grade_factor` kilowatt hours per kilometer for each unit of decimal grade
+    struct MockVehicle {
+        grade_factor: f64,
+    }
+
+    impl VehicleType for MockVehicle {
+        fn name(&self) -> String {
+            String::from("mock")
+        }
+
+        fn state_features(&self) -> Vec<(String, StateFeature)> {
+            vec![(
+                String::from("energy"),
+                StateFeature::Energy {
+                    energy_unit: EnergyUnit::KilowattHours,
+                    initial: Energy::ZERO,
+                },
+            )]
+        }
+
+        fn consume_energy(
+            &self,
+            _speed: (Speed, SpeedUnit),
+            grade: (Grade, GradeUnit),
+            distance: (Distance, DistanceUnit),
+            state: &mut Vec<StateVariable>,
+            state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            let (grade, grade_unit) = grade;
+            let (distance, distance_unit) = distance;
+            let decimal = grade_unit.convert(&grade, &GradeUnit::Decimal).as_f64();
+            let km = distance_unit
+                .convert(&distance, &DistanceUnit::Kilometers)
+                .as_f64();
+            let energy = Energy::new(km * (1.0 + self.grade_factor * decimal));
+            state_model.add_energy(
+                state,
+                &String::from("energy"),
+                &energy,
+                &EnergyUnit::KilowattHours,
+            )?;
+            Ok(())
+        }
+
+        fn best_case_energy(
+            &self,
+            _distance: (Distance, DistanceUnit),
+        ) -> Result<(Energy, EnergyUnit), TraversalModelError> {
+            Ok((Energy::ZERO, EnergyUnit::KilowattHours))
+        }
+
+        fn best_case_energy_state(
+            &self,
+            _distance: (Distance, DistanceUnit),
+            _state: &mut Vec<StateVariable>,
+            _state_model: &StateModel,
+        ) -> Result<(), TraversalModelError> {
+            Ok(())
+        }
+
+        fn update_from_query(
+            &self,
+            _query: &serde_json::Value,
+        ) -> Result<Arc<dyn VehicleType>, TraversalModelError> {
+            Ok(Arc::new(MockVehicle {
+                grade_factor: self.grade_factor,
+            }))
+        }
+    }
+
+    fn test_file(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("model")
+            .join("test")
+            .join(name)
+    }
+
+    #[test]
+    fn test_grade_sign_flips_leg_grade() {
+        let leg_grade = |grade_sign: GradeSign, edge_id: usize| -> f64 {
+            let time_engine = Arc::new(
+                SpeedTraversalEngine::new(
+                    &test_file("velocities.txt"),
+                    SpeedUnit::KilometersPerHour,
+                    None,
+                    None,
+                )
+                .unwrap(),
+            );
+            let mut library: HashMap<String, Arc<dyn VehicleType>> = HashMap::new();
+            library.insert(
+                String::from("mock"),
+                Arc::new(MockVehicle { grade_factor: 10.0 }),
+            );
+            let service = EnergyModelService::new(
+                Arc::new(SpeedLookupService { e: time_engine }),
+                SpeedUnit::KilometersPerHour,
+                &Some(test_file("grades_signed.txt")),
+                GradeUnit::Decimal,
+                grade_sign,
+                None,
+                None,
+                library,
+            )
+            .unwrap();
+            let conf = serde_json::json!({"model_name": "mock"});
+            let model = EnergyTraversalModel::new(Arc::new(service), &conf).unwrap();
+            let state_model = StateModel::empty().extend(model.state_features()).unwrap();
+            let mut state = state_model.initial_state().unwrap();
+            let v = Vertex {
+                vertex_id: VertexId(0),
+                coordinate: InternalCoord(coord! {x: -86.67, y: 36.12}),
+            };
+            let edge = Edge::new(edge_id, 0, 1, 100.0);
+            model
+                .traverse_edge((&v, &edge, &v), &mut state, &state_model)
+                .unwrap();
+            state_model
+                .get_custom_f64(&state, &EnergyTraversalModel::LEG_GRADE.into())
+                .unwrap()
+        };
+        assert_eq!(leg_grade(GradeSign::UphillPositive, 0), 0.05);
+        assert_eq!(leg_grade(GradeSign::DownhillPositive, 0), -0.05);
+        assert_eq!(leg_grade(GradeSign::UphillPositive, 1), -0.02);
+        assert_eq!(leg_grade(GradeSign::DownhillPositive, 1), 0.02);
+    }
+
+    #[test]
+    fn test_shared_edge_attributes_file() {
+        let attributes_file = test_file("edge_attributes.csv");
+        let time_engine = SpeedTraversalEngine::from_edge_attributes(
+            &attributes_file,
+            "speed",
+            SpeedUnit::KilometersPerHour,
+            None,
+            None,
+        )
+        .unwrap();
+        let grades: Box<[Grade]> =
+            read_utils::read_edge_attribute_column(&attributes_file, "grade", None).unwrap();
+        let mut library: HashMap<String, Arc<dyn VehicleType>> = HashMap::new();
+        library.insert(
+            String::from("mock"),
+            Arc::new(MockVehicle { grade_factor: 10.0 }),
+        );
+        let service = EnergyModelService::new_with_grade_table(
+            Arc::new(SpeedLookupService {
+                e: Arc::new(time_engine),
+            }),
+            SpeedUnit::KilometersPerHour,
+            Some(grades),
+            GradeUnit::Decimal,
+            GradeSign::UphillPositive,
+            None,
+            None,
+            library,
+        )
+        .unwrap();
+        let conf = serde_json::json!({"model_name": "mock"});
+        let model = EnergyTraversalModel::new(Arc::new(service), &conf).unwrap();
+        let state_model = StateModel::empty().extend(model.state_features()).unwrap();
+        let v = Vertex {
+            vertex_id: VertexId(0),
+            coordinate: InternalCoord(coord! {x: -86.67, y: 36.12}),
+        };
+        let traverse = |edge_id: usize| -> (f64, f64) {
+            let mut state = state_model.initial_state().unwrap();
+            let edge = Edge::new(edge_id, 0, 1, 100.0);
+            model
+                .traverse_edge((&v, &edge, &v), &mut state, &state_model)
+                .unwrap();
+            let time = state_model
+                .get_time(&state, &"time".into(), &TimeUnit::Seconds)
+                .unwrap()
+                .as_f64();
+            let grade = state_model
+                .get_custom_f64(&state, &EnergyTraversalModel::LEG_GRADE.into())
+                .unwrap();
+            (time, grade)
+        };
+        // 100 meters @ 36kph takes 10 seconds, @ 72kph takes 5 seconds
+        let (time_0, grade_0) = traverse(0);
+        let (time_1, grade_1) = traverse(1);
+        assert!(
+            (time_0 - 10.0).abs() < 1e-6,
+            "expected 10 seconds, found {}",
+            time_0
+        );
+        assert!(
+            (time_1 - 5.0).abs() < 1e-6,
+            "expected 5 seconds, found {}",
+            time_1
+        );
+        assert_eq!(grade_0, 0.05);
+        assert_eq!(grade_1, -0.02);
+    }
+
+    #[test]
+    fn test_elevation_grade_source() {
+        let attributes_file = test_file("edge_attributes.csv");
+        // edge 0 climbs 5 meters over 100 as in the grade table, while edge 1 is
+        // recorded as a 2% descent but climbs 3 meters
+        let edges = [Edge::new(0, 0, 1, 100.0), Edge::new(1, 1, 2, 100.0)];
+        let elevations = [100.0, 105.0, 108.0];
+        let leg_grades = |source: GradeSource| -> Vec<f64> {
+            let time_engine = SpeedTraversalEngine::from_edge_attributes(
+                &attributes_file,
+                "speed",
+                SpeedUnit::KilometersPerHour,
+                None,
+                None,
+            )
+            .unwrap();
+            let grades: Box<[Grade]> =
+                read_utils::read_edge_attribute_column(&attributes_file, "grade", None).unwrap();
+            let mut library: HashMap<String, Arc<dyn VehicleType>> = HashMap::new();
+            library.insert(
+                String::from("mock"),
+                Arc::new(MockVehicle { grade_factor: 10.0 }),
+            );
+            let derived = grades_from_elevation(
+                &edges,
+                &elevations,
+                DistanceUnit::Meters,
+                GradeUnit::Decimal,
+            )
+            .unwrap();
+            let service = EnergyModelService::new_with_grade_table(
+                Arc::new(SpeedLookupService {
+                    e: Arc::new(time_engine),
+                }),
+                SpeedUnit::KilometersPerHour,
+                Some(grades),
+                GradeUnit::Decimal,
+                GradeSign::UphillPositive,
+                None,
+                None,
+                library,
+            )
+            .unwrap()
+            .with_elevation_grades(derived, source, Grade::new(0.01))
+            .unwrap();
+            let conf = serde_json::json!({"model_name": "mock"});
+            let model = EnergyTraversalModel::new(Arc::new(service), &conf).unwrap();
+            let state_model = StateModel::empty().extend(model.state_features()).unwrap();
+            let v = Vertex {
+                vertex_id: VertexId(0),
+                coordinate: InternalCoord(coord! {x: -86.67, y: 36.12}),
+            };
+            edges
+                .iter()
+                .map(|edge| {
+                    let mut state = state_model.initial_state().unwrap();
+                    model
+                        .traverse_edge((&v, edge, &v), &mut state, &state_model)
+                        .unwrap();
+                    let grade = state_model
+                        .get_custom_f64(&state, &EnergyTraversalModel::LEG_GRADE.into())
+                        .unwrap();
+                    (grade * 1000.0).round() / 1000.0
+                })
+                .collect()
+        };
+        assert_eq!(leg_grades(GradeSource::Table), vec![0.05, -0.02]);
+        assert_eq!(leg_grades(GradeSource::Elevation), vec![0.05, 0.03]);
+    }
+
+    #[test]
+    fn test_wind_adjusts_energy() {
+        let attributes_file = test_file("edge_attributes.csv");
+        let build_model = |wind_direction: Option<f64>| {
+            let time_engine = SpeedTraversalEngine::from_edge_attributes(
+                &attributes_file,
+                "speed",
+                SpeedUnit::KilometersPerHour,
+                None,
+                None,
+            )
+            .unwrap();
+            let grades: Box<[Grade]> =
+                read_utils::read_edge_attribute_column(&attributes_file, "grade", None).unwrap();
+            let mut library: HashMap<String, Arc<dyn VehicleType>> = HashMap::new();
+            library.insert(
+                String::from("mock"),
+                Arc::new(MockVehicle { grade_factor: 10.0 }),
+            );
+            let service = EnergyModelService::new_with_grade_table(
+                Arc::new(SpeedLookupService {
+                    e: Arc::new(time_engine),
+                }),
+                SpeedUnit::KilometersPerHour,
+                Some(grades),
+                GradeUnit::Decimal,
+                GradeSign::UphillPositive,
+                None,
+                None,
+                library,
+            )
+            .unwrap();
+            // both edges head east
+            let service = match wind_direction {
+                None => service,
+                Some(direction) => service
+                    .with_wind_model(WindModel {
+                        wind: WindField::Regional(Wind {
+                            speed: 18.0,
+                            direction,
+                        }),
+                        speed_unit: SpeedUnit::KilometersPerHour,
+                        edge_headings: vec![EdgeHeading::new(90, 90), EdgeHeading::new(80, 100)]
+                            .into_boxed_slice(),
+                        drag_area: WindModel::DEFAULT_DRAG_AREA,
+                        mass_kg: WindModel::DEFAULT_MASS_KG,
+                    })
+                    .unwrap(),
+            };
+            let conf = serde_json::json!({"model_name": "mock"});
+            EnergyTraversalModel::new(Arc::new(service), &conf).unwrap()
+        };
+        let v = Vertex {
+            vertex_id: VertexId(0),
+            coordinate: InternalCoord(coord! {x: -86.67, y: 36.12}),
+        };
+        let energy = |wind_direction: Option<f64>, edge_id: usize| -> f64 {
+            let model = build_model(wind_direction);
+            let state_model = StateModel::empty().extend(model.state_features()).unwrap();
+            let mut state = state_model.initial_state().unwrap();
+            let edge = Edge::new(edge_id, 0, 1, 100.0);
+            model
+                .traverse_edge((&v, &edge, &v), &mut state, &state_model)
+                .unwrap();
+            state_model
+                .get_energy(&state, &"energy".into(), &EnergyUnit::KilowattHours)
+                .unwrap()
+                .as_f64()
+        };
+        for edge_id in [0, 1] {
+            let still = energy(None, edge_id);
+            let tailwind = energy(Some(270.0), edge_id);
+            let headwind = energy(Some(90.0), edge_id);
+            let crosswind = energy(Some(0.0), edge_id);
+            assert!(tailwind < still, "tailwind {} >= {}", tailwind, still);
+            assert!(headwind > still, "headwind {} <= {}", headwind, still);
+            assert!(
+                (crosswind - still).abs() < 1e-9,
+                "crosswind {} should be near {}",
+                crosswind,
+                still
+            );
+        }
+    }
+
+    #[test]
+    fn test_temperature_recorded_on_every_edge() {
+        let time_engine = Arc::new(
+            SpeedTraversalEngine::new(
+                &test_file("velocities.txt"),
+                SpeedUnit::KilometersPerHour,
+                None,
+                None,
+            )
+            .unwrap(),
+        );
+        let mut library: HashMap<String, Arc<dyn VehicleType>> = HashMap::new();
+        library.insert(
+            String::from("mock"),
+            Arc::new(MockVehicle { grade_factor: 10.0 }),
+        );
+        let service = EnergyModelService::new(
+            Arc::new(SpeedLookupService { e: time_engine }),
+            SpeedUnit::KilometersPerHour,
+            &Some(test_file("grades_signed.txt")),
+            GradeUnit::Decimal,
+            GradeSign::UphillPositive,
+            None,
+            None,
+            library,
+        )
+        .unwrap()
+        .with_temperature_model(TemperatureModel {
+            edge_temperatures: Some(HashMap::from([(EdgeId(0), -12.5)])),
+            ambient_temperature: -5.0,
+            temperature_unit: String::from(TemperatureModel::DEFAULT_TEMPERATURE_UNIT),
+            temperature_feature_name: String::from(TemperatureModel::DEFAULT_FEATURE_NAME),
+        });
+        let conf = serde_json::json!({"model_name": "mock"});
+        let model = EnergyTraversalModel::new(Arc::new(service), &conf).unwrap();
+        let state_model = StateModel::empty().extend(model.state_features()).unwrap();
+        let v = Vertex {
+            vertex_id: VertexId(0),
+            coordinate: InternalCoord(coord! {x: -86.67, y: 36.12}),
+        };
+        let temperature = |state: &[StateVariable]| {
+            state_model
+                .get_custom_f64(state, &TemperatureModel::DEFAULT_FEATURE_NAME.into())
+                .unwrap()
+        };
+
+        // the first edge of a route takes its listed temperature rather than the ambient
+        let mut state = state_model.initial_state().unwrap();
+        assert_eq!(temperature(&state), -5.0);
+        model
+            .traverse_edge(
+                (&v, &Edge::new(0, 0, 1, 100.0), &v),
+                &mut state,
+                &state_model,
+            )
+            .unwrap();
+        assert_eq!(temperature(&state), -12.5);
+
+        // an edge missing from the table takes the ambient temperature
+        model
+            .traverse_edge(
+                (&v, &Edge::new(1, 1, 0, 100.0), &v),
+                &mut state,
+                &state_model,
+            )
+            .unwrap();
+        assert_eq!(temperature(&state), -5.0);
+    }
+
+    #[test]
+    fn test_downhill_energy_increase_ratio() {
+        let grades = [0.05, -0.02, -0.04, 0.0].map(Grade::new);
+        let consistent = MockVehicle { grade_factor: 10.0 };
+        let inverted = MockVehicle {
+            grade_factor: -10.0,
+        };
+        let ratio = |v: &MockVehicle| {
+            downhill_energy_increase_ratio(v, &grades, GradeUnit::Decimal)
+                .unwrap()
+                .unwrap()
+        };
+        assert_eq!(ratio(&consistent), 0.0);
+        assert_eq!(ratio(&inverted), 1.0);
+    }
 }
@@ -0,0 +1,26 @@
+use routee_compass_core::model::unit::Grade;
+use serde::{Deserialize, Serialize};
+
+/// sign convention of the values in a grade table. internally, Compass
+/// treats uphill grades as positive, and grade tables using the opposite
+/// convention are normalized when they are loaded.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GradeSign {
+    /// uphill grades are positive, downhill grades are negative
+    #[default]
+    UphillPositive,
+    /// downhill grades are positive, uphill grades are negative
+    DownhillPositive,
+}
+
+impl GradeSign {
+    /// converts a grade value in this convention to the internal,
+    /// uphill-positive convention
+    pub fn normalize(&self, grade: Grade) -> Grade {
+        match self {
+            GradeSign::UphillPositive => grade,
+            GradeSign::DownhillPositive => -grade,
+        }
+    }
+}
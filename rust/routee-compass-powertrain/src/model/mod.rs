@@ -1,5 +1,9 @@
+pub mod elevation_grade;
 pub mod energy_model_ops;
 pub mod energy_model_service;
 pub mod energy_traversal_model;
+pub mod grade_sign;
 pub mod prediction;
+pub mod temperature;
 pub mod vehicle;
+pub mod wind;
@@ -0,0 +1,73 @@
+use routee_compass_core::model::{traversal::TraversalModelError, unit::EnergyRate};
+use serde::{Deserialize, Serialize};
+
+/// a bias correction of the energy rates predicted by a model, used to tune
+/// a model to observed fleet energy data without retraining it. predictions
+/// are multiplied by `scale` and then shifted by `offset`, which is an energy
+/// rate in the energy rate unit of the model.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct EnergyCalibration {
+    #[serde(default = "EnergyCalibration::default_scale")]
+    pub scale: f64,
+    #[serde(default)]
+    pub offset: EnergyRate,
+}
+
+impl Default for EnergyCalibration {
+    fn default() -> Self {
+        EnergyCalibration {
+            scale: EnergyCalibration::default_scale(),
+            offset: EnergyRate::ZERO,
+        }
+    }
+}
+
+impl EnergyCalibration {
+    fn default_scale() -> f64 {
+        1.0
+    }
+
+    /// confirms the scale is a positive number
+    pub fn validate(&self) -> Result<(), TraversalModelError> {
+        if self.scale > 0.0 && self.scale.is_finite() {
+            Ok(())
+        } else {
+            Err(TraversalModelError::BuildError(format!(
+                "energy calibration scale must be positive, found {}",
+                self.scale
+            )))
+        }
+    }
+
+    /// applies the calibration to a predicted energy rate
+    pub fn apply(&self, energy_rate: EnergyRate) -> EnergyRate {
+        energy_rate * self.scale + self.offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use routee_compass_core::model::unit::AsF64;
+
+    #[test]
+    fn test_apply() {
+        let calibration = EnergyCalibration {
+            scale: 1.1,
+            offset: EnergyRate::new(0.5),
+        };
+        let calibrated = calibration.apply(EnergyRate::new(2.0));
+        assert!((calibrated.as_f64() - 2.7).abs() < 1e-9);
+        assert_eq!(
+            EnergyCalibration::default().apply(EnergyRate::new(2.0)),
+            EnergyRate::new(2.0)
+        );
+        let parsed: EnergyCalibration = serde_json::from_str(r#"{"scale": 0.9}"#).unwrap();
+        assert_eq!(parsed.offset, EnergyRate::ZERO);
+        let negative = EnergyCalibration {
+            scale: -1.0,
+            offset: EnergyRate::ZERO,
+        };
+        assert!(negative.validate().is_err());
+    }
+}
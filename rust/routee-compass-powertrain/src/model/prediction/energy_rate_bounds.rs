@@ -0,0 +1,50 @@
+use routee_compass_core::model::unit::EnergyRate;
+use serde::{Deserialize, Serialize};
+
+/// physical bounds on the energy rate predicted by a model, in the energy rate
+/// unit of that model. predictions outside of the bounds, such as negative rates
+/// on flat ground or very large rates when a model extrapolates beyond its
+/// training data, are clamped to the nearest bound.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct EnergyRateBounds {
+    pub min: Option<EnergyRate>,
+    pub max: Option<EnergyRate>,
+}
+
+impl EnergyRateBounds {
+    /// clamps an energy rate to these bounds
+    ///
+    /// # Returns
+    ///
+    /// the clamped energy rate, or None if the energy rate is within bounds
+    pub fn clamp(&self, energy_rate: EnergyRate) -> Option<EnergyRate> {
+        match (self.min, self.max) {
+            (Some(min), _) if energy_rate < min => Some(min),
+            (_, Some(max)) if energy_rate > max => Some(max),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp() {
+        let bounds = EnergyRateBounds {
+            min: Some(EnergyRate::ZERO),
+            max: Some(EnergyRate::new(2.0)),
+        };
+        assert_eq!(bounds.clamp(EnergyRate::new(-0.5)), Some(EnergyRate::ZERO));
+        assert_eq!(
+            bounds.clamp(EnergyRate::new(9.0)),
+            Some(EnergyRate::new(2.0))
+        );
+        assert_eq!(bounds.clamp(EnergyRate::new(1.0)), None);
+        assert_eq!(
+            EnergyRateBounds::default().clamp(EnergyRate::new(-9.0)),
+            None
+        );
+    }
+}
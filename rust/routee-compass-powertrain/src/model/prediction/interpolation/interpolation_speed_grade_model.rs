@@ -103,6 +103,7 @@ impl InterpolationSpeedGradeModel {
             None,
             None,
             None,
+            None,
         )?;
 
         // Create a linear grid of speed and grade values
@@ -194,6 +195,7 @@ mod test {
             None,
             None,
             None,
+            None,
         )
         .unwrap();
 
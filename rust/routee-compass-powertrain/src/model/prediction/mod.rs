@@ -1,5 +1,8 @@
+mod energy_calibration;
+mod energy_rate_bounds;
 pub mod interpolation;
 mod model_type;
+mod onnx_threads;
 mod prediction_model;
 pub mod prediction_model_ops;
 mod prediction_model_record;
@@ -8,7 +11,10 @@ pub mod smartcore;
 #[cfg(feature = "onnx")]
 pub mod onnx;
 
+pub use energy_calibration::EnergyCalibration;
+pub use energy_rate_bounds::EnergyRateBounds;
 pub use model_type::ModelType;
+pub use onnx_threads::OnnxThreads;
 pub use prediction_model::PredictionModel;
 pub use prediction_model_ops::load_prediction_model;
 pub use prediction_model_record::PredictionModelRecord;
@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use crate::routee::prediction::prediction_model::PredictionModel;
+use crate::model::prediction::{OnnxThreads, PredictionModel};
 
 use ort::{GraphOptimizationLevel, Session, Value};
 use routee_compass_core::{
@@ -24,8 +24,8 @@ impl PredictionModel for OnnxSpeedGradeModel {
         let (speed, speed_unit) = speed;
         let (grade, grade_unit) = grade;
 
-        let speed_value: f32 = speed_unit.convert(speed, self.speed_unit).as_f64() as f32;
-        let grade_value: f32 = grade_unit.convert(grade, self.grade_unit).as_f64() as f32;
+        let speed_value: f32 = speed_unit.convert(&speed, &self.speed_unit).as_f64() as f32;
+        let grade_value: f32 = grade_unit.convert(&grade, &self.grade_unit).as_f64() as f32;
         let array = ndarray::Array1::from(vec![speed_value, grade_value])
             .into_shape((1, 2))
             .map_err(|e| {
@@ -63,10 +63,27 @@ impl OnnxSpeedGradeModel {
         speed_unit: SpeedUnit,
         grade_unit: GradeUnit,
         energy_rate_unit: EnergyRateUnit,
+        threads: OnnxThreads,
     ) -> Result<Self, TraversalModelError> {
+        let cores = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        if let Some(total) = threads.oversubscribed(rayon::current_num_threads(), cores) {
+            log::warn!(
+                "onnx sessions with {} intra-op and {} inter-op threads may run {} threads at once on {} cores, consider lowering onnx_threads or parallelism",
+                threads.intra_op,
+                threads.inter_op,
+                total,
+                cores
+            );
+        }
         let session = Session::builder()
             .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
-            .with_intra_threads(1)
+            .with_intra_threads(threads.intra_op)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+            .with_inter_threads(threads.inter_op)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+            .with_parallel_execution(threads.inter_op > 1)
             .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
             .with_optimization_level(GraphOptimizationLevel::Level3)
             .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
@@ -86,8 +103,8 @@ impl OnnxSpeedGradeModel {
 mod test {
     use std::path::PathBuf;
 
-    use crate::routee::{
-        prediction::onnx::onnx_speed_grade_model::OnnxSpeedGradeModel, prediction::PredictionModel,
+    use crate::model::prediction::{
+        onnx::onnx_speed_grade_model::OnnxSpeedGradeModel, OnnxThreads, PredictionModel,
     };
     use rayon::prelude::*;
     use routee_compass_core::{
@@ -109,7 +126,8 @@ mod test {
                 &model_file_path,
                 SpeedUnit::MilesPerHour,
                 GradeUnit::Decimal,
-                routee_compass_core::model::unit::GallonsGasolinePerMile,
+                EnergyRateUnit::GallonsGasolinePerMile,
+                OnnxThreads::default(),
             )
             .unwrap(),
         );
@@ -147,4 +165,52 @@ mod test {
             }
         }));
     }
+
+    #[test]
+    #[ignore = "benchmark, run on a multi-core machine with --features onnx -- --ignored"]
+    // with queries already spread across every core, a single thread per onnx
+    // session is faster than letting each session use every core
+    fn bench_single_thread_sessions_outperform_oversubscribed() {
+        let model_file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("model")
+            .join("test")
+            .join("Toyota_Camry.onnx");
+        let cores = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        let run = |threads: OnnxThreads| {
+            let model = OnnxSpeedGradeModel::new(
+                &model_file_path,
+                SpeedUnit::MilesPerHour,
+                GradeUnit::Decimal,
+                EnergyRateUnit::GallonsGasolinePerMile,
+                threads,
+            )
+            .unwrap();
+            let start = std::time::Instant::now();
+            (0..20_000).into_par_iter().for_each(|i| {
+                let speed = Speed::new((i % 80) as f64);
+                model
+                    .predict(
+                        (speed, SpeedUnit::MilesPerHour),
+                        (Grade::ZERO, GradeUnit::Decimal),
+                    )
+                    .unwrap();
+            });
+            start.elapsed()
+        };
+        let single = run(OnnxThreads::default());
+        let oversubscribed = run(OnnxThreads {
+            intra_op: cores,
+            inter_op: 1,
+        });
+        assert!(
+            single < oversubscribed,
+            "single thread sessions took {:?}, sessions with {} threads took {:?}",
+            single,
+            cores,
+            oversubscribed
+        );
+    }
 }
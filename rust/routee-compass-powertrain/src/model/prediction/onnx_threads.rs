@@ -0,0 +1,96 @@
+use routee_compass_core::model::traversal::TraversalModelError;
+use serde::{Deserialize, Serialize};
+
+/// thread counts of the ONNX runtime session of an energy model. queries are
+/// already searched in parallel, one per core, so each session uses a single
+/// thread by default. raising these counts multiplies the threads competing
+/// for the cores, which slows batch runs unless `parallelism` is lowered to match.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct OnnxThreads {
+    /// threads used within a single operator of the model
+    #[serde(default = "one_thread")]
+    pub intra_op: usize,
+    /// threads used to run independent operators of the model at the same time
+    #[serde(default = "one_thread")]
+    pub inter_op: usize,
+}
+
+fn one_thread() -> usize {
+    1
+}
+
+impl Default for OnnxThreads {
+    fn default() -> Self {
+        OnnxThreads {
+            intra_op: 1,
+            inter_op: 1,
+        }
+    }
+}
+
+impl OnnxThreads {
+    /// checks that each thread count is at least one
+    pub fn validate(&self) -> Result<(), TraversalModelError> {
+        if self.intra_op == 0 || self.inter_op == 0 {
+            return Err(TraversalModelError::BuildError(format!(
+                "onnx_threads intra_op and inter_op must be at least 1, found {} and {}",
+                self.intra_op, self.inter_op
+            )));
+        }
+        Ok(())
+    }
+
+    /// the most threads a single prediction may run on
+    pub fn threads_per_session(&self) -> usize {
+        self.intra_op * self.inter_op
+    }
+
+    /// finds the threads that may run at once when each of the query threads
+    /// runs a prediction, if that is more than the cores available.
+    ///
+    /// # Arguments
+    ///
+    /// * `query_threads` - threads searching queries in parallel
+    /// * `cores` - cores available to the process
+    ///
+    /// # Returns
+    ///
+    /// the total thread count when the cores are oversubscribed, or None
+    pub fn oversubscribed(&self, query_threads: usize, cores: usize) -> Option<usize> {
+        let total = query_threads * self.threads_per_session();
+        if total > cores.max(query_threads) {
+            Some(total)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_onnx_threads() {
+        let threads: OnnxThreads = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(threads, OnnxThreads::default());
+        assert_eq!(threads.threads_per_session(), 1);
+        // one thread per session never adds threads to those searching queries
+        assert_eq!(threads.oversubscribed(16, 8), None);
+        assert_eq!(threads.oversubscribed(8, 8), None);
+
+        let threads: OnnxThreads =
+            serde_json::from_value(serde_json::json!({"intra_op": 4})).unwrap();
+        assert_eq!(threads.threads_per_session(), 4);
+        assert_eq!(threads.oversubscribed(8, 8), Some(32));
+        assert_eq!(threads.oversubscribed(2, 8), None);
+
+        let invalid = OnnxThreads {
+            intra_op: 0,
+            inter_op: 1,
+        };
+        assert!(invalid.validate().is_err());
+        assert!(serde_json::from_value::<OnnxThreads>(serde_json::json!({"intra": 2})).is_err());
+    }
+}
@@ -1,13 +1,16 @@
 use super::{
     interpolation::InterpolationSpeedGradeModel, model_type::ModelType,
-    smartcore::SmartcoreSpeedGradeModel, PredictionModel, PredictionModelRecord,
+    smartcore::SmartcoreSpeedGradeModel, OnnxThreads, PredictionModel, PredictionModelRecord,
 };
 use routee_compass_core::{
     model::traversal::TraversalModelError,
     model::unit::{EnergyRate, EnergyRateUnit, Grade, GradeUnit, Speed, SpeedUnit},
     util::cache_policy::float_cache_policy::FloatCachePolicy,
 };
-use std::{path::Path, sync::Arc};
+use std::{
+    path::Path,
+    sync::{atomic::AtomicU64, Arc},
+};
 
 #[cfg(feature = "onnx")]
 use crate::model::prediction::onnx::OnnxSpeedGradeModel;
@@ -23,7 +26,10 @@ pub fn load_prediction_model<P: AsRef<Path>>(
     ideal_energy_rate_option: Option<EnergyRate>,
     real_world_energy_adjustment_option: Option<f64>,
     cache: Option<FloatCachePolicy>,
+    onnx_threads: Option<OnnxThreads>,
 ) -> Result<PredictionModelRecord, TraversalModelError> {
+    let onnx_threads = onnx_threads.unwrap_or_default();
+    onnx_threads.validate()?;
     let prediction_model: Arc<dyn PredictionModel> = match model_type.clone() {
         ModelType::Smartcore => {
             let model = SmartcoreSpeedGradeModel::new(
@@ -37,8 +43,13 @@ pub fn load_prediction_model<P: AsRef<Path>>(
         ModelType::Onnx => {
             #[cfg(feature = "onnx")]
             {
-                let model =
-                    OnnxSpeedGradeModel::new(model_path, speed_unit, grade_unit, energy_rate_unit)?;
+                let model = OnnxSpeedGradeModel::new(
+                    model_path,
+                    speed_unit,
+                    grade_unit,
+                    energy_rate_unit,
+                    onnx_threads,
+                )?;
                 Arc::new(model)
             }
             #[cfg(not(feature = "onnx"))]
@@ -90,6 +101,9 @@ pub fn load_prediction_model<P: AsRef<Path>>(
         ideal_energy_rate,
         real_world_energy_adjustment,
         cache,
+        energy_rate_bounds: None,
+        clamped_predictions: AtomicU64::new(0),
+        calibration: None,
     })
 }
 
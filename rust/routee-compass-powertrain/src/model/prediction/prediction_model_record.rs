@@ -1,3 +1,4 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use routee_compass_core::{
@@ -9,7 +10,7 @@ use routee_compass_core::{
     util::cache_policy::float_cache_policy::FloatCachePolicy,
 };
 
-use super::{model_type::ModelType, PredictionModel};
+use super::{model_type::ModelType, EnergyCalibration, EnergyRateBounds, PredictionModel};
 /// A struct to hold the prediction model and associated metadata
 pub struct PredictionModelRecord {
     pub name: String,
@@ -21,9 +22,46 @@ pub struct PredictionModelRecord {
     pub ideal_energy_rate: EnergyRate,
     pub real_world_energy_adjustment: f64,
     pub cache: Option<FloatCachePolicy>,
+    /// optional physical bounds that predicted energy rates are clamped to
+    pub energy_rate_bounds: Option<EnergyRateBounds>,
+    /// number of predictions that were clamped to the energy rate bounds
+    pub clamped_predictions: AtomicU64,
+    /// optional bias correction applied to predicted energy rates
+    pub calibration: Option<EnergyCalibration>,
 }
 
 impl PredictionModelRecord {
+    /// clamps the energy rates predicted by this model to physical bounds
+    pub fn with_energy_rate_bounds(self, bounds: EnergyRateBounds) -> PredictionModelRecord {
+        PredictionModelRecord {
+            energy_rate_bounds: Some(bounds),
+            ..self
+        }
+    }
+
+    /// applies a calibration to the energy rates predicted by this model. the
+    /// ideal energy rate is lowered if the calibration reduces it, so that it
+    /// remains a lower bound on the calibrated rates.
+    pub fn with_calibration(
+        self,
+        calibration: EnergyCalibration,
+    ) -> Result<PredictionModelRecord, TraversalModelError> {
+        calibration.validate()?;
+        let ideal_energy_rate = self
+            .ideal_energy_rate
+            .min(calibration.apply(self.ideal_energy_rate));
+        Ok(PredictionModelRecord {
+            calibration: Some(calibration),
+            ideal_energy_rate,
+            ..self
+        })
+    }
+
+    /// the number of predictions clamped to the energy rate bounds so far
+    pub fn clamped_prediction_count(&self) -> u64 {
+        self.clamped_predictions.load(Ordering::Relaxed)
+    }
+
     pub fn predict(
         &self,
         speed: (Speed, SpeedUnit),
@@ -52,7 +90,12 @@ impl PredictionModelRecord {
             }
         };
 
+        let energy_rate = self.clamp_energy_rate(energy_rate, speed, grade);
         let energy_rate_real_world = energy_rate * self.real_world_energy_adjustment;
+        let energy_rate_real_world = match &self.calibration {
+            Some(calibration) => calibration.apply(energy_rate_real_world),
+            None => energy_rate_real_world,
+        };
 
         let (energy, energy_unit) = Energy::create(
             &energy_rate_real_world,
@@ -63,4 +106,140 @@ impl PredictionModelRecord {
 
         Ok((energy, energy_unit))
     }
+
+    /// applies the energy rate bounds, if any, to a predicted energy rate. the
+    /// first clamped prediction is logged as a warning along with the input
+    /// features, and later ones at debug level.
+    fn clamp_energy_rate(
+        &self,
+        energy_rate: EnergyRate,
+        speed: (Speed, SpeedUnit),
+        grade: (Grade, GradeUnit),
+    ) -> EnergyRate {
+        let clamped = match self
+            .energy_rate_bounds
+            .and_then(|bounds| bounds.clamp(energy_rate))
+        {
+            None => return energy_rate,
+            Some(clamped) => clamped,
+        };
+        let count = self.clamped_predictions.fetch_add(1, Ordering::Relaxed);
+        let msg = format!(
+            "model {} predicted energy rate {} {} at speed {} {} and grade {} {}, clamped to {}",
+            self.name,
+            energy_rate,
+            self.energy_rate_unit,
+            speed.0,
+            speed.1,
+            grade.0,
+            grade.1,
+            clamped
+        );
+        if count == 0 {
+            log::warn!("{} (further clamping is logged at debug level)", msg);
+        } else {
+            log::debug!("{}", msg);
+        }
+        clamped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::prediction::load_prediction_model;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_energy_rate_clamped_to_bounds() {
+        let model_file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("model")
+            .join("test")
+            .join("Toyota_Camry.bin");
+        let load = || {
+            load_prediction_model(
+                "Toyota_Camry".to_string(),
+                &model_file_path,
+                ModelType::Smartcore,
+                SpeedUnit::MilesPerHour,
+                GradeUnit::Decimal,
+                EnergyRateUnit::GallonsGasolinePerMile,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+        };
+        let speed = (Speed::new(60.0), SpeedUnit::MilesPerHour);
+        let grade = (Grade::ZERO, GradeUnit::Decimal);
+        let distance = (Distance::new(1.0), DistanceUnit::Miles);
+
+        // a lower bound above any plausible prediction of the model clamps the prediction
+        let min = EnergyRate::new(10.0);
+        let bounded = load().with_energy_rate_bounds(EnergyRateBounds {
+            min: Some(min),
+            max: None,
+        });
+        let (energy, _) = bounded.predict(speed, grade, distance).unwrap();
+        assert!((energy.as_f64() - min.as_f64()).abs() < 1e-9);
+        assert_eq!(bounded.clamped_prediction_count(), 1);
+
+        // predictions within the bounds are unchanged
+        let unbounded = load();
+        let (expected, _) = unbounded.predict(speed, grade, distance).unwrap();
+        let wide = load().with_energy_rate_bounds(EnergyRateBounds {
+            min: Some(EnergyRate::ZERO),
+            max: Some(EnergyRate::new(1.0)),
+        });
+        let (energy, _) = wide.predict(speed, grade, distance).unwrap();
+        assert_eq!(energy, expected);
+        assert_eq!(wide.clamped_prediction_count(), 0);
+    }
+
+    #[test]
+    fn test_calibration_scales_energy() {
+        let model_file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("model")
+            .join("test")
+            .join("Toyota_Camry.bin");
+        let load = || {
+            load_prediction_model(
+                "Toyota_Camry".to_string(),
+                &model_file_path,
+                ModelType::Smartcore,
+                SpeedUnit::MilesPerHour,
+                GradeUnit::Decimal,
+                EnergyRateUnit::GallonsGasolinePerMile,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+        };
+        let uncalibrated = load();
+        let calibrated = load()
+            .with_calibration(EnergyCalibration {
+                scale: 1.1,
+                offset: EnergyRate::ZERO,
+            })
+            .unwrap();
+        let distance = (Distance::new(10.0), DistanceUnit::Miles);
+        for (speed, grade) in [(25.0, -0.02), (45.0, 0.0), (65.0, 0.04)] {
+            let speed = (Speed::new(speed), SpeedUnit::MilesPerHour);
+            let grade = (Grade::new(grade), GradeUnit::Decimal);
+            let (expected, _) = uncalibrated.predict(speed, grade, distance).unwrap();
+            let (energy, _) = calibrated.predict(speed, grade, distance).unwrap();
+            assert!(
+                (energy.as_f64() - 1.1 * expected.as_f64()).abs() < 1e-9,
+                "calibrated energy {} should be 10% more than {}",
+                energy,
+                expected
+            );
+        }
+        assert!(calibrated.ideal_energy_rate <= uncalibrated.ideal_energy_rate);
+    }
 }
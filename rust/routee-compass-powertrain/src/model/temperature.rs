@@ -0,0 +1,78 @@
+use routee_compass_core::model::{
+    network::EdgeId,
+    state::{CustomFeatureFormat, StateFeature},
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// the temperature along a single edge. a row of an edge temperature file,
+/// which is a CSV with header `edge_id,temperature`, with temperatures in the
+/// unit of the temperature model.
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct EdgeTemperature {
+    pub edge_id: EdgeId,
+    pub temperature: f64,
+}
+
+/// the temperature along each edge, such as from a raster of winter temperatures,
+/// which the energy traversal model records to a state feature on every edge.
+pub struct TemperatureModel {
+    /// temperature along each listed edge
+    pub edge_temperatures: Option<HashMap<EdgeId, f64>>,
+    /// the ambient temperature, used for edges missing from the table
+    pub ambient_temperature: f64,
+    pub temperature_unit: String,
+    pub temperature_feature_name: String,
+}
+
+impl TemperatureModel {
+    pub const DEFAULT_TEMPERATURE_UNIT: &'static str = "celsius";
+    pub const DEFAULT_FEATURE_NAME: &'static str = "leg_temperature";
+
+    /// the temperature along an edge, or the ambient temperature when the
+    /// edge is not listed
+    pub fn get_temperature(&self, edge_id: &EdgeId) -> f64 {
+        self.edge_temperatures
+            .as_ref()
+            .and_then(|table| table.get(edge_id))
+            .cloned()
+            .unwrap_or(self.ambient_temperature)
+    }
+
+    /// the state feature holding the temperature of the most recently traversed edge
+    pub fn state_feature(&self) -> (String, StateFeature) {
+        (
+            self.temperature_feature_name.clone(),
+            StateFeature::Custom {
+                r#type: String::from("temperature"),
+                unit: self.temperature_unit.clone(),
+                format: CustomFeatureFormat::FloatingPoint {
+                    initial: self.ambient_temperature.into(),
+                },
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_edges_use_ambient_temperature() {
+        let model = TemperatureModel {
+            edge_temperatures: Some(HashMap::from([(EdgeId(1), -12.5)])),
+            ambient_temperature: -5.0,
+            temperature_unit: String::from(TemperatureModel::DEFAULT_TEMPERATURE_UNIT),
+            temperature_feature_name: String::from(TemperatureModel::DEFAULT_FEATURE_NAME),
+        };
+        assert_eq!(model.get_temperature(&EdgeId(1)), -12.5);
+        assert_eq!(model.get_temperature(&EdgeId(0)), -5.0);
+
+        let constant = TemperatureModel {
+            edge_temperatures: None,
+            ..model
+        };
+        assert_eq!(constant.get_temperature(&EdgeId(1)), -5.0);
+    }
+}
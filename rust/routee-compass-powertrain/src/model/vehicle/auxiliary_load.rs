@@ -0,0 +1,193 @@
+use routee_compass_core::model::{
+    traversal::TraversalModelError,
+    unit::{AsF64, Distance, DistanceUnit, Energy, EnergyUnit, Grade, GradeUnit, Speed, SpeedUnit},
+};
+
+/// additional load placed on a vehicle that is not captured by the energy
+/// prediction model, such as cargo payload or accessories (AC, refrigeration).
+/// these are provided on the query and applied as a post-adjustment to the
+/// predicted energy of each edge traversal.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AuxiliaryLoad {
+    /// extra mass carried by the vehicle in kilograms
+    pub payload_kg: f64,
+    /// constant accessory power draw in kilowatts
+    pub accessory_power_kw: f64,
+}
+
+impl AuxiliaryLoad {
+    pub const PAYLOAD_KG: &'static str = "payload_kg";
+    pub const ACCESSORY_POWER_KW: &'static str = "accessory_power_kw";
+
+    /// gravitational acceleration in meters per second squared
+    const GRAVITY: f64 = 9.81;
+    /// rolling resistance coefficient for a passenger tire on asphalt
+    const ROLLING_RESISTANCE: f64 = 0.008;
+    /// joules per kilowatt hour
+    const JOULES_PER_KWH: f64 = 3_600_000.0;
+
+    /// reads the optional auxiliary load parameters from a query. missing
+    /// keys are treated as zero load.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - the incoming search query
+    ///
+    /// # Returns
+    ///
+    /// the auxiliary load for this query, or an error if the values are
+    /// not non-negative numbers
+    pub fn from_query(query: &serde_json::Value) -> Result<AuxiliaryLoad, TraversalModelError> {
+        let payload_kg = get_non_negative_f64(query, AuxiliaryLoad::PAYLOAD_KG)?;
+        let accessory_power_kw = get_non_negative_f64(query, AuxiliaryLoad::ACCESSORY_POWER_KW)?;
+        Ok(AuxiliaryLoad {
+            payload_kg,
+            accessory_power_kw,
+        })
+    }
+
+    /// true if this load will not modify the energy of a traversal
+    pub fn is_zero(&self) -> bool {
+        self.payload_kg == 0.0 && self.accessory_power_kw == 0.0
+    }
+
+    /// computes the additional energy required to traverse an edge due to this
+    /// auxiliary load.
+    ///
+    /// payload energy is the work done against rolling resistance and gravity
+    /// by the extra mass, and so depends on distance and grade. it is not allowed
+    /// to go negative: on descents, the energy of the extra mass is assumed lost
+    /// to braking. accessory energy is the accessory power integrated over the
+    /// time spent on the edge, and so depends on distance and speed.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed`       - speed of the traversal
+    /// * `grade`       - grade of the traversal
+    /// * `distance`    - distance of the traversal
+    /// * `efficiency`  - fraction of source energy delivered to the wheels/accessories
+    /// * `energy_unit` - unit of the resulting energy value
+    ///
+    /// # Returns
+    ///
+    /// the additional energy consumed from the vehicle's energy source
+    pub fn energy(
+        &self,
+        speed: (Speed, SpeedUnit),
+        grade: (Grade, GradeUnit),
+        distance: (Distance, DistanceUnit),
+        efficiency: f64,
+        energy_unit: &EnergyUnit,
+    ) -> Energy {
+        if self.is_zero() {
+            return Energy::ZERO;
+        }
+        let (speed, speed_unit) = speed;
+        let (grade, grade_unit) = grade;
+        let (distance, distance_unit) = distance;
+        let meters = distance_unit
+            .convert(&distance, &DistanceUnit::Meters)
+            .as_f64();
+        let grade_decimal = grade_unit.convert(&grade, &GradeUnit::Decimal).as_f64();
+        let angle = grade_decimal.atan();
+
+        let payload_joules = self.payload_kg
+            * AuxiliaryLoad::GRAVITY
+            * meters
+            * (AuxiliaryLoad::ROLLING_RESISTANCE * angle.cos() + angle.sin());
+        let payload_kwh = payload_joules.max(0.0) / AuxiliaryLoad::JOULES_PER_KWH;
+
+        let speed_value = speed_unit
+            .convert(&speed, &SpeedUnit::KilometersPerHour)
+            .as_f64();
+        let accessory_kwh = if speed_value > 0.0 {
+            let hours = distance_unit
+                .convert(&distance, &DistanceUnit::Kilometers)
+                .as_f64()
+                / speed_value;
+            self.accessory_power_kw * hours
+        } else {
+            0.0
+        };
+
+        let source_kwh = Energy::new((payload_kwh + accessory_kwh) / efficiency);
+        EnergyUnit::KilowattHours.convert(&source_kwh, energy_unit)
+    }
+}
+
+fn get_non_negative_f64(query: &serde_json::Value, key: &str) -> Result<f64, TraversalModelError> {
+    match query.get(key) {
+        None => Ok(0.0),
+        Some(v) => {
+            let value = v.as_f64().ok_or_else(|| {
+                TraversalModelError::BuildError(format!("Expected '{}' value to be numeric", key))
+            })?;
+            if value < 0.0 {
+                Err(TraversalModelError::BuildError(format!(
+                    "Expected '{}' value to be non-negative",
+                    key
+                )))
+            } else {
+                Ok(value)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(load: &AuxiliaryLoad, speed_mph: f64, grade_percent: f64, miles: f64) -> f64 {
+        load.energy(
+            (Speed::new(speed_mph), SpeedUnit::MilesPerHour),
+            (Grade::new(grade_percent), GradeUnit::Percent),
+            (Distance::new(miles), DistanceUnit::Miles),
+            1.0,
+            &EnergyUnit::KilowattHours,
+        )
+        .as_f64()
+    }
+
+    #[test]
+    fn test_from_query() {
+        let query = serde_json::json!({"payload_kg": 500.0, "accessory_power_kw": 2});
+        let load = AuxiliaryLoad::from_query(&query).unwrap();
+        assert_eq!(load.payload_kg, 500.0);
+        assert_eq!(load.accessory_power_kw, 2.0);
+        let empty = AuxiliaryLoad::from_query(&serde_json::json!({})).unwrap();
+        assert!(empty.is_zero());
+        let negative = serde_json::json!({"payload_kg": -1.0});
+        assert!(AuxiliaryLoad::from_query(&negative).is_err());
+    }
+
+    #[test]
+    fn test_payload_increases_energy_more_on_climbs() {
+        let load = AuxiliaryLoad {
+            payload_kg: 1000.0,
+            accessory_power_kw: 0.0,
+        };
+        let flat = run(&load, 40.0, 0.0, 1.0);
+        let climb = run(&load, 40.0, 5.0, 1.0);
+        let descent = run(&load, 40.0, -5.0, 1.0);
+        assert!(flat > 0.0, "payload energy {} should be > 0", flat);
+        assert!(climb > flat, "climb {} should exceed flat {}", climb, flat);
+        assert_eq!(descent, 0.0);
+    }
+
+    #[test]
+    fn test_accessory_scales_with_time_not_distance() {
+        let load = AuxiliaryLoad {
+            payload_kg: 0.0,
+            accessory_power_kw: 3.0,
+        };
+        // one hour at 30 mph and one hour at 60 mph consume the same accessory energy
+        let slow = run(&load, 30.0, 0.0, 30.0);
+        let fast = run(&load, 60.0, 0.0, 60.0);
+        assert!((slow - 3.0).abs() < 1e-6, "expected 3 kWh, found {}", slow);
+        assert!((fast - 3.0).abs() < 1e-6, "expected 3 kWh, found {}", fast);
+        // the same distance at half the speed takes twice as long
+        let half_speed = run(&load, 30.0, 0.0, 60.0);
+        assert!((half_speed - 2.0 * fast).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,208 @@
+use routee_compass_core::model::{
+    state::{CustomFeatureFormat, StateFeature, StateModel, StateVariable},
+    traversal::TraversalModelError,
+    unit::{AsF64, Distance, DistanceUnit, Energy, Speed, SpeedUnit, Time, TimeUnit},
+};
+use serde::{Deserialize, Serialize};
+
+/// extra energy used by a vehicle while its engine warms up at the start of a
+/// trip. the predicted energy of each edge is increased by `initial_penalty` at
+/// the trip origin, decaying linearly to zero once the vehicle has traveled the
+/// warm-up distance or time.
+///
+/// warm-up progress is tracked in the state as the fraction of the warm-up
+/// completed, which starts at zero for every trip.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ColdStart {
+    /// extra fraction of the predicted energy used at the start of a trip,
+    /// such as 0.5 for 50% more energy
+    pub initial_penalty: f64,
+    #[serde(flatten)]
+    pub warmup: Warmup,
+}
+
+/// the extent of the warm-up period, by distance or by time
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Warmup {
+    Distance {
+        warmup_distance: Distance,
+        warmup_distance_unit: DistanceUnit,
+    },
+    Time {
+        warmup_time: Time,
+        warmup_time_unit: TimeUnit,
+    },
+}
+
+impl ColdStart {
+    pub const WARMUP_FEATURE_NAME: &'static str = "engine_warmup";
+
+    /// the state feature tracking the fraction of the warm-up completed
+    pub fn state_feature(&self) -> (String, StateFeature) {
+        (
+            String::from(ColdStart::WARMUP_FEATURE_NAME),
+            StateFeature::Custom {
+                r#type: String::from("warmup"),
+                unit: String::from("fraction"),
+                format: CustomFeatureFormat::FloatingPoint {
+                    initial: 0.0.into(),
+                },
+            },
+        )
+    }
+
+    /// advances the warm-up over an edge traversal and computes the extra energy
+    /// used on the edge. the penalty is averaged over the edge, so an edge that
+    /// ends after the vehicle is warm is only penalized for the part before it.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed`        - speed of the traversal
+    /// * `distance`     - distance of the traversal
+    /// * `model_energy` - energy predicted for the traversal with a warm engine
+    /// * `state`        - state to update with the warm-up progress
+    /// * `state_model`  - model for the state
+    ///
+    /// # Returns
+    ///
+    /// the additional energy consumed on the edge, in the unit of `model_energy`
+    pub fn energy(
+        &self,
+        speed: (Speed, SpeedUnit),
+        distance: (Distance, DistanceUnit),
+        model_energy: Energy,
+        state: &mut [StateVariable],
+        state_model: &StateModel,
+    ) -> Result<Energy, TraversalModelError> {
+        let feature = String::from(ColdStart::WARMUP_FEATURE_NAME);
+        let start = state_model.get_custom_f64(state, &feature)?;
+        if start >= 1.0 {
+            return Ok(Energy::ZERO);
+        }
+        let (distance, distance_unit) = distance;
+        let progress = match self.warmup {
+            Warmup::Distance {
+                warmup_distance,
+                warmup_distance_unit,
+            } => {
+                let traveled = distance_unit.convert(&distance, &warmup_distance_unit);
+                ratio(traveled.as_f64(), warmup_distance.as_f64())
+            }
+            Warmup::Time {
+                warmup_time,
+                warmup_time_unit,
+            } => {
+                let (speed, speed_unit) = speed;
+                if speed.as_f64() <= 0.0 {
+                    return Ok(Energy::ZERO);
+                }
+                let elapsed = Time::create(
+                    &speed,
+                    &speed_unit,
+                    &distance,
+                    &distance_unit,
+                    &warmup_time_unit,
+                )?;
+                ratio(elapsed.as_f64(), warmup_time.as_f64())
+            }
+        };
+        if progress <= 0.0 {
+            return Ok(Energy::ZERO);
+        }
+        let end = (start + progress).min(1.0);
+        state_model.set_custom_f64(state, &feature, &end)?;
+
+        // the penalty decays as (1 - w) over the warm-up fraction w, so its mean
+        // over the edge is the integral from start to end divided by the progress
+        let integral = (end - start) - (end * end - start * start) / 2.0;
+        let factor = self.initial_penalty * integral / progress;
+        Ok(Energy::new(model_energy.as_f64() * factor))
+    }
+}
+
+/// fraction of the warm-up covered by a traversal. a warm-up of zero extent
+/// completes immediately.
+fn ratio(value: f64, warmup: f64) -> f64 {
+    if warmup <= 0.0 {
+        f64::INFINITY
+    } else {
+        value / warmup
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn per_mile_energy(cold_start: &ColdStart, miles: f64) -> f64 {
+        let state_model = StateModel::empty()
+            .extend(vec![cold_start.state_feature()])
+            .unwrap();
+        let mut state = state_model.initial_state().unwrap();
+        // a trip of one mile edges, each using 0.03 gallons with a warm engine
+        let edges = (miles as usize).max(1);
+        let edge_miles = miles / edges as f64;
+        let warm_energy = 0.03 * edge_miles;
+        let mut total = 0.0;
+        for _ in 0..edges {
+            let extra = cold_start
+                .energy(
+                    (Speed::new(30.0), SpeedUnit::MilesPerHour),
+                    (Distance::new(edge_miles), DistanceUnit::Miles),
+                    Energy::new(warm_energy),
+                    &mut state,
+                    &state_model,
+                )
+                .unwrap();
+            total += warm_energy + extra.as_f64();
+        }
+        total / miles
+    }
+
+    #[test]
+    fn test_short_trip_has_higher_energy_rate() {
+        let cold_start = ColdStart {
+            initial_penalty: 0.5,
+            warmup: Warmup::Distance {
+                warmup_distance: Distance::new(4.0),
+                warmup_distance_unit: DistanceUnit::Miles,
+            },
+        };
+        let short = per_mile_energy(&cold_start, 2.0);
+        let long = per_mile_energy(&cold_start, 40.0);
+        // the first 4 miles use 25% more energy on average, so a 2 mile trip
+        // averages 37.5% over the warm rate while a 40 mile trip averages 2.5%
+        assert!((short - 0.03 * 1.375).abs() < 1e-9, "{}", short);
+        assert!((long - 0.03 * 1.025).abs() < 1e-9, "{}", long);
+
+        // the same warm-up expressed as 8 minutes at 30 mph
+        let by_time = ColdStart {
+            initial_penalty: 0.5,
+            warmup: Warmup::Time {
+                warmup_time: Time::new(8.0),
+                warmup_time_unit: TimeUnit::Minutes,
+            },
+        };
+        // minute to hour conversions are rounded, so these only agree approximately
+        assert!((per_mile_energy(&by_time, 2.0) - short).abs() < 1e-6);
+        assert!((per_mile_energy(&by_time, 40.0) - long).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let conf = serde_json::json!({
+            "initial_penalty": 0.3,
+            "warmup_time": 5.0,
+            "warmup_time_unit": "minutes"
+        });
+        let cold_start: ColdStart = serde_json::from_value(conf).unwrap();
+        assert_eq!(
+            cold_start.warmup,
+            Warmup::Time {
+                warmup_time: Time::new(5.0),
+                warmup_time_unit: TimeUnit::Minutes
+            }
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::model::{
     prediction::PredictionModelRecord,
-    vehicle::{vehicle_ops, vehicle_type::VehicleType},
+    vehicle::{vehicle_ops, vehicle_type::VehicleType, AuxiliaryLoad, RegenBraking},
 };
 use routee_compass_core::model::{
     state::{CustomFeatureFormat, StateFeature, StateModel, StateVariable},
@@ -15,11 +15,17 @@ pub struct BEV {
     pub battery_capacity: Energy,
     pub starting_battery_energy: Energy,
     pub battery_energy_unit: EnergyUnit,
+    pub auxiliary_load: AuxiliaryLoad,
+    /// speed-dependent efficiency of regenerative braking. without it, the
+    /// recovery predicted by the energy model is used as is.
+    pub regen_braking: Option<RegenBraking>,
 }
 
 impl BEV {
     const ENERGY_FEATURE_NAME: &'static str = "energy_electric";
     const SOC_FEATURE_NAME: &'static str = "battery_state";
+    /// fraction of battery energy delivered to the wheels and accessories
+    const DRIVETRAIN_EFFICIENCY: f64 = 0.9;
 
     pub fn new(
         name: String,
@@ -34,6 +40,16 @@ impl BEV {
             battery_capacity,
             starting_battery_energy,
             battery_energy_unit,
+            auxiliary_load: AuxiliaryLoad::default(),
+            regen_braking: None,
+        }
+    }
+
+    /// sets the speed-dependent efficiency of regenerative braking
+    pub fn with_regen_braking(self, regen_braking: Option<RegenBraking>) -> Self {
+        Self {
+            regen_braking,
+            ..self
         }
     }
 }
@@ -114,9 +130,25 @@ impl VehicleType for BEV {
         state: &mut Vec<StateVariable>,
         state_model: &StateModel,
     ) -> Result<(), TraversalModelError> {
-        let (predicted_energy, energy_unit) = self
+        let (model_energy, energy_unit) = self
             .prediction_model_record
             .predict(speed, grade, distance)?;
+        let model_energy = match &self.regen_braking {
+            Some(regen) if model_energy < Energy::ZERO => {
+                let predicted = Energy::new(-model_energy.as_f64());
+                let recovered = regen.recovered_energy(speed, distance, predicted, &energy_unit)?;
+                Energy::new(-recovered.as_f64())
+            }
+            _ => model_energy,
+        };
+        let auxiliary_energy = self.auxiliary_load.energy(
+            speed,
+            grade,
+            distance,
+            BEV::DRIVETRAIN_EFFICIENCY,
+            &energy_unit,
+        );
+        let predicted_energy = model_energy + auxiliary_energy;
         let battery_delta = energy_unit.convert(&predicted_energy, &self.battery_energy_unit);
         state_model.add_energy(
             state,
@@ -162,6 +194,8 @@ impl VehicleType for BEV {
             battery_capacity: self.battery_capacity,
             starting_battery_energy,
             battery_energy_unit: self.battery_energy_unit,
+            auxiliary_load: AuxiliaryLoad::from_query(query)?,
+            regen_braking: self.regen_braking.clone(),
         };
 
         Ok(Arc::new(new_bev))
@@ -200,6 +234,7 @@ mod tests {
             Some(EnergyRate::new(0.2)),
             Some(1.3958),
             None,
+            None,
         )
         .unwrap();
 
@@ -1,4 +1,7 @@
-use crate::model::{prediction::PredictionModelRecord, vehicle::VehicleType};
+use crate::model::{
+    prediction::PredictionModelRecord,
+    vehicle::{AuxiliaryLoad, ColdStart, VehicleType},
+};
 use routee_compass_core::model::{
     state::{StateFeature, StateModel, StateVariable},
     traversal::TraversalModelError,
@@ -9,10 +12,16 @@ use std::sync::Arc;
 pub struct ICE {
     pub name: String,
     pub prediction_model_record: Arc<PredictionModelRecord>,
+    pub auxiliary_load: AuxiliaryLoad,
+    /// extra energy used while the engine warms up, if configured
+    pub cold_start: Option<ColdStart>,
 }
 
 impl ICE {
     const ENERGY_FEATURE_NAME: &'static str = "energy_liquid";
+    /// fraction of fuel energy delivered to the wheels and accessories
+    const ENGINE_EFFICIENCY: f64 = 0.25;
+
     pub fn new(
         name: String,
         prediction_model_record: PredictionModelRecord,
@@ -20,8 +29,15 @@ impl ICE {
         Ok(Self {
             name,
             prediction_model_record: Arc::new(prediction_model_record),
+            auxiliary_load: AuxiliaryLoad::default(),
+            cold_start: None,
         })
     }
+
+    /// sets the cold-start penalty applied at the start of each trip
+    pub fn with_cold_start(self, cold_start: Option<ColdStart>) -> Self {
+        Self { cold_start, ..self }
+    }
 }
 
 impl VehicleType for ICE {
@@ -33,13 +49,17 @@ impl VehicleType for ICE {
             .prediction_model_record
             .energy_rate_unit
             .associated_energy_unit();
-        vec![(
+        let mut features = vec![(
             String::from(ICE::ENERGY_FEATURE_NAME),
             StateFeature::Energy {
                 energy_unit,
                 initial: Energy::ZERO,
             },
-        )]
+        )];
+        if let Some(cold_start) = &self.cold_start {
+            features.push(cold_start.state_feature());
+        }
+        features
     }
     fn best_case_energy(
         &self,
@@ -82,29 +102,44 @@ impl VehicleType for ICE {
         state: &mut Vec<StateVariable>,
         state_model: &StateModel,
     ) -> Result<(), TraversalModelError> {
-        let (energy, _energy_unit) = self
+        let energy_unit = self
+            .prediction_model_record
+            .energy_rate_unit
+            .associated_energy_unit();
+        let (model_energy, _energy_unit) = self
             .prediction_model_record
             .predict(speed, grade, distance)?;
+        let auxiliary_energy = self.auxiliary_load.energy(
+            speed,
+            grade,
+            distance,
+            ICE::ENGINE_EFFICIENCY,
+            &energy_unit,
+        );
+        let cold_start_energy = match &self.cold_start {
+            Some(cold_start) => {
+                cold_start.energy(speed, distance, model_energy, state, state_model)?
+            }
+            None => Energy::ZERO,
+        };
         state_model.add_energy(
             state,
             &ICE::ENERGY_FEATURE_NAME.into(),
-            &energy,
-            &self
-                .prediction_model_record
-                .energy_rate_unit
-                .associated_energy_unit(),
+            &(model_energy + auxiliary_energy + cold_start_energy),
+            &energy_unit,
         )?;
         Ok(())
     }
 
     fn update_from_query(
         &self,
-        _query: &serde_json::Value,
+        query: &serde_json::Value,
     ) -> Result<Arc<dyn VehicleType>, TraversalModelError> {
-        // just return a clone of self
         Ok(Arc::new(ICE {
             name: self.name.clone(),
             prediction_model_record: self.prediction_model_record.clone(),
+            auxiliary_load: AuxiliaryLoad::from_query(query)?,
+            cold_start: self.cold_start,
         }))
     }
 }
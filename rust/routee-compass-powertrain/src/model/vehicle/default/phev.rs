@@ -1,6 +1,6 @@
 use crate::model::{
     prediction::PredictionModelRecord,
-    vehicle::{vehicle_ops, vehicle_type::VehicleType},
+    vehicle::{vehicle_ops, vehicle_type::VehicleType, AuxiliaryLoad},
 };
 use routee_compass_core::model::{
     state::{CustomFeatureFormat, StateFeature, StateModel, StateVariable},
@@ -17,12 +17,17 @@ pub struct PHEV {
     pub starting_battery_energy: Energy,
     pub battery_energy_unit: EnergyUnit,
     pub custom_liquid_fuel_to_kwh: Option<f64>,
+    pub auxiliary_load: AuxiliaryLoad,
 }
 
 impl PHEV {
     const LIQUID_FEATURE_NAME: &'static str = "energy_liquid";
     const ELECTRIC_FEATURE_NAME: &'static str = "energy_electric";
     const SOC_FEATURE_NAME: &'static str = "battery_state";
+    /// fraction of battery energy delivered to the wheels and accessories
+    const ELECTRIC_EFFICIENCY: f64 = 0.9;
+    /// fraction of fuel energy delivered to the wheels and accessories
+    const LIQUID_EFFICIENCY: f64 = 0.25;
 
     pub fn new(
         name: String,
@@ -41,6 +46,7 @@ impl PHEV {
             starting_battery_energy,
             battery_energy_unit,
             custom_liquid_fuel_to_kwh,
+            auxiliary_load: AuxiliaryLoad::default(),
         })
     }
 }
@@ -193,6 +199,7 @@ impl VehicleType for PHEV {
             starting_battery_energy,
             battery_energy_unit: self.battery_energy_unit,
             custom_liquid_fuel_to_kwh: self.custom_liquid_fuel_to_kwh,
+            auxiliary_load: AuxiliaryLoad::from_query(query)?,
         };
 
         Ok(Arc::new(new_phev))
@@ -228,9 +235,17 @@ fn get_phev_energy(
 
     if battery_soc_percent > 0.0 {
         // assume we can just use the battery
-        let (electrical_energy, electrical_energy_unit) = vehicle
+        let (model_energy, electrical_energy_unit) = vehicle
             .charge_depleting_model
             .predict(speed, grade, distance)?;
+        let auxiliary_energy = vehicle.auxiliary_load.energy(
+            speed,
+            grade,
+            distance,
+            PHEV::ELECTRIC_EFFICIENCY,
+            &electrical_energy_unit,
+        );
+        let electrical_energy = model_energy + auxiliary_energy;
         Ok((
             electrical_energy,
             electrical_energy_unit,
@@ -239,9 +254,17 @@ fn get_phev_energy(
         ))
     } else {
         // just use the liquid_fuel engine
-        let (liquid_fuel_energy, liquid_fuel_energy_unit) = vehicle
+        let (model_energy, liquid_fuel_energy_unit) = vehicle
             .charge_sustain_model
             .predict(speed, grade, distance)?;
+        let auxiliary_energy = vehicle.auxiliary_load.energy(
+            speed,
+            grade,
+            distance,
+            PHEV::LIQUID_EFFICIENCY,
+            &liquid_fuel_energy_unit,
+        );
+        let liquid_fuel_energy = model_energy + auxiliary_energy;
         Ok((
             Energy::new(0.0),
             electrical_energy_unit,
@@ -289,6 +312,7 @@ mod tests {
             Some(EnergyRate::new(0.02)),
             Some(1.1252),
             None,
+            None,
         )
         .unwrap();
         let charge_depleting_model_record = load_prediction_model(
@@ -301,6 +325,7 @@ mod tests {
             Some(EnergyRate::new(0.2)),
             Some(1.3958),
             None,
+            None,
         )
         .unwrap();
 
@@ -1,7 +1,13 @@
+mod auxiliary_load;
+mod cold_start;
 pub mod default;
+mod regen_braking;
 mod vehicle_energy_result;
 pub mod vehicle_ops;
 mod vehicle_type;
 
+pub use auxiliary_load::AuxiliaryLoad;
+pub use cold_start::{ColdStart, Warmup};
+pub use regen_braking::RegenBraking;
 pub use vehicle_energy_result::VehicleEnergyResult;
 pub use vehicle_type::VehicleType;
@@ -0,0 +1,185 @@
+use routee_compass_core::model::{
+    traversal::TraversalModelError,
+    unit::{AsF64, Distance, DistanceUnit, Energy, EnergyUnit, Speed, SpeedUnit, Time, TimeUnit},
+};
+use serde::{Deserialize, Serialize};
+
+/// speed-dependent efficiency of regenerative braking for a battery electric
+/// vehicle. when the predicted energy of an edge is negative, the energy
+/// recovered is scaled by the efficiency at the edge speed, which is low at
+/// very low speeds where the motor recovers little. the recovery on an edge
+/// is optionally capped by the most power the drivetrain can absorb, which
+/// limits recovery under hard deceleration.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RegenBraking {
+    /// `[speed, efficiency]` points of the efficiency curve, with efficiencies
+    /// between 0 and 1. the efficiency between points is linearly interpolated
+    /// and held constant beyond the first and last points.
+    pub efficiency_curve: Vec<(Speed, f64)>,
+    /// unit of the speeds of the efficiency curve
+    pub speed_unit: SpeedUnit,
+    /// most power the battery can take in while braking, in kilowatts
+    pub max_regen_power_kw: Option<f64>,
+}
+
+impl RegenBraking {
+    /// checks that the curve has points, sorted by speed, with efficiencies
+    /// between 0 and 1, and that any power cap is not negative
+    pub fn validate(&self) -> Result<(), TraversalModelError> {
+        if self.efficiency_curve.is_empty() {
+            return Err(TraversalModelError::BuildError(String::from(
+                "regen_braking efficiency_curve must have at least one point",
+            )));
+        }
+        if self.efficiency_curve.windows(2).any(|w| w[0].0 >= w[1].0) {
+            return Err(TraversalModelError::BuildError(String::from(
+                "regen_braking efficiency_curve speeds must be strictly increasing",
+            )));
+        }
+        if let Some((speed, efficiency)) = self
+            .efficiency_curve
+            .iter()
+            .find(|(_, e)| !(0.0..=1.0).contains(e))
+        {
+            return Err(TraversalModelError::BuildError(format!(
+                "regen_braking efficiency {} at speed {} must be between 0 and 1",
+                efficiency, speed
+            )));
+        }
+        match self.max_regen_power_kw {
+            Some(power) if power < 0.0 => Err(TraversalModelError::BuildError(format!(
+                "regen_braking max_regen_power_kw must not be negative, found {}",
+                power
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// the efficiency of regenerative braking at a speed, interpolated from the curve
+    pub fn efficiency(&self, speed: (Speed, SpeedUnit)) -> f64 {
+        let (speed, speed_unit) = speed;
+        let speed = speed_unit.convert(&speed, &self.speed_unit).as_f64();
+        let curve = &self.efficiency_curve;
+        let upper = curve.partition_point(|(s, _)| s.as_f64() < speed);
+        match (
+            upper.checked_sub(1).and_then(|i| curve.get(i)),
+            curve.get(upper),
+        ) {
+            (None, Some((_, e))) | (Some((_, e)), None) => *e,
+            (Some((s0, e0)), Some((s1, e1))) => {
+                let t = (speed - s0.as_f64()) / (s1.as_f64() - s0.as_f64());
+                e0 + t * (e1 - e0)
+            }
+            (None, None) => 0.0,
+        }
+    }
+
+    /// the energy recovered on an edge, from the recovery predicted by the
+    /// energy model scaled by the efficiency at the edge speed, and capped by
+    /// the maximum regen power over the time spent on the edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed`        - speed of the traversal
+    /// * `distance`     - distance of the traversal
+    /// * `predicted`    - recovery predicted by the energy model, as a positive energy
+    /// * `energy_unit`  - unit of the predicted energy
+    ///
+    /// # Returns
+    ///
+    /// the recovered energy as a positive energy, in the unit of `predicted`
+    pub fn recovered_energy(
+        &self,
+        speed: (Speed, SpeedUnit),
+        distance: (Distance, DistanceUnit),
+        predicted: Energy,
+        energy_unit: &EnergyUnit,
+    ) -> Result<Energy, TraversalModelError> {
+        let recovered = Energy::new(predicted.as_f64() * self.efficiency(speed));
+        let max_power = match self.max_regen_power_kw {
+            Some(power) => power,
+            None => return Ok(recovered),
+        };
+        let ((speed, speed_unit), (distance, distance_unit)) = (speed, distance);
+        if speed.as_f64() <= 0.0 {
+            return Ok(recovered);
+        }
+        let hours = Time::create(
+            &speed,
+            &speed_unit,
+            &distance,
+            &distance_unit,
+            &TimeUnit::Hours,
+        )?;
+        let cap = EnergyUnit::KilowattHours
+            .convert(&Energy::new(max_power * hours.as_f64()), energy_unit);
+        Ok(if recovered > cap { cap } else { recovered })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regen() -> RegenBraking {
+        RegenBraking {
+            efficiency_curve: vec![
+                (Speed::new(5.0), 0.1),
+                (Speed::new(25.0), 0.7),
+                (Speed::new(60.0), 0.8),
+            ],
+            speed_unit: SpeedUnit::MilesPerHour,
+            max_regen_power_kw: Some(50.0),
+        }
+    }
+
+    #[test]
+    fn test_efficiency_curve() {
+        let regen = regen();
+        regen.validate().unwrap();
+        let at = |mph: f64| regen.efficiency((Speed::new(mph), SpeedUnit::MilesPerHour));
+        assert_eq!(at(0.0), 0.1);
+        assert!((at(15.0) - 0.4).abs() < 1e-9);
+        assert!((at(25.0) - 0.7).abs() < 1e-9);
+        assert_eq!(at(80.0), 0.8);
+    }
+
+    #[test]
+    fn test_recovery_depends_on_speed_and_is_capped() {
+        let regen = regen();
+        let recover = |mph: f64, kwh: f64| {
+            regen
+                .recovered_energy(
+                    (Speed::new(mph), SpeedUnit::MilesPerHour),
+                    (Distance::new(1.0), DistanceUnit::Miles),
+                    Energy::new(kwh),
+                    &EnergyUnit::KilowattHours,
+                )
+                .unwrap()
+                .as_f64()
+        };
+        // the same predicted recovery of 0.5 kWh over a mile descent
+        let slow = recover(10.0, 0.5);
+        let fast = recover(60.0, 0.5);
+        assert!((slow - 0.5 * 0.25).abs() < 1e-9, "{}", slow);
+        assert!((fast - 0.5 * 0.8).abs() < 1e-9, "{}", fast);
+        // a mile at 60 mph takes a minute, so 50 kW can recover at most 0.833 kWh
+        let capped = recover(60.0, 5.0);
+        assert!((capped - 50.0 / 60.0).abs() < 1e-3, "{}", capped);
+        assert!(capped < 5.0 * 0.8);
+    }
+
+    #[test]
+    fn test_invalid_curve() {
+        let unsorted = RegenBraking {
+            efficiency_curve: vec![(Speed::new(25.0), 0.7), (Speed::new(5.0), 0.1)],
+            ..regen()
+        };
+        assert!(unsorted.validate().is_err());
+        let over_one = RegenBraking {
+            efficiency_curve: vec![(Speed::new(25.0), 1.2)],
+            ..regen()
+        };
+        assert!(over_one.validate().is_err());
+    }
+}
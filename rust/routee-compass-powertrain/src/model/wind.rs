@@ -0,0 +1,156 @@
+use routee_compass_core::model::{
+    access::default::turn_delays::EdgeHeading,
+    network::EdgeId,
+    traversal::TraversalModelError,
+    unit::{AsF64, Speed, SpeedUnit},
+};
+use serde::{Deserialize, Serialize};
+
+/// a wind vector. the direction is the compass bearing, in degrees clockwise
+/// from north, that the wind blows from, following the meteorological convention.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Wind {
+    pub speed: f64,
+    pub direction: f64,
+}
+
+/// the wind over the road network, either one regional vector or one vector per edge
+pub enum WindField {
+    Regional(Wind),
+    PerEdge(Box<[Wind]>),
+}
+
+/// adjusts the energy of an edge traversal for the headwind or tailwind along the
+/// edge. the aerodynamic drag of the vehicle is proportional to the square of its
+/// speed relative to the air, so a headwind increases and a tailwind decreases
+/// the share of energy spent on drag. the remaining energy, spent on rolling
+/// resistance, is unchanged.
+pub struct WindModel {
+    pub wind: WindField,
+    pub speed_unit: SpeedUnit,
+    pub edge_headings: Box<[EdgeHeading]>,
+    /// drag coefficient times frontal area of the vehicle in square meters
+    pub drag_area: f64,
+    /// mass of the vehicle in kilograms, used for rolling resistance
+    pub mass_kg: f64,
+}
+
+impl WindModel {
+    /// drag area of a passenger car, in square meters
+    pub const DEFAULT_DRAG_AREA: f64 = 0.7;
+    /// mass of a passenger car, in kilograms
+    pub const DEFAULT_MASS_KG: f64 = 1800.0;
+    /// density of air at sea level in kilograms per cubic meter
+    const AIR_DENSITY: f64 = 1.225;
+    /// gravitational acceleration in meters per second squared
+    const GRAVITY: f64 = 9.81;
+    /// rolling resistance coefficient for a passenger tire on asphalt
+    const ROLLING_RESISTANCE: f64 = 0.008;
+
+    /// confirms that the wind field and heading tables cover the same edges
+    pub fn validate(&self) -> Result<(), TraversalModelError> {
+        if let WindField::PerEdge(winds) = &self.wind {
+            if winds.len() != self.edge_headings.len() {
+                return Err(TraversalModelError::BuildError(format!(
+                    "wind table has {} rows but the edge heading table has {} rows",
+                    winds.len(),
+                    self.edge_headings.len()
+                )));
+            }
+        }
+        if !(self.drag_area > 0.0 && self.mass_kg > 0.0) {
+            return Err(TraversalModelError::BuildError(String::from(
+                "wind model drag_area and mass_kg must be positive",
+            )));
+        }
+        Ok(())
+    }
+
+    /// the component of the wind against the direction of travel on an edge, in
+    /// meters per second. positive for a headwind, negative for a tailwind.
+    pub fn headwind(&self, edge_id: &EdgeId) -> Result<f64, TraversalModelError> {
+        let heading = self.edge_headings.get(edge_id.0).ok_or_else(|| {
+            TraversalModelError::TraversalModelFailure(format!(
+                "edge {} is missing from the wind model edge headings",
+                edge_id
+            ))
+        })?;
+        let wind = match &self.wind {
+            WindField::Regional(wind) => wind,
+            WindField::PerEdge(winds) => winds.get(edge_id.0).ok_or_else(|| {
+                TraversalModelError::TraversalModelFailure(format!(
+                    "edge {} is missing from the wind table",
+                    edge_id
+                ))
+            })?,
+        };
+        // average the start and end headings as unit vectors
+        let (sin, cos) = [heading.start_heading(), heading.end_heading()]
+            .iter()
+            .map(|h| (*h as f64).to_radians().sin_cos())
+            .fold((0.0, 0.0), |(s, c), (hs, hc)| (s + hs, c + hc));
+        let edge_heading = sin.atan2(cos);
+        let wind_speed = self
+            .speed_unit
+            .convert(&Speed::new(wind.speed), &SpeedUnit::MetersPerSecond)
+            .as_f64();
+        Ok(wind_speed * (wind.direction.to_radians() - edge_heading).cos())
+    }
+
+    /// the factor applied to the energy of traversing an edge at some speed
+    pub fn energy_factor(
+        &self,
+        edge_id: &EdgeId,
+        speed: (Speed, SpeedUnit),
+    ) -> Result<f64, TraversalModelError> {
+        let (speed, speed_unit) = speed;
+        let ground_speed = speed_unit
+            .convert(&speed, &SpeedUnit::MetersPerSecond)
+            .as_f64();
+        if ground_speed <= 0.0 {
+            return Ok(1.0);
+        }
+        let air_speed = ground_speed + self.headwind(edge_id)?;
+
+        // share of the still-air energy spent on drag on flat ground
+        let drag_force = 0.5 * WindModel::AIR_DENSITY * self.drag_area * ground_speed.powi(2);
+        let rolling_force = WindModel::ROLLING_RESISTANCE * self.mass_kg * WindModel::GRAVITY;
+        let drag_share = drag_force / (drag_force + rolling_force);
+
+        // a tailwind faster than the vehicle pushes it along
+        let drag_ratio = air_speed * air_speed.abs() / ground_speed.powi(2);
+        Ok((1.0 + drag_share * (drag_ratio - 1.0)).max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wind_model(direction: f64) -> WindModel {
+        WindModel {
+            wind: WindField::Regional(Wind {
+                speed: 10.0,
+                direction,
+            }),
+            speed_unit: SpeedUnit::MetersPerSecond,
+            edge_headings: vec![EdgeHeading::new(90, 90)].into_boxed_slice(),
+            drag_area: WindModel::DEFAULT_DRAG_AREA,
+            mass_kg: WindModel::DEFAULT_MASS_KG,
+        }
+    }
+
+    #[test]
+    fn test_energy_factor() {
+        // an edge heading east at 30 m/s
+        let speed = (Speed::new(30.0), SpeedUnit::MetersPerSecond);
+        let factor = |direction: f64| wind_model(direction).energy_factor(&EdgeId(0), speed);
+        let tailwind = factor(270.0).unwrap();
+        let headwind = factor(90.0).unwrap();
+        let crosswind = factor(0.0).unwrap();
+        assert!(tailwind < 1.0, "tailwind factor {} should be < 1", tailwind);
+        assert!(headwind > 1.0, "headwind factor {} should be > 1", headwind);
+        assert!((crosswind - 1.0).abs() < 1e-9);
+        assert!(wind_model(0.0).headwind(&EdgeId(1)).is_err());
+    }
+}
@@ -8,6 +8,7 @@ use routee_compass_core::{
         unit::{AsF64, DistanceUnit},
     },
 };
+use std::collections::HashMap;
 use std::str::FromStr;
 
 /// Defines the interface for exposing the application via a set of language bindings using
@@ -131,20 +132,7 @@ pub trait CompassAppBindings {
         edge_id: usize,
         distance_unit: Option<String>,
     ) -> Result<f64, CompassAppError> {
-        let du_internal: Option<DistanceUnit> = match distance_unit {
-            Some(du_str) => {
-                let du = DistanceUnit::from_str(du_str.as_str()).map_err(|_| {
-                    CompassAppError::InternalError(format!(
-                        "could not deserialize distance unit '{}'",
-                        du_str
-                    ))
-                })?;
-
-                Some(du)
-            }
-
-            None => None,
-        };
+        let du_internal = parse_distance_unit(distance_unit)?;
         let edge_id_internal = EdgeId(edge_id);
         self.app()
             .search_app
@@ -152,6 +140,32 @@ pub trait CompassAppBindings {
             .map(|o| o.as_f64())
     }
 
+    /// Get the distances of many edges, looking up the graph once rather than
+    /// once per edge
+    ///
+    /// # Arguments
+    /// * `edge_ids` - the ids of the edges
+    /// * `distance_unit` - the distance unit to use. If not provided, the default distance unit is meters
+    ///
+    /// # Returns
+    /// * the distance of each edge in the specified distance unit, in the order given
+    fn graph_edge_distances(
+        &self,
+        edge_ids: Vec<usize>,
+        distance_unit: Option<String>,
+    ) -> Result<Vec<f64>, CompassAppError> {
+        let du_internal = parse_distance_unit(distance_unit)?;
+        let search_app = &self.app().search_app;
+        edge_ids
+            .into_iter()
+            .map(|edge_id| {
+                search_app
+                    .get_edge_distance(&EdgeId(edge_id), du_internal)
+                    .map(|d| d.as_f64())
+            })
+            .collect()
+    }
+
     /// Get the ids of the edges incident to a vertex in the forward direction
     ///
     /// # Arguments
@@ -187,6 +201,113 @@ pub trait CompassAppBindings {
             .collect_vec()
     }
 
+    /// Get the ids of the edges incident to many vertices in the forward direction,
+    /// looking up the graph once rather than once per vertex
+    ///
+    /// # Arguments
+    /// * `vertex_ids` - the ids of the vertices
+    ///
+    /// # Returns
+    /// * the ids of the edges incident to each vertex in the forward direction, in the order given
+    fn graph_get_out_edge_ids_batch(&self, vertex_ids: Vec<usize>) -> Vec<Vec<usize>> {
+        let search_app = &self.app().search_app;
+        vertex_ids
+            .into_iter()
+            .map(|vertex_id| {
+                search_app
+                    .get_incident_edge_ids(&VertexId(vertex_id), &Direction::Forward)
+                    .into_iter()
+                    .map(|e| e.0)
+                    .collect_vec()
+            })
+            .collect_vec()
+    }
+
+    /// Get the ids of the edges incident to many vertices in the reverse direction,
+    /// looking up the graph once rather than once per vertex
+    ///
+    /// # Arguments
+    /// * `vertex_ids` - the ids of the vertices
+    ///
+    /// # Returns
+    /// * the ids of the edges incident to each vertex in the reverse direction, in the order given
+    fn graph_get_in_edge_ids_batch(&self, vertex_ids: Vec<usize>) -> Vec<Vec<usize>> {
+        let search_app = &self.app().search_app;
+        vertex_ids
+            .into_iter()
+            .map(|vertex_id| {
+                search_app
+                    .get_incident_edge_ids(&VertexId(vertex_id), &Direction::Reverse)
+                    .into_iter()
+                    .map(|e| e.0)
+                    .collect_vec()
+            })
+            .collect_vec()
+    }
+
+    /// Get the ordered sequence of vertices visited by a route
+    ///
+    /// # Arguments
+    /// * `edge_ids` - the ids of the edges of the route, in order of traversal
+    ///
+    /// # Returns
+    /// * the ids of the vertices visited by the route, beginning at the origin
+    ///   of the first edge and ending at the destination of the last edge
+    fn graph_vertex_sequence(&self, edge_ids: Vec<usize>) -> Result<Vec<usize>, CompassAppError> {
+        let edge_ids_internal = edge_ids.into_iter().map(EdgeId).collect_vec();
+        self.app()
+            .search_app
+            .get_vertex_sequence(&edge_ids_internal)
+            .map(|vs| vs.into_iter().map(|v| v.0).collect_vec())
+    }
+
+    /// Get the coordinate of a vertex
+    ///
+    /// # Arguments
+    /// * `vertex_id` - the id of the vertex
+    ///
+    /// # Returns
+    /// * the `(x, y)` coordinate of the vertex, which is its longitude and latitude
+    fn graph_vertex_coordinate(&self, vertex_id: usize) -> Result<(f64, f64), CompassAppError> {
+        self.app()
+            .search_app
+            .get_vertex_coordinate(&VertexId(vertex_id))
+    }
+
+    /// Get the coordinates of many vertices, such as the vertices of a route,
+    /// looking up the graph once rather than once per vertex
+    ///
+    /// # Arguments
+    /// * `vertex_ids` - the ids of the vertices
+    ///
+    /// # Returns
+    /// * the `(x, y)` coordinate of each vertex, in the order given
+    fn graph_vertex_coordinates(
+        &self,
+        vertex_ids: Vec<usize>,
+    ) -> Result<Vec<(f64, f64)>, CompassAppError> {
+        let search_app = &self.app().search_app;
+        vertex_ids
+            .into_iter()
+            .map(|vertex_id| search_app.get_vertex_coordinate(&VertexId(vertex_id)))
+            .collect()
+    }
+
+    /// Get the fingerprints of the loaded graph and configuration, which are also
+    /// included in each response as `fingerprint`
+    ///
+    /// # Returns
+    /// * a map with the `graph` and `config` fingerprints
+    fn fingerprint(&self) -> HashMap<String, String> {
+        match &self.app().search_app.fingerprint {
+            None => HashMap::new(),
+            Some(fingerprint) => HashMap::from([
+                (String::from("graph"), fingerprint.graph.clone()),
+                (String::from("config"), fingerprint.config.clone()),
+            ]),
+        }
+    }
+
     /// Runs a set of queries and returns the results
     ///
     /// # Arguments
@@ -217,4 +338,221 @@ pub trait CompassAppBindings {
         let string_results: Vec<String> = results.iter().map(|r| r.to_string()).collect();
         Ok(string_results)
     }
+
+    /// Runs a set of queries and returns the results keyed by query id
+    ///
+    /// # Arguments
+    /// * `queries` - a list of queries to run as json strings, each with a `query_id`
+    ///
+    /// # Returns
+    /// * a json string of an object mapping each query id to its result
+    fn run_queries_keyed(
+        &self,
+        queries: Vec<String>,
+        config: Option<String>,
+    ) -> Result<String, CompassAppError> {
+        let config_inner: Option<serde_json::Value> = match config {
+            Some(c) => {
+                let c_serde: serde_json::Value = serde_json::from_str(&c)?;
+                Some(c_serde)
+            }
+            None => None,
+        };
+
+        let mut json_queries = queries
+            .iter()
+            .map(|q| serde_json::from_str(q))
+            .collect::<Result<Vec<serde_json::Value>, serde_json::Error>>()?;
+
+        let results = self
+            .app()
+            .run_keyed(&mut json_queries, config_inner.as_ref())?;
+        Ok(results.to_string())
+    }
+
+    /// Runs a set of queries and returns the results along with the number of
+    /// routes that traverse each edge across the batch
+    ///
+    /// # Arguments
+    /// * `queries` - a list of queries to run as json strings
+    ///
+    /// # Returns
+    /// * a list of json strings containing the results of the queries, and the
+    ///   route count of each edge traversed by at least one route
+    fn run_queries_with_edge_usage(
+        &self,
+        queries: Vec<String>,
+        config: Option<String>,
+    ) -> Result<(Vec<String>, HashMap<usize, u64>), CompassAppError> {
+        let config_inner: Option<serde_json::Value> = match config {
+            Some(c) => {
+                let c_serde: serde_json::Value = serde_json::from_str(&c)?;
+                Some(c_serde)
+            }
+            None => None,
+        };
+
+        let mut json_queries = queries
+            .iter()
+            .map(|q| serde_json::from_str(q))
+            .collect::<Result<Vec<serde_json::Value>, serde_json::Error>>()?;
+
+        let (results, edge_usage) = self
+            .app()
+            .run_with_edge_usage(&mut json_queries, config_inner.as_ref())?;
+
+        let string_results: Vec<String> = results.iter().map(|r| r.to_string()).collect();
+        let counts = edge_usage
+            .counts()
+            .into_iter()
+            .map(|(edge_id, count)| (edge_id.0, count))
+            .collect();
+        Ok((string_results, counts))
+    }
+
+    /// Approximates the betweenness of each edge by routing between a random sample
+    /// of origin and destination vertices
+    ///
+    /// # Arguments
+    /// * `samples` - the number of origin-destination pairs to route between
+    /// * `seed` - optional random seed, so that the sample can be repeated
+    ///
+    /// # Returns
+    /// * the share of the sampled routes that traverse each edge, for each edge
+    ///   traversed by at least one route
+    fn run_edge_importance(
+        &self,
+        samples: usize,
+        seed: Option<u64>,
+        config: Option<String>,
+    ) -> Result<HashMap<usize, f64>, CompassAppError> {
+        let config_inner: Option<serde_json::Value> = match config {
+            Some(c) => {
+                let c_serde: serde_json::Value = serde_json::from_str(&c)?;
+                Some(c_serde)
+            }
+            None => None,
+        };
+        let importance = self
+            .app()
+            .run_edge_importance(samples, seed, config_inner.as_ref())?;
+        let scores = importance
+            .scores()
+            .iter()
+            .map(|(edge_id, score)| (edge_id.0, *score))
+            .collect();
+        Ok(scores)
+    }
+
+    /// Runs a query once for each of a number of samples of its edge travel times
+    ///
+    /// # Arguments
+    /// * `query` - the query to sample, as a json string
+    /// * `samples` - the number of samples to run
+    ///
+    /// # Returns
+    /// * the distribution of the trip time across the samples, as a json string
+    fn run_eta_distribution(
+        &self,
+        query: String,
+        samples: usize,
+    ) -> Result<String, CompassAppError> {
+        let json_query: serde_json::Value = serde_json::from_str(&query)?;
+        let eta = self.app().run_eta_distribution(&json_query, samples)?;
+        let result = serde_json::to_string(&eta)?;
+        Ok(result)
+    }
+
+    /// Computes the network cost from each origin vertex to each destination vertex
+    ///
+    /// # Arguments
+    /// * `origins` - the vertex ids to search from
+    /// * `destinations` - the vertex ids to search to
+    /// * `query` - optional query used to build the models, as a json string
+    ///
+    /// # Returns
+    /// * for each origin, the cost to each destination in the order given, or None
+    ///   where the destination is not reachable
+    fn run_matrix(
+        &self,
+        origins: Vec<usize>,
+        destinations: Vec<usize>,
+        query: Option<String>,
+    ) -> Result<Vec<Vec<Option<f64>>>, CompassAppError> {
+        let json_query: serde_json::Value = match query {
+            Some(q) => serde_json::from_str(&q)?,
+            None => serde_json::json!({}),
+        };
+        let matrix = self.app().run_matrix(
+            origins.into_iter().map(VertexId).collect(),
+            destinations.into_iter().map(VertexId).collect(),
+            &json_query,
+        )?;
+        let result = matrix
+            .into_iter()
+            .map(|row| row.into_iter().map(|c| c.map(|c| c.as_f64())).collect())
+            .collect();
+        Ok(result)
+    }
+
+    /// Runs a set of queries and returns only the cost of each route, skipping
+    /// the output plugins
+    ///
+    /// # Arguments
+    /// * `queries` - a list of queries to run as json strings
+    /// * `state_features` - names of state features to report along with each cost
+    ///
+    /// # Returns
+    /// * a json string of the list of costs, or cost objects when state features are listed
+    fn run_queries_cost_only(
+        &self,
+        queries: Vec<String>,
+        state_features: Vec<String>,
+    ) -> Result<String, CompassAppError> {
+        let json_queries = queries
+            .iter()
+            .map(|q| serde_json::from_str(q))
+            .collect::<Result<Vec<serde_json::Value>, serde_json::Error>>()?;
+
+        let results = self.app().run_cost_only(&json_queries, &state_features)?;
+        Ok(serde_json::Value::Array(results).to_string())
+    }
+
+    /// Runs a set of queries and returns the results in the order of the queries.
+    /// Used to stream results of a larger batch one chunk at a time.
+    ///
+    /// # Arguments
+    /// * `queries` - a list of queries to run as json strings
+    ///
+    /// # Returns
+    /// * a list of json strings containing the results of the queries, in query order
+    fn run_queries_in_order(&self, queries: Vec<String>) -> Result<Vec<String>, CompassAppError> {
+        let json_queries = queries
+            .iter()
+            .map(|q| serde_json::from_str(q))
+            .collect::<Result<Vec<serde_json::Value>, serde_json::Error>>()?;
+
+        let results = self.app().run_in_order(&json_queries)?;
+
+        let string_results: Vec<String> = results.iter().map(|r| r.to_string()).collect();
+        Ok(string_results)
+    }
+}
+
+/// parses an optional distance unit name passed through the bindings
+fn parse_distance_unit(
+    distance_unit: Option<String>,
+) -> Result<Option<DistanceUnit>, CompassAppError> {
+    match distance_unit {
+        Some(du_str) => {
+            let du = DistanceUnit::from_str(du_str.as_str()).map_err(|_| {
+                CompassAppError::InternalError(format!(
+                    "could not deserialize distance unit '{}'",
+                    du_str
+                ))
+            })?;
+            Ok(Some(du))
+        }
+        None => Ok(None),
+    }
 }
@@ -1,6 +1,9 @@
 use crate::app::compass::{CompassAppError, CompassConfigurationError};
 use clap::Parser;
 
+/// number of newline-delimited queries read into memory at a time if no chunksize is set
+pub const DEFAULT_CHUNKSIZE: usize = 10000;
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct CliArgs {
@@ -12,7 +15,8 @@ pub struct CliArgs {
     #[arg(short, long, value_name = "*.json")]
     pub query_file: String,
 
-    /// Size of batches to load into memory at a time
+    /// Size of batches to load into memory at a time. Requires newline-delimited
+    /// queries, which are read in batches of 10000 queries if not set
     #[arg(long)]
     pub chunksize: Option<i64>,
 
@@ -26,7 +30,7 @@ impl CliArgs {
         match (self.chunksize, self.newline_delimited) {
             (Some(_), false) => Err(CompassAppError::CompassConfigurationError(
                 CompassConfigurationError::UserConfigurationError(String::from(
-                    "chunksize must be set if newline_delimited_queries is true",
+                    "newline_delimited must be set if chunksize is set, as only newline-delimited query files can be read in chunks",
                 )),
             )),
             (Some(chunksize), _) if chunksize < 1 => {
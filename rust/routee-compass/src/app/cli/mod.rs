@@ -1,2 +1,3 @@
 pub mod cli_args;
+pub mod query_chunks;
 pub mod run;
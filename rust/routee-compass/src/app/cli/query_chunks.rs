@@ -0,0 +1,104 @@
+use crate::app::compass::{CompassAppError, CompassConfigurationError};
+use serde_json::Value;
+use std::io::{BufRead, Lines};
+
+/// lazily reads a newline-delimited JSON file of queries in chunks. only the rows
+/// of the current chunk are read from the file, so the peak memory of a run is
+/// bounded by the chunk size rather than the size of the file. blank rows are
+/// skipped, and each row that is not valid JSON is returned as an error in
+/// place of its query.
+pub struct QueryChunks<R: BufRead> {
+    lines: Lines<R>,
+    chunksize: usize,
+}
+
+impl<R: BufRead> QueryChunks<R> {
+    pub fn new(reader: R, chunksize: usize) -> QueryChunks<R> {
+        QueryChunks {
+            lines: reader.lines(),
+            chunksize: chunksize.max(1),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for QueryChunks<R> {
+    type Item = Vec<Result<Value, CompassAppError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = Vec::with_capacity(self.chunksize);
+        for row in self.lines.by_ref() {
+            let query = match row {
+                Ok(string) if string.trim().is_empty() => continue,
+                Ok(string) => serde_json::from_str(&string).map_err(|e| {
+                    CompassAppError::CompassConfigurationError(
+                        CompassConfigurationError::SerdeDeserializationError(e),
+                    )
+                }),
+                Err(e) => Err(CompassAppError::CompassFailure(format!(
+                    "failed to parse query row due to: {}",
+                    e
+                ))),
+            };
+            chunk.push(query);
+            if chunk.len() == self.chunksize {
+                break;
+            }
+        }
+        if chunk.is_empty() {
+            None
+        } else {
+            Some(chunk)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::io::{BufReader, Read};
+    use std::rc::Rc;
+
+    /// a reader that counts the bytes read from it
+    struct CountingReader<R> {
+        inner: R,
+        count: Rc<Cell<usize>>,
+    }
+
+    impl<R: Read> Read for CountingReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.inner.read(buf)?;
+            self.count.set(self.count.get() + n);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_query_chunks_read_lazily() {
+        let n_queries = 10000;
+        let mut contents = (0..n_queries)
+            .map(|i| format!("{{\"query_id\": {}, \"origin_vertex\": 0}}", i))
+            .collect::<Vec<_>>();
+        contents.insert(3, String::from("not json"));
+        contents.insert(4, String::new());
+        let contents = contents.join("\n");
+
+        let count = Rc::new(Cell::new(0));
+        let reader = CountingReader {
+            inner: contents.as_bytes(),
+            count: count.clone(),
+        };
+        let mut chunks = QueryChunks::new(BufReader::new(reader), 100);
+
+        // the first chunk only reads the beginning of the file
+        let first = chunks.next().unwrap();
+        assert_eq!(first.len(), 100);
+        assert!(first[3].is_err());
+        assert_eq!(first[4].as_ref().unwrap()["query_id"], 3);
+        assert!(count.get() < contents.len() / 10);
+
+        let sizes = chunks.map(|c| c.len()).collect::<Vec<_>>();
+        assert!(sizes.iter().all(|s| *s <= 100));
+        assert_eq!(100 + sizes.iter().sum::<usize>(), n_queries + 1);
+    }
+}
@@ -1,6 +1,6 @@
-use super::cli_args::CliArgs;
+use super::cli_args::{CliArgs, DEFAULT_CHUNKSIZE};
+use super::query_chunks::QueryChunks;
 use crate::app::compass::compass_app_ops as ops;
-use crate::app::compass::CompassConfigurationError;
 use crate::app::compass::{
     compass_app::CompassApp, compass_json_extensions::CompassJsonExtensions, CompassAppBuilder,
     CompassAppError,
@@ -48,16 +48,19 @@ pub fn command_line_runner(
 
     // execute queries on app
     match (args.chunksize, args.newline_delimited) {
-        (None, true) => Err(CompassAppError::InternalError(String::from(
-            "invalid argument combination should have been caught during CLI validation",
-        ))),
-        (None, false) => run_json(&query_file, &compass_app, run_config),
-        (Some(_), true) => {
-            let chunksize = args.get_chunksize_option()?;
-            run_newline_json(&query_file, chunksize, &compass_app, run_config)
+        (_, true) => {
+            let chunksize = args.get_chunksize_option()?.unwrap_or(DEFAULT_CHUNKSIZE);
+            run_newline_json(
+                BufReader::new(query_file),
+                chunksize,
+                &compass_app,
+                run_config,
+                &mut |result| log_error(result),
+            )
         }
+        (None, false) => run_json(&query_file, &compass_app, run_config),
         (Some(_), false) => Err(CompassAppError::InternalError(String::from(
-            "not yet implemented",
+            "invalid argument combination should have been caught during CLI validation",
         ))),
     }
 }
@@ -79,41 +82,38 @@ fn run_json(
     Ok(())
 }
 
-/// parses a file as newline-delimited JSON which can be optionally chunked into sub-batches
-/// and each sub-batch run as queries against the CompassApp.run command.
-/// chunksize should be >> the configured CompassApp parallelism (from TOML file) for best
-/// performance.
-fn run_newline_json(
-    query_file: &File,
-    chunksize_option: Option<usize>,
+/// reads a newline-delimited JSON file in chunks of `chunksize` queries and runs each
+/// chunk as queries against the CompassApp.run command. each chunk and its responses are
+/// released before the next chunk is read, so the peak memory of the run is bounded by
+/// the chunk size. chunksize should be >> the configured CompassApp parallelism (from
+/// TOML file) for best performance.
+///
+/// # Arguments
+///
+/// * `reader` - reader over the newline-delimited JSON queries
+/// * `chunksize` - maximum number of queries read into memory at a time
+/// * `compass_app` - app used to run the queries
+/// * `run_config` - optional CompassApp configuration overrides
+/// * `on_response` - called with each response after its chunk has run, and with an
+///   error response for each row that could not be parsed
+pub fn run_newline_json<R: BufRead>(
+    reader: R,
+    chunksize: usize,
     compass_app: &CompassApp,
     run_config: Option<&Value>,
+    on_response: &mut dyn FnMut(&Value),
 ) -> Result<(), CompassAppError> {
-    let reader = BufReader::new(query_file);
-    let iterator = reader.lines();
-    let chunksize = chunksize_option.unwrap_or(usize::MAX);
-    let chunks = iterator.chunks(chunksize);
-
-    for (iteration, chunk) in chunks.into_iter().enumerate() {
+    for (iteration, chunk) in QueryChunks::new(reader, chunksize).enumerate() {
         debug!("executing batch {}", iteration + 1);
-        // parse JSON output
         let (mut chunk_queries, errors): (Vec<Value>, Vec<CompassAppError>) =
-            chunk.partition_map(|row| match row {
-                Ok(string) => match serde_json::from_str(&string) {
-                    Ok(query) => Either::Left(query),
-                    Err(e) => Either::Right(CompassAppError::CompassConfigurationError(
-                        CompassConfigurationError::SerdeDeserializationError(e),
-                    )),
-                },
-                Err(e) => Either::Right(CompassAppError::CompassFailure(format!(
-                    "failed to parse query row due to: {}",
-                    e
-                ))),
+            chunk.into_iter().partition_map(|row| match row {
+                Ok(query) => Either::Left(query),
+                Err(e) => Either::Right(e),
             });
 
         // run Compass on this chunk of queries
         for result in compass_app.run(&mut chunk_queries, run_config)?.iter() {
-            log_error(result)
+            on_response(result)
         }
 
         // report JSON parsing errors
@@ -122,7 +122,7 @@ fn run_newline_json(
                 "request": "failed to parse",
                 "error": error.to_string()
             });
-            log_error(&error_json)
+            on_response(&error_json)
         }
     }
 
@@ -135,3 +135,62 @@ fn log_error(result: &Value) {
         error!("Error: {}", error_string);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::compass::CompassConfigurationError;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_newline_json_chunks_match_whole_file() {
+        let test_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("app")
+            .join("compass")
+            .join("test")
+            .join("speeds_test");
+        let app = match CompassApp::try_from(test_dir.join("speeds_test.toml").as_path()) {
+            Ok(a) => Ok(a),
+            Err(CompassAppError::CompassConfigurationError(
+                CompassConfigurationError::FileNormalizationNotFound(_key, _f1, _f2),
+            )) => CompassApp::try_from(test_dir.join("speeds_debug.toml").as_path()),
+            Err(other) => panic!("{}", other),
+        }
+        .unwrap();
+
+        // vertex 2 has no out edges, so queries from it fail their search
+        let od_pairs = [(0, 1), (0, 2), (1, 2), (2, 0)];
+        let mut queries = (0..200)
+            .map(|i| {
+                let (o, d) = od_pairs[i % od_pairs.len()];
+                json!({"query_id": i, "origin_vertex": o, "destination_vertex": d})
+            })
+            .collect::<Vec<_>>();
+        let contents = queries
+            .iter()
+            .map(|q| q.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let whole = app.run(&mut queries, None).unwrap();
+
+        let mut chunked = vec![];
+        run_newline_json(contents.as_bytes(), 7, &app, None, &mut |r| {
+            chunked.push(r.clone())
+        })
+        .unwrap();
+
+        assert_eq!(chunked.len(), whole.len());
+        for result in chunked.iter() {
+            let id = &result["request"]["query_id"];
+            let expected = whole
+                .iter()
+                .find(|r| &r["request"]["query_id"] == id)
+                .expect("whole-file run should have a result for each query");
+            for key in ["path", "traversal_summary", "cost"] {
+                assert_eq!(result["route"][key], expected["route"][key]);
+            }
+            assert_eq!(result.get("error"), expected.get("error"));
+        }
+    }
+}
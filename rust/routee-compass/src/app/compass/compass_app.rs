@@ -1,24 +1,26 @@
 use super::compass_app_configuration::CompassAppConfiguration;
+use super::compass_app_fingerprint::CompassAppFingerprint;
+use super::compass_models::CompassModels;
+use super::duplicate_query_id_policy::{self as query_id, DuplicateQueryIdPolicy};
+use super::edge_importance::{self, EdgeImportance};
+use super::edge_usage::EdgeUsage;
+use super::eta_distribution::EtaDistribution;
+use super::poi_cost_cache::PoiCostCache;
 use super::response::response_output_policy::ResponseOutputPolicy;
 use super::response::response_sink::ResponseSink;
 use super::{compass_app_ops as ops, CompassAppBuilder};
 use crate::app::compass::response::response_persistence_policy::ResponsePersistencePolicy;
-use crate::app::compass::{CompassConfigurationField, ConfigJsonExtensions};
+use crate::app::compass::{
+    CompassConfigurationError, CompassConfigurationField, ConfigJsonExtensions,
+};
 use crate::{
     app::{
-        compass::{
-            compass_input_field::CompassInputField,
-            model::{
-                cost_model::cost_model_builder::CostModelBuilder,
-                termination_model_builder::TerminationModelBuilder,
-            },
-            CompassAppError,
-        },
-        search::{SearchApp, SearchAppResult},
+        compass::{compass_input_field::CompassInputField, CompassAppError},
+        search::{search_app_ops, SearchApp, SearchAppResult},
     },
     plugin::{
         input::{input_plugin_ops as in_ops, InputPlugin},
-        output::{output_plugin_ops as out_ops, OutputPlugin},
+        output::{output_plugin_ops as out_ops, NamedOutputPlugin},
     },
 };
 use chrono::{Duration, Local};
@@ -26,12 +28,15 @@ use config::Config;
 use itertools::{Either, Itertools};
 use kdam::{Bar, BarExt};
 use rayon::{current_num_threads, prelude::*};
-use routee_compass_core::algorithm::search::{SearchAlgorithm, SearchInstance};
+use routee_compass_core::algorithm::search::a_star::{Landmarks, LandmarksConfig};
+use routee_compass_core::algorithm::search::{SearchAlgorithm, SearchError, SearchInstance};
 use routee_compass_core::model::map::{MapModel, MapModelConfig};
-use routee_compass_core::model::network::Graph;
-use routee_compass_core::model::state::StateModel;
+use routee_compass_core::model::network::{EdgeId, Graph, VertexId};
+use routee_compass_core::model::traversal::default::SEED;
+use routee_compass_core::model::unit::Cost;
 use routee_compass_core::util::duration_extension::DurationExtension;
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use std::{
     path::{Path, PathBuf},
@@ -49,8 +54,11 @@ use std::{
 pub struct CompassApp {
     pub search_app: Arc<SearchApp>,
     pub input_plugins: Vec<Arc<dyn InputPlugin>>,
-    pub output_plugins: Vec<Arc<dyn OutputPlugin>>,
+    pub output_plugins: Vec<NamedOutputPlugin>,
     pub configuration: CompassAppConfiguration,
+    pub poi_cost_cache: Option<PoiCostCache>,
+    /// the configuration this app was loaded with, as JSON, before file paths are made absolute
+    pub config: serde_json::Value,
 }
 
 impl CompassApp {
@@ -131,69 +139,19 @@ impl TryFrom<(&Config, &CompassAppBuilder)> for CompassApp {
         let root_config_path =
             config.get::<PathBuf>(CompassInputField::ConfigInputFile.to_str())?;
 
-        let config_json = config
-            .clone()
-            .try_deserialize::<serde_json::Value>()?
-            .normalize_file_paths(&"", &root_config_path)?;
+        let raw_config_json = config.clone().try_deserialize::<serde_json::Value>()?;
+        let config_json = raw_config_json.normalize_file_paths(&"", &root_config_path)?;
 
         let search_algorithm: SearchAlgorithm =
             config_json.get_config_serde(&CompassConfigurationField::Algorithm, &"TOML")?;
+        let search_algorithms: HashMap<String, SearchAlgorithm> = config_json
+            .get_config_serde_optional(&CompassConfigurationField::SearchAlgorithms, &"TOML")?
+            .unwrap_or_default();
+        let landmarks_config: Option<LandmarksConfig> = config_json
+            .get_config_serde_optional(&CompassConfigurationField::Landmarks, &"TOML")?;
 
-        let state_model = match config_json.get(CompassConfigurationField::State.to_string()) {
-            Some(state_config) => Arc::new(StateModel::try_from(state_config)?),
-            None => Arc::new(StateModel::empty()),
-        };
-
-        // build traversal model
-        let traversal_start = Local::now();
-        let traversal_params =
-            config_json.get_config_section(CompassConfigurationField::Traversal, &"TOML")?;
-        let traversal_model_service = builder.build_traversal_model_service(&traversal_params)?;
-        let traversal_duration = (Local::now() - traversal_start)
-            .to_std()
-            .map_err(|e| CompassAppError::InternalError(e.to_string()))?;
-        log::info!(
-            "finished reading traversal model with duration {}",
-            traversal_duration.hhmmss()
-        );
-
-        // build access model
-        let access_start = Local::now();
-        let access_params =
-            config_json.get_config_section(CompassConfigurationField::Access, &"TOML")?;
-        let access_model_service = builder.build_access_model_service(&access_params)?;
-        let access_duration = (Local::now() - access_start)
-            .to_std()
-            .map_err(|e| CompassAppError::InternalError(e.to_string()))?;
-        log::info!(
-            "finished reading access model with duration {}",
-            access_duration.hhmmss()
-        );
-
-        // build utility model
-        let cost_params =
-            config_json.get_config_section(CompassConfigurationField::Cost, &"TOML")?;
-        let cost_model_service = CostModelBuilder {}.build(&cost_params)?;
-
-        // build frontier model
-        let frontier_start = Local::now();
-        let frontier_params =
-            config_json.get_config_section(CompassConfigurationField::Frontier, &"TOML")?;
-
-        let frontier_model_service = builder.build_frontier_model_service(&frontier_params)?;
-
-        let frontier_duration = (Local::now() - frontier_start)
-            .to_std()
-            .map_err(|e| CompassAppError::InternalError(e.to_string()))?;
-        log::info!(
-            "finished reading frontier model with duration {}",
-            frontier_duration.hhmmss()
-        );
-
-        // build termination model
-        let termination_model_json =
-            config_json.get_config_section(CompassConfigurationField::Termination, &"TOML")?;
-        let termination_model = TerminationModelBuilder::build(&termination_model_json, None)?;
+        // build the models, which do not depend on the graph
+        let models = CompassModels::build(&config_json, builder)?;
 
         // build graph
         let graph_start = Local::now();
@@ -223,6 +181,10 @@ impl TryFrom<(&Config, &CompassAppBuilder)> for CompassApp {
             "finished loading map model with duration {}",
             map_dur.hhmmss()
         );
+        log::info!(
+            "edge geometry size: {} GB",
+            map_model.geometry_model.allocated_bytes() as f64 / 1e9
+        );
 
         #[cfg(debug_assertions)]
         {
@@ -251,18 +213,105 @@ impl TryFrom<(&Config, &CompassAppBuilder)> for CompassApp {
             output_file.write_all(output.as_bytes()).unwrap();
         }
 
+        let fingerprint = CompassAppFingerprint::new(&graph, &raw_config_json);
+        log::info!(
+            "graph fingerprint: {}, config fingerprint: {}",
+            fingerprint.graph,
+            fingerprint.config
+        );
+
         // build search app
-        let search_app = Arc::new(SearchApp::new(
+        let search_app = SearchApp::new(
             search_algorithm,
             graph,
             map_model,
-            state_model,
-            traversal_model_service,
-            access_model_service,
-            cost_model_service,
-            frontier_model_service,
-            termination_model,
-        ));
+            models.state_model,
+            models.traversal_model_service,
+            models.access_model_service,
+            models.cost_model_service,
+            models.frontier_model_service,
+            models.termination_model,
+        )
+        .with_search_algorithms(search_algorithms)
+        .with_fingerprint(fingerprint);
+
+        // precompute the landmark cost tables for the A* heuristic, if configured.
+        // the tables use the models of an empty query and are shared by the queries
+        // with the same cost configuration. they are skipped when those models would
+        // not admit the bound, such as with a cost expression or turn delays.
+        let search_app = match landmarks_config {
+            None => search_app,
+            Some(landmarks_config) => {
+                let landmarks_start = Local::now();
+                let query = serde_json::json!({});
+                let si = search_app.build_search_instance(&query).map_err(|e| {
+                    CompassAppError::BuildFailure(format!("failed to build landmarks: {}", e))
+                })?;
+                if !si.cost_model.is_additive() || si.access_model.adds_costs() {
+                    log::warn!(
+                        "landmarks are not used with a non-additive cost model or access costs, skipping"
+                    );
+                    search_app
+                } else {
+                    let landmarks = search_app_ops::get_cost_fingerprint(&query, &si.cost_model)
+                        .and_then(|fingerprint| {
+                            Landmarks::build(&landmarks_config, &si, fingerprint)
+                        })
+                        .map_err(|e| {
+                            CompassAppError::BuildFailure(format!(
+                                "failed to build landmarks: {}",
+                                e
+                            ))
+                        })?;
+                    let landmarks_duration = to_std(Local::now() - landmarks_start)?;
+                    log::info!(
+                        "finished building {} landmarks with duration {}",
+                        landmarks.vertex_ids().len(),
+                        landmarks_duration.hhmmss()
+                    );
+                    search_app.with_landmarks(landmarks)
+                }
+            }
+        };
+        let search_app = Arc::new(search_app);
+
+        // a configured cost expression may only reference known state features. these
+        // depend on the models built for a query, so check the models of an empty query.
+        if search_app.cost_model_service.expression.is_some() {
+            search_app
+                .build_search_instance(&serde_json::json!({}))
+                .map_err(|e| {
+                    CompassAppError::BuildFailure(format!(
+                        "failed to validate cost expression: {}",
+                        e
+                    ))
+                })?;
+        }
+
+        // confirm the state model is self-consistent. models that read query parameters
+        // when they are built cannot be checked until a query arrives.
+        match search_app.validate_state_model(&serde_json::json!({})) {
+            Ok(features) => log::info!(
+                "state model features: [{}]",
+                features
+                    .iter()
+                    .map(|(name, source)| format!("{} ({})", name, source))
+                    .join(", ")
+            ),
+            Err(
+                e @ (SearchError::TraversalModelFailure { .. }
+                | SearchError::AccessModelFailure { .. }),
+            ) => log::debug!(
+                "skipping state model validation, the models require a query to build: {}",
+                e
+            ),
+            Err(e) => {
+                return Err(CompassAppError::BuildFailure(format!(
+                    "invalid state model: {}",
+                    e
+                )))
+            }
+        }
 
         // build plugins
         let plugins_start = Local::now();
@@ -271,6 +320,21 @@ impl TryFrom<(&Config, &CompassAppBuilder)> for CompassApp {
 
         let input_plugins = builder.build_input_plugins(&plugins_config)?;
         let output_plugins = builder.build_output_plugins(&plugins_config)?;
+        if !search_app.map_model.geometry_model.is_loaded() {
+            let geometry_plugins = output_plugins
+                .iter()
+                .filter(|(_, plugin)| plugin.requires_geometry())
+                .map(|(name, _)| name.clone())
+                .collect_vec();
+            if !geometry_plugins.is_empty() {
+                return Err(CompassAppError::CompassConfigurationError(
+                    CompassConfigurationError::UserConfigurationError(format!(
+                        "output plugins [{}] require edge geometries, which are not loaded when the mapping section sets load_geometry = false",
+                        geometry_plugins.join(", ")
+                    )),
+                ));
+            }
+        }
 
         let plugins_duration = to_std(Local::now() - plugins_start)?;
         log::info!(
@@ -280,6 +344,9 @@ impl TryFrom<(&Config, &CompassAppBuilder)> for CompassApp {
 
         let configuration = CompassAppConfiguration::try_from(config)?;
 
+        // precompute the costs between points of interest, if configured
+        let poi_cost_cache = PoiCostCache::from_config(&config_json, &search_app)?;
+
         log::info!(
             "additional parameters - parallelism={}",
             configuration.parallelism,
@@ -290,6 +357,8 @@ impl TryFrom<(&Config, &CompassAppBuilder)> for CompassApp {
             input_plugins,
             output_plugins,
             configuration,
+            poi_cost_cache,
+            config: raw_config_json,
         })
     }
 }
@@ -317,6 +386,106 @@ impl CompassApp {
         &self,
         queries: &mut [serde_json::Value],
         config: Option<&Value>,
+    ) -> Result<Vec<Value>, CompassAppError> {
+        self.run_batch(queries, config, None)
+    }
+
+    /// runs a set of queries as in [`CompassApp::run`], while also counting
+    /// how many routes traverse each edge of the graph across the batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `queries` - list of search queries to execute
+    /// * `config` - configuration for this run batch which may override default configurations
+    ///
+    /// # Result
+    ///
+    /// the responses of the batch along with the usage count of each edge
+    pub fn run_with_edge_usage(
+        &self,
+        queries: &mut [serde_json::Value],
+        config: Option<&Value>,
+    ) -> Result<(Vec<Value>, EdgeUsage), CompassAppError> {
+        let edge_usage = EdgeUsage::new(self.search_app.graph.n_edges());
+        let responses = self.run_batch(queries, config, Some(&edge_usage))?;
+        Ok((responses, edge_usage))
+    }
+
+    /// runs a set of queries as in [`CompassApp::run`], returning the responses
+    /// as a JSON object keyed by the `query_id` of each query rather than as an
+    /// ordered list. every query must have a `query_id`. see
+    /// [`query_id::key_by_query_id`] for how responses sharing an id are handled.
+    ///
+    /// # Arguments
+    ///
+    /// * `queries` - list of search queries to execute
+    /// * `config` - configuration for this run batch which may override default configurations
+    ///
+    /// # Result
+    ///
+    /// the responses of the batch keyed by query id
+    pub fn run_keyed(
+        &self,
+        queries: &mut [serde_json::Value],
+        config: Option<&Value>,
+    ) -> Result<Value, CompassAppError> {
+        let policy = self.get_duplicate_query_id_policy(config)?;
+        let responses = self.run_batch(queries, config, None)?;
+        query_id::key_by_query_id(responses, &policy)
+    }
+
+    /// approximates the betweenness of each edge by routing between a random sample
+    /// of origin and destination vertices, in parallel as a batch of queries, and
+    /// counting how often each edge lies on a route.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - the number of origin-destination pairs to route between
+    /// * `seed` - optional random seed, so that the sample can be repeated
+    /// * `config` - configuration for this run batch which may override default configurations
+    ///
+    /// # Result
+    ///
+    /// the share of the sampled routes that traverse each edge
+    pub fn run_edge_importance(
+        &self,
+        samples: usize,
+        seed: Option<u64>,
+        config: Option<&Value>,
+    ) -> Result<EdgeImportance, CompassAppError> {
+        let pairs = edge_importance::sample_od_pairs(&self.search_app.graph, samples, seed)?;
+        let mut queries = pairs
+            .into_iter()
+            .map(|(origin, destination)| {
+                serde_json::json!({
+                    "origin_vertex": origin,
+                    "destination_vertex": destination
+                })
+            })
+            .collect::<Vec<_>>();
+        let (_, edge_usage) = self.run_with_edge_usage(&mut queries, config)?;
+        Ok(EdgeImportance::new(&edge_usage, samples))
+    }
+
+    /// the duplicate query id policy of a run, which the run configuration may override
+    fn get_duplicate_query_id_policy(
+        &self,
+        config: Option<&Value>,
+    ) -> Result<DuplicateQueryIdPolicy, CompassAppError> {
+        let policy = get_optional_run_config(
+            &CompassConfigurationField::DuplicateQueryIdPolicy.to_str(),
+            &"run configuration",
+            config,
+        )?
+        .unwrap_or(self.configuration.duplicate_query_id_policy);
+        Ok(policy)
+    }
+
+    fn run_batch(
+        &self,
+        queries: &mut [serde_json::Value],
+        config: Option<&Value>,
+        edge_usage: Option<&EdgeUsage>,
     ) -> Result<Vec<Value>, CompassAppError> {
         // allow the user to overwrite global configurations for this run
         let parallelism: usize = get_optional_run_config(
@@ -337,6 +506,7 @@ impl CompassApp {
             config,
         )?
         .unwrap_or_else(|| self.configuration.response_output_policy.clone());
+        self.get_duplicate_query_id_policy(config)?.check(queries)?;
         let response_writer = response_output_policy.build()?;
 
         // INPUT PROCESSING
@@ -348,7 +518,12 @@ impl CompassApp {
             self.search_app.clone(),
             parallel_batch_size,
         )?;
-        let (processed_inputs, input_errors) = input_plugin_result;
+        let (processed_inputs, mut input_errors) = input_plugin_result;
+        for error in input_errors.iter_mut() {
+            if let Some(request) = error.get("request").cloned() {
+                query_id::echo_query_id(&request, error);
+            }
+        }
         let mut load_balanced_inputs =
             ops::apply_load_balancing_policy(processed_inputs, parallelism, 1.0)?;
 
@@ -388,6 +563,7 @@ impl CompassApp {
                 &self.search_app,
                 &response_writer,
                 search_pb_shared,
+                edge_usage,
             )?,
             ResponsePersistencePolicy::DiscardResponseFromMemory => run_batch_without_responses(
                 &mut load_balanced_inputs,
@@ -395,6 +571,7 @@ impl CompassApp {
                 &self.search_app,
                 &response_writer,
                 search_pb_shared,
+                edge_usage,
             )?,
         };
         eprintln!();
@@ -408,6 +585,356 @@ impl CompassApp {
     }
 }
 
+impl CompassApp {
+    /// runs a query once for each of a number of samples of its edge travel times,
+    /// giving a distribution of its trip time for Monte Carlo ETA analysis. each
+    /// sample sets the query `seed`, starting from the seed of the query (or 0),
+    /// which requires a traversal model that samples travel times from a seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - the search query to sample
+    /// * `samples` - the number of samples to run
+    ///
+    /// # Result
+    ///
+    /// the distribution of the trip time across the samples that found a route
+    pub fn run_eta_distribution(
+        &self,
+        query: &Value,
+        samples: usize,
+    ) -> Result<EtaDistribution, CompassAppError> {
+        let base_seed = match query.get(SEED) {
+            None => 0,
+            Some(value) => value.as_u64().ok_or_else(|| {
+                CompassAppError::CompassFailure(format!(
+                    "query field '{}' must be a non-negative integer, found {}",
+                    SEED, value
+                ))
+            })?,
+        };
+        let queries = (0..samples as u64)
+            .map(|i| {
+                let mut sample = query.clone();
+                sample[SEED] = serde_json::json!(base_seed.wrapping_add(i));
+                sample
+            })
+            .collect::<Vec<_>>();
+        let responses = self.run_in_order(&queries)?;
+        let time_unit = responses.iter().find_map(|r| {
+            r["route"]["state_model"]["time"]["time_unit"]
+                .as_str()
+                .map(String::from)
+        });
+        let times = responses
+            .iter()
+            .filter_map(|r| r["route"]["traversal_summary"]["time"].as_f64())
+            .collect::<Vec<_>>();
+        if let (true, Some(error)) = (
+            times.is_empty(),
+            responses.iter().find_map(|r| r.get("error")),
+        ) {
+            return Err(CompassAppError::CompassFailure(format!(
+                "no sample of the query found a route: {}",
+                error
+            )));
+        }
+        Ok(EtaDistribution::new(samples, times, time_unit))
+    }
+
+    /// runs a set of queries, returning the responses in the order of the
+    /// queries. queries expanded by an input plugin produce their responses
+    /// in place of the original query, and queries that fail input processing
+    /// produce their error response in place. searches run in parallel on the
+    /// shared thread pool. unlike [`CompassApp::run`], responses are not
+    /// written to the configured response output policy, and are always
+    /// returned to the caller.
+    ///
+    /// # Arguments
+    ///
+    /// * `queries` - list of search queries to execute
+    ///
+    /// # Result
+    ///
+    /// the JSON responses, in query order
+    pub fn run_in_order(&self, queries: &[Value]) -> Result<Vec<Value>, CompassAppError> {
+        self.configuration
+            .duplicate_query_id_policy
+            .check(queries)?;
+        let responses = queries
+            .par_iter()
+            .map(|query| {
+                match apply_input_plugins_to_query(query, &self.input_plugins, &self.search_app) {
+                    Err(mut error_response) => {
+                        query_id::echo_query_id(query, &mut error_response);
+                        Ok(vec![error_response])
+                    }
+                    Ok(mut processed) => processed
+                        .iter_mut()
+                        .map(|q| run_single_query(q, &self.output_plugins, &self.search_app))
+                        .collect::<Result<Vec<_>, _>>(),
+                }
+            })
+            .collect::<Result<Vec<_>, CompassAppError>>()?;
+        Ok(responses.into_iter().flatten().collect())
+    }
+
+    /// runs a set of queries in the leanest mode, for cost-only workloads such
+    /// as large origin-destination studies. the response of each query is just
+    /// the cost of its route, the value minimized by the search. the request is
+    /// not echoed, no output plugins run and route geometries are never read.
+    /// input plugins are still applied, so queries may give their endpoints in
+    /// any form, and responses are in query order as in [`CompassApp::run_in_order`].
+    ///
+    /// # Arguments
+    ///
+    /// * `queries` - list of search queries to execute
+    /// * `state_features` - state features, such as `distance` and `time`, to
+    ///   report along with the cost
+    ///
+    /// # Result
+    ///
+    /// for each query, the route cost as a number. when state features are
+    /// listed, an object with the `cost` and the value of each feature at the
+    /// end of the route, in the units of the state model. a failed query gives
+    /// its error response.
+    pub fn run_cost_only(
+        &self,
+        queries: &[Value],
+        state_features: &[String],
+    ) -> Result<Vec<Value>, CompassAppError> {
+        self.configuration
+            .duplicate_query_id_policy
+            .check(queries)?;
+        let responses = queries
+            .par_iter()
+            .map(|query| {
+                match apply_input_plugins_to_query(query, &self.input_plugins, &self.search_app) {
+                    Err(mut error_response) => {
+                        query_id::echo_query_id(query, &mut error_response);
+                        vec![error_response]
+                    }
+                    Ok(mut processed) => processed
+                        .iter_mut()
+                        .map(|q| run_single_query_cost_only(q, &self.search_app, state_features))
+                        .collect(),
+                }
+            })
+            .collect::<Vec<_>>();
+        Ok(responses.into_iter().flatten().collect())
+    }
+
+    /// lazily runs a set of queries in chunks, yielding the responses of each
+    /// chunk in query order as it completes. a chunk is only run when the
+    /// iterator is advanced, so at most one chunk of responses is held in memory
+    /// at a time while the caller processes the previous chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `queries` - list of search queries to execute
+    /// * `chunk_size` - number of queries to run per chunk
+    ///
+    /// # Result
+    ///
+    /// an iterator over the responses of each chunk, see [`CompassApp::run_in_order`]
+    pub fn run_chunks(
+        &self,
+        queries: Vec<Value>,
+        chunk_size: usize,
+    ) -> impl Iterator<Item = Result<Vec<Value>, CompassAppError>> + '_ {
+        let mut remaining = queries.into_iter();
+        std::iter::from_fn(move || {
+            let chunk = remaining
+                .by_ref()
+                .take(chunk_size.max(1))
+                .collect::<Vec<_>>();
+            if chunk.is_empty() {
+                None
+            } else {
+                Some(self.run_in_order(&chunk))
+            }
+        })
+    }
+}
+
+impl CompassApp {
+    /// evaluates a fixed route without running a search, for example to score a
+    /// route produced by another routing system. the edges are traversed in order
+    /// using the full traversal, access and cost model stack for the given query
+    /// parameters, and the result is processed by the output plugins. input
+    /// plugins are not applied, as there is no origin or destination to process.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge_ids` - the route to evaluate, as a sequence of connected edges
+    /// * `query_params` - query used to build the models, such as model overrides
+    ///
+    /// # Result
+    ///
+    /// the JSON response for the evaluated route, including the accumulated state
+    /// and cost, or an error if the route is empty or not edge-connected
+    pub fn evaluate_route(
+        &self,
+        edge_ids: &[EdgeId],
+        query_params: &serde_json::Value,
+    ) -> Result<Value, CompassAppError> {
+        let result = self.search_app.evaluate_route(edge_ids, query_params)?;
+        let output = apply_output_processing(
+            query_params,
+            Ok(result),
+            &self.search_app,
+            &self.output_plugins,
+        );
+        Ok(output)
+    }
+}
+
+impl CompassApp {
+    /// finds the `k` points of interest nearest to an origin vertex by network
+    /// cost, such as the closest charging stations by travel time. the search
+    /// expands outward from the origin and stops as soon as `k` points of interest
+    /// are reached. input and output plugins are not applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - the vertex to search from
+    /// * `poi_set` - the vertices that are points of interest
+    /// * `k` - the number of points of interest to find
+    /// * `query_params` - query used to build the models, such as model overrides
+    ///
+    /// # Result
+    ///
+    /// up to `k` points of interest with the cost to reach each, nearest first
+    pub fn nearest_pois(
+        &self,
+        origin: VertexId,
+        poi_set: &HashSet<VertexId>,
+        k: usize,
+        query_params: &serde_json::Value,
+    ) -> Result<Vec<(VertexId, Cost)>, CompassAppError> {
+        self.search_app
+            .nearest_pois(origin, poi_set, k, query_params)
+    }
+
+    /// computes a matrix of network costs between origin and destination vertices,
+    /// such as a travel time matrix for dispatching. runs one search per origin that
+    /// stops once every destination is reached, and does not build routes. input and
+    /// output plugins are not applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `origins` - the vertices to search from
+    /// * `destinations` - the vertices to search to
+    /// * `query_params` - query used to build the models, such as model overrides
+    ///
+    /// # Result
+    ///
+    /// for each origin, the cost to each destination in the order given, where
+    /// None marks a destination that is not reachable
+    pub fn run_matrix(
+        &self,
+        origins: Vec<VertexId>,
+        destinations: Vec<VertexId>,
+        query_params: &serde_json::Value,
+    ) -> Result<Vec<Vec<Option<Cost>>>, CompassAppError> {
+        self.search_app
+            .cost_matrix(&origins, &destinations, query_params)
+    }
+}
+
+impl CompassApp {
+    /// routes to the best of several candidate destinations, such as the nearest
+    /// open branch of a store, and ranks all of the candidates by network cost. a
+    /// single one-to-many search from the origin stops once every candidate has been
+    /// reached. the route to the lowest-cost candidate is processed by the output
+    /// plugins, and input plugins are not applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - the vertex to search from
+    /// * `candidates` - the candidate destination vertices
+    /// * `query_params` - query used to build the models, such as model overrides
+    ///
+    /// # Result
+    ///
+    /// the JSON response for the route to the best candidate, with the reachable
+    /// `candidates` ranked by `cost` and the `unreachable_candidates`. if no candidate
+    /// is reachable, the response is an error with a `no_route` cause.
+    pub fn route_to_best(
+        &self,
+        origin: VertexId,
+        candidates: &[VertexId],
+        query_params: &serde_json::Value,
+    ) -> Result<Value, CompassAppError> {
+        let candidate_set: HashSet<VertexId> = candidates.iter().cloned().collect();
+        let mut request = query_params.clone();
+        let (ranked, result) =
+            match self
+                .search_app
+                .route_to_best(origin, &candidate_set, query_params)
+            {
+                Ok((ranked, result, si)) => {
+                    if let (Some(obj), Some((best, _))) = (request.as_object_mut(), ranked.first())
+                    {
+                        obj.insert(String::from("origin_vertex"), serde_json::json!(origin));
+                        obj.insert(String::from("destination_vertex"), serde_json::json!(best));
+                    }
+                    (ranked, Ok((result, si)))
+                }
+                Err(e) if e.no_route_cause().is_some() => (vec![], Err(e)),
+                Err(e) => return Err(e),
+            };
+
+        let mut output =
+            apply_output_processing(&request, result, &self.search_app, &self.output_plugins);
+        let reached: HashSet<VertexId> = ranked.iter().map(|(v, _)| *v).collect();
+        let unreachable = candidates
+            .iter()
+            .filter(|v| !reached.contains(v))
+            .unique()
+            .collect_vec();
+        if let Some(obj) = output.as_object_mut() {
+            let ranked_json = ranked
+                .iter()
+                .map(|(vertex_id, cost)| serde_json::json!({"vertex_id": vertex_id, "cost": cost}))
+                .collect_vec();
+            obj.insert(String::from("candidates"), serde_json::json!(ranked_json));
+            obj.insert(
+                String::from("unreachable_candidates"),
+                serde_json::json!(unreachable),
+            );
+        }
+        Ok(output)
+    }
+}
+
+impl CompassApp {
+    /// looks up the precomputed cost between two points of interest listed in
+    /// the `[poi_cost_cache]` configuration section, without running a search.
+    ///
+    /// # Arguments
+    ///
+    /// * `src_poi` - the point of interest to travel from
+    /// * `dst_poi` - the point of interest to travel to
+    ///
+    /// # Result
+    ///
+    /// the cost, None if the destination is not reachable, or an error if no
+    /// cache is configured or either vertex is not a point of interest
+    pub fn poi_cost(
+        &self,
+        src_poi: VertexId,
+        dst_poi: VertexId,
+    ) -> Result<Option<Cost>, CompassAppError> {
+        let cache = self.poi_cost_cache.as_ref().ok_or_else(|| {
+            CompassAppError::CompassFailure(String::from(
+                "no [poi_cost_cache] section in the configuration",
+            ))
+        })?;
+        cache.get(&src_poi, &dst_poi)
+    }
+}
+
 /// executes the input plugins on each query, returning all
 /// successful mappings (left) and mapping errors (right) as the pair
 /// (left, right). errors are already serialized into JSON.
@@ -438,19 +965,12 @@ fn apply_input_plugins(
             let (good, bad): (Vec<Vec<Value>>, Vec<Value>) = qs
                 .iter()
                 .map(|q| {
-                    let mut plugin_state = serde_json::Value::Array(vec![q.to_owned()]);
-                    for plugin in input_plugins {
-                        let p = plugin.clone();
-                        let op: in_ops::InputArrayOp =
-                            Rc::new(|q| p.process(q, search_app.clone()));
-                        in_ops::json_array_op(&mut plugin_state, op)?
-                    }
-                    let inner_processed = in_ops::json_array_flatten(&mut plugin_state)?;
-                    // let inner_processed = apply_input_plugins(q, input_plugins);
+                    let inner_processed =
+                        apply_input_plugins_to_query(q, input_plugins, &search_app);
                     if let Ok(mut pb_local) = input_pb_shared.lock() {
                         let _ = pb_local.update(1);
                     }
-                    Ok(inner_processed)
+                    inner_processed
                 })
                 .partition_map(|r| match r {
                     Ok(values) => Either::Left(values),
@@ -469,6 +989,23 @@ fn apply_input_plugins(
     Ok(result)
 }
 
+/// executes the input plugins on a single query, which may expand it into
+/// several queries. a failure is returned as a serialized JSON error.
+fn apply_input_plugins_to_query(
+    query: &Value,
+    input_plugins: &Vec<Arc<dyn InputPlugin>>,
+    search_app: &Arc<SearchApp>,
+) -> Result<Vec<Value>, Value> {
+    let mut plugin_state = serde_json::Value::Array(vec![query.to_owned()]);
+    for plugin in input_plugins {
+        let p = plugin.clone();
+        let op: in_ops::InputArrayOp = Rc::new(|q| p.process(q, search_app.clone()));
+        in_ops::json_array_op(&mut plugin_state, op)?
+    }
+    let inner_processed = in_ops::json_array_flatten(&mut plugin_state)?;
+    Ok(inner_processed)
+}
+
 pub fn get_optional_run_config<'a, K, T>(
     key: &K,
     parent_key: &K,
@@ -501,14 +1038,87 @@ where
 /// * The result of the search and post-processing as a JSON object, or, an error
 pub fn run_single_query(
     query: &mut serde_json::Value,
-    output_plugins: &[Arc<dyn OutputPlugin>],
+    output_plugins: &[NamedOutputPlugin],
     search_app: &SearchApp,
+) -> Result<serde_json::Value, CompassAppError> {
+    run_single_query_with_edge_usage(query, output_plugins, search_app, None)
+}
+
+/// runs a single query as in [`run_single_query`], recording the edges of
+/// each route of a successful search in the (optional) edge usage counts.
+pub fn run_single_query_with_edge_usage(
+    query: &mut serde_json::Value,
+    output_plugins: &[NamedOutputPlugin],
+    search_app: &SearchApp,
+    edge_usage: Option<&EdgeUsage>,
 ) -> Result<serde_json::Value, CompassAppError> {
     let search_result = search_app.run(query);
+    if let (Some(edge_usage), Ok((result, _))) = (edge_usage, &search_result) {
+        for route in result.routes.iter() {
+            let edge_ids = route.iter().map(|e| e.edge_id).collect::<Vec<_>>();
+            edge_usage.record(&edge_ids)?;
+        }
+    }
     let output = apply_output_processing(query, search_result, search_app, output_plugins);
     Ok(output)
 }
 
+/// runs a single query for [`CompassApp::run_cost_only`], returning only the
+/// cost of the first route and the listed state features at its end.
+fn run_single_query_cost_only(
+    query: &mut serde_json::Value,
+    search_app: &SearchApp,
+    state_features: &[String],
+) -> serde_json::Value {
+    let (result, si) = match search_app.run(query) {
+        Ok(r) => r,
+        Err(e) => return out_ops::package_search_error(query, &e),
+    };
+    let route = match result.routes.first() {
+        Some(route) => route,
+        None => {
+            return out_ops::package_error(query, "cost only mode requires a query with a route")
+        }
+    };
+    let cost = route
+        .iter()
+        .fold(Cost::ZERO, |acc, et| acc + et.total_cost());
+    if state_features.is_empty() {
+        return serde_json::json!(cost);
+    }
+    let final_state = match route.last() {
+        Some(et) => et.result_state.clone(),
+        None => match si.state_model.initial_state() {
+            Ok(state) => state,
+            Err(e) => return out_ops::package_error(query, e),
+        },
+    };
+    let mut response = serde_json::Map::new();
+    response.insert(String::from("cost"), serde_json::json!(cost));
+    for name in state_features.iter() {
+        let value = si
+            .state_model
+            .iter()
+            .zip(final_state.iter())
+            .find(|((feature, _), _)| *feature == name)
+            .map(|(_, value)| *value);
+        match value {
+            Some(value) => response.insert(name.clone(), serde_json::json!(value)),
+            None => {
+                return out_ops::package_error(
+                    query,
+                    format!(
+                        "unknown state feature '{}', should be one of: {}",
+                        name,
+                        si.state_model.get_names()
+                    ),
+                )
+            }
+        };
+    }
+    Value::Object(response)
+}
+
 /// helper for handling conversion from Chrono Duration to std Duration
 fn to_std(dur: Duration) -> Result<std::time::Duration, CompassAppError> {
     dur.to_std().map_err(|e| {
@@ -523,10 +1133,11 @@ fn to_std(dur: Duration) -> Result<std::time::Duration, CompassAppError> {
 /// and retains the responses from each search in memory.
 pub fn run_batch_with_responses(
     load_balanced_inputs: &mut Vec<Vec<Value>>,
-    output_plugins: &[Arc<dyn OutputPlugin>],
+    output_plugins: &[NamedOutputPlugin],
     search_app: &SearchApp,
     response_writer: &ResponseSink,
     pb: Arc<Mutex<Bar>>,
+    edge_usage: Option<&EdgeUsage>,
 ) -> Result<Box<dyn Iterator<Item = Value>>, CompassAppError> {
     let run_query_result = load_balanced_inputs
         .par_iter_mut()
@@ -534,7 +1145,12 @@ pub fn run_batch_with_responses(
             queries
                 .iter_mut()
                 .map(|q| {
-                    let mut response = run_single_query(q, output_plugins, search_app)?;
+                    let mut response = run_single_query_with_edge_usage(
+                        q,
+                        output_plugins,
+                        search_app,
+                        edge_usage,
+                    )?;
                     if let Ok(mut pb_local) = pb.lock() {
                         let _ = pb_local.update(1);
                     }
@@ -554,10 +1170,11 @@ pub fn run_batch_with_responses(
 /// the search result is not persisted in memory.
 pub fn run_batch_without_responses(
     load_balanced_inputs: &mut Vec<Vec<Value>>,
-    output_plugins: &[Arc<dyn OutputPlugin>],
+    output_plugins: &[NamedOutputPlugin],
     search_app: &SearchApp,
     response_writer: &ResponseSink,
     pb: Arc<Mutex<Bar>>,
+    edge_usage: Option<&EdgeUsage>,
 ) -> Result<Box<dyn Iterator<Item = Value>>, CompassAppError> {
     // run the computations, discard values that do not trigger an error
     let _ = load_balanced_inputs
@@ -568,7 +1185,8 @@ pub fn run_batch_without_responses(
             // within a for loop or for_each call, and map creates more allocations. open to other ideas!
             let initial: Result<(), CompassAppError> = Ok(());
             let _ = queries.iter_mut().fold(initial, |_, q| {
-                let mut response = run_single_query(q, output_plugins, search_app)?;
+                let mut response =
+                    run_single_query_with_edge_usage(q, output_plugins, search_app, edge_usage)?;
                 if let Ok(mut pb_local) = pb.lock() {
                     let _ = pb_local.update(1);
                 }
@@ -584,19 +1202,39 @@ pub fn run_batch_without_responses(
 
 // helper that applies the output processing. this includes
 // 1. summarizing from the TraversalModel
-// 2. applying the output plugins
+// 2. applying the output plugins, or the subset selected by the query
 pub fn apply_output_processing(
     request_json: &serde_json::Value,
     result: Result<(SearchAppResult, SearchInstance), CompassAppError>,
     search_app: &SearchApp,
-    output_plugins: &[Arc<dyn OutputPlugin>],
+    output_plugins: &[NamedOutputPlugin],
 ) -> serde_json::Value {
+    let mut output = apply_output_plugins(request_json, result, search_app, output_plugins);
+    query_id::echo_query_id(request_json, &mut output);
+    if let (Some(fingerprint), Some(obj)) = (&search_app.fingerprint, output.as_object_mut()) {
+        obj.insert(String::from("fingerprint"), serde_json::json!(fingerprint));
+    }
+    output
+}
+
+/// builds the initial response for a search result and runs each output plugin
+/// selected by the request.
+fn apply_output_plugins(
+    request_json: &serde_json::Value,
+    result: Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    search_app: &SearchApp,
+    output_plugins: &[NamedOutputPlugin],
+) -> serde_json::Value {
+    let selected = match out_ops::select_output_plugins(request_json, output_plugins) {
+        Ok(plugins) => plugins,
+        Err(e) => return out_ops::package_error(request_json, e),
+    };
     let mut initial: Value = match out_ops::create_initial_output(request_json, &result, search_app)
     {
         Ok(value) => value,
         Err(error_value) => return error_value,
     };
-    for output_plugin in output_plugins.iter() {
+    for output_plugin in selected.into_iter() {
         match output_plugin.process(&mut initial, &result) {
             Ok(()) => {}
             Err(e) => return out_ops::package_error(request_json, e),
@@ -608,9 +1246,7 @@ pub fn apply_output_processing(
 
 #[cfg(test)]
 mod tests {
-    use super::CompassApp;
-    use crate::app::compass::{CompassAppError, CompassConfigurationError};
-    use std::path::PathBuf;
+    use crate::app::compass::test::load_test_app;
 
     #[test]
     fn test_speeds() {
@@ -621,38 +1257,7 @@ mod tests {
         // eprintln!("cwd           : {}", cwd_str);
         // eprintln!("Cargo.toml dir: {}", env!("CARGO_MANIFEST_DIR"));
 
-        // rust runs test and debug at different locations, which breaks the URLs
-        // written in the referenced TOML files. here's a quick fix
-        // turnaround that doesn't leak into anyone's VS Code settings.json files
-        // see https://github.com/rust-lang/rust-analyzer/issues/4705 for discussion
-        let conf_file_test = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .join("src")
-            .join("app")
-            .join("compass")
-            .join("test")
-            .join("speeds_test")
-            .join("speeds_test.toml");
-
-        let conf_file_debug = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .join("src")
-            .join("app")
-            .join("compass")
-            .join("test")
-            .join("speeds_test")
-            .join("speeds_debug.toml");
-
-        let app = match CompassApp::try_from(conf_file_test.as_path()) {
-            Ok(a) => Ok(a),
-            Err(CompassAppError::CompassConfigurationError(
-                CompassConfigurationError::FileNormalizationNotFound(_key, _f1, _f2),
-            )) => {
-                // could just be the run location, depending on the environment/runner/IDE
-                // try the alternative configuration that runs from the root directory
-                CompassApp::try_from(conf_file_debug.as_path())
-            }
-            Err(other) => panic!("{}", other),
-        }
-        .unwrap();
+        let app = load_test_app("speeds");
         let query = serde_json::json!({
             "origin_vertex": 0,
             "destination_vertex": 2
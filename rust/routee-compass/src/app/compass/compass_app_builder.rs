@@ -9,20 +9,25 @@ use crate::plugin::{
     },
     output::{
         default::{
-            summary::SummaryOutputPluginBuilder, traversal::TraversalPluginBuilder,
-            uuid::UUIDOutputPluginBuilder,
+            charging::ChargingOutputPluginBuilder, geojson::GeoJsonOutputPluginBuilder,
+            gpx::GpxOutputPluginBuilder, polyline::PolylineOutputPluginBuilder,
+            query_hash::QueryHashOutputPluginBuilder, summary::SummaryOutputPluginBuilder,
+            traversal::TraversalPluginBuilder, uuid::UUIDOutputPluginBuilder,
         },
-        OutputPlugin,
+        NamedOutputPlugin,
     },
 };
 use crate::{
     app::compass::model::{
         access_model::{
             combined_access_model_builder::CombinedAccessModelBuilder,
+            comfort_access_model_builder::ComfortAccessModelBuilder,
             turn_delay_access_model_builder::TurnDelayAccessModelBuilder,
         },
         frontier_model::{
             combined::combined_builder::CombinedBuilder,
+            edge_avoidance::edge_avoidance_builder::EdgeAvoidanceBuilder,
+            gradeability::gradeability_builder::GradeabilityBuilder,
             no_restriction_builder::NoRestrictionBuilder,
             road_class::road_class_builder::RoadClassBuilder,
             turn_restrictions::turn_restriction_builder::TurnRestrictionBuilder,
@@ -210,18 +215,24 @@ impl CompassAppBuilder {
         Ok(plugins)
     }
 
+    /// builds the configured output plugins. each plugin is named by its optional
+    /// `name` key, or by its `type` otherwise. queries may select plugins by name.
     pub fn build_output_plugins(
         &self,
         config: &serde_json::Value,
-    ) -> Result<Vec<Arc<dyn OutputPlugin>>, CompassConfigurationError> {
+    ) -> Result<Vec<NamedOutputPlugin>, CompassConfigurationError> {
         let output_plugins = config.get_config_array(
             &CompassConfigurationField::OutputPlugins,
             &CompassConfigurationField::Plugins,
         )?;
 
-        let mut plugins: Vec<Arc<dyn OutputPlugin>> = Vec::new();
+        let mut plugins: Vec<NamedOutputPlugin> = Vec::new();
         for plugin_json in output_plugins.into_iter() {
             let plugin_type = plugin_json.get_config_string(&"type", &"output_plugin")?;
+            let plugin_name = plugin_json
+                .get_config_serde_optional::<String>(&"name", &"output_plugin")?
+                .unwrap_or_else(|| plugin_type.clone());
+
             let builder = self
                 .output_plugin_builders
                 .get(&plugin_type)
@@ -233,7 +244,7 @@ impl CompassAppBuilder {
                     )
                 })?;
             let output_plugin = builder.build(&plugin_json)?;
-            plugins.push(output_plugin);
+            plugins.push((plugin_name, output_plugin));
         }
         Ok(plugins)
     }
@@ -263,20 +274,25 @@ impl Default for CompassAppBuilder {
         // Access model builders
         let no_access_model: Rc<dyn AccessModelBuilder> = Rc::new(NoAccessModel {});
         let turn_delay: Rc<dyn AccessModelBuilder> = Rc::new(TurnDelayAccessModelBuilder {});
+        let comfort: Rc<dyn AccessModelBuilder> = Rc::new(ComfortAccessModelBuilder {});
         let combined_am: Rc<dyn AccessModelBuilder> = Rc::new(CombinedAccessModelBuilder {
             builders: HashMap::from([
                 (String::from("no_access_model"), no_access_model.clone()),
                 (String::from("turn_delay"), turn_delay.clone()),
+                (String::from("comfort"), comfort.clone()),
             ]),
         });
         let am_builders: HashMap<String, Rc<dyn AccessModelBuilder>> = HashMap::from([
             (String::from("no_access_model"), no_access_model),
             (String::from("turn_delay"), turn_delay),
+            (String::from("comfort"), comfort),
             (String::from("combined"), combined_am),
         ]);
 
         // Frontier model builders
         let no_restriction: Rc<dyn FrontierModelBuilder> = Rc::new(NoRestrictionBuilder {});
+        let edge_avoidance: Rc<dyn FrontierModelBuilder> = Rc::new(EdgeAvoidanceBuilder {});
+        let gradeability: Rc<dyn FrontierModelBuilder> = Rc::new(GradeabilityBuilder {});
         let road_class: Rc<dyn FrontierModelBuilder> = Rc::new(RoadClassBuilder {});
         let turn_restriction: Rc<dyn FrontierModelBuilder> = Rc::new(TurnRestrictionBuilder {});
         let vehicle_restriction: Rc<dyn FrontierModelBuilder> =
@@ -284,6 +300,8 @@ impl Default for CompassAppBuilder {
         let base_frontier_builders: HashMap<String, Rc<dyn FrontierModelBuilder>> =
             HashMap::from([
                 (String::from("no_restriction"), no_restriction),
+                (String::from("edge_avoidance"), edge_avoidance),
+                (String::from("gradeability"), gradeability),
                 (String::from("road_class"), road_class),
                 (String::from("turn_restriction"), turn_restriction),
                 (String::from("vehicle_restriction"), vehicle_restriction),
@@ -309,11 +327,21 @@ impl Default for CompassAppBuilder {
         // Output plugin builders
         let traversal: Rc<dyn OutputPluginBuilder> = Rc::new(TraversalPluginBuilder {});
         let summary: Rc<dyn OutputPluginBuilder> = Rc::new(SummaryOutputPluginBuilder {});
+        let query_hash: Rc<dyn OutputPluginBuilder> = Rc::new(QueryHashOutputPluginBuilder {});
         let uuid: Rc<dyn OutputPluginBuilder> = Rc::new(UUIDOutputPluginBuilder {});
+        let charging: Rc<dyn OutputPluginBuilder> = Rc::new(ChargingOutputPluginBuilder {});
+        let geojson: Rc<dyn OutputPluginBuilder> = Rc::new(GeoJsonOutputPluginBuilder {});
+        let gpx: Rc<dyn OutputPluginBuilder> = Rc::new(GpxOutputPluginBuilder {});
+        let polyline: Rc<dyn OutputPluginBuilder> = Rc::new(PolylineOutputPluginBuilder {});
         let output_plugin_builders = HashMap::from([
             (String::from("traversal"), traversal),
             (String::from("summary"), summary),
+            (String::from("query_hash"), query_hash),
             (String::from("uuid"), uuid),
+            (String::from("charging"), charging),
+            (String::from("geojson"), geojson),
+            (String::from("gpx"), gpx),
+            (String::from("polyline"), polyline),
         ]);
 
         CompassAppBuilder {
@@ -1,4 +1,5 @@
 use super::{
+    duplicate_query_id_policy::DuplicateQueryIdPolicy,
     response::{
         response_output_policy::ResponseOutputPolicy,
         response_persistence_policy::ResponsePersistencePolicy,
@@ -13,6 +14,7 @@ pub struct CompassAppConfiguration {
     pub parallelism: usize,
     pub response_persistence_policy: ResponsePersistencePolicy,
     pub response_output_policy: ResponseOutputPolicy,
+    pub duplicate_query_id_policy: DuplicateQueryIdPolicy,
 }
 
 impl CompassAppConfiguration {
@@ -20,11 +22,13 @@ impl CompassAppConfiguration {
         parallelism: usize,
         response_persistence_policy: ResponsePersistencePolicy,
         response_output_policy: ResponseOutputPolicy,
+        duplicate_query_id_policy: DuplicateQueryIdPolicy,
     ) -> CompassAppConfiguration {
         CompassAppConfiguration {
             parallelism,
             response_persistence_policy,
             response_output_policy,
+            duplicate_query_id_policy,
         }
     }
 }
@@ -41,10 +45,14 @@ impl TryFrom<&Config> for CompassAppConfiguration {
         let response_output_policy = config.get::<ResponseOutputPolicy>(
             CompassConfigurationField::ResponseOutputPolicy.to_str(),
         )?;
+        let duplicate_query_id_policy = config.get::<DuplicateQueryIdPolicy>(
+            CompassConfigurationField::DuplicateQueryIdPolicy.to_str(),
+        )?;
         let configuration = CompassAppConfiguration::new(
             parallelism,
             response_persistence_policy,
             response_output_policy,
+            duplicate_query_id_policy,
         );
 
         Ok(configuration)
@@ -2,7 +2,7 @@ use super::CompassConfigurationError;
 use crate::plugin::{input::InputPluginError, output::OutputPluginError, PluginError};
 use config::ConfigError;
 use routee_compass_core::{
-    algorithm::search::SearchError,
+    algorithm::search::{NoRouteCause, SearchError},
     model::{
         access::AccessModelError, cost::CostModelError, frontier::FrontierModelError,
         map::MapError, network::network_error::NetworkError, state::StateModelError,
@@ -22,6 +22,8 @@ pub enum CompassAppError {
     InternalError(String),
     #[error("error accessing shared read-only dataset: {0}")]
     ReadOnlyPoisonError(String),
+    #[error("missing field: {0}")]
+    MissingQueryField(String),
 
     // TRANSPARENT MODULE FAILURES
     //   failures from these modules are detailed enough to get surfaced directly to the user
@@ -87,3 +89,20 @@ pub enum CompassAppError {
         source: serde_json::Error,
     },
 }
+
+impl CompassAppError {
+    /// if this error was caused by a search that ended without finding a route,
+    /// classifies the cause. for all other errors, returns None.
+    pub fn no_route_cause(&self) -> Option<NoRouteCause> {
+        match self {
+            CompassAppError::SearchFailure(e) => e.no_route_cause(),
+            CompassAppError::TerminationModelFailure { source } => {
+                SearchError::from(source.clone()).no_route_cause()
+            }
+            CompassAppError::MappingFailure {
+                source: MapError::OffNetwork(..),
+            } => Some(NoRouteCause::OffNetwork),
+            _ => None,
+        }
+    }
+}
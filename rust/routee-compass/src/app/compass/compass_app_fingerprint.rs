@@ -0,0 +1,32 @@
+use super::compass_input_field::CompassInputField;
+use routee_compass_core::{model::network::Graph, util::fingerprint::Fingerprint};
+use serde::{Deserialize, Serialize};
+
+/// fingerprints of the graph and configuration loaded by a CompassApp. they are
+/// included in each response so that a stored result can be tied to the exact
+/// inputs that produced it. the configuration fingerprint covers the parsed
+/// settings, so formatting and comments do not change it, but it does not cover
+/// the contents of model input files other than the graph.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CompassAppFingerprint {
+    pub graph: String,
+    pub config: String,
+}
+
+impl CompassAppFingerprint {
+    /// computes the fingerprints of a loaded graph and the configuration it was
+    /// loaded with, before file paths are made absolute. the location of the
+    /// configuration file is left out so that moving it does not change the fingerprint.
+    pub fn new(graph: &Graph, config: &serde_json::Value) -> CompassAppFingerprint {
+        let mut settings = config.clone();
+        if let Some(obj) = settings.as_object_mut() {
+            obj.remove(CompassInputField::ConfigInputFile.to_str());
+        }
+        let mut config_fingerprint = Fingerprint::default();
+        config_fingerprint.write_json(&settings);
+        CompassAppFingerprint {
+            graph: graph.fingerprint(),
+            config: config_fingerprint.to_hex(),
+        }
+    }
+}
@@ -10,6 +10,7 @@ pub enum CompassConfigurationField {
     Access,
     Cost,
     Algorithm,
+    SearchAlgorithms,
     Plugins,
     MapModel,
     InputPlugins,
@@ -21,6 +22,10 @@ pub enum CompassConfigurationField {
     ChargeSustaining,
     ResponsePersistencePolicy,
     ResponseOutputPolicy,
+    DuplicateQueryIdPolicy,
+    PoiCostCache,
+    MapMatching,
+    Landmarks,
 }
 
 impl CompassConfigurationField {
@@ -34,6 +39,7 @@ impl CompassConfigurationField {
             CompassConfigurationField::Frontier => "frontier",
             CompassConfigurationField::Termination => "termination",
             CompassConfigurationField::Algorithm => "algorithm",
+            CompassConfigurationField::SearchAlgorithms => "search_algorithms",
             CompassConfigurationField::Parallelism => "parallelism",
             CompassConfigurationField::QueryTimeoutMs => "query_timeout_ms",
             CompassConfigurationField::IncludeTree => "include_tree",
@@ -45,6 +51,10 @@ impl CompassConfigurationField {
             CompassConfigurationField::ChargeSustaining => "charge_sustaining",
             CompassConfigurationField::ResponsePersistencePolicy => "response_persistence_policy",
             CompassConfigurationField::ResponseOutputPolicy => "response_output_policy",
+            CompassConfigurationField::DuplicateQueryIdPolicy => "duplicate_query_id_policy",
+            CompassConfigurationField::PoiCostCache => "poi_cost_cache",
+            CompassConfigurationField::MapMatching => "map_matching",
+            CompassConfigurationField::Landmarks => "landmarks",
         }
     }
 }
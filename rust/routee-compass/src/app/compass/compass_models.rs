@@ -0,0 +1,261 @@
+use super::{
+    compass_input_field::CompassInputField,
+    model::{
+        cost_model::{cost_model_builder::CostModelBuilder, cost_model_service::CostModelService},
+        termination_model_builder::TerminationModelBuilder,
+    },
+    CompassAppBuilder, CompassAppError, CompassConfigurationField, ConfigJsonExtensions,
+};
+use crate::app::search::search_app_ops;
+use chrono::Local;
+use config::Config;
+use routee_compass_core::{
+    algorithm::search::SearchError,
+    model::{
+        access::AccessModelService, frontier::FrontierModelService, state::StateModel,
+        termination::TerminationModel, traversal::TraversalModelService,
+    },
+    util::duration_extension::DurationExtension,
+};
+use std::{path::PathBuf, sync::Arc};
+
+/// the models of a RouteE Compass configuration, which are built without loading the
+/// road network graph. this is everything that commands describing or validating a
+/// configuration need, so they can skip parsing the edges and vertices of a large
+/// network. [`super::compass_app::CompassApp`] builds these and then loads the graph.
+pub struct CompassModels {
+    pub state_model: Arc<StateModel>,
+    pub traversal_model_service: Arc<dyn TraversalModelService>,
+    pub access_model_service: Arc<dyn AccessModelService>,
+    pub cost_model_service: CostModelService,
+    pub frontier_model_service: Arc<dyn FrontierModelService>,
+    pub termination_model: TerminationModel,
+}
+
+impl CompassModels {
+    /// builds the models from a configuration whose file paths are already normalized.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_json` - the configuration, as JSON
+    /// * `builder` - the builder of each type of model
+    ///
+    /// # Returns
+    ///
+    /// the models, or an error if any model fails to build
+    pub fn build(
+        config_json: &serde_json::Value,
+        builder: &CompassAppBuilder,
+    ) -> Result<CompassModels, CompassAppError> {
+        let state_model = match config_json.get(CompassConfigurationField::State.to_string()) {
+            Some(state_config) => Arc::new(StateModel::try_from(state_config)?),
+            None => Arc::new(StateModel::empty()),
+        };
+
+        // build traversal model
+        let traversal_start = Local::now();
+        let traversal_params =
+            config_json.get_config_section(CompassConfigurationField::Traversal, &"TOML")?;
+        let traversal_model_service = builder.build_traversal_model_service(&traversal_params)?;
+        let traversal_duration = (Local::now() - traversal_start)
+            .to_std()
+            .map_err(|e| CompassAppError::InternalError(e.to_string()))?;
+        log::info!(
+            "finished reading traversal model with duration {}",
+            traversal_duration.hhmmss()
+        );
+
+        // build access model
+        let access_start = Local::now();
+        let access_params =
+            config_json.get_config_section(CompassConfigurationField::Access, &"TOML")?;
+        let access_model_service = builder.build_access_model_service(&access_params)?;
+        let access_duration = (Local::now() - access_start)
+            .to_std()
+            .map_err(|e| CompassAppError::InternalError(e.to_string()))?;
+        log::info!(
+            "finished reading access model with duration {}",
+            access_duration.hhmmss()
+        );
+
+        // build utility model
+        let cost_params =
+            config_json.get_config_section(CompassConfigurationField::Cost, &"TOML")?;
+        let cost_model_service = CostModelBuilder {}.build(&cost_params)?;
+
+        // build frontier model
+        let frontier_start = Local::now();
+        let frontier_params =
+            config_json.get_config_section(CompassConfigurationField::Frontier, &"TOML")?;
+
+        let frontier_model_service = builder.build_frontier_model_service(&frontier_params)?;
+
+        let frontier_duration = (Local::now() - frontier_start)
+            .to_std()
+            .map_err(|e| CompassAppError::InternalError(e.to_string()))?;
+        log::info!(
+            "finished reading frontier model with duration {}",
+            frontier_duration.hhmmss()
+        );
+
+        // build termination model
+        let termination_model_json =
+            config_json.get_config_section(CompassConfigurationField::Termination, &"TOML")?;
+        let termination_model = TerminationModelBuilder::build(&termination_model_json, None)?;
+
+        Ok(CompassModels {
+            state_model,
+            traversal_model_service,
+            access_model_service,
+            cost_model_service,
+            frontier_model_service,
+            termination_model,
+        })
+    }
+
+    /// confirms that the state model assembled for a query is self-consistent,
+    /// as in [`crate::app::search::SearchApp::validate_state_model`].
+    ///
+    /// # Returns
+    ///
+    /// the features of the state model in state vector order, each paired with
+    /// the source providing it
+    pub fn validate_state_model(
+        &self,
+        query: &serde_json::Value,
+    ) -> Result<Vec<(String, String)>, SearchError> {
+        let (features, _, _) = search_app_ops::build_query_models(
+            query,
+            &self.state_model,
+            self.traversal_model_service.as_ref(),
+            self.access_model_service.as_ref(),
+            &self.cost_model_service,
+        )?;
+        Ok(features)
+    }
+
+    /// describes the models built for a query: the state model with the source of
+    /// each feature, and the weights and rates of the cost model. these are the
+    /// `state_model` and `cost_model` reported with each route.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - JSON query used to build the models, such as one with a vehicle `model_name`
+    ///
+    /// # Returns
+    ///
+    /// a JSON description of the models, or an error if they cannot be built
+    pub fn describe(&self, query: &serde_json::Value) -> Result<serde_json::Value, SearchError> {
+        let (features, state_model, cost_model) = search_app_ops::build_query_models(
+            query,
+            &self.state_model,
+            self.traversal_model_service.as_ref(),
+            self.access_model_service.as_ref(),
+            &self.cost_model_service,
+        )?;
+        let sources: serde_json::Map<String, serde_json::Value> = features
+            .into_iter()
+            .map(|(name, source)| (name, serde_json::json!(source)))
+            .collect();
+        let cost_model_info = cost_model
+            .serialize_cost_info()
+            .map_err(|e| SearchError::BuildError(e.to_string()))?;
+        Ok(serde_json::json!({
+            "state_model": state_model.serialize_state_model(),
+            "feature_sources": sources,
+            "cost_model": cost_model_info
+        }))
+    }
+}
+
+impl TryFrom<(&Config, &CompassAppBuilder)> for CompassModels {
+    type Error = CompassAppError;
+
+    /// builds the models of a configuration, such as a parsed TOML file, without
+    /// loading the graph.
+    fn try_from(pair: (&Config, &CompassAppBuilder)) -> Result<Self, Self::Error> {
+        let (config, builder) = pair;
+        let root_config_path =
+            config.get::<PathBuf>(CompassInputField::ConfigInputFile.to_str())?;
+        let config_json = config
+            .clone()
+            .try_deserialize::<serde_json::Value>()?
+            .normalize_file_paths(&"", &root_config_path)?;
+        CompassModels::build(&config_json, builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::compass::{
+        compass_app::CompassApp, compass_app_ops::read_config_from_string,
+        CompassConfigurationError,
+    };
+
+    /// reads the speeds test configuration with its edge list replaced by the
+    /// vertex list, which cannot be parsed as edges
+    fn unparseable_edges_config(file_name: &str) -> Config {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("app")
+            .join("compass")
+            .join("test")
+            .join("speeds_test")
+            .join(file_name);
+        let contents = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace("test_edges.csv", "test_vertices.csv");
+        read_config_from_string(
+            contents,
+            config::FileFormat::Toml,
+            path.to_str().unwrap().to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_describe_without_graph() {
+        let builder = CompassAppBuilder::default();
+        let models = match CompassModels::try_from((
+            &unparseable_edges_config("speeds_test.toml"),
+            &builder,
+        )) {
+            Ok(m) => Ok(m),
+            Err(CompassAppError::CompassConfigurationError(
+                CompassConfigurationError::FileNormalizationNotFound(_key, _f1, _f2),
+            )) => {
+                CompassModels::try_from((&unparseable_edges_config("speeds_debug.toml"), &builder))
+            }
+            Err(other) => panic!("{}", other),
+        }
+        .unwrap();
+
+        // the models are described without reading the edge list
+        let description = models.describe(&serde_json::json!({})).unwrap();
+        assert_eq!(description["state_model"]["time"]["time_unit"], "seconds");
+        assert_eq!(
+            description["feature_sources"]["distance"],
+            "traversal model"
+        );
+        assert_eq!(description["cost_model"]["time"]["weight"], 1.0);
+
+        // while loading the app parses the edge list, and so fails
+        let app =
+            match CompassApp::try_from((&unparseable_edges_config("speeds_test.toml"), &builder)) {
+                Err(CompassAppError::CompassConfigurationError(
+                    CompassConfigurationError::FileNormalizationNotFound(_key, _f1, _f2),
+                )) => {
+                    CompassApp::try_from((&unparseable_edges_config("speeds_debug.toml"), &builder))
+                }
+                other => other,
+            };
+        match app {
+            Ok(_) => panic!("app should fail to parse the edge list"),
+            Err(CompassAppError::CompassConfigurationError(
+                CompassConfigurationError::FileNormalizationNotFound(_key, _f1, _f2),
+            )) => panic!("app should find the configured files"),
+            Err(_) => {}
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use super::CompassAppError;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// query key holding an id assigned by the caller, which is echoed in the response
+pub const QUERY_ID: &str = "query_id";
+
+/// declares how a batch of queries that share a query id is handled.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateQueryIdPolicy {
+    /// run the batch without checking for duplicate ids
+    #[default]
+    PassThrough,
+    /// log a warning listing the duplicate ids and run the batch
+    Warn,
+    /// fail the batch without running any queries
+    Error,
+}
+
+impl DuplicateQueryIdPolicy {
+    /// applies this policy to a batch of queries, prior to input processing.
+    pub fn check(&self, queries: &[Value]) -> Result<(), CompassAppError> {
+        if *self == DuplicateQueryIdPolicy::PassThrough {
+            return Ok(());
+        }
+        let duplicates = find_duplicate_query_ids(queries);
+        if duplicates.is_empty() {
+            return Ok(());
+        }
+        let msg = format!(
+            "batch contains duplicate query ids: [{}]",
+            duplicates.iter().join(", ")
+        );
+        match self {
+            DuplicateQueryIdPolicy::Error => Err(CompassAppError::CompassFailure(msg)),
+            _ => {
+                log::warn!("{}", msg);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// finds the query ids that appear on more than one query of a batch, in the
+/// order they first appear. queries without an id are ignored.
+pub fn find_duplicate_query_ids(queries: &[Value]) -> Vec<Value> {
+    let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+    for (idx, id) in queries.iter().filter_map(|q| q.get(QUERY_ID)).enumerate() {
+        let entry = counts.entry(id.to_string()).or_insert((idx, 0));
+        entry.1 += 1;
+    }
+    queries
+        .iter()
+        .filter_map(|q| q.get(QUERY_ID))
+        .enumerate()
+        .filter(|(idx, id)| {
+            counts
+                .get(&id.to_string())
+                .is_some_and(|(first, count)| first == idx && *count > 1)
+        })
+        .map(|(_, id)| id.clone())
+        .collect()
+}
+
+/// copies the query id of a request, if present, to the top level of its response
+pub fn echo_query_id(request: &Value, response: &mut Value) {
+    if let (Some(id), Some(obj)) = (request.get(QUERY_ID), response.as_object_mut()) {
+        obj.insert(String::from(QUERY_ID), id.clone());
+    }
+}
+
+/// arranges the responses of a batch into a JSON object keyed by query id, so
+/// that downstream systems can join results to their queries by id rather than
+/// by position. string ids are used as keys as is, and other ids as their JSON
+/// text, so `1` and `"1"` share a key. when several responses share a key, as for
+/// queries expanded by an input plugin or duplicate ids allowed by the policy,
+/// they are stored as an array under that key in response order.
+///
+/// # Arguments
+///
+/// * `responses` - responses of a batch, each with a `query_id`
+/// * `policy` - how responses sharing a key are handled
+///
+/// # Returns
+///
+/// the responses keyed by query id, or an error if a response has no id or if
+/// the policy rejects responses sharing an id
+pub fn key_by_query_id(
+    responses: Vec<Value>,
+    policy: &DuplicateQueryIdPolicy,
+) -> Result<Value, CompassAppError> {
+    let mut keyed: Map<String, Value> = Map::new();
+    let mut duplicates: Vec<String> = vec![];
+    for response in responses.into_iter() {
+        let key = match response.get(QUERY_ID) {
+            Some(Value::String(id)) => id.clone(),
+            Some(id) => id.to_string(),
+            None => {
+                return Err(CompassAppError::CompassFailure(format!(
+                    "cannot key responses by '{}' as a response has none: {}",
+                    QUERY_ID, response
+                )))
+            }
+        };
+        match keyed.get_mut(&key) {
+            None => {
+                keyed.insert(key, response);
+            }
+            Some(Value::Array(shared)) if duplicates.contains(&key) => shared.push(response),
+            Some(existing) => {
+                *existing = Value::Array(vec![existing.take(), response]);
+                duplicates.push(key);
+            }
+        }
+    }
+    if !duplicates.is_empty() {
+        let msg = format!(
+            "responses share query ids: [{}]",
+            duplicates.iter().join(", ")
+        );
+        match policy {
+            DuplicateQueryIdPolicy::Error => return Err(CompassAppError::CompassFailure(msg)),
+            DuplicateQueryIdPolicy::Warn => log::warn!("{}", msg),
+            DuplicateQueryIdPolicy::PassThrough => {}
+        }
+    }
+    Ok(Value::Object(keyed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_duplicate_query_id_policy() {
+        let queries = vec![
+            json!({"query_id": "a"}),
+            json!({"query_id": 1}),
+            json!({}),
+            json!({"query_id": "a"}),
+            json!({"query_id": "1"}),
+            json!({"query_id": 1}),
+        ];
+        assert_eq!(
+            find_duplicate_query_ids(&queries),
+            vec![json!("a"), json!(1)]
+        );
+        assert!(DuplicateQueryIdPolicy::PassThrough.check(&queries).is_ok());
+        assert!(DuplicateQueryIdPolicy::Warn.check(&queries).is_ok());
+        assert!(DuplicateQueryIdPolicy::Error.check(&queries).is_err());
+        assert!(DuplicateQueryIdPolicy::Error.check(&queries[..3]).is_ok());
+    }
+
+    #[test]
+    fn test_key_by_query_id() {
+        let responses = vec![
+            json!({"query_id": "a", "cost": 1}),
+            json!({"query_id": 7, "cost": 2}),
+            json!({"query_id": "a", "cost": 3}),
+        ];
+        let keyed =
+            key_by_query_id(responses.clone(), &DuplicateQueryIdPolicy::PassThrough).unwrap();
+        assert_eq!(keyed["7"]["cost"], json!(2));
+        assert_eq!(
+            keyed["a"],
+            json!([{"query_id": "a", "cost": 1}, {"query_id": "a", "cost": 3}])
+        );
+        assert!(key_by_query_id(responses.clone(), &DuplicateQueryIdPolicy::Error).is_err());
+        assert!(key_by_query_id(responses[..2].to_vec(), &DuplicateQueryIdPolicy::Error).is_ok());
+        assert!(key_by_query_id(vec![json!({})], &DuplicateQueryIdPolicy::PassThrough).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use super::edge_usage::EdgeUsage;
+use super::CompassAppError;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use routee_compass_core::model::network::{EdgeId, Graph, VertexId};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// an approximate edge betweenness over a sample of origin-destination pairs. the
+/// importance of an edge is the share of the sampled shortest paths that traverse
+/// it, from 0 (never used) to 1 (used by every route), which highlights the
+/// bottlenecks and key corridors of a network.
+pub struct EdgeImportance {
+    /// the number of origin-destination pairs sampled
+    pub samples: usize,
+    /// the number of sampled pairs with a route
+    pub routes: u64,
+    scores: HashMap<EdgeId, f64>,
+}
+
+impl EdgeImportance {
+    /// normalizes the usage counts of the routes of a sample into importance scores
+    pub fn new(edge_usage: &EdgeUsage, samples: usize) -> EdgeImportance {
+        let routes = edge_usage.n_routes();
+        let scores = edge_usage
+            .counts()
+            .into_iter()
+            .map(|(edge_id, count)| (edge_id, count as f64 / routes as f64))
+            .collect();
+        EdgeImportance {
+            samples,
+            routes,
+            scores,
+        }
+    }
+
+    /// the importance of an edge, which is zero for edges not on any sampled route
+    pub fn get(&self, edge_id: &EdgeId) -> f64 {
+        self.scores.get(edge_id).cloned().unwrap_or_default()
+    }
+
+    /// the importance of each edge traversed by at least one sampled route
+    pub fn scores(&self) -> &HashMap<EdgeId, f64> {
+        &self.scores
+    }
+
+    /// writes the importance of each used edge to a CSV file with columns
+    /// `edge_id,importance`, sorted by edge id, for joining to edge geometries.
+    pub fn write_csv(&self, path: &Path) -> Result<(), CompassAppError> {
+        let mut writer = csv::Writer::from_path(path).map_err(|e| {
+            CompassAppError::InternalError(format!(
+                "failure opening edge importance file {}: {}",
+                path.to_string_lossy(),
+                e
+            ))
+        })?;
+        let mut rows = self.scores.iter().collect::<Vec<_>>();
+        rows.sort_by_key(|(edge_id, _)| edge_id.0);
+        writer
+            .write_record(["edge_id", "importance"])
+            .map_err(|e| CompassAppError::InternalError(e.to_string()))?;
+        for (edge_id, importance) in rows {
+            writer
+                .write_record([edge_id.to_string(), importance.to_string()])
+                .map_err(|e| CompassAppError::InternalError(e.to_string()))?;
+        }
+        writer
+            .flush()
+            .map_err(|e| CompassAppError::InternalError(e.to_string()))
+    }
+}
+
+/// draws random pairs of distinct origin and destination vertices, uniformly from
+/// the vertices of a graph that have at least one edge.
+///
+/// # Arguments
+///
+/// * `graph` - the graph to sample from
+/// * `samples` - the number of pairs to draw
+/// * `seed` - optional seed, so that a sample can be repeated
+///
+/// # Returns
+///
+/// the sampled pairs, or an error if the graph has fewer than two connected vertices
+pub fn sample_od_pairs(
+    graph: &Graph,
+    samples: usize,
+    seed: Option<u64>,
+) -> Result<Vec<(VertexId, VertexId)>, CompassAppError> {
+    let connected = graph
+        .vertex_ids()
+        .filter(|v| !graph.is_isolated(v))
+        .collect::<Vec<_>>();
+    if connected.len() < 2 {
+        return Err(CompassAppError::CompassFailure(format!(
+            "cannot sample origin-destination pairs from a graph with {} connected vertices",
+            connected.len()
+        )));
+    }
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let pairs = (0..samples)
+        .map(|_| {
+            let pair = connected
+                .choose_multiple(&mut rng, 2)
+                .cloned()
+                .collect::<Vec<_>>();
+            (pair[0], pair[1])
+        })
+        .collect();
+    Ok(pairs)
+}
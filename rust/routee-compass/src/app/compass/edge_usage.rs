@@ -0,0 +1,118 @@
+use super::CompassAppError;
+use routee_compass_core::model::network::EdgeId;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// the number of routes that traverse each edge of the graph across a batch of
+/// queries, used to find the key corridors of a set of trips. counters are atomic
+/// so that the parallel searches of a batch can record their routes concurrently.
+pub struct EdgeUsage {
+    counts: Vec<AtomicU64>,
+    routes: AtomicU64,
+}
+
+impl EdgeUsage {
+    /// creates a counter for each edge of a graph, all starting at zero
+    pub fn new(n_edges: usize) -> EdgeUsage {
+        let counts = (0..n_edges).map(|_| AtomicU64::new(0)).collect();
+        EdgeUsage {
+            counts,
+            routes: AtomicU64::new(0),
+        }
+    }
+
+    /// records a route, incrementing the count of each edge it traverses. an
+    /// edge visited more than once by the same route is counted once.
+    pub fn record(&self, route: &[EdgeId]) -> Result<(), CompassAppError> {
+        let edges: HashSet<&EdgeId> = route.iter().collect();
+        for edge_id in edges {
+            let counter = self.counts.get(edge_id.0).ok_or_else(|| {
+                CompassAppError::InternalError(format!(
+                    "edge {} is not in the edge usage table of {} edges",
+                    edge_id,
+                    self.counts.len()
+                ))
+            })?;
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+        self.routes.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// the number of routes recorded
+    pub fn n_routes(&self) -> u64 {
+        self.routes.load(Ordering::Relaxed)
+    }
+
+    /// the number of routes that traversed an edge
+    pub fn get(&self, edge_id: &EdgeId) -> u64 {
+        self.counts
+            .get(edge_id.0)
+            .map(|c| c.load(Ordering::Relaxed))
+            .unwrap_or_default()
+    }
+
+    /// the count of each edge traversed by at least one route
+    pub fn counts(&self) -> HashMap<EdgeId, u64> {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, c)| {
+                let count = c.load(Ordering::Relaxed);
+                (count > 0).then_some((EdgeId(idx), count))
+            })
+            .collect()
+    }
+
+    /// writes the count of each used edge to a CSV file with columns
+    /// `edge_id,count`, sorted by edge id.
+    pub fn write_csv(&self, path: &Path) -> Result<(), CompassAppError> {
+        let mut writer = csv::Writer::from_path(path).map_err(|e| {
+            CompassAppError::InternalError(format!(
+                "failure opening edge usage file {}: {}",
+                path.to_string_lossy(),
+                e
+            ))
+        })?;
+        let mut rows = self.counts().into_iter().collect::<Vec<_>>();
+        rows.sort_by_key(|(edge_id, _)| edge_id.0);
+        writer
+            .write_record(["edge_id", "count"])
+            .map_err(|e| CompassAppError::InternalError(e.to_string()))?;
+        for (edge_id, count) in rows {
+            writer
+                .write_record([edge_id.to_string(), count.to_string()])
+                .map_err(|e| CompassAppError::InternalError(e.to_string()))?;
+        }
+        writer
+            .flush()
+            .map_err(|e| CompassAppError::InternalError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rayon::prelude::*;
+
+    #[test]
+    fn test_record_in_parallel() {
+        let usage = EdgeUsage::new(4);
+        let routes = [
+            vec![EdgeId(0), EdgeId(1)],
+            vec![EdgeId(1), EdgeId(2)],
+            vec![EdgeId(1), EdgeId(1)],
+        ];
+        (0..100).into_par_iter().for_each(|i| {
+            usage.record(&routes[i % routes.len()]).unwrap();
+        });
+        assert_eq!(usage.get(&EdgeId(0)), 34);
+        assert_eq!(usage.get(&EdgeId(1)), 100);
+        assert_eq!(usage.get(&EdgeId(2)), 33);
+        assert_eq!(usage.get(&EdgeId(3)), 0);
+        assert!(!usage.counts().contains_key(&EdgeId(3)));
+        assert_eq!(usage.n_routes(), 100);
+        assert!(usage.record(&[EdgeId(4)]).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use serde::Serialize;
+
+/// percentiles of the trip time reported by an [`EtaDistribution`]
+pub const ETA_PERCENTILES: [f64; 5] = [5.0, 25.0, 50.0, 75.0, 95.0];
+
+/// the distribution of the trip time of a query across samples of its edge
+/// travel times, as found by Monte Carlo simulation.
+#[derive(Serialize, Clone, Debug)]
+pub struct EtaDistribution {
+    /// the number of samples run
+    pub samples: usize,
+    /// the number of samples that found a route
+    pub routes: usize,
+    /// unit of the trip times, as reported by the state model of the routes
+    pub time_unit: Option<String>,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+    /// each of the [`ETA_PERCENTILES`] paired with its trip time
+    pub percentiles: Vec<(f64, f64)>,
+    /// the trip time of each sample that found a route, in sample order
+    pub times: Vec<f64>,
+}
+
+impl EtaDistribution {
+    /// summarizes the trip times of the routes found across a set of samples.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - the number of samples run
+    /// * `times` - the trip time of each sample that found a route
+    /// * `time_unit` - unit of the trip times
+    pub fn new(samples: usize, times: Vec<f64>, time_unit: Option<String>) -> EtaDistribution {
+        let n = times.len();
+        let mean = times.iter().sum::<f64>() / n as f64;
+        let variance = times.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / n as f64;
+        let mut sorted = times.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let percentiles = ETA_PERCENTILES
+            .iter()
+            .map(|p| (*p, percentile(&sorted, *p)))
+            .collect();
+        EtaDistribution {
+            samples,
+            routes: n,
+            time_unit,
+            mean,
+            std_dev: variance.sqrt(),
+            min: sorted.first().cloned().unwrap_or(f64::NAN),
+            max: sorted.last().cloned().unwrap_or(f64::NAN),
+            percentiles,
+            times,
+        }
+    }
+}
+
+/// a percentile of sorted values, interpolating linearly between ranks
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    match sorted.len() {
+        0 => f64::NAN,
+        1 => sorted[0],
+        n => {
+            let rank = p / 100.0 * (n - 1) as f64;
+            let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+            sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eta_distribution() {
+        let times = vec![4.0, 1.0, 3.0, 2.0, 5.0];
+        let eta = EtaDistribution::new(6, times, None);
+        assert_eq!(eta.samples, 6);
+        assert_eq!(eta.routes, 5);
+        assert_eq!(eta.mean, 3.0);
+        assert_eq!(eta.std_dev, 2.0_f64.sqrt());
+        assert_eq!((eta.min, eta.max), (1.0, 5.0));
+        assert_eq!(
+            eta.percentiles,
+            vec![
+                (5.0, 1.2),
+                (25.0, 2.0),
+                (50.0, 3.0),
+                (75.0, 4.0),
+                (95.0, 4.8)
+            ]
+        );
+    }
+}
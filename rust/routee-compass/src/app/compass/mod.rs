@@ -2,14 +2,23 @@ pub mod compass_app;
 mod compass_app_builder;
 pub mod compass_app_configuration;
 mod compass_app_error;
+pub mod compass_app_fingerprint;
 pub mod compass_app_ops;
 mod compass_configuration_error;
 mod compass_configuration_field;
 pub mod compass_input_field;
 pub mod compass_json_extensions;
+pub mod compass_models;
 mod config_json_extension;
+pub mod duplicate_query_id_policy;
+pub mod edge_importance;
+pub mod edge_usage;
+pub mod eta_distribution;
 pub mod model;
+pub mod poi_cost_cache;
 pub mod response;
+#[cfg(test)]
+mod test;
 
 pub use compass_app_builder::CompassAppBuilder;
 pub use compass_app_error::CompassAppError;
@@ -22,6 +22,7 @@ impl AccessModelBuilder for CombinedAccessModelBuilder {
                     e
                 ))
             })?;
+        let mut dependencies = HashMap::new();
         let services = model_params
             .iter()
             .map(|params| {
@@ -40,9 +41,27 @@ impl AccessModelBuilder for CombinedAccessModelBuilder {
                         model_type, alts
                     ))
                 })?;
-                builder.build(params)
+                // models are referred to by type unless a name is provided, for example
+                // when listing models to disable for a query via "disable_models"
+                let name = params
+                    .get_config_serde_optional::<String>(&"name", &"combined.access_models")
+                    .map_err(|e| AccessModelError::BuildError(e.to_string()))?
+                    .unwrap_or(model_type);
+                let depends_on = params
+                    .get_config_serde_optional::<Vec<String>>(
+                        &"depends_on",
+                        &"combined.access_models",
+                    )
+                    .map_err(|e| AccessModelError::BuildError(e.to_string()))?
+                    .unwrap_or_default();
+                dependencies.insert(name.clone(), depends_on);
+                let service = builder.build(params)?;
+                Ok((name, service))
             })
-            .collect::<Result<_, _>>()?;
-        Ok(Arc::new(CombinedAccessModelService { services }))
+            .collect::<Result<_, AccessModelError>>()?;
+        Ok(Arc::new(CombinedAccessModelService {
+            services,
+            dependencies,
+        }))
     }
 }
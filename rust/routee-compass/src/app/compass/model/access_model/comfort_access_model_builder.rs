@@ -0,0 +1,86 @@
+use crate::app::compass::ConfigJsonExtensions;
+use kdam::Bar;
+use routee_compass_core::{
+    model::{
+        access::{
+            default::comfort::{
+                ComfortAccessModelEngine, ComfortAccessModelService, ComfortWeights,
+            },
+            AccessModelBuilder, AccessModelError, AccessModelService,
+        },
+        unit::Speed,
+    },
+    util::fs::{read_decoders, read_utils},
+};
+use std::sync::Arc;
+
+pub struct ComfortAccessModelBuilder {}
+
+impl AccessModelBuilder for ComfortAccessModelBuilder {
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn AccessModelService>, AccessModelError> {
+        let parent_key = String::from("comfort access model");
+        let speed_table = match parameters.get("speed_table_input_file") {
+            None => None,
+            Some(_) => {
+                let file_path = parameters
+                    .get_config_path(&"speed_table_input_file", &parent_key)
+                    .map_err(|e| AccessModelError::BuildError(e.to_string()))?;
+                let table: Box<[Speed]> = read_utils::read_raw_file(
+                    &file_path,
+                    read_decoders::default,
+                    Some(Bar::builder().desc("comfort edge speeds")),
+                    None,
+                )
+                .map_err(|e| {
+                    AccessModelError::BuildError(format!(
+                        "error reading speeds from file {:?}: {}",
+                        file_path, e
+                    ))
+                })?;
+                Some(table)
+            }
+        };
+        let stop_table = match parameters.get("stop_input_file") {
+            None => None,
+            Some(_) => {
+                let file_path = parameters
+                    .get_config_path(&"stop_input_file", &parent_key)
+                    .map_err(|e| AccessModelError::BuildError(e.to_string()))?;
+                let table: Box<[u8]> = read_utils::read_raw_file(
+                    &file_path,
+                    read_decoders::u8,
+                    Some(Bar::builder().desc("comfort vertex stops")),
+                    None,
+                )
+                .map_err(|e| {
+                    AccessModelError::BuildError(format!(
+                        "error reading stops from file {:?}: {}",
+                        file_path, e
+                    ))
+                })?;
+                Some(table.iter().map(|stop| *stop != 0).collect())
+            }
+        };
+        let weights = parameters
+            .get_config_serde_optional::<ComfortWeights>(&"weights", &parent_key)
+            .map_err(|e| AccessModelError::BuildError(e.to_string()))?
+            .unwrap_or_default();
+        let comfort_feature_name = parameters
+            .get_config_serde_optional::<String>(&"comfort_feature_name", &parent_key)
+            .map_err(|e| AccessModelError::BuildError(e.to_string()))?
+            .unwrap_or_else(|| String::from("comfort_cost"));
+        let engine = ComfortAccessModelEngine {
+            speed_table,
+            stop_table,
+            weights,
+            comfort_feature_name,
+        };
+        let service = ComfortAccessModelService {
+            engine: Arc::new(engine),
+        };
+        Ok(Arc::new(service))
+    }
+}
@@ -1,2 +1,3 @@
 pub mod combined_access_model_builder;
+pub mod comfort_access_model_builder;
 pub mod turn_delay_access_model_builder;
@@ -3,12 +3,14 @@ use kdam::Bar;
 use routee_compass_core::{
     model::access::{
         default::turn_delays::{
-            EdgeHeading, TurnDelayAccessModelEngine, TurnDelayAccessModelService, TurnDelayModel,
+            EdgeHeading, MovementDelay, TurnDelayAccessModelEngine, TurnDelayAccessModelService,
+            TurnDelayModel, TurnPenalties,
         },
         AccessModelBuilder, AccessModelError, AccessModelService,
     },
     util::fs::read_utils,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
 
 pub struct TurnDelayAccessModelBuilder {}
@@ -55,10 +57,59 @@ impl AccessModelBuilder for TurnDelayAccessModelBuilder {
                 ))
             })?
             .unwrap_or_else(|| String::from("time"));
+        let turn_penalties = parameters
+            .get_config_serde_optional::<TurnPenalties>(
+                &"turn_penalties",
+                &"turn delay access model",
+            )
+            .map_err(|e| {
+                AccessModelError::BuildError(format!(
+                    "failure reading 'turn_penalties' from access model configuration: {}",
+                    e
+                ))
+            })?;
+        let movement_delays = match parameters.get("movement_delay_input_file") {
+            None => None,
+            Some(_) => {
+                let file_path = parameters
+                    .get_config_path(&"movement_delay_input_file", &"turn delay access model")
+                    .map_err(|e| {
+                        AccessModelError::BuildError(format!(
+                            "failure reading 'movement_delay_input_file' from access model configuration: {}",
+                            e
+                        ))
+                    })?;
+                let rows = read_utils::from_csv::<MovementDelay>(
+                    &file_path.as_path(),
+                    true,
+                    Some(Bar::builder().desc("movement delays")),
+                    None,
+                )
+                .map_err(|e| {
+                    AccessModelError::BuildError(format!(
+                        "error reading movement delays from file {:?}: {}",
+                        file_path, e
+                    ))
+                })?;
+                let mut delays = HashMap::with_capacity(rows.len());
+                for row in rows.iter() {
+                    let movement = (row.from_edge_id, row.to_edge_id);
+                    if delays.insert(movement, row.delay).is_some() {
+                        return Err(AccessModelError::BuildError(format!(
+                            "movement delay file {:?} lists the movement from edge {} to edge {} more than once",
+                            file_path, row.from_edge_id, row.to_edge_id
+                        )));
+                    }
+                }
+                Some(delays)
+            }
+        };
         let engine = TurnDelayAccessModelEngine {
             edge_headings,
             turn_delay_model,
             time_feature_name,
+            turn_penalties,
+            movement_delays,
         };
         let service = TurnDelayAccessModelService {
             engine: Arc::new(engine),
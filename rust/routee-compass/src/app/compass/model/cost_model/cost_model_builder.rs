@@ -3,7 +3,7 @@ use crate::app::compass::{
     CompassConfigurationError, CompassConfigurationField, ConfigJsonExtensions,
 };
 use routee_compass_core::model::cost::{
-    network::NetworkCostRate, CostAggregation, VehicleCostRate,
+    cost_ops, network::NetworkCostRate, CostAggregation, CostExpression, VehicleCostRate,
 };
 use std::{collections::HashMap, sync::Arc};
 
@@ -18,6 +18,9 @@ impl CostModelBuilder {
         let vehicle_rates: HashMap<String, VehicleCostRate> = config
             .get_config_serde_optional(&"vehicle_rates", &parent_key)?
             .unwrap_or_default();
+        let vehicle_rates_by_model: HashMap<String, HashMap<String, VehicleCostRate>> = config
+            .get_config_serde_optional(&"vehicle_rates_by_model", &parent_key)?
+            .unwrap_or_default();
         let network_rates: HashMap<String, NetworkCostRate> = config
             .get_config_serde_optional(&"network_rates", &parent_key)?
             .unwrap_or_default();
@@ -25,21 +28,55 @@ impl CostModelBuilder {
         let weights: HashMap<String, f64> = config
             .get_config_serde_optional(&"weights", &parent_key)?
             .unwrap_or_default();
+        let normalization: HashMap<String, f64> = config
+            .get_config_serde_optional(&"normalization", &parent_key)?
+            .unwrap_or_default();
+        // checked here so that invalid references are reported when the application is built
+        cost_ops::normalize_weights(&weights, &normalization)
+            .map_err(|e| CompassConfigurationError::UserConfigurationError(e.to_string()))?;
         let cost_aggregation: CostAggregation = config
             .get_config_serde_optional(&"cost_aggregation", &parent_key)?
             .unwrap_or_default();
 
+        let expression = config
+            .get_config_serde_optional::<String>(&"expression", &parent_key)?
+            .map(|e| parse_cost_expression(&e))
+            .transpose()?;
+
         let ignore_unknown_weights = config
             .get_config_serde_optional(&"ignore_unknown_user_provided_weights", &parent_key)?
             .unwrap_or(true);
 
         let model = CostModelService {
             vehicle_rates: Arc::new(vehicle_rates),
+            vehicle_rates_by_model: Arc::new(vehicle_rates_by_model),
             network_rates: Arc::new(network_rates),
             weights: Arc::new(weights),
+            normalization: Arc::new(normalization),
             cost_aggregation,
             ignore_unknown_weights,
+            expression,
         };
         Ok(model)
     }
 }
+
+/// parses a cost expression, checking that it is non-negative so that errors
+/// are reported when the application is built.
+pub fn parse_cost_expression(
+    expression: &str,
+) -> Result<CostExpression, CompassConfigurationError> {
+    let parsed = expression.parse::<CostExpression>().map_err(|e| {
+        CompassConfigurationError::UserConfigurationError(format!(
+            "failed to parse cost expression: {}",
+            e
+        ))
+    })?;
+    if !parsed.is_non_negative() {
+        return Err(CompassConfigurationError::UserConfigurationError(format!(
+            "cost expression '{}' may be negative. use only non-negative constants, '+', '*' and division by positive constants",
+            expression
+        )));
+    }
+    Ok(parsed)
+}
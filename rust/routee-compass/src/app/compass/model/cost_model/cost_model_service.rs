@@ -1,7 +1,11 @@
+use super::cost_model_builder::parse_cost_expression;
 use crate::app::compass::CompassConfigurationError;
 use crate::app::compass::ConfigJsonExtensions;
 use routee_compass_core::model::{
-    cost::{network::NetworkCostRate, CostAggregation, CostModel, VehicleCostRate},
+    cost::{
+        cost_ops, network::NetworkCostRate, CostAggregation, CostExpression, CostModel,
+        VehicleCostRate,
+    },
     state::StateModel,
 };
 use std::{
@@ -11,10 +15,18 @@ use std::{
 
 pub struct CostModelService {
     pub vehicle_rates: Arc<HashMap<String, VehicleCostRate>>,
+    /// vehicle rates for specific vehicles, keyed by the vehicle `model_name`,
+    /// which replace the global vehicle rates of the same state variable
+    pub vehicle_rates_by_model: Arc<HashMap<String, HashMap<String, VehicleCostRate>>>,
     pub network_rates: Arc<HashMap<String, NetworkCostRate>>,
     pub weights: Arc<HashMap<String, f64>>,
+    /// reference value for each feature, which its weight is divided by
+    pub normalization: Arc<HashMap<String, f64>>,
     pub cost_aggregation: CostAggregation,
     pub ignore_unknown_weights: bool,
+    /// an expression over the state features which replaces the weighted
+    /// vehicle rates when computing the vehicle cost of each edge
+    pub expression: Option<CostExpression>,
 }
 
 impl CostModelService {
@@ -26,7 +38,10 @@ impl CostModelService {
     /// ```python
     /// {
     ///   "state_variable_names": [],  # list of state variables to convert to costs
-    ///   "cost_aggregation": ''     # operation for combining costs, 'sum' or 'mul'
+    ///   "cost_aggregation": '',    # operation for combining costs, 'sum' or 'mul'
+    ///   "model_name": '',          # vehicle used to select vehicle-specific rates
+    ///   "normalization": {},       # reference value for each feature, dividing its weight
+    ///   "cost_expression": ''      # expression replacing the weighted vehicle rates
     /// }
     /// ```
     ///
@@ -80,7 +95,8 @@ impl CostModelService {
             return Err(CompassConfigurationError::UserConfigurationError(msg));
         }
 
-        // the user can append/replace rates from the query
+        // the user can append/replace rates from the query. otherwise, the rates of the
+        // query vehicle are used, falling back to the global rates.
         let vehicle_rates = query
             .get_config_serde_optional::<HashMap<String, VehicleCostRate>>(
                 &"vehicle_rates",
@@ -88,20 +104,52 @@ impl CostModelService {
             )
             .map(|opt_rates| match opt_rates {
                 Some(rates) => Arc::new(rates),
-                None => self.vehicle_rates.clone(),
+                None => self.get_vehicle_rates(query),
             })?;
 
         let cost_aggregation: CostAggregation = query
             .get_config_serde_optional(&"cost_aggregation", &"cost_model")?
             .unwrap_or(self.cost_aggregation.to_owned());
 
-        let model = CostModel::new(
-            weights,
-            vehicle_rates,
-            self.network_rates.clone(),
-            cost_aggregation,
-            state_model,
-        )
+        let expression =
+            match query.get_config_serde_optional::<String>(&"cost_expression", &"cost_model")? {
+                Some(e) => Some(parse_cost_expression(&e)?),
+                None => self.expression.clone(),
+            };
+
+        // weights are divided by the reference value of each normalized feature
+        let normalization = query
+            .get_config_serde_optional::<HashMap<String, f64>>(&"normalization", &"cost_model")?
+            .map(Arc::new)
+            .unwrap_or(self.normalization.clone());
+        let weights = match normalization.is_empty() {
+            true => weights,
+            false => Arc::new(
+                cost_ops::normalize_weights(&weights, &normalization).map_err(|e| {
+                    CompassConfigurationError::UserConfigurationError(format!(
+                        "failed to build cost model: {}",
+                        e
+                    ))
+                })?,
+            ),
+        };
+
+        let model = match expression {
+            Some(expression) => CostModel::from_expression(
+                &expression,
+                weights,
+                self.network_rates.clone(),
+                cost_aggregation,
+                state_model,
+            ),
+            None => CostModel::new(
+                weights,
+                vehicle_rates,
+                self.network_rates.clone(),
+                cost_aggregation,
+                state_model,
+            ),
+        }
         .map_err(|e| {
             CompassConfigurationError::UserConfigurationError(format!(
                 "failed to build cost model: {}",
@@ -111,4 +159,25 @@ impl CostModelService {
 
         Ok(model)
     }
+
+    /// the vehicle rates for the vehicle named by the query `model_name`, with
+    /// the global vehicle rates used for any state variable the vehicle does
+    /// not define a rate for.
+    fn get_vehicle_rates(
+        &self,
+        query: &serde_json::Value,
+    ) -> Arc<HashMap<String, VehicleCostRate>> {
+        let model_rates = query
+            .get("model_name")
+            .and_then(|v| v.as_str())
+            .and_then(|model_name| self.vehicle_rates_by_model.get(model_name));
+        match model_rates {
+            None => self.vehicle_rates.clone(),
+            Some(rates) => {
+                let mut merged = self.vehicle_rates.as_ref().clone();
+                merged.extend(rates.clone());
+                Arc::new(merged)
+            }
+        }
+    }
 }
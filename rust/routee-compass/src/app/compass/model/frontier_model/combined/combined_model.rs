@@ -1,6 +1,6 @@
 use routee_compass_core::model::{
     frontier::{FrontierModel, FrontierModelError},
-    network::Edge,
+    network::{Edge, EdgeId},
     state::{StateModel, StateVariable},
 };
 use std::sync::Arc;
@@ -13,6 +13,7 @@ impl FrontierModel for CombinedFrontierModel {
     fn valid_frontier(
         &self,
         edge: &Edge,
+        previous_edge_id: Option<EdgeId>,
         state: &[StateVariable],
         tree: &std::collections::HashMap<
             routee_compass_core::model::network::VertexId,
@@ -24,7 +25,14 @@ impl FrontierModel for CombinedFrontierModel {
         // If any of the inner models return an invalid frontier, it invalidates the whole set and we
         // return an early false. We only return true if all the frontiers are valid.
         for frontier_model in self.inner_models.iter() {
-            if !frontier_model.valid_frontier(edge, state, tree, direction, state_model)? {
+            if !frontier_model.valid_frontier(
+                edge,
+                previous_edge_id,
+                state,
+                tree,
+                direction,
+                state_model,
+            )? {
                 return Ok(false);
             }
         }
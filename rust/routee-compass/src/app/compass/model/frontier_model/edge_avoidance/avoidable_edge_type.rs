@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// a type of edge that a query can ask to avoid with a boolean toggle
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AvoidableEdgeType {
+    Ferry,
+    Toll,
+}
+
+impl AvoidableEdgeType {
+    pub const ALL: [AvoidableEdgeType; 2] = [AvoidableEdgeType::Ferry, AvoidableEdgeType::Toll];
+
+    /// configuration key for the file flagging edges of this type
+    pub fn input_file_key(&self) -> &'static str {
+        match self {
+            AvoidableEdgeType::Ferry => "ferry_input_file",
+            AvoidableEdgeType::Toll => "toll_input_file",
+        }
+    }
+
+    /// query key for the boolean that activates avoidance of this type
+    pub fn query_key(&self) -> &'static str {
+        match self {
+            AvoidableEdgeType::Ferry => "avoid_ferries",
+            AvoidableEdgeType::Toll => "avoid_tolls",
+        }
+    }
+}
+
+impl std::fmt::Display for AvoidableEdgeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = serde_json::to_string(self)
+            .map_err(|_| std::fmt::Error)?
+            .replace('\"', "");
+        write!(f, "{}", s)
+    }
+}
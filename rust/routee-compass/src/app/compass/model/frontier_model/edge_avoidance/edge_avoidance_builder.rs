@@ -0,0 +1,71 @@
+use super::{
+    avoidable_edge_type::AvoidableEdgeType, edge_avoidance_service::EdgeAvoidanceFrontierService,
+};
+use crate::app::compass::{CompassConfigurationField, ConfigJsonExtensions};
+use kdam::Bar;
+use routee_compass_core::{
+    model::frontier::{FrontierModelBuilder, FrontierModelError, FrontierModelService},
+    util::fs::{read_decoders, read_utils},
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// builds a frontier model that removes ferry or toll edges from the search
+/// when a query sets `avoid_ferries` or `avoid_tolls` to true.
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [frontier]
+/// type = "edge_avoidance"
+/// # one row per edge, 1 for ferry edges and 0 otherwise
+/// ferry_input_file = "edges-ferry-enumerated.txt.gz"
+/// # one row per edge, 1 for toll edges and 0 otherwise
+/// toll_input_file = "edges-toll-enumerated.txt.gz"
+/// ```
+///
+/// either file may be omitted, in which case queries may not ask to avoid
+/// that type of edge.
+pub struct EdgeAvoidanceBuilder {}
+
+impl FrontierModelBuilder for EdgeAvoidanceBuilder {
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn FrontierModelService>, FrontierModelError> {
+        let frontier_key = CompassConfigurationField::Frontier.to_string();
+
+        let mut edge_flags = HashMap::new();
+        for edge_type in AvoidableEdgeType::ALL {
+            let file_key = edge_type.input_file_key();
+            let file_option = parameters
+                .get_config_path_optional(&file_key, &frontier_key)
+                .map_err(|e| {
+                    FrontierModelError::BuildError(format!(
+                        "configuration error due to {}: {}",
+                        file_key, e
+                    ))
+                })?;
+            if let Some(file) = file_option {
+                let flags: Box<[u8]> = read_utils::read_raw_file(
+                    &file,
+                    read_decoders::u8,
+                    Some(Bar::builder().desc(format!("{} edges", edge_type))),
+                    None,
+                )
+                .map_err(|e| {
+                    FrontierModelError::BuildError(format!(
+                        "failed to load file at {:?}: {}",
+                        file.to_str(),
+                        e
+                    ))
+                })?;
+                let flags: Box<[bool]> = flags.iter().map(|f| *f != 0).collect();
+                edge_flags.insert(edge_type, Arc::new(flags));
+            }
+        }
+
+        let m: Arc<dyn FrontierModelService> =
+            Arc::new(EdgeAvoidanceFrontierService { edge_flags });
+        Ok(m)
+    }
+}
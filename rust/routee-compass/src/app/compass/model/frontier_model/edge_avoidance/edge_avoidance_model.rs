@@ -0,0 +1,44 @@
+use super::avoidable_edge_type::AvoidableEdgeType;
+use routee_compass_core::{
+    algorithm::search::{Direction, SearchTreeBranch},
+    model::{
+        frontier::{FrontierModel, FrontierModelError},
+        network::{Edge, EdgeId, VertexId},
+        state::{StateModel, StateVariable},
+    },
+};
+use std::{collections::HashMap, sync::Arc};
+
+pub struct EdgeAvoidanceFrontierModel {
+    /// the edge types this query avoids, with the per-edge flags for each
+    pub avoid: Vec<(AvoidableEdgeType, Arc<Box<[bool]>>)>,
+}
+
+impl FrontierModel for EdgeAvoidanceFrontierModel {
+    fn valid_frontier(
+        &self,
+        edge: &Edge,
+        _previous_edge_id: Option<EdgeId>,
+        _state: &[StateVariable],
+        _tree: &HashMap<VertexId, SearchTreeBranch>,
+        _direction: &Direction,
+        _state_model: &StateModel,
+    ) -> Result<bool, FrontierModelError> {
+        self.valid_edge(edge)
+    }
+
+    fn valid_edge(&self, edge: &Edge) -> Result<bool, FrontierModelError> {
+        for (edge_type, flags) in self.avoid.iter() {
+            let flagged = flags.get(edge.edge_id.0).ok_or_else(|| {
+                FrontierModelError::FrontierModelError(format!(
+                    "edge id {} missing from {} edge file",
+                    edge.edge_id, edge_type
+                ))
+            })?;
+            if *flagged {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
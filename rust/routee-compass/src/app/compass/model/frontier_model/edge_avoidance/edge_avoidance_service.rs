@@ -0,0 +1,48 @@
+use super::{
+    avoidable_edge_type::AvoidableEdgeType, edge_avoidance_model::EdgeAvoidanceFrontierModel,
+};
+use routee_compass_core::model::{
+    frontier::{FrontierModel, FrontierModelError, FrontierModelService},
+    state::StateModel,
+};
+use std::{collections::HashMap, sync::Arc};
+
+#[derive(Clone)]
+pub struct EdgeAvoidanceFrontierService {
+    /// for each configured edge type, whether each edge (by edge id) is of that type
+    pub edge_flags: HashMap<AvoidableEdgeType, Arc<Box<[bool]>>>,
+}
+
+impl FrontierModelService for EdgeAvoidanceFrontierService {
+    fn build(
+        &self,
+        query: &serde_json::Value,
+        _state_model: Arc<StateModel>,
+    ) -> Result<Arc<dyn FrontierModel>, FrontierModelError> {
+        let mut avoid = vec![];
+        for edge_type in AvoidableEdgeType::ALL {
+            let key = edge_type.query_key();
+            let active = match query.get(key) {
+                None => false,
+                Some(value) => value.as_bool().ok_or_else(|| {
+                    FrontierModelError::BuildError(format!(
+                        "query field {} must be a boolean, found {}",
+                        key, value
+                    ))
+                })?,
+            };
+            if !active {
+                continue;
+            }
+            let flags = self.edge_flags.get(&edge_type).ok_or_else(|| {
+                FrontierModelError::BuildError(format!(
+                    "query sets {} but no {} is configured for the frontier model",
+                    key,
+                    edge_type.input_file_key()
+                ))
+            })?;
+            avoid.push((edge_type, flags.clone()));
+        }
+        Ok(Arc::new(EdgeAvoidanceFrontierModel { avoid }))
+    }
+}
@@ -0,0 +1,4 @@
+pub mod avoidable_edge_type;
+pub mod edge_avoidance_builder;
+pub mod edge_avoidance_model;
+pub mod edge_avoidance_service;
@@ -0,0 +1,77 @@
+use super::gradeability_service::GradeabilityFrontierService;
+use crate::app::compass::{CompassConfigurationField, ConfigJsonExtensions};
+use kdam::Bar;
+use routee_compass_core::{
+    model::{
+        frontier::{FrontierModelBuilder, FrontierModelError, FrontierModelService},
+        unit::Grade,
+    },
+    util::fs::{read_decoders, read_utils},
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// builds a frontier model that removes edges steeper than the query vehicle can
+/// climb. this is a hard feasibility limit, separate from the energy cost of
+/// climbing. vehicles without a maximum grade are not limited.
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [frontier]
+/// type = "gradeability"
+/// # one row per edge, uphill positive
+/// grade_input_file = "edges-grade-enumerated.txt.gz"
+/// # the steepest grade each vehicle can climb, keyed by the query model_name,
+/// # in the unit of the grade file
+/// [frontier.max_grade]
+/// heavy_truck = 0.08
+/// ```
+///
+/// a query may also set `max_grade` to replace the maximum of its vehicle.
+pub struct GradeabilityBuilder {}
+
+impl FrontierModelBuilder for GradeabilityBuilder {
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn FrontierModelService>, FrontierModelError> {
+        let frontier_key = CompassConfigurationField::Frontier.to_string();
+        let grade_file = parameters
+            .get_config_path(&"grade_input_file", &frontier_key)
+            .map_err(|e| {
+                FrontierModelError::BuildError(format!(
+                    "configuration error due to grade_input_file: {}",
+                    e
+                ))
+            })?;
+        let max_grades: HashMap<String, f64> = parameters
+            .get_config_serde_optional(&"max_grade", &frontier_key)
+            .map_err(|e| {
+                FrontierModelError::BuildError(format!(
+                    "configuration error due to max_grade: {}",
+                    e
+                ))
+            })?
+            .unwrap_or_default();
+
+        let grades: Box<[Grade]> = read_utils::read_raw_file(
+            &grade_file,
+            read_decoders::default,
+            Some(Bar::builder().desc("edge grades")),
+            None,
+        )
+        .map_err(|e| {
+            FrontierModelError::BuildError(format!(
+                "failed to load file at {:?}: {}",
+                grade_file.to_str(),
+                e
+            ))
+        })?;
+
+        let m: Arc<dyn FrontierModelService> = Arc::new(GradeabilityFrontierService {
+            grades: Arc::new(grades),
+            max_grades,
+        });
+        Ok(m)
+    }
+}
@@ -0,0 +1,46 @@
+use routee_compass_core::{
+    algorithm::search::{Direction, SearchTreeBranch},
+    model::{
+        frontier::{FrontierModel, FrontierModelError},
+        network::{Edge, EdgeId, VertexId},
+        state::{StateModel, StateVariable},
+        unit::{AsF64, Grade},
+    },
+};
+use std::{collections::HashMap, sync::Arc};
+
+pub struct GradeabilityFrontierModel {
+    /// the grade of each edge, uphill positive, by edge id
+    pub grades: Arc<Box<[Grade]>>,
+    /// the steepest uphill grade the query vehicle can climb, in the unit of
+    /// the grade table, or None if the vehicle is not limited
+    pub max_grade: Option<f64>,
+}
+
+impl FrontierModel for GradeabilityFrontierModel {
+    fn valid_frontier(
+        &self,
+        edge: &Edge,
+        _previous_edge_id: Option<EdgeId>,
+        _state: &[StateVariable],
+        _tree: &HashMap<VertexId, SearchTreeBranch>,
+        _direction: &Direction,
+        _state_model: &StateModel,
+    ) -> Result<bool, FrontierModelError> {
+        self.valid_edge(edge)
+    }
+
+    fn valid_edge(&self, edge: &Edge) -> Result<bool, FrontierModelError> {
+        let max_grade = match self.max_grade {
+            None => return Ok(true),
+            Some(max_grade) => max_grade,
+        };
+        let grade = self.grades.get(edge.edge_id.0).ok_or_else(|| {
+            FrontierModelError::FrontierModelError(format!(
+                "edge id {} missing from grade table",
+                edge.edge_id
+            ))
+        })?;
+        Ok(grade.as_f64() <= max_grade)
+    }
+}
@@ -0,0 +1,44 @@
+use super::gradeability_model::GradeabilityFrontierModel;
+use routee_compass_core::model::{
+    frontier::{FrontierModel, FrontierModelError, FrontierModelService},
+    state::StateModel,
+    unit::Grade,
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// query key for a maximum grade that replaces the configured maximum of the vehicle
+pub const MAX_GRADE: &str = "max_grade";
+
+#[derive(Clone)]
+pub struct GradeabilityFrontierService {
+    /// the grade of each edge, uphill positive, by edge id
+    pub grades: Arc<Box<[Grade]>>,
+    /// the steepest uphill grade each vehicle can climb, keyed by the vehicle `model_name`
+    pub max_grades: HashMap<String, f64>,
+}
+
+impl FrontierModelService for GradeabilityFrontierService {
+    fn build(
+        &self,
+        query: &serde_json::Value,
+        _state_model: Arc<StateModel>,
+    ) -> Result<Arc<dyn FrontierModel>, FrontierModelError> {
+        let max_grade = match query.get(MAX_GRADE) {
+            Some(value) => Some(value.as_f64().ok_or_else(|| {
+                FrontierModelError::BuildError(format!(
+                    "query field {} must be a number, found {}",
+                    MAX_GRADE, value
+                ))
+            })?),
+            None => query
+                .get("model_name")
+                .and_then(|v| v.as_str())
+                .and_then(|model_name| self.max_grades.get(model_name))
+                .cloned(),
+        };
+        Ok(Arc::new(GradeabilityFrontierModel {
+            grades: self.grades.clone(),
+            max_grade,
+        }))
+    }
+}
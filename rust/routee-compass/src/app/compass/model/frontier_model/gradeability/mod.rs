@@ -0,0 +1,3 @@
+pub mod gradeability_builder;
+pub mod gradeability_model;
+pub mod gradeability_service;
@@ -1,4 +1,6 @@
 pub mod combined;
+pub mod edge_avoidance;
+pub mod gradeability;
 pub mod no_restriction_builder;
 pub mod road_class;
 pub mod turn_restrictions;
@@ -3,7 +3,7 @@ use routee_compass_core::{
     algorithm::search::SearchTreeBranch,
     model::{
         frontier::{FrontierModel, FrontierModelError},
-        network::{Edge, VertexId},
+        network::{Edge, EdgeId, VertexId},
         state::{StateModel, StateVariable},
     },
 };
@@ -21,6 +21,7 @@ impl FrontierModel for RoadClassFrontierModel {
     fn valid_frontier(
         &self,
         edge: &Edge,
+        _previous_edge_id: Option<EdgeId>,
         _state: &[StateVariable],
         _tree: &HashMap<VertexId, SearchTreeBranch>,
         _direction: &routee_compass_core::algorithm::search::Direction,
@@ -2,7 +2,7 @@ use routee_compass_core::{
     algorithm::search::{Direction, SearchTreeBranch},
     model::{
         frontier::{FrontierModel, FrontierModelError},
-        network::{Edge, VertexId},
+        network::{Edge, EdgeId, VertexId},
         state::{StateModel, StateVariable},
     },
 };
@@ -18,31 +18,74 @@ impl FrontierModel for TurnRestrictionFrontierModel {
     fn valid_frontier(
         &self,
         edge: &Edge,
+        previous_edge_id: Option<EdgeId>,
         _state: &[StateVariable],
-        tree: &HashMap<VertexId, SearchTreeBranch>,
+        _tree: &HashMap<VertexId, SearchTreeBranch>,
         direction: &Direction,
         _state_model: &StateModel,
     ) -> Result<bool, FrontierModelError> {
-        let previous_edge = match direction {
-            Direction::Forward => tree.get(&edge.src_vertex_id),
-            Direction::Reverse => tree.get(&edge.dst_vertex_id),
+        let Some(previous_edge_id) = previous_edge_id else {
+            return Ok(true);
         };
-        match previous_edge {
-            None => Ok(true),
-            Some(previous_edge) => {
-                let edge_pair = RestrictedEdgePair {
-                    prev_edge_id: previous_edge.edge_traversal.edge_id,
-                    next_edge_id: edge.edge_id,
-                };
-                if self.service.restricted_edge_pairs.contains(&edge_pair) {
-                    return Ok(false);
-                }
-                Ok(true)
-            }
-        }
+        // a reverse search reaches the edges of a trip in reverse order
+        let edge_pair = match direction {
+            Direction::Forward => RestrictedEdgePair {
+                prev_edge_id: previous_edge_id,
+                next_edge_id: edge.edge_id,
+            },
+            Direction::Reverse => RestrictedEdgePair {
+                prev_edge_id: edge.edge_id,
+                next_edge_id: previous_edge_id,
+            },
+        };
+        Ok(!self.service.restricted_edge_pairs.contains(&edge_pair))
     }
 
     fn valid_edge(&self, _edge: &Edge) -> Result<bool, FrontierModelError> {
         Ok(true)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn model() -> TurnRestrictionFrontierModel {
+        let restricted = HashSet::from([RestrictedEdgePair {
+            prev_edge_id: EdgeId(0),
+            next_edge_id: EdgeId(1),
+        }]);
+        TurnRestrictionFrontierModel {
+            service: Arc::new(TurnRestrictionFrontierService {
+                restricted_edge_pairs: Arc::new(restricted),
+            }),
+        }
+    }
+
+    fn valid(previous: Option<usize>, next: usize, direction: Direction) -> bool {
+        let edge = Edge::new(next, 0, 1, 1.0);
+        model()
+            .valid_frontier(
+                &edge,
+                previous.map(EdgeId),
+                &[],
+                &HashMap::new(),
+                &direction,
+                &StateModel::empty(),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_restricted_turn() {
+        // the turn from edge 0 onto edge 1 is forbidden
+        assert!(!valid(Some(0), 1, Direction::Forward));
+        assert!(valid(Some(0), 2, Direction::Forward));
+        assert!(valid(Some(1), 0, Direction::Forward));
+        assert!(valid(None, 1, Direction::Forward));
+        // a reverse search reaches edge 0 after edge 1
+        assert!(!valid(Some(1), 0, Direction::Reverse));
+        assert!(valid(Some(0), 1, Direction::Reverse));
+    }
+}
@@ -4,7 +4,7 @@ use super::{
 };
 use routee_compass_core::model::{
     frontier::{FrontierModel, FrontierModelError},
-    network::Edge,
+    network::{Edge, EdgeId},
     state::{StateModel, StateVariable},
 };
 use std::sync::Arc;
@@ -18,6 +18,7 @@ impl FrontierModel for VehicleRestrictionFrontierModel {
     fn valid_frontier(
         &self,
         edge: &Edge,
+        _previous_edge_id: Option<EdgeId>,
         _state: &[StateVariable],
         _tree: &std::collections::HashMap<
             routee_compass_core::model::network::VertexId,
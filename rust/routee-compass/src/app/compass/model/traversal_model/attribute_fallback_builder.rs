@@ -0,0 +1,94 @@
+use crate::app::compass::ConfigJsonExtensions;
+use kdam::Bar;
+use routee_compass_core::model::traversal::attribute_fallback::{
+    AttributeFallback, AttributeFallbackChain,
+};
+use routee_compass_core::model::traversal::TraversalModelError;
+use routee_compass_core::util::fs::{read_decoders, read_utils};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// configuration for one level of an attribute fallback chain
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AttributeFallbackConfig<T> {
+    RoadClass {
+        road_class_input_file: String,
+        defaults: HashMap<u8, T>,
+    },
+    Global {
+        value: T,
+    },
+}
+
+/// reads an optional fallback chain from a traversal model configuration. the
+/// chain is a list of levels under `fallback_key`, consulted in order. if a
+/// `record_key` is given, the source of each edge's value is recorded when the
+/// configuration sets that key to true.
+///
+/// # Arguments
+///
+/// * `params` - the traversal model configuration
+/// * `fallback_key` - key of the list of fallback levels
+/// * `record_key` - optional key of the flag to record the source of each value
+/// * `parent_key` - name of the configuration section, for errors
+///
+/// # Returns
+///
+/// the fallback chain, None if no fallbacks are configured, or an error
+/// if the configuration is invalid or a road class file cannot be read
+pub fn build_fallback_chain<T: DeserializeOwned>(
+    params: &serde_json::Value,
+    fallback_key: &str,
+    record_key: Option<&str>,
+    parent_key: &str,
+) -> Result<Option<AttributeFallbackChain<T>>, TraversalModelError> {
+    let configs = params
+        .get_config_serde_optional::<Vec<AttributeFallbackConfig<T>>>(&fallback_key, &parent_key)
+        .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+    let configs = match configs {
+        None => return Ok(None),
+        Some(configs) => configs,
+    };
+    let record_source = match record_key {
+        None => false,
+        Some(key) => params
+            .get_config_serde_optional::<bool>(&key, &parent_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+            .unwrap_or_default(),
+    };
+
+    let mut fallbacks = vec![];
+    for config in configs.into_iter() {
+        let fallback = match config {
+            AttributeFallbackConfig::RoadClass {
+                road_class_input_file,
+                defaults,
+            } => {
+                let road_classes: Box<[u8]> = read_utils::read_raw_file(
+                    &road_class_input_file,
+                    read_decoders::u8,
+                    Some(Bar::builder().desc("road class")),
+                    None,
+                )
+                .map_err(|e| {
+                    TraversalModelError::BuildError(format!(
+                        "failed to load {} road class file {}: {}",
+                        fallback_key, road_class_input_file, e
+                    ))
+                })?;
+                AttributeFallback::RoadClass {
+                    road_classes,
+                    defaults,
+                }
+            }
+            AttributeFallbackConfig::Global { value } => AttributeFallback::Global(value),
+        };
+        fallbacks.push(fallback);
+    }
+    Ok(Some(AttributeFallbackChain {
+        fallbacks,
+        record_source,
+    }))
+}
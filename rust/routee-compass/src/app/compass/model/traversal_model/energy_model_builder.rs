@@ -1,14 +1,23 @@
 use crate::app::compass::ConfigJsonExtensions;
 use itertools::Itertools;
+use kdam::Bar;
+use routee_compass_core::model::access::default::turn_delays::EdgeHeading;
+use routee_compass_core::model::network::Edge;
 use routee_compass_core::model::traversal::TraversalModelBuilder;
 use routee_compass_core::model::traversal::TraversalModelError;
 use routee_compass_core::model::traversal::TraversalModelService;
-use routee_compass_core::model::unit::{DistanceUnit, GradeUnit, SpeedUnit, TimeUnit};
+use routee_compass_core::model::unit::{DistanceUnit, Grade, GradeUnit, SpeedUnit, TimeUnit};
+use routee_compass_core::util::fs::{read_decoders, read_utils};
+use routee_compass_powertrain::model::elevation_grade::{self, GradeSource};
 use routee_compass_powertrain::model::energy_model_service::EnergyModelService;
+use routee_compass_powertrain::model::grade_sign::GradeSign;
+use routee_compass_powertrain::model::temperature::{EdgeTemperature, TemperatureModel};
+use routee_compass_powertrain::model::wind::{Wind, WindField, WindModel};
 use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::Arc;
 
+use super::attribute_fallback_builder::build_fallback_chain;
 use super::energy_model_vehicle_builders::VehicleBuilder;
 
 pub struct EnergyModelBuilder {
@@ -56,6 +65,10 @@ impl TraversalModelBuilder for EnergyModelBuilder {
         let grade_table_grade_unit = params
             .get_config_serde::<GradeUnit>(&"grade_table_grade_unit", &parent_key)
             .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        let grade_sign = params
+            .get_config_serde_optional::<GradeSign>(&"grade_sign", &parent_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+            .unwrap_or_default();
 
         let vehicle_configs = params
             .get_config_array(&"vehicles", &parent_key)
@@ -83,16 +96,292 @@ impl TraversalModelBuilder for EnergyModelBuilder {
             .get_config_serde_optional::<DistanceUnit>(&"distance_unit", &parent_key)
             .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
 
-        let service = EnergyModelService::new(
-            time_model_service,
-            time_model_speed_unit,
-            &grade_table_path_option,
-            grade_table_grade_unit,
-            time_unit_option,
-            distance_unit_option,
-            vehicle_library,
-        )?;
+        // grades come from either a grade table file or a column of a shared edge attributes file
+        let grade_column = params
+            .get_config_serde_optional::<String>(&"grade_column", &parent_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        // missing grades in a grade table may be filled from an optional chain of fallbacks
+        let fallback = build_fallback_chain::<Grade>(params, "grade_fallback", None, &parent_key)?;
+        let service = match (grade_column, fallback) {
+            (None, None) => EnergyModelService::new(
+                time_model_service,
+                time_model_speed_unit,
+                &grade_table_path_option,
+                grade_table_grade_unit,
+                grade_sign,
+                time_unit_option,
+                distance_unit_option,
+                vehicle_library,
+            )?,
+            (None, Some(fallback)) => {
+                let filename = params
+                    .get_config_path(&"grade_table_input_file", &parent_key)
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+                let table: Box<[Option<Grade>]> = read_utils::read_raw_file(
+                    &filename,
+                    read_decoders::optional,
+                    Some(Bar::builder().desc("link grades")),
+                    None,
+                )
+                .map_err(|e| {
+                    TraversalModelError::BuildError(format!(
+                        "failure reading grade table {} due to {}",
+                        filename.to_str().unwrap_or_default(),
+                        e
+                    ))
+                })?;
+                let resolved = fallback.resolve("grade", &table)?;
+                EnergyModelService::new_with_grade_table(
+                    time_model_service,
+                    time_model_speed_unit,
+                    Some(resolved.values),
+                    grade_table_grade_unit,
+                    grade_sign,
+                    time_unit_option,
+                    distance_unit_option,
+                    vehicle_library,
+                )?
+            }
+            (Some(_), Some(_)) => return Err(TraversalModelError::BuildError(String::from(
+                "grade_fallback is only supported with grade_table_input_file, not grade_column",
+            ))),
+            (Some(column), None) => {
+                let filename = params
+                    .get_config_path(&"edge_attributes_input_file", &parent_key)
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+                let grades: Box<[Grade]> = read_utils::read_edge_attribute_column(
+                    &filename,
+                    &column,
+                    Some(Bar::builder().desc("link grades")),
+                )
+                .map_err(|e| {
+                    TraversalModelError::BuildError(format!(
+                        "failure reading column {} of {} due to {}",
+                        column,
+                        filename.to_str().unwrap_or_default(),
+                        e
+                    ))
+                })?;
+                EnergyModelService::new_with_grade_table(
+                    time_model_service,
+                    time_model_speed_unit,
+                    Some(grades),
+                    grade_table_grade_unit,
+                    grade_sign,
+                    time_unit_option,
+                    distance_unit_option,
+                    vehicle_library,
+                )?
+            }
+        };
+
+        // optionally check or replace the grade table with grades derived from vertex elevations
+        let service = match params.get("grade_from_elevation") {
+            Some(elevation_params) => {
+                let (grades, source, tolerance) =
+                    build_elevation_grades(elevation_params, grade_table_grade_unit)?;
+                service.with_elevation_grades(grades, source, tolerance)?
+            }
+            None => service,
+        };
+
+        // optionally adjust energy for the wind along each edge
+        let service = match params.get("wind") {
+            Some(wind_params) => service.with_wind_model(build_wind_model(wind_params)?)?,
+            None => service,
+        };
+
+        // optionally record the temperature along each edge
+        let service = match params.get("temperature") {
+            Some(temperature_params) => {
+                service.with_temperature_model(build_temperature_model(temperature_params)?)
+            }
+            None => service,
+        };
 
         Ok(Arc::new(service))
     }
 }
+
+/// derives edge grades from the `grade_from_elevation` section of the energy model
+/// configuration, which has a `vertex_elevation_input_file` with an elevation for
+/// each vertex and the graph's `edge_list_input_file`. the grades, and the tolerance
+/// for disagreements with the grade table, are in the grade table unit.
+fn build_elevation_grades(
+    params: &serde_json::Value,
+    grade_unit: GradeUnit,
+) -> Result<(Box<[Grade]>, GradeSource, Grade), TraversalModelError> {
+    let parent_key = String::from("energy traversal model grade_from_elevation");
+    let elevation_file = params
+        .get_config_path(&"vertex_elevation_input_file", &parent_key)
+        .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+    let elevations: Box<[f64]> = read_utils::read_raw_file(
+        &elevation_file,
+        read_decoders::f64,
+        Some(Bar::builder().desc("vertex elevations")),
+        None,
+    )
+    .map_err(|e| {
+        TraversalModelError::BuildError(format!(
+            "failure reading vertex elevations {} due to {}",
+            elevation_file.to_str().unwrap_or_default(),
+            e
+        ))
+    })?;
+    let edge_file = params
+        .get_config_path(&"edge_list_input_file", &parent_key)
+        .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+    let edges = read_utils::from_csv::<Edge>(
+        &edge_file.as_path(),
+        true,
+        Some(Bar::builder().desc("graph edges")),
+        None,
+    )
+    .map_err(|e| {
+        TraversalModelError::BuildError(format!(
+            "error reading edges from file {:?}: {}",
+            edge_file, e
+        ))
+    })?;
+    let elevation_unit = params
+        .get_config_serde_optional::<DistanceUnit>(&"elevation_unit", &parent_key)
+        .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+        .unwrap_or(DistanceUnit::Meters);
+    let source = params
+        .get_config_serde_optional::<GradeSource>(&"source", &parent_key)
+        .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+        .unwrap_or_default();
+    let tolerance = params
+        .get_config_serde_optional::<f64>(&"tolerance", &parent_key)
+        .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+        .map(Grade::new)
+        .unwrap_or_else(|| {
+            GradeUnit::Percent.convert(
+                &Grade::new(elevation_grade::DEFAULT_TOLERANCE_PERCENT),
+                &grade_unit,
+            )
+        });
+    let grades =
+        elevation_grade::grades_from_elevation(&edges, &elevations, elevation_unit, grade_unit)?;
+    Ok((grades, source, tolerance))
+}
+
+/// builds a wind model from the `wind` section of the energy model configuration.
+/// the wind is either a regional `speed` and `direction`, or a `wind_input_file`
+/// with `speed` and `direction` columns and a row for each edge.
+fn build_wind_model(params: &serde_json::Value) -> Result<WindModel, TraversalModelError> {
+    let parent_key = String::from("energy traversal model wind");
+    let heading_file = params
+        .get_config_path(&"edge_heading_input_file", &parent_key)
+        .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+    let edge_headings = read_utils::from_csv::<EdgeHeading>(
+        &heading_file.as_path(),
+        true,
+        Some(Bar::builder().desc("edge headings")),
+        None,
+    )
+    .map_err(|e| {
+        TraversalModelError::BuildError(format!(
+            "error reading headings from file {:?}: {}",
+            heading_file, e
+        ))
+    })?;
+    let speed_unit = params
+        .get_config_serde::<SpeedUnit>(&"speed_unit", &parent_key)
+        .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+    let get_f64 = |key: &str| {
+        params
+            .get_config_serde_optional::<f64>(&key, &parent_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))
+    };
+    let wind_file = params
+        .get_config_path_optional(&"wind_input_file", &parent_key)
+        .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+    let wind = match (wind_file, get_f64("speed")?, get_f64("direction")?) {
+        (Some(wind_file), None, None) => {
+            let winds = read_utils::from_csv::<Wind>(
+                &wind_file.as_path(),
+                true,
+                Some(Bar::builder().desc("edge winds")),
+                None,
+            )
+            .map_err(|e| {
+                TraversalModelError::BuildError(format!(
+                    "error reading winds from file {:?}: {}",
+                    wind_file, e
+                ))
+            })?;
+            WindField::PerEdge(winds)
+        }
+        (None, Some(speed), Some(direction)) => WindField::Regional(Wind { speed, direction }),
+        _ => {
+            return Err(TraversalModelError::BuildError(format!(
+                "{} requires either a wind_input_file or a regional speed and direction",
+                parent_key
+            )))
+        }
+    };
+    Ok(WindModel {
+        wind,
+        speed_unit,
+        edge_headings,
+        drag_area: get_f64("drag_area")?.unwrap_or(WindModel::DEFAULT_DRAG_AREA),
+        mass_kg: get_f64("mass_kg")?.unwrap_or(WindModel::DEFAULT_MASS_KG),
+    })
+}
+
+/// builds a temperature model from the `temperature` section of the energy model
+/// configuration, which has an `ambient_temperature` and an optional
+/// `edge_temperature_input_file` with `edge_id` and `temperature` columns.
+fn build_temperature_model(
+    params: &serde_json::Value,
+) -> Result<TemperatureModel, TraversalModelError> {
+    let parent_key = String::from("energy traversal model temperature");
+    let ambient_temperature = params
+        .get_config_serde::<f64>(&"ambient_temperature", &parent_key)
+        .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+    let temperature_file = params
+        .get_config_path_optional(&"edge_temperature_input_file", &parent_key)
+        .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+    let edge_temperatures = match temperature_file {
+        None => None,
+        Some(file_path) => {
+            let rows = read_utils::from_csv::<EdgeTemperature>(
+                &file_path.as_path(),
+                true,
+                Some(Bar::builder().desc("edge temperatures")),
+                None,
+            )
+            .map_err(|e| {
+                TraversalModelError::BuildError(format!(
+                    "error reading temperatures from file {:?}: {}",
+                    file_path, e
+                ))
+            })?;
+            let mut temperatures = HashMap::with_capacity(rows.len());
+            for row in rows.iter() {
+                if temperatures.insert(row.edge_id, row.temperature).is_some() {
+                    return Err(TraversalModelError::BuildError(format!(
+                        "edge temperature file {:?} lists edge {} more than once",
+                        file_path, row.edge_id
+                    )));
+                }
+            }
+            Some(temperatures)
+        }
+    };
+    let temperature_unit = params
+        .get_config_serde_optional::<String>(&"temperature_unit", &parent_key)
+        .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+        .unwrap_or_else(|| String::from(TemperatureModel::DEFAULT_TEMPERATURE_UNIT));
+    let temperature_feature_name = params
+        .get_config_serde_optional::<String>(&"temperature_feature_name", &parent_key)
+        .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+        .unwrap_or_else(|| String::from(TemperatureModel::DEFAULT_FEATURE_NAME));
+    Ok(TemperatureModel {
+        edge_temperatures,
+        ambient_temperature,
+        temperature_unit,
+        temperature_feature_name,
+    })
+}
@@ -8,10 +8,13 @@ use routee_compass_core::util::cache_policy::float_cache_policy::{
     FloatCachePolicy, FloatCachePolicyConfig,
 };
 use routee_compass_powertrain::model::{
-    prediction::{load_prediction_model, ModelType, PredictionModelRecord},
+    prediction::{
+        load_prediction_model, EnergyCalibration, EnergyRateBounds, ModelType, OnnxThreads,
+        PredictionModelRecord,
+    },
     vehicle::{
         default::{BEV, ICE, PHEV},
-        VehicleType,
+        ColdStart, RegenBraking, VehicleType,
     },
 };
 use std::sync::Arc;
@@ -54,7 +57,10 @@ fn build_conventional(
 
     let model_record = get_model_record_from_params(parameters, &name)?;
 
-    let vehicle = ICE::new(name, model_record)?;
+    let cold_start =
+        parameters.get_config_serde_optional::<ColdStart>(&"cold_start", &vehicle_key)?;
+
+    let vehicle = ICE::new(name, model_record)?.with_cold_start(cold_start);
 
     Ok(Arc::new(vehicle))
 }
@@ -70,6 +76,11 @@ fn build_battery_electric(
     let battery_energy_unit =
         parameters.get_config_serde::<EnergyUnit>(&"battery_capacity_unit", &"bev")?;
     let starting_battery_energy = battery_capacity;
+    let regen_braking =
+        parameters.get_config_serde_optional::<RegenBraking>(&"regen_braking", &"bev")?;
+    if let Some(regen) = &regen_braking {
+        regen.validate()?;
+    }
 
     let vehicle = BEV::new(
         name,
@@ -77,7 +88,8 @@ fn build_battery_electric(
         battery_capacity,
         starting_battery_energy,
         battery_energy_unit,
-    );
+    )
+    .with_regen_braking(regen_braking);
 
     Ok(Arc::new(vehicle))
 }
@@ -146,6 +158,13 @@ fn get_model_record_from_params(
         None => None,
     };
 
+    let energy_rate_bounds = parameters
+        .get_config_serde_optional::<EnergyRateBounds>(&"energy_rate_bounds", &parent_key)?;
+    let calibration =
+        parameters.get_config_serde_optional::<EnergyCalibration>(&"calibration", &parent_key)?;
+    let onnx_threads =
+        parameters.get_config_serde_optional::<OnnxThreads>(&"onnx_threads", &parent_key)?;
+
     let model_record = load_prediction_model(
         name.clone(),
         &model_path,
@@ -156,7 +175,15 @@ fn get_model_record_from_params(
         ideal_energy_rate_option,
         real_world_energy_adjustment_option,
         cache,
+        onnx_threads,
     )?;
 
-    Ok(model_record)
+    let model_record = match energy_rate_bounds {
+        Some(bounds) => model_record.with_energy_rate_bounds(bounds),
+        None => model_record,
+    };
+    match calibration {
+        Some(calibration) => Ok(model_record.with_calibration(calibration)?),
+        None => Ok(model_record),
+    }
 }
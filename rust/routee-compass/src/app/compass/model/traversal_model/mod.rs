@@ -1,3 +1,4 @@
+pub mod attribute_fallback_builder;
 pub mod distance_traversal_builder;
 pub mod energy_model_builder;
 pub mod energy_model_vehicle_builders;
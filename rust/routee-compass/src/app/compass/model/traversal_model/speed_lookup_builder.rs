@@ -1,11 +1,13 @@
+use super::attribute_fallback_builder::build_fallback_chain;
 use crate::app::compass::CompassConfigurationField;
 use crate::app::compass::ConfigJsonExtensions;
 use routee_compass_core::model::traversal::default::SpeedLookupService;
 use routee_compass_core::model::traversal::default::SpeedTraversalEngine;
+use routee_compass_core::model::traversal::default::SurfacePenalty;
 use routee_compass_core::model::traversal::TraversalModelBuilder;
 use routee_compass_core::model::traversal::TraversalModelError;
 use routee_compass_core::model::traversal::TraversalModelService;
-use routee_compass_core::model::unit::{DistanceUnit, SpeedUnit, TimeUnit};
+use routee_compass_core::model::unit::{DistanceUnit, Speed, SpeedUnit, TimeUnit};
 use std::sync::Arc;
 
 pub struct SpeedLookupBuilder {}
@@ -17,9 +19,6 @@ impl TraversalModelBuilder for SpeedLookupBuilder {
     ) -> Result<Arc<dyn TraversalModelService>, TraversalModelError> {
         let traversal_key = CompassConfigurationField::Traversal.to_string();
         // todo: optional output time unit
-        let filename = params
-            .get_config_path(&"speed_table_input_file", &traversal_key)
-            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
         let speed_unit = params
             .get_config_serde::<SpeedUnit>(&"speed_unit", &traversal_key)
             .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
@@ -30,7 +29,131 @@ impl TraversalModelBuilder for SpeedLookupBuilder {
             .get_config_serde_optional::<TimeUnit>(&"time_unit", &traversal_key)
             .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
 
-        let e = SpeedTraversalEngine::new(&filename, speed_unit, distance_unit, time_unit)?;
+        // speeds come from either a speed table file or a column of a shared edge attributes file
+        let speed_column = params
+            .get_config_serde_optional::<String>(&"speed_column", &traversal_key)
+            .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+        // missing speeds in a speed table may be filled from an optional chain of fallbacks
+        let fallback = build_fallback_chain::<Speed>(
+            params,
+            "speed_fallback",
+            Some("record_speed_source"),
+            &traversal_key,
+        )?;
+        let engine = match (speed_column, fallback) {
+            (None, None) => {
+                let filename = params
+                    .get_config_path(&"speed_table_input_file", &traversal_key)
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+                SpeedTraversalEngine::new(&filename, speed_unit, distance_unit, time_unit)?
+            }
+            (None, Some(fallback)) => {
+                let filename = params
+                    .get_config_path(&"speed_table_input_file", &traversal_key)
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+                SpeedTraversalEngine::new_with_fallback(
+                    &filename,
+                    &fallback,
+                    speed_unit,
+                    distance_unit,
+                    time_unit,
+                )?
+            }
+            (Some(_), Some(_)) => return Err(TraversalModelError::BuildError(String::from(
+                "speed_fallback is only supported with speed_table_input_file, not speed_column",
+            ))),
+            (Some(column), None) => {
+                let filename = params
+                    .get_config_path(&"edge_attributes_input_file", &traversal_key)
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+                SpeedTraversalEngine::from_edge_attributes(
+                    &filename,
+                    &column,
+                    speed_unit,
+                    distance_unit,
+                    time_unit,
+                )?
+            }
+        };
+
+        // optionally smooth noisy speeds across topologically neighboring edges
+        let e = match params.get("speed_smoothing") {
+            None => engine,
+            Some(smoothing) => {
+                let edge_list_file = smoothing
+                    .get_config_path(&"edge_list_input_file", &"speed_smoothing")
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+                let window = smoothing
+                    .get_config_serde::<usize>(&"window", &"speed_smoothing")
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+                engine.with_smoothing(&edge_list_file, window)?
+            }
+        };
+
+        // optionally replace speeds by typical speeds across the week, such as from probe data
+        let e = match params.get("speed_profile_input_file") {
+            None => e,
+            Some(_) => {
+                let profile_file = params
+                    .get_config_path(&"speed_profile_input_file", &traversal_key)
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+                e.with_speed_profile(&profile_file)?
+            }
+        };
+        // optionally cap speeds during time-windowed speed limits such as school zones
+        let e = match params.get("speed_schedule_input_file") {
+            None => e,
+            Some(_) => {
+                let schedule_file = params
+                    .get_config_path(&"speed_schedule_input_file", &traversal_key)
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+                e.with_speed_schedule(&schedule_file)?
+            }
+        };
+        // optionally charge tolls that vary by time of day, such as congestion pricing
+        let e = match params.get("toll_schedule_input_file") {
+            None => e,
+            Some(_) => {
+                let toll_file = params
+                    .get_config_path(&"toll_schedule_input_file", &traversal_key)
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+                e.with_toll_schedule(&toll_file)?
+            }
+        };
+        // optionally track travel time variance to penalize unreliable edges
+        let e = match params.get("time_variance_input_file") {
+            None => e,
+            Some(_) => {
+                let variance_file = params
+                    .get_config_path(&"time_variance_input_file", &traversal_key)
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+                e.with_time_variance(&variance_file)?
+            }
+        };
+        // optionally penalize edges by road surface, such as gravel or dirt
+        let e = match params.get("surface_input_file") {
+            None => e,
+            Some(_) => {
+                let surface_file = params
+                    .get_config_path(&"surface_input_file", &traversal_key)
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+                let surface_penalty = params
+                    .get_config_serde_optional::<SurfacePenalty>(&"surface_penalty", &traversal_key)
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?
+                    .unwrap_or_default();
+                e.with_surface(&surface_file, surface_penalty)?
+            }
+        };
+        // optionally track each numeric column of an edge attributes file as a custom feature
+        let e = match params.get("custom_features_input_file") {
+            None => e,
+            Some(_) => {
+                let custom_file = params
+                    .get_config_path(&"custom_features_input_file", &traversal_key)
+                    .map_err(|e| TraversalModelError::BuildError(e.to_string()))?;
+                e.with_custom_features(&custom_file)?
+            }
+        };
         let service = Arc::new(SpeedLookupService { e: Arc::new(e) });
         Ok(service)
     }
@@ -0,0 +1,90 @@
+use super::{CompassAppError, CompassConfigurationField, ConfigJsonExtensions};
+use crate::app::search::SearchApp;
+use kdam::{Bar, BarExt};
+use rayon::prelude::*;
+use routee_compass_core::model::{network::VertexId, unit::Cost};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// the costs between every pair of a small set of points of interest, such as
+/// depots, computed once when the application is built so that queries between
+/// them are lookups instead of searches. costs are computed with the default
+/// model parameters, as if by a query with no parameters.
+///
+/// configured with a list of vertex ids:
+///
+/// ```toml
+/// [poi_cost_cache]
+/// vertex_ids = [0, 15, 742]
+/// ```
+pub struct PoiCostCache {
+    costs: HashMap<VertexId, HashMap<VertexId, Cost>>,
+}
+
+impl PoiCostCache {
+    /// builds the cache from the optional `[poi_cost_cache]` section of the
+    /// application configuration. returns None if the section is missing.
+    pub fn from_config(
+        config: &serde_json::Value,
+        search_app: &SearchApp,
+    ) -> Result<Option<PoiCostCache>, CompassAppError> {
+        let key = CompassConfigurationField::PoiCostCache;
+        let section = match config.get(key.to_str()) {
+            None => return Ok(None),
+            Some(section) => section,
+        };
+        let vertex_ids: Vec<VertexId> = section.get_config_serde(&"vertex_ids", &key)?;
+        let cache = PoiCostCache::new(&vertex_ids, search_app)?;
+        Ok(Some(cache))
+    }
+
+    /// computes the costs between every pair of points of interest. each search
+    /// from a point of interest stops once all other points of interest are reached.
+    pub fn new(pois: &[VertexId], search_app: &SearchApp) -> Result<PoiCostCache, CompassAppError> {
+        let poi_set: HashSet<VertexId> = pois.iter().cloned().collect();
+        let query = serde_json::json!({});
+        let bar = Bar::builder()
+            .total(poi_set.len())
+            .animation("fillup")
+            .desc("poi cost cache")
+            .build()
+            .map_err(|e| {
+                CompassAppError::InternalError(format!("could not build progress bar: {}", e))
+            })?;
+        let bar = Mutex::new(bar);
+        let costs = poi_set
+            .par_iter()
+            .map(|src| {
+                let nearest = search_app.nearest_pois(*src, &poi_set, poi_set.len(), &query)?;
+                if let Ok(mut bar) = bar.lock() {
+                    let _ = bar.update(1);
+                }
+                Ok((*src, nearest.into_iter().collect::<HashMap<_, _>>()))
+            })
+            .collect::<Result<HashMap<_, _>, CompassAppError>>()?;
+        eprintln!();
+        Ok(PoiCostCache { costs })
+    }
+
+    /// the cached cost from one point of interest to another
+    ///
+    /// # Returns
+    ///
+    /// the cost, None if the destination is not reachable from the source, or
+    /// an error if either vertex is not a point of interest
+    pub fn get(&self, src: &VertexId, dst: &VertexId) -> Result<Option<Cost>, CompassAppError> {
+        let from_src = self.costs.get(src).ok_or_else(|| {
+            CompassAppError::CompassFailure(format!(
+                "vertex {} is not a cached point of interest",
+                src
+            ))
+        })?;
+        if !self.costs.contains_key(dst) {
+            return Err(CompassAppError::CompassFailure(format!(
+                "vertex {} is not a cached point of interest",
+                dst
+            )));
+        }
+        Ok(from_src.get(dst).cloned())
+    }
+}
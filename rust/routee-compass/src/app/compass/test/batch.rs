@@ -0,0 +1,370 @@
+//! app-level tests of running batches of queries: chunked runs, cost matrices,
+//! edge usage tallies and query ids.
+
+use crate::app::compass::test::{load_test_app, load_test_app_with};
+use routee_compass_core::model::network::{EdgeId, VertexId};
+use routee_compass_core::model::unit::{AsF64, Cost};
+use std::collections::{HashMap, HashSet};
+
+#[test]
+fn test_run_chunks_matches_batch_in_order() {
+    let app = load_test_app("speeds");
+
+    // vertex 2 has no out edges, so the last query fails its search
+    let od_pairs = [(0, 1), (0, 2), (1, 2), (0, 1), (2, 0)];
+    let queries = od_pairs
+        .iter()
+        .enumerate()
+        .map(|(i, (o, d))| {
+            serde_json::json!({
+                "query_id": i,
+                "origin_vertex": o,
+                "destination_vertex": d
+            })
+        })
+        .collect::<Vec<_>>();
+    let batch = app.run(&mut queries.clone(), None).unwrap();
+    let chunks = app
+        .run_chunks(queries.clone(), 2)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        chunks.iter().map(|c| c.len()).collect::<Vec<_>>(),
+        vec![2, 2, 1]
+    );
+    let streamed = chunks.into_iter().flatten().collect::<Vec<_>>();
+
+    assert_eq!(streamed.len(), queries.len());
+    for (i, result) in streamed.iter().enumerate() {
+        assert_eq!(result["request"]["query_id"], serde_json::json!(i));
+        let expected = batch
+            .iter()
+            .find(|r| r["request"]["query_id"] == serde_json::json!(i))
+            .expect("batch should have a result for each query");
+        for key in ["path", "traversal_summary", "cost"] {
+            assert_eq!(result["route"][key], expected["route"][key]);
+        }
+        assert_eq!(result.get("error"), expected.get("error"));
+    }
+    assert!(streamed[4].get("error").is_some());
+}
+
+#[test]
+fn test_edge_usage_matches_route_tally() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "plugin": {
+                "output_plugins": [
+                    {"type": "summary"},
+                    {"type": "traversal", "route": "edge_id", "vertex_sequence": true}
+                ]
+            }
+        }),
+    );
+
+    let mut queries = vec![
+        serde_json::json!({"origin_vertex": 0, "destination_vertex": 2}),
+        serde_json::json!({"origin_vertex": 0, "destination_vertex": 1}),
+        serde_json::json!({"origin_vertex": 1, "destination_vertex": 2}),
+        serde_json::json!({"origin_vertex": 0, "destination_vertex": 2}),
+    ];
+    let (results, edge_usage) = app.run_with_edge_usage(&mut queries, None).unwrap();
+    assert_eq!(results.len(), 4, "expected one result per query");
+
+    let mut tally: HashMap<EdgeId, u64> = HashMap::new();
+    for result in results.iter() {
+        let edge_ids: Vec<EdgeId> = serde_json::from_value(result["route"]["path"].clone())
+            .expect("route path should be edge ids");
+        for edge_id in edge_ids {
+            *tally.entry(edge_id).or_default() += 1;
+        }
+    }
+    assert_eq!(edge_usage.counts(), tally);
+    assert_eq!(edge_usage.get(&EdgeId(0)), 3);
+    assert_eq!(edge_usage.get(&EdgeId(1)), 0);
+    assert_eq!(edge_usage.get(&EdgeId(2)), 3);
+}
+
+#[test]
+fn test_run_cost_only_matches_full_response() {
+    let app = load_test_app("speeds");
+    let queries = vec![
+        serde_json::json!({"origin_vertex": 0, "destination_vertex": 2}),
+        serde_json::json!({"origin_vertex": 1, "destination_vertex": 2}),
+    ];
+    let full = app.run_in_order(&queries).unwrap();
+    let costs = app.run_cost_only(&queries, &[]).unwrap();
+    assert_eq!(costs.len(), full.len());
+    for (cost, response) in costs.iter().zip(full.iter()) {
+        // the search minimizes the weighted cost of each feature
+        let route = &response["route"];
+        let expected = ["distance", "time"]
+            .iter()
+            .map(|f| {
+                let weight = route["cost_model"][f]["weight"].as_f64().unwrap();
+                weight * route["cost"][f].as_f64().unwrap()
+            })
+            .sum::<f64>();
+        let cost = cost
+            .as_f64()
+            .expect("cost only response should be a number");
+        assert!((cost - expected).abs() < 1e-9, "{} != {}", cost, expected);
+        // the response is a fraction of the size of the full response
+        assert!(cost.to_string().len() * 10 < response.to_string().len());
+    }
+
+    let features = vec![String::from("distance"), String::from("time")];
+    let with_features = app.run_cost_only(&queries[0..1], &features).unwrap();
+    let summary = &full[0]["route"]["traversal_summary"];
+    assert_eq!(with_features[0]["distance"], summary["distance"]);
+    assert_eq!(with_features[0]["time"], summary["time"]);
+    assert_eq!(
+        with_features[0].as_object().unwrap().len(),
+        3,
+        "only the cost and listed features are returned"
+    );
+
+    let unknown = app
+        .run_cost_only(&queries[0..1], &[String::from("energy")])
+        .unwrap();
+    assert!(unknown[0]["error"].is_string());
+}
+
+#[test]
+fn test_duplicate_query_id_policy() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "plugin": {
+                "output_plugins": [
+                    {"type": "summary"},
+                    {"type": "traversal", "route": "edge_id", "vertex_sequence": true}
+                ]
+            }
+        }),
+    );
+
+    // vertex 2 has no out edges, so the last query fails its search
+    let queries = vec![
+        serde_json::json!({"query_id": "a", "origin_vertex": 0, "destination_vertex": 2}),
+        serde_json::json!({"query_id": "b", "origin_vertex": 0, "destination_vertex": 1}),
+        serde_json::json!({"query_id": "a", "origin_vertex": 1, "destination_vertex": 2}),
+        serde_json::json!({"query_id": "c", "origin_vertex": 2, "destination_vertex": 0}),
+    ];
+    let run_with_policy = |policy: &str| {
+        let config = serde_json::json!({ "duplicate_query_id_policy": policy });
+        app.run(&mut queries.clone(), Some(&config))
+    };
+
+    let error = run_with_policy("error");
+    assert!(error.is_err(), "duplicate ids should fail the batch");
+    assert!(error.unwrap_err().to_string().contains("\"a\""));
+
+    for policy in ["warn", "pass_through"] {
+        let results = run_with_policy(policy).unwrap();
+        assert_eq!(results.len(), queries.len());
+        let mut ids = results
+            .iter()
+            .map(|r| r["query_id"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, vec!["a", "a", "b", "c"]);
+        for result in results.iter() {
+            assert_eq!(result["query_id"], result["request"]["query_id"]);
+        }
+    }
+
+    // the configured default is to pass duplicates through
+    assert_eq!(app.run(&mut queries.clone(), None).unwrap().len(), 4);
+    let unique = &queries[1..];
+    assert!(app
+        .run(
+            &mut unique.to_vec(),
+            Some(&serde_json::json!({"duplicate_query_id_policy": "error"}))
+        )
+        .is_ok());
+
+    // keyed by id, the responses sharing id "a" are grouped in query order
+    let keyed = app.run_keyed(&mut queries.clone(), None).unwrap();
+    let keys = keyed
+        .as_object()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(keys.len(), 3);
+    assert_eq!(keyed["b"]["query_id"], serde_json::json!("b"));
+    assert_eq!(
+        keyed["b"]["request"]["destination_vertex"],
+        serde_json::json!(1)
+    );
+    assert!(keyed["c"].get("error").is_some());
+    let shared = keyed["a"].as_array().unwrap();
+    assert_eq!(shared.len(), 2);
+    assert!(shared
+        .iter()
+        .all(|r| r["query_id"] == serde_json::json!("a")));
+    let keyed_error = Some(serde_json::json!({"duplicate_query_id_policy": "error"}));
+    assert!(app
+        .run_keyed(&mut queries.clone(), keyed_error.as_ref())
+        .is_err());
+    let keyed_unique = app
+        .run_keyed(&mut unique.to_vec(), keyed_error.as_ref())
+        .unwrap();
+    assert_eq!(
+        keyed_unique["a"]["request"]["origin_vertex"],
+        serde_json::json!(1)
+    );
+}
+
+#[test]
+fn test_run_matrix() {
+    let app = load_test_app("speeds");
+
+    let origins = vec![VertexId(0), VertexId(2)];
+    let destinations = vec![VertexId(2), VertexId(1), VertexId(0)];
+    let matrix = app
+        .run_matrix(origins, destinations.clone(), &serde_json::json!({}))
+        .unwrap();
+    assert_eq!(matrix.len(), 2);
+
+    // each cost matches the travel time of the route found by a one-to-one search,
+    // as this configuration weights time only
+    for (destination, cost) in destinations.iter().zip(matrix[0].iter()) {
+        if *destination == VertexId(0) {
+            assert_eq!(*cost, Some(Cost::ZERO));
+            continue;
+        }
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": destination.0
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        let route_time = result[0]["route"]["cost"]["time"].as_f64().unwrap();
+        assert!((cost.unwrap().as_f64() - route_time).abs() < 1e-9);
+    }
+
+    // there are no edges out of vertex 2
+    assert_eq!(matrix[1], vec![Some(Cost::ZERO), None, None]);
+}
+
+#[test]
+fn test_edge_importance() {
+    let app = load_test_app("bottleneck");
+
+    let importance = app.run_edge_importance(200, Some(42), None).unwrap();
+    assert_eq!(importance.samples, 200);
+    assert_eq!(importance.routes, 200);
+
+    // every pair between the two triangles crosses the bridge, in one direction
+    let bridge = importance.get(&EdgeId(6)) + importance.get(&EdgeId(7));
+    assert!(
+        (0.45..0.75).contains(&bridge),
+        "bridge importance {}",
+        bridge
+    );
+    let (most_important, _) = importance
+        .scores()
+        .iter()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .unwrap();
+    assert!([EdgeId(6), EdgeId(7)].contains(most_important));
+    for (edge_id, score) in importance.scores() {
+        assert!(*score > 0.0 && *score <= 1.0);
+        if ![EdgeId(6), EdgeId(7)].contains(edge_id) {
+            assert!(*score < importance.get(&EdgeId(6)).min(importance.get(&EdgeId(7))));
+        }
+    }
+
+    // the same seed repeats the sample
+    let repeated = app.run_edge_importance(200, Some(42), None).unwrap();
+    assert_eq!(importance.scores(), repeated.scores());
+}
+
+#[test]
+fn test_poi_cost_cache() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({"poi_cost_cache": {"vertex_ids": [0, 2]}}),
+    );
+
+    // the cached cost matches the travel time of a fresh search, as this
+    // configuration weights time only
+    let cached = app.poi_cost(VertexId(0), VertexId(2)).unwrap().unwrap();
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2
+    });
+    let result = app.run(&mut [query], None).unwrap();
+    let route_time = result[0]["route"]["cost"]["time"].as_f64().unwrap();
+    assert!((cached.as_f64() - route_time).abs() < 1e-9);
+
+    assert_eq!(
+        app.poi_cost(VertexId(0), VertexId(0)).unwrap(),
+        Some(Cost::ZERO)
+    );
+    // there are no edges out of vertex 2
+    assert_eq!(app.poi_cost(VertexId(2), VertexId(0)).unwrap(), None);
+    // vertex 1 is not a point of interest
+    assert!(app.poi_cost(VertexId(0), VertexId(1)).is_err());
+}
+
+#[test]
+fn test_nearest_pois() {
+    let app = load_test_app("speeds");
+
+    let params = serde_json::json!({});
+    let pois = HashSet::from([VertexId(1), VertexId(2)]);
+    let nearest = app.nearest_pois(VertexId(0), &pois, 2, &params).unwrap();
+    let ids: Vec<VertexId> = nearest.iter().map(|(v, _)| *v).collect();
+    assert_eq!(ids, vec![VertexId(1), VertexId(2)]);
+
+    // each cost matches the travel time of the route found by a one-to-one search,
+    // as this configuration weights time only
+    for (vertex_id, cost) in nearest.iter() {
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": vertex_id.0
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        let route_time = result[0]["route"]["cost"]["time"].as_f64().unwrap();
+        assert!((cost.as_f64() - route_time).abs() < 1e-9);
+    }
+
+    let nearest_one = app.nearest_pois(VertexId(0), &pois, 1, &params).unwrap();
+    assert_eq!(nearest_one, nearest[..1].to_vec());
+}
+
+#[test]
+fn test_missing_query_field() {
+    let app = load_test_app("speeds");
+    let mut queries = vec![
+        serde_json::json!({
+            "query_id": "incomplete",
+            "origin_x": -105.1683038,
+            "origin_y": 39.7379033,
+            "destination_y": 41.1480000
+        }),
+        serde_json::json!({
+            "query_id": "valid",
+            "origin_vertex": 0,
+            "destination_vertex": 1
+        }),
+    ];
+    let results = app.run(&mut queries, None).unwrap();
+    assert_eq!(results.len(), 2);
+    let by_id = |id: &str| {
+        results
+            .iter()
+            .find(|r| r["query_id"] == serde_json::json!(id))
+            .unwrap()
+    };
+    assert_eq!(
+        by_id("incomplete")["error"],
+        serde_json::json!("missing field: destination_x")
+    );
+    let valid = by_id("valid");
+    assert!(valid.get("error").is_none(), "{}", valid);
+    assert_eq!(valid["route"]["path"], serde_json::json!([0]));
+}
@@ -0,0 +1,71 @@
+//! app-level tests of the language bindings: the batch graph methods match
+//! their singular counterparts.
+
+use crate::app::bindings::CompassAppBindings;
+use crate::app::compass::compass_app::CompassApp;
+use crate::app::compass::test::load_test_app;
+use crate::app::compass::{CompassAppBuilder, CompassAppError};
+
+struct TestBindings {
+    app: CompassApp,
+}
+
+impl CompassAppBindings for TestBindings {
+    fn from_config_toml_string(
+        config_string: String,
+        original_file_path: String,
+    ) -> Result<Self, CompassAppError> {
+        let app = CompassApp::try_from_config_toml_string(
+            config_string,
+            original_file_path,
+            &CompassAppBuilder::default(),
+        )?;
+        Ok(TestBindings { app })
+    }
+
+    fn app(&self) -> &CompassApp {
+        &self.app
+    }
+}
+
+#[test]
+fn test_batch_graph_methods_match_singular() {
+    let bindings = TestBindings {
+        app: load_test_app("speeds"),
+    };
+
+    let edge_ids = vec![0, 1, 2];
+    for distance_unit in [None, Some(String::from("kilometers"))] {
+        let batch = bindings
+            .graph_edge_distances(edge_ids.clone(), distance_unit.clone())
+            .unwrap();
+        let singular = edge_ids
+            .iter()
+            .map(|edge_id| {
+                bindings
+                    .graph_edge_distance(*edge_id, distance_unit.clone())
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(batch, singular);
+    }
+
+    // the speeds network has 3 edges, so edge 3 fails in both methods
+    assert!(bindings.graph_edge_distance(3, None).is_err());
+    assert!(bindings.graph_edge_distances(vec![0, 3], None).is_err());
+
+    let vertex_ids = vec![0, 1, 2];
+    let out_edges = vertex_ids
+        .iter()
+        .map(|vertex_id| bindings.graph_get_out_edge_ids(*vertex_id))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        bindings.graph_get_out_edge_ids_batch(vertex_ids.clone()),
+        out_edges
+    );
+    let in_edges = vertex_ids
+        .iter()
+        .map(|vertex_id| bindings.graph_get_in_edge_ids(*vertex_id))
+        .collect::<Vec<_>>();
+    assert_eq!(bindings.graph_get_in_edge_ids_batch(vertex_ids), in_edges);
+}
@@ -0,0 +1,167 @@
+//! app-level tests of building the app from its configuration.
+
+use crate::app::compass::compass_app::CompassApp;
+use crate::app::compass::test::{
+    load_test_app, load_test_app_with, test_dir, try_load_test_app_with,
+};
+use crate::app::compass::CompassAppBuilder;
+use crate::app::compass::{CompassAppError, CompassConfigurationError};
+use routee_compass_core::model::network::{EdgeId, VertexId};
+use std::path::PathBuf;
+
+#[test]
+fn test_fingerprint() {
+    // builds the app from an edited copy of the configuration text
+    let build = |edit: &dyn Fn(String) -> String| {
+        let from_file = |path: PathBuf| {
+            let text = std::fs::read_to_string(&path).unwrap();
+            CompassApp::try_from_config_toml_string(
+                edit(text),
+                path.to_string_lossy().to_string(),
+                &CompassAppBuilder::default(),
+            )
+        };
+        match from_file(test_dir("speeds").join("speeds_test.toml")) {
+            Err(CompassAppError::CompassConfigurationError(
+                CompassConfigurationError::FileNormalizationNotFound(_key, _f1, _f2),
+            )) => from_file(test_dir("speeds").join("speeds_debug.toml")),
+            result => result,
+        }
+        .unwrap()
+    };
+    let fingerprint = |app: &CompassApp| app.search_app.fingerprint.clone().unwrap();
+
+    let app = build(&|text| text);
+    let commented = build(&|text| format!("# a comment about this configuration\n{}", text));
+    assert_eq!(fingerprint(&app), fingerprint(&commented));
+
+    let reweighted = load_test_app_with(
+        "speeds",
+        serde_json::json!({"cost": {"weights": {"time": 2}}}),
+    );
+    assert_eq!(fingerprint(&app).graph, fingerprint(&reweighted).graph);
+    assert_ne!(fingerprint(&app).config, fingerprint(&reweighted).config);
+
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2
+    });
+    let result = app.run(&mut [query], None).unwrap();
+    assert_eq!(
+        result[0]["fingerprint"],
+        serde_json::json!(fingerprint(&app))
+    );
+}
+
+#[test]
+fn test_graph_filter_by_road_class() {
+    let unfiltered = {
+        load_test_app_with(
+            "speeds",
+            serde_json::json!({
+                "plugin": {
+                    "output_plugins": [
+                        {"type": "summary"},
+                        {
+                            "type": "traversal",
+                            "route": "edge_id",
+                            "vertex_sequence": true
+                        }
+                    ]
+                }
+            }),
+        )
+    };
+    let filtered = {
+        load_test_app_with(
+            "speeds",
+            serde_json::json!({
+                "graph": {
+                    "filter": {
+                        "road_class_input_file": "test_edge_road_class.csv",
+                        "exclude_road_classes": ["service"]
+                    }
+                }
+            }),
+        )
+    };
+
+    // the fastest route uses edge 2, a service road
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2
+    });
+    let result = unfiltered.run(&mut [query.clone()], None).unwrap();
+    let edge_ids: Vec<EdgeId> = serde_json::from_value(result[0]["route"]["path"].clone())
+        .expect("route path should be edge ids");
+    assert_eq!(edge_ids, vec![EdgeId(0), EdgeId(2)]);
+
+    // without service roads, the route takes the direct edge instead
+    let graph = &filtered.search_app.graph;
+    assert!(!graph.contains_edge(&EdgeId(2)));
+    assert!(graph.contains_edge(&EdgeId(0)));
+    assert!(graph.contains_edge(&EdgeId(1)));
+    assert!(graph.out_edges(&VertexId(1)).is_empty());
+    let result = filtered.run(&mut [query], None).unwrap();
+    let edge_ids: Vec<EdgeId> = serde_json::from_value(result[0]["route"]["path"].clone())
+        .expect("route path should be edge ids");
+    assert_eq!(edge_ids, vec![EdgeId(1)]);
+
+    // vertex 2 is no longer reachable from vertex 1
+    let query = serde_json::json!({
+        "origin_vertex": 1,
+        "destination_vertex": 2
+    });
+    let result = filtered.run(&mut [query], None).unwrap();
+    assert!(result[0].get("error").is_some(), "expected no route");
+}
+
+#[test]
+fn test_compute_only_without_geometry() {
+    let compute_only_mapping = serde_json::json!({
+        "mapping": {
+            "type": "vertex",
+            "queries_without_destinations": false,
+            "load_geometry": false
+        }
+    });
+    let full = load_test_app("speeds");
+    let compute_only = load_test_app_with("speeds", compute_only_mapping.clone());
+
+    let full_bytes = full.search_app.map_model.geometry_model.allocated_bytes();
+    let compute_only_bytes = compute_only
+        .search_app
+        .map_model
+        .geometry_model
+        .allocated_bytes();
+    assert!(!compute_only.search_app.map_model.geometry_model.is_loaded());
+    assert_eq!(compute_only_bytes, 0);
+    assert!(compute_only_bytes < full_bytes);
+
+    // costs are still computed without geometries
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2
+    });
+    let result = compute_only.run(&mut [query], None).unwrap();
+    assert_eq!(result[0]["route"]["path"], serde_json::json!([0, 2]));
+
+    // a plugin that outputs geometries is rejected at configuration time
+    let mut geometry_output = compute_only_mapping;
+    geometry_output["plugin"] = serde_json::json!({
+        "output_plugins": [
+            {"type": "summary"},
+            {"type": "traversal", "route": "geo_json"}
+        ]
+    });
+    let error = try_load_test_app_with("speeds", geometry_output)
+        .err()
+        .expect("geometry output should be rejected without geometries");
+    assert!(
+        error
+            .to_string()
+            .contains("[traversal] require edge geometries"),
+        "unexpected error: {}",
+        error
+    );
+}
@@ -0,0 +1,564 @@
+//! app-level tests of route costs: cost model weights, rates and expressions,
+//! and the traversal and access models that add costs.
+
+use crate::app::compass::compass_app::CompassApp;
+use crate::app::compass::test::{load_test_app, load_test_app_with, try_load_test_app_with};
+use routee_compass_core::model::network::EdgeId;
+use routee_compass_core::model::unit::AsF64;
+
+#[test]
+fn test_cost_normalization() {
+    let app = load_test_app("speeds");
+
+    let run = |weights: serde_json::Value, normalization: serde_json::Value| {
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "weights": weights,
+            "normalization": normalization
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        assert!(result[0].get("error").is_none(), "{}", result[0]);
+        result[0]["route"].clone()
+    };
+    let assert_same = |a: &serde_json::Value, b: &serde_json::Value| {
+        assert_eq!(a["path"], b["path"]);
+        assert_eq!(a["cost"], b["cost"]);
+        assert_eq!(a["cost_model"], b["cost_model"]);
+    };
+
+    // each pair of normalized weights matches raw weights divided by the references
+    let normalized = run(
+        serde_json::json!({"distance": 1.0, "time": 1.0}),
+        serde_json::json!({"distance": 1000.0, "time": 1.0}),
+    );
+    let raw = run(
+        serde_json::json!({"distance": 0.001, "time": 1.0}),
+        serde_json::json!({}),
+    );
+    assert_same(&normalized, &raw);
+    assert_eq!(normalized["path"], serde_json::json!([0, 2]));
+
+    let normalized = run(
+        serde_json::json!({"distance": 1.0, "time": 1.0}),
+        serde_json::json!({"distance": 1.0, "time": 1000.0}),
+    );
+    let raw = run(
+        serde_json::json!({"distance": 1.0, "time": 0.001}),
+        serde_json::json!({}),
+    );
+    assert_same(&normalized, &raw);
+    assert_eq!(normalized["path"], serde_json::json!([1]));
+
+    // a reference that is not positive would break the search
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2,
+        "normalization": {"time": 0.0}
+    });
+    let result = app.run(&mut [query], None).unwrap();
+    assert!(result[0].get("error").is_some());
+}
+
+#[test]
+fn test_secondary_cost_models() {
+    let app = load_test_app("speeds");
+
+    let secondary = serde_json::json!({
+        "shortest": {"weights": {"distance": 1.0, "time": 0.0}},
+        "blended": {"weights": {"distance": 0.5, "time": 2.0}}
+    });
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2,
+        "secondary_cost_models": secondary
+    });
+    let result = app.run(&mut [query], None).unwrap();
+    assert!(result[0].get("error").is_none(), "{}", result[0]);
+    // the search still optimizes time, the primary objective
+    assert_eq!(result[0]["route"]["path"], serde_json::json!([0, 2]));
+
+    for name in ["shortest", "blended"] {
+        let reported = result[0]["route"]["secondary_costs"][name]
+            .as_f64()
+            .unwrap();
+        let (evaluated, _) = app
+            .search_app
+            .evaluate_route(&[EdgeId(0), EdgeId(2)], &secondary[name])
+            .unwrap();
+        let expected: f64 = evaluated.routes[0]
+            .iter()
+            .map(|et| et.total_cost().as_f64())
+            .sum();
+        assert!(
+            (reported - expected).abs() < 1e-9,
+            "{}: {} != {}",
+            name,
+            reported,
+            expected
+        );
+    }
+    // the shortest cost model costs the distance of the route, 883.341 kilometers
+    let shortest = result[0]["route"]["secondary_costs"]["shortest"]
+        .as_f64()
+        .unwrap();
+    assert!((shortest - 883.341).abs() < 1e-6, "{}", shortest);
+}
+
+#[test]
+fn test_vehicle_specific_rates() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "cost": {
+                "weights": {"distance": 1},
+                "vehicle_rates": {"time": {"type": "factor", "factor": 0.001}},
+                "vehicle_rates_by_model": {
+                    "delivery_van": {"time": {"type": "factor", "factor": 1.0}}
+                }
+            }
+        }),
+    );
+
+    let run = |model_name: &str| {
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "model_name": model_name
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0]["route"]["path"].clone()
+    };
+    // the delivery van values time highly and takes the time-optimal path [0, 2].
+    // the personal car has no rates of its own and uses the global rates, which
+    // make the distance-optimal path [1] the cheapest.
+    assert_eq!(run("delivery_van"), serde_json::json!([0, 2]));
+    assert_eq!(run("personal_car"), serde_json::json!([1]));
+}
+
+#[test]
+fn test_time_variance_weight() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "traversal": {
+                "time_unit": "hours",
+                "time_variance_input_file": "test_edge_time_variance.csv"
+            },
+            "cost": {"vehicle_rates": {"time_variance": {"type": "raw"}}},
+            "plugin": {
+                "output_plugins": [
+                    {"type": "summary", "eta_percentiles": [10, 50, 90]},
+                    {"type": "traversal", "route": "edge_id"}
+                ]
+            }
+        }),
+    );
+
+    let run = |variance_weight: f64| {
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "weights": { "time": 1.0, "time_variance": variance_weight }
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0].clone()
+    };
+    // the fast corridor [0, 2] takes about 7.9 hours with a variance of 5.0 hours
+    // squared, while the direct edge [1] takes 12 hours with a variance of 0.25.
+    let fast = run(0.0);
+    assert_eq!(fast["route"]["path"], serde_json::json!([0, 2]));
+    assert_eq!(
+        fast["route"]["traversal_summary"]["time_variance"],
+        serde_json::json!(5.0)
+    );
+    assert_eq!(run(0.5)["route"]["path"], serde_json::json!([0, 2]));
+    let steady = run(2.0);
+    assert_eq!(steady["route"]["path"], serde_json::json!([1]));
+    assert_eq!(
+        steady["route"]["traversal_summary"]["time_variance"],
+        serde_json::json!(0.25)
+    );
+
+    // the summary reports a wider range of trip times for the fast corridor,
+    // centered on the mean trip time of each route
+    let span = |result: &serde_json::Value| {
+        let range = &result["eta_range"];
+        range["p90"].as_f64().unwrap() - range["p10"].as_f64().unwrap()
+    };
+    assert!((span(&fast) - 2.0 * 1.2816 * 5.0_f64.sqrt() * 3600.0).abs() < 1.0);
+    assert!((span(&steady) - 2.0 * 1.2816 * 0.5 * 3600.0).abs() < 1.0);
+    assert!(span(&fast) > span(&steady));
+    assert!((steady["eta_range"]["p50"].as_f64().unwrap() - 12.0 * 3600.0).abs() < 1.0);
+}
+
+#[test]
+fn test_time_varying_tolls() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "traversal": {
+                "time_unit": "hours",
+                "toll_schedule_input_file": "test_edge_tolls.csv"
+            },
+            "cost": {"weights": {"toll": 1}, "vehicle_rates": {"toll": {"type": "raw"}}}
+        }),
+    );
+
+    let run = |start_time: &str, toll_weight: f64| {
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "start_time": start_time,
+            "weights": { "time": 1.0, "toll": toll_weight }
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0]["route"].clone()
+    };
+    // ignoring tolls, the fast corridor [0, 2] is taken at any time of day, and
+    // pays the peak or off-peak toll of edge 0 depending on the departure
+    let peak = run("08:00", 0.0);
+    assert_eq!(peak["path"], serde_json::json!([0, 2]));
+    assert_eq!(peak["traversal_summary"]["toll"], serde_json::json!(5.0));
+    assert_eq!(
+        peak["traversal_summary"]["toll_peak"],
+        serde_json::json!(5.0)
+    );
+    assert_eq!(
+        peak["traversal_summary"]["toll_off_peak"],
+        serde_json::json!(0.0)
+    );
+    let off_peak = run("10:00", 0.0);
+    assert_eq!(off_peak["path"], serde_json::json!([0, 2]));
+    assert_eq!(
+        off_peak["traversal_summary"]["toll"],
+        serde_json::json!(1.0)
+    );
+    assert_eq!(
+        off_peak["traversal_summary"]["toll_off_peak"],
+        serde_json::json!(1.0)
+    );
+
+    // the corridor saves about 4.1 hours over the direct edge [1], which is worth
+    // the off-peak toll but not the peak toll
+    assert_eq!(run("10:00", 1.0)["path"], serde_json::json!([0, 2]));
+    let avoided = run("08:00", 1.0);
+    assert_eq!(avoided["path"], serde_json::json!([1]));
+    assert_eq!(avoided["traversal_summary"]["toll"], serde_json::json!(0.0));
+}
+
+#[test]
+fn test_surface_penalty() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "traversal": {
+                "time_unit": "hours",
+                "distance_unit": "kilometers",
+                "surface_input_file": "test_edge_surface.csv",
+                "surface_penalty": {"gravel": 0.01, "dirt": 0.05}
+            },
+            "cost": {
+                "weights": {"surface_penalty": 1},
+                "vehicle_rates": {"surface_penalty": {"type": "raw"}}
+            }
+        }),
+    );
+
+    let run = |surface_penalty: Option<serde_json::Value>| {
+        let mut query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2
+        });
+        if let Some(penalty) = surface_penalty {
+            query["surface_penalty"] = penalty;
+        }
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0]["route"].clone()
+    };
+    // edge 2 is gravel. the fast corridor [0, 2] takes about 7.9 hours while
+    // the paved edge [1] takes 12 hours, but the 708km of gravel on the
+    // corridor costs 7.1 at the configured penalty.
+    let paved = run(None);
+    assert_eq!(paved["path"], serde_json::json!([1]));
+    assert_eq!(
+        paved["traversal_summary"]["unpaved_distance"],
+        serde_json::json!(0.0)
+    );
+    // with a zero penalty the surface is ignored, though unpaved distance
+    // is still reported
+    let ignored = run(Some(serde_json::json!({ "gravel": 0.0 })));
+    assert_eq!(ignored["path"], serde_json::json!([0, 2]));
+    let unpaved = ignored["traversal_summary"]["unpaved_distance"]
+        .as_f64()
+        .unwrap();
+    assert!(
+        (unpaved - 707.96).abs() < 1e-6,
+        "unpaved distance {}",
+        unpaved
+    );
+
+    let invalid = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2,
+        "surface_penalty": { "gravel": -1.0 }
+    });
+    let result = app.run(&mut [invalid], None).unwrap();
+    assert!(result[0].get("error").is_some());
+}
+
+#[test]
+fn test_cost_expression_matches_linear_cost() {
+    let linear = load_test_app("speeds");
+    let expression = load_test_app_with(
+        "speeds",
+        serde_json::json!({"cost": {"expression": "200 * distance + time"}}),
+    );
+    let route = |app: &CompassApp, query: serde_json::Value| {
+        let result = app.run(&mut [query], None).unwrap();
+        assert!(result[0].get("error").is_none(), "{}", result[0]);
+        result[0]["route"].clone()
+    };
+
+    // the hand-coded linear equivalent of "200 * distance + time"
+    let linear_route = route(
+        &linear,
+        serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "weights": {"distance": 1.0, "time": 1.0},
+            "vehicle_rates": {
+                "distance": {"type": "factor", "factor": 200.0},
+                "time": {"type": "raw"}
+            }
+        }),
+    );
+    let expression_route = route(
+        &expression,
+        serde_json::json!({"origin_vertex": 0, "destination_vertex": 2}),
+    );
+    assert_eq!(linear_route["path"], serde_json::json!([1]));
+    assert_eq!(expression_route["path"], linear_route["path"]);
+    assert_eq!(
+        expression_route["traversal_summary"],
+        linear_route["traversal_summary"]
+    );
+    assert_eq!(
+        expression_route["cost_model"]["expression"],
+        serde_json::json!("((200 * distance) + time)")
+    );
+
+    // a query may replace the expression, here minimizing time only
+    let time_route = route(
+        &expression,
+        serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "cost_expression": "time"
+        }),
+    );
+    assert_eq!(time_route["path"], serde_json::json!([0, 2]));
+
+    // unknown features and possibly negative costs are rejected
+    for cost_expression in ["2 * tolls", "time - distance"] {
+        let mut query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "cost_expression": cost_expression
+        });
+        let result = expression
+            .run(std::slice::from_mut(&mut query), None)
+            .unwrap();
+        assert!(result[0].get("error").is_some(), "{}", cost_expression);
+    }
+
+    // configured expressions are validated when the application is built
+    let error = try_load_test_app_with(
+        "speeds",
+        serde_json::json!({"cost": {"expression": "2 * tolls"}}),
+    )
+    .err()
+    .expect("unknown feature in cost expression should be rejected");
+    assert!(
+        error.to_string().contains("tolls"),
+        "unexpected error: {}",
+        error
+    );
+}
+
+#[test]
+fn test_cost_expression_total_cost_sums_edge_costs() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "cost": {"expression": "time * time"},
+            "plugin": {
+                "output_plugins": [
+                    {"type": "summary"},
+                    {"type": "traversal", "route": "json"}
+                ]
+            }
+        }),
+    );
+    let mut query = serde_json::json!({"origin_vertex": 0, "destination_vertex": 2});
+    let result = app.run(std::slice::from_mut(&mut query), None).unwrap();
+    let route = &result[0]["route"];
+    let edges = route["path"].as_array().unwrap();
+    assert_eq!(edges.len(), 2, "{}", route);
+
+    // the expression is not linear, so the total is the sum of the edge costs
+    // rather than the squared time of the whole route
+    let edge_costs: f64 = edges
+        .iter()
+        .map(|e| e["access_cost"].as_f64().unwrap() + e["traversal_cost"].as_f64().unwrap())
+        .sum();
+    let time = route["traversal_summary"]["time"].as_f64().unwrap();
+    let total_cost = route["cost"]["total_cost"].as_f64().unwrap();
+    assert!((total_cost - edge_costs).abs() < 1e-9, "{}", route["cost"]);
+    assert!((total_cost - time * time).abs() > 1e-9, "{}", route["cost"]);
+}
+
+#[test]
+fn test_comfort_weight() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "traversal": {"time_unit": "hours"},
+            "access": {
+                "type": "comfort",
+                "speed_table_input_file": "test_edge_speeds.csv",
+                "stop_input_file": "test_vertex_stops.csv",
+                "weights": {"turn": 1.0, "stop": 1.0, "speed_change": 0.05}
+            },
+            "cost": {"vehicle_rates": {"comfort_cost": {"type": "raw"}}}
+        }),
+    );
+
+    let run = |comfort_weight: f64| {
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "weights": { "time": 1.0, "comfort_cost": comfort_weight }
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0]["route"].clone()
+    };
+    // the fast corridor [0, 2] takes about 7.9 hours but turns left at vertex 1,
+    // where traffic stops, while the direct edge [1] takes 12 hours without either
+    let fast = run(0.0);
+    assert_eq!(fast["path"], serde_json::json!([0, 2]));
+    let fast_comfort = fast["traversal_summary"]["comfort_cost"].as_f64().unwrap();
+    assert!(fast_comfort > 1.0, "{}", fast_comfort);
+
+    let smooth = run(5.0);
+    assert_eq!(smooth["path"], serde_json::json!([1]));
+    assert_eq!(
+        smooth["traversal_summary"]["comfort_cost"],
+        serde_json::json!(0.0)
+    );
+}
+
+#[test]
+fn test_disable_turn_delay_model() {
+    let app = load_test_app("turn_delay");
+
+    // the left turn from edge 0 onto edge 2 has a 10 hour delay, so the time-optimal
+    // route [0, 2] is only chosen when the turn delay model is disabled
+    let mut queries = vec![
+        serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2
+        }),
+        serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "disable_models": ["turn_delay"]
+        }),
+    ];
+    let result = app.run(&mut queries, None).unwrap();
+    assert_eq!(result.len(), 2, "expected two results");
+    let with_delay = &result[0]["route"];
+    let without_delay = &result[1]["route"];
+    assert_eq!(with_delay["path"], serde_json::json!(vec![1]));
+    assert_eq!(without_delay["path"], serde_json::json!(vec![0, 2]));
+
+    // with the model disabled, no turn time is added to the total
+    let time_without_delay = without_delay["traversal_summary"]["time"].as_f64().unwrap();
+    let expected_time = (175381.0 + 707960.0) / 1000.0 / 112.0;
+    assert!(
+        (time_without_delay - expected_time).abs() < 1e-6,
+        "expected {} hours, found {}",
+        expected_time,
+        time_without_delay
+    );
+}
+
+#[test]
+fn test_left_turn_penalty() {
+    let app = load_test_app_with(
+        "turn_delay",
+        serde_json::json!({
+            "access": {
+                "access_models": [
+                    {
+                        "type": "turn_delay",
+                        "edge_heading_input_file": "test_edge_headings.csv",
+                        "turn_delay_model": {
+                            "type": "tabular_discrete",
+                            "time_unit": "hours",
+                            "table": {
+                                "no_turn": 0.0,
+                                "slight_right": 0.0,
+                                "right": 0.0,
+                                "sharp_right": 0.0,
+                                "slight_left": 0.0,
+                                "left": 0.0,
+                                "sharp_left": 0.0,
+                                "u_turn": 0.0
+                            }
+                        },
+                        "turn_penalties": {"left": 5.0, "right": 0.0}
+                    }
+                ]
+            },
+            "cost": {
+                "weights": {"turn_penalty": 1},
+                "vehicle_rates": {"turn_penalty": {"type": "raw"}}
+            }
+        }),
+    );
+
+    let run = |penalty_weight: f64| {
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "weights": { "time": 1.0, "turn_penalty": penalty_weight }
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0]["route"].clone()
+    };
+    // the fastest route [0, 2] turns left at vertex 1 and takes about 7.9 hours,
+    // while the direct edge [1] takes 12 hours without turning
+    let fastest = run(0.0);
+    assert_eq!(fastest["path"], serde_json::json!([0, 2]));
+    assert_eq!(
+        fastest["traversal_summary"]["left_turns"].as_f64(),
+        Some(1.0)
+    );
+    // the 5 hour penalty for the left turn outweighs the extra 4 hours of travel
+    let no_left_turns = run(1.0);
+    assert_eq!(no_left_turns["path"], serde_json::json!([1]));
+    assert_eq!(
+        no_left_turns["traversal_summary"]["left_turns"].as_f64(),
+        Some(0.0)
+    );
+    let fastest_time = fastest["traversal_summary"]["time"].as_f64().unwrap();
+    let no_left_time = no_left_turns["traversal_summary"]["time"].as_f64().unwrap();
+    assert!(no_left_time > fastest_time);
+}
@@ -0,0 +1,64 @@
+//! test networks and the loader for the app-level tests, which are split by
+//! feature into the submodules.
+
+mod batch;
+mod bindings;
+mod configuration;
+mod cost;
+mod output;
+mod routing;
+
+use crate::app::compass::compass_app::CompassApp;
+use crate::app::compass::compass_app_ops as ops;
+use crate::app::compass::CompassAppBuilder;
+use crate::app::compass::{CompassAppError, CompassConfigurationError};
+use config::Config;
+use std::path::PathBuf;
+
+/// the directory holding the configuration and data of the named test network
+pub fn test_dir(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("app")
+        .join("compass")
+        .join("test")
+        .join(format!("{}_test", name))
+}
+
+/// builds the app for the named test network, with the overrides merged into
+/// its configuration. file names in the overrides are found in the test directory.
+///
+/// rust runs test and debug at different locations, which breaks the URLs
+/// written in the referenced TOML files. here's a quick fix
+/// turnaround that doesn't leak into anyone's VS Code settings.json files:
+/// when `{name}_test.toml` cannot find its files, `{name}_debug.toml` is used.
+/// see https://github.com/rust-lang/rust-analyzer/issues/4705 for discussion
+pub fn try_load_test_app_with(
+    name: &str,
+    overrides: serde_json::Value,
+) -> Result<CompassApp, CompassAppError> {
+    let build = |conf_file: PathBuf| {
+        let config = Config::builder()
+            .add_source(ops::read_config_from_file(&conf_file)?)
+            .add_source(config::File::from_str(
+                &overrides.to_string(),
+                config::FileFormat::Json,
+            ))
+            .build()?;
+        CompassApp::try_from((&config, &CompassAppBuilder::default()))
+    };
+    match build(test_dir(name).join(format!("{}_test.toml", name))) {
+        Err(CompassAppError::CompassConfigurationError(
+            CompassConfigurationError::FileNormalizationNotFound(_key, _f1, _f2),
+        )) => build(test_dir(name).join(format!("{}_debug.toml", name))),
+        result => result,
+    }
+}
+
+pub fn load_test_app_with(name: &str, overrides: serde_json::Value) -> CompassApp {
+    try_load_test_app_with(name, overrides).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn load_test_app(name: &str) -> CompassApp {
+    load_test_app_with(name, serde_json::json!({}))
+}
@@ -0,0 +1,285 @@
+//! app-level tests of the route output plugins.
+
+use crate::app::compass::test::{load_test_app, load_test_app_with};
+
+#[test]
+fn test_route_trajectory() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "traversal": {"speed_schedule_input_file": "school_zones.csv"},
+            "plugin": {
+                "output_plugins": [
+                    {"type": "summary"},
+                    {"type": "traversal", "route": "edge_id", "trajectory": true}
+                ]
+            }
+        }),
+    );
+
+    // edge 0 is a school zone from 07:00 to 09:00, so the two start times take
+    // different routes with different scheduled speeds
+    for (start_time, start_seconds) in [("07:30", 27_000.0), ("12:00", 43_200.0)] {
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "start_time": start_time
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        let route = &result[0]["route"];
+        let trajectory = route["trajectory"]
+            .as_array()
+            .expect("expected a trajectory");
+        assert_eq!(trajectory.len(), route["path"].as_array().unwrap().len());
+
+        let mut enter_time = start_seconds;
+        for step in trajectory.iter() {
+            assert_eq!(step["enter_time"].as_f64().unwrap(), enter_time);
+            enter_time = step["exit_time"].as_f64().unwrap();
+        }
+        // trip time is reported in the default time unit of seconds
+        let trip_seconds = route["traversal_summary"]["time"].as_f64().unwrap();
+        let expected = start_seconds + trip_seconds;
+        assert!(
+            (enter_time - expected).abs() < 1e-6,
+            "final timestamp {} should be {} for start time {}",
+            enter_time,
+            expected,
+            start_time
+        );
+    }
+}
+
+#[test]
+fn test_road_segments() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "plugin": {
+                "output_plugins": [
+                    {"type": "summary"},
+                    {
+                        "type": "traversal",
+                        "route": "edge_id",
+                        "road_name_input_file": "test_edge_road_names.txt"
+                    }
+                ]
+            }
+        }),
+    );
+
+    let run = |weights: serde_json::Value| {
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "weights": weights
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0]["route"].clone()
+    };
+
+    // the time-optimal route [0, 2] runs along US-36 and then CO-93
+    let route = run(serde_json::json!({ "distance": 0.0, "time": 1.0 }));
+    assert_eq!(route["path"], serde_json::json!([0, 2]));
+    let segments = route["road_segments"].as_array().unwrap();
+    let names: Vec<&str> = segments
+        .iter()
+        .map(|s| s["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["US-36", "CO-93"]);
+    assert_eq!(segments[0]["distance_unit"], "kilometers");
+    let distances: Vec<f64> = segments
+        .iter()
+        .map(|s| s["distance"].as_f64().unwrap())
+        .collect();
+    assert!((distances[0] - 175.381).abs() < 1e-6, "{:?}", distances);
+    assert!((distances[1] - 707.96).abs() < 1e-6, "{:?}", distances);
+    let total = route["traversal_summary"]["distance"].as_f64().unwrap();
+    assert!((distances.iter().sum::<f64>() - total).abs() < 1e-6);
+
+    // the distance-optimal route [1] has no road name
+    let route = run(serde_json::json!({ "distance": 1.0, "time": 0.0 }));
+    assert_eq!(route["path"], serde_json::json!([1]));
+    assert_eq!(route["road_segments"][0]["name"], "unnamed road");
+    assert_eq!(route["road_segments"][0]["edge_count"], 1);
+}
+
+#[test]
+fn test_original_edges() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "plugin": {
+                "output_plugins": [
+                    {"type": "summary"},
+                    {
+                        "type": "traversal",
+                        "route": "edge_id",
+                        "original_edges_input_file": "test_edge_original_ids.txt"
+                    }
+                ]
+            }
+        }),
+    );
+
+    // the test graph stands in for a compacted graph where edges 0 and 2
+    // replace original edges [0, 1] and [3, 4, 5]
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2
+    });
+    let result = app.run(&mut [query], None).unwrap();
+    let route = &result[0]["route"];
+    assert_eq!(route["path"], serde_json::json!([0, 2]));
+    assert_eq!(
+        route["original_edge_ids"],
+        serde_json::json!([0, 1, 3, 4, 5])
+    );
+}
+
+#[test]
+fn test_eta_distribution() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "traversal": {
+                "time_unit": "hours",
+                "time_variance_input_file": "test_edge_time_variance.csv"
+            },
+            "cost": {"vehicle_rates": {"time_variance": {"type": "raw"}}},
+            "plugin": {
+                "output_plugins": [
+                    {"type": "summary", "eta_percentiles": [10, 50, 90]},
+                    {"type": "traversal", "route": "edge_id"}
+                ]
+            }
+        }),
+    );
+
+    // edge 0 is the only route from vertex 0 to vertex 1, taking 175.381 km at
+    // 112 kph, with a variance of 1.0 hours squared
+    let query = serde_json::json!({ "origin_vertex": 0, "destination_vertex": 1 });
+    let deterministic = app.run(&mut [query.clone()], None).unwrap()[0]["route"]
+        ["traversal_summary"]["time"]
+        .as_f64()
+        .unwrap();
+    assert!((deterministic - 175.381 / 112.0).abs() < 1e-9);
+
+    // a seeded query draws a different travel time, and repeats it
+    let mut seeded = query.clone();
+    seeded["seed"] = serde_json::json!(3);
+    let sampled = |q: &serde_json::Value| {
+        app.run(&mut [q.clone()], None).unwrap()[0]["route"]["traversal_summary"]["time"]
+            .as_f64()
+            .unwrap()
+    };
+    assert_ne!(sampled(&seeded), deterministic);
+    assert_eq!(sampled(&seeded), sampled(&seeded));
+
+    // the sampled mean converges to the deterministic mean as samples grow
+    let error = |samples: usize| {
+        let eta = app.run_eta_distribution(&query, samples).unwrap();
+        assert_eq!(eta.samples, samples);
+        assert_eq!(eta.routes, samples);
+        assert_eq!(eta.time_unit.as_deref(), Some("hours"));
+        assert!(eta.min <= eta.percentiles[2].1 && eta.percentiles[2].1 <= eta.max);
+        (eta.mean - deterministic).abs() / deterministic
+    };
+    let coarse = error(20);
+    let fine = error(5000);
+    assert!(fine < 0.03, "relative error of the mean is {}", fine);
+    assert!(fine < coarse.max(0.03));
+}
+
+#[test]
+fn test_geojson_output() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "plugin": {
+                "output_plugins": [
+                    {"type": "summary"},
+                    {"type": "traversal", "route": "edge_id"},
+                    {"type": "geojson", "state_features": ["distance", "time"]}
+                ]
+            }
+        }),
+    );
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2
+    });
+    let result = app.run(&mut [query], None).unwrap()[0].clone();
+    let geojson = &result["geojson"];
+    assert_eq!(geojson["type"], serde_json::json!("FeatureCollection"));
+    let features = geojson["features"].as_array().unwrap();
+    let edge_ids = features
+        .iter()
+        .map(|f| f["properties"]["edge_id"].as_u64().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(edge_ids, vec![0, 2]);
+
+    // coordinates are [lon, lat], starting at vertex 0
+    let first = &features[0]["geometry"];
+    assert_eq!(first["type"], serde_json::json!("LineString"));
+    let start = first["coordinates"][0].as_array().unwrap();
+    assert!((start[0].as_f64().unwrap() - -105.1683038).abs() < 1e-4);
+    assert!((start[1].as_f64().unwrap() - 39.7379033).abs() < 1e-4);
+
+    // only the configured state features are written, as the change over each edge
+    let mut keys = features[0]["properties"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(
+        keys,
+        vec![
+            "access_cost",
+            "distance",
+            "edge_id",
+            "time",
+            "traversal_cost"
+        ]
+    );
+    let total_time: f64 = features
+        .iter()
+        .map(|f| f["properties"]["time"].as_f64().unwrap())
+        .sum();
+    let summary_time = result["route"]["traversal_summary"]["time"]
+        .as_f64()
+        .unwrap();
+    assert!((total_time - summary_time).abs() < 1e-6);
+}
+
+#[test]
+fn test_select_output_plugins() {
+    let app = load_test_app("speeds");
+
+    let run = |query: serde_json::Value| {
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0].clone()
+    };
+    let base = serde_json::json!({"origin_vertex": 0, "destination_vertex": 2});
+    let mut summary_only = base.clone();
+    summary_only["output_plugins"] = serde_json::json!(["summary"]);
+    let mut unknown = base.clone();
+    unknown["output_plugins"] = serde_json::json!(["geometry"]);
+    let result = [run(base), run(summary_only), run(unknown)];
+
+    // by default, all configured plugins run
+    assert!(result[0].get("iterations").is_some());
+    assert!(result[0].get("route").is_some());
+
+    // only the summary plugin runs, so there is no route block
+    assert!(result[1].get("iterations").is_some());
+    assert!(result[1].get("route").is_none());
+
+    // unknown plugin names are reported along with the configured plugins
+    let error = result[2]["error"].as_str().unwrap_or_default();
+    assert!(error.contains("{summary,traversal}"), "{}", error);
+}
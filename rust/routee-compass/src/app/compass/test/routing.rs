@@ -0,0 +1,933 @@
+//! app-level tests of route search: query options, destinations, constraints
+//! and the selection of search algorithms.
+
+use crate::app::compass::test::{load_test_app, load_test_app_with};
+use crate::app::search::SearchAppGraphOps;
+use routee_compass_core::algorithm::search::a_star::{Landmarks, LandmarksConfig};
+use routee_compass_core::algorithm::search::SearchInstance;
+use routee_compass_core::model::network::{EdgeId, VertexId};
+use routee_compass_core::model::unit::AsF64;
+
+#[test]
+fn test_school_zone_changes_route() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({"traversal": {"speed_schedule_input_file": "school_zones.csv"}}),
+    );
+
+    let run = |start_time: &str| {
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "start_time": start_time
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0]["route"]["path"].clone()
+    };
+    // edge 0 is a school zone from 07:00 to 09:00, which makes path [1] time-optimal
+    assert_eq!(run("07:30"), serde_json::json!([1]));
+    assert_eq!(run("12:00"), serde_json::json!([0, 2]));
+}
+
+#[test]
+fn test_destination_polygon() {
+    let app = load_test_app("speeds");
+
+    // a region stretching from west of vertex 2 to just past vertex 1. its
+    // centroid is nearest to vertex 2, but vertex 1 is cheaper to reach from 0
+    let region = serde_json::json!([
+        [-115.0, 40.0],
+        [-104.5, 40.0],
+        [-104.5, 42.0],
+        [-115.0, 42.0]
+    ]);
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_polygon": region
+    });
+    let result = app.run(&mut [query], None).unwrap();
+    assert!(result[0].get("error").is_none(), "{}", result[0]);
+    assert_eq!(result[0]["route"]["path"], serde_json::json!([0]));
+    assert_eq!(
+        result[0]["request"]["destination_vertex"],
+        serde_json::json!(1)
+    );
+    let to_entry = serde_json::json!({"origin_vertex": 0, "destination_vertex": 1});
+    let direct = app.run(&mut [to_entry], None).unwrap();
+    assert_eq!(
+        result[0]["route"]["cost"]["time"],
+        direct[0]["route"]["cost"]["time"]
+    );
+
+    // a region around vertex 2 alone is entered through the faster edges via vertex 1
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_polygon": [[-112.5, 40.5], [-111.5, 40.5], [-111.5, 41.0], [-112.5, 41.0]]
+    });
+    let result = app.run(&mut [query], None).unwrap();
+    assert_eq!(result[0]["route"]["path"], serde_json::json!([0, 2]));
+
+    // a region without any vertices has no entry point
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_polygon": [[-90.0, 30.0], [-89.0, 30.0], [-89.0, 31.0]]
+    });
+    let result = app.run(&mut [query], None).unwrap();
+    assert!(result[0].get("error").is_some());
+}
+
+#[test]
+fn test_require_edges() {
+    let app = load_test_app("speeds");
+
+    // the fastest route from 0 to 2 is through 1, but edge 1 goes there directly
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2,
+        "require_edges": [1]
+    });
+    let result = app.run(&mut [query], None).unwrap();
+    assert!(result[0].get("error").is_none(), "{}", result[0]);
+    assert_eq!(result[0]["route"]["path"], serde_json::json!([1]));
+
+    // requiring both edges of the fastest route, in order, keeps that route,
+    // and the states of the legs are joined into one continuous route
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2,
+        "require_edges": [0, 2]
+    });
+    let result = app.run(&mut [query], None).unwrap();
+    assert_eq!(result[0]["route"]["path"], serde_json::json!([0, 2]));
+    let free = serde_json::json!({"origin_vertex": 0, "destination_vertex": 2});
+    let free = app.run(&mut [free], None).unwrap();
+    assert_eq!(
+        result[0]["route"]["traversal_summary"],
+        free[0]["route"]["traversal_summary"]
+    );
+
+    // edge 0 cannot be reached after edge 2, which ends at a dead end
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2,
+        "require_edges": [2, 0]
+    });
+    let result = app.run(&mut [query], None).unwrap();
+    let error = result[0]["error"].as_str().unwrap();
+    assert!(
+        error.contains("required edge 0 cannot be reached from vertex 2"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_distinct_alternatives() {
+    let app = load_test_app("alternatives");
+
+    let run = |query: serde_json::Value| {
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0].clone()
+    };
+    // the k-shortest paths search also returns the detour around the link from
+    // vertex 1 to vertex 2, which shares all but 200 meters of the corridor
+    let ksp = run(serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 4,
+        "search_algorithm": "ksp"
+    }));
+    let ksp_paths: Vec<serde_json::Value> = ksp["route"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["path"].clone())
+        .collect();
+    assert_eq!(
+        ksp_paths,
+        vec![
+            serde_json::json!([0, 1, 4]),
+            serde_json::json!([0, 2, 3, 4])
+        ]
+    );
+
+    // the corridor is the only distinct alternative, so one route is returned
+    let distinct = run(serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 4
+    }));
+    assert_eq!(distinct["route"]["path"], serde_json::json!([0, 1, 4]));
+    assert_eq!(
+        distinct["alternatives"],
+        serde_json::json!({ "requested": 3, "found": 1 })
+    );
+
+    // the number of alternatives may be set per query
+    let distinct = run(serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 4,
+        "max_alternatives": 5
+    }));
+    assert_eq!(
+        distinct["alternatives"],
+        serde_json::json!({ "requested": 5, "found": 1 })
+    );
+
+    // the corridor and the detour share 2 of their 5 edges, within the
+    // diversity constraint, and no third route exists
+    let diverse = run(serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 4,
+        "search_algorithm": "ksp_diverse"
+    }));
+    let diverse_paths: Vec<serde_json::Value> = diverse["route"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["path"].clone())
+        .collect();
+    assert_eq!(diverse_paths, ksp_paths);
+    let too_few = run(serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 4,
+        "search_algorithm": "ksp_diverse",
+        "k": 3
+    }));
+    let error = too_few["error"].as_str().expect("expected an error");
+    assert!(
+        error.contains("found 2 of the 3 requested routes"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_max_detour_ratio() {
+    let app = load_test_app("speeds");
+
+    let run = |destination: usize| {
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": destination,
+            "max_detour_ratio": 1.4
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0].clone()
+    };
+    // the route to vertex 1 is about 1.1 times the straight-line distance
+    assert_eq!(run(1)["route"]["path"], serde_json::json!([0]));
+    // the time-optimal route to vertex 2, [0, 2], is about 1.5 times the
+    // straight-line distance
+    let detour = run(2);
+    assert!(detour.get("route").is_none());
+    assert_eq!(
+        detour["no_route"]["cause"],
+        serde_json::json!("detour_exceeded")
+    );
+}
+
+#[test]
+fn test_off_network_coordinates() {
+    // snap coordinates to vertices within 100 meters
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "mapping": {
+                "type": "vertex",
+                "tolerance": {"distance": 100.0, "unit": "meters"},
+                "queries_without_destinations": true
+            }
+        }),
+    );
+
+    // from a point north of vertex 0, at (-105.1683038, 39.7379033), to a point
+    // north of vertex 1, at (-104.8086039, 41.1475252)
+    let run = |origin_y: f64, destination_y: f64| {
+        let query = serde_json::json!({
+            "origin_x": -105.1683038,
+            "origin_y": origin_y,
+            "destination_x": -104.8086039,
+            "destination_y": destination_y
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0].clone()
+    };
+    // about 20 meters away, the points snap to vertices 0 and 1
+    let near = run(39.7380833, 41.1477052);
+    assert_eq!(near["route"]["path"], serde_json::json!([0]));
+    // about 5 kilometers away, either point is off-network
+    for far in [run(39.7829033, 41.1477052), run(39.7380833, 41.1925252)] {
+        assert!(far.get("route").is_none());
+        assert_eq!(far["no_route"]["cause"], serde_json::json!("off_network"));
+        assert!(far["error"].as_str().unwrap().contains("off-network"));
+    }
+}
+
+#[test]
+fn test_reroute_preference() {
+    let app = load_test_app("speeds");
+
+    let run = |preference: Option<f64>| {
+        let mut query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "previous_route": [1]
+        });
+        if let Some(preference) = preference {
+            query["reroute_preference"] = serde_json::json!(preference);
+        }
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0].clone()
+    };
+    // the previous route [1] takes 12 hours against about 7.9 hours for [0, 2],
+    // which the default preference does not overcome
+    assert_eq!(run(None)["route"]["path"], serde_json::json!([0, 2]));
+    assert_eq!(run(Some(0.2))["route"]["path"], serde_json::json!([0, 2]));
+    // at half cost the previous route is kept
+    assert_eq!(run(Some(0.5))["route"]["path"], serde_json::json!([1]));
+    // larger preferences could make costs negative
+    assert!(run(Some(0.9)).get("error").is_some());
+
+    // the preference does not discount the reported edge costs of the route
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "plugin": {
+                "output_plugins": [
+                    {"type": "summary"},
+                    {"type": "traversal", "route": "json"}
+                ]
+            }
+        }),
+    );
+    let edge_costs = |preference: Option<f64>| {
+        let mut query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "previous_route": [0, 2]
+        });
+        if let Some(preference) = preference {
+            query["reroute_preference"] = serde_json::json!(preference);
+        }
+        let result = app.run(&mut [query], None).unwrap();
+        result[0]["route"]["path"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["access_cost"].as_f64().unwrap() + e["traversal_cost"].as_f64().unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(edge_costs(Some(0.5)), edge_costs(None));
+}
+
+#[test]
+fn test_vertex_sequence() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "plugin": {
+                "output_plugins": [
+                    {"type": "summary"},
+                    {"type": "traversal", "route": "edge_id", "vertex_sequence": true}
+                ]
+            }
+        }),
+    );
+
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2
+    });
+    let result = app.run(&mut [query], None).unwrap();
+    assert_eq!(result.len(), 1, "expected one result");
+    let edge_ids: Vec<EdgeId> = serde_json::from_value(result[0]["route"]["path"].clone())
+        .expect("route path should be edge ids");
+    let vertex_ids: Vec<VertexId> =
+        serde_json::from_value(result[0]["route"]["vertex_sequence"].clone())
+            .expect("route should have a vertex sequence");
+    assert_eq!(edge_ids, vec![EdgeId(0), EdgeId(2)]);
+    assert_eq!(vertex_ids, vec![VertexId(0), VertexId(1), VertexId(2)]);
+
+    // each edge begins at the vertex preceding it and ends at the vertex
+    // following it, so the endpoints are the origin and destination
+    assert_eq!(vertex_ids.len(), edge_ids.len() + 1);
+    for (i, edge_id) in edge_ids.iter().enumerate() {
+        let src = app.search_app.get_edge_origin(edge_id).unwrap();
+        let dst = app.search_app.get_edge_destination(edge_id).unwrap();
+        assert_eq!(src, vertex_ids[i]);
+        assert_eq!(dst, vertex_ids[i + 1]);
+    }
+    assert_eq!(vertex_ids.first(), Some(&VertexId(0)));
+    assert_eq!(vertex_ids.last(), Some(&VertexId(2)));
+
+    // vertex coordinates come from the graph vertices
+    let (x, y) = app.search_app.get_vertex_coordinate(&VertexId(1)).unwrap();
+    assert!((x - -104.8086039).abs() < 1e-5 && (y - 41.1475252).abs() < 1e-5);
+    assert!(app.search_app.get_vertex_coordinate(&VertexId(3)).is_err());
+
+    // the derivation used by the bindings matches the response
+    let derived = app.search_app.get_vertex_sequence(&edge_ids).unwrap();
+    assert_eq!(derived, vertex_ids);
+    assert!(app.search_app.get_vertex_sequence(&[]).unwrap().is_empty());
+    // edges 0 (0 -> 1) and 1 (0 -> 2) do not form a route
+    assert!(app
+        .search_app
+        .get_vertex_sequence(&[EdgeId(0), EdgeId(1)])
+        .is_err());
+}
+
+#[test]
+fn test_query_search_algorithm() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "algorithm": {"type": "a*", "weight_factor": 10.0},
+            "search_algorithms": {"exact": {"type": "a*"}, "dijkstra": {"type": "dijkstra"}}
+        }),
+    );
+
+    let run = |algorithm: Option<&str>| {
+        let mut query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2
+        });
+        if let Some(name) = algorithm {
+            query["search_algorithm"] = serde_json::json!(name);
+        }
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0].clone()
+    };
+    // the default weighted A* overestimates the remaining cost via vertex 1,
+    // settling the target after one iteration on the slower direct edge
+    let default = run(None);
+    assert_eq!(default["route"]["path"], serde_json::json!([1]));
+    assert_eq!(default["iterations"], serde_json::json!(1));
+
+    // the exact searches settle vertex 1 first and find the faster route through it
+    let exact = run(Some("exact"));
+    assert_eq!(exact["route"]["path"], serde_json::json!([0, 2]));
+    assert_eq!(exact["iterations"], serde_json::json!(2));
+    let dijkstra = run(Some("dijkstra"));
+    assert_eq!(dijkstra["route"]["path"], serde_json::json!([0, 2]));
+    assert_eq!(dijkstra["iterations"], serde_json::json!(2));
+
+    let unknown = run(Some("fastest"));
+    let error = unknown["error"].as_str().expect("expected an error");
+    assert!(
+        error.contains("unknown search_algorithm 'fastest'") && error.contains("[dijkstra, exact]"),
+        "unexpected error: {}",
+        error
+    );
+}
+
+#[test]
+fn test_avoid_ferries() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "frontier": {"type": "edge_avoidance", "ferry_input_file": "test_edge_ferry.csv"}
+        }),
+    );
+
+    let run = |destination: usize, avoid_ferries: bool| {
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": destination,
+            "avoid_ferries": avoid_ferries
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0].clone()
+    };
+    // edge 0 is a ferry. the time-optimal path to vertex 2 uses it, but
+    // the land route [1] is taken when avoiding ferries.
+    assert_eq!(run(2, false)["route"]["path"], serde_json::json!([0, 2]));
+    assert_eq!(run(2, true)["route"]["path"], serde_json::json!([1]));
+    // vertex 1 can only be reached by ferry
+    assert_eq!(run(1, false)["route"]["path"], serde_json::json!([0]));
+    let no_route = run(1, true);
+    assert!(no_route.get("route").is_none());
+    assert_eq!(
+        no_route["no_route"]["cause"],
+        serde_json::json!("restricted")
+    );
+}
+
+#[test]
+fn test_max_gradeability() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "frontier": {
+                "type": "gradeability",
+                "grade_input_file": "test_edge_grade.csv",
+                "max_grade": {"heavy_truck": 0.08, "car": 0.3}
+            }
+        }),
+    );
+
+    let run = |destination: usize, model_name: &str| {
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": destination,
+            "model_name": model_name
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        result[0].clone()
+    };
+    // edge 0 has a 12% grade. the time-optimal path to vertex 2 climbs it, but
+    // a truck limited to 8% detours on the flat route [1].
+    assert_eq!(run(2, "car")["route"]["path"], serde_json::json!([0, 2]));
+    assert_eq!(
+        run(2, "heavy_truck")["route"]["path"],
+        serde_json::json!([1])
+    );
+    // a vehicle without a configured limit is not restricted
+    assert_eq!(
+        run(2, "bicycle")["route"]["path"],
+        serde_json::json!([0, 2])
+    );
+    // vertex 1 can only be reached by climbing edge 0
+    let no_route = run(1, "heavy_truck");
+    assert!(no_route.get("route").is_none());
+    assert_eq!(
+        no_route["no_route"]["cause"],
+        serde_json::json!("restricted")
+    );
+}
+
+#[test]
+fn test_arrival_bearing() {
+    let app = load_test_app("arrival_bearing");
+
+    // the destination is closer to the eastbound carriageway (edge 0) than to the
+    // westbound carriageway (edge 1)
+    let run = |arrival_bearing: Option<f64>| {
+        let mut query = serde_json::json!({
+            "origin_x": -105.0013,
+            "origin_y": 39.70012,
+            "destination_x": -105.0,
+            "destination_y": 39.70005
+        });
+        if let Some(bearing) = arrival_bearing {
+            query["arrival_bearing"] = serde_json::json!(bearing);
+        }
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result.len(), 1, "expected one result");
+        (
+            result[0]["request"]["destination_edge"].clone(),
+            result[0]["route"]["path"].clone(),
+        )
+    };
+    let (edge, path) = run(None);
+    assert_eq!(edge, serde_json::json!(0));
+    assert_eq!(path, serde_json::json!([3, 0]));
+
+    // arriving westbound selects the farther carriageway, reached by a u-turn
+    let (edge, path) = run(Some(270.0));
+    assert_eq!(edge, serde_json::json!(1));
+    assert_eq!(path, serde_json::json!([3, 0, 2, 1]));
+
+    // an eastbound arrival bearing matches the nearest carriageway
+    let (edge, _) = run(Some(80.0));
+    assert_eq!(edge, serde_json::json!(0));
+}
+
+#[test]
+fn test_include_partial_edges() {
+    let app = load_test_app("arrival_bearing");
+
+    // the origin snaps 40% of the way along edge 3 and the destination snaps halfway
+    // along edge 1, reached westbound by way of edges 0 and 2
+    let run = |include_partial_edges: Option<bool>| {
+        let mut query = serde_json::json!({
+            "origin_x": -105.0013,
+            "origin_y": 39.70012,
+            "destination_x": -105.0,
+            "destination_y": 39.70005,
+            "arrival_bearing": 270.0
+        });
+        if let Some(include) = include_partial_edges {
+            query["include_partial_edges"] = serde_json::json!(include);
+        }
+        let result = app.run(&mut [query], None).unwrap();
+        assert_eq!(result[0]["route"]["path"], serde_json::json!([3, 0, 2, 1]));
+        let summary = &result[0]["route"]["traversal_summary"];
+        (
+            summary["distance"].as_f64().unwrap(),
+            summary["time"].as_f64().unwrap(),
+        )
+    };
+    let (default_distance, default_time) = run(None);
+    let (with_distance, with_time) = run(Some(true));
+    let (without_distance, without_time) = run(Some(false));
+    assert_eq!(default_distance, with_distance);
+    assert_eq!(default_time, with_time);
+
+    // distances are in kilometers, and coordinates are stored with about a meter of precision
+    assert!((without_distance - (0.1713 + 0.0222)).abs() < 1e-6);
+    let partial_distance = with_distance - without_distance;
+    let expected = 0.6 * 0.0428 + 0.5 * 0.1713;
+    assert!(
+        (partial_distance - expected).abs() < 0.001,
+        "partial edges add {} km, expected {} km",
+        partial_distance,
+        expected
+    );
+    // every edge has the same speed, so the partial edges add time in proportion
+    let partial_time = with_time - without_time;
+    assert!((partial_time / without_time - partial_distance / without_distance).abs() < 1e-6);
+}
+
+#[test]
+fn test_snap_endpoint() {
+    // snap coordinates to the nearest edge
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "mapping": {
+                "type": "edge",
+                "geometry_input_file": "edge_geometries.txt",
+                "queries_without_destinations": false,
+                "matching_type": ["point"]
+            }
+        }),
+    );
+
+    // the origin snaps to the one-way edge 1 (0 -> 2), near vertex 2 which has no
+    // out-edges, and the destination snaps to edge 0 (0 -> 1), near vertex 1
+    let run = |snap_endpoint: Option<&str>| {
+        let mut query = serde_json::json!({
+            "origin_x": -111.235,
+            "origin_y": 40.6585,
+            "destination_x": -104.8446,
+            "destination_y": 41.0066
+        });
+        if let Some(snap_endpoint) = snap_endpoint {
+            query["snap_endpoint"] = serde_json::json!(snap_endpoint);
+        }
+        let result = app.run(&mut [query], None).unwrap();
+        result[0].clone()
+    };
+
+    // traveling along the origin edge leads to vertex 2, which traps the search
+    for trapped in [run(None), run(Some("mid_edge")), run(Some("reachable"))] {
+        assert!(trapped.get("route").is_none());
+        assert!(trapped.get("error").is_some());
+    }
+
+    // the nearest endpoint that the search can leave is vertex 0
+    let nearest = run(Some("nearest"));
+    assert_eq!(nearest["request"]["origin_vertex"], serde_json::json!(0));
+    assert_eq!(
+        nearest["request"]["destination_vertex"],
+        serde_json::json!(1)
+    );
+    assert!(nearest["request"].get("origin_edge").is_none());
+    assert_eq!(nearest["route"]["path"], serde_json::json!([0]));
+
+    let invalid = run(Some("src"));
+    assert!(invalid["error"].as_str().unwrap().contains("snap_endpoint"));
+}
+
+#[test]
+fn test_landmarks_skipped_for_query_costs() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({"landmarks": {"count": 2, "seed": 0}}),
+    );
+    let run = |query: serde_json::Value| {
+        let result = app.run(&mut [query], None).unwrap();
+        assert!(result[0].get("error").is_none(), "{}", result[0]);
+        result[0]["route"].clone()
+    };
+    let route = run(serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2
+    }));
+    assert_eq!(route["path"], serde_json::json!([0, 2]));
+
+    // the tables bound the cost of edge 2 by its travel time, about 7 hours,
+    // which overestimates the cost of the whole route at this weight. the
+    // time-optimal route [0, 2] is still found.
+    let reweighted = run(serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2,
+        "weights": {"distance": 0, "time": 0.01}
+    }));
+    assert_eq!(reweighted["path"], serde_json::json!([0, 2]));
+}
+
+#[test]
+fn test_landmarks_ignored_with_turn_delays() {
+    let app = load_test_app_with(
+        "turn_delay",
+        serde_json::json!({"landmarks": {"count": 2, "seed": 0}}),
+    );
+    // turn delays are access costs, which the vertex cost tables cannot bound
+    assert!(app.search_app.landmarks.is_none());
+
+    // tables built from the same models are still ignored by the heuristic
+    let si = app
+        .search_app
+        .build_search_instance(&serde_json::json!({}))
+        .unwrap();
+    let config = LandmarksConfig {
+        count: 2,
+        selection: Default::default(),
+        seed: Some(0),
+    };
+    let landmarks = Landmarks::build(&config, &si, serde_json::json!({})).unwrap();
+    let si = SearchInstance {
+        landmarks: Some(std::sync::Arc::new(landmarks)),
+        ..si
+    };
+    assert!(si.admissible_landmarks().is_none());
+
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2
+    });
+    let result = app.run(&mut [query], None).unwrap();
+    assert_eq!(result[0]["route"]["path"], serde_json::json!([1]));
+}
+
+#[test]
+fn test_swapped_query_coordinates_are_flagged() {
+    let app = load_test_app("speeds");
+    // vertex 0 and vertex 2 written in latitude, longitude order
+    let query = serde_json::json!({
+        "origin_x": 39.738,
+        "origin_y": -105.168,
+        "destination_x": 40.76,
+        "destination_y": -111.91
+    });
+    let result = app.run(&mut [query], None).unwrap();
+    let error = result[0]["error"].as_str().unwrap();
+    assert!(error.contains("look swapped"), "{}", error);
+}
+
+#[test]
+fn test_route_to_best() {
+    let app = load_test_app("speeds");
+    let params = serde_json::json!({});
+
+    // from vertex 0 both candidates are reachable, and each cost matches the
+    // travel time of a one-to-one search to it
+    let best = app
+        .route_to_best(VertexId(0), &[VertexId(2), VertexId(1)], &params)
+        .unwrap();
+    assert_eq!(best["route"]["path"], serde_json::json!([0]));
+    assert_eq!(best["request"]["destination_vertex"], serde_json::json!(1));
+    let ranked = best["candidates"].as_array().unwrap();
+    let ids: Vec<&serde_json::Value> = ranked.iter().map(|c| &c["vertex_id"]).collect();
+    assert_eq!(ids, vec![&serde_json::json!(1), &serde_json::json!(2)]);
+    for candidate in ranked.iter() {
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": candidate["vertex_id"]
+        });
+        let result = app.run(&mut [query], None).unwrap();
+        let route_time = result[0]["route"]["cost"]["time"].as_f64().unwrap();
+        let cost = candidate["cost"].as_f64().unwrap();
+        assert!((cost - route_time).abs() < 1e-9);
+    }
+    assert_eq!(best["unreachable_candidates"], serde_json::json!([]));
+
+    // vertex 0 is the geographically nearest candidate to vertex 1, but it cannot
+    // be reached, so the route goes to the far candidate
+    let best = app
+        .route_to_best(VertexId(1), &[VertexId(0), VertexId(2)], &params)
+        .unwrap();
+    assert_eq!(best["route"]["path"], serde_json::json!([2]));
+    assert_eq!(best["candidates"][0]["vertex_id"], serde_json::json!(2));
+    assert_eq!(best["unreachable_candidates"], serde_json::json!([0]));
+
+    // vertex 2 has no out edges, so no candidate is reachable
+    let none = app
+        .route_to_best(VertexId(2), &[VertexId(0), VertexId(1)], &params)
+        .unwrap();
+    assert!(none.get("route").is_none());
+    assert_eq!(none["no_route"]["cause"], serde_json::json!("disconnected"));
+    assert_eq!(none["unreachable_candidates"], serde_json::json!([0, 1]));
+}
+
+#[test]
+fn test_route_to_best_restricted_candidates() {
+    let app = load_test_app_with(
+        "speeds",
+        serde_json::json!({
+            "frontier": {"type": "edge_avoidance", "ferry_input_file": "test_edge_ferry_last.csv"}
+        }),
+    );
+    let params = serde_json::json!({"avoid_ferries": true});
+
+    // from vertex 1, candidate 0 is not connected and candidate 2 is only
+    // reached over the ferry edge 2, so the search is cut off by the frontier model
+    let none = app
+        .route_to_best(VertexId(1), &[VertexId(0), VertexId(2)], &params)
+        .unwrap();
+    assert!(none.get("route").is_none());
+    assert_eq!(none["no_route"]["cause"], serde_json::json!("restricted"));
+    assert_eq!(none["unreachable_candidates"], serde_json::json!([0, 2]));
+}
+
+#[test]
+fn test_no_route_fallback() {
+    use routee_compass_core::model::unit::{Distance, DistanceUnit, TimeUnit};
+    use routee_compass_core::util::geo::haversine;
+
+    let app = load_test_app("speeds");
+    let run = |destination: usize| {
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": destination,
+            "max_detour_ratio": 1.4,
+            "no_route_fallback": { "speed": 100.0, "speed_unit": "kilometers_per_hour" }
+        });
+        app.run(&mut [query], None).unwrap()[0].clone()
+    };
+
+    // the route to vertex 2 exceeds the detour ratio, so the trip is estimated
+    // from the straight-line distance at 100 kilometers per hour
+    let estimated = run(2);
+    assert!(estimated.get("error").is_none());
+    assert_eq!(estimated["estimated"], serde_json::json!(true));
+    assert_eq!(
+        estimated["no_route"]["cause"],
+        serde_json::json!("detour_exceeded")
+    );
+    let route = &estimated["route"];
+    let distance_unit: DistanceUnit =
+        serde_json::from_value(route["distance_unit"].clone()).unwrap();
+    let time_unit: TimeUnit = serde_json::from_value(route["time_unit"].clone()).unwrap();
+    let distance = route["traversal_summary"]["distance"].as_f64().unwrap();
+    let time = route["traversal_summary"]["time"].as_f64().unwrap();
+    let vertex = |v: usize| {
+        let vertex = app.search_app.graph.get_vertex(&VertexId(v)).unwrap();
+        geo::Coord::from((vertex.x(), vertex.y()))
+    };
+    let expected_km = haversine::coord_distance(&vertex(0), &vertex(2), DistanceUnit::Kilometers)
+        .unwrap()
+        .as_f64();
+    let distance_km = distance_unit
+        .convert(&Distance::new(distance), &DistanceUnit::Kilometers)
+        .as_f64();
+    assert!((distance_km - expected_km).abs() < 1e-6);
+    let hours = time_unit
+        .convert(
+            &routee_compass_core::model::unit::Time::new(time),
+            &TimeUnit::Hours,
+        )
+        .as_f64();
+    assert!((hours - expected_km / 100.0).abs() < 1e-6);
+
+    // a reachable pair is routed as usual
+    let exact = run(1);
+    assert!(exact.get("estimated").is_none());
+    assert_eq!(exact["route"]["path"], serde_json::json!([0]));
+
+    // without the option, the missing route is an error
+    let query = serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2,
+        "max_detour_ratio": 1.4
+    });
+    let failed = app.run(&mut [query], None).unwrap()[0].clone();
+    assert!(failed.get("error").is_some());
+    assert!(failed.get("estimated").is_none());
+}
+
+#[test]
+fn test_query_termination_limits() {
+    let app = load_test_app("speeds");
+
+    // the configured runtime limit is ten minutes. only the first query sets
+    // a runtime limit that has already passed when the search begins.
+    let mut queries = vec![
+        serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "termination": { "query_runtime": "00:00:00" }
+        }),
+        serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2
+        }),
+        serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2,
+            "termination": { "iterations": 0 }
+        }),
+    ];
+    let result = app.run(&mut queries, None).unwrap();
+    assert_eq!(result.len(), 3, "expected three results");
+    let find = |termination: serde_json::Value| {
+        result
+            .iter()
+            .find(|r| r["request"]["termination"] == termination)
+            .unwrap()
+    };
+    let timed_out = find(serde_json::json!({ "query_runtime": "00:00:00" }));
+    assert_eq!(
+        timed_out["no_route"]["cause"],
+        serde_json::json!("timeout"),
+        "{}",
+        timed_out
+    );
+    let unaffected = find(serde_json::Value::Null);
+    assert_eq!(
+        unaffected["route"]["path"],
+        serde_json::json!([0, 2]),
+        "{}",
+        unaffected
+    );
+    let invalid = find(serde_json::json!({ "iterations": 0 }));
+    let error = invalid["error"].as_str().unwrap_or_default();
+    assert!(error.contains("positive integer"), "{}", invalid);
+}
+
+#[test]
+fn test_evaluate_route() {
+    let app = load_test_app("turn_delay");
+
+    // evaluating the route returned by a search reproduces its cost
+    let mut queries = vec![serde_json::json!({
+        "origin_vertex": 0,
+        "destination_vertex": 2,
+        "disable_models": ["turn_delay"]
+    })];
+    let result = app.run(&mut queries, None).unwrap();
+    let searched = &result[0]["route"];
+    let edge_ids: Vec<EdgeId> = serde_json::from_value(searched["path"].clone()).unwrap();
+    assert_eq!(edge_ids, vec![EdgeId(0), EdgeId(2)]);
+    let params = serde_json::json!({"disable_models": ["turn_delay"]});
+    let evaluated = app.evaluate_route(&edge_ids, &params).unwrap();
+    assert_eq!(evaluated["route"]["path"], searched["path"]);
+    assert!(!searched["cost"].is_null());
+    assert_eq!(evaluated["route"]["cost"], searched["cost"]);
+    assert_eq!(
+        evaluated["route"]["traversal_summary"],
+        searched["traversal_summary"]
+    );
+
+    // with the turn delay model enabled, the same route includes the left turn delay
+    let delayed = app
+        .evaluate_route(&edge_ids, &serde_json::json!({}))
+        .unwrap();
+    let time_with_delay = delayed["route"]["traversal_summary"]["time"]
+        .as_f64()
+        .unwrap();
+    let time_without_delay = searched["traversal_summary"]["time"].as_f64().unwrap();
+    assert!((time_with_delay - time_without_delay - 10.0).abs() < 1e-6);
+
+    // edge 2 does not end where edge 0 begins
+    let disconnected = app.evaluate_route(&[EdgeId(2), EdgeId(0)], &params);
+    assert!(disconnected.is_err());
+}
@@ -0,0 +1,101 @@
+use routee_compass_core::model::unit::{AsF64, Distance};
+use routee_compass_core::util::geo::coordinate_orientation::CoordinateOrientation;
+use serde::{Deserialize, Serialize};
+
+/// configuration of the `[map_matching]` section, which sets how far from
+/// each point of a trace to look for candidate edges.
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [map_matching]
+/// # radius for points that do not report their accuracy
+/// candidate_radius_meters = 50.0
+/// # points with an accuracy_m look within accuracy_m * accuracy_radius_factor
+/// accuracy_radius_factor = 2.0
+/// # traces with fewer points or a shorter length are not matched
+/// min_points = 3
+/// min_trace_length_meters = 100.0
+/// # match confidence is exp(-distance / confidence_scale_meters)
+/// confidence_scale_meters = 20.0
+/// low_confidence_threshold = 0.5
+/// # consecutive points farther apart than this are separated by a gap
+/// max_gap_meters = 500.0
+/// # order of the x and y values of trace points
+/// coordinate_orientation = "lon_lat"
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MapMatchingConfig {
+    /// candidate search radius for points without a reported accuracy. if
+    /// omitted, the nearest edge is matched regardless of distance.
+    #[serde(default)]
+    pub candidate_radius_meters: Option<f64>,
+    /// multiplier from a point's reported accuracy to its candidate search radius
+    #[serde(default = "default_accuracy_radius_factor")]
+    pub accuracy_radius_factor: f64,
+    /// traces with fewer points are rejected as too short to match
+    #[serde(default)]
+    pub min_points: Option<usize>,
+    /// traces whose length, the sum of the distances between consecutive
+    /// points, is below this are rejected as too short to match
+    #[serde(default)]
+    pub min_trace_length_meters: Option<f64>,
+    /// distance from a point to its matched edge at which the confidence of the
+    /// match falls to 1/e
+    #[serde(default = "default_confidence_scale_meters")]
+    pub confidence_scale_meters: f64,
+    /// matches with a confidence below this are marked as low confidence
+    #[serde(default = "default_low_confidence_threshold")]
+    pub low_confidence_threshold: f64,
+    /// consecutive points farther apart than this are separated by a gap. points
+    /// matched to edges that do not share a vertex are always separated by a gap.
+    #[serde(default)]
+    pub max_gap_meters: Option<f64>,
+    /// order of the values in the x and y fields of trace points
+    #[serde(default)]
+    pub coordinate_orientation: CoordinateOrientation,
+}
+
+fn default_accuracy_radius_factor() -> f64 {
+    2.0
+}
+
+fn default_confidence_scale_meters() -> f64 {
+    20.0
+}
+
+fn default_low_confidence_threshold() -> f64 {
+    0.5
+}
+
+impl Default for MapMatchingConfig {
+    fn default() -> Self {
+        MapMatchingConfig {
+            candidate_radius_meters: None,
+            accuracy_radius_factor: default_accuracy_radius_factor(),
+            min_points: None,
+            min_trace_length_meters: None,
+            confidence_scale_meters: default_confidence_scale_meters(),
+            low_confidence_threshold: default_low_confidence_threshold(),
+            max_gap_meters: None,
+            coordinate_orientation: CoordinateOrientation::default(),
+        }
+    }
+}
+
+impl MapMatchingConfig {
+    /// the candidate search radius for a point, in meters, given its
+    /// reported accuracy, if any
+    pub fn candidate_radius(&self, accuracy_m: Option<f64>) -> Option<f64> {
+        match accuracy_m {
+            Some(accuracy) => Some(accuracy * self.accuracy_radius_factor),
+            None => self.candidate_radius_meters,
+        }
+    }
+
+    /// the confidence of a match, in [0, 1], given the distance from the point to
+    /// its matched edge
+    pub fn confidence(&self, distance_meters: Distance) -> f64 {
+        (-distance_meters.as_f64() / self.confidence_scale_meters).exp()
+    }
+}
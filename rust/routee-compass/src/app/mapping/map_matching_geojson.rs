@@ -0,0 +1,96 @@
+use super::map_matching_request::TracePoint;
+use super::map_matching_response::MapMatchingResponse;
+use super::mapping_app::MappingApp;
+use super::mapping_app_error::MappingAppError;
+use geo::line_string;
+use geojson::feature::Id;
+use geojson::{Feature, FeatureCollection};
+use serde_json::json;
+
+/// property naming the kind of a segment of a matched trace
+pub const SEGMENT: &str = "segment";
+/// segment value of a run of points matched to the same edge
+pub const MATCHED: &str = "matched";
+/// segment value of the straight line between two points separated by a gap
+pub const GAP: &str = "gap";
+
+/// creates a GeoJSON FeatureCollection of a matched trace, for styling the match by
+/// confidence. each run of consecutive points matched to the same edge becomes a
+/// feature with the edge geometry, carrying the lowest confidence of its points and
+/// whether that is below the low confidence threshold. each gap becomes a feature
+/// with a straight line between the points on either side of it.
+///
+/// # Arguments
+///
+/// * `trace` - the points of the trace, in order of travel
+/// * `response` - the match of the trace
+/// * `app` - the app that matched the trace, providing the edge geometries
+///
+/// # Returns
+///
+/// the FeatureCollection as JSON, or an error if an edge geometry is missing
+pub fn create_match_geojson(
+    trace: &[TracePoint],
+    response: &MapMatchingResponse,
+    app: &MappingApp,
+) -> Result<serde_json::Value, MappingAppError> {
+    let mut features = vec![];
+    let mut run_start = 0;
+    for (idx, edge_id) in response.point_matches.iter().enumerate() {
+        let is_gap = response.gaps.contains(&idx);
+        let run_ends = is_gap || response.point_matches.get(idx + 1) != Some(edge_id);
+        if !run_ends {
+            continue;
+        }
+
+        let confidence = response.confidence[run_start..=idx]
+            .iter()
+            .cloned()
+            .fold(1.0, f64::min);
+        let low_confidence = confidence < app.config.low_confidence_threshold;
+        let geometry = app.get_edge_linestring(*edge_id)?;
+        features.push(Feature {
+            bbox: None,
+            geometry: Some(geojson::Geometry::from(geometry)),
+            id: Some(Id::Number(serde_json::Number::from(edge_id.0))),
+            properties: json!({
+                SEGMENT: MATCHED,
+                "edge_id": edge_id,
+                "confidence": confidence,
+                "low_confidence": low_confidence,
+                "first_point": run_start,
+                "last_point": idx,
+            })
+            .as_object()
+            .cloned(),
+            foreign_members: None,
+        });
+
+        if is_gap {
+            let (a, b) = (&trace[idx], &trace[idx + 1]);
+            let line = line_string![(x: a.x, y: a.y), (x: b.x, y: b.y)];
+            features.push(Feature {
+                bbox: None,
+                geometry: Some(geojson::Geometry::from(&line)),
+                id: None,
+                properties: json!({
+                    SEGMENT: GAP,
+                    "first_point": idx,
+                    "last_point": idx + 1,
+                })
+                .as_object()
+                .cloned(),
+                foreign_members: None,
+            });
+        }
+        run_start = idx + 1;
+    }
+
+    let feature_collection = FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    };
+    serde_json::to_value(feature_collection)
+        .map_err(|e| MappingAppError::GeoJsonError(e.to_string()))
+}
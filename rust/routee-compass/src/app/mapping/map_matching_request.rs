@@ -0,0 +1,46 @@
+use super::mapping_app_error::MappingAppError;
+use serde::{Deserialize, Serialize};
+
+/// a single GPS trace to be matched to the road network. the trace is a
+/// list of points, in order of travel.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MapMatchingRequest {
+    pub trace: Vec<TracePoint>,
+    /// if true, the response includes the candidate edges considered for
+    /// each point of the trace, for debugging
+    #[serde(default)]
+    pub include_candidates: bool,
+    /// limits the candidates reported for each point to the nearest n edges.
+    /// if omitted, every edge is reported, which is large for real networks.
+    #[serde(default)]
+    pub candidates_top_n: Option<usize>,
+    /// if true, the response includes the matched trace as GeoJSON, with
+    /// the confidence of each matched segment and the gaps between them
+    #[serde(default)]
+    pub include_geojson: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct TracePoint {
+    pub x: f64,
+    pub y: f64,
+    /// reported accuracy of the point in meters, such as the GPS horizontal
+    /// accuracy, which scales the radius searched for candidate edges
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accuracy_m: Option<f64>,
+}
+
+impl TryFrom<&serde_json::Value> for MapMatchingRequest {
+    type Error = MappingAppError;
+
+    fn try_from(value: &serde_json::Value) -> Result<Self, Self::Error> {
+        let request: MapMatchingRequest = serde_json::from_value(value.clone())
+            .map_err(|e| MappingAppError::InvalidTrace(e.to_string()))?;
+        if request.trace.is_empty() {
+            return Err(MappingAppError::InvalidTrace(String::from(
+                "trace has no points",
+            )));
+        }
+        Ok(request)
+    }
+}
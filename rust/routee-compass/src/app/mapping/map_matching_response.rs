@@ -0,0 +1,66 @@
+use routee_compass_core::model::{network::EdgeId, unit::Distance};
+use serde::Serialize;
+
+/// result of matching a single trace to the road network.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct MapMatchingResponse {
+    /// the matched edge for each point of the trace, in trace order
+    pub point_matches: Vec<EdgeId>,
+    /// the matched edges with consecutive duplicates removed
+    pub matched_path: Vec<EdgeId>,
+    /// the confidence of each point match, in [0, 1], which decays with the
+    /// distance from the point to its matched edge
+    pub confidence: Vec<f64>,
+    /// the indices of the points that are followed by a gap in the match,
+    /// because the next point is too far away or is matched to an edge that
+    /// is not connected to the edge of this point
+    pub gaps: Vec<usize>,
+    /// the candidate edges for each point of the trace, nearest first, if
+    /// requested. the first candidate of each point is its match.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub candidates: Option<Vec<Vec<MatchCandidate>>>,
+    /// the matched trace as a GeoJSON FeatureCollection, if requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geojson: Option<serde_json::Value>,
+}
+
+/// an edge considered as a match for a point of a trace
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct MatchCandidate {
+    pub edge_id: EdgeId,
+    /// distance from the point to the nearest point of the edge geometry
+    pub distance_meters: Distance,
+}
+
+impl MapMatchingResponse {
+    pub fn new(
+        point_matches: Vec<EdgeId>,
+        confidence: Vec<f64>,
+        gaps: Vec<usize>,
+    ) -> MapMatchingResponse {
+        let mut matched_path = point_matches.clone();
+        matched_path.dedup();
+        MapMatchingResponse {
+            point_matches,
+            matched_path,
+            confidence,
+            gaps,
+            candidates: None,
+            geojson: None,
+        }
+    }
+
+    pub fn with_candidates(self, candidates: Vec<Vec<MatchCandidate>>) -> MapMatchingResponse {
+        MapMatchingResponse {
+            candidates: Some(candidates),
+            ..self
+        }
+    }
+
+    pub fn with_geojson(self, geojson: serde_json::Value) -> MapMatchingResponse {
+        MapMatchingResponse {
+            geojson: Some(geojson),
+            ..self
+        }
+    }
+}
@@ -1,18 +1,47 @@
+use super::map_matching_config::MapMatchingConfig;
+use super::map_matching_geojson;
+use super::map_matching_request::{MapMatchingRequest, TracePoint};
+use super::map_matching_response::{MapMatchingResponse, MatchCandidate};
 use super::mapping_app_error::MappingAppError;
-use geo::LineString;
-use routee_compass_core::model::{map::MapModel, network::EdgeId};
+use crate::app::search::query_mode::QueryMode;
+use crate::plugin::output::output_plugin_ops::package_error;
+use geo::{Closest, ClosestPoint, LineString, Point};
+use rayon::prelude::*;
+use routee_compass_core::model::{
+    map::{MapModel, NearestSearchResult, SpatialIndex},
+    network::{EdgeId, Graph},
+    unit::{AsF64, Distance},
+};
+use routee_compass_core::util::geo::{coordinate_orientation::check_coordinate, haversine};
+use serde_json::json;
+use std::sync::Arc;
 
-/// stub for a binary centered on map matching
+/// application centered on map matching. matches each point of a trace
+/// to its nearest edge using an edge-oriented spatial index, regardless of
+/// the orientation of the configured [`MapModel`]. if a candidate search
+/// radius applies to a point, only edges within that radius are considered.
 pub struct MappingApp {
-    pub map_model: MapModel,
+    pub graph: Arc<Graph>,
+    pub map_model: Arc<MapModel>,
+    pub config: MapMatchingConfig,
+    edge_index: SpatialIndex,
 }
 
 impl MappingApp {
-    // pub fn append_graph_ids(&self, query: &mut serde_json::Value) -> Result<(), MappingAppError> {
-    //     self.map_model
-    //         .map_match(query)
-    //         .map_err(MappingAppError::MapError)
-    // }
+    pub fn new(graph: Arc<Graph>, map_model: Arc<MapModel>) -> MappingApp {
+        let edge_index =
+            SpatialIndex::new_edge_oriented(graph.clone(), &map_model.geometry_model, None);
+        MappingApp {
+            graph,
+            map_model,
+            config: MapMatchingConfig::default(),
+            edge_index,
+        }
+    }
+
+    pub fn with_config(self, config: MapMatchingConfig) -> MappingApp {
+        MappingApp { config, ..self }
+    }
 
     pub fn get_edge_linestring(
         &self,
@@ -22,4 +51,474 @@ impl MappingApp {
             .get(&edge_id)
             .map_err(MappingAppError::MapError)
     }
+
+    /// matches a single trace to the road network.
+    pub fn match_trace(
+        &self,
+        request: &MapMatchingRequest,
+    ) -> Result<MapMatchingResponse, MappingAppError> {
+        let trace = self.orient_trace(&request.trace)?;
+        self.check_trace_length(&trace)?;
+        let matches = trace
+            .iter()
+            .map(|p| self.match_point(p))
+            .collect::<Result<Vec<_>, _>>()?;
+        let confidence = matches
+            .iter()
+            .map(|m| self.config.confidence(m.distance_meters))
+            .collect();
+        let gaps = self.find_gaps(&trace, &matches)?;
+        let point_matches = matches.iter().map(|m| m.edge_id).collect();
+        let mut response = MapMatchingResponse::new(point_matches, confidence, gaps);
+        if request.include_candidates {
+            let candidates = trace
+                .iter()
+                .map(|p| self.match_candidates(p, request.candidates_top_n))
+                .collect::<Result<Vec<_>, _>>()?;
+            response = response.with_candidates(candidates);
+        }
+        if request.include_geojson {
+            let geojson = map_matching_geojson::create_match_geojson(&trace, &response, self)?;
+            response = response.with_geojson(geojson);
+        }
+        Ok(response)
+    }
+
+    /// reads the points of a trace in the configured coordinate orientation,
+    /// rejecting a trace with a point that is not a valid longitude, latitude pair.
+    fn orient_trace(&self, trace: &[TracePoint]) -> Result<Vec<TracePoint>, MappingAppError> {
+        let orientation = self.config.coordinate_orientation;
+        trace
+            .iter()
+            .enumerate()
+            .map(|(idx, p)| {
+                let oriented = orientation.orient(geo::coord! {x: p.x, y: p.y});
+                check_coordinate(&geo::coord! {x: oriented.x as f32, y: oriented.y as f32})
+                    .map_err(|e| {
+                        MappingAppError::InvalidTrace(format!(
+                            "point {}: {}, coordinate_orientation is {}",
+                            idx, e, orientation
+                        ))
+                    })?;
+                Ok(TracePoint {
+                    x: oriented.x,
+                    y: oriented.y,
+                    ..*p
+                })
+            })
+            .collect()
+    }
+
+    /// finds the indices of the points followed by a gap in the match, where the
+    /// next point is farther away than the configured maximum gap or is matched
+    /// to an edge that does not share a vertex with the edge of this point.
+    fn find_gaps(
+        &self,
+        trace: &[TracePoint],
+        matches: &[MatchCandidate],
+    ) -> Result<Vec<usize>, MappingAppError> {
+        let mut gaps = vec![];
+        for (idx, (a, b)) in matches.iter().zip(matches.iter().skip(1)).enumerate() {
+            let connected = a.edge_id == b.edge_id || {
+                let a_edge = self
+                    .graph
+                    .get_edge(&a.edge_id)
+                    .map_err(|_| MappingAppError::InvalidEdgeId(a.edge_id))?;
+                let b_edge = self
+                    .graph
+                    .get_edge(&b.edge_id)
+                    .map_err(|_| MappingAppError::InvalidEdgeId(b.edge_id))?;
+                let a_vertices = [a_edge.src_vertex_id, a_edge.dst_vertex_id];
+                a_vertices.contains(&b_edge.src_vertex_id)
+                    || a_vertices.contains(&b_edge.dst_vertex_id)
+            };
+            let too_far = match self.config.max_gap_meters {
+                Some(max_gap) => {
+                    let (p, q) = (&trace[idx], &trace[idx + 1]);
+                    let p = geo::coord! {x: p.x as f32, y: p.y as f32};
+                    let q = geo::coord! {x: q.x as f32, y: q.y as f32};
+                    let distance = haversine::coord_distance_meters(&p, &q)
+                        .map_err(|e| MappingAppError::InvalidTrace(e.to_string()))?;
+                    distance.as_f64() > max_gap
+                }
+                None => false,
+            };
+            if !connected || too_far {
+                gaps.push(idx);
+            }
+        }
+        Ok(gaps)
+    }
+
+    /// rejects traces below the configured minimum number of points or length,
+    /// which produce meaningless matches.
+    fn check_trace_length(&self, trace: &[TracePoint]) -> Result<(), MappingAppError> {
+        if let Some(min_points) = self.config.min_points {
+            if trace.len() < min_points {
+                return Err(MappingAppError::TraceTooShort(format!(
+                    "trace has {} points, the minimum is {}",
+                    trace.len(),
+                    min_points
+                )));
+            }
+        }
+        if let Some(min_length) = self.config.min_trace_length_meters {
+            let mut length = 0.0;
+            for (a, b) in trace.iter().zip(trace.iter().skip(1)) {
+                let a = geo::coord! {x: a.x as f32, y: a.y as f32};
+                let b = geo::coord! {x: b.x as f32, y: b.y as f32};
+                length += haversine::coord_distance_meters(&a, &b)
+                    .map_err(|e| MappingAppError::InvalidTrace(e.to_string()))?
+                    .as_f64();
+            }
+            if length < min_length {
+                return Err(MappingAppError::TraceTooShort(format!(
+                    "trace is {:.1} meters long, the minimum is {} meters",
+                    length, min_length
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// matches a point to the first of its candidate edges
+    fn match_point(&self, trace_point: &TracePoint) -> Result<MatchCandidate, MappingAppError> {
+        let radius = self.config.candidate_radius(trace_point.accuracy_m);
+        if radius.is_none() {
+            // without a radius, only the nearest edge is needed
+            let point = Point::new(trace_point.x as f32, trace_point.y as f32);
+            return match self.edge_index.nearest_graph_id(&point)? {
+                nearest @ NearestSearchResult::NearestEdge(_) => {
+                    self.match_candidate(&point, nearest)
+                }
+                NearestSearchResult::NearestVertex(vertex_id) => {
+                    Err(MappingAppError::InvalidTrace(format!(
+                        "expected edge match for point ({}, {}), found vertex {}",
+                        trace_point.x, trace_point.y, vertex_id
+                    )))
+                }
+            };
+        }
+        match self.match_candidates(trace_point, Some(1))?.first() {
+            Some(candidate) => Ok(*candidate),
+            None => Err(MappingAppError::InvalidTrace(format!(
+                "no edges within {} meters of point ({}, {})",
+                radius.unwrap_or_default(),
+                trace_point.x,
+                trace_point.y
+            ))),
+        }
+    }
+
+    /// lists up to `limit` edges considered as matches for a point, in the
+    /// order the spatial index ranks them, so that the first candidate is the
+    /// match. if a candidate search radius applies to the point, edges beyond
+    /// it are excluded.
+    fn match_candidates(
+        &self,
+        trace_point: &TracePoint,
+        limit: Option<usize>,
+    ) -> Result<Vec<MatchCandidate>, MappingAppError> {
+        let point = Point::new(trace_point.x as f32, trace_point.y as f32);
+        let radius = self.config.candidate_radius(trace_point.accuracy_m);
+        let nearest = match radius {
+            Some(r) => self
+                .edge_index
+                .nearest_graph_id_iter_within(&point, Distance::new(r)),
+            None => self.edge_index.nearest_graph_id_iter(&point),
+        };
+        nearest
+            .map(|nearest| self.match_candidate(&point, nearest))
+            .filter(|candidate| match (candidate, radius) {
+                (Ok(c), Some(r)) => c.distance_meters.as_f64() <= r,
+                _ => true,
+            })
+            .take(limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+
+    fn match_candidate(
+        &self,
+        point: &Point<f32>,
+        nearest: NearestSearchResult,
+    ) -> Result<MatchCandidate, MappingAppError> {
+        match nearest {
+            NearestSearchResult::NearestEdge(edge_id) => {
+                let linestring = self.get_edge_linestring(edge_id)?;
+                let closest = match linestring.closest_point(point) {
+                    Closest::Intersection(p) | Closest::SinglePoint(p) => p,
+                    Closest::Indeterminate => *point,
+                };
+                let distance_meters = haversine::coord_distance_meters(&point.0, &closest.0)
+                    .map_err(|e| MappingAppError::InvalidTrace(e.to_string()))?;
+                Ok(MatchCandidate {
+                    edge_id,
+                    distance_meters,
+                })
+            }
+            NearestSearchResult::NearestVertex(vertex_id) => {
+                Err(MappingAppError::InvalidTrace(format!(
+                    "expected edge candidate for point ({}, {}), found vertex {}",
+                    point.x(),
+                    point.y(),
+                    vertex_id
+                )))
+            }
+        }
+    }
+
+    /// matches a batch of traces in parallel. each trace is either a JSON
+    /// request or the error produced while reading it. failures are isolated
+    /// to the trace that caused them and are returned as error responses in
+    /// place of the match, preserving the order of the input.
+    pub fn match_trace_batch(
+        &self,
+        traces: Vec<Result<serde_json::Value, MappingAppError>>,
+    ) -> Vec<serde_json::Value> {
+        traces
+            .into_par_iter()
+            .map(|trace| match trace {
+                Err(e) => json!({ "error": e.to_string() }),
+                Ok(json) => {
+                    if let Some(field) = QueryMode::MapMatching.missing_field(&json) {
+                        return package_error(&json, MappingAppError::MissingQueryField(field));
+                    }
+                    let result = MapMatchingRequest::try_from(&json)
+                        .and_then(|request| self.match_trace(&request));
+                    match result {
+                        Ok(response) => json!({ "request": json, "result": response }),
+                        Err(e @ MappingAppError::TraceTooShort(_)) => {
+                            let mut response = package_error(&json, e);
+                            response["reason"] = json!("trace_too_short");
+                            response
+                        }
+                        Err(e) => package_error(&json, e),
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::mapping::mapping_app_ops::read_traces;
+    use routee_compass_core::model::map::MapModelConfig;
+    use std::path::PathBuf;
+
+    fn test_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("app")
+            .join("compass")
+            .join("test")
+            .join("speeds_test")
+    }
+
+    fn build_mapping_app() -> MappingApp {
+        let graph_conf = json!({
+            "edge_list_input_file": test_dir().join("test_edges.csv"),
+            "vertex_list_input_file": test_dir().join("test_vertices.csv"),
+        });
+        let graph = Arc::new(Graph::try_from(&graph_conf).unwrap());
+        let map_model = Arc::new(MapModel::new(graph.clone(), MapModelConfig::default()).unwrap());
+        MappingApp::new(graph, map_model)
+    }
+
+    #[test]
+    fn test_match_trace_batch() {
+        let app = build_mapping_app();
+        let traces_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("app")
+            .join("mapping")
+            .join("test")
+            .join("traces.jsonl");
+        let traces = read_traces(&traces_file).unwrap();
+        assert_eq!(traces.len(), 4);
+        let results = app.match_trace_batch(traces);
+        assert_eq!(results.len(), 4);
+
+        let path_of = |result: &serde_json::Value| -> Vec<u64> {
+            result["result"]["matched_path"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|e| e.as_u64().unwrap())
+                .collect()
+        };
+        assert_eq!(path_of(&results[0]), vec![0]);
+        assert_eq!(results[0]["request"]["trace_id"], "a");
+        assert_eq!(path_of(&results[1]), vec![1, 2]);
+        assert_eq!(
+            results[1]["result"]["point_matches"],
+            json!([1, 1, 2]),
+            "each point should be matched individually"
+        );
+        // malformed JSON line
+        assert!(results[2].get("result").is_none());
+        assert!(results[2]["error"].as_str().unwrap().contains("line 3"));
+        // well-formed JSON but an empty trace
+        assert_eq!(results[3]["request"]["trace_id"], "d");
+        assert!(results[3]["error"].as_str().unwrap().contains("no points"));
+    }
+
+    #[test]
+    fn test_match_candidates() {
+        let app = build_mapping_app();
+        let trace = json!({
+            "trace": [{"x": -108.0, "y": 40.2}, {"x": -110.5, "y": 40.5}, {"x": -111.0, "y": 40.85}],
+            "include_candidates": true,
+            "candidates_top_n": 2
+        });
+        let request = MapMatchingRequest::try_from(&trace).unwrap();
+        let response = app.match_trace(&request).unwrap();
+        let candidates = response.candidates.clone().unwrap();
+        assert_eq!(candidates.len(), request.trace.len());
+        assert!(candidates.iter().all(|c| c.len() == 2));
+        // the best candidate of each point reproduces the match
+        let best: Vec<EdgeId> = candidates.iter().map(|c| c[0].edge_id).collect();
+        assert_eq!(best, response.point_matches);
+
+        // candidates are only reported when requested, and without a limit
+        // every edge is a candidate
+        let full = json!({"trace": trace["trace"], "include_candidates": true});
+        let full = app
+            .match_trace(&MapMatchingRequest::try_from(&full).unwrap())
+            .unwrap();
+        assert!(full.candidates.unwrap().iter().all(|c| c.len() == 3));
+        let plain = json!({"trace": trace["trace"]});
+        let plain = app
+            .match_trace(&MapMatchingRequest::try_from(&plain).unwrap())
+            .unwrap();
+        assert!(plain.candidates.is_none());
+        assert!(serde_json::to_value(&plain)
+            .unwrap()
+            .get("candidates")
+            .is_none());
+    }
+
+    #[test]
+    fn test_candidate_radius_from_accuracy() {
+        let app = build_mapping_app().with_config(MapMatchingConfig {
+            candidate_radius_meters: Some(50.0),
+            accuracy_radius_factor: 2.0,
+            ..Default::default()
+        });
+        // all points lie on edge 0, 45km or more from the other edges
+        let trace = json!({
+            "trace": [
+                {"x": -105.096364, "y": 40.019828, "accuracy_m": 5.0},
+                {"x": -104.988454, "y": 40.442714, "accuracy_m": 100000.0},
+                {"x": -104.916514, "y": 40.724639}
+            ],
+            "include_candidates": true
+        });
+        let request = MapMatchingRequest::try_from(&trace).unwrap();
+        let response = app.match_trace(&request).unwrap();
+        assert_eq!(response.point_matches, vec![EdgeId(0); 3]);
+        let n_candidates: Vec<usize> = response
+            .candidates
+            .unwrap()
+            .iter()
+            .map(|c| c.len())
+            .collect();
+        // the accurate point and the point using the configured radius only
+        // consider edge 0, while the inaccurate point considers every edge
+        assert_eq!(n_candidates, vec![1, 3, 1]);
+
+        // no edge is within the radius of an accurate point far from the network
+        let far = json!({"trace": [{"x": -100.0, "y": 35.0, "accuracy_m": 10.0}]});
+        let result = app.match_trace(&MapMatchingRequest::try_from(&far).unwrap());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_trace_too_short() {
+        let app = build_mapping_app().with_config(MapMatchingConfig {
+            min_points: Some(3),
+            min_trace_length_meters: Some(1000.0),
+            ..Default::default()
+        });
+        // two points a few meters apart in a parking lot, and a trace along edge 0
+        let short =
+            json!({"trace": [{"x": -105.0964, "y": 40.0198}, {"x": -105.0963, "y": 40.0198}]});
+        let huddled = json!({"trace": [
+            {"x": -105.0964, "y": 40.0198},
+            {"x": -105.0963, "y": 40.0198},
+            {"x": -105.0964, "y": 40.0199}
+        ]});
+        let valid = json!({"trace": [
+            {"x": -105.096364, "y": 40.019828},
+            {"x": -104.988454, "y": 40.442714},
+            {"x": -104.916514, "y": 40.724639}
+        ]});
+        let results = app.match_trace_batch(vec![Ok(short), Ok(huddled), Ok(valid)]);
+
+        assert_eq!(results[0]["reason"], "trace_too_short");
+        let error = results[0]["error"].as_str().unwrap();
+        assert!(error.contains("2 points"), "unexpected error {}", error);
+        assert_eq!(results[1]["reason"], "trace_too_short");
+        let error = results[1]["error"].as_str().unwrap();
+        assert!(error.contains("meters long"), "unexpected error {}", error);
+        assert!(results[2].get("error").is_none());
+        assert_eq!(results[2]["result"]["matched_path"], json!([0]));
+    }
+
+    #[test]
+    fn test_match_geojson() {
+        let app = build_mapping_app().with_config(MapMatchingConfig {
+            max_gap_meters: Some(40000.0),
+            ..Default::default()
+        });
+        // points along edge 0, the first two about 48km apart, and the last about
+        // 800m from the edge
+        let trace = json!({
+            "trace": [
+                {"x": -105.096364, "y": 40.019828},
+                {"x": -104.988454, "y": 40.442714},
+                {"x": -104.906514, "y": 40.724639}
+            ],
+            "include_geojson": true
+        });
+        let request = MapMatchingRequest::try_from(&trace).unwrap();
+        let response = app.match_trace(&request).unwrap();
+        assert_eq!(response.point_matches, vec![EdgeId(0); 3]);
+        assert_eq!(response.gaps, vec![0]);
+
+        let geojson = response.geojson.clone().unwrap();
+        let collection = match geojson::GeoJson::from_json_value(geojson).unwrap() {
+            geojson::GeoJson::FeatureCollection(fc) => fc,
+            other => panic!("expected a FeatureCollection, found {}", other),
+        };
+        let props: Vec<_> = collection
+            .features
+            .iter()
+            .map(|f| serde_json::Value::Object(f.properties.clone().unwrap()))
+            .collect();
+        assert_eq!(props.len(), 3);
+        assert_eq!(props[0]["segment"], "matched");
+        assert_eq!(props[0]["low_confidence"], false);
+        assert!(props[0]["confidence"].as_f64().unwrap() > 0.9);
+        assert_eq!(props[1]["segment"], "gap");
+        assert_eq!(
+            (
+                props[1]["first_point"].clone(),
+                props[1]["last_point"].clone()
+            ),
+            (json!(0), json!(1))
+        );
+        assert_eq!(props[2]["segment"], "matched");
+        assert_eq!(props[2]["edge_id"], 0);
+        assert_eq!(props[2]["low_confidence"], true);
+        assert!(props[2]["confidence"].as_f64().unwrap() < 0.5);
+
+        // GeoJSON is only included when requested
+        let plain = json!({"trace": trace["trace"]});
+        let plain = app
+            .match_trace(&MapMatchingRequest::try_from(&plain).unwrap())
+            .unwrap();
+        assert!(plain.geojson.is_none());
+    }
 }
@@ -6,4 +6,14 @@ pub enum MappingAppError {
     MapError(#[from] MapError),
     #[error("expecting edge id {0} not found")]
     InvalidEdgeId(EdgeId),
+    #[error("missing field: {0}")]
+    MissingQueryField(String),
+    #[error("invalid trace: {0}")]
+    InvalidTrace(String),
+    #[error("trace too short to match: {0}")]
+    TraceTooShort(String),
+    #[error("failure reading traces: {0}")]
+    TraceFileError(String),
+    #[error("failure building GeoJSON: {0}")]
+    GeoJsonError(String),
 }
@@ -0,0 +1,30 @@
+use super::mapping_app_error::MappingAppError;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// reads a newline-delimited JSON file of traces, one trace per line.
+/// blank lines are skipped. a line that cannot be parsed does not fail the
+/// whole file; instead its error is returned in place of the trace so that
+/// it can be reported alongside the matching results.
+pub fn read_traces(
+    path: &Path,
+) -> Result<Vec<Result<serde_json::Value, MappingAppError>>, MappingAppError> {
+    let file = File::open(path).map_err(|e| {
+        MappingAppError::TraceFileError(format!("unable to open {}: {}", path.display(), e))
+    })?;
+    let mut traces = vec![];
+    for (idx, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| {
+            MappingAppError::TraceFileError(format!("unable to read {}: {}", path.display(), e))
+        })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let trace = serde_json::from_str(&line).map_err(|e| {
+            MappingAppError::InvalidTrace(format!("line {} is not valid JSON: {}", idx + 1, e))
+        });
+        traces.push(trace);
+    }
+    Ok(traces)
+}
@@ -1,2 +1,7 @@
+pub mod map_matching_config;
+pub mod map_matching_geojson;
+pub mod map_matching_request;
+pub mod map_matching_response;
 pub mod mapping_app;
 pub mod mapping_app_error;
+pub mod mapping_app_ops;
@@ -1,3 +1,4 @@
+pub mod query_mode;
 mod search_app;
 mod search_app_graph_ops;
 pub mod search_app_ops;
@@ -0,0 +1,156 @@
+use crate::plugin::input::InputField;
+use routee_compass_core::model::map::MatchingType;
+
+/// the kind of request a query makes, which decides the fields it requires.
+pub enum QueryMode<'a> {
+    /// a route or tree search from an origin, and to a destination when one is
+    /// required, with endpoints given in any of the forms of the matching type
+    Route {
+        matching_type: &'a MatchingType,
+        destination_required: bool,
+    },
+    /// a trace of points to match to the road network
+    MapMatching,
+}
+
+impl QueryMode<'_> {
+    /// finds the first required field missing from a query, so that it can be
+    /// rejected with an error naming the field before any search runs. an
+    /// endpoint may be given in any form of the matching type, such as a point
+    /// or a vertex id. when it is partly given, as a `destination_y` without a
+    /// `destination_x`, the missing part of that form is reported. when a
+    /// required destination is not given at all, the field reported is of the
+    /// same form as the origin.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - the query to check, after input plugins have run
+    ///
+    /// # Returns
+    ///
+    /// the name of the missing field, or None if the query has every field it requires
+    pub fn missing_field(&self, query: &serde_json::Value) -> Option<String> {
+        match self {
+            QueryMode::MapMatching => match query.get("trace") {
+                None => Some(String::from("trace")),
+                Some(_) => None,
+            },
+            QueryMode::Route {
+                matching_type,
+                destination_required,
+            } => {
+                let forms = matching_forms(matching_type);
+                let origin_forms = forms.iter().map(origin_fields).collect::<Vec<_>>();
+                let origin_form = match check_endpoint(query, &origin_forms) {
+                    Endpoint::Missing(field) => return Some(field),
+                    Endpoint::Absent => return Some(origin_forms.first()?.first()?.to_string()),
+                    Endpoint::Found(form) => form,
+                };
+                let destination_forms = forms.iter().map(destination_fields).collect::<Vec<_>>();
+                match check_endpoint(query, &destination_forms) {
+                    Endpoint::Missing(field) => Some(field),
+                    Endpoint::Absent if *destination_required => destination_forms
+                        .get(origin_form)
+                        .and_then(|fields| fields.first())
+                        .map(|f| f.to_string()),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+/// how an endpoint is given on a query
+enum Endpoint {
+    /// every field of the form at this index is present
+    Found(usize),
+    /// some fields of a form are present, but not this one
+    Missing(String),
+    /// no field of any form is present
+    Absent,
+}
+
+fn check_endpoint(query: &serde_json::Value, forms: &[Vec<InputField>]) -> Endpoint {
+    let present = |field: &InputField| query.get(field.to_str()).is_some();
+    if let Some(index) = forms.iter().position(|f| f.iter().all(present)) {
+        return Endpoint::Found(index);
+    }
+    forms
+        .iter()
+        .filter(|f| f.iter().any(present))
+        .find_map(|f| f.iter().find(|field| !present(field)))
+        .map(|field| Endpoint::Missing(field.to_string()))
+        .unwrap_or(Endpoint::Absent)
+}
+
+/// the single forms of a matching type, in the order they are tried
+fn matching_forms(matching_type: &MatchingType) -> Vec<MatchingType> {
+    match matching_type {
+        MatchingType::Combined(types) => types.iter().flat_map(matching_forms).collect(),
+        other => vec![other.clone()],
+    }
+}
+
+fn origin_fields(matching_type: &MatchingType) -> Vec<InputField> {
+    match matching_type {
+        MatchingType::Point => vec![InputField::OriginX, InputField::OriginY],
+        MatchingType::VertexId => vec![InputField::OriginVertex],
+        MatchingType::EdgeId => vec![InputField::OriginEdge],
+        MatchingType::Combined(_) => vec![],
+    }
+}
+
+fn destination_fields(matching_type: &MatchingType) -> Vec<InputField> {
+    match matching_type {
+        MatchingType::Point => vec![InputField::DestinationX, InputField::DestinationY],
+        MatchingType::VertexId => vec![InputField::DestinationVertex],
+        MatchingType::EdgeId => vec![InputField::DestinationEdge],
+        MatchingType::Combined(_) => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_route_fields() {
+        let matching_type = MatchingType::default();
+        let mode = QueryMode::Route {
+            matching_type: &matching_type,
+            destination_required: true,
+        };
+        let missing = |query: serde_json::Value| mode.missing_field(&query);
+        assert_eq!(
+            missing(json!({"origin_x": 0.0, "origin_y": 0.0, "destination_y": 0.0})),
+            Some(String::from("destination_x"))
+        );
+        assert_eq!(
+            missing(json!({"origin_vertex": 0})),
+            Some(String::from("destination_vertex"))
+        );
+        assert_eq!(
+            missing(json!({"destination_vertex": 0})),
+            Some(String::from("origin_x"))
+        );
+        assert_eq!(
+            missing(json!({"origin_x": 0.0, "destination_vertex": 1})),
+            Some(String::from("origin_y"))
+        );
+        assert_eq!(
+            missing(json!({"origin_edge": 0, "destination_vertex": 1})),
+            None
+        );
+
+        let tree = QueryMode::Route {
+            matching_type: &matching_type,
+            destination_required: false,
+        };
+        assert_eq!(tree.missing_field(&json!({"origin_vertex": 0})), None);
+        assert_eq!(
+            QueryMode::MapMatching.missing_field(&json!({"origin_vertex": 0})),
+            Some(String::from("trace"))
+        );
+    }
+}
@@ -1,23 +1,43 @@
-use super::{search_app_ops, search_app_result::SearchAppResult};
+use super::{query_mode::QueryMode, search_app_ops, search_app_result::SearchAppResult};
 use crate::{
-    app::compass::{model::cost_model::cost_model_service::CostModelService, CompassAppError},
+    app::compass::{
+        compass_app_fingerprint::CompassAppFingerprint,
+        model::cost_model::cost_model_service::CostModelService, CompassAppError,
+    },
     plugin::{input::InputJsonExtensions, PluginError},
 };
 use chrono::Local;
+use geo::Polygon;
+use rayon::prelude::*;
 use routee_compass_core::{
-    algorithm::search::{Direction, SearchAlgorithm, SearchError, SearchInstance},
+    algorithm::search::{
+        a_star::{self, Landmarks},
+        backtrack, ev_isochrone, Direction, NoRouteCause, SearchAlgorithm, SearchAlgorithmResult,
+        SearchError, SearchInstance,
+    },
     model::{
-        access::AccessModelService, frontier::FrontierModelService, map::MapModel,
-        network::graph::Graph, state::StateModel, termination::TerminationModel,
+        access::AccessModelService,
+        frontier::FrontierModelService,
+        map::MapModel,
+        network::{graph::Graph, EdgeId, VertexId},
+        state::StateModel,
+        termination::TerminationModel,
         traversal::TraversalModelService,
+        unit::Cost,
     },
 };
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time;
 
+/// candidate destinations ranked by cost along with the route to the best one
+pub type RankedRouteResult = (Vec<(VertexId, Cost)>, SearchAppResult, SearchInstance);
+
 /// a configured and loaded application to execute searches.
 pub struct SearchApp {
     pub search_algorithm: SearchAlgorithm,
+    /// algorithms that a query may select by name in place of `search_algorithm`
+    pub search_algorithms: HashMap<String, SearchAlgorithm>,
     pub graph: Arc<Graph>,
     pub map_model: Arc<MapModel>,
     pub state_model: Arc<StateModel>,
@@ -26,6 +46,10 @@ pub struct SearchApp {
     pub cost_model_service: Arc<CostModelService>,
     pub frontier_model_service: Arc<dyn FrontierModelService>,
     pub termination_model: Arc<TerminationModel>,
+    /// fingerprints of the loaded inputs, added to each response when set
+    pub fingerprint: Option<CompassAppFingerprint>,
+    /// landmark cost tables shared by the search instance of every query
+    pub landmarks: Option<Arc<Landmarks>>,
 }
 
 impl SearchApp {
@@ -45,6 +69,7 @@ impl SearchApp {
     ) -> Self {
         SearchApp {
             search_algorithm,
+            search_algorithms: HashMap::new(),
             graph,
             map_model,
             state_model,
@@ -53,6 +78,36 @@ impl SearchApp {
             cost_model_service: Arc::new(cost_model_service),
             frontier_model_service,
             termination_model: Arc::new(termination_model),
+            fingerprint: None,
+            landmarks: None,
+        }
+    }
+
+    /// registers algorithms that a query may select by name with the
+    /// `search_algorithm` key in place of the default algorithm.
+    pub fn with_search_algorithms(
+        self,
+        search_algorithms: HashMap<String, SearchAlgorithm>,
+    ) -> SearchApp {
+        SearchApp {
+            search_algorithms,
+            ..self
+        }
+    }
+
+    /// sets the fingerprints of the loaded inputs to add to each response
+    pub fn with_fingerprint(self, fingerprint: CompassAppFingerprint) -> SearchApp {
+        SearchApp {
+            fingerprint: Some(fingerprint),
+            ..self
+        }
+    }
+
+    /// sets the landmark cost tables used to tighten the A* heuristic
+    pub fn with_landmarks(self, landmarks: Landmarks) -> SearchApp {
+        SearchApp {
+            landmarks: Some(Arc::new(landmarks)),
+            ..self
         }
     }
 
@@ -73,18 +128,49 @@ impl SearchApp {
         query: &mut serde_json::Value,
     ) -> Result<(SearchAppResult, SearchInstance), CompassAppError> {
         let search_start_time = Local::now();
+        let search_algorithm = search_app_ops::select_search_algorithm(
+            query,
+            &self.search_algorithm,
+            &self.search_algorithms,
+        )?;
         let si = self.build_search_instance(query)?;
-        self.map_model.map_match(query, &si)?;
+        let destination_polygon =
+            search_app_ops::get_destination_polygon(query, &self.map_model.coordinate_orientation)?;
+        let require_edges = search_app_ops::get_require_edges(query)?;
+        if require_edges.is_some() && destination_polygon.is_some() {
+            return Err(CompassAppError::CompassFailure(format!(
+                "{} cannot be combined with {}",
+                search_app_ops::REQUIRE_EDGES,
+                search_app_ops::DESTINATION_POLYGON
+            )));
+        }
+        let mode = QueryMode::Route {
+            matching_type: &self.map_model.matching_type,
+            destination_required: destination_polygon.is_none()
+                && !self.map_model.queries_without_destinations,
+        };
+        if let Some(field) = mode.missing_field(query) {
+            return Err(CompassAppError::MissingQueryField(field));
+        }
+        match destination_polygon {
+            // the destination is a region, so only the origin is map matched
+            Some(_) => self.map_model.matching_type.process_origin(query, &si)?,
+            None => self.map_model.map_match(query, &si)?,
+        }
 
         // depending on the presence of an origin edge or origin vertex, we run each type of query
-        let results = if query.get_origin_edge().is_ok() {
+        let results = if let Some(polygon) = &destination_polygon {
+            self.run_to_region(query, polygon, &si)
+        } else if let Some(required) = &require_edges {
+            self.run_through_edges(query, required, search_algorithm, &si)
+        } else if query.get_origin_edge().is_ok() {
             let o = query.get_origin_edge().map_err(|e| {
                 CompassAppError::PluginError(PluginError::InputPluginFailed { source: e })
             })?;
             let d_opt = query.get_destination_edge().map_err(|e| {
                 CompassAppError::PluginError(PluginError::InputPluginFailed { source: e })
             })?;
-            self.search_algorithm
+            search_algorithm
                 .run_edge_oriented(o, d_opt, query, &Direction::Forward, &si)
                 .map_err(CompassAppError::SearchFailure)
         } else if query.get_origin_vertex().is_ok() {
@@ -95,13 +181,59 @@ impl SearchApp {
                 CompassAppError::PluginError(PluginError::InputPluginFailed { source: e })
             })?;
 
-            self.search_algorithm
+            search_algorithm
                 .run_vertex_oriented(o, d, query, &Direction::Forward, &si)
                 .map_err(CompassAppError::SearchFailure)
         } else {
             Err(CompassAppError::CompassFailure(String::from("SearchApp.run called with query that lacks origin_edge and origin_vertex, at least one required")))
         }?;
 
+        // edge-oriented routes measure between the vertices of the origin and destination
+        // edges, so add the parts of those edges traveled from or to snapped coordinates
+        let mut results = results;
+        if destination_polygon.is_none() && query.get_origin_edge().is_ok() {
+            let include = search_app_ops::get_include_partial_edges(query)?;
+            let first_traveled = self.traveled_fraction(query, true, include)?;
+            let last_traveled = self.traveled_fraction(query, false, include)?;
+            for route in results.routes.iter_mut() {
+                search_app_ops::apply_partial_edges(route, first_traveled, last_traveled, &si)?;
+            }
+        }
+
+        if let Some(max_ratio) = search_app_ops::get_max_detour_ratio(query)? {
+            for route in results.routes.iter() {
+                match search_app_ops::detour_ratio(route, &si.graph)? {
+                    Some(ratio) if ratio > max_ratio => {
+                        return Err(CompassAppError::SearchFailure(
+                            SearchError::DetourRatioExceeded(ratio, max_ratio),
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // the routes are also costed under any secondary cost models, from the
+        // states already accumulated by the search
+        let secondary_queries = search_app_ops::get_secondary_cost_queries(query)?;
+        let mut secondary_costs = vec![HashMap::new(); results.routes.len()];
+        for (name, secondary_query) in secondary_queries.iter() {
+            let cost_model = self
+                .cost_model_service
+                .build(secondary_query, si.state_model.clone())?;
+            for (route, costs) in results.routes.iter().zip(secondary_costs.iter_mut()) {
+                let cost = search_app_ops::route_cost(route, &cost_model, &si)?;
+                costs.insert(name.clone(), cost);
+            }
+        }
+
+        // only a search between the origin and destination returns alternative routes
+        let alternatives_requested = if destination_polygon.is_none() && require_edges.is_none() {
+            search_algorithm.alternatives_requested(query)?
+        } else {
+            None
+        };
+
         let search_end_time = Local::now();
         let search_runtime = (search_end_time - search_start_time)
             .to_std()
@@ -118,11 +250,451 @@ impl SearchApp {
             search_executed_time: search_start_time.to_rfc3339(),
             search_runtime,
             iterations: results.iterations,
+            secondary_costs,
+            alternatives_requested,
+        };
+
+        Ok((result, si))
+    }
+
+    /// the fraction of the origin or destination edge of an edge-oriented query that
+    /// is traveled, when the edge was snapped from a query coordinate. this is the
+    /// part between the coordinate and the rest of the route, or zero if partial
+    /// edges are not included. None if the edge was not snapped from a coordinate.
+    fn traveled_fraction(
+        &self,
+        query: &serde_json::Value,
+        origin: bool,
+        include_partial_edges: bool,
+    ) -> Result<Option<f64>, CompassAppError> {
+        let (edge_id, coordinate) = if origin {
+            (
+                query.get_origin_edge().ok(),
+                query.get_origin_coordinate().ok(),
+            )
+        } else {
+            (
+                query.get_destination_edge().ok().flatten(),
+                query.get_destination_coordinate().ok().flatten(),
+            )
         };
+        let (edge_id, coordinate) = match (edge_id, coordinate) {
+            (Some(edge_id), Some(coordinate)) => (edge_id, coordinate),
+            _ => return Ok(None),
+        };
+        if !include_partial_edges {
+            return Ok(Some(0.0));
+        }
+        let coordinate = self.map_model.coordinate_orientation.orient(coordinate);
+        let fraction = search_app_ops::snapped_fraction(self.map_model.get(&edge_id)?, coordinate);
+        if origin {
+            Ok(Some(1.0 - fraction))
+        } else {
+            Ok(Some(fraction))
+        }
+    }
+
+    /// finds the route from the query origin to the cheapest entry point of a
+    /// destination region. the search stops at the first vertex inside the polygon
+    /// that it reaches, which is set as the `destination_vertex` of the query.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - a JSON search query with an origin vertex
+    /// * `polygon` - the destination region
+    /// * `si` - the search assets for this query
+    ///
+    /// # Results
+    ///
+    /// The route to the entry point and the search tree, or an error if the region
+    /// contains no vertices or none of them are reachable.
+    fn run_to_region(
+        &self,
+        query: &mut serde_json::Value,
+        polygon: &Polygon<f32>,
+        si: &SearchInstance,
+    ) -> Result<SearchAlgorithmResult, CompassAppError> {
+        let origin = query.get_origin_vertex().map_err(|e| {
+            CompassAppError::PluginError(PluginError::InputPluginFailed { source: e })
+        })?;
+        let region = self
+            .map_model
+            .spatial_index
+            .vertices_within(polygon, &si.graph)?;
+        let target = region.iter().min().cloned().ok_or_else(|| {
+            SearchError::BuildError(format!(
+                "{} does not contain any vertices of the graph",
+                search_app_ops::DESTINATION_POLYGON
+            ))
+        })?;
+        let (entry, result) = a_star::run_first_target(origin, &region, &Direction::Forward, si)?;
+        let (entry, _) = match entry {
+            Some(entry) => entry,
+            None => return Err(si.no_path_error(origin, target, result.restricted).into()),
+        };
+        let route = backtrack::vertex_oriented_route(origin, entry, &result.tree)?;
+        if let Some(obj) = query.as_object_mut() {
+            obj.insert(String::from("destination_vertex"), serde_json::json!(entry));
+        }
+        Ok(SearchAlgorithmResult {
+            trees: vec![result.tree],
+            routes: vec![route],
+            iterations: result.iterations,
+        })
+    }
+
+    /// finds a route from the query origin to its destination that traverses the
+    /// required edges in the given order. a shortest path is found from the origin
+    /// to the start of the first required edge, from the end of each required edge
+    /// to the start of the next, and from the end of the last to the destination.
+    /// the legs are joined and traversed again so that the state of the route is
+    /// continuous.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - a JSON search query with an origin and destination vertex
+    /// * `required` - the edges the route must traverse, in order
+    /// * `search_algorithm` - the algorithm used to find each leg
+    /// * `si` - the search assets for this query
+    ///
+    /// # Results
+    ///
+    /// The route and the search tree of each leg, or an error naming the required
+    /// edge or destination that cannot be reached.
+    fn run_through_edges(
+        &self,
+        query: &serde_json::Value,
+        required: &[EdgeId],
+        search_algorithm: &SearchAlgorithm,
+        si: &SearchInstance,
+    ) -> Result<SearchAlgorithmResult, CompassAppError> {
+        if query.get_origin_edge().is_ok() {
+            return Err(CompassAppError::CompassFailure(format!(
+                "{} is only supported for queries with an origin and destination vertex",
+                search_app_ops::REQUIRE_EDGES
+            )));
+        }
+        let origin = query.get_origin_vertex().map_err(|e| {
+            CompassAppError::PluginError(PluginError::InputPluginFailed { source: e })
+        })?;
+        let destination = query
+            .get_destination_vertex()
+            .map_err(|e| {
+                CompassAppError::PluginError(PluginError::InputPluginFailed { source: e })
+            })?
+            .ok_or_else(|| {
+                CompassAppError::CompassFailure(format!(
+                    "{} requires a destination",
+                    search_app_ops::REQUIRE_EDGES
+                ))
+            })?;
+
+        let mut edge_ids: Vec<EdgeId> = vec![];
+        let mut trees = vec![];
+        let mut iterations = 0;
+        let mut leg_start = origin;
+        let mut prev_required: Option<EdgeId> = None;
+        let targets = required.iter().map(Some).chain(std::iter::once(None));
+        for target in targets {
+            let leg_end = match target {
+                Some(edge_id) => si.graph.src_vertex_id(edge_id)?,
+                None => destination,
+            };
+            // a leg cut off by the frontier model keeps the restricted cause
+            let unreachable = |e: SearchError| match (e.no_route_cause(), target) {
+                (Some(NoRouteCause::Restricted), _) => e,
+                (Some(_), Some(edge_id)) => {
+                    SearchError::RequiredEdgeUnreachable(*edge_id, leg_start)
+                }
+                (Some(_), None) => match prev_required {
+                    Some(edge_id) => {
+                        SearchError::DestinationUnreachableAfterRequiredEdge(edge_id, destination)
+                    }
+                    None => e,
+                },
+                (None, _) => e,
+            };
+            let leg = search_algorithm
+                .run_vertex_oriented(leg_start, Some(leg_end), query, &Direction::Forward, si)
+                .map_err(unreachable)?;
+            let route = leg.routes.into_iter().next().unwrap_or_default();
+            if route.is_empty() && leg_start != leg_end {
+                return Err(CompassAppError::SearchFailure(unreachable(
+                    SearchError::NoPathExistsBetweenVertices(leg_start, leg_end),
+                )));
+            }
+            edge_ids.extend(route.iter().map(|et| et.edge_id));
+            trees.extend(leg.trees);
+            iterations += leg.iterations;
+            if let Some(edge_id) = target {
+                edge_ids.push(*edge_id);
+                leg_start = si.graph.dst_vertex_id(edge_id)?;
+                prev_required = Some(*edge_id);
+            }
+        }
+
+        let route = search_app_ops::traverse_edges(&edge_ids, si)?;
+        Ok(SearchAlgorithmResult {
+            trees,
+            routes: vec![route],
+            iterations,
+        })
+    }
+
+    /// evaluates a user-supplied route without running a search. the route is
+    /// traversed in order, applying the traversal, access and cost models built
+    /// for this query, so that it can be compared with routes from other sources.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge_ids` - the route to evaluate, as a sequence of connected edges
+    /// * `query` - a JSON query providing any model parameters for this evaluation
+    ///
+    /// # Results
+    ///
+    /// A result containing the evaluated route, along with the search assets, or an
+    /// error if the route is empty or the edges are not connected.
+    pub fn evaluate_route(
+        &self,
+        edge_ids: &[EdgeId],
+        query: &serde_json::Value,
+    ) -> Result<(SearchAppResult, SearchInstance), CompassAppError> {
+        let search_start_time = Local::now();
+        let si = self.build_search_instance(query)?;
+        if edge_ids.is_empty() {
+            return Err(CompassAppError::CompassFailure(String::from(
+                "cannot evaluate an empty route",
+            )));
+        }
+
+        let route = search_app_ops::traverse_edges(edge_ids, &si)?;
 
+        let search_runtime = (Local::now() - search_start_time)
+            .to_std()
+            .unwrap_or(time::Duration::ZERO);
+        let result = SearchAppResult {
+            routes: vec![route],
+            trees: vec![],
+            search_executed_time: search_start_time.to_rfc3339(),
+            search_runtime,
+            iterations: 0,
+            secondary_costs: vec![],
+            alternatives_requested: None,
+        };
         Ok((result, si))
     }
 
+    /// counts the vertices reachable from the query origin within the query's
+    /// `cost_budget`, including the origin itself. only the size of the reachable
+    /// set is computed; no search tree is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - a JSON search query with an origin and a `cost_budget`
+    ///
+    /// # Results
+    ///
+    /// The number of reachable vertices, or an error if the query has no origin
+    /// or budget, or the search fails.
+    pub fn reachable_count(&self, query: &mut serde_json::Value) -> Result<usize, CompassAppError> {
+        let si = self.build_search_instance(query)?;
+        let budget = search_app_ops::get_cost_budget(query)?;
+        // reachable set searches have no destination, so only the origin is map matched
+        self.map_model.matching_type.process_origin(query, &si)?;
+        let origin = query.get_origin_vertex().map_err(|e| {
+            CompassAppError::PluginError(PluginError::InputPluginFailed { source: e })
+        })?;
+        let count = a_star::run_reachable_count(origin, budget, &Direction::Forward, &si)?;
+        Ok(count)
+    }
+
+    /// finds the vertices an electric vehicle can reach from the query origin while
+    /// keeping the query's `reserve_soc_percent`, charging to full at any of the
+    /// query's `chargers` along the way. the starting state of charge is set on the
+    /// vehicle, such as with `starting_soc_percent`.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - a JSON search query with an origin and optionally the
+    ///   `chargers`, `reserve_soc_percent` and `soc_feature`
+    ///
+    /// # Results
+    ///
+    /// Each reachable vertex with the most charge remaining there in percent, or an
+    /// error if the query has no origin, the state has no state of charge feature,
+    /// or the search fails.
+    pub fn ev_isochrone(
+        &self,
+        query: &mut serde_json::Value,
+    ) -> Result<Vec<(VertexId, f64)>, CompassAppError> {
+        let si = self.build_search_instance(query)?;
+        let chargers = search_app_ops::get_chargers(query)?;
+        let reserve_soc = search_app_ops::get_reserve_soc_percent(query)?;
+        let soc_feature = match query.get(search_app_ops::SOC_FEATURE) {
+            None => String::from(search_app_ops::DEFAULT_SOC_FEATURE),
+            Some(value) => value.as_str().map(String::from).ok_or_else(|| {
+                SearchError::BuildError(format!(
+                    "{} must be a string, found {}",
+                    search_app_ops::SOC_FEATURE,
+                    value
+                ))
+            })?,
+        };
+        // isochrone searches have no destination, so only the origin is map matched
+        self.map_model.matching_type.process_origin(query, &si)?;
+        let origin = query.get_origin_vertex().map_err(|e| {
+            CompassAppError::PluginError(PluginError::InputPluginFailed { source: e })
+        })?;
+        let reachable = ev_isochrone::run(origin, &chargers, &soc_feature, reserve_soc, &si)?;
+        Ok(reachable)
+    }
+
+    /// finds the `k` points of interest nearest to the origin by network cost,
+    /// in order of increasing cost. the search stops once `k` points of interest
+    /// have been reached, rather than searching the whole graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - the vertex to search from
+    /// * `poi_set` - the vertices that are points of interest
+    /// * `k` - the number of points of interest to find
+    /// * `query` - query used to build the models, such as model overrides
+    ///
+    /// # Results
+    ///
+    /// Up to `k` points of interest along with the cost to reach them. fewer are
+    /// returned if fewer are reachable from the origin.
+    pub fn nearest_pois(
+        &self,
+        origin: VertexId,
+        poi_set: &HashSet<VertexId>,
+        k: usize,
+        query: &serde_json::Value,
+    ) -> Result<Vec<(VertexId, Cost)>, CompassAppError> {
+        let si = self.build_search_instance(query)?;
+        let nearest = a_star::run_nearest_targets(origin, poi_set, k, &Direction::Forward, &si)?;
+        Ok(nearest)
+    }
+
+    /// computes the network cost from each origin to each destination. runs one
+    /// one-to-many search per origin, in parallel, each stopping once every
+    /// destination has been reached. routes are not reconstructed. the search
+    /// instance is built once and shared by the searches.
+    ///
+    /// # Arguments
+    ///
+    /// * `origins` - the vertices to search from
+    /// * `destinations` - the vertices to search to
+    /// * `query` - query used to build the models, such as model overrides
+    ///
+    /// # Results
+    ///
+    /// A row for each origin with the cost to each destination, in the order given,
+    /// where None marks a destination that is not reachable from that origin.
+    pub fn cost_matrix(
+        &self,
+        origins: &[VertexId],
+        destinations: &[VertexId],
+        query: &serde_json::Value,
+    ) -> Result<Vec<Vec<Option<Cost>>>, CompassAppError> {
+        let si = self.build_search_instance(query)?;
+        let destination_set: HashSet<VertexId> = destinations.iter().cloned().collect();
+        origins
+            .par_iter()
+            .map(|origin| {
+                let (ranked, _) = a_star::run_ranked_targets(
+                    *origin,
+                    &destination_set,
+                    &Direction::Forward,
+                    &si,
+                )?;
+                let costs: HashMap<VertexId, Cost> = ranked.into_iter().collect();
+                let row = destinations
+                    .iter()
+                    .map(|destination| costs.get(destination).cloned())
+                    .collect();
+                Ok(row)
+            })
+            .collect()
+    }
+
+    /// ranks candidate destinations by network cost from the origin and finds the
+    /// route to the best one. runs a single one-to-many search that stops once every
+    /// candidate has been reached, rather than one search per candidate.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - the vertex to search from
+    /// * `candidates` - the candidate destination vertices
+    /// * `query` - query used to build the models, such as model overrides
+    ///
+    /// # Results
+    ///
+    /// The reachable candidates along with the cost to reach them, lowest cost first,
+    /// and the route to the first of them, or an error if no candidate is reachable.
+    pub fn route_to_best(
+        &self,
+        origin: VertexId,
+        candidates: &HashSet<VertexId>,
+        query: &serde_json::Value,
+    ) -> Result<RankedRouteResult, CompassAppError> {
+        let search_start_time = Local::now();
+        let si = self.build_search_instance(query)?;
+        let (ranked, result) =
+            a_star::run_ranked_targets(origin, candidates, &Direction::Forward, &si)?;
+        // the search toward every candidate is classified together, reported
+        // against the candidate with the lowest id
+        let (best, _) = match ranked.first() {
+            Some(best) => best,
+            None => {
+                let target = candidates.iter().min().cloned().unwrap_or(origin);
+                return Err(si.no_path_error(origin, target, result.restricted).into());
+            }
+        };
+        let route = backtrack::vertex_oriented_route(origin, *best, &result.tree)?;
+
+        let search_runtime = (Local::now() - search_start_time)
+            .to_std()
+            .unwrap_or(time::Duration::ZERO);
+        let search_result = SearchAppResult {
+            routes: vec![route],
+            trees: vec![],
+            search_executed_time: search_start_time.to_rfc3339(),
+            search_runtime,
+            iterations: result.iterations,
+            secondary_costs: vec![],
+            alternatives_requested: None,
+        };
+        Ok((ranked, search_result, si))
+    }
+
+    /// confirms that the state model assembled for a query is self-consistent. the
+    /// input features of the traversal and access models must be provided upstream,
+    /// feature names must not collide, and the cost weights and cost expression must
+    /// reference known state features.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - the query used to build the models, which may be empty
+    ///
+    /// # Returns
+    ///
+    /// the state features in state vector order, each paired with the source providing
+    /// it, or an error describing the inconsistency
+    pub fn validate_state_model(
+        &self,
+        query: &serde_json::Value,
+    ) -> Result<Vec<(String, String)>, SearchError> {
+        let (features, _, _) = search_app_ops::build_query_models(
+            query,
+            &self.state_model,
+            self.traversal_model_service.as_ref(),
+            self.access_model_service.as_ref(),
+            &self.cost_model_service,
+        )?;
+        Ok(features)
+    }
+
     /// builds the assets that will run the search for this query instance.
     ///
     /// # Arguments
@@ -151,6 +723,27 @@ impl SearchApp {
         let frontier_model = self
             .frontier_model_service
             .build(query, state_model.clone())?;
+        // the landmark bound is only admissible for the costs the tables were built with
+        let landmarks = match &self.landmarks {
+            None => None,
+            Some(landmarks) => {
+                let fingerprint = search_app_ops::get_cost_fingerprint(query, &cost_model)?;
+                Some(landmarks.clone()).filter(|l| l.fingerprint() == &fingerprint)
+            }
+        };
+        let wrong_way_penalty = search_app_ops::get_wrong_way_penalty(query)?;
+        let prefer_fewer_turns = search_app_ops::get_prefer_fewer_turns(query)?;
+        let reroute_hysteresis = search_app_ops::get_reroute_hysteresis(query)?;
+        let termination_model = match search_app_ops::get_termination_limits(query)? {
+            None => self.termination_model.clone(),
+            Some(limits) => {
+                let model = self
+                    .termination_model
+                    .with_limits(&limits)
+                    .map_err(|e| SearchError::BuildError(e.to_string()))?;
+                Arc::new(model)
+            }
+        };
 
         let search_assets = SearchInstance {
             graph: self.graph.clone(),
@@ -160,7 +753,11 @@ impl SearchApp {
             access_model,
             cost_model: Arc::new(cost_model),
             frontier_model,
-            termination_model: self.termination_model.clone(),
+            termination_model,
+            wrong_way_penalty,
+            prefer_fewer_turns,
+            reroute_hysteresis,
+            landmarks,
         };
 
         Ok(search_assets)
@@ -1,7 +1,7 @@
 use super::search_app::SearchApp;
 use crate::app::compass::CompassAppError;
 use routee_compass_core::{
-    algorithm::search::Direction,
+    algorithm::search::{util::vertex_sequence, Direction},
     model::network::{edge_id::EdgeId, vertex_id::VertexId},
     model::unit::{Distance, DistanceUnit},
 };
@@ -15,6 +15,8 @@ pub trait SearchAppGraphOps {
         distance_unit: Option<DistanceUnit>,
     ) -> Result<Distance, CompassAppError>;
     fn get_incident_edge_ids(&self, vertex_id: &VertexId, direction: &Direction) -> Vec<EdgeId>;
+    fn get_vertex_sequence(&self, edge_ids: &[EdgeId]) -> Result<Vec<VertexId>, CompassAppError>;
+    fn get_vertex_coordinate(&self, vertex_id: &VertexId) -> Result<(f64, f64), CompassAppError>;
 }
 
 impl SearchAppGraphOps for SearchApp {
@@ -45,4 +47,15 @@ impl SearchAppGraphOps for SearchApp {
     fn get_incident_edge_ids(&self, vertex_id: &VertexId, direction: &Direction) -> Vec<EdgeId> {
         self.graph.incident_edges(vertex_id, direction)
     }
+
+    fn get_vertex_sequence(&self, edge_ids: &[EdgeId]) -> Result<Vec<VertexId>, CompassAppError> {
+        let vertex_ids = vertex_sequence::vertex_sequence(edge_ids, &self.graph)?;
+        Ok(vertex_ids)
+    }
+
+    /// the `(x, y)` coordinate of a vertex, which is its longitude and latitude
+    fn get_vertex_coordinate(&self, vertex_id: &VertexId) -> Result<(f64, f64), CompassAppError> {
+        let vertex = self.graph.get_vertex(vertex_id)?;
+        Ok((vertex.x() as f64, vertex.y() as f64))
+    }
 }
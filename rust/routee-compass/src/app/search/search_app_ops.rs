@@ -1,11 +1,25 @@
+use crate::app::compass::model::cost_model::cost_model_service::CostModelService;
 use crate::app::compass::ConfigJsonExtensions;
+use geo::{Coord, LineLocatePoint, LineString, Point, Polygon};
 use itertools::Itertools;
+use routee_compass_core::algorithm::search::{
+    EdgeTraversal, RerouteHysteresis, SearchAlgorithm, SearchError, SearchInstance,
+};
 use routee_compass_core::model::{
-    access::AccessModel,
-    state::{StateFeature, StateModelError},
-    traversal::TraversalModel,
+    access::{AccessModel, AccessModelService},
+    cost::CostModel,
+    network::{EdgeId, Graph, VertexId},
+    state::{StateFeature, StateModel, StateModelError, StateVariable},
+    termination::TerminationLimits,
+    traversal::{TraversalModel, TraversalModelService},
+    unit::{AsF64, Cost},
+};
+use routee_compass_core::util::conversion::duration_extension::DurationExtension;
+use routee_compass_core::util::geo::{coordinate_orientation::CoordinateOrientation, haversine};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
 };
-use std::{collections::HashMap, sync::Arc};
 
 /// collects the state features to use in this search. the features are collected in
 /// the following order:
@@ -50,7 +64,966 @@ pub fn collect_features(
             Some(_) => Ok((name, feature)),
         })
         .collect::<Result<Vec<_>, _>>()?;
-    let mut added_features: Vec<(String, StateFeature)> = model_features.into_iter().collect_vec();
+    // sorted so that the state vector layout is the same for every run of a query
+    let mut added_features: Vec<(String, StateFeature)> = model_features
+        .into_iter()
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+        .collect_vec();
     added_features.extend(user_features);
     Ok(added_features)
 }
+
+/// confirms that the state model assembled from the state configuration and the
+/// traversal and access models is self-consistent:
+///   1. no feature name is declared by two sources with different definitions
+///   2. every input feature of the traversal model is provided by the state configuration
+///      or the traversal model itself
+///   3. every input feature of the access model is provided by the state configuration,
+///      the traversal model or the access model itself
+///
+/// # Returns
+///
+/// the features of the assembled state model in state vector order, each paired with
+/// the source providing it, or an error naming the first inconsistency found
+pub fn validate_state_features(
+    state_model: &StateModel,
+    traversal_model: Arc<dyn TraversalModel>,
+    access_model: Arc<dyn AccessModel>,
+) -> Result<Vec<(String, String)>, StateModelError> {
+    let sources = [
+        ("traversal model", traversal_model.state_features()),
+        ("access model", access_model.state_features()),
+    ];
+
+    // name collisions between sources with different definitions
+    let mut declared: HashMap<String, (&str, StateFeature)> = state_model
+        .iter()
+        .map(|(name, feature)| (name.clone(), ("state configuration", feature.clone())))
+        .collect();
+    for (source, features) in sources.iter() {
+        for (name, feature) in features.iter() {
+            match declared.get(name) {
+                Some((other, existing)) if existing != feature => {
+                    return Err(StateModelError::BuildError(format!(
+                        "state feature '{}' is declared by the {} as {} and by the {} as {}",
+                        name, other, existing, source, feature
+                    )));
+                }
+                Some(_) => {}
+                None => {
+                    declared.insert(name.clone(), (source, feature.clone()));
+                }
+            }
+        }
+    }
+
+    // each model's inputs are satisfied by the features upstream of it
+    let mut upstream: Vec<String> = state_model.iter().map(|(n, _)| n.clone()).collect();
+    let consumers = [
+        ("traversal model", traversal_model.input_features()),
+        ("access model", access_model.input_features()),
+    ];
+    for ((source, features), (_, inputs)) in sources.iter().zip(consumers.iter()) {
+        upstream.extend(features.iter().map(|(n, _)| n.clone()));
+        if let Some(missing) = inputs.iter().find(|i| !upstream.contains(i)) {
+            return Err(StateModelError::MissingInputFeature(
+                source.to_string(),
+                missing.clone(),
+                upstream.iter().unique().join(", "),
+            ));
+        }
+    }
+
+    let assembled = state_model.extend(sources.into_iter().flat_map(|(_, f)| f).collect())?;
+    let ordered = assembled
+        .iter()
+        .map(|(name, _)| {
+            let source = declared
+                .get(name)
+                .map(|(s, _)| s.to_string())
+                .unwrap_or_default();
+            (name.clone(), source)
+        })
+        .collect();
+    Ok(ordered)
+}
+
+/// the state features of a query with their sources, along with its state model and cost model
+pub type QueryModels = (Vec<(String, String)>, Arc<StateModel>, CostModel);
+
+/// builds the traversal, access and cost models of a query, confirming that the state
+/// model assembled from them is self-consistent. none of these models depend on the
+/// road network graph.
+///
+/// # Arguments
+///
+/// * `query` - the user query whose models are built
+/// * `state_model` - the state model of the state configuration
+/// * `traversal_model_service` - builds the traversal model of the query
+/// * `access_model_service` - builds the access model of the query
+/// * `cost_model_service` - builds the cost model of the query
+///
+/// # Returns
+///
+/// the features of the state model with the source providing each, as in
+/// [`validate_state_features`], along with the assembled state model and cost model
+pub fn build_query_models(
+    query: &serde_json::Value,
+    state_model: &StateModel,
+    traversal_model_service: &dyn TraversalModelService,
+    access_model_service: &dyn AccessModelService,
+    cost_model_service: &CostModelService,
+) -> Result<QueryModels, SearchError> {
+    let traversal_model = traversal_model_service.build(query)?;
+    let access_model = access_model_service.build(query)?;
+    let features =
+        validate_state_features(state_model, traversal_model.clone(), access_model.clone())?;
+    let state_features = collect_features(query, traversal_model, access_model)?;
+    let state_model = Arc::new(state_model.extend(state_features)?);
+    let cost_model = cost_model_service
+        .build(query, state_model.clone())
+        .map_err(|e| SearchError::BuildError(e.to_string()))?;
+    Ok((features, state_model, cost_model))
+}
+
+/// query key for the penalty factor applied to wrong-way traversals of one-way edges
+pub const WRONG_WAY_PENALTY: &str = "wrong_way_penalty";
+
+/// reads the optional wrong-way penalty from the query. when present, one-way
+/// edges may be traversed against their direction (for example, by emergency
+/// vehicles) with their traversal cost multiplied by this factor. when absent,
+/// one-way edges can only be traversed in their direction.
+///
+/// # Arguments
+///
+/// * `query` - the user query initiating this search
+///
+/// # Returns
+///
+/// the penalty factor, or an error if it is not a number greater than or equal to one
+pub fn get_wrong_way_penalty(query: &serde_json::Value) -> Result<Option<Cost>, SearchError> {
+    match query.get(WRONG_WAY_PENALTY) {
+        None => Ok(None),
+        Some(value) => match value.as_f64() {
+            Some(penalty) if penalty >= 1.0 => Ok(Some(Cost::new(penalty))),
+            _ => Err(SearchError::BuildError(format!(
+                "{} must be a number greater than or equal to 1.0, found {}",
+                WRONG_WAY_PENALTY, value
+            ))),
+        },
+    }
+}
+
+/// query key for the turn tie-break preference
+pub const PREFER_FEWER_TURNS: &str = "prefer_fewer_turns";
+
+/// reads the optional turn tie-break preference from the query. when true,
+/// ties between equal-cost paths are broken in favor of the path with fewer
+/// turns. this never changes the cost of the returned route.
+///
+/// # Arguments
+///
+/// * `query` - the user query initiating this search
+///
+/// # Returns
+///
+/// the preference, false by default, or an error if it is not a boolean
+pub fn get_prefer_fewer_turns(query: &serde_json::Value) -> Result<bool, SearchError> {
+    match query.get(PREFER_FEWER_TURNS) {
+        None => Ok(false),
+        Some(value) => value.as_bool().ok_or_else(|| {
+            SearchError::BuildError(format!(
+                "{} must be a boolean, found {}",
+                PREFER_FEWER_TURNS, value
+            ))
+        }),
+    }
+}
+
+/// query key for the edges of the previously recommended route
+pub const PREVIOUS_ROUTE: &str = "previous_route";
+
+/// query key for the preference given to the edges of the previous route
+pub const REROUTE_PREFERENCE: &str = "reroute_preference";
+
+/// the reroute preference used when a previous route is given without one
+pub const DEFAULT_REROUTE_PREFERENCE: f64 = 0.05;
+
+/// reads the optional previous route from the query. when present, the
+/// traversal cost of each of its edges is reduced by the reroute preference
+/// fraction, so a periodic reroute keeps the previous recommendation unless
+/// another route is meaningfully better.
+///
+/// # Arguments
+///
+/// * `query` - the user query initiating this search
+///
+/// # Returns
+///
+/// the preference for the previous route, or an error if the edge ids are not
+/// integers or the preference is not between 0 and
+/// [`routee_compass_core::algorithm::search::MAX_REROUTE_PREFERENCE`]
+pub fn get_reroute_hysteresis(
+    query: &serde_json::Value,
+) -> Result<Option<RerouteHysteresis>, SearchError> {
+    let previous_route = match query.get(PREVIOUS_ROUTE) {
+        None => return Ok(None),
+        Some(value) => serde_json::from_value::<Vec<EdgeId>>(value.clone()).map_err(|_| {
+            SearchError::BuildError(format!(
+                "{} must be a list of edge ids, found {}",
+                PREVIOUS_ROUTE, value
+            ))
+        })?,
+    };
+    let preference = match query.get(REROUTE_PREFERENCE) {
+        None => DEFAULT_REROUTE_PREFERENCE,
+        Some(value) => value.as_f64().ok_or_else(|| {
+            SearchError::BuildError(format!(
+                "{} must be a number, found {}",
+                REROUTE_PREFERENCE, value
+            ))
+        })?,
+    };
+    RerouteHysteresis::new(previous_route, preference).map(Some)
+}
+
+/// query keys that locate or label a query, or select its search algorithm,
+/// none of which change the cost of an edge
+const COST_NEUTRAL_QUERY_KEYS: [&str; 12] = [
+    "origin_x",
+    "origin_y",
+    "destination_x",
+    "destination_y",
+    "origin_vertex",
+    "destination_vertex",
+    "origin_edge",
+    "destination_edge",
+    "query_weight_estimate",
+    "query_id",
+    SEARCH_ALGORITHM,
+    // bounds a reachable set search without changing its edge costs
+    COST_BUDGET,
+];
+
+/// query keys read by the cost model service, whose effect is described by the
+/// serialized cost model
+const COST_MODEL_QUERY_KEYS: [&str; 5] = [
+    "weights",
+    "vehicle_rates",
+    "cost_aggregation",
+    "cost_expression",
+    "normalization",
+];
+
+/// describes the cost configuration of a search instance built for a query, so
+/// that precomputed costs such as landmark tables are only used by queries with
+/// the same edge costs. the fingerprint combines the serialized cost model with
+/// any query parameters that may change the traversal or access models, such as
+/// a `model_name`, disabled models, a start time or a previous route. any
+/// such parameter gives the query a fingerprint of its own.
+///
+/// # Arguments
+///
+/// * `query` - the user query initiating this search
+/// * `cost_model` - the cost model built for the query
+///
+/// # Returns
+///
+/// the fingerprint, or an error if the cost model cannot be serialized
+pub fn get_cost_fingerprint(
+    query: &serde_json::Value,
+    cost_model: &CostModel,
+) -> Result<serde_json::Value, SearchError> {
+    let cost = cost_model
+        .serialize_cost_info()
+        .map_err(|e| SearchError::BuildError(e.to_string()))?;
+    let parameters = query
+        .as_object()
+        .map(|q| {
+            q.iter()
+                .filter(|(k, _)| {
+                    !COST_NEUTRAL_QUERY_KEYS.contains(&k.as_str())
+                        && !COST_MODEL_QUERY_KEYS.contains(&k.as_str())
+                })
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect::<serde_json::Map<_, _>>()
+        })
+        .unwrap_or_default();
+    Ok(serde_json::json!({
+        "cost": cost,
+        "parameters": parameters,
+    }))
+}
+
+/// query key for the cost budget of a reachable set search
+pub const COST_BUDGET: &str = "cost_budget";
+
+/// reads the cost budget for a reachable set search from the query.
+///
+/// # Arguments
+///
+/// * `query` - the user query initiating this search
+///
+/// # Returns
+///
+/// the budget, or an error if it is missing or not a non-negative number
+pub fn get_cost_budget(query: &serde_json::Value) -> Result<Cost, SearchError> {
+    let value = query.get(COST_BUDGET).ok_or_else(|| {
+        SearchError::BuildError(format!("query is missing required field {}", COST_BUDGET))
+    })?;
+    match value.as_f64() {
+        Some(budget) if budget >= 0.0 => Ok(Cost::new(budget)),
+        _ => Err(SearchError::BuildError(format!(
+            "{} must be a non-negative number, found {}",
+            COST_BUDGET, value
+        ))),
+    }
+}
+
+/// query key for the maximum ratio of route distance to straight-line distance
+pub const MAX_DETOUR_RATIO: &str = "max_detour_ratio";
+
+/// reads the optional maximum detour ratio from the query. when present, a
+/// route whose network distance exceeds the great-circle distance between its
+/// endpoints by more than this factor is rejected, since it likely indicates
+/// a data problem.
+///
+/// # Arguments
+///
+/// * `query` - the user query initiating this search
+///
+/// # Returns
+///
+/// the maximum ratio, or an error if it is not a number greater than or equal to one
+pub fn get_max_detour_ratio(query: &serde_json::Value) -> Result<Option<f64>, SearchError> {
+    match query.get(MAX_DETOUR_RATIO) {
+        None => Ok(None),
+        Some(value) => match value.as_f64() {
+            Some(ratio) if ratio >= 1.0 => Ok(Some(ratio)),
+            _ => Err(SearchError::BuildError(format!(
+                "{} must be a number greater than or equal to 1.0, found {}",
+                MAX_DETOUR_RATIO, value
+            ))),
+        },
+    }
+}
+
+/// query key naming a registered search algorithm to use in place of the default
+pub const SEARCH_ALGORITHM: &str = "search_algorithm";
+
+/// selects the search algorithm for a query. when the query names an algorithm,
+/// it is looked up among the algorithms registered in the configuration,
+/// otherwise the default algorithm is used.
+///
+/// # Arguments
+///
+/// * `query` - the user query initiating this search
+/// * `default` - the configured default algorithm
+/// * `registered` - the configured algorithms that a query may select by name
+///
+/// # Returns
+///
+/// the selected algorithm, or an error if the name is not a string or is not registered
+pub fn select_search_algorithm<'a>(
+    query: &serde_json::Value,
+    default: &'a SearchAlgorithm,
+    registered: &'a HashMap<String, SearchAlgorithm>,
+) -> Result<&'a SearchAlgorithm, SearchError> {
+    match query.get(SEARCH_ALGORITHM) {
+        None => Ok(default),
+        Some(value) => {
+            let name = value.as_str().ok_or_else(|| {
+                SearchError::BuildError(format!(
+                    "{} must be a string, found {}",
+                    SEARCH_ALGORITHM, value
+                ))
+            })?;
+            registered.get(name).ok_or_else(|| {
+                let names = registered.keys().sorted().join(", ");
+                SearchError::BuildError(format!(
+                    "unknown {} '{}', expected one of [{}]",
+                    SEARCH_ALGORITHM, name, names
+                ))
+            })
+        }
+    }
+}
+
+/// query key for the vertices with chargers in an EV isochrone search or a
+/// charging plan. each charger is a vertex id, or an object with a `vertex_id`
+/// and the `power_kw` rating of the charger
+pub const CHARGERS: &str = "chargers";
+
+/// query key for the state of charge, in percent, to keep in reserve in an EV
+/// isochrone search, such as enough for the return trip
+pub const RESERVE_SOC_PERCENT: &str = "reserve_soc_percent";
+
+/// query key for the name of the state of charge feature in an EV isochrone search
+pub const SOC_FEATURE: &str = "soc_feature";
+
+/// state of charge feature of battery electric vehicles, used when the query has no `soc_feature`
+pub const DEFAULT_SOC_FEATURE: &str = "battery_state";
+
+/// a charger listed in a query, either by vertex id alone or with its power rating
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum ChargerEntry {
+    Vertex(VertexId),
+    Rated {
+        vertex_id: VertexId,
+        power_kw: Option<f64>,
+    },
+}
+
+/// reads the vertices with chargers for an EV isochrone search from the query.
+///
+/// # Arguments
+///
+/// * `query` - the user query initiating this search
+///
+/// # Returns
+///
+/// the charger vertices, which are empty if the query has none, or an error if
+/// the field is not a list of chargers
+pub fn get_chargers(query: &serde_json::Value) -> Result<HashSet<VertexId>, SearchError> {
+    let chargers = get_charger_power(query)?;
+    Ok(chargers.into_keys().collect())
+}
+
+/// reads the chargers from the query along with their power ratings.
+///
+/// # Arguments
+///
+/// * `query` - the user query initiating this search
+///
+/// # Returns
+///
+/// the power rating in kilowatts of each charger vertex, which is None when the
+/// query lists the charger without one, or an error if the field is not a list
+/// of chargers or a power rating is not positive
+pub fn get_charger_power(
+    query: &serde_json::Value,
+) -> Result<HashMap<VertexId, Option<f64>>, SearchError> {
+    let Some(value) = query.get(CHARGERS) else {
+        return Ok(HashMap::new());
+    };
+    let entries = serde_json::from_value::<Vec<ChargerEntry>>(value.clone()).map_err(|_| {
+        SearchError::BuildError(format!(
+            "{} must be a list of vertex ids or of objects with a vertex_id and power_kw, found {}",
+            CHARGERS, value
+        ))
+    })?;
+    entries
+        .into_iter()
+        .map(|entry| match entry {
+            ChargerEntry::Vertex(vertex_id) => Ok((vertex_id, None)),
+            ChargerEntry::Rated {
+                vertex_id,
+                power_kw,
+            } => match power_kw {
+                Some(power) if power <= 0.0 => Err(SearchError::BuildError(format!(
+                    "charger at vertex {} must have a positive power_kw, found {}",
+                    vertex_id, power
+                ))),
+                _ => Ok((vertex_id, power_kw)),
+            },
+        })
+        .collect()
+}
+
+/// reads the reserve state of charge for an EV isochrone search from the query.
+///
+/// # Arguments
+///
+/// * `query` - the user query initiating this search
+///
+/// # Returns
+///
+/// the reserve in percent, which is zero if not set, or an error if it is not
+/// a number between 0 and 100
+pub fn get_reserve_soc_percent(query: &serde_json::Value) -> Result<f64, SearchError> {
+    match query.get(RESERVE_SOC_PERCENT) {
+        None => Ok(0.0),
+        Some(value) => match value.as_f64() {
+            Some(reserve) if (0.0..=100.0).contains(&reserve) => Ok(reserve),
+            _ => Err(SearchError::BuildError(format!(
+                "{} must be a number between 0 and 100, found {}",
+                RESERVE_SOC_PERCENT, value
+            ))),
+        },
+    }
+}
+
+/// query key for termination limits that replace the configured ones
+pub const TERMINATION: &str = "termination";
+
+/// reads the termination limits set by the query, such as
+/// `{"query_runtime": "00:00:30", "iterations": 100000}`. runtime limits use
+/// the same "HH:MM:SS" format as the configuration.
+///
+/// # Arguments
+///
+/// * `query` - the user query initiating this search
+///
+/// # Returns
+///
+/// the limits, or an error if the field is not an object, has an unknown key
+/// or has an invalid limit
+pub fn get_termination_limits(
+    query: &serde_json::Value,
+) -> Result<Option<TerminationLimits>, SearchError> {
+    let Some(value) = query.get(TERMINATION) else {
+        return Ok(None);
+    };
+    let fields = value.as_object().ok_or_else(|| {
+        SearchError::BuildError(format!(
+            "{} must be an object, found {}",
+            TERMINATION, value
+        ))
+    })?;
+    let mut limits = TerminationLimits::default();
+    for (key, v) in fields.iter() {
+        let invalid = |expected: &str| {
+            SearchError::BuildError(format!(
+                "{}.{} must be {}, found {}",
+                TERMINATION, key, expected, v
+            ))
+        };
+        let positive = || v.as_u64().filter(|n| *n > 0);
+        match key.as_str() {
+            "query_runtime" => {
+                let limit = v
+                    .as_duration()
+                    .map_err(|_| invalid("a duration in HH:MM:SS format"))?;
+                limits.runtime = Some(limit);
+            }
+            "frequency" => {
+                limits.frequency = Some(positive().ok_or_else(|| invalid("a positive integer"))?);
+            }
+            "iterations" => {
+                limits.iterations = Some(positive().ok_or_else(|| invalid("a positive integer"))?);
+            }
+            "solution_size" => {
+                let limit = positive().ok_or_else(|| invalid("a positive integer"))?;
+                limits.solution_size = Some(limit as usize);
+            }
+            _ => {
+                return Err(SearchError::BuildError(format!(
+                    "unknown {} limit '{}', expected one of [query_runtime, frequency, iterations, solution_size]",
+                    TERMINATION, key
+                )))
+            }
+        }
+    }
+    Ok(Some(limits))
+}
+
+/// query key for a destination region, a polygon given as a list of `[x, y]` coordinates
+pub const DESTINATION_POLYGON: &str = "destination_polygon";
+
+/// reads the destination region from the query. the ring is closed if the last
+/// coordinate does not repeat the first.
+///
+/// # Arguments
+///
+/// * `query` - the user query initiating this search
+/// * `orientation` - order of the values of each coordinate
+///
+/// # Returns
+///
+/// the polygon, or an error if the field is not a list of at least three valid
+/// coordinates
+pub fn get_destination_polygon(
+    query: &serde_json::Value,
+    orientation: &CoordinateOrientation,
+) -> Result<Option<Polygon<f32>>, SearchError> {
+    let Some(value) = query.get(DESTINATION_POLYGON) else {
+        return Ok(None);
+    };
+    let pairs = serde_json::from_value::<Vec<[f32; 2]>>(value.clone()).map_err(|_| {
+        SearchError::BuildError(format!(
+            "{} must be a list of [x, y] coordinates, found {}",
+            DESTINATION_POLYGON, value
+        ))
+    })?;
+    if pairs.len() < 3 {
+        return Err(SearchError::BuildError(format!(
+            "{} must have at least 3 coordinates, found {}",
+            DESTINATION_POLYGON,
+            pairs.len()
+        )));
+    }
+    let coords = pairs
+        .into_iter()
+        .map(|[x, y]| {
+            orientation
+                .orient_checked(Coord { x, y })
+                .map_err(|e| SearchError::BuildError(format!("{}: {}", DESTINATION_POLYGON, e)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Some(Polygon::new(LineString::new(coords), vec![])))
+}
+
+/// query key for whether route totals include the traveled part of the origin and
+/// destination edges that query coordinates were snapped to, measuring to the query
+/// coordinates, or leave those edges out, measuring between network vertices
+pub const INCLUDE_PARTIAL_EDGES: &str = "include_partial_edges";
+
+/// reads whether partial origin and destination edges are included in route totals.
+///
+/// # Arguments
+///
+/// * `query` - the user query initiating this search
+///
+/// # Returns
+///
+/// the setting, which is true if not set, or an error if it is not a boolean
+pub fn get_include_partial_edges(query: &serde_json::Value) -> Result<bool, SearchError> {
+    match query.get(INCLUDE_PARTIAL_EDGES) {
+        None => Ok(true),
+        Some(value) => value.as_bool().ok_or_else(|| {
+            SearchError::BuildError(format!(
+                "{} must be a boolean, found {}",
+                INCLUDE_PARTIAL_EDGES, value
+            ))
+        }),
+    }
+}
+
+/// finds how far along an edge a coordinate snaps to, as a fraction of the length
+/// of the edge geometry from its start.
+pub fn snapped_fraction(geometry: &LineString<f32>, coordinate: Coord<f32>) -> f64 {
+    geometry
+        .line_locate_point(&Point(coordinate))
+        .map(|f| f as f64)
+        .unwrap_or_default()
+}
+
+/// sets the distance and time of the first and last edges of an edge-oriented route
+/// to the traveled fraction of each edge, updating each state along the route. the
+/// full distance and time of an edge are found by traversing it. edges without a
+/// traveled fraction are left as they are. when the route has a single edge, the
+/// traveled part lies between the two snapped points.
+///
+/// # Arguments
+///
+/// * `route` - the route to update
+/// * `first_traveled` - fraction of the first edge that is traveled, from the origin to its end
+/// * `last_traveled` - fraction of the last edge that is traveled, from its start to the destination
+/// * `si` - the search assets used to find the route
+pub fn apply_partial_edges(
+    route: &mut [EdgeTraversal],
+    first_traveled: Option<f64>,
+    last_traveled: Option<f64>,
+    si: &SearchInstance,
+) -> Result<(), SearchError> {
+    let indices = si
+        .state_model
+        .indexed_iter()
+        .filter(|(_, (_, feature))| {
+            matches!(
+                feature,
+                StateFeature::Distance { .. } | StateFeature::Time { .. }
+            )
+        })
+        .map(|(index, _)| index)
+        .collect_vec();
+    let value =
+        |state: &[StateVariable], index: usize| state.get(index).map(|v| v.0).unwrap_or_default();
+    let initial_state = si.state_model.initial_state()?;
+    let n = route.len();
+    if n == 0 {
+        return Ok(());
+    }
+    let first_traveled = match (n, first_traveled, last_traveled) {
+        (1, Some(first), Some(last)) => Some((first + last - 1.0).clamp(0.0, 1.0)),
+        (1, None, last) => last,
+        (_, first, _) => first,
+    };
+
+    if let Some(traveled) = first_traveled {
+        let full = EdgeTraversal::forward_traversal(route[0].edge_id, None, &initial_state, si)?;
+        for index in indices.iter() {
+            let initial = value(&initial_state, *index);
+            let target = traveled * (value(&full.result_state, *index) - initial);
+            let adjustment = target - (value(&route[0].result_state, *index) - initial);
+            for et in route.iter_mut() {
+                if let Some(v) = et.result_state.get_mut(*index) {
+                    v.0 += adjustment;
+                }
+            }
+        }
+    }
+
+    if let (true, Some(traveled)) = (n > 1, last_traveled) {
+        let prev_edge_id = route[n - 2].edge_id;
+        let prev_state = route[n - 2].result_state.clone();
+        let full = EdgeTraversal::forward_traversal(
+            route[n - 1].edge_id,
+            Some(prev_edge_id),
+            &prev_state,
+            si,
+        )?;
+        for index in indices.iter() {
+            let prev_value = value(&prev_state, *index);
+            let target = traveled * (value(&full.result_state, *index) - prev_value);
+            if let Some(v) = route[n - 1].result_state.get_mut(*index) {
+                v.0 = prev_value + target;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// query key for edges that a route must traverse, in order, as a list of edge ids
+pub const REQUIRE_EDGES: &str = "require_edges";
+
+/// reads the edges that the route must traverse from the query.
+///
+/// # Arguments
+///
+/// * `query` - the user query initiating this search
+///
+/// # Returns
+///
+/// the required edges in the order they are traversed, None if the field is not
+/// set or empty, or an error if it is not a list of edge ids
+pub fn get_require_edges(query: &serde_json::Value) -> Result<Option<Vec<EdgeId>>, SearchError> {
+    let Some(value) = query.get(REQUIRE_EDGES) else {
+        return Ok(None);
+    };
+    let edge_ids = serde_json::from_value::<Vec<EdgeId>>(value.clone()).map_err(|_| {
+        SearchError::BuildError(format!(
+            "{} must be a list of edge ids, found {}",
+            REQUIRE_EDGES, value
+        ))
+    })?;
+    if edge_ids.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(edge_ids))
+    }
+}
+
+/// traverses a sequence of connected edges in order from the initial state, so
+/// that the state of each edge continues from the one before it.
+///
+/// # Arguments
+///
+/// * `edge_ids` - the route to traverse
+/// * `si` - the search assets for this query
+///
+/// # Returns
+///
+/// the traversal of each edge, or an error if the edges are not connected
+pub fn traverse_edges(
+    edge_ids: &[EdgeId],
+    si: &SearchInstance,
+) -> Result<Vec<EdgeTraversal>, SearchError> {
+    let mut route: Vec<EdgeTraversal> = Vec::with_capacity(edge_ids.len());
+    let mut state = si.state_model.initial_state()?;
+    let mut prev_edge_id: Option<EdgeId> = None;
+    for edge_id in edge_ids.iter() {
+        if let Some(prev) = prev_edge_id {
+            let prev_dst = si.graph.dst_vertex_id(&prev)?;
+            let next_src = si.graph.src_vertex_id(edge_id)?;
+            if prev_dst != next_src {
+                return Err(SearchError::RouteNotConnected(prev, *edge_id));
+            }
+        }
+        let et = EdgeTraversal::forward_traversal(*edge_id, prev_edge_id, &state, si)?;
+        state.clone_from(&et.result_state);
+        prev_edge_id = Some(*edge_id);
+        route.push(et);
+    }
+    Ok(route)
+}
+
+/// query key for cost models that routes are also costed under, without changing
+/// the search, as an object mapping a name to the cost model fields of a query,
+/// such as `{"energy": {"weights": {"energy_liquid": 1.0}}}`
+pub const SECONDARY_COST_MODELS: &str = "secondary_cost_models";
+
+/// reads the secondary cost models from the query. each is returned as a copy of
+/// the query with its fields replacing those of the query, so that any field it
+/// does not set, such as the `model_name`, is shared with the primary cost model.
+///
+/// # Arguments
+///
+/// * `query` - the user query initiating this search
+///
+/// # Returns
+///
+/// the name and query of each secondary cost model, ordered by name, or an error
+/// if the field is not an object of objects
+pub fn get_secondary_cost_queries(
+    query: &serde_json::Value,
+) -> Result<Vec<(String, serde_json::Value)>, SearchError> {
+    let Some(value) = query.get(SECONDARY_COST_MODELS) else {
+        return Ok(vec![]);
+    };
+    let models = value.as_object().ok_or_else(|| {
+        SearchError::BuildError(format!(
+            "{} must be an object, found {}",
+            SECONDARY_COST_MODELS, value
+        ))
+    })?;
+    models
+        .iter()
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(name, model)| {
+            let fields = model.as_object().ok_or_else(|| {
+                SearchError::BuildError(format!(
+                    "{} entry '{}' must be an object, found {}",
+                    SECONDARY_COST_MODELS, name, model
+                ))
+            })?;
+            let mut secondary = query.clone();
+            if let Some(obj) = secondary.as_object_mut() {
+                obj.remove(SECONDARY_COST_MODELS);
+                for (k, v) in fields.iter() {
+                    obj.insert(k.clone(), v.clone());
+                }
+            }
+            Ok((name.clone(), secondary))
+        })
+        .collect()
+}
+
+/// computes the total cost of a route under a cost model from the states of the
+/// route. the cost of each edge is found from the change in state across it,
+/// including any access costs, as in the search.
+///
+/// # Arguments
+///
+/// * `route` - the route to cost
+/// * `cost_model` - the cost model to apply
+/// * `si` - the search assets used to find the route
+///
+/// # Returns
+///
+/// the total cost of the route
+pub fn route_cost(
+    route: &[EdgeTraversal],
+    cost_model: &CostModel,
+    si: &SearchInstance,
+) -> Result<Cost, SearchError> {
+    let mut prev_state = si.state_model.initial_state()?;
+    let mut total = Cost::ZERO;
+    for et in route.iter() {
+        let edge = si.graph.get_edge(&et.edge_id)?;
+        total = total + cost_model.traversal_cost(edge, &prev_state, &et.result_state)?;
+        prev_state.clone_from(&et.result_state);
+    }
+    Ok(total)
+}
+
+/// computes the ratio of a route's network distance to the great-circle
+/// distance between the start of its first edge and the end of its last edge.
+///
+/// # Arguments
+///
+/// * `route` - the route to measure
+/// * `graph` - the graph the route traverses
+///
+/// # Returns
+///
+/// the detour ratio, or None if the route is empty or starts and ends at the same place
+pub fn detour_ratio(route: &[EdgeTraversal], graph: &Graph) -> Result<Option<f64>, SearchError> {
+    let (first, last) = match (route.first(), route.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Ok(None),
+    };
+    let src = graph.get_vertex(&graph.src_vertex_id(&first.edge_id)?)?;
+    let dst = graph.get_vertex(&graph.dst_vertex_id(&last.edge_id)?)?;
+    let straight_line = haversine::coord_distance_meters(&src.coordinate, &dst.coordinate)
+        .map_err(SearchError::InternalError)?;
+    if straight_line.as_f64() <= 0.0 {
+        return Ok(None);
+    }
+    let network_distance = route
+        .iter()
+        .map(|e| {
+            graph
+                .get_edge(&e.edge_id)
+                .map(|edge| edge.distance.as_f64())
+        })
+        .sum::<Result<f64, _>>()?;
+    Ok(Some(network_distance / straight_line.as_f64()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use routee_compass_core::model::{
+        access::{default::NoAccessModel, AccessModelError},
+        network::{Edge, Vertex},
+        state::StateVariable,
+        traversal::default::DistanceTraversalModel,
+        unit::{Distance, DistanceUnit},
+    };
+
+    /// an access model that reads a speed feature it does not declare
+    struct SpeedReader;
+
+    impl AccessModel for SpeedReader {
+        fn state_features(&self) -> Vec<(String, StateFeature)> {
+            vec![]
+        }
+
+        fn input_features(&self) -> Vec<String> {
+            vec![String::from("speed")]
+        }
+
+        fn access_edge(
+            &self,
+            _traversal: (&Vertex, &Edge, &Vertex, &Edge, &Vertex),
+            _state: &mut Vec<StateVariable>,
+            _state_model: &StateModel,
+        ) -> Result<(), AccessModelError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_validate_state_features() {
+        let traversal: Arc<dyn TraversalModel> =
+            Arc::new(DistanceTraversalModel::new(DistanceUnit::Miles));
+        let state_model = StateModel::new(vec![(
+            String::from("distance"),
+            StateFeature::Distance {
+                distance_unit: DistanceUnit::Miles,
+                initial: Distance::ZERO,
+            },
+        )]);
+        let valid =
+            validate_state_features(&state_model, traversal.clone(), Arc::new(NoAccessModel {}))
+                .unwrap();
+        assert_eq!(
+            valid,
+            vec![(
+                String::from("distance"),
+                String::from("state configuration")
+            )]
+        );
+
+        // the distance model reads a distance feature it does not declare
+        let error = validate_state_features(
+            &StateModel::empty(),
+            traversal.clone(),
+            Arc::new(NoAccessModel {}),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "traversal model requires state feature 'distance' which is not provided upstream, found []"
+        );
+
+        let error =
+            validate_state_features(&state_model, traversal, Arc::new(SpeedReader)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "access model requires state feature 'speed' which is not provided upstream, found [distance]"
+        );
+    }
+}
@@ -2,7 +2,7 @@ use allocative::Allocative;
 
 use routee_compass_core::{
     algorithm::search::{EdgeTraversal, SearchTreeBranch},
-    model::network::vertex_id::VertexId,
+    model::{network::vertex_id::VertexId, unit::Cost},
 };
 
 use std::{collections::HashMap, time::Duration};
@@ -14,4 +14,8 @@ pub struct SearchAppResult {
     pub search_executed_time: String,
     pub search_runtime: Duration,
     pub iterations: u64,
+    /// for each route, its total cost under each secondary cost model of the query, by name
+    pub secondary_costs: Vec<HashMap<String, Cost>>,
+    /// the number of alternative routes requested, when the search algorithm takes one
+    pub alternatives_requested: Option<usize>,
 }
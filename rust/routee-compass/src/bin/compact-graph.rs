@@ -0,0 +1,205 @@
+use clap::Parser;
+use routee_compass::app::compass::compass_app_ops::read_config_from_file;
+use routee_compass::app::compass::compass_input_field::CompassInputField;
+use routee_compass::app::compass::{CompassConfigurationField, ConfigJsonExtensions};
+use routee_compass_core::model::map::GeometryModel;
+use routee_compass_core::model::network::{Graph, GraphCompaction};
+use routee_compass_core::util::fs::{read_decoders, read_utils};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use wkt::ToWkt;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct CompactGraphCliArgs {
+    /// RouteE Compass service configuration TOML file
+    #[arg(short, long, value_name = "*.toml")]
+    pub config_file: String,
+
+    /// directory to write the compacted graph files to
+    #[arg(short, long)]
+    pub output_directory: String,
+
+    /// edge geometries of the graph, one WKT LINESTRING per line. when not
+    /// provided, the geometries are drawn between the vertices of each edge
+    #[arg(long)]
+    pub geometry_input_file: Option<String>,
+
+    /// edge speed file to merge so that each compacted edge keeps the travel
+    /// time of the edges it replaces. may be repeated
+    #[arg(long)]
+    pub speed_input_file: Vec<String>,
+
+    /// numeric edge attribute file, such as grade, to merge by the
+    /// distance-weighted mean. may be repeated
+    #[arg(long)]
+    pub attribute_input_file: Vec<String>,
+
+    /// edge attribute file, such as road class, where vertices between edges
+    /// with different values are kept. each compacted edge takes the value of
+    /// the edges it replaces. may be repeated
+    #[arg(long)]
+    pub split_input_file: Vec<String>,
+}
+
+/// preprocessing application that contracts chains of degree-2 vertices of the
+/// [graph] of a RouteE Compass configuration into single edges. writes the
+/// compacted edge and vertex lists, edge geometries, the merged edge attribute
+/// files under their original names, and `edges-original-ids.txt`, which lists
+/// the original edge ids of each compacted edge for the `original_edges_input_file`
+/// option of the traversal output plugin.
+///
+/// turn restrictions and turn delays are defined on pairs of edges meeting at a
+/// vertex, which compaction renumbers or removes, so configurations that use
+/// them are refused.
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+
+    let args = CompactGraphCliArgs::parse();
+    let config = read_config_from_file(Path::new(&args.config_file))?;
+    let root_config_path = config.get::<PathBuf>(CompassInputField::ConfigInputFile.to_str())?;
+    let config_json = config
+        .try_deserialize::<serde_json::Value>()?
+        .normalize_file_paths(&"", &root_config_path)?;
+    if let Some(key) = find_turn_input(&config_json) {
+        return Err(format!(
+            "cannot compact a graph for a configuration with '{}': turn restrictions and \
+             turn delays refer to edges and vertices that compaction renumbers or removes",
+            key
+        )
+        .into());
+    }
+    let graph_params = config_json.get_config_section(CompassConfigurationField::Graph, &"TOML")?;
+    let graph = Arc::new(Graph::try_from(&graph_params)?);
+
+    let splits = args
+        .split_input_file
+        .iter()
+        .map(|file| read_utils::read_raw_file(file, read_decoders::string, None, None))
+        .collect::<Result<Vec<_>, _>>()?;
+    let compaction = GraphCompaction::new_with_splits(&graph, |a, b| {
+        splits
+            .iter()
+            .all(|values| values.get(a.0) == values.get(b.0))
+    })?;
+    log::info!(
+        "compacted graph from {} vertices and {} edges to {} vertices and {} edges",
+        graph.n_vertices(),
+        graph.n_edges(),
+        compaction.graph.n_vertices(),
+        compaction.graph.n_edges()
+    );
+
+    let output_directory = Path::new(&args.output_directory);
+    std::fs::create_dir_all(output_directory)?;
+
+    let mut edge_writer = csv::Writer::from_path(output_directory.join("edges-compass.csv"))?;
+    for edge in compaction.graph.edges.iter() {
+        edge_writer.serialize(edge)?;
+    }
+    edge_writer.flush()?;
+
+    let mut vertex_writer = csv::Writer::from_path(output_directory.join("vertices-compass.csv"))?;
+    vertex_writer.write_record(["vertex_id", "x", "y"])?;
+    for vertex in compaction.graph.vertices.iter() {
+        vertex_writer.write_record([
+            vertex.vertex_id.to_string(),
+            vertex.x().to_string(),
+            vertex.y().to_string(),
+        ])?;
+    }
+    vertex_writer.flush()?;
+
+    write_lines(
+        &output_directory.join("edges-original-ids.txt"),
+        compaction.original_edges.iter().map(|edges| {
+            edges
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        }),
+    )?;
+
+    let geometry_model = match &args.geometry_input_file {
+        Some(file) => GeometryModel::new_from_edges(file, graph.clone())?,
+        None => GeometryModel::new_from_vertices(graph.clone())?,
+    };
+    let geometries = geometry_model.geometries().cloned().collect::<Vec<_>>();
+    let merged_geometries = compaction.merge_geometries(&geometries)?;
+    write_lines(
+        &output_directory.join("edges-geometries-enumerated.txt"),
+        merged_geometries.iter().map(|g| g.to_wkt().to_string()),
+    )?;
+
+    for (file, is_speed) in args
+        .speed_input_file
+        .iter()
+        .map(|f| (f, true))
+        .chain(args.attribute_input_file.iter().map(|f| (f, false)))
+    {
+        let values = read_utils::read_raw_file(file, read_decoders::f64, None, None)?;
+        let merged = if is_speed {
+            compaction.merge_speeds(&graph, &values)?
+        } else {
+            compaction.merge_by_distance(&graph, &values)?
+        };
+        write_lines(
+            &output_path(output_directory, file)?,
+            merged.iter().map(|v| v.to_string()),
+        )?;
+    }
+    for (file, values) in args.split_input_file.iter().zip(splits.iter()) {
+        let merged = compaction
+            .original_edges
+            .iter()
+            .map(|edges| values.get(edges[0].0).cloned().unwrap_or_default());
+        write_lines(&output_path(output_directory, file)?, merged)?;
+    }
+
+    Ok(())
+}
+
+/// the first configuration key found for an input file of turn restrictions or
+/// turn delays, which are not remapped onto the compacted graph
+fn find_turn_input(config: &serde_json::Value) -> Option<String> {
+    match config {
+        serde_json::Value::Object(map) => map.iter().find_map(|(key, value)| {
+            if TURN_INPUT_KEYS.contains(&key.as_str()) {
+                Some(key.clone())
+            } else {
+                find_turn_input(value)
+            }
+        }),
+        serde_json::Value::Array(values) => values.iter().find_map(find_turn_input),
+        _ => None,
+    }
+}
+
+const TURN_INPUT_KEYS: [&str; 3] = [
+    "turn_restriction_input_file",
+    "edge_heading_input_file",
+    "movement_delay_input_file",
+];
+
+/// the path of a merged attribute file, with the name of the input file less
+/// any .gz extension, in the output directory
+fn output_path(output_directory: &Path, input_file: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let name = Path::new(input_file)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("invalid input file name {}", input_file))?;
+    Ok(output_directory.join(name.trim_end_matches(".gz")))
+}
+
+fn write_lines(path: &Path, lines: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for line in lines {
+        writeln!(writer, "{}", line)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use clap::Parser;
+use routee_compass::app::compass::compass_app_ops::read_config_from_file;
+use routee_compass::app::compass::compass_models::CompassModels;
+use routee_compass::app::compass::CompassAppBuilder;
+use std::error::Error;
+use std::path::Path;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct DescribeModelsCliArgs {
+    /// RouteE Compass service configuration TOML file
+    #[arg(short, long, value_name = "*.toml")]
+    pub config_file: String,
+
+    /// JSON query used to build the models, for models that read query
+    /// parameters such as the vehicle model_name
+    #[arg(short, long, value_name = "JSON", default_value = "{}")]
+    pub query: String,
+}
+
+/// simple application to describe the models of a RouteE Compass configuration.
+/// builds the models for a query and prints the state model, the source of each
+/// state feature and the cost model as JSON. the graph is not loaded, so this
+/// runs quickly even for configurations of large road networks.
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+
+    let args = DescribeModelsCliArgs::parse();
+    let query: serde_json::Value = serde_json::from_str(&args.query)?;
+    let config = read_config_from_file(Path::new(&args.config_file))?;
+    let models = CompassModels::try_from((&config, &CompassAppBuilder::default()))?;
+    let description = models.describe(&query)?;
+    println!("{}", serde_json::to_string_pretty(&description)?);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use clap::Parser;
+use routee_compass::app::compass::compass_app_ops::read_config_from_file;
+use routee_compass::app::compass::compass_input_field::CompassInputField;
+use routee_compass::app::compass::{CompassConfigurationField, ConfigJsonExtensions};
+use routee_compass_core::model::network::Graph;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct GraphStatsCliArgs {
+    /// RouteE Compass service configuration TOML file
+    #[arg(short, long, value_name = "*.toml")]
+    pub config_file: String,
+
+    /// print the statistics as JSON instead of plain text
+    #[arg(short, long)]
+    pub json: bool,
+
+    /// also approximate the graph diameter, the longest shortest path, with a
+    /// double sweep search from this many sampled vertices
+    #[arg(long)]
+    pub diameter_samples: Option<usize>,
+
+    /// also compute the exact graph diameter, which searches from every vertex
+    /// and is only practical for small graphs
+    #[arg(long)]
+    pub exact_diameter: bool,
+}
+
+/// simple application to summarize the road network of a RouteE Compass
+/// configuration. loads only the [graph] section of the config file and
+/// prints vertex and edge counts, degree distributions, an edge length
+/// histogram and the number of one-way and two-way edges, and optionally
+/// the graph diameter.
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+
+    let args = GraphStatsCliArgs::parse();
+    let config = read_config_from_file(Path::new(&args.config_file))?;
+    let root_config_path = config.get::<PathBuf>(CompassInputField::ConfigInputFile.to_str())?;
+    let config_json = config
+        .try_deserialize::<serde_json::Value>()?
+        .normalize_file_paths(&"", &root_config_path)?;
+    let graph_params = config_json.get_config_section(CompassConfigurationField::Graph, &"TOML")?;
+    let graph = Graph::try_from(&graph_params)?;
+
+    let stats = graph.statistics();
+    let diameter = match (args.exact_diameter, args.diameter_samples) {
+        (true, _) => graph.diameter(),
+        (false, Some(samples)) => graph.approx_diameter(samples),
+        (false, None) => None,
+    };
+    if args.json {
+        let mut output = serde_json::to_value(&stats)?;
+        if let Some(d) = diameter {
+            output["diameter"] = serde_json::to_value(d)?;
+        }
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        print!("{}", stats);
+        if let Some(d) = diameter {
+            print!("{}", d);
+        }
+    }
+    Ok(())
+}
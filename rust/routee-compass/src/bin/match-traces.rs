@@ -0,0 +1,69 @@
+use clap::Parser;
+use routee_compass::app::compass::compass_app_ops::read_config_from_file;
+use routee_compass::app::compass::compass_input_field::CompassInputField;
+use routee_compass::app::compass::{CompassConfigurationField, ConfigJsonExtensions};
+use routee_compass::app::mapping::map_matching_config::MapMatchingConfig;
+use routee_compass::app::mapping::mapping_app::MappingApp;
+use routee_compass::app::mapping::mapping_app_ops::read_traces;
+use routee_compass_core::model::map::{MapModel, MapModelConfig};
+use routee_compass_core::model::network::Graph;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct MatchTracesCliArgs {
+    /// RouteE Compass service configuration TOML file
+    #[arg(short, long, value_name = "*.toml")]
+    pub config_file: String,
+
+    /// newline-delimited JSON file with one trace per line
+    #[arg(short, long, value_name = "*.jsonl")]
+    pub traces_file: String,
+
+    /// file to write the results to, one JSON result per line. if not
+    /// provided, results are written to stdout
+    #[arg(short, long)]
+    pub output_file: Option<String>,
+}
+
+/// batch map matching application. loads the [graph], [mapping] and optional
+/// [map_matching] sections of the config file, matches each trace in the traces file in parallel and
+/// writes one result per trace. traces that fail to parse or match are
+/// written as error results without stopping the batch.
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+
+    let args = MatchTracesCliArgs::parse();
+    let config = read_config_from_file(Path::new(&args.config_file))?;
+    let root_config_path = config.get::<PathBuf>(CompassInputField::ConfigInputFile.to_str())?;
+    let config_json = config
+        .try_deserialize::<serde_json::Value>()?
+        .normalize_file_paths(&"", &root_config_path)?;
+    let graph_params = config_json.get_config_section(CompassConfigurationField::Graph, &"TOML")?;
+    let graph = Arc::new(Graph::try_from(&graph_params)?);
+    let map_model_json = config_json.get(CompassConfigurationField::MapModel.to_str());
+    let map_model_config = MapModelConfig::try_from(map_model_json)?;
+    let map_model = Arc::new(MapModel::new(graph.clone(), map_model_config)?);
+    let map_matching_config: MapMatchingConfig = config_json
+        .get_config_serde_optional(&CompassConfigurationField::MapMatching, &"TOML")?
+        .unwrap_or_default();
+    let app = MappingApp::new(graph, map_model).with_config(map_matching_config);
+
+    let traces = read_traces(Path::new(&args.traces_file))?;
+    log::info!("matching {} traces", traces.len());
+    let results = app.match_trace_batch(traces);
+
+    let mut writer: Box<dyn Write> = match &args.output_file {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(std::io::stdout())),
+    };
+    for result in results.iter() {
+        writeln!(writer, "{}", serde_json::to_string(result)?)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use clap::Parser;
+use rayon::prelude::*;
+use routee_compass::app::compass::compass_app::CompassApp;
+use routee_compass::app::search::search_app_ops::COST_BUDGET;
+use std::error::Error;
+use std::path::Path;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct ReachabilityCliArgs {
+    /// RouteE Compass service configuration TOML file
+    #[arg(short, long, value_name = "*.toml")]
+    pub config_file: String,
+
+    /// cost budget for each reachable set search, in the units of the cost model
+    #[arg(short = 'b', long)]
+    pub cost_budget: f64,
+
+    /// CSV file to write the reachable set size of each vertex to
+    #[arg(short, long, value_name = "*.csv")]
+    pub output_file: String,
+
+    /// optional JSON object of additional query parameters applied to every search
+    #[arg(short, long)]
+    pub query: Option<String>,
+}
+
+/// reachability centrality application. counts, for every vertex of the graph,
+/// the number of vertices reachable within a cost budget, and writes the counts
+/// to a CSV file with columns `vertex_id,reachable_count,error`.
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+
+    let args = ReachabilityCliArgs::parse();
+    let app = CompassApp::try_from(Path::new(&args.config_file))?;
+    let mut base_query = match &args.query {
+        Some(q) => serde_json::from_str::<serde_json::Value>(q)?,
+        None => serde_json::json!({}),
+    };
+    base_query[COST_BUDGET] = serde_json::json!(args.cost_budget);
+
+    let vertices = &app.search_app.graph.vertices;
+    log::info!(
+        "counting reachable vertices from {} origins",
+        vertices.len()
+    );
+    let rows: Vec<(usize, Option<usize>, String)> = vertices
+        .par_iter()
+        .map(|v| {
+            let mut query = base_query.clone();
+            query["origin_vertex"] = serde_json::json!(v.vertex_id.0);
+            match app.search_app.reachable_count(&mut query) {
+                Ok(count) => (v.vertex_id.0, Some(count), String::new()),
+                Err(e) => (v.vertex_id.0, None, e.to_string()),
+            }
+        })
+        .collect();
+
+    let mut writer = csv::Writer::from_path(&args.output_file)?;
+    writer.write_record(["vertex_id", "reachable_count", "error"])?;
+    for (vertex_id, count, error) in rows.iter() {
+        let count_str = count.map(|c| c.to_string()).unwrap_or_default();
+        writer.write_record([vertex_id.to_string(), count_str, error.clone()])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use clap::Parser;
+use routee_compass::app::compass::compass_app_ops::read_config_from_file;
+use routee_compass::app::compass::compass_models::CompassModels;
+use routee_compass::app::compass::CompassAppBuilder;
+use std::error::Error;
+use std::path::Path;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct ValidateConfigCliArgs {
+    /// RouteE Compass service configuration TOML file
+    #[arg(short, long, value_name = "*.toml")]
+    pub config_file: String,
+
+    /// JSON query used to build the models, for models that read query
+    /// parameters such as the vehicle model_name
+    #[arg(short, long, value_name = "JSON", default_value = "{}")]
+    pub query: String,
+}
+
+/// simple application to check a RouteE Compass configuration without running
+/// any queries. builds the models, confirms that the state model assembled from
+/// the state configuration and the traversal, access and cost models is
+/// self-consistent, and prints the state features in state vector order along
+/// with the source providing each one. the graph is not loaded, as none of
+/// these models depend on it.
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+
+    let args = ValidateConfigCliArgs::parse();
+    let query: serde_json::Value = serde_json::from_str(&args.query)?;
+    let config = read_config_from_file(Path::new(&args.config_file))?;
+    let models = CompassModels::try_from((&config, &CompassAppBuilder::default()))?;
+    let features = models.validate_state_model(&query)?;
+
+    println!("state model is valid with features:");
+    for (index, (name, source)) in features.iter().enumerate() {
+        println!("  {}: {} ({})", index, name, source);
+    }
+    Ok(())
+}
@@ -1,15 +1,19 @@
 use super::custom_weight_type::CustomWeightType;
 use crate::plugin::{input::InputJsonExtensions, input::InputPluginError};
 use routee_compass_core::model::unit::{AsF64, DistanceUnit};
-use routee_compass_core::util::geo::haversine;
+use routee_compass_core::util::geo::{coordinate_orientation::CoordinateOrientation, haversine};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum WeightHeuristic {
     /// computes a weight directly as the haversine distance estimation between
-    /// trip origin and destination, in kilometers.
-    Haversine,
+    /// trip origin and destination, in kilometers. the query coordinates are
+    /// read in the given orientation, lon_lat by default.
+    Haversine {
+        #[serde(default)]
+        coordinate_orientation: CoordinateOrientation,
+    },
     /// user provides a field of some custom weight type that is used directly
     /// for weight estimates.
     Custom {
@@ -20,9 +24,13 @@ pub enum WeightHeuristic {
 impl WeightHeuristic {
     pub fn estimate_weight(&self, query: &serde_json::Value) -> Result<f64, InputPluginError> {
         match self {
-            WeightHeuristic::Haversine => {
-                let o = query.get_origin_coordinate()?;
-                let d_option = query.get_destination_coordinate()?;
+            WeightHeuristic::Haversine {
+                coordinate_orientation,
+            } => {
+                let o = coordinate_orientation.orient(query.get_origin_coordinate()?);
+                let d_option = query
+                    .get_destination_coordinate()?
+                    .map(|d| coordinate_orientation.orient(d));
                 match d_option {
                     None => Err(InputPluginError::InputPluginFailed(String::from(
                         "cannot estimate search size without destination coordinate",
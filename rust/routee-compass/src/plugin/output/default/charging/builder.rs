@@ -0,0 +1,67 @@
+use super::plugin::ChargingOutputPlugin;
+use crate::{
+    app::compass::{CompassConfigurationError, ConfigJsonExtensions},
+    app::search::search_app_ops,
+    plugin::output::{OutputPlugin, OutputPluginBuilder},
+};
+use std::sync::Arc;
+
+/// electrical energy feature of battery electric vehicles
+const DEFAULT_ENERGY_FEATURE: &str = "energy_electric";
+
+/// Builds a plugin that plans the charging stops of an electric vehicle route.
+///
+/// # Configuration
+///
+/// * `battery_capacity_kwh` (optional) - battery capacity of the vehicle, when
+///   the query does not set `battery_capacity_kwh`
+/// * `default_power_kw` (optional) - power rating of chargers listed in the
+///   query without a `power_kw`
+/// * `soc_feature` (optional) - state of charge feature, `battery_state` by default
+/// * `energy_feature` (optional) - electrical energy feature, `energy_electric` by default
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [[plugin.output_plugins]]
+/// type = "charging"
+/// battery_capacity_kwh = 60
+/// default_power_kw = 50
+/// ```
+pub struct ChargingOutputPluginBuilder {}
+
+impl OutputPluginBuilder for ChargingOutputPluginBuilder {
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        let parent_key = String::from("charging");
+        let battery_capacity_kwh: Option<f64> =
+            parameters.get_config_serde_optional(&"battery_capacity_kwh", &parent_key)?;
+        let default_power_kw: Option<f64> =
+            parameters.get_config_serde_optional(&"default_power_kw", &parent_key)?;
+        for (key, value) in [
+            ("battery_capacity_kwh", battery_capacity_kwh),
+            ("default_power_kw", default_power_kw),
+        ] {
+            if let Some(v) = value.filter(|v| *v <= 0.0) {
+                return Err(CompassConfigurationError::UserConfigurationError(format!(
+                    "charging {} must be positive, found {}",
+                    key, v
+                )));
+            }
+        }
+        let soc_feature = parameters
+            .get_config_serde_optional(&"soc_feature", &parent_key)?
+            .unwrap_or_else(|| String::from(search_app_ops::DEFAULT_SOC_FEATURE));
+        let energy_feature = parameters
+            .get_config_serde_optional(&"energy_feature", &parent_key)?
+            .unwrap_or_else(|| String::from(DEFAULT_ENERGY_FEATURE));
+        Ok(Arc::new(ChargingOutputPlugin {
+            battery_capacity_kwh,
+            default_power_kw,
+            soc_feature,
+            energy_feature,
+        }))
+    }
+}
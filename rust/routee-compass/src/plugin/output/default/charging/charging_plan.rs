@@ -0,0 +1,232 @@
+use routee_compass_core::model::network::VertexId;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// state of charge of a full battery, in percent
+const FULL_SOC_PERCENT: f64 = 100.0;
+
+const MINUTES_PER_HOUR: f64 = 60.0;
+
+/// a stop to charge along a route
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ChargingStop {
+    pub vertex_id: VertexId,
+    /// number of route edges traveled before the stop
+    pub route_index: usize,
+    /// power rating of the charger in kilowatts
+    pub power_kw: f64,
+    pub arrival_soc_percent: f64,
+    pub departure_soc_percent: f64,
+    /// energy used from the origin to the stop, in kilowatt hours
+    pub cumulative_energy_kwh: f64,
+    pub energy_added_kwh: f64,
+    pub charge_duration_minutes: f64,
+}
+
+/// the charging stops of a route and the charge remaining at its destination
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ChargingPlan {
+    pub stops: Vec<ChargingStop>,
+    pub total_energy_added_kwh: f64,
+    pub total_charge_duration_minutes: f64,
+    /// energy used over the whole route, in kilowatt hours
+    pub total_energy_kwh: f64,
+    pub destination_soc_percent: f64,
+    /// true if the state of charge stays at or above the reserve along the route
+    pub feasible: bool,
+}
+
+impl ChargingPlan {
+    /// plans where a battery electric vehicle charges along a route. at each
+    /// charger, the vehicle stops only if it cannot reach the next charger on the
+    /// route, or the destination, while keeping the reserve. when it stops, it
+    /// charges enough to reach the destination with the reserve, or to full if
+    /// that is not enough, at the constant power of the charger.
+    ///
+    /// the state of charge is tracked from the energy of each edge, so energy
+    /// recovered by regenerative braking counts toward the charge, up to full.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertices` - the vertices of the route in travel order, one more than the edges
+    /// * `edge_energy` - energy used on each edge of the route, in kilowatt hours
+    /// * `chargers` - the power rating in kilowatts of each charger vertex
+    /// * `initial_soc` - state of charge at the origin, in percent
+    /// * `reserve_soc` - state of charge to keep in reserve, in percent
+    /// * `battery_capacity` - capacity of the battery in kilowatt hours
+    ///
+    /// # Returns
+    ///
+    /// the charging plan, or an error if the vertices and edges do not match
+    pub fn new(
+        vertices: &[VertexId],
+        edge_energy: &[f64],
+        chargers: &HashMap<VertexId, f64>,
+        initial_soc: f64,
+        reserve_soc: f64,
+        battery_capacity: f64,
+    ) -> Result<ChargingPlan, String> {
+        if vertices.len() != edge_energy.len() + 1 {
+            return Err(format!(
+                "route with {} edges must have {} vertices, found {}",
+                edge_energy.len(),
+                edge_energy.len() + 1,
+                vertices.len()
+            ));
+        }
+        if battery_capacity <= 0.0 {
+            return Err(format!(
+                "battery capacity must be positive, found {}",
+                battery_capacity
+            ));
+        }
+        let as_percent = |energy: f64| energy / battery_capacity * FULL_SOC_PERCENT;
+        let n_edges = edge_energy.len();
+        let is_charger = |index: usize| index < n_edges && chargers.contains_key(&vertices[index]);
+
+        let mut stops = vec![];
+        let mut soc = initial_soc;
+        let mut min_soc = initial_soc;
+        let mut cumulative_energy = 0.0;
+        for index in 0..n_edges {
+            if index > 0 {
+                cumulative_energy += edge_energy[index - 1];
+                soc = (soc - as_percent(edge_energy[index - 1])).min(FULL_SOC_PERCENT);
+                min_soc = min_soc.min(soc);
+            }
+            let Some(power_kw) = chargers.get(&vertices[index]) else {
+                continue;
+            };
+            let next_stop = (index + 1..=n_edges)
+                .find(|i| is_charger(*i))
+                .unwrap_or(n_edges);
+            let to_next_stop = peak_energy(&edge_energy[index..next_stop]);
+            if soc - as_percent(to_next_stop) >= reserve_soc {
+                continue;
+            }
+            let to_destination = peak_energy(&edge_energy[index..]);
+            let departure_soc = (reserve_soc + as_percent(to_destination)).min(FULL_SOC_PERCENT);
+            if departure_soc <= soc {
+                continue;
+            }
+            let energy_added = (departure_soc - soc) / FULL_SOC_PERCENT * battery_capacity;
+            stops.push(ChargingStop {
+                vertex_id: vertices[index],
+                route_index: index,
+                power_kw: *power_kw,
+                arrival_soc_percent: soc,
+                departure_soc_percent: departure_soc,
+                cumulative_energy_kwh: cumulative_energy,
+                energy_added_kwh: energy_added,
+                charge_duration_minutes: energy_added / power_kw * MINUTES_PER_HOUR,
+            });
+            soc = departure_soc;
+        }
+        if let Some(last) = edge_energy.last() {
+            cumulative_energy += last;
+            soc = (soc - as_percent(*last)).min(FULL_SOC_PERCENT);
+            min_soc = min_soc.min(soc);
+        }
+
+        Ok(ChargingPlan {
+            total_energy_added_kwh: stops.iter().map(|s| s.energy_added_kwh).sum(),
+            total_charge_duration_minutes: stops.iter().map(|s| s.charge_duration_minutes).sum(),
+            stops,
+            total_energy_kwh: cumulative_energy,
+            destination_soc_percent: soc,
+            feasible: min_soc >= reserve_soc,
+        })
+    }
+}
+
+/// the most energy used from the start of a sequence of edges to the end of any
+/// of them, which is the charge needed to traverse them all when some recover energy
+fn peak_energy(edge_energy: &[f64]) -> f64 {
+    edge_energy
+        .iter()
+        .scan(0.0, |used, energy| {
+            *used += energy;
+            Some(*used)
+        })
+        .fold(0.0, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vertices(n: usize) -> Vec<VertexId> {
+        (0..n).map(VertexId).collect()
+    }
+
+    #[test]
+    fn test_stops_balance_energy_and_time() {
+        // a 100 kWh battery using 30 kWh on each of 6 edges, starting at 50%
+        let edge_energy = vec![30.0; 6];
+        let chargers = HashMap::from([(VertexId(1), 50.0), (VertexId(3), 150.0)]);
+        let plan =
+            ChargingPlan::new(&vertices(7), &edge_energy, &chargers, 50.0, 10.0, 100.0).unwrap();
+
+        // at vertex 1 the vehicle arrives with 20% and cannot reach vertex 3, so it
+        // charges to full. at vertex 3 it arrives with 40% and charges to the 100%
+        // it needs to reach the destination with the reserve.
+        assert_eq!(plan.stops.len(), 2);
+        let (first, second) = (&plan.stops[0], &plan.stops[1]);
+        assert_eq!(first.vertex_id, VertexId(1));
+        assert!((first.arrival_soc_percent - 20.0).abs() < 1e-9);
+        assert!((first.departure_soc_percent - 100.0).abs() < 1e-9);
+        assert!((first.cumulative_energy_kwh - 30.0).abs() < 1e-9);
+        assert_eq!(second.vertex_id, VertexId(3));
+        assert!((second.arrival_soc_percent - 40.0).abs() < 1e-9);
+        assert!((second.cumulative_energy_kwh - 90.0).abs() < 1e-9);
+
+        for stop in plan.stops.iter() {
+            // the battery holds 100 kWh, so each kWh is 1%
+            let added_percent = stop.energy_added_kwh;
+            assert!(
+                (stop.arrival_soc_percent + added_percent - stop.departure_soc_percent).abs()
+                    < 1e-9
+            );
+            let expected_minutes = stop.energy_added_kwh / stop.power_kw * 60.0;
+            assert!((stop.charge_duration_minutes - expected_minutes).abs() < 1e-9);
+        }
+        // the faster charger takes less time per kilowatt hour
+        assert!((first.charge_duration_minutes - 80.0 / 50.0 * 60.0).abs() < 1e-9);
+        assert!((second.charge_duration_minutes - 60.0 / 150.0 * 60.0).abs() < 1e-9);
+        let total_minutes = plan
+            .stops
+            .iter()
+            .map(|s| s.charge_duration_minutes)
+            .sum::<f64>();
+        assert!((plan.total_charge_duration_minutes - total_minutes).abs() < 1e-9);
+        assert!((plan.total_energy_added_kwh - 140.0).abs() < 1e-9);
+        assert!((plan.total_energy_kwh - 180.0).abs() < 1e-9);
+        assert!((plan.destination_soc_percent - 10.0).abs() < 1e-9);
+        assert!(plan.feasible);
+    }
+
+    #[test]
+    fn test_no_stop_when_charge_suffices() {
+        let edge_energy = vec![10.0, 10.0];
+        let chargers = HashMap::from([(VertexId(1), 50.0)]);
+        let plan =
+            ChargingPlan::new(&vertices(3), &edge_energy, &chargers, 80.0, 20.0, 100.0).unwrap();
+        assert!(plan.stops.is_empty());
+        assert_eq!(plan.total_charge_duration_minutes, 0.0);
+        assert!((plan.destination_soc_percent - 60.0).abs() < 1e-9);
+        assert!(plan.feasible);
+
+        // without chargers, the route drops below the reserve
+        let plan = ChargingPlan::new(
+            &vertices(3),
+            &edge_energy,
+            &HashMap::new(),
+            30.0,
+            20.0,
+            100.0,
+        )
+        .unwrap();
+        assert!(plan.stops.is_empty());
+        assert!(!plan.feasible);
+    }
+}
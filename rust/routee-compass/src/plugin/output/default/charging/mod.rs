@@ -0,0 +1,7 @@
+mod builder;
+mod charging_plan;
+mod plugin;
+
+pub use builder::ChargingOutputPluginBuilder;
+pub use charging_plan::{ChargingPlan, ChargingStop};
+pub use plugin::ChargingOutputPlugin;
@@ -0,0 +1,154 @@
+use super::charging_plan::ChargingPlan;
+use crate::app::search::search_app_ops;
+use crate::app::{compass::CompassAppError, search::SearchAppResult};
+use crate::plugin::output::output_plugin::OutputPlugin;
+use crate::plugin::output::OutputPluginError;
+use routee_compass_core::algorithm::search::{EdgeTraversal, SearchInstance};
+use routee_compass_core::model::network::{NetworkError, VertexId};
+use routee_compass_core::model::state::StateVariable;
+use routee_compass_core::model::unit::{AsF64, EnergyUnit};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// query key for the battery capacity of the vehicle in kilowatt hours, which
+/// replaces the configured capacity
+pub const BATTERY_CAPACITY_KWH: &str = "battery_capacity_kwh";
+
+/// plans the charging stops of an electric vehicle along the route of a query
+/// with `chargers`, reporting the state of charge on arrival at and departure from
+/// each stop, the energy added and the charge duration. see [`ChargingPlan`].
+pub struct ChargingOutputPlugin {
+    /// battery capacity in kilowatt hours, when the query does not set one
+    pub battery_capacity_kwh: Option<f64>,
+    /// power rating in kilowatts of chargers listed without one
+    pub default_power_kw: Option<f64>,
+    /// name of the state of charge feature, in percent
+    pub soc_feature: String,
+    /// name of the feature accumulating the electrical energy of the route
+    pub energy_feature: String,
+}
+
+impl OutputPlugin for ChargingOutputPlugin {
+    fn process(
+        &self,
+        output: &mut serde_json::Value,
+        search_result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    ) -> Result<(), OutputPluginError> {
+        let Ok((result, si)) = search_result else {
+            return Ok(());
+        };
+        let request = &output["request"];
+        if request.get(search_app_ops::CHARGERS).is_none() {
+            return Ok(());
+        }
+        let Some(route) = result.routes.first().filter(|r| !r.is_empty()) else {
+            return Ok(());
+        };
+        let failed = |e: String| OutputPluginError::OutputPluginFailed(e);
+        let chargers = self.charger_power(request)?;
+        let reserve_soc =
+            search_app_ops::get_reserve_soc_percent(request).map_err(|e| failed(e.to_string()))?;
+        let battery_capacity = match request.get(BATTERY_CAPACITY_KWH) {
+            None => self.battery_capacity_kwh.ok_or_else(|| {
+                failed(format!(
+                    "charging plan requires a battery capacity, set in the query as '{}' or configured as 'battery_capacity_kwh'",
+                    BATTERY_CAPACITY_KWH
+                ))
+            })?,
+            Some(value) => value.as_f64().ok_or_else(|| {
+                failed(format!(
+                    "query field '{}' must be a number, found {}",
+                    BATTERY_CAPACITY_KWH, value
+                ))
+            })?,
+        };
+
+        let initial_state = si
+            .state_model
+            .initial_state()
+            .map_err(|e| failed(e.to_string()))?;
+        let initial_soc = si
+            .state_model
+            .get_custom_f64(&initial_state, &self.soc_feature)
+            .map_err(|e| failed(e.to_string()))?;
+        let vertices = route_vertices(route, si)?;
+        let edge_energy = self.edge_energy(route, &initial_state, si)?;
+        let plan = ChargingPlan::new(
+            &vertices,
+            &edge_energy,
+            &chargers,
+            initial_soc,
+            reserve_soc,
+            battery_capacity,
+        )
+        .map_err(failed)?;
+        output["charging_plan"] = json![plan];
+        Ok(())
+    }
+}
+
+impl ChargingOutputPlugin {
+    /// the power rating of each charger in the query, using the default for
+    /// chargers listed without one
+    fn charger_power(
+        &self,
+        request: &serde_json::Value,
+    ) -> Result<HashMap<VertexId, f64>, OutputPluginError> {
+        search_app_ops::get_charger_power(request)
+            .map_err(|e| OutputPluginError::OutputPluginFailed(e.to_string()))?
+            .into_iter()
+            .map(|(vertex_id, power_kw)| {
+                power_kw
+                    .or(self.default_power_kw)
+                    .map(|power| (vertex_id, power))
+                    .ok_or_else(|| {
+                        OutputPluginError::OutputPluginFailed(format!(
+                            "charger at vertex {} has no power_kw and no default_power_kw is configured",
+                            vertex_id
+                        ))
+                    })
+            })
+            .collect()
+    }
+
+    /// the energy used on each edge of a route in kilowatt hours, read from the
+    /// change in the energy feature over the edge
+    fn edge_energy(
+        &self,
+        route: &[EdgeTraversal],
+        initial_state: &[StateVariable],
+        si: &SearchInstance,
+    ) -> Result<Vec<f64>, OutputPluginError> {
+        let energy = |state: &[StateVariable]| {
+            si.state_model
+                .get_energy(state, &self.energy_feature, &EnergyUnit::KilowattHours)
+                .map(|e| e.as_f64())
+                .map_err(|e| OutputPluginError::OutputPluginFailed(e.to_string()))
+        };
+        let mut previous = energy(initial_state)?;
+        let mut edge_energy = Vec::with_capacity(route.len());
+        for et in route.iter() {
+            let current = energy(&et.result_state)?;
+            edge_energy.push(current - previous);
+            previous = current;
+        }
+        Ok(edge_energy)
+    }
+}
+
+/// the vertices of a route in travel order, from the source of the first edge
+/// to the destination of the last
+fn route_vertices(
+    route: &[EdgeTraversal],
+    si: &SearchInstance,
+) -> Result<Vec<VertexId>, OutputPluginError> {
+    let failed = |e: NetworkError| OutputPluginError::OutputPluginFailed(e.to_string());
+    let mut vertices = Vec::with_capacity(route.len() + 1);
+    for (index, et) in route.iter().enumerate() {
+        if index == 0 {
+            vertices.push(si.graph.src_vertex_id(&et.edge_id).map_err(failed)?);
+        }
+        vertices.push(si.graph.dst_vertex_id(&et.edge_id).map_err(failed)?);
+    }
+    Ok(vertices)
+}
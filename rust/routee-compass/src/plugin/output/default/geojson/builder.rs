@@ -0,0 +1,34 @@
+use super::plugin::GeoJsonOutputPlugin;
+use crate::{
+    app::compass::{CompassConfigurationError, ConfigJsonExtensions},
+    plugin::output::{OutputPlugin, OutputPluginBuilder},
+};
+use std::sync::Arc;
+
+/// Builds a plugin that writes each route as a GeoJSON FeatureCollection
+/// with one feature per edge.
+///
+/// # Configuration
+///
+/// * `state_features` (optional) - names of the state features whose change over
+///   each edge is written to the feature properties. all features when omitted.
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [[plugin.output_plugins]]
+/// type = "geojson"
+/// state_features = ["distance", "time"]
+/// ```
+pub struct GeoJsonOutputPluginBuilder {}
+
+impl OutputPluginBuilder for GeoJsonOutputPluginBuilder {
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        let state_features: Option<Vec<String>> =
+            parameters.get_config_serde_optional(&"state_features", &"geojson")?;
+        Ok(Arc::new(GeoJsonOutputPlugin { state_features }))
+    }
+}
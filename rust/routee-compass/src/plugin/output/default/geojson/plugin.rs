@@ -0,0 +1,108 @@
+use crate::app::{compass::CompassAppError, search::SearchAppResult};
+use crate::plugin::output::output_plugin::OutputPlugin;
+use crate::plugin::output::OutputPluginError;
+use geojson::feature::Id;
+use geojson::{Feature, FeatureCollection, Geometry};
+use routee_compass_core::algorithm::search::{EdgeTraversal, SearchInstance};
+use serde_json::json;
+
+/// writes each route to a `geojson` key as an RFC 7946 FeatureCollection, ready
+/// for web maps such as Leaflet. each traversed edge is a LineString feature in
+/// `[lon, lat]` order whose properties are the `edge_id`, the `access_cost` and
+/// `traversal_cost` of the edge, and the change of each state feature over the
+/// edge, keyed by feature name, so edges can be styled by their attributes.
+pub struct GeoJsonOutputPlugin {
+    /// state features written to the feature properties, or all features if None
+    pub state_features: Option<Vec<String>>,
+}
+
+impl OutputPlugin for GeoJsonOutputPlugin {
+    fn process(
+        &self,
+        output: &mut serde_json::Value,
+        search_result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    ) -> Result<(), OutputPluginError> {
+        let (result, si) = match search_result {
+            Err(_) => return Ok(()),
+            Ok(r) => r,
+        };
+        let collections = result
+            .routes
+            .iter()
+            .map(|route| self.route_feature_collection(route, si))
+            .collect::<Result<Vec<_>, _>>()?;
+        // like the traversal plugin, a single route is stored as an object and
+        // more than one as an array
+        output["geojson"] = match collections.as_slice() {
+            [] => serde_json::Value::Null,
+            [collection] => collection.to_owned(),
+            _ => json![collections],
+        };
+        Ok(())
+    }
+
+    fn requires_geometry(&self) -> bool {
+        true
+    }
+}
+
+impl GeoJsonOutputPlugin {
+    fn route_feature_collection(
+        &self,
+        route: &[EdgeTraversal],
+        si: &SearchInstance,
+    ) -> Result<serde_json::Value, OutputPluginError> {
+        let feature_names = match &self.state_features {
+            Some(names) => names.clone(),
+            None => si
+                .state_model
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect(),
+        };
+        let initial_state = si
+            .state_model
+            .initial_state()
+            .map_err(|e| OutputPluginError::OutputPluginFailed(e.to_string()))?;
+        let mut prev_state = &initial_state;
+        let mut features = Vec::with_capacity(route.len());
+        for et in route.iter() {
+            let linestring = si.map_model.get(&et.edge_id).map_err(|e| {
+                OutputPluginError::OutputPluginFailed(format!(
+                    "failure building route geojson: {}",
+                    e
+                ))
+            })?;
+            let mut properties = serde_json::Map::new();
+            properties.insert(String::from("edge_id"), json![et.edge_id]);
+            properties.insert(String::from("access_cost"), json![et.access_cost]);
+            properties.insert(String::from("traversal_cost"), json![et.traversal_cost]);
+            for name in feature_names.iter() {
+                let delta = si
+                    .state_model
+                    .get_delta(prev_state, &et.result_state, name)
+                    .map_err(|e| {
+                        OutputPluginError::OutputPluginFailed(format!(
+                            "cannot write state feature '{}' to geojson: {}",
+                            name, e
+                        ))
+                    })?;
+                properties.insert(name.clone(), json![delta]);
+            }
+            features.push(Feature {
+                bbox: None,
+                geometry: Some(Geometry::from(linestring)),
+                id: Some(Id::Number(serde_json::Number::from(et.edge_id.0))),
+                properties: Some(properties),
+                foreign_members: None,
+            });
+            prev_state = &et.result_state;
+        }
+        let collection = FeatureCollection {
+            bbox: None,
+            features,
+            foreign_members: None,
+        };
+        Ok(serde_json::to_value(collection)?)
+    }
+}
@@ -0,0 +1,45 @@
+use super::plugin::GpxOutputPlugin;
+use crate::{
+    app::compass::{CompassConfigurationError, ConfigJsonExtensions},
+    plugin::output::{OutputPlugin, OutputPluginBuilder},
+};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+/// Builds a plugin that writes each route as a GPX track.
+///
+/// # Configuration
+///
+/// * `start_timestamp` (optional) - the trip start as an RFC 3339 timestamp, such as
+///   `"2024-05-01T08:00:00Z"`. when set, each track point has the time it is reached.
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [[plugin.output_plugins]]
+/// type = "gpx"
+/// start_timestamp = "2024-05-01T08:00:00-06:00"
+/// ```
+pub struct GpxOutputPluginBuilder {}
+
+impl OutputPluginBuilder for GpxOutputPluginBuilder {
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        let start_timestamp = parameters
+            .get_config_string_optional(&"start_timestamp")?
+            .map(|s| {
+                DateTime::parse_from_rfc3339(&s)
+                    .map(|t| t.with_timezone(&Utc))
+                    .map_err(|e| {
+                        CompassConfigurationError::UserConfigurationError(format!(
+                            "gpx start_timestamp '{}' is not an RFC 3339 timestamp: {}",
+                            s, e
+                        ))
+                    })
+            })
+            .transpose()?;
+        Ok(Arc::new(GpxOutputPlugin { start_timestamp }))
+    }
+}
@@ -0,0 +1,5 @@
+mod builder;
+mod plugin;
+
+pub use builder::GpxOutputPluginBuilder;
+pub use plugin::GpxOutputPlugin;
@@ -0,0 +1,184 @@
+use crate::app::{compass::CompassAppError, search::SearchAppResult};
+use crate::plugin::output::output_plugin::OutputPlugin;
+use crate::plugin::output::OutputPluginError;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use routee_compass_core::algorithm::search::{EdgeTraversal, SearchInstance};
+use routee_compass_core::model::network::VertexId;
+use routee_compass_core::model::unit::{AsF64, TimeUnit};
+use serde_json::json;
+use std::fmt::Write;
+
+/// name of the state feature read for the time each track point is reached
+const TIME_FEATURE: &str = "time";
+
+/// writes each route to a `gpx` key as a GPX 1.1 document for consumer GPS apps,
+/// with a single `<trk>` holding one `<trkseg>` of a `<trkpt>` for each vertex of
+/// the route. when a start timestamp is configured and the state model has a
+/// `time` feature, each point has the `<time>` it is reached, from the trip time
+/// after each edge. otherwise `<time>` elements are left out.
+pub struct GpxOutputPlugin {
+    pub start_timestamp: Option<DateTime<Utc>>,
+}
+
+/// a track point at a longitude and latitude, with the time it is reached if known
+struct TrackPoint {
+    lon: f32,
+    lat: f32,
+    time: Option<DateTime<Utc>>,
+}
+
+impl OutputPlugin for GpxOutputPlugin {
+    fn process(
+        &self,
+        output: &mut serde_json::Value,
+        search_result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    ) -> Result<(), OutputPluginError> {
+        let (result, si) = match search_result {
+            Err(_) => return Ok(()),
+            Ok(r) => r,
+        };
+        let documents = result
+            .routes
+            .iter()
+            .map(|route| {
+                let points = self.track_points(route, si)?;
+                gpx_document(&points).map_err(|e| {
+                    OutputPluginError::InternalError(format!("failure writing gpx: {}", e))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // like the traversal plugin, a single route is stored as a string and
+        // more than one as an array
+        output["gpx"] = match documents.as_slice() {
+            [] => serde_json::Value::Null,
+            [document] => json![document],
+            _ => json![documents],
+        };
+        Ok(())
+    }
+}
+
+impl GpxOutputPlugin {
+    fn track_points(
+        &self,
+        route: &[EdgeTraversal],
+        si: &SearchInstance,
+    ) -> Result<Vec<TrackPoint>, OutputPluginError> {
+        let has_time = si.state_model.contains_key(&TIME_FEATURE.into());
+        let start = self.start_timestamp.filter(|_| has_time);
+        let failure = |e: String| {
+            OutputPluginError::OutputPluginFailed(format!("failure building gpx track: {}", e))
+        };
+        let point = |vertex_id: VertexId, time| {
+            si.graph
+                .get_vertex(&vertex_id)
+                .map(|v| TrackPoint {
+                    lon: v.x(),
+                    lat: v.y(),
+                    time,
+                })
+                .map_err(|e| failure(e.to_string()))
+        };
+        let mut points = Vec::with_capacity(route.len() + 1);
+        if let Some(first) = route.first() {
+            let origin = si
+                .graph
+                .src_vertex_id(&first.edge_id)
+                .map_err(|e| failure(e.to_string()))?;
+            points.push(point(origin, start)?);
+        }
+        for et in route.iter() {
+            let time = match start {
+                None => None,
+                Some(start) => {
+                    let seconds = si
+                        .state_model
+                        .get_time(&et.result_state, &TIME_FEATURE.into(), &TimeUnit::Seconds)
+                        .map_err(|e| failure(e.to_string()))?;
+                    let elapsed = Duration::milliseconds((seconds.as_f64() * 1000.0) as i64);
+                    Some(start + elapsed)
+                }
+            };
+            let destination = si
+                .graph
+                .dst_vertex_id(&et.edge_id)
+                .map_err(|e| failure(e.to_string()))?;
+            points.push(point(destination, time)?);
+        }
+        Ok(points)
+    }
+}
+
+/// writes a GPX 1.1 document with a single track segment of the points
+fn gpx_document(points: &[TrackPoint]) -> Result<String, std::fmt::Error> {
+    let mut gpx = String::new();
+    writeln!(gpx, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        gpx,
+        r#"<gpx version="1.1" creator="RouteE Compass" xmlns="http://www.topografix.com/GPX/1/1">"#
+    )?;
+    writeln!(gpx, "  <trk>")?;
+    writeln!(gpx, "    <trkseg>")?;
+    for point in points.iter() {
+        match point.time {
+            None => writeln!(
+                gpx,
+                r#"      <trkpt lat="{}" lon="{}"/>"#,
+                point.lat, point.lon
+            )?,
+            Some(time) => writeln!(
+                gpx,
+                r#"      <trkpt lat="{}" lon="{}"><time>{}</time></trkpt>"#,
+                point.lat,
+                point.lon,
+                time.to_rfc3339_opts(SecondsFormat::Secs, true)
+            )?,
+        }
+    }
+    writeln!(gpx, "    </trkseg>")?;
+    writeln!(gpx, "  </trk>")?;
+    write!(gpx, "</gpx>")?;
+    Ok(gpx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gpx_document() {
+        let start = DateTime::parse_from_rfc3339("2024-05-01T08:00:00-06:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let points = vec![
+            TrackPoint {
+                lon: -105.17,
+                lat: 39.74,
+                time: Some(start),
+            },
+            TrackPoint {
+                lon: -104.81,
+                lat: 41.15,
+                time: Some(start + Duration::seconds(90)),
+            },
+        ];
+        let gpx = gpx_document(&points).unwrap();
+        assert!(gpx.contains(r#"<gpx version="1.1""#));
+        assert_eq!(gpx.matches("<trk>").count(), 1);
+        assert_eq!(gpx.matches("<trkseg>").count(), 1);
+        assert!(gpx.contains(
+            r#"<trkpt lat="39.74" lon="-105.17"><time>2024-05-01T14:00:00Z</time></trkpt>"#
+        ));
+        assert!(gpx.contains("<time>2024-05-01T14:01:30Z</time>"));
+
+        // without times, points are written without a time element
+        let untimed = points
+            .iter()
+            .map(|p| TrackPoint { time: None, ..*p })
+            .collect::<Vec<_>>();
+        let gpx = gpx_document(&untimed).unwrap();
+        assert!(!gpx.contains("<time>"));
+        assert!(gpx.contains(r#"<trkpt lat="41.15" lon="-104.81"/>"#));
+        assert!(gpx.ends_with("</gpx>"));
+    }
+}
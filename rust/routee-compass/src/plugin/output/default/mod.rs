@@ -1,3 +1,8 @@
+pub mod charging;
+pub mod geojson;
+pub mod gpx;
+pub mod polyline;
+pub mod query_hash;
 pub mod summary;
 pub mod traversal;
 pub mod uuid;
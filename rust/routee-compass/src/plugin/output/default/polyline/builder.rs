@@ -0,0 +1,52 @@
+use super::plugin::PolylineOutputPlugin;
+use crate::{
+    app::compass::{CompassConfigurationError, ConfigJsonExtensions},
+    plugin::output::{OutputPlugin, OutputPluginBuilder},
+};
+use std::sync::Arc;
+
+const DEFAULT_PRECISION: u32 = 5;
+const DEFAULT_OUTPUT_KEY: &str = "polyline";
+
+/// Builds a plugin that writes each route geometry as an encoded polyline.
+///
+/// # Configuration
+///
+/// * `precision` (optional) - decimal places of the encoded coordinates, either
+///   5 (the default, used by Google Maps) or 6 (used by OSRM and Valhalla).
+/// * `output_key` (optional) - the key of the output to write the polyline to.
+///   `"polyline"` when omitted.
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [[plugin.output_plugins]]
+/// type = "polyline"
+/// precision = 6
+/// output_key = "route_polyline"
+/// ```
+pub struct PolylineOutputPluginBuilder {}
+
+impl OutputPluginBuilder for PolylineOutputPluginBuilder {
+    fn build(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        let precision: u32 = parameters
+            .get_config_serde_optional(&"precision", &"polyline")?
+            .unwrap_or(DEFAULT_PRECISION);
+        if precision != 5 && precision != 6 {
+            return Err(CompassConfigurationError::UserConfigurationError(format!(
+                "polyline precision must be 5 or 6, found {}",
+                precision
+            )));
+        }
+        let output_key = parameters
+            .get_config_string_optional(&"output_key")?
+            .unwrap_or_else(|| String::from(DEFAULT_OUTPUT_KEY));
+        Ok(Arc::new(PolylineOutputPlugin {
+            precision,
+            output_key,
+        }))
+    }
+}
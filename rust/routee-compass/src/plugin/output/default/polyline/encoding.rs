@@ -0,0 +1,119 @@
+use geo::Coord;
+
+/// encodes coordinates with the encoded polyline algorithm used by Google Maps.
+/// each latitude and longitude is rounded to `precision` decimal places and
+/// written as the difference from the previous coordinate, so long routes of
+/// nearby points encode to short strings.
+///
+/// # Arguments
+///
+/// * `coords`    - coordinates in `x` = longitude, `y` = latitude order
+/// * `precision` - decimal places kept, 5 for Google Maps or 6 for OSRM
+///
+/// # Returns
+///
+/// the encoded polyline, which is empty when there are no coordinates
+pub fn encode_polyline(coords: &[Coord<f64>], precision: u32) -> String {
+    let factor = 10_f64.powi(precision as i32);
+    let mut encoded = String::new();
+    let (mut prev_lat, mut prev_lon) = (0_i64, 0_i64);
+    for coord in coords.iter() {
+        let lat = (coord.y * factor).round() as i64;
+        let lon = (coord.x * factor).round() as i64;
+        encode_value(lat - prev_lat, &mut encoded);
+        encode_value(lon - prev_lon, &mut encoded);
+        (prev_lat, prev_lon) = (lat, lon);
+    }
+    encoded
+}
+
+/// writes a signed value as chunks of 5 bits, least significant first, with
+/// the sign in the lowest bit and each chunk offset into printable ASCII.
+fn encode_value(value: i64, encoded: &mut String) {
+    let mut remaining = if value < 0 { !(value << 1) } else { value << 1 } as u64;
+    while remaining >= 0x20 {
+        encoded.push(char::from((0x20 | (remaining & 0x1f)) as u8 + 63));
+        remaining >>= 5;
+    }
+    encoded.push(char::from(remaining as u8 + 63));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::coord;
+
+    /// decodes a polyline, as in the reference implementation of the algorithm
+    fn decode_polyline(encoded: &str, precision: u32) -> Vec<Coord<f64>> {
+        let factor = 10_f64.powi(precision as i32);
+        let mut values = vec![];
+        let (mut value, mut shift) = (0_i64, 0);
+        for byte in encoded.bytes() {
+            let chunk = (byte - 63) as i64;
+            value |= (chunk & 0x1f) << shift;
+            shift += 5;
+            if chunk < 0x20 {
+                values.push(if value & 1 == 1 {
+                    !(value >> 1)
+                } else {
+                    value >> 1
+                });
+                (value, shift) = (0, 0);
+            }
+        }
+        let (mut lat, mut lon) = (0, 0);
+        values
+            .chunks(2)
+            .map(|delta| {
+                lat += delta[0];
+                lon += delta[1];
+                coord! {x: lon as f64 / factor, y: lat as f64 / factor}
+            })
+            .collect()
+    }
+
+    fn reference_coords() -> Vec<Coord<f64>> {
+        vec![
+            coord! {x: -120.2, y: 38.5},
+            coord! {x: -120.95, y: 40.7},
+            coord! {x: -126.453, y: 43.252},
+        ]
+    }
+
+    #[test]
+    fn test_encode_reference_polyline() {
+        // the example of the Google Maps polyline algorithm documentation
+        assert_eq!(
+            encode_polyline(&reference_coords(), 5),
+            "_p~iF~ps|U_ulLnnqC_mqNvxq`@"
+        );
+        // the same coordinates at the precision used by OSRM
+        assert_eq!(
+            encode_polyline(&reference_coords(), 6),
+            "_izlhA~rlgdF_{geC~ywl@_kwzCn`{nI"
+        );
+        assert_eq!(encode_polyline(&[], 5), "");
+    }
+
+    #[test]
+    fn test_polyline_round_trip() {
+        let coords = vec![
+            coord! {x: -105.1683038, y: 39.7379033},
+            coord! {x: -104.8086039, y: 41.1475252},
+            coord! {x: -111.9095014, y: 40.7607176},
+            coord! {x: -111.9095014, y: 40.7607176},
+            coord! {x: 0.0, y: -0.000001},
+        ];
+        for precision in [5, 6] {
+            let tolerance = 0.5 / 10_f64.powi(precision as i32) + 1e-12;
+            let decoded = decode_polyline(&encode_polyline(&coords, precision), precision);
+            assert_eq!(decoded.len(), coords.len());
+            for (a, b) in coords.iter().zip(decoded.iter()) {
+                assert!((a.x - b.x).abs() <= tolerance, "{:?} {:?}", a, b);
+                assert!((a.y - b.y).abs() <= tolerance, "{:?} {:?}", a, b);
+            }
+        }
+        let decoded = decode_polyline("_p~iF~ps|U_ulLnnqC_mqNvxq`@", 5);
+        assert_eq!(decoded, reference_coords());
+    }
+}
@@ -0,0 +1,7 @@
+mod builder;
+mod encoding;
+mod plugin;
+
+pub use builder::PolylineOutputPluginBuilder;
+pub use encoding::encode_polyline;
+pub use plugin::PolylineOutputPlugin;
@@ -0,0 +1,55 @@
+use super::encoding::encode_polyline;
+use crate::app::{compass::CompassAppError, search::SearchAppResult};
+use crate::plugin::output::default::traversal::traversal_ops;
+use crate::plugin::output::output_plugin::OutputPlugin;
+use crate::plugin::output::OutputPluginError;
+use geo::{Coord, CoordsIter};
+use routee_compass_core::algorithm::search::SearchInstance;
+use serde_json::json;
+
+/// writes the geometry of each route to the `output_key` of the output as an
+/// encoded polyline, a compact string that the Google Maps SDK and OSRM-style
+/// clients decode directly, in place of a full GeoJSON geometry.
+pub struct PolylineOutputPlugin {
+    /// decimal places of the encoded coordinates, 5 or 6
+    pub precision: u32,
+    pub output_key: String,
+}
+
+impl OutputPlugin for PolylineOutputPlugin {
+    fn process(
+        &self,
+        output: &mut serde_json::Value,
+        search_result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    ) -> Result<(), OutputPluginError> {
+        let (result, si) = match search_result {
+            Err(_) => return Ok(()),
+            Ok(r) => r,
+        };
+        let polylines = result
+            .routes
+            .iter()
+            .map(|route| {
+                let linestring =
+                    traversal_ops::create_route_linestring(route, si.map_model.clone())?;
+                let coords = linestring
+                    .coords_iter()
+                    .map(|c| Coord::from((c.x as f64, c.y as f64)))
+                    .collect::<Vec<_>>();
+                Ok(encode_polyline(&coords, self.precision))
+            })
+            .collect::<Result<Vec<_>, OutputPluginError>>()?;
+        // like the traversal plugin, a single route is stored as a string and
+        // more than one as an array
+        output[&self.output_key] = match polylines.as_slice() {
+            [] => serde_json::Value::Null,
+            [polyline] => json![polyline],
+            _ => json![polylines],
+        };
+        Ok(())
+    }
+
+    fn requires_geometry(&self) -> bool {
+        true
+    }
+}
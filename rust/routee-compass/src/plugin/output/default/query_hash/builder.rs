@@ -0,0 +1,17 @@
+use super::plugin::QueryHashOutputPlugin;
+use crate::{
+    app::compass::CompassConfigurationError,
+    plugin::output::{OutputPlugin, OutputPluginBuilder},
+};
+use std::sync::Arc;
+
+pub struct QueryHashOutputPluginBuilder {}
+
+impl OutputPluginBuilder for QueryHashOutputPluginBuilder {
+    fn build(
+        &self,
+        _parameters: &serde_json::Value,
+    ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
+        Ok(Arc::new(QueryHashOutputPlugin {}))
+    }
+}
@@ -0,0 +1,5 @@
+mod builder;
+mod plugin;
+
+pub use builder::QueryHashOutputPluginBuilder;
+pub use plugin::{query_hash, QueryHashOutputPlugin};
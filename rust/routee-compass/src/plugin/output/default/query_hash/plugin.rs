@@ -0,0 +1,77 @@
+use crate::app::{compass::CompassAppError, search::SearchAppResult};
+use crate::plugin::output::output_plugin::OutputPlugin;
+use crate::plugin::output::OutputPluginError;
+use routee_compass_core::algorithm::search::SearchInstance;
+use routee_compass_core::util::fingerprint::Fingerprint;
+use serde_json::{json, Value};
+
+/// appends a `query_hash` to the result, a stable hash of the effective query
+/// after input plugins have been applied. queries with the same parameters have
+/// the same hash regardless of the order of their keys, so the hash can be used
+/// to key a cache of results.
+pub struct QueryHashOutputPlugin {}
+
+impl OutputPlugin for QueryHashOutputPlugin {
+    fn process(
+        &self,
+        output: &mut serde_json::Value,
+        _search_result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
+    ) -> Result<(), OutputPluginError> {
+        let request = output.get("request").ok_or_else(|| {
+            OutputPluginError::OutputPluginFailed(String::from(
+                "output is missing the request to hash",
+            ))
+        })?;
+        output["query_hash"] = json![query_hash(request)];
+        Ok(())
+    }
+}
+
+/// computes a hash of a query that is stable across runs and platforms. the
+/// query is written as JSON with the keys of every object sorted, and the text
+/// is hashed with 64-bit FNV-1a.
+///
+/// # Arguments
+///
+/// * `query` - the query to hash
+///
+/// # Returns
+///
+/// the hash as 16 hexadecimal characters
+pub fn query_hash(query: &Value) -> String {
+    let mut fingerprint = Fingerprint::default();
+    fingerprint.write_json(query);
+    fingerprint.to_hex()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_hash_key_order() {
+        let a = serde_json::from_str::<Value>(
+            r#"{"origin_vertex": 0, "destination_vertex": 2, "weights": {"time": 1, "distance": 0}}"#,
+        )
+        .unwrap();
+        let b = serde_json::from_str::<Value>(
+            r#"{"weights": {"distance": 0, "time": 1}, "destination_vertex": 2, "origin_vertex": 0}"#,
+        )
+        .unwrap();
+        assert_eq!(query_hash(&a), query_hash(&b));
+
+        let c = serde_json::from_str::<Value>(
+            r#"{"origin_vertex": 0, "destination_vertex": 2, "weights": {"time": 1, "distance": 1}}"#,
+        )
+        .unwrap();
+        assert_ne!(query_hash(&a), query_hash(&c));
+    }
+
+    #[test]
+    fn test_query_hash_stable() {
+        // the hash must not change between runs or releases, as it keys external caches
+        let query = serde_json::json!({"origin_vertex": 0, "destination_vertex": 2});
+        assert_eq!(query_hash(&query).len(), 16);
+        assert_eq!(query_hash(&serde_json::json!({})), "08f44b07b5901a25");
+    }
+}
@@ -1,17 +1,48 @@
 use super::plugin::SummaryOutputPlugin;
 use crate::{
-    app::compass::CompassConfigurationError,
+    app::compass::{CompassConfigurationError, ConfigJsonExtensions},
     plugin::output::{OutputPlugin, OutputPluginBuilder},
 };
 use std::sync::Arc;
 
+/// Builds a plugin that summarizes the search.
+///
+/// # Configuration
+///
+/// * `arrival_rounding_seconds` (optional) - round the reported arrival time to
+///   the nearest multiple of this many seconds, such as 60 for the nearest minute
+/// * `eta_percentiles` (optional) - percentiles of the trip time to report from the
+///   accumulated `time_variance` of the route, each strictly between 0 and 100
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [[plugin.output_plugins]]
+/// type = "summary"
+/// arrival_rounding_seconds = 60
+/// eta_percentiles = [10, 50, 90]
+/// ```
 pub struct SummaryOutputPluginBuilder {}
 
 impl OutputPluginBuilder for SummaryOutputPluginBuilder {
     fn build(
         &self,
-        _parameters: &serde_json::Value,
+        parameters: &serde_json::Value,
     ) -> Result<Arc<dyn OutputPlugin>, CompassConfigurationError> {
-        Ok(Arc::new(SummaryOutputPlugin {}))
+        let arrival_rounding_seconds: Option<u64> =
+            parameters.get_config_serde_optional(&"arrival_rounding_seconds", &"summary")?;
+        let eta_percentiles: Vec<f64> = parameters
+            .get_config_serde_optional(&"eta_percentiles", &"summary")?
+            .unwrap_or_default();
+        if let Some(p) = eta_percentiles.iter().find(|p| !(**p > 0.0 && **p < 100.0)) {
+            return Err(CompassConfigurationError::UserConfigurationError(format!(
+                "summary eta_percentiles must be between 0 and 100 exclusive, found {}",
+                p
+            )));
+        }
+        Ok(Arc::new(SummaryOutputPlugin {
+            arrival_rounding_seconds,
+            eta_percentiles,
+        }))
     }
 }
@@ -1,12 +1,38 @@
 use crate::app::{compass::CompassAppError, search::SearchAppResult};
 use crate::plugin::output::output_plugin::OutputPlugin;
 use crate::plugin::output::OutputPluginError;
+use chrono::{NaiveTime, Weekday};
 use routee_compass_core::algorithm::search::SearchInstance;
+use routee_compass_core::model::state::{StateModel, StateVariable};
+use routee_compass_core::model::traversal::default::{
+    parse_time_of_day, SpeedTraversalModel, START_TIME,
+};
+use routee_compass_core::model::unit::{AsF64, Time, TimeUnit};
 use routee_compass_core::util::duration_extension::DurationExtension;
 use serde_json::{self, json};
 
-/// provides metrics for the performance of the search algorithm.
-pub struct SummaryOutputPlugin {}
+/// query key for the weekday of the trip start, such as "monday"
+pub const START_WEEKDAY: &str = "start_weekday";
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// provides metrics for the performance of the search algorithm. when the
+/// query has a `start_time`, also reports the arrival clock time of the route.
+pub struct SummaryOutputPlugin {
+    /// if set, the arrival time is rounded to the nearest multiple of this many seconds
+    pub arrival_rounding_seconds: Option<u64>,
+    /// percentiles of the trip time reported when the route tracks its time variance
+    pub eta_percentiles: Vec<f64>,
+}
+
+/// the wall-clock arrival of a trip
+#[derive(Debug, PartialEq)]
+pub struct ArrivalTime {
+    pub time: NaiveTime,
+    /// number of midnights passed during the trip
+    pub day_offset: u64,
+    pub weekday: Option<Weekday>,
+}
 
 impl OutputPlugin for SummaryOutputPlugin {
     /// append "Cost" value to the output JSON
@@ -17,7 +43,7 @@ impl OutputPlugin for SummaryOutputPlugin {
     ) -> Result<(), OutputPluginError> {
         match search_result {
             Err(_e) => Ok(()),
-            Ok((result, _)) => {
+            Ok((result, si)) => {
                 let memory_bytes = allocative::size_of_unique(result) as f64;
                 let memory_mib = memory_bytes / 1_048_576.0;
                 let route_edges = result.routes.iter().map(|r| r.len()).sum::<usize>();
@@ -28,8 +54,285 @@ impl OutputPlugin for SummaryOutputPlugin {
                 output["tree_size_count"] = json![tree_edges];
                 output["search_result_size_mib"] = json![memory_mib];
                 output["iterations"] = json![result.iterations];
+                if let Some(requested) = result.alternatives_requested {
+                    output["alternatives"] = json![{
+                        "requested": requested,
+                        "found": result.routes.len()
+                    }];
+                }
+
+                // report the arrival of the first route from the trip start time
+                let start_time = output["request"].get(START_TIME).cloned();
+                let last_edge = result.routes.first().and_then(|r| r.last());
+                if let (Some(start_time), Some(last_edge)) = (start_time, last_edge) {
+                    let start_seconds = start_time
+                        .as_str()
+                        .ok_or_else(|| {
+                            OutputPluginError::OutputPluginFailed(format!(
+                                "query field '{}' must be a string, found {}",
+                                START_TIME, start_time
+                            ))
+                        })
+                        .and_then(|s| {
+                            parse_time_of_day(s)
+                                .map_err(|e| OutputPluginError::OutputPluginFailed(e.to_string()))
+                        })?;
+                    let start_weekday = match output["request"].get(START_WEEKDAY) {
+                        None => None,
+                        Some(value) => Some(
+                            value
+                                .as_str()
+                                .and_then(|s| s.parse::<Weekday>().ok())
+                                .ok_or_else(|| {
+                                    OutputPluginError::OutputPluginFailed(format!(
+                                        "query field '{}' must be a weekday such as 'monday', found {}",
+                                        START_WEEKDAY, value
+                                    ))
+                                })?,
+                        ),
+                    };
+                    let trip_time = si
+                        .state_model
+                        .get_time(
+                            &last_edge.result_state,
+                            &String::from("time"),
+                            &TimeUnit::Seconds,
+                        )
+                        .map_err(|e| OutputPluginError::OutputPluginFailed(e.to_string()))?;
+                    let arrival = arrival_time(
+                        start_seconds,
+                        trip_time.as_f64(),
+                        start_weekday,
+                        self.arrival_rounding_seconds,
+                    );
+                    output["arrival_time"] = json![arrival.time.format("%H:%M:%S").to_string()];
+                    output["arrival_day_offset"] = json![arrival.day_offset];
+                    if let Some(weekday) = arrival.weekday {
+                        output["arrival_weekday"] = json![weekday_name(&weekday)];
+                    }
+                }
+
+                // report a range of trip times for the first route from its time variance
+                let last_edge = last_edge.filter(|_| !self.eta_percentiles.is_empty());
+                if let Some(last_edge) = last_edge {
+                    let state = &last_edge.result_state;
+                    let variance = time_variance_seconds(&si.state_model, state)
+                        .map_err(OutputPluginError::OutputPluginFailed)?;
+                    if let Some(variance) = variance {
+                        let trip_time = si
+                            .state_model
+                            .get_time(state, &String::from("time"), &TimeUnit::Seconds)
+                            .map_err(|e| OutputPluginError::OutputPluginFailed(e.to_string()))?;
+                        let range: serde_json::Map<String, serde_json::Value> =
+                            eta_range(trip_time.as_f64(), variance, &self.eta_percentiles)
+                                .into_iter()
+                                .map(|(p, seconds)| (format!("p{}", p), json![seconds]))
+                                .collect();
+                        output["eta_range"] = json![range];
+                    }
+                }
                 Ok(())
             }
         }
     }
 }
+
+/// computes the wall-clock arrival of a trip, rolling over midnight as needed.
+///
+/// # Arguments
+///
+/// * `start_seconds` - trip start, in seconds since midnight
+/// * `trip_seconds` - duration of the trip in seconds
+/// * `start_weekday` - optional weekday of the trip start
+/// * `rounding_seconds` - if set, round the arrival to the nearest multiple of this many seconds
+///
+/// # Returns
+///
+/// the arrival time of day, the number of days after the start day, and the
+/// arrival weekday if the start weekday is known
+pub fn arrival_time(
+    start_seconds: f64,
+    trip_seconds: f64,
+    start_weekday: Option<Weekday>,
+    rounding_seconds: Option<u64>,
+) -> ArrivalTime {
+    let arrival = (start_seconds + trip_seconds).max(0.0).round() as u64;
+    let arrival = match rounding_seconds {
+        Some(r) if r > 0 => ((arrival + r / 2) / r) * r,
+        _ => arrival,
+    };
+    let day_offset = arrival / SECONDS_PER_DAY;
+    let time_seconds = (arrival % SECONDS_PER_DAY) as u32;
+    let time = NaiveTime::from_num_seconds_from_midnight_opt(time_seconds, 0).unwrap_or_default();
+    let weekday = start_weekday.map(|w| (0..day_offset % 7).fold(w, |acc, _| acc.succ()));
+    ArrivalTime {
+        time,
+        day_offset,
+        weekday,
+    }
+}
+
+/// computes percentiles of a trip time from its mean and variance. edge travel
+/// times are assumed independent, so the trip time is approximated as normally
+/// distributed with the summed variance. times are clamped at zero.
+///
+/// # Arguments
+///
+/// * `mean_seconds` - expected trip time in seconds
+/// * `variance_seconds` - variance of the trip time in seconds squared
+/// * `percentiles` - percentiles to compute, each strictly between 0 and 100
+///
+/// # Returns
+///
+/// each percentile paired with the trip time at that percentile, in seconds
+pub fn eta_range(mean_seconds: f64, variance_seconds: f64, percentiles: &[f64]) -> Vec<(f64, f64)> {
+    let std_dev = variance_seconds.max(0.0).sqrt();
+    percentiles
+        .iter()
+        .map(|p| {
+            let z = inverse_normal_cdf(p / 100.0);
+            (*p, (mean_seconds + z * std_dev).max(0.0))
+        })
+        .collect()
+}
+
+/// reads the accumulated time variance of a state, converted to seconds squared.
+/// the variance is recorded in the square of the traversal model's time unit.
+///
+/// # Returns
+///
+/// the variance, or None if the state model does not track one
+fn time_variance_seconds(
+    state_model: &StateModel,
+    state: &[StateVariable],
+) -> Result<Option<f64>, String> {
+    let name = String::from(SpeedTraversalModel::TIME_VARIANCE);
+    let feature = match state_model.iter().find(|(n, _)| **n == name) {
+        Some((_, feature)) => feature,
+        None => return Ok(None),
+    };
+    let unit_name = feature.get_feature_unit_name();
+    let time_unit = unit_name
+        .strip_suffix("_squared")
+        .and_then(|u| u.parse::<TimeUnit>().ok())
+        .ok_or_else(|| {
+            format!(
+                "unable to read time unit of feature '{}' with unit '{}'",
+                name, unit_name
+            )
+        })?;
+    let variance = state_model
+        .get_custom_f64(state, &name)
+        .map_err(|e| e.to_string())?;
+    let seconds = time_unit
+        .convert(&Time::new(1.0), &TimeUnit::Seconds)
+        .as_f64();
+    Ok(Some(variance * seconds * seconds))
+}
+
+/// inverse of the standard normal cumulative distribution function, using the
+/// rational approximation of Acklam, with a relative error below 1.15e-9.
+fn inverse_normal_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239e0,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838e0,
+        -2.549732539343734e0,
+        4.374664141464968e0,
+        2.938163982698783e0,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996e0,
+        3.754408661907416e0,
+    ];
+    const P_LOW: f64 = 0.02425;
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+fn weekday_name(weekday: &Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "monday",
+        Weekday::Tue => "tuesday",
+        Weekday::Wed => "wednesday",
+        Weekday::Thu => "thursday",
+        Weekday::Fri => "friday",
+        Weekday::Sat => "saturday",
+        Weekday::Sun => "sunday",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arrival_rolls_over_midnight() {
+        let start = parse_time_of_day("23:50").unwrap();
+        let arrival = arrival_time(start, 20.0 * 60.0, Some(Weekday::Sun), None);
+        assert_eq!(arrival.time, NaiveTime::from_hms_opt(0, 10, 0).unwrap());
+        assert_eq!(arrival.day_offset, 1);
+        assert_eq!(arrival.weekday, Some(Weekday::Mon));
+    }
+
+    #[test]
+    fn test_arrival_rounding() {
+        let start = parse_time_of_day("08:00").unwrap();
+        let arrival = arrival_time(start, 29.0 * 60.0 + 31.0, None, Some(60));
+        assert_eq!(arrival.time, NaiveTime::from_hms_opt(8, 30, 0).unwrap());
+        assert_eq!(arrival.day_offset, 0);
+        assert_eq!(arrival.weekday, None);
+        // rounding up to midnight counts as the next day
+        let start = parse_time_of_day("23:59:50").unwrap();
+        let arrival = arrival_time(start, 0.0, None, Some(60));
+        assert_eq!(arrival.time, NaiveTime::from_hms_opt(0, 0, 0).unwrap());
+        assert_eq!(arrival.day_offset, 1);
+    }
+
+    #[test]
+    fn test_eta_range_widens_with_variance() {
+        let percentiles = [10.0, 50.0, 90.0];
+        // two routes with a mean trip of one hour, one with a standard deviation
+        // of 10 minutes and one with a standard deviation of 2 minutes
+        let volatile = eta_range(3600.0, 600.0 * 600.0, &percentiles);
+        let steady = eta_range(3600.0, 120.0 * 120.0, &percentiles);
+        let span = |r: &[(f64, f64)]| r[2].1 - r[0].1;
+        assert!((volatile[1].1 - 3600.0).abs() < 1e-6);
+        assert!((steady[1].1 - 3600.0).abs() < 1e-6);
+        // the 10th and 90th percentiles are 1.2816 standard deviations from the mean
+        assert!((span(&volatile) - 2.0 * 1.2816 * 600.0).abs() < 0.1);
+        assert!((span(&steady) - 2.0 * 1.2816 * 120.0).abs() < 0.1);
+        assert!(span(&volatile) > span(&steady));
+        // a trip time is never negative
+        assert_eq!(eta_range(60.0, 600.0 * 600.0, &[1.0])[0].1, 0.0);
+    }
+}
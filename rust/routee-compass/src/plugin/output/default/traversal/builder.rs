@@ -1,4 +1,7 @@
-use super::{plugin::TraversalPlugin, traversal_output_format::TraversalOutputFormat};
+use super::{
+    original_edges::OriginalEdges, plugin::TraversalPlugin, road_segments::RoadNames,
+    summary_units::SummaryUnits, traversal_output_format::TraversalOutputFormat,
+};
 use crate::{
     app::compass::{CompassConfigurationError, ConfigJsonExtensions},
     plugin::{
@@ -16,6 +19,19 @@ use std::sync::Arc;
 /// * `geometry_file` - the filename providing edge geometries
 /// * `route` (optional) - traversal output format for the route result
 /// * `tree` (optional) - traversal output format for the search tree result
+/// * `vertex_sequence` (optional) - if true, include the ordered vertex ids of each route
+/// * `trajectory` (optional) - if true, include the time each route enters and exits
+///   each edge, in seconds from midnight of the query `start_time` day, or from the
+///   trip start if the query has no `start_time`
+/// * `bbox` (optional) - if true, include the bounding box of each route geometry as
+///   `[west, south, east, north]`, which crosses the antimeridian when west > east
+/// * `road_name_input_file` (optional) - a file with the road name of each edge, one
+///   per line, used to summarize each route as consecutive named road segments
+/// * `original_edges_input_file` (optional) - for a graph compacted by the `compact-graph`
+///   app, the file listing the original edge ids of each edge, used to include the
+///   original edge ids of each route
+/// * `summary_units` (optional) - a list of distance, time or energy units for each
+///   state feature to also report in the `traversal_summary_units` of each route
 ///
 /// See [TraversalOutputFormat] for information on the output formats supported.
 ///
@@ -28,7 +44,12 @@ use std::sync::Arc;
 /// type = "traversal"
 /// route = "geo_json"
 /// tree = "geo_json"
+/// vertex_sequence = true
+/// trajectory = true
+/// bbox = true
+/// road_name_input_file = "edges-road-names.txt.gz"
 /// geometry_input_file = "edges-geometries-enumerated.txt.gz"
+/// summary_units = { energy_electric = ["kilowatt_hours", "megajoules"], distance = ["miles", "kilometers"] }
 /// ```
 ///
 pub struct TraversalPluginBuilder {}
@@ -47,8 +68,39 @@ impl OutputPluginBuilder for TraversalPluginBuilder {
             parameters.get_config_serde_optional(&"route", &parent_key)?;
         let tree: Option<TraversalOutputFormat> =
             parameters.get_config_serde_optional(&"tree", &parent_key)?;
+        let vertex_sequence: bool = parameters
+            .get_config_serde_optional(&"vertex_sequence", &parent_key)?
+            .unwrap_or_default();
+        let trajectory: bool = parameters
+            .get_config_serde_optional(&"trajectory", &parent_key)?
+            .unwrap_or_default();
+        let bbox: bool = parameters
+            .get_config_serde_optional(&"bbox", &parent_key)?
+            .unwrap_or_default();
+
+        let road_names = parameters
+            .get_config_path_optional(&"road_name_input_file", &parent_key)?
+            .map(|path| RoadNames::from_file(&path))
+            .transpose()
+            .map_err(|e| PluginError::OutputPluginFailed { source: e })?;
+        let original_edges = parameters
+            .get_config_path_optional(&"original_edges_input_file", &parent_key)?
+            .map(|path| OriginalEdges::from_file(&path))
+            .transpose()
+            .map_err(|e| PluginError::OutputPluginFailed { source: e })?;
+        let summary_units: SummaryUnits = parameters
+            .get_config_serde_optional(&"summary_units", &parent_key)?
+            .unwrap_or_default();
 
         let geom_plugin = TraversalPlugin::new(route, tree)
+            .map(|p| {
+                p.with_vertex_sequence(vertex_sequence)
+                    .with_trajectory(trajectory)
+                    .with_bbox(bbox)
+                    .with_road_names(road_names)
+                    .with_original_edges(original_edges)
+                    .with_summary_units(summary_units)
+            })
             .map_err(|e| PluginError::OutputPluginFailed { source: e })?;
         Ok(Arc::new(geom_plugin))
     }
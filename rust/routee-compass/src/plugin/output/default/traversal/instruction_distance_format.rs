@@ -0,0 +1,198 @@
+use routee_compass_core::model::unit::{AsF64, Distance, DistanceUnit};
+use serde::{Deserialize, Serialize};
+
+/// the system of units used when presenting distances in turn-by-turn instructions
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstructionUnits {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+/// a rounding rule applied to distances below some threshold. the distance
+/// is shown in `unit`, rounded to the nearest multiple of `step`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct RoundingBucket {
+    /// upper bound (exclusive) of this bucket, in `unit`. None for the last bucket.
+    pub below: Option<f64>,
+    pub unit: DistanceUnit,
+    pub step: f64,
+}
+
+/// formats the distance to the next maneuver of a turn-by-turn instruction,
+/// such as "in 300 meters" or "in about 0.2 mi".
+///
+/// # Example Configuration
+///
+/// ```toml
+/// units = "imperial"
+/// abbreviate = true
+/// approximate = true
+/// # optional, replaces the default rounding buckets of the unit system
+/// rounding = [
+///   { below = 0.1, unit = "miles", step = 0.01 },
+///   { unit = "miles", step = 0.5 },
+/// ]
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct InstructionDistanceFormat {
+    #[serde(default)]
+    pub units: InstructionUnits,
+    /// use unit symbols ("km") instead of unit names ("kilometers")
+    #[serde(default)]
+    pub abbreviate: bool,
+    /// prefix rounded distances with "about"
+    #[serde(default)]
+    pub approximate: bool,
+    /// rounding buckets in increasing order. the first bucket the distance
+    /// falls below is used, or the last bucket if none match.
+    #[serde(default)]
+    pub rounding: Option<Vec<RoundingBucket>>,
+}
+
+impl InstructionUnits {
+    /// default rounding buckets for this system of units
+    pub fn default_rounding(&self) -> Vec<RoundingBucket> {
+        use DistanceUnit as D;
+        let bucket = |below: Option<f64>, unit: D, step: f64| RoundingBucket { below, unit, step };
+        match self {
+            InstructionUnits::Metric => vec![
+                bucket(Some(100.0), D::Meters, 10.0),
+                bucket(Some(1000.0), D::Meters, 50.0),
+                bucket(Some(10.0), D::Kilometers, 0.1),
+                bucket(None, D::Kilometers, 1.0),
+            ],
+            InstructionUnits::Imperial => vec![
+                bucket(Some(528.0), D::Feet, 50.0),
+                bucket(Some(10.0), D::Miles, 0.1),
+                bucket(None, D::Miles, 1.0),
+            ],
+        }
+    }
+}
+
+impl InstructionDistanceFormat {
+    /// formats a distance for presentation in an instruction
+    ///
+    /// # Arguments
+    ///
+    /// * `distance` - distance to the next maneuver
+    /// * `distance_unit` - unit of `distance`
+    ///
+    /// # Returns
+    ///
+    /// the instruction distance text, such as "in about 500 m"
+    pub fn format(&self, distance: Distance, distance_unit: &DistanceUnit) -> String {
+        let rounding = self
+            .rounding
+            .clone()
+            .unwrap_or_else(|| self.units.default_rounding());
+        let bucket = rounding
+            .iter()
+            .find(|b| {
+                let value = distance_unit.convert(&distance, &b.unit).as_f64();
+                b.below.is_some_and(|below| value < below)
+            })
+            .or(rounding.last());
+        let (value, unit) = match bucket {
+            Some(b) => {
+                let value = distance_unit.convert(&distance, &b.unit).as_f64();
+                let rounded = if b.step > 0.0 {
+                    ((value / b.step).round() * b.step).max(b.step)
+                } else {
+                    value
+                };
+                (rounded, b.unit)
+            }
+            None => (distance.as_f64(), *distance_unit),
+        };
+        let text = format_value(value);
+        let label = unit_label(&unit, self.abbreviate, text == "1");
+        let about = if self.approximate { "about " } else { "" };
+        format!("in {}{} {}", about, text, label)
+    }
+}
+
+/// writes a value with as few decimal places as it needs, up to two
+fn format_value(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn unit_label(unit: &DistanceUnit, abbreviate: bool, singular: bool) -> &'static str {
+    use DistanceUnit as D;
+    match (unit, abbreviate, singular) {
+        (D::Meters, true, _) => "m",
+        (D::Kilometers, true, _) => "km",
+        (D::Miles, true, _) => "mi",
+        (D::Feet, true, _) => "ft",
+        (D::Inches, true, _) => "in",
+        (D::Meters, false, true) => "meter",
+        (D::Meters, false, false) => "meters",
+        (D::Kilometers, false, true) => "kilometer",
+        (D::Kilometers, false, false) => "kilometers",
+        (D::Miles, false, true) => "mile",
+        (D::Miles, false, false) => "miles",
+        (D::Feet, false, true) => "foot",
+        (D::Feet, false, false) => "feet",
+        (D::Inches, false, true) => "inch",
+        (D::Inches, false, false) => "inches",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metric_and_imperial() {
+        let metric = InstructionDistanceFormat::default();
+        let imperial = InstructionDistanceFormat {
+            units: InstructionUnits::Imperial,
+            ..Default::default()
+        };
+        let m = DistanceUnit::Meters;
+        let cases = [
+            (42.0, "in 40 meters", "in 150 feet"),
+            (120.0, "in 100 meters", "in 400 feet"),
+            (322.0, "in 300 meters", "in 0.2 miles"),
+            (1609.34, "in 1.6 kilometers", "in 1 mile"),
+            (25_400.0, "in 25 kilometers", "in 16 miles"),
+        ];
+        for (meters, expected_metric, expected_imperial) in cases {
+            let distance = Distance::new(meters);
+            assert_eq!(metric.format(distance, &m), expected_metric);
+            assert_eq!(imperial.format(distance, &m), expected_imperial);
+        }
+    }
+
+    #[test]
+    fn test_abbreviated_approximate() {
+        let format = InstructionDistanceFormat {
+            abbreviate: true,
+            approximate: true,
+            ..Default::default()
+        };
+        let distance = Distance::new(0.49);
+        assert_eq!(
+            format.format(distance, &DistanceUnit::Kilometers),
+            "in about 500 m"
+        );
+    }
+
+    #[test]
+    fn test_custom_rounding() {
+        let format: InstructionDistanceFormat = serde_json::from_value(serde_json::json!({
+            "units": "imperial",
+            "abbreviate": true,
+            "rounding": [{ "unit": "miles", "step": 0.5 }]
+        }))
+        .unwrap();
+        let distance = Distance::new(1.3);
+        assert_eq!(format.format(distance, &DistanceUnit::Miles), "in 1.5 mi");
+        // short distances round up to one step rather than to zero
+        let distance = Distance::new(0.1);
+        assert_eq!(format.format(distance, &DistanceUnit::Miles), "in 0.5 mi");
+    }
+}
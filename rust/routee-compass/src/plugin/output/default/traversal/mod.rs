@@ -1,10 +1,21 @@
 mod builder;
+mod instruction_distance_format;
 mod json_extensions;
+mod original_edges;
 mod plugin;
+mod road_segments;
+mod route_bbox;
+mod summary_units;
 pub mod traversal_ops;
 mod traversal_output_format;
 
 pub use builder::TraversalPluginBuilder;
+pub use instruction_distance_format::{
+    InstructionDistanceFormat, InstructionUnits, RoundingBucket,
+};
 pub use json_extensions::TraversalJsonExtensions;
+pub use original_edges::OriginalEdges;
 pub use plugin::TraversalPlugin;
+pub use road_segments::{RoadNames, UNNAMED_ROAD};
+pub use summary_units::{SummaryUnit, SummaryUnits};
 pub use traversal_output_format::TraversalOutputFormat;
@@ -0,0 +1,53 @@
+use crate::plugin::output::OutputPluginError;
+use kdam::Bar;
+use routee_compass_core::algorithm::search::EdgeTraversal;
+use routee_compass_core::model::network::EdgeId;
+use routee_compass_core::util::fs::read_utils::read_raw_file;
+use std::path::Path;
+
+/// for each edge of a compacted graph, the edges of the original graph that it
+/// replaces, read from a file with one line per edge in edge id order listing the
+/// original edge ids separated by spaces, as written by the `compact-graph` app.
+pub struct OriginalEdges(Box<[Vec<EdgeId>]>);
+
+impl OriginalEdges {
+    pub fn from_file<P: AsRef<Path>>(filename: &P) -> Result<OriginalEdges, OutputPluginError> {
+        let edges = read_raw_file(
+            filename,
+            |idx, row| {
+                row.split_whitespace()
+                    .map(|id| id.parse::<usize>().map(EdgeId))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            format!("invalid original edge id on line {}: {}", idx, e),
+                        )
+                    })
+            },
+            Some(Bar::builder().desc("original edges")),
+            None,
+        )
+        .map_err(|e| {
+            OutputPluginError::BuildFailed(format!(
+                "failure reading original edges file {}: {}",
+                filename.as_ref().to_str().unwrap_or_default(),
+                e
+            ))
+        })?;
+        Ok(OriginalEdges(edges))
+    }
+
+    /// the original edge ids of a route, in travel order
+    pub fn route_edges(&self, route: &[EdgeTraversal]) -> Result<Vec<EdgeId>, String> {
+        let mut edges = vec![];
+        for et in route.iter() {
+            let original = self
+                .0
+                .get(et.edge_id.0)
+                .ok_or_else(|| format!("original edges file missing edge id {}", et.edge_id))?;
+            edges.extend(original.iter());
+        }
+        Ok(edges)
+    }
+}
@@ -1,18 +1,32 @@
 use super::json_extensions::TraversalJsonField;
+use super::original_edges::OriginalEdges;
+use super::road_segments::RoadNames;
+use super::route_bbox;
+use super::summary_units::{self, SummaryUnits};
 use super::traversal_output_format::TraversalOutputFormat;
 use crate::app::compass::CompassAppError;
 use crate::app::search::SearchAppResult;
 use crate::plugin::output::output_plugin::OutputPlugin;
 use crate::plugin::output::OutputPluginError;
+use routee_compass_core::algorithm::search::util::vertex_sequence;
 use routee_compass_core::algorithm::search::EdgeTraversal;
 use routee_compass_core::algorithm::search::SearchInstance;
+use routee_compass_core::model::traversal::default::{parse_time_of_day, START_TIME};
+use routee_compass_core::model::unit::{AsF64, Cost, TimeUnit};
 use serde_json::json;
+use std::collections::HashMap;
 
 pub struct TraversalPlugin {
     route: Option<TraversalOutputFormat>,
     tree: Option<TraversalOutputFormat>,
     route_key: String,
     tree_key: String,
+    vertex_sequence: bool,
+    trajectory: bool,
+    bbox: bool,
+    road_names: Option<RoadNames>,
+    original_edges: Option<OriginalEdges>,
+    summary_units: SummaryUnits,
 }
 
 impl TraversalPlugin {
@@ -27,8 +41,53 @@ impl TraversalPlugin {
             tree,
             route_key,
             tree_key,
+            vertex_sequence: false,
+            trajectory: false,
+            bbox: false,
+            road_names: None,
+            original_edges: None,
+            summary_units: SummaryUnits::new(),
         })
     }
+
+    /// include the ordered vertex ids visited by each route in the route output
+    pub fn with_vertex_sequence(self, vertex_sequence: bool) -> TraversalPlugin {
+        TraversalPlugin {
+            vertex_sequence,
+            ..self
+        }
+    }
+
+    /// include the time each route enters and exits each edge in the route output
+    pub fn with_trajectory(self, trajectory: bool) -> TraversalPlugin {
+        TraversalPlugin { trajectory, ..self }
+    }
+
+    /// include the bounding box of each route geometry in the route output
+    pub fn with_bbox(self, bbox: bool) -> TraversalPlugin {
+        TraversalPlugin { bbox, ..self }
+    }
+
+    /// summarize each route as consecutive named road segments in the route output
+    pub fn with_road_names(self, road_names: Option<RoadNames>) -> TraversalPlugin {
+        TraversalPlugin { road_names, ..self }
+    }
+
+    /// include the original edge ids of each route on a compacted graph in the route output
+    pub fn with_original_edges(self, original_edges: Option<OriginalEdges>) -> TraversalPlugin {
+        TraversalPlugin {
+            original_edges,
+            ..self
+        }
+    }
+
+    /// report the final state of each route in additional units of its features
+    pub fn with_summary_units(self, summary_units: SummaryUnits) -> TraversalPlugin {
+        TraversalPlugin {
+            summary_units,
+            ..self
+        }
+    }
 }
 
 impl OutputPlugin for TraversalPlugin {
@@ -43,12 +102,29 @@ impl OutputPlugin for TraversalPlugin {
                 match self.route {
                     None => {}
                     Some(route_args) => {
+                        let trajectory_start = if self.trajectory {
+                            Some(trajectory_start_seconds(&output["request"])?)
+                        } else {
+                            None
+                        };
                         let routes_serialized = result
                             .routes
                             .iter()
-                            .map(|route| {
+                            .enumerate()
+                            .map(|(index, route)| {
                                 // construct_route_output(route, si, &route_args, &self.geoms)
-                                construct_route_output(route, si, &route_args)
+                                construct_route_output(
+                                    route,
+                                    si,
+                                    &route_args,
+                                    self.vertex_sequence,
+                                    trajectory_start,
+                                    self.bbox,
+                                    self.road_names.as_ref(),
+                                    self.original_edges.as_ref(),
+                                    &self.summary_units,
+                                    result.secondary_costs.get(index),
+                                )
                             })
                             .collect::<Result<Vec<_>, _>>()
                             .map_err(OutputPluginError::OutputPluginFailed)?;
@@ -89,13 +165,29 @@ impl OutputPlugin for TraversalPlugin {
             }
         }
     }
+
+    fn requires_geometry(&self) -> bool {
+        self.bbox
+            || [self.route, self.tree]
+                .iter()
+                .flatten()
+                .any(|format| format.requires_geometry())
+    }
 }
 
 /// creates the JSON output for a route.
+#[allow(clippy::too_many_arguments)]
 fn construct_route_output(
     route: &Vec<EdgeTraversal>,
     si: &SearchInstance,
     output_format: &TraversalOutputFormat,
+    include_vertex_sequence: bool,
+    trajectory_start: Option<f64>,
+    include_bbox: bool,
+    road_names: Option<&RoadNames>,
+    original_edges: Option<&OriginalEdges>,
+    summary_units: &SummaryUnits,
+    secondary_costs: Option<&HashMap<String, Cost>>,
 ) -> Result<serde_json::Value, String> {
     let last_edge = route
         .last()
@@ -105,20 +197,98 @@ fn construct_route_output(
         .map_err(|e| e.to_string())?;
     let traversal_summary = si.state_model.serialize_state(&last_edge.result_state);
     let state_model = si.state_model.serialize_state_model();
+    let route_cost = route
+        .iter()
+        .fold(Cost::ZERO, |acc, et| acc + et.total_cost());
     let cost = si
         .cost_model
-        .serialize_cost(&last_edge.result_state)
+        .serialize_cost(&last_edge.result_state, route_cost)
         .map_err(|e| e.to_string())?;
     let cost_model = si
         .cost_model
         .serialize_cost_info()
         .map_err(|e| e.to_string())?;
-    let result = serde_json::json![{
+    let mut result = serde_json::json![{
         "traversal_summary": traversal_summary,
         "state_model": state_model,
         "cost_model": cost_model,
         "cost": cost,
         "path": path_json
     }];
+    if !summary_units.is_empty() {
+        result["traversal_summary_units"] = summary_units::convert_summary(
+            summary_units,
+            &last_edge.result_state,
+            &si.state_model,
+        )?;
+    }
+    if include_vertex_sequence {
+        let edge_ids = route.iter().map(|e| e.edge_id).collect::<Vec<_>>();
+        let vertex_ids =
+            vertex_sequence::vertex_sequence(&edge_ids, &si.graph).map_err(|e| e.to_string())?;
+        result["vertex_sequence"] = json![vertex_ids];
+    }
+    if let Some(start_seconds) = trajectory_start {
+        result["trajectory"] = route_trajectory(route, si, start_seconds)?;
+    }
+    if include_bbox {
+        result["bbox"] = json![route_bbox::route_bbox(route, &si.map_model)?];
+    }
+    if let Some(road_names) = road_names {
+        result["road_segments"] = road_names.route_segments(route, si)?;
+    }
+    if let Some(original_edges) = original_edges {
+        result["original_edge_ids"] = json![original_edges.route_edges(route)?];
+    }
+    if let Some(costs) = secondary_costs.filter(|c| !c.is_empty()) {
+        result["secondary_costs"] = json![costs];
+    }
     Ok(result)
 }
+
+/// the time, in seconds, that trajectories are offset by. this is the query
+/// `start_time` in seconds since midnight, or zero if the query has none.
+fn trajectory_start_seconds(request: &serde_json::Value) -> Result<f64, OutputPluginError> {
+    match request.get(START_TIME) {
+        None => Ok(0.0),
+        Some(start_time) => start_time
+            .as_str()
+            .ok_or_else(|| {
+                OutputPluginError::OutputPluginFailed(format!(
+                    "query field '{}' must be a string, found {}",
+                    START_TIME, start_time
+                ))
+            })
+            .and_then(|s| {
+                parse_time_of_day(s)
+                    .map_err(|e| OutputPluginError::OutputPluginFailed(e.to_string()))
+            }),
+    }
+}
+
+/// creates the trajectory of a route, the time in seconds that it enters and
+/// exits each edge. times are read from the trip time in the state after each
+/// edge traversal, so they follow any time-dependent speeds used in the search.
+fn route_trajectory(
+    route: &[EdgeTraversal],
+    si: &SearchInstance,
+    start_seconds: f64,
+) -> Result<serde_json::Value, String> {
+    let time_feature = String::from("time");
+    let mut enter_time = start_seconds;
+    let mut trajectory = Vec::with_capacity(route.len());
+    for et in route.iter() {
+        let trip_time = si
+            .state_model
+            .get_time(&et.result_state, &time_feature, &TimeUnit::Seconds)
+            .map_err(|e| e.to_string())?;
+        let exit_time = start_seconds + trip_time.as_f64();
+        trajectory.push(json!({
+            "edge_id": et.edge_id,
+            "enter_time": enter_time,
+            "exit_time": exit_time
+        }));
+        enter_time = exit_time;
+    }
+    Ok(json![trajectory])
+}
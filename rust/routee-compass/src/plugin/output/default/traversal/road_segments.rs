@@ -0,0 +1,134 @@
+use crate::plugin::output::OutputPluginError;
+use kdam::Bar;
+use routee_compass_core::algorithm::search::{EdgeTraversal, SearchInstance};
+use routee_compass_core::model::network::EdgeId;
+use routee_compass_core::model::state::StateVariable;
+use routee_compass_core::model::unit::AsF64;
+use routee_compass_core::util::fs::read_utils::read_raw_file;
+use serde_json::json;
+use std::path::Path;
+
+/// name given to a segment of edges without a road name
+pub const UNNAMED_ROAD: &str = "unnamed road";
+
+/// the road name of each edge, read from a file with one name per line in edge
+/// id order. an empty line marks an edge without a name.
+pub struct RoadNames(Box<[String]>);
+
+impl RoadNames {
+    pub fn from_file<P: AsRef<Path>>(filename: &P) -> Result<RoadNames, OutputPluginError> {
+        let names = read_raw_file(
+            filename,
+            |_idx, row| Ok(row.trim().to_string()),
+            Some(Bar::builder().desc("road names")),
+            None,
+        )
+        .map_err(|e| {
+            OutputPluginError::BuildFailed(format!(
+                "failure reading road name file {}: {}",
+                filename.as_ref().to_str().unwrap_or_default(),
+                e
+            ))
+        })?;
+        Ok(RoadNames(names))
+    }
+
+    /// the name of an edge, or [`UNNAMED_ROAD`] if it has none
+    pub fn get(&self, edge_id: &EdgeId) -> Result<&str, String> {
+        match self.0.get(edge_id.0) {
+            None => Err(format!("road name file missing edge id {}", edge_id)),
+            Some(name) if name.is_empty() => Ok(UNNAMED_ROAD),
+            Some(name) => Ok(name.as_str()),
+        }
+    }
+
+    /// summarizes a route as consecutive named road segments, such as "US-36 for
+    /// 4.2 miles, then CO-93 for 1.1 miles". consecutive edges sharing a name are
+    /// merged into one segment. distances are read from the `distance` feature of
+    /// the state model, so the segment distances sum to the route distance.
+    ///
+    /// # Returns
+    ///
+    /// a JSON array of segments, each with a `name`, `distance`, `distance_unit`
+    /// and `edge_count`
+    pub fn route_segments(
+        &self,
+        route: &[EdgeTraversal],
+        si: &SearchInstance,
+    ) -> Result<serde_json::Value, String> {
+        let distance_feature = String::from("distance");
+        let distance_unit = si
+            .state_model
+            .iter()
+            .find(|(name, _)| **name == distance_feature)
+            .map(|(_, feature)| feature.get_distance_unit())
+            .ok_or_else(|| String::from("road segments require a 'distance' state feature"))?
+            .map_err(|e| e.to_string())?;
+        let distance_at = |state: &[StateVariable]| {
+            si.state_model
+                .get_distance(state, &distance_feature, &distance_unit)
+                .map(|d| d.as_f64())
+                .map_err(|e| e.to_string())
+        };
+        let initial_state = si.state_model.initial_state().map_err(|e| e.to_string())?;
+        let mut last_distance = distance_at(&initial_state)?;
+        let mut edges = Vec::with_capacity(route.len());
+        for et in route.iter() {
+            let distance = distance_at(&et.result_state)?;
+            edges.push((self.get(&et.edge_id)?, distance - last_distance));
+            last_distance = distance;
+        }
+        let segments = merge_segments(edges)
+            .into_iter()
+            .map(|(name, distance, edge_count)| {
+                json!({
+                    "name": name,
+                    "distance": distance,
+                    "distance_unit": distance_unit,
+                    "edge_count": edge_count
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(json!(segments))
+    }
+}
+
+/// merges consecutive edges sharing a name, summing their distances and
+/// counting their edges
+fn merge_segments(edges: Vec<(&str, f64)>) -> Vec<(&str, f64, usize)> {
+    let mut segments: Vec<(&str, f64, usize)> = vec![];
+    for (name, distance) in edges {
+        match segments.last_mut() {
+            Some((last_name, last_distance, count)) if *last_name == name => {
+                *last_distance += distance;
+                *count += 1;
+            }
+            _ => segments.push((name, distance, 1)),
+        }
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_segments() {
+        let edges = vec![
+            ("US-36", 2.0),
+            ("US-36", 2.2),
+            (UNNAMED_ROAD, 0.1),
+            (UNNAMED_ROAD, 0.2),
+            ("CO-93", 1.1),
+            ("US-36", 0.5),
+        ];
+        let segments = merge_segments(edges);
+        let names = segments.iter().map(|(n, _, _)| *n).collect::<Vec<_>>();
+        assert_eq!(names, vec!["US-36", UNNAMED_ROAD, "CO-93", "US-36"]);
+        let counts = segments.iter().map(|(_, _, c)| *c).collect::<Vec<_>>();
+        assert_eq!(counts, vec![2, 2, 1, 1]);
+        assert!((segments[0].1 - 4.2).abs() < 1e-9);
+        assert!((segments[1].1 - 0.3).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,85 @@
+use geo::{Coord, CoordsIter};
+use routee_compass_core::algorithm::search::EdgeTraversal;
+use routee_compass_core::model::map::MapModel;
+
+/// computes the bounding box of a route from the geometries of its edges, as
+/// `[west, south, east, north]` in degrees, the GeoJSON `bbox` order.
+pub fn route_bbox(route: &[EdgeTraversal], map_model: &MapModel) -> Result<[f64; 4], String> {
+    let mut coords = vec![];
+    for et in route.iter() {
+        let linestring = map_model
+            .get(&et.edge_id)
+            .map_err(|e| format!("failure building route bbox: {}", e))?;
+        coords.extend(linestring.coords_iter());
+    }
+    coordinate_bbox(&coords)
+        .ok_or_else(|| String::from("cannot build bbox of a route without coordinates"))
+}
+
+/// finds the bounding box of some coordinates, as `[west, south, east, north]`.
+/// when the coordinates fit in a narrower box by wrapping across the antimeridian,
+/// that box is returned, with a west edge greater than its east edge as in RFC 7946.
+fn coordinate_bbox(coords: &[Coord<f32>]) -> Option<[f64; 4]> {
+    let (first, rest) = coords.split_first()?;
+    let init = |x: f64| (x, x);
+    let extend = |(min, max): (f64, f64), x: f64| (min.min(x), max.max(x));
+    let (mut lon, mut wrapped_lon, mut lat) = (
+        init(first.x as f64),
+        init((first.x as f64).rem_euclid(360.0)),
+        init(first.y as f64),
+    );
+    for coord in rest {
+        lon = extend(lon, coord.x as f64);
+        wrapped_lon = extend(wrapped_lon, (coord.x as f64).rem_euclid(360.0));
+        lat = extend(lat, coord.y as f64);
+    }
+    let (west, east) = if wrapped_lon.1 - wrapped_lon.0 < lon.1 - lon.0 {
+        let unwrap = |x: f64| if x > 180.0 { x - 360.0 } else { x };
+        (unwrap(wrapped_lon.0), unwrap(wrapped_lon.1))
+    } else {
+        lon
+    };
+    Some([west, lat.0, east, lat.1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::coord;
+
+    #[test]
+    fn test_bbox_contains_coordinates() {
+        let coords = [
+            coord! {x: -105.2, y: 39.7},
+            coord! {x: -105.1, y: 39.9},
+            coord! {x: -104.9, y: 39.8},
+        ];
+        let [west, south, east, north] = coordinate_bbox(&coords).unwrap();
+        for c in coords.iter() {
+            assert!(west <= c.x as f64 && c.x as f64 <= east);
+            assert!(south <= c.y as f64 && c.y as f64 <= north);
+        }
+        // tight: every edge of the box touches a coordinate
+        assert_eq!(west, coords[0].x as f64);
+        assert_eq!(east, coords[2].x as f64);
+        assert_eq!(south, coords[0].y as f64);
+        assert_eq!(north, coords[1].y as f64);
+        assert_eq!(coordinate_bbox(&[]), None);
+    }
+
+    #[test]
+    fn test_bbox_across_antimeridian() {
+        // a route in fiji heading east across 180 degrees
+        let coords = [
+            coord! {x: 179.5, y: -16.8},
+            coord! {x: 179.9, y: -16.7},
+            coord! {x: -179.8, y: -16.6},
+        ];
+        let [west, south, east, north] = coordinate_bbox(&coords).unwrap();
+        assert_eq!(west, coords[0].x as f64);
+        assert!((east - coords[2].x as f64).abs() < 1e-4);
+        assert!(west > east);
+        assert_eq!(south, coords[0].y as f64);
+        assert_eq!(north, coords[2].y as f64);
+    }
+}
@@ -0,0 +1,148 @@
+use routee_compass_core::model::state::{StateFeature, StateModel, StateVariable};
+use routee_compass_core::model::unit::{AsF64, DistanceUnit, EnergyUnit, TimeUnit};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+
+/// a unit to report a distance, time or energy state feature in. the unit names
+/// of each kind are distinct, so the kind is inferred from the name.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum SummaryUnit {
+    Distance(DistanceUnit),
+    Time(TimeUnit),
+    Energy(EnergyUnit),
+}
+
+impl std::fmt::Display for SummaryUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SummaryUnit::Distance(unit) => write!(f, "{}", unit),
+            SummaryUnit::Time(unit) => write!(f, "{}", unit),
+            SummaryUnit::Energy(unit) => write!(f, "{}", unit),
+        }
+    }
+}
+
+/// the additional units to report state features in, by feature name
+pub type SummaryUnits = HashMap<String, Vec<SummaryUnit>>;
+
+/// expresses the final state of a route in each of the configured units of its
+/// features, as an object keyed by feature name and then unit name, such as
+/// `{"energy_electric": {"kilowatt_hours": 2.5, "megajoules": 9.0}}`. features
+/// configured but not in the state model of the search are skipped.
+///
+/// # Arguments
+///
+/// * `summary_units` - the units to report each feature in
+/// * `state` - the state at the end of the route
+/// * `state_model` - the state model of the search
+///
+/// # Returns
+///
+/// the converted values, or an error if a unit does not match the kind of its
+/// feature
+pub fn convert_summary(
+    summary_units: &SummaryUnits,
+    state: &[StateVariable],
+    state_model: &StateModel,
+) -> Result<serde_json::Value, String> {
+    let mut result = serde_json::Map::new();
+    for (name, feature) in state_model.iter() {
+        let Some(units) = summary_units.get(name) else {
+            continue;
+        };
+        let mut converted = serde_json::Map::new();
+        for unit in units.iter() {
+            let value = match (feature, unit) {
+                (StateFeature::Distance { .. }, SummaryUnit::Distance(u)) => {
+                    state_model.get_distance(state, name, u).map(|v| v.as_f64())
+                }
+                (StateFeature::Time { .. }, SummaryUnit::Time(u)) => {
+                    state_model.get_time(state, name, u).map(|v| v.as_f64())
+                }
+                (StateFeature::Energy { .. }, SummaryUnit::Energy(u)) => {
+                    state_model.get_energy(state, name, u).map(|v| v.as_f64())
+                }
+                _ => {
+                    return Err(format!(
+                        "summary unit '{}' cannot be used for state feature '{}': {}",
+                        unit, name, feature
+                    ))
+                }
+            }
+            .map_err(|e| e.to_string())?;
+            converted.insert(unit.to_string(), json![value]);
+        }
+        result.insert(name.clone(), serde_json::Value::Object(converted));
+    }
+    Ok(serde_json::Value::Object(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use routee_compass_core::model::unit::{Distance, Energy, Time};
+
+    fn state_model() -> StateModel {
+        StateModel::empty()
+            .extend(vec![
+                (
+                    String::from("distance"),
+                    StateFeature::Distance {
+                        distance_unit: DistanceUnit::Kilometers,
+                        initial: Distance::new(0.0),
+                    },
+                ),
+                (
+                    String::from("time"),
+                    StateFeature::Time {
+                        time_unit: TimeUnit::Minutes,
+                        initial: Time::new(0.0),
+                    },
+                ),
+                (
+                    String::from("energy_electric"),
+                    StateFeature::Energy {
+                        energy_unit: EnergyUnit::KilowattHours,
+                        initial: Energy::new(0.0),
+                    },
+                ),
+            ])
+            .unwrap()
+    }
+
+    #[test]
+    fn test_energy_in_two_units() {
+        let state_model = state_model();
+        let state = vec![
+            StateVariable(16.09344),
+            StateVariable(12.0),
+            StateVariable(2.5),
+        ];
+        let summary_units: SummaryUnits = serde_json::from_value(json!({
+            "energy_electric": ["kilowatt_hours", "megajoules"],
+            "distance": ["miles", "kilometers"]
+        }))
+        .unwrap();
+        let result = convert_summary(&summary_units, &state, &state_model).unwrap();
+        let value = |name: &str, unit: &str| result[name][unit].as_f64().unwrap();
+        assert!((value("energy_electric", "kilowatt_hours") - 2.5).abs() < 1e-9);
+        assert!((value("energy_electric", "megajoules") - 9.0).abs() < 1e-9);
+        assert!((value("distance", "kilometers") - 16.09344).abs() < 1e-9);
+        let miles = DistanceUnit::Kilometers
+            .convert(&Distance::new(16.09344), &DistanceUnit::Miles)
+            .as_f64();
+        assert!((value("distance", "miles") - miles).abs() < 1e-9);
+        // features without configured units are not reported
+        assert!(result.get("time").is_none());
+    }
+
+    #[test]
+    fn test_unit_must_match_feature() {
+        let state = vec![StateVariable(1.0), StateVariable(1.0), StateVariable(1.0)];
+        let summary_units: SummaryUnits =
+            serde_json::from_value(json!({ "time": ["megajoules"] })).unwrap();
+        assert!(convert_summary(&summary_units, &state, &state_model()).is_err());
+    }
+}
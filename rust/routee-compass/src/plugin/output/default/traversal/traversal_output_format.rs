@@ -25,6 +25,16 @@ pub enum TraversalOutputFormat {
 }
 
 impl TraversalOutputFormat {
+    /// true if this format is built from edge geometries
+    pub fn requires_geometry(&self) -> bool {
+        match self {
+            TraversalOutputFormat::Wkt
+            | TraversalOutputFormat::Wkb
+            | TraversalOutputFormat::GeoJson => true,
+            TraversalOutputFormat::Json | TraversalOutputFormat::EdgeId => false,
+        }
+    }
+
     /// generates output for a route based on the configured TraversalOutputFormat
     pub fn generate_route_output(
         &self,
@@ -1,9 +1,11 @@
 pub mod default;
+pub mod no_route_fallback;
 mod output_plugin;
 mod output_plugin_builder;
 mod output_plugin_error;
 pub mod output_plugin_ops;
 
+pub use output_plugin::NamedOutputPlugin;
 pub use output_plugin::OutputPlugin;
 pub use output_plugin_builder::OutputPluginBuilder;
 pub use output_plugin_error::OutputPluginError;
@@ -0,0 +1,162 @@
+use crate::app::search::SearchApp;
+use crate::plugin::input::InputJsonExtensions;
+use routee_compass_core::model::unit::{
+    AsF64, Distance, DistanceUnit, Speed, SpeedUnit, Time, TimeUnit,
+};
+use routee_compass_core::util::geo::haversine;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// query key that opts in to an estimated result when no route exists
+pub const NO_ROUTE_FALLBACK: &str = "no_route_fallback";
+
+/// speed of the estimated trip when the query does not set one
+pub const DEFAULT_FALLBACK_SPEED_KPH: f64 = 40.0;
+
+/// the speed used to estimate the time of a trip without a route, set in the
+/// query as `"no_route_fallback": true` or as an object with these fields
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NoRouteFallback {
+    #[serde(default = "default_speed")]
+    speed: f64,
+    #[serde(default = "default_speed_unit")]
+    speed_unit: SpeedUnit,
+}
+
+fn default_speed() -> f64 {
+    DEFAULT_FALLBACK_SPEED_KPH
+}
+
+fn default_speed_unit() -> SpeedUnit {
+    SpeedUnit::KilometersPerHour
+}
+
+/// estimates a trip for a query without a route as the great-circle distance
+/// between its origin and destination, traveled at the fallback speed. the
+/// response replaces the error with an `estimated` route so that aggregations
+/// over a batch of queries do not break. it is marked `"estimated": true`,
+/// keeps the `no_route` cause and error message, and has a `traversal_summary`
+/// with only the `distance` and `time`, in the units of the `distance` and `time`
+/// state features when they exist, otherwise kilometers and hours.
+///
+/// the origin and destination are read from the query coordinates, or else from
+/// the vertices or edges of the query.
+///
+/// # Arguments
+///
+/// * `req` - the query, which opts in with the `no_route_fallback` key
+/// * `error` - the message of the error of the search
+/// * `cause` - the reason no route was found
+/// * `app` - the search app, for the graph and state model
+///
+/// # Returns
+///
+/// the estimated response, None if the query did not opt in, or an error if
+/// the option is invalid or the origin or destination cannot be located
+pub fn estimate_response(
+    req: &Value,
+    error: &str,
+    cause: &Value,
+    app: &SearchApp,
+) -> Result<Option<Value>, String> {
+    let fallback = match req.get(NO_ROUTE_FALLBACK) {
+        None | Some(Value::Bool(false)) | Some(Value::Null) => return Ok(None),
+        Some(Value::Bool(true)) => NoRouteFallback {
+            speed: default_speed(),
+            speed_unit: default_speed_unit(),
+        },
+        Some(value) => serde_json::from_value::<NoRouteFallback>(value.clone()).map_err(|e| {
+            format!(
+                "{} must be true or an object with a speed and speed_unit: {}",
+                NO_ROUTE_FALLBACK, e
+            )
+        })?,
+    };
+    if fallback.speed <= 0.0 {
+        return Err(format!(
+            "{} speed must be positive, found {}",
+            NO_ROUTE_FALLBACK, fallback.speed
+        ));
+    }
+
+    let (origin, destination) = endpoints(req, app)?;
+    let distance_meters = haversine::coord_distance_meters(&origin, &destination)?;
+    let speed_mps = fallback
+        .speed_unit
+        .convert(&Speed::new(fallback.speed), &SpeedUnit::MetersPerSecond);
+    let seconds = Time::new(distance_meters.as_f64() / speed_mps.as_f64());
+
+    let feature_unit = |name: &str| {
+        app.state_model
+            .iter()
+            .find(|(n, _)| n.as_str() == name)
+            .map(|(_, feature)| feature)
+    };
+    let distance_unit = feature_unit("distance")
+        .and_then(|f| f.get_distance_unit().ok())
+        .unwrap_or(DistanceUnit::Kilometers);
+    let time_unit = feature_unit("time")
+        .and_then(|f| f.get_time_unit().ok())
+        .unwrap_or(TimeUnit::Hours);
+    let distance: Distance = DistanceUnit::Meters.convert(&distance_meters, &distance_unit);
+    let time = TimeUnit::Seconds.convert(&seconds, &time_unit);
+
+    Ok(Some(json!({
+        "request": req,
+        "estimated": true,
+        "no_route": { "cause": cause, "error": error },
+        "route": {
+            "estimated": true,
+            "traversal_summary": {
+                "distance": distance.as_f64(),
+                "time": time.as_f64(),
+            },
+            "distance_unit": distance_unit,
+            "time_unit": time_unit,
+        }
+    })))
+}
+
+/// the coordinates of the origin and destination of a query
+fn endpoints(req: &Value, app: &SearchApp) -> Result<(geo::Coord<f32>, geo::Coord<f32>), String> {
+    let vertex_coord = |vertex_id| {
+        app.graph
+            .get_vertex(&vertex_id)
+            .map(|v| geo::Coord::from((v.x(), v.y())))
+            .map_err(|e| e.to_string())
+    };
+    let origin = match req.get_origin_coordinate() {
+        Ok(coord) => coord,
+        Err(_) => match req.get_origin_vertex() {
+            Ok(vertex_id) => vertex_coord(vertex_id)?,
+            Err(_) => {
+                let edge_id = req.get_origin_edge().map_err(|e| e.to_string())?;
+                vertex_coord(
+                    app.graph
+                        .src_vertex_id(&edge_id)
+                        .map_err(|e| e.to_string())?,
+                )?
+            }
+        },
+    };
+    let destination = match req.get_destination_coordinate() {
+        Ok(Some(coord)) => coord,
+        _ => match req.get_destination_vertex() {
+            Ok(Some(vertex_id)) => vertex_coord(vertex_id)?,
+            _ => match req.get_destination_edge() {
+                Ok(Some(edge_id)) => vertex_coord(
+                    app.graph
+                        .dst_vertex_id(&edge_id)
+                        .map_err(|e| e.to_string())?,
+                )?,
+                _ => {
+                    return Err(String::from(
+                        "query has no destination to estimate a trip to",
+                    ))
+                }
+            },
+        },
+    };
+    Ok((origin, destination))
+}
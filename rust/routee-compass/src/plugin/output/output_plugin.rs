@@ -2,6 +2,10 @@ use super::output_plugin_error::OutputPluginError;
 use crate::app::compass::CompassAppError;
 use crate::app::search::SearchAppResult;
 use routee_compass_core::algorithm::search::SearchInstance;
+use std::sync::Arc;
+
+/// an [`OutputPlugin`] along with the name used to select it in a query
+pub type NamedOutputPlugin = (String, Arc<dyn OutputPlugin>);
 
 /// Performs some kind of post-processing on a search result. The result JSON is available
 /// to the plugin as a reference which was potentially modified upstream by another output
@@ -13,10 +17,14 @@ use routee_compass_core::algorithm::search::SearchInstance;
 ///
 /// The following default set of output plugin builders are found in the [`super::default`] module:
 ///
+/// * [charging] - plans the charging stops of an electric vehicle route
+/// * [query_hash] - appends a stable hash of the effective query, for caching results
 /// * [summary] - simple plugin appends cost and distance to result
 /// * [traversal] - fully-featured plugin for traversal outputs in different formats
 /// * [uuid] - attach the original graph ids to a result
 ///
+/// [charging]: super::default::charging::ChargingOutputPluginBuilder
+/// [query_hash]: super::default::query_hash::QueryHashOutputPluginBuilder
 /// [summary]: super::default::summary::builder::SummaryOutputPluginBuilder
 /// [traversal]: super::default::traversal::builder::TraversalPluginBuilder
 /// [uuid]: super::default::uuid::builder::UUIDOutputPluginBuilder
@@ -40,4 +48,10 @@ pub trait OutputPlugin: Send + Sync {
         output: &mut serde_json::Value,
         result: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
     ) -> Result<(), OutputPluginError>;
+
+    /// true if this plugin reads edge geometries from the map model, in which case
+    /// it cannot be used when the map model is configured without geometries.
+    fn requires_geometry(&self) -> bool {
+        false
+    }
 }
@@ -1,19 +1,35 @@
+use super::{no_route_fallback, NamedOutputPlugin, OutputPlugin, OutputPluginError};
 use crate::app::{
     compass::CompassAppError,
     search::{SearchApp, SearchAppResult},
 };
-use routee_compass_core::algorithm::search::SearchInstance;
+use itertools::Itertools;
+use routee_compass_core::algorithm::search::{SearchError, SearchInstance};
 use serde_json::{json, Value};
+use std::sync::Arc;
 
 /// creates the initial output with summary information from the search app,
-/// which happens regardless of the output plugin setup.
+/// which happens regardless of the output plugin setup. a failed search returns
+/// the response to send in place of running the output plugins, which is an
+/// estimated trip when no route exists and the query opts in to the
+/// [`no_route_fallback`], and otherwise the error.
 pub fn create_initial_output(
     req: &Value,
     res: &Result<(SearchAppResult, SearchInstance), CompassAppError>,
-    _app: &SearchApp,
+    app: &SearchApp,
 ) -> Result<Value, Value> {
     match &res {
-        Err(e) => Err(package_error(req, e)),
+        Err(e) => match e.no_route_cause() {
+            Some(cause) => {
+                match no_route_fallback::estimate_response(req, &e.to_string(), &json!(cause), app)
+                {
+                    Ok(Some(estimated)) => Err(estimated),
+                    Ok(None) => Err(package_search_error(req, e)),
+                    Err(msg) => Err(package_error(req, msg)),
+                }
+            }
+            None => Err(package_search_error(req, e)),
+        },
         Ok((_, _)) => {
             let mut init_output = serde_json::json!({
                 "request": req,
@@ -28,6 +44,76 @@ pub fn create_initial_output(
     }
 }
 
+/// query key listing the names of the output plugins to run for a query
+pub const OUTPUT_PLUGINS: &str = "output_plugins";
+
+/// selects the output plugins to run for a query. if the query lists plugin
+/// names under the `output_plugins` key, only the configured plugins with those
+/// names are run, in their configured order. otherwise, all plugins are run.
+///
+/// # Arguments
+///
+/// * `req` - the query, which may select a subset of output plugins
+/// * `output_plugins` - the configured output plugins along with their names
+///
+/// # Returns
+///
+/// the plugins to run, or an error if the query names an unknown plugin
+pub fn select_output_plugins<'a>(
+    req: &Value,
+    output_plugins: &'a [NamedOutputPlugin],
+) -> Result<Vec<&'a Arc<dyn OutputPlugin>>, OutputPluginError> {
+    let selected: Vec<String> = match req.get(OUTPUT_PLUGINS) {
+        None => return Ok(output_plugins.iter().map(|(_, p)| p).collect()),
+        Some(value) => serde_json::from_value(value.clone()).map_err(|e| {
+            OutputPluginError::OutputPluginFailed(format!(
+                "expected '{}' to be a list of output plugin names: {}",
+                OUTPUT_PLUGINS, e
+            ))
+        })?,
+    };
+    if let Some(unknown) = selected
+        .iter()
+        .find(|name| !output_plugins.iter().any(|(n, _)| n == *name))
+    {
+        return Err(OutputPluginError::OutputPluginFailed(format!(
+            "unknown output plugin '{}', should be one of: {{{}}}",
+            unknown,
+            output_plugins.iter().map(|(n, _)| n).join(",")
+        )));
+    }
+    let plugins = output_plugins
+        .iter()
+        .filter(|(name, _)| selected.contains(name))
+        .map(|(_, p)| p)
+        .collect();
+    Ok(plugins)
+}
+
+/// helper to return errors from running a search as JSON response objects. if
+/// the search ended without a route, a `no_route` object is included which
+/// reports the cause as one of `disconnected`, `restricted`, `budget_exceeded`,
+/// `termination_limit`, `timeout`, `detour_exceeded`, `resource_budget_exceeded`
+/// or `off_network`.
+/// a route over a resource budget also reports the minimum achievable resource.
+pub fn package_search_error(req: &Value, error: &CompassAppError) -> Value {
+    let mut response = package_error(req, error);
+    if let Some(cause) = error.no_route_cause() {
+        response["no_route"] = json!({ "cause": cause });
+    }
+    if let CompassAppError::SearchFailure(SearchError::ResourceBudgetExceeded {
+        feature,
+        budget,
+        minimum,
+    }) = error
+    {
+        response["no_route"]["feature"] = json!(feature);
+        response["no_route"]["budget"] = json!(budget);
+        response["no_route"]["minimum"] = json!(minimum);
+    }
+    response
+}
+
 /// helper to return errors as JSON response objects which include the
 /// original request along with the error message
 pub fn package_error<E: ToString>(req: &Value, error: E) -> Value {
@@ -36,3 +122,46 @@ pub fn package_error<E: ToString>(req: &Value, error: E) -> Value {
         "error": error.to_string()
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use routee_compass_core::model::network::{EdgeId, VertexId};
+
+    #[test]
+    fn test_package_search_error_no_route() {
+        let req = json!({"origin_edge": 0, "destination_edge": 1});
+        let disconnected = CompassAppError::SearchFailure(SearchError::NoPathExistsBetweenEdges(
+            EdgeId(0),
+            EdgeId(1),
+        ));
+        let restricted = CompassAppError::SearchFailure(
+            SearchError::NoPathExistsDueToRestrictions(VertexId(0), VertexId(1)),
+        );
+        let other = CompassAppError::InternalError(String::from("oops"));
+        assert_eq!(
+            package_search_error(&req, &disconnected)["no_route"]["cause"],
+            json!("disconnected")
+        );
+        assert_eq!(
+            package_search_error(&req, &restricted)["no_route"]["cause"],
+            json!("restricted")
+        );
+        assert!(package_search_error(&req, &other).get("no_route").is_none());
+    }
+
+    #[test]
+    fn test_package_search_error_resource_budget() {
+        let req = json!({"origin_vertex": 0, "destination_vertex": 3, "resource_budget": 0.5});
+        let error = CompassAppError::SearchFailure(SearchError::ResourceBudgetExceeded {
+            feature: String::from("co2"),
+            budget: 0.5,
+            minimum: 1.25,
+        });
+        let no_route = &package_search_error(&req, &error)["no_route"];
+        assert_eq!(no_route["cause"], json!("resource_budget_exceeded"));
+        assert_eq!(no_route["feature"], json!("co2"));
+        assert_eq!(no_route["budget"], json!(0.5));
+        assert_eq!(no_route["minimum"], json!(1.25));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod replay_trace;
+
+pub use replay_trace::{ReplayDifference, ReplayTrace};
@@ -0,0 +1,259 @@
+use crate::app::compass::{
+    compass_app::CompassApp, compass_app_fingerprint::CompassAppFingerprint, CompassAppError,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// response fields that vary between runs of the same query, which are left out
+/// of a captured result
+pub const VOLATILE_FIELDS: [&str; 4] = [
+    "search_executed_time",
+    "search_runtime",
+    "output_plugin_executed_time",
+    "search_result_size_mib",
+];
+
+/// a query captured together with its inputs and its result, which can be
+/// replayed later to confirm the result is unchanged. captured traces of
+/// production incidents become permanent regression tests.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReplayTrace {
+    /// the query as submitted
+    pub query: serde_json::Value,
+    /// the query after input plugins were applied, as it was searched
+    pub effective_query: serde_json::Value,
+    /// fingerprints of the graph and configuration the query ran against
+    pub fingerprint: Option<CompassAppFingerprint>,
+    /// the configuration of the app, before file paths were made absolute
+    pub config: serde_json::Value,
+    /// the response, without the effective query, fingerprint or volatile fields
+    pub result: serde_json::Value,
+}
+
+/// a value of a replayed trace that differs from the captured one
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplayDifference {
+    /// location of the value, such as `result.route.path[1]`
+    pub path: String,
+    pub expected: serde_json::Value,
+    pub actual: serde_json::Value,
+}
+
+impl ReplayTrace {
+    /// runs a query and captures it along with its inputs and result.
+    ///
+    /// # Arguments
+    ///
+    /// * `app` - the app to run the query with
+    /// * `query` - the query to capture
+    ///
+    /// # Returns
+    ///
+    /// the captured trace, or an error if the app fails to respond
+    pub fn capture(
+        app: &CompassApp,
+        query: &serde_json::Value,
+    ) -> Result<ReplayTrace, CompassAppError> {
+        let (effective_query, result) = run_single(app, query)?;
+        Ok(ReplayTrace {
+            query: query.clone(),
+            effective_query,
+            fingerprint: app.search_app.fingerprint.clone(),
+            config: app.config.clone(),
+            result,
+        })
+    }
+
+    /// re-runs the captured query and compares the effective query, the
+    /// fingerprints and the result with those captured.
+    ///
+    /// # Returns
+    ///
+    /// each value that changed, which is empty when the replay matches the capture
+    pub fn replay(&self, app: &CompassApp) -> Result<Vec<ReplayDifference>, CompassAppError> {
+        let replayed = ReplayTrace::capture(app, &self.query)?;
+        let mut differences = vec![];
+        diff_json(
+            "fingerprint",
+            &serde_json::json!(self.fingerprint),
+            &serde_json::json!(replayed.fingerprint),
+            &mut differences,
+        );
+        diff_json(
+            "effective_query",
+            &self.effective_query,
+            &replayed.effective_query,
+            &mut differences,
+        );
+        diff_json("result", &self.result, &replayed.result, &mut differences);
+        Ok(differences)
+    }
+
+    /// writes this trace to a JSON file
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), CompassAppError> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| CompassAppError::InternalError(e.to_string()))?;
+        std::fs::write(path.as_ref(), contents).map_err(|e| {
+            CompassAppError::CompassFailure(format!(
+                "unable to write replay trace to {}: {}",
+                path.as_ref().to_string_lossy(),
+                e
+            ))
+        })
+    }
+
+    /// reads a trace from a JSON file written by [`ReplayTrace::write`]
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<ReplayTrace, CompassAppError> {
+        let contents = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+            CompassAppError::CompassFailure(format!(
+                "unable to read replay trace from {}: {}",
+                path.as_ref().to_string_lossy(),
+                e
+            ))
+        })?;
+        serde_json::from_str(&contents).map_err(|e| {
+            CompassAppError::CompassFailure(format!(
+                "unable to parse replay trace from {}: {}",
+                path.as_ref().to_string_lossy(),
+                e
+            ))
+        })
+    }
+}
+
+/// runs one query, splitting its response into the effective query and the
+/// remaining result without volatile fields
+fn run_single(
+    app: &CompassApp,
+    query: &serde_json::Value,
+) -> Result<(serde_json::Value, serde_json::Value), CompassAppError> {
+    let mut responses = app.run(&mut [query.clone()], None)?;
+    if responses.len() != 1 {
+        return Err(CompassAppError::CompassFailure(format!(
+            "expected a single response to capture, found {}",
+            responses.len()
+        )));
+    }
+    let mut result = responses.remove(0);
+    let obj = match result.as_object_mut() {
+        Some(obj) => obj,
+        None => {
+            return Err(CompassAppError::InternalError(format!(
+                "response is not a JSON object: {}",
+                result
+            )))
+        }
+    };
+    let effective_query = obj.remove("request").unwrap_or(serde_json::Value::Null);
+    obj.remove("fingerprint");
+    for field in VOLATILE_FIELDS {
+        obj.remove(field);
+    }
+    Ok((effective_query, result))
+}
+
+/// collects the paths at which two JSON values differ
+fn diff_json(
+    path: &str,
+    expected: &serde_json::Value,
+    actual: &serde_json::Value,
+    differences: &mut Vec<ReplayDifference>,
+) {
+    use serde_json::Value;
+    match (expected, actual) {
+        (Value::Object(e), Value::Object(a)) => {
+            let mut keys: Vec<&String> = e.keys().chain(a.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = format!("{}.{}", path, key);
+                let e_value = e.get(key).unwrap_or(&Value::Null);
+                let a_value = a.get(key).unwrap_or(&Value::Null);
+                diff_json(&child, e_value, a_value, differences);
+            }
+        }
+        (Value::Array(e), Value::Array(a)) if e.len() == a.len() => {
+            for (idx, (e_value, a_value)) in e.iter().zip(a).enumerate() {
+                diff_json(&format!("{}[{}]", path, idx), e_value, a_value, differences);
+            }
+        }
+        _ if expected != actual => differences.push(ReplayDifference {
+            path: String::from(path),
+            expected: expected.clone(),
+            actual: actual.clone(),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::compass::{CompassAppBuilder, CompassConfigurationError};
+    use std::path::PathBuf;
+
+    /// builds the speeds test app from an edited copy of its configuration text
+    fn build(edit: &dyn Fn(String) -> String) -> CompassApp {
+        let speeds_test = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("app")
+            .join("compass")
+            .join("test")
+            .join("speeds_test");
+        let from_file = |path: PathBuf| {
+            let text = std::fs::read_to_string(&path).unwrap();
+            CompassApp::try_from_config_toml_string(
+                edit(text),
+                path.to_string_lossy().to_string(),
+                &CompassAppBuilder::default(),
+            )
+        };
+        match from_file(speeds_test.join("speeds_test.toml")) {
+            Ok(a) => Ok(a),
+            Err(CompassAppError::CompassConfigurationError(
+                CompassConfigurationError::FileNormalizationNotFound(_key, _f1, _f2),
+            )) => from_file(speeds_test.join("speeds_debug.toml")),
+            Err(other) => panic!("{}", other),
+        }
+        .unwrap()
+    }
+
+    #[test]
+    fn test_capture_and_replay() {
+        let app = build(&|text| text);
+        let query = serde_json::json!({
+            "origin_vertex": 0,
+            "destination_vertex": 2
+        });
+        let trace = ReplayTrace::capture(&app, &query).unwrap();
+        assert_eq!(trace.result["route"]["path"], serde_json::json!([0, 2]));
+        assert!(trace.result.get("search_runtime").is_none());
+
+        // the trace round-trips through a file and replays unchanged
+        let path = std::env::temp_dir().join(format!(
+            "routee_compass_replay_trace_{}.json",
+            std::process::id()
+        ));
+        trace.write(&path).unwrap();
+        let read = ReplayTrace::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read, trace);
+        assert_eq!(read.replay(&app).unwrap(), vec![]);
+
+        // weighting distance instead of time changes the configuration and the route
+        let changed = build(&|text| {
+            text.replacen("distance = 0", "distance = 1", 1)
+                .replacen("time = 1", "time = 0", 1)
+        });
+        let differences = read.replay(&changed).unwrap();
+        let paths: Vec<&str> = differences.iter().map(|d| d.path.as_str()).collect();
+        assert!(paths.contains(&"fingerprint.config"), "{:?}", paths);
+        assert!(!paths.contains(&"fingerprint.graph"), "{:?}", paths);
+        let path_change = differences
+            .iter()
+            .find(|d| d.path == "result.route.path")
+            .unwrap();
+        assert_eq!(path_change.expected, serde_json::json!([0, 2]));
+        assert_eq!(path_change.actual, serde_json::json!([1]));
+    }
+}